@@ -0,0 +1,115 @@
+// Copyright 2020-2024 Trust Computing GmbH.
+// This file is part of Litentry.
+//
+// Litentry is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// Litentry is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with Litentry.  If not, see <https://www.gnu.org/licenses/>.
+
+use crate::generate_config::specific_config;
+use bridge_core::config::{BridgeConfig, Listener, Relayer};
+use ethereum_listener::listener::Finalization;
+use std::fmt::Write;
+
+/// Resolved summary of listeners and the relayers they route to, as seen by the worker after
+/// deserializing their specific configs.
+pub fn explain(config: &BridgeConfig) -> Result<String, String> {
+    let mut out = String::new();
+    for listener in &config.listeners {
+        explain_listener(&mut out, listener)?;
+        writeln!(out, "  relayers:").unwrap();
+        for id in &listener.relayers {
+            match config.relayers.iter().find(|relayer| &relayer.id == id) {
+                Some(relayer) => explain_relayer(&mut out, relayer)?,
+                None => writeln!(out, "    {}: unknown relayer", id).unwrap(),
+            }
+        }
+    }
+    Ok(out)
+}
+
+fn explain_listener(out: &mut String, listener: &Listener) -> Result<(), String> {
+    writeln!(out, "Listener {}", listener.id).unwrap();
+    writeln!(out, "  type: {}", listener.listener_type).unwrap();
+    writeln!(out, "  chain_id: {}", listener.chain_id).unwrap();
+    if let Some(start_nonce) = listener.start_nonce {
+        writeln!(out, "  start nonce: {}", start_nonce).unwrap();
+    }
+    match listener.listener_type.as_str() {
+        "ethereum" => {
+            let specific: ethereum_listener::listener::ListenerConfig =
+                specific_config(&listener.config, "listener", &listener.id)?;
+            writeln!(out, "  rpc url: {}", specific.node_rpc_url).unwrap();
+            writeln!(out, "  bridge address: {}", specific.bridge_contract_address).unwrap();
+            match specific.finalization() {
+                Finalization::Tag => {
+                    writeln!(out, "  finalization: finalized tag, fallback gap: {}", specific.finalization_gap).unwrap()
+                },
+                Finalization::Gap(gap) => writeln!(out, "  finalization gap: {}", gap).unwrap(),
+            }
+        },
+        "substrate" => {
+            let specific: substrate_listener::listener::ListenerConfig =
+                specific_config(&listener.config, "listener", &listener.id)?;
+            writeln!(out, "  rpc url: {}", specific.ws_rpc_endpoint).unwrap();
+            writeln!(out, "  chain: {}", specific.chain.as_deref().unwrap_or("selected by node runtime")).unwrap();
+            if specific.confirmation_blocks > 0 {
+                writeln!(out, "  confirmation blocks: {}", specific.confirmation_blocks).unwrap();
+            }
+        },
+        _ => {},
+    }
+    Ok(())
+}
+
+fn explain_relayer(out: &mut String, relayer: &Relayer) -> Result<(), String> {
+    write!(out, "    {}: {} destination {}", relayer.id, relayer.relayer_type, relayer.destination_id).unwrap();
+    match relayer.relayer_type.as_str() {
+        "ethereum" => {
+            let specific: ethereum_relayer::RelayerConfig = specific_config(&relayer.config, "relayer", &relayer.id)?;
+            write!(out, ", rpc url {}, bridge address {}", specific.node_rpc_url, specific.bridge_contract_address)
+                .unwrap();
+        },
+        "substrate" => {
+            let specific: substrate_relayer::RelayerConfig = specific_config(&relayer.config, "relayer", &relayer.id)?;
+            write!(
+                out,
+                ", rpc url {}, chain {}",
+                specific.ws_rpc_endpoint,
+                specific.chain.as_deref().unwrap_or("selected by node runtime")
+            )
+            .unwrap();
+        },
+        _ => {},
+    }
+    writeln!(out).unwrap();
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::generate_config::read_config;
+
+    #[test]
+    pub fn local_config_is_explained() {
+        let config = read_config("../local/config.json").unwrap();
+
+        let explained = explain(&config).unwrap();
+
+        assert!(explained.contains(
+            "Listener sepolia\n  type: ethereum\n  chain_id: 0\n  rpc url: http://ethereum-node:8545\n  bridge address: 0x5FbDB2315678afecb367f032d93F642f64180aa3\n  finalization gap: 6\n  relayers:\n    rococo: substrate destination 02, rpc url ws://heima-node:9944, chain local\n"
+        ));
+        assert!(explained.contains(
+            "Listener rococo\n  type: substrate\n  chain_id: 0\n  rpc url: ws://heima-node:9944\n  chain: local\n  relayers:\n    sepolia: ethereum destination 0100000000, rpc url http://ethereum-node:8545, bridge address 0x5FbDB2315678afecb367f032d93F642f64180aa3\n    ethereum-2: ethereum destination 0138000000, rpc url http://ethereum-2-node:8545, bridge address 0x5FbDB2315678afecb367f032d93F642f64180aa3\n"
+        ));
+    }
+}
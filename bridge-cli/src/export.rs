@@ -0,0 +1,437 @@
+// Copyright 2020-2024 Trust Computing GmbH.
+// This file is part of Litentry.
+//
+// Litentry is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// Litentry is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with Litentry.  If not, see <https://www.gnu.org/licenses/>.
+
+use crate::generate_config::{read_config, specific_config};
+use alloy::primitives::Address;
+use bridge_core::config::{BridgeConfig, Listener, Relayer};
+use bridge_core::fetcher::BlockPayInEventsFetcher;
+use bridge_core::listener::PayIn;
+use clap::{Args, ValueEnum};
+use ethereum_listener::fetcher::{executed_proposals, Fetcher as EthereumFetcher, PROPOSAL_EVENT_TOPIC};
+use ethereum_listener::rpc_client::{EthereumRpcClient, EthersRpcClient};
+use serde::Serialize;
+use serde_with::{serde_as, DisplayFromStr};
+use std::collections::{HashMap, HashSet};
+use std::fmt::{Display, Write};
+use std::fs;
+use std::str::FromStr;
+use substrate_listener::fetcher::Fetcher as SubstrateFetcher;
+use substrate_listener::rpc_client::{RpcClient, RpcClientFactory, SubstrateRpcClient, SubstrateRpcClientFactory};
+use substrate_listener::{ChainRuntime, CustomConfig, HeimaRuntime, LocalRuntime, PaseoRuntime};
+
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, ValueEnum)]
+#[serde(rename_all = "kebab-case")]
+pub enum Direction {
+    EthToSub,
+    SubToEth,
+}
+
+impl Direction {
+    fn source_type(&self) -> &'static str {
+        match self {
+            Direction::EthToSub => "ethereum",
+            Direction::SubToEth => "substrate",
+        }
+    }
+
+    fn destination_type(&self) -> &'static str {
+        match self {
+            Direction::EthToSub => "substrate",
+            Direction::SubToEth => "ethereum",
+        }
+    }
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+pub enum Format {
+    Csv,
+    Json,
+}
+
+#[derive(Args)]
+pub struct ExportArgs {
+    #[arg(long)]
+    config: String,
+    /// Source listener, defaults to the first listener of the source chain type
+    #[arg(long)]
+    listener: Option<String>,
+    #[arg(long)]
+    from_block: u64,
+    #[arg(long)]
+    to_block: u64,
+    #[arg(long, value_enum)]
+    direction: Direction,
+    #[arg(long, value_enum, default_value_t = Format::Csv)]
+    format: Format,
+    /// Destination chain blocks searched for completed transfers, transfers are not correlated if not set
+    #[arg(long, requires = "destination_to_block")]
+    destination_from_block: Option<u64>,
+    #[arg(long, requires = "destination_from_block")]
+    destination_to_block: Option<u64>,
+    #[arg(long)]
+    out: String,
+}
+
+/// Single bridged transfer. Events are referenced by their position, `<block>-<tx>-<log>` on ethereum
+/// and `<block>-<event>` on substrate.
+#[serde_as]
+#[derive(Debug, PartialEq, Serialize)]
+pub struct TransferRecord {
+    pub timestamp: Option<u64>,
+    pub direction: Direction,
+    pub listener: String,
+    pub nonce: u64,
+    #[serde_as(as = "DisplayFromStr")]
+    pub amount: u128,
+    pub resource_id: String,
+    pub source_event: String,
+    pub destination: Option<String>,
+    pub destination_event: Option<String>,
+    // not emitted by bridge events, kept for the report layout
+    #[serde_as(as = "Option<DisplayFromStr>")]
+    pub fee: Option<u128>,
+}
+
+impl TransferRecord {
+    fn new<Id: Clone + Display>(
+        direction: Direction,
+        listener: &str,
+        event: &PayIn<Id, String>,
+        timestamp: Option<u64>,
+    ) -> Self {
+        Self {
+            timestamp,
+            direction,
+            listener: listener.to_string(),
+            nonce: event.nonce(),
+            amount: event.amount(),
+            resource_id: hex::encode(event.resource_id()),
+            source_event: event.id().to_string(),
+            destination: event.destination_id().cloned(),
+            destination_event: None,
+            fee: None,
+        }
+    }
+}
+
+/// Transfer completed on the destination chain
+#[derive(Debug, PartialEq)]
+pub struct Completion {
+    pub nonce: u64,
+    pub event: String,
+}
+
+pub async fn handle(args: &ExportArgs) -> Result<(), String> {
+    let config = read_config(&args.config)?;
+    let listener = source_listener(&config, args)?;
+
+    let mut records = match args.direction {
+        Direction::EthToSub => ethereum_transfers(listener, args).await?,
+        Direction::SubToEth => substrate_transfers(listener, args).await?,
+    };
+
+    if let (Some(from_block), Some(to_block)) = (args.destination_from_block, args.destination_to_block) {
+        let relayer = destination_relayer(&config, listener, args.direction)?;
+        let completions = match args.direction {
+            Direction::EthToSub => substrate_completions(relayer, from_block, to_block).await?,
+            Direction::SubToEth => ethereum_completions(relayer, from_block, to_block).await?,
+        };
+        correlate(&mut records, &completions);
+    }
+
+    let output = match args.format {
+        Format::Csv => to_csv(&records),
+        Format::Json => serde_json::to_string_pretty(&records).map_err(|e| format!("Could not serialize: {}", e))?,
+    };
+    fs::write(&args.out, output).map_err(|e| format!("Could not write export to {}: {}", args.out, e))?;
+    println!("Exported {} transfers to {}", records.len(), args.out);
+    Ok(())
+}
+
+fn source_listener<'a>(config: &'a BridgeConfig, args: &ExportArgs) -> Result<&'a Listener, String> {
+    let source_type = args.direction.source_type();
+    config
+        .listeners
+        .iter()
+        .filter(|listener| listener.listener_type == source_type)
+        .find(|listener| match args.listener {
+            Some(ref id) => &listener.id == id,
+            None => true,
+        })
+        .ok_or_else(|| format!("No {} listener {} found", source_type, args.listener.clone().unwrap_or_default()))
+}
+
+fn destination_relayer<'a>(
+    config: &'a BridgeConfig,
+    listener: &Listener,
+    direction: Direction,
+) -> Result<&'a Relayer, String> {
+    config
+        .relayers
+        .iter()
+        .filter(|relayer| listener.relayers.contains(&relayer.id))
+        .find(|relayer| relayer.relayer_type == direction.destination_type())
+        .ok_or_else(|| format!("No {} relayer of {} listener found", direction.destination_type(), listener.id))
+}
+
+/// Fills destination events of transfers completed with the same nonce.
+pub fn correlate(records: &mut [TransferRecord], completions: &[Completion]) {
+    let completions: HashMap<u64, &Completion> =
+        completions.iter().map(|completion| (completion.nonce, completion)).collect();
+    for record in records.iter_mut() {
+        record.destination_event = completions.get(&record.nonce).map(|completion| completion.event.clone());
+    }
+}
+
+pub fn to_csv(records: &[TransferRecord]) -> String {
+    let mut csv = String::from(
+        "timestamp,direction,listener,nonce,amount,resource_id,source_event,destination,destination_event,fee\n",
+    );
+    for record in records {
+        writeln!(
+            csv,
+            "{},{},{},{},{},{},{},{},{},{}",
+            optional(record.timestamp),
+            record
+                .direction
+                .to_possible_value()
+                .expect("no skipped variants; qed")
+                .get_name(),
+            csv_field(&record.listener),
+            record.nonce,
+            record.amount,
+            record.resource_id,
+            record.source_event,
+            csv_field(record.destination.as_deref().unwrap_or_default()),
+            record.destination_event.as_deref().unwrap_or_default(),
+            optional(record.fee),
+        )
+        .unwrap();
+    }
+    csv
+}
+
+fn optional<T: Display>(value: Option<T>) -> String {
+    value.map(|value| value.to_string()).unwrap_or_default()
+}
+
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+async fn ethereum_transfers(listener: &Listener, args: &ExportArgs) -> Result<Vec<TransferRecord>, String> {
+    let config: ethereum_listener::listener::ListenerConfig =
+        specific_config(&listener.config, "listener", &listener.id)?;
+    let bridge_contract_address = parse_address(&config.bridge_contract_address, &listener.id)?;
+    let client = || {
+        EthersRpcClient::new(&config.node_rpc_url, config.rpc_auth.as_ref())
+            .map_err(|_| format!("Could not create rpc client of {}", listener.id))
+    };
+    let timestamps = client()?;
+    // finalization gap is not used, blocks are fetched directly
+    let mut fetcher = EthereumFetcher::new(0, client()?, HashSet::from([bridge_contract_address]));
+
+    let mut records = vec![];
+    for block_num in args.from_block..=args.to_block {
+        let events = fetcher
+            .get_block_pay_in_events(block_num)
+            .await
+            .map_err(|_| format!("Could not fetch events of block {}", block_num))?;
+        if events.is_empty() {
+            continue;
+        }
+        let timestamp = timestamps
+            .get_block_timestamp(block_num)
+            .await
+            .map_err(|_| format!("Could not fetch timestamp of block {}", block_num))?;
+        records.extend(
+            events
+                .iter()
+                .map(|event| TransferRecord::new(args.direction, &listener.id, event, timestamp)),
+        );
+    }
+    Ok(records)
+}
+
+async fn ethereum_completions(relayer: &Relayer, from_block: u64, to_block: u64) -> Result<Vec<Completion>, String> {
+    let config: ethereum_relayer::RelayerConfig = specific_config(&relayer.config, "relayer", &relayer.id)?;
+    let bridge_contract_address = parse_address(&config.bridge_contract_address, &relayer.id)?;
+    let client = EthersRpcClient::new(&config.node_rpc_url, config.rpc_auth.as_ref())
+        .map_err(|_| format!("Could not create rpc client of {}", relayer.id))?;
+
+    let mut completions = vec![];
+    for block_num in from_block..=to_block {
+        let logs = client
+            .get_block_logs(block_num, vec![bridge_contract_address], PROPOSAL_EVENT_TOPIC)
+            .await
+            .map_err(|_| format!("Could not fetch proposals of block {}", block_num))?;
+        completions.extend(
+            executed_proposals(logs)
+                .into_iter()
+                .map(|proposal| Completion { nonce: proposal.deposit_nonce, event: proposal.id.to_string() }),
+        );
+    }
+    Ok(completions)
+}
+
+async fn substrate_transfers(listener: &Listener, args: &ExportArgs) -> Result<Vec<TransferRecord>, String> {
+    let config: substrate_listener::listener::ListenerConfig =
+        specific_config(&listener.config, "listener", &listener.id)?;
+    let chain = substrate_listener::resolve_chain::<CustomConfig>(&listener.id, &config)
+        .await
+        .map_err(|_| format!("Could not resolve chain of {}", listener.id))?;
+    match chain {
+        _ if chain == LocalRuntime::NAME => substrate_runtime_transfers::<LocalRuntime>(listener, &config, args).await,
+        _ if chain == PaseoRuntime::NAME => substrate_runtime_transfers::<PaseoRuntime>(listener, &config, args).await,
+        _ if chain == HeimaRuntime::NAME => substrate_runtime_transfers::<HeimaRuntime>(listener, &config, args).await,
+        _ => Err(format!("Unknown chain of {}: {}", listener.id, chain)),
+    }
+}
+
+async fn substrate_runtime_transfers<Runtime: ChainRuntime>(
+    listener: &Listener,
+    config: &substrate_listener::listener::ListenerConfig,
+    args: &ExportArgs,
+) -> Result<Vec<TransferRecord>, String> {
+    let client_factory = || RpcClientFactory::<CustomConfig>::new(&config.ws_rpc_endpoint, config.rpc_auth.clone());
+    let mut timestamps: RpcClient<CustomConfig, Runtime::PaidInEvent> = client_factory()
+        .new_client()
+        .await
+        .map_err(|_| format!("Could not connect to {}", config.ws_rpc_endpoint))?;
+    let mut fetcher = SubstrateFetcher::<RpcClient<CustomConfig, Runtime::PaidInEvent>, _>::new(client_factory());
+
+    let mut records = vec![];
+    for block_num in args.from_block..=args.to_block {
+        let events = fetcher
+            .get_block_pay_in_events(block_num)
+            .await
+            .map_err(|_| format!("Could not fetch events of block {}", block_num))?;
+        if events.is_empty() {
+            continue;
+        }
+        let timestamp = timestamps
+            .get_block_timestamp(block_num)
+            .await
+            .map_err(|_| format!("Could not fetch timestamp of block {}", block_num))?;
+        records.extend(
+            events
+                .iter()
+                .map(|event| TransferRecord::new(args.direction, &listener.id, event, timestamp)),
+        );
+    }
+    Ok(records)
+}
+
+// `PaidOut` events are decoded independently of the runtime
+async fn substrate_completions(relayer: &Relayer, from_block: u64, to_block: u64) -> Result<Vec<Completion>, String> {
+    let config: substrate_relayer::RelayerConfig = specific_config(&relayer.config, "relayer", &relayer.id)?;
+    let mut client: RpcClient<CustomConfig, <LocalRuntime as ChainRuntime>::PaidInEvent> =
+        RpcClientFactory::<CustomConfig>::new(&config.ws_rpc_endpoint, config.rpc_auth.clone())
+            .new_client()
+            .await
+            .map_err(|_| format!("Could not connect to {}", config.ws_rpc_endpoint))?;
+
+    let mut completions = vec![];
+    for block_num in from_block..=to_block {
+        let events = client
+            .get_block_paid_out_events(block_num)
+            .await
+            .map_err(|_| format!("Could not fetch pay outs of block {}", block_num))?;
+        completions.extend(
+            events
+                .into_iter()
+                .map(|event| Completion { nonce: event.event.nonce, event: event.id.to_string() }),
+        );
+    }
+    Ok(completions)
+}
+
+fn parse_address(address: &str, id: &str) -> Result<Address, String> {
+    Address::from_str(address).map_err(|_| format!("Invalid bridge contract address of {}: {}", id, address))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn record(nonce: u64) -> TransferRecord {
+        TransferRecord {
+            timestamp: Some(1700000000),
+            direction: Direction::EthToSub,
+            listener: "sepolia".to_string(),
+            nonce,
+            amount: 100_000_000_000_000_000_000,
+            resource_id: "00".repeat(32),
+            source_event: format!("10-0-{}", nonce),
+            destination: Some("02".to_string()),
+            destination_event: None,
+            fee: None,
+        }
+    }
+
+    #[test]
+    pub fn transfers_are_correlated_by_nonce() {
+        let mut records = vec![record(1), record(2)];
+        let completions = vec![Completion { nonce: 2, event: "20-3".to_string() }];
+
+        correlate(&mut records, &completions);
+
+        assert_eq!(records[0].destination_event, None);
+        assert_eq!(records[1].destination_event, Some("20-3".to_string()));
+    }
+
+    #[test]
+    pub fn transfers_are_serialized_to_csv() {
+        let mut records = vec![record(1), record(2)];
+        records[1].timestamp = None;
+        records[1].destination_event = Some("20-3".to_string());
+
+        assert_eq!(
+            to_csv(&records),
+            format!(
+                "timestamp,direction,listener,nonce,amount,resource_id,source_event,destination,destination_event,fee\n\
+                 1700000000,eth-to-sub,sepolia,1,100000000000000000000,{0},10-0-1,02,,\n\
+                 ,eth-to-sub,sepolia,2,100000000000000000000,{0},10-0-2,02,20-3,\n",
+                "00".repeat(32)
+            )
+        );
+    }
+
+    #[test]
+    pub fn transfers_are_serialized_to_json() {
+        let json = serde_json::to_value(vec![record(1)]).unwrap();
+
+        assert_eq!(
+            json,
+            serde_json::json!([{
+                "timestamp": 1700000000,
+                "direction": "eth-to-sub",
+                "listener": "sepolia",
+                "nonce": 1,
+                "amount": "100000000000000000000",
+                "resource_id": "00".repeat(32),
+                "source_event": "10-0-1",
+                "destination": "02",
+                "destination_event": null,
+                "fee": null
+            }])
+        );
+    }
+}
@@ -0,0 +1,407 @@
+// Copyright 2020-2024 Trust Computing GmbH.
+// This file is part of Litentry.
+//
+// Litentry is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// Litentry is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with Litentry.  If not, see <https://www.gnu.org/licenses/>.
+
+use bridge_core::config::{BridgeConfig, CheckpointBatching, Listener, Relayer};
+use bridge_core::listener::{DEFAULT_FETCH_TIMEOUT, DEFAULT_POLL_INTERVAL};
+use clap::Args;
+use serde::de::DeserializeOwned;
+use serde_json::json;
+use std::collections::HashMap;
+use std::fs;
+use std::str::FromStr;
+
+const SUBSTRATE_CHAINS: [&str; 3] = ["local", "paseo", "heima"];
+
+#[derive(Args, Default)]
+pub struct GenerateConfigArgs {
+    /// `<id>:<rpc_url>:<bridge_contract_address>:chain_id=<u32>:finalization_gap=<u64>[:evm_chain_id=<u64>][:relayers=<id>,..]`
+    #[arg(long)]
+    ethereum_listener: Vec<String>,
+    /// `<id>:<ws_rpc_endpoint>[:chain=<local|paseo|heima>]:chain_id=<u32>[:expected_genesis_hash=<hex>][:expected_spec_name=<name>][:relayers=<id>,..]`
+    #[arg(long)]
+    substrate_listener: Vec<String>,
+    /// `<id>:<rpc_url>:<bridge_contract_address>:destination=<hex>`
+    #[arg(long)]
+    ethereum_relayer: Vec<String>,
+    /// `<id>:<ws_rpc_endpoint>[:chain=<local|paseo|heima>]:destination=<hex>`
+    #[arg(long)]
+    substrate_relayer: Vec<String>,
+    /// Existing config to extend with the given listeners and relayers
+    #[arg(long)]
+    from_existing: Option<String>,
+    /// Where to write the config, printed to stdout if not set
+    #[arg(long)]
+    output: Option<String>,
+}
+
+pub fn handle(args: &GenerateConfigArgs) -> Result<(), String> {
+    let config = generate(args)?;
+    let json = serde_json::to_string_pretty(&config).map_err(|e| format!("Could not serialize config: {}", e))?;
+    match args.output {
+        Some(ref path) => {
+            fs::write(path, json).map_err(|e| format!("Could not write config to {}: {}", path, e))?;
+            println!("Config written to {}", path);
+        },
+        None => println!("{}", json),
+    }
+    Ok(())
+}
+
+/// Builds config from cli flags. Listeners without explicit `relayers` option relay through all relayers
+/// of the other chain type. Generated config passes the same validation as `check-config`.
+pub fn generate(args: &GenerateConfigArgs) -> Result<BridgeConfig, String> {
+    let mut config = match args.from_existing {
+        Some(ref path) => read_config(path)?,
+        None => {
+            BridgeConfig { listeners: vec![], relayers: vec![], resources: Default::default(), notifications: None }
+        },
+    };
+
+    for spec in &args.ethereum_relayer {
+        config.relayers.push(parse_ethereum_relayer(spec)?);
+    }
+    for spec in &args.substrate_relayer {
+        config.relayers.push(parse_substrate_relayer(spec)?);
+    }
+    for spec in &args.ethereum_listener {
+        let listener = parse_ethereum_listener(spec, &config.relayers)?;
+        config.listeners.push(listener);
+    }
+    for spec in &args.substrate_listener {
+        let listener = parse_substrate_listener(spec, &config.relayers)?;
+        config.listeners.push(listener);
+    }
+
+    config.validate().map_err(|e| format!("Invalid config: {}", e))?;
+    validate_specific_configs(&config)?;
+    Ok(config)
+}
+
+pub fn read_config(path: &str) -> Result<BridgeConfig, String> {
+    BridgeConfig::from_file(path).map_err(|e| e.to_string())
+}
+
+/// Checks that listener and relayer specific configs deserialize into the types used by the worker.
+pub fn validate_specific_configs(config: &BridgeConfig) -> Result<(), String> {
+    for listener in &config.listeners {
+        match listener.listener_type.as_str() {
+            "ethereum" => {
+                let specific: ethereum_listener::listener::ListenerConfig =
+                    specific_config(&listener.config, "listener", &listener.id)?;
+                validate_address(&specific.bridge_contract_address, &listener.id)?;
+            },
+            "substrate" => {
+                let specific: substrate_listener::listener::ListenerConfig =
+                    specific_config(&listener.config, "listener", &listener.id)?;
+                validate_substrate_chain(specific.chain.as_deref(), &listener.id)?;
+            },
+            _ => {},
+        }
+    }
+    for relayer in &config.relayers {
+        hex::decode(&relayer.destination_id)
+            .map_err(|_| format!("Destination id of {} relayer is not hex encoded", relayer.id))?;
+        match relayer.relayer_type.as_str() {
+            "ethereum" => {
+                let specific: ethereum_relayer::RelayerConfig =
+                    specific_config(&relayer.config, "relayer", &relayer.id)?;
+                validate_address(&specific.bridge_contract_address, &relayer.id)?;
+                validate_resource_allowlist(specific.resource_allowlist(), &relayer.id)?;
+            },
+            "substrate" => {
+                let specific: substrate_relayer::RelayerConfig =
+                    specific_config(&relayer.config, "relayer", &relayer.id)?;
+                validate_substrate_chain(specific.chain.as_deref(), &relayer.id)?;
+                validate_resource_allowlist(specific.resource_allowlist(), &relayer.id)?;
+            },
+            _ => {},
+        }
+    }
+    Ok(())
+}
+
+fn validate_resource_allowlist<T>(allowlist: Result<T, ()>, id: &str) -> Result<(), String> {
+    allowlist
+        .map(|_| ())
+        .map_err(|_| format!("Allowed resource ids of {} relayer are not hex encoded resource ids", id))
+}
+
+pub fn specific_config<T: DeserializeOwned>(config: &serde_json::Value, kind: &str, id: &str) -> Result<T, String> {
+    serde_json::from_value(config.clone()).map_err(|e| format!("Invalid config of {} {}: {}", id, kind, e))
+}
+
+fn validate_address(address: &str, id: &str) -> Result<(), String> {
+    match address.strip_prefix("0x").map(hex::decode) {
+        Some(Ok(bytes)) if bytes.len() == 20 => Ok(()),
+        _ => Err(format!("Invalid bridge contract address of {}: {}", id, address)),
+    }
+}
+
+// chain is selected by the node's runtime if not set
+fn validate_substrate_chain(chain: Option<&str>, id: &str) -> Result<(), String> {
+    if let Some(chain) = chain.filter(|chain| !SUBSTRATE_CHAINS.contains(chain)) {
+        return Err(format!("Unknown chain of {}: {}, expected one of {:?}", id, chain, SUBSTRATE_CHAINS));
+    }
+    Ok(())
+}
+
+fn parse_ethereum_listener(spec: &str, relayers: &[Relayer]) -> Result<Listener, String> {
+    let spec = Spec::parse(spec, 2, &["chain_id", "finalization_gap", "evm_chain_id", "relayers"])?;
+    let mut config = json!({
+        "node_rpc_url": spec.positional[0],
+        "bridge_contract_address": spec.positional[1],
+        "finalization_gap": spec.required::<u64>("finalization_gap")?,
+    });
+    if let Some(evm_chain_id) = spec.optional::<u64>("evm_chain_id")? {
+        config["evm_chain_id"] = json!(evm_chain_id);
+    }
+    Ok(Listener {
+        listener_type: "ethereum".to_string(),
+        relayers: spec.relayers("ethereum", relayers),
+        id: spec.id.clone(),
+        chain_id: spec.required("chain_id")?,
+        checkpoint_batching: CheckpointBatching::default(),
+        skip_failed_events: false,
+        fetch_timeout_secs: DEFAULT_FETCH_TIMEOUT.as_secs(),
+        halt_on_nonce_gap: false,
+        deployed_at_block: None,
+        start_nonce: None,
+        poll_interval_ms: DEFAULT_POLL_INTERVAL.as_millis() as u64,
+        fast_sync_interval_ms: 0,
+        config,
+    })
+}
+
+fn parse_substrate_listener(spec: &str, relayers: &[Relayer]) -> Result<Listener, String> {
+    let spec = Spec::parse(spec, 1, &["chain", "chain_id", "expected_genesis_hash", "expected_spec_name", "relayers"])?;
+    let mut config = json!({
+        "ws_rpc_endpoint": spec.positional[0],
+    });
+    for key in ["chain", "expected_genesis_hash", "expected_spec_name"] {
+        if let Some(value) = spec.optional::<String>(key)? {
+            config[key] = json!(value);
+        }
+    }
+    Ok(Listener {
+        listener_type: "substrate".to_string(),
+        relayers: spec.relayers("substrate", relayers),
+        id: spec.id.clone(),
+        chain_id: spec.required("chain_id")?,
+        checkpoint_batching: CheckpointBatching::default(),
+        skip_failed_events: false,
+        fetch_timeout_secs: DEFAULT_FETCH_TIMEOUT.as_secs(),
+        halt_on_nonce_gap: false,
+        deployed_at_block: None,
+        start_nonce: None,
+        poll_interval_ms: DEFAULT_POLL_INTERVAL.as_millis() as u64,
+        fast_sync_interval_ms: 0,
+        config,
+    })
+}
+
+fn parse_ethereum_relayer(spec: &str) -> Result<Relayer, String> {
+    let spec = Spec::parse(spec, 2, &["destination"])?;
+    Ok(Relayer {
+        relayer_type: "ethereum".to_string(),
+        id: spec.id.clone(),
+        destination_id: spec.required("destination")?,
+        config: json!({
+            "node_rpc_url": spec.positional[0],
+            "bridge_contract_address": spec.positional[1],
+        }),
+    })
+}
+
+fn parse_substrate_relayer(spec: &str) -> Result<Relayer, String> {
+    let spec = Spec::parse(spec, 1, &["chain", "destination"])?;
+    let mut config = json!({
+        "ws_rpc_endpoint": spec.positional[0],
+    });
+    if let Some(chain) = spec.optional::<String>("chain")? {
+        config["chain"] = json!(chain);
+    }
+    Ok(Relayer {
+        relayer_type: "substrate".to_string(),
+        id: spec.id.clone(),
+        destination_id: spec.required("destination")?,
+        config,
+    })
+}
+
+/// Colon separated listener or relayer definition: `<id>:<url>[:<positional>..][:<key>=<value>..]`.
+/// Url may contain colons, so positional values following it are taken from the end.
+struct Spec {
+    id: String,
+    positional: Vec<String>,
+    options: HashMap<String, String>,
+}
+
+impl Spec {
+    fn parse(spec: &str, positional_count: usize, known_options: &[&str]) -> Result<Self, String> {
+        let mut parts: Vec<&str> = spec.split(':').collect();
+        let id = parts.remove(0).to_string();
+        if id.is_empty() {
+            return Err(format!("Missing id in {}", spec));
+        }
+
+        let mut options = HashMap::new();
+        while let Some((key, value)) = parts.last().and_then(|part| part.split_once('=')) {
+            if key.is_empty() || !key.chars().all(|c| c.is_ascii_lowercase() || c == '_') {
+                break;
+            }
+            if !known_options.contains(&key) {
+                return Err(format!("Unknown option {} in {}, expected one of {:?}", key, spec, known_options));
+            }
+            options.insert(key.to_string(), value.to_string());
+            parts.pop();
+        }
+
+        if parts.len() < positional_count {
+            return Err(format!("Expected {} positional values in {}", positional_count, spec));
+        }
+        let trailing = parts.split_off(parts.len() - (positional_count - 1));
+        let mut positional = vec![parts.join(":")];
+        positional.extend(trailing.into_iter().map(|part| part.to_string()));
+        if positional.iter().any(|value| value.is_empty()) {
+            return Err(format!("Empty positional value in {}", spec));
+        }
+
+        Ok(Self { id, positional, options })
+    }
+
+    fn optional<T: FromStr>(&self, key: &str) -> Result<Option<T>, String> {
+        self.options
+            .get(key)
+            .map(|value| {
+                value
+                    .parse()
+                    .map_err(|_| format!("Invalid value of {} in {} definition", key, self.id))
+            })
+            .transpose()
+    }
+
+    fn required<T: FromStr>(&self, key: &str) -> Result<T, String> {
+        self.optional(key)?
+            .ok_or_else(|| format!("Missing {} option in {} definition", key, self.id))
+    }
+
+    // defaults to all relayers of the other chain type
+    fn relayers(&self, listener_type: &str, relayers: &[Relayer]) -> Vec<String> {
+        match self.options.get("relayers") {
+            Some(ids) => ids.split(',').map(|id| id.to_string()).collect(),
+            None => relayers
+                .iter()
+                .filter(|relayer| relayer.relayer_type != listener_type)
+                .map(|relayer| relayer.id.clone())
+                .collect(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const BRIDGE_ADDRESS: &str = "0x5FbDB2315678afecb367f032d93F642f64180aa3";
+
+    fn local_args() -> GenerateConfigArgs {
+        GenerateConfigArgs {
+            ethereum_listener: vec![format!(
+                "sepolia:http://ethereum-node:8545:{}:chain_id=0:finalization_gap=6:evm_chain_id=31337",
+                BRIDGE_ADDRESS
+            )],
+            substrate_listener: vec!["rococo:ws://heima-node:9944:chain=local:chain_id=0".to_string()],
+            ethereum_relayer: vec![format!(
+                "sepolia:http://ethereum-node:8545:{}:destination=0100000000",
+                BRIDGE_ADDRESS
+            )],
+            substrate_relayer: vec!["rococo:ws://heima-node:9944:chain=local:destination=02".to_string()],
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    pub fn generated_config_deserializes_into_worker_configs() {
+        let json = serde_json::to_string(&generate(&local_args()).unwrap()).unwrap();
+        let config: BridgeConfig = serde_json::from_str(&json).unwrap();
+        config.validate().unwrap();
+
+        assert_eq!(config.listeners[0].relayers, vec!["rococo"]);
+        assert_eq!(config.listeners[1].relayers, vec!["sepolia"]);
+
+        let sepolia: ethereum_listener::listener::ListenerConfig = config.listeners[0].to_specific_config();
+        assert_eq!(sepolia.node_rpc_url, "http://ethereum-node:8545");
+        assert_eq!(sepolia.bridge_contract_address, BRIDGE_ADDRESS);
+        assert_eq!(sepolia.finalization_gap, 6);
+        assert_eq!(sepolia.evm_chain_id, Some(31337));
+
+        let rococo: substrate_listener::listener::ListenerConfig = config.listeners[1].to_specific_config();
+        assert_eq!(rococo.ws_rpc_endpoint, "ws://heima-node:9944");
+        assert_eq!(rococo.chain, Some("local".to_string()));
+
+        let sepolia_relayer: ethereum_relayer::RelayerConfig = config.relayers[0].to_specific_config();
+        assert_eq!(sepolia_relayer.node_rpc_url, "http://ethereum-node:8545");
+        assert_eq!(config.relayers[0].destination_id, "0100000000");
+
+        let rococo_relayer: substrate_relayer::RelayerConfig = config.relayers[1].to_specific_config();
+        assert_eq!(rococo_relayer.ws_rpc_endpoint, "ws://heima-node:9944");
+        assert_eq!(rococo_relayer.chain, Some("local".to_string()));
+    }
+
+    #[test]
+    pub fn existing_config_can_be_extended() {
+        let args = GenerateConfigArgs {
+            ethereum_listener: vec![format!(
+                "bsc:http://bsc-node:8545:{}:chain_id=57:finalization_gap=6:relayers=rococo",
+                BRIDGE_ADDRESS
+            )],
+            from_existing: Some("../local/config.json".to_string()),
+            ..Default::default()
+        };
+
+        let config = generate(&args).unwrap();
+        assert_eq!(config.listeners.len(), 4);
+        assert_eq!(config.listeners[3].id, "bsc");
+        assert_eq!(config.relayers.len(), 3);
+    }
+
+    #[test]
+    pub fn unknown_option_is_rejected() {
+        let mut args = local_args();
+        args.substrate_relayer = vec!["rococo:ws://heima-node:9944:chian=local:destination=02".to_string()];
+        assert!(generate(&args).err().unwrap().contains("Unknown option chian"));
+    }
+
+    #[test]
+    pub fn invalid_specific_config_is_rejected() {
+        let mut args = local_args();
+        args.ethereum_relayer = vec!["sepolia:http://ethereum-node:8545:0x5FbD:destination=0100000000".to_string()];
+        assert!(generate(&args).is_err());
+
+        let mut args = local_args();
+        args.substrate_listener = vec!["rococo:ws://heima-node:9944:chain=rococo:chain_id=0".to_string()];
+        assert!(generate(&args).is_err());
+    }
+
+    #[test]
+    pub fn invalid_allowed_resource_id_is_rejected() {
+        for relayer in 0..2 {
+            let mut config = generate(&local_args()).unwrap();
+            config.relayers[relayer].config["allowed_resource_ids"] = serde_json::json!(["0x9ee6"]);
+            assert!(validate_specific_configs(&config).unwrap_err().contains("Allowed resource ids"));
+        }
+    }
+}
@@ -14,11 +14,29 @@
 // You should have received a copy of the GNU General Public License
 // along with Litentry.  If not, see <https://www.gnu.org/licenses/>.
 
-use bridge_core::config::BridgeConfig;
 use clap::{Args, Parser, Subcommand};
 use ethereum_cli::EthereumCommand;
-use std::fs;
+use export::ExportArgs;
+use generate_config::GenerateConfigArgs;
+use redrive::RedriveArgs;
+use self_test::SelfTestArgs;
+use serde::Serialize;
+use shield_key::ShieldKeyArgs;
+use sign_pay_out::SignPayOutArgs;
+use std::fmt::Display;
+use std::process::ExitCode;
 use substrate_cli::SubstrateCommand;
+use verify_key::VerifyKeyArgs;
+
+mod explain_config;
+mod export;
+mod generate_config;
+mod redrive;
+mod self_test;
+mod shield_key;
+mod sign_pay_out;
+mod verify_key;
+
 // !!!Only for dev purposes!!!
 
 #[derive(Parser)]
@@ -26,11 +44,21 @@ use substrate_cli::SubstrateCommand;
 struct Cli {
     #[command(subcommand)]
     command: Option<Command>,
+
+    /// Print command results as JSON
+    #[arg(long, global = true)]
+    json: bool,
 }
 
 #[derive(Args)]
 pub struct CheckConfigArgs {
     path: String,
+    /// Print resolved listener and relayer settings
+    #[arg(long)]
+    explain: bool,
+    /// Print config as JSON with secrets redacted
+    #[arg(long)]
+    show: bool,
 }
 
 #[derive(Subcommand)]
@@ -40,28 +68,116 @@ pub enum Command {
     #[command(subcommand)]
     Substrate(SubstrateCommand),
     CheckConfig(CheckConfigArgs),
+    GenerateConfig(GenerateConfigArgs),
+    /// Export bridged transfers of a block range for accounting
+    Export(ExportArgs),
+    /// Shield relayer key with worker's shielding key for import
+    ShieldKey(ShieldKeyArgs),
+    /// Check that relayer key controls the expected address, fails on mismatch
+    VerifyKey(VerifyKeyArgs),
+    /// Transfer back and forth between test accounts on a running environment, fails on timeout
+    SelfTest(SelfTestArgs),
+    /// Sign pay out request queued for offline signing
+    SignPayOut(SignPayOutArgs),
+    /// Relay a single deposit again, e.g. a stuck transfer, bypassing listener checkpoint
+    Redrive(RedriveArgs),
 }
 
 #[tokio::main]
-async fn main() -> Result<(), ()> {
+async fn main() -> ExitCode {
     env_logger::builder().init();
     let cli = Cli::parse();
 
     match &cli.command {
         Some(Command::Ethereum(ethereum_command)) => {
-            ethereum_cli::handle(ethereum_command).await;
+            return print_result(ethereum_cli::handle(ethereum_command).await, cli.json);
         },
         Some(Command::Substrate(substrate_command)) => {
-            substrate_cli::handle(substrate_command).await;
+            return print_result(substrate_cli::handle(substrate_command).await, cli.json);
         },
         Some(Command::CheckConfig(args)) => {
-            let config: String = fs::read_to_string(&args.path).unwrap();
-            let config: BridgeConfig = serde_json::from_str(&config).unwrap();
+            let config = generate_config::read_config(&args.path).unwrap();
             config.validate().unwrap();
+            generate_config::validate_specific_configs(&config).unwrap();
+            if args.explain {
+                print!("{}", explain_config::explain(&config).unwrap());
+            }
+            if args.show {
+                println!("{}", serde_json::to_string_pretty(&config.redacted()).unwrap());
+            }
             println!("Config ok.");
         },
+        Some(Command::GenerateConfig(args)) => {
+            if let Err(e) = generate_config::handle(args) {
+                eprintln!("Could not generate config: {}", e);
+                return ExitCode::FAILURE;
+            }
+        },
+        Some(Command::Export(args)) => {
+            if let Err(e) = export::handle(args).await {
+                eprintln!("Could not export transfers: {}", e);
+                return ExitCode::FAILURE;
+            }
+        },
+        Some(Command::Redrive(args)) => {
+            if let Err(e) = redrive::handle(args).await {
+                eprintln!("Could not re-drive deposit: {}", e);
+                return ExitCode::FAILURE;
+            }
+        },
+        Some(Command::ShieldKey(args)) => match shield_key::handle(args) {
+            Ok(shielded) => println!("{}", shielded),
+            Err(e) => {
+                eprintln!("Could not shield key: {}", e);
+                return ExitCode::FAILURE;
+            },
+        },
+        Some(Command::VerifyKey(args)) => match verify_key::handle(args) {
+            Ok(address) => println!("Key controls expected address {}", address),
+            Err(e) => {
+                eprintln!("Could not verify key: {}", e);
+                return ExitCode::FAILURE;
+            },
+        },
+        Some(Command::SelfTest(args)) => match self_test::handle(args).await {
+            Ok(report) => {
+                println!("{}", report);
+                if !report.passed() {
+                    return ExitCode::FAILURE;
+                }
+            },
+            Err(e) => {
+                eprintln!("Could not run self-test: {}", e);
+                return ExitCode::FAILURE;
+            },
+        },
+        Some(Command::SignPayOut(args)) => match sign_pay_out::handle(args) {
+            Ok(signature_path) => println!("Signature written to {}", signature_path),
+            Err(e) => {
+                eprintln!("Could not sign pay out request: {}", e);
+                return ExitCode::FAILURE;
+            },
+        },
         _ => println!("No command specified!"),
     }
 
-    Ok(())
+    ExitCode::SUCCESS
+}
+
+// prints command output to stdout or error to stderr, scripts rely on the exit code
+fn print_result<O: Display + Serialize, E: Display>(result: Result<O, E>, json: bool) -> ExitCode {
+    match result {
+        Ok(output) => {
+            if json {
+                println!("{}", serde_json::to_string(&output).unwrap());
+            } else {
+                println!("{}", output.to_string().trim_end());
+            }
+            ExitCode::SUCCESS
+        },
+        Err(e) => {
+            eprintln!("Error: {}", e);
+            ExitCode::FAILURE
+        },
+    }
 }
@@ -0,0 +1,383 @@
+// Copyright 2020-2024 Trust Computing GmbH.
+// This file is part of Litentry.
+//
+// Litentry is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// Litentry is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with Litentry.  If not, see <https://www.gnu.org/licenses/>.
+
+use crate::generate_config::{read_config, specific_config};
+use alloy::primitives::Address;
+use bridge_core::config::{BridgeConfig, Listener, Relayer as RelayerConfig};
+use bridge_core::fetcher::{BlockPayInEventsFetcher, LastFinalizedBlockNumFetcher};
+use bridge_core::listener::PayIn;
+use bridge_core::notification::Notifier;
+use bridge_core::relay::{Recipient, RelayRequest, Relayer};
+use clap::Args;
+use ethereum_listener::fetcher::Fetcher as EthereumFetcher;
+use ethereum_listener::rpc_client::EthersRpcClient;
+use std::collections::HashSet;
+use std::fmt::Display;
+use std::str::FromStr;
+use std::sync::Arc;
+use substrate_listener::fetcher::Fetcher as SubstrateFetcher;
+use substrate_listener::rpc_client::{RpcClient, RpcClientFactory};
+use substrate_listener::{ChainRuntime, CustomConfig, HeimaRuntime, LocalRuntime, PaseoRuntime};
+
+// blocks fetched before checking for the deposit when searching backwards
+const SEARCH_WINDOW_BLOCKS: u64 = 1000;
+// backwards search gives up after this many blocks, `--block` needs to be given for older deposits
+const MAX_SEARCHED_BLOCKS: u64 = 100_000;
+
+#[derive(Args)]
+pub struct RedriveArgs {
+    #[arg(long)]
+    config: String,
+    /// Source listener the deposit was emitted to
+    #[arg(long)]
+    listener: String,
+    /// Deposit nonce to relay
+    #[arg(long)]
+    nonce: u64,
+    /// Source chain block containing the deposit, first block searched if `--to-block` is set.
+    /// Blocks are searched backwards from the finalized block if not set
+    #[arg(long)]
+    block: Option<u64>,
+    /// Last block searched, backwards search starts here if `--block` is not set
+    #[arg(long)]
+    to_block: Option<u64>,
+    /// Destination the deposit is sent to, required if the nonce is found for more than one destination
+    #[arg(long)]
+    destination: Option<String>,
+    #[arg(short, long, default_value = "keystore", value_name = "keystore folder path")]
+    keystore_dir: String,
+    /// Relay the deposit, listener checkpoint is not consulted so it may be relayed twice.
+    /// Deposit and relayer are only printed if not set
+    #[arg(long)]
+    confirm: bool,
+    /// Relay the deposit even if it exceeds relayer's amount limits, for deposits reviewed after being
+    /// dead-lettered due to the limits. The amount still counts towards the limits
+    #[arg(long)]
+    skip_amount_limits: bool,
+}
+
+/// Deposit found on the source chain, independent of the chain's event id type
+#[derive(Debug, PartialEq)]
+pub struct Deposit {
+    pub source_event: String,
+    pub source_ref: String,
+    pub destination_id: Option<String>,
+    pub amount: u128,
+    pub nonce: u64,
+    pub resource_id: [u8; 32],
+    pub recipient: Recipient,
+}
+
+impl<Id: Clone + Display> From<&PayIn<Id, String>> for Deposit {
+    fn from(event: &PayIn<Id, String>) -> Self {
+        Self {
+            source_event: event.id().to_string(),
+            source_ref: event.source_ref_hex(),
+            destination_id: event.destination_id().cloned(),
+            amount: event.amount(),
+            nonce: event.nonce(),
+            resource_id: *event.resource_id(),
+            recipient: event.recipient().clone(),
+        }
+    }
+}
+
+impl Display for Deposit {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "deposit nonce: {}, event: {}, source: {}, destination: {}, amount: {}, resource id: 0x{}, recipient: 0x{}",
+            self.nonce,
+            self.source_event,
+            self.source_ref,
+            self.destination_id.as_deref().unwrap_or("-"),
+            self.amount,
+            hex::encode(self.resource_id),
+            hex::encode(self.recipient.as_bytes())
+        )
+    }
+}
+
+pub async fn handle(args: &RedriveArgs) -> Result<(), String> {
+    let config = read_config(&args.config)?;
+    let listener = config
+        .listeners
+        .iter()
+        .find(|listener| listener.id == args.listener)
+        .ok_or_else(|| format!("Listener {} not found", args.listener))?;
+
+    let deposits = match listener.listener_type.as_str() {
+        "ethereum" => ethereum_deposits(listener, args).await?,
+        "substrate" => substrate_deposits(listener, args).await?,
+        other => return Err(format!("Unknown type of listener {}: {}", listener.id, other)),
+    };
+    let deposit = find_deposit(&deposits, args.nonce, args.destination.as_deref())?;
+    let relayer_config = select_relayer(&config, listener, deposit)?;
+    println!("Found {}", deposit);
+    println!("Relayer: {}", relayer_config.id);
+
+    if !args.confirm {
+        return Err("Deposit is relayed regardless of listener checkpoint, pass --confirm to proceed".to_string());
+    }
+
+    let relayer = create_relayer(relayer_config, &args.keystore_dir).await?;
+    println!("Re-driving deposit nonce {} of {} via relayer {}", deposit.nonce, listener.id, relayer_config.id);
+    relayer
+        .relay(&RelayRequest {
+            amount: deposit.amount,
+            nonce: deposit.nonce,
+            resource_id: deposit.resource_id,
+            recipient: deposit.recipient.clone(),
+            source_chain_id: listener.chain_id,
+            skip_amount_limits: args.skip_amount_limits,
+        })
+        .await
+        .map_err(|e| format!("Could not relay deposit nonce {}: {:?}", deposit.nonce, e))?;
+    println!("Re-driven deposit nonce {} of {} via relayer {}", deposit.nonce, listener.id, relayer_config.id);
+    Ok(())
+}
+
+/// Finds the deposit with given nonce, nonces are assigned per destination so it may need disambiguation.
+pub fn find_deposit<'a>(deposits: &'a [Deposit], nonce: u64, destination: Option<&str>) -> Result<&'a Deposit, String> {
+    let mut matching = deposits
+        .iter()
+        .filter(|deposit| deposit.nonce == nonce)
+        .filter(|deposit| destination.is_none() || deposit.destination_id.as_deref() == destination);
+    let deposit = matching
+        .next()
+        .ok_or_else(|| format!("Deposit nonce {} not found in searched blocks", nonce))?;
+    if matching.next().is_some() {
+        return Err(format!("Deposit nonce {} found for more than one destination, pass --destination", nonce));
+    }
+    Ok(deposit)
+}
+
+/// Block ranges searched for the deposit, newest first, when no block is given. Ranges are `window` blocks long
+/// and span at most `max_blocks` blocks ending at `newest_block`.
+pub fn search_windows(newest_block: u64, window: u64, max_blocks: u64) -> Vec<(u64, u64)> {
+    let oldest_block = newest_block.saturating_sub(max_blocks.saturating_sub(1));
+    let mut windows = vec![];
+    let mut to_block = newest_block;
+    loop {
+        let from_block = to_block.saturating_sub(window.saturating_sub(1)).max(oldest_block);
+        windows.push((from_block, to_block));
+        if from_block == oldest_block {
+            return windows;
+        }
+        to_block = from_block - 1;
+    }
+}
+
+// fetches deposits of the given blocks or, without `--block`, of windows going backwards until the nonce is found
+async fn search_deposits<Id, F>(fetcher: &mut F, args: &RedriveArgs) -> Result<Vec<Deposit>, String>
+where
+    Id: Clone + Display,
+    F: BlockPayInEventsFetcher<Id, String> + LastFinalizedBlockNumFetcher,
+{
+    let ranges = match args.block {
+        Some(block) => vec![(block, args.to_block.unwrap_or(block))],
+        None => {
+            let newest_block = match args.to_block {
+                Some(to_block) => to_block,
+                None => fetcher
+                    .get_last_finalized_block_num()
+                    .await
+                    .map_err(|_| "Could not fetch finalized block".to_string())?
+                    .ok_or_else(|| "No finalized block yet".to_string())?,
+            };
+            search_windows(newest_block, SEARCH_WINDOW_BLOCKS, MAX_SEARCHED_BLOCKS)
+        },
+    };
+
+    let mut deposits = vec![];
+    for (from_block, to_block) in ranges {
+        println!("Searching blocks {}..={} for deposit nonce {}", from_block, to_block, args.nonce);
+        deposits.clear();
+        for block_num in from_block..=to_block {
+            let events = fetcher
+                .get_block_pay_in_events(block_num)
+                .await
+                .map_err(|_| format!("Could not fetch events of block {}", block_num))?;
+            deposits.extend(events.iter().map(Deposit::from));
+        }
+        if deposits.iter().any(|deposit| {
+            deposit.nonce == args.nonce
+                && (args.destination.is_none() || deposit.destination_id.as_deref() == args.destination.as_deref())
+        }) {
+            break;
+        }
+    }
+    Ok(deposits)
+}
+
+/// First configured relayer of the listener relaying to the deposit's destination, others are its fail-overs.
+pub fn select_relayer<'a>(
+    config: &'a BridgeConfig,
+    listener: &Listener,
+    deposit: &Deposit,
+) -> Result<&'a RelayerConfig, String> {
+    listener
+        .relayers
+        .iter()
+        .filter_map(|id| config.relayers.iter().find(|relayer| &relayer.id == id))
+        .find(|relayer| match deposit.destination_id {
+            Some(ref destination_id) => &relayer.destination_id == destination_id,
+            None => true,
+        })
+        .ok_or_else(|| {
+            format!(
+                "No relayer of {} listener relays to destination {}",
+                listener.id,
+                deposit.destination_id.as_deref().unwrap_or("-")
+            )
+        })
+}
+
+// created the same way as by the worker, including amount limits shared with it
+async fn create_relayer(
+    relayer_config: &RelayerConfig,
+    keystore_dir: &str,
+) -> Result<Arc<Box<dyn Relayer<String>>>, String> {
+    let relayers = match relayer_config.relayer_type.as_str() {
+        "ethereum" => {
+            let config = BridgeConfig {
+                listeners: vec![],
+                relayers: vec![relayer_config.clone()],
+                resources: Default::default(),
+                notifications: None,
+            };
+            ethereum_relayer::create_from_config(keystore_dir.to_string(), &config, Arc::new(Notifier::disabled()))
+                .await
+                .map_err(|_| format!("Could not create relayer {}", relayer_config.id))?
+        },
+        "substrate" => substrate_relayer::create_from_config::<CustomConfig>(
+            keystore_dir.to_string(),
+            std::slice::from_ref(relayer_config),
+            Arc::new(Notifier::disabled()),
+        )
+        .await
+        .map_err(|_| format!("Could not create relayer {}", relayer_config.id))?,
+        other => return Err(format!("Unknown type of relayer {}: {}", relayer_config.id, other)),
+    };
+    relayers
+        .get(&relayer_config.id)
+        .cloned()
+        .ok_or_else(|| format!("Could not create relayer {}", relayer_config.id))
+}
+
+async fn ethereum_deposits(listener: &Listener, args: &RedriveArgs) -> Result<Vec<Deposit>, String> {
+    let config: ethereum_listener::listener::ListenerConfig =
+        specific_config(&listener.config, "listener", &listener.id)?;
+    let bridge_contract_address = Address::from_str(&config.bridge_contract_address).map_err(|_| {
+        format!("Invalid bridge contract address of {}: {}", listener.id, config.bridge_contract_address)
+    })?;
+    let client = EthersRpcClient::new(&config.node_rpc_url, config.rpc_auth.as_ref())
+        .map_err(|_| format!("Could not create rpc client of {}", listener.id))?;
+    // finalization gap is not used, blocks are fetched directly
+    let mut fetcher = EthereumFetcher::new(0, client, HashSet::from([bridge_contract_address]));
+    search_deposits(&mut fetcher, args).await
+}
+
+async fn substrate_deposits(listener: &Listener, args: &RedriveArgs) -> Result<Vec<Deposit>, String> {
+    let config: substrate_listener::listener::ListenerConfig =
+        specific_config(&listener.config, "listener", &listener.id)?;
+    let chain = substrate_listener::resolve_chain::<CustomConfig>(&listener.id, &config)
+        .await
+        .map_err(|_| format!("Could not resolve chain of {}", listener.id))?;
+    match chain {
+        _ if chain == LocalRuntime::NAME => substrate_runtime_deposits::<LocalRuntime>(&config, args).await,
+        _ if chain == PaseoRuntime::NAME => substrate_runtime_deposits::<PaseoRuntime>(&config, args).await,
+        _ if chain == HeimaRuntime::NAME => substrate_runtime_deposits::<HeimaRuntime>(&config, args).await,
+        _ => Err(format!("Unknown chain of {}: {}", listener.id, chain)),
+    }
+}
+
+async fn substrate_runtime_deposits<Runtime: ChainRuntime>(
+    config: &substrate_listener::listener::ListenerConfig,
+    args: &RedriveArgs,
+) -> Result<Vec<Deposit>, String> {
+    let mut fetcher =
+        SubstrateFetcher::<RpcClient<CustomConfig, Runtime::PaidInEvent>, _>::new(
+            RpcClientFactory::<CustomConfig>::new(&config.ws_rpc_endpoint, config.rpc_auth.clone()),
+        );
+    search_deposits(&mut fetcher, args).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn deposit(nonce: u64, destination_id: Option<&str>) -> Deposit {
+        Deposit {
+            source_event: format!("10-0-{}", nonce),
+            source_ref: "0x".to_string(),
+            destination_id: destination_id.map(|id| id.to_string()),
+            amount: 100,
+            nonce,
+            resource_id: [0; 32],
+            recipient: Recipient::Substrate([1; 32]),
+        }
+    }
+
+    fn config() -> BridgeConfig {
+        serde_json::from_value(serde_json::json!({
+            "listeners": [
+                {"listener_type": "ethereum", "id": "sepolia", "chain_id": 0, "relayers": ["heima_backup", "heima", "bsc"], "config": {}},
+                {"listener_type": "substrate", "id": "heima_source", "chain_id": 2, "relayers": ["sepolia_relayer"], "config": {}}
+            ],
+            "relayers": [
+                {"relayer_type": "substrate", "id": "heima", "destination_id": "2", "config": {}},
+                {"relayer_type": "substrate", "id": "heima_backup", "destination_id": "2", "config": {}},
+                {"relayer_type": "ethereum", "id": "bsc", "destination_id": "56", "config": {}},
+                {"relayer_type": "ethereum", "id": "sepolia_relayer", "destination_id": "0", "config": {}}
+            ]
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    pub fn deposit_is_found_by_nonce_and_destination() {
+        let deposits = vec![deposit(1, Some("2")), deposit(2, Some("2")), deposit(2, Some("56"))];
+
+        assert_eq!(find_deposit(&deposits, 1, None), Ok(&deposits[0]));
+        assert_eq!(find_deposit(&deposits, 2, Some("56")), Ok(&deposits[2]));
+        assert!(find_deposit(&deposits, 2, None).is_err());
+        assert!(find_deposit(&deposits, 3, None).is_err());
+        assert!(find_deposit(&deposits, 1, Some("56")).is_err());
+    }
+
+    #[test]
+    pub fn search_windows_go_backwards_up_to_max_blocks() {
+        assert_eq!(search_windows(2500, 1000, 100_000), vec![(1501, 2500), (501, 1500), (0, 500)]);
+        assert_eq!(search_windows(10_000, 1000, 2500), vec![(9001, 10_000), (8001, 9000), (7501, 8000)]);
+        assert_eq!(search_windows(0, 1000, 100_000), vec![(0, 0)]);
+    }
+
+    #[test]
+    pub fn first_listener_relayer_of_destination_is_selected() {
+        let config = config();
+
+        let relayer = select_relayer(&config, &config.listeners[0], &deposit(1, Some("2"))).unwrap();
+        assert_eq!(relayer.id, "heima_backup");
+        let relayer = select_relayer(&config, &config.listeners[0], &deposit(1, Some("56"))).unwrap();
+        assert_eq!(relayer.id, "bsc");
+        assert!(select_relayer(&config, &config.listeners[0], &deposit(1, Some("0"))).is_err());
+
+        // substrate deposits are relayed by the only relayer of the listener
+        let relayer = select_relayer(&config, &config.listeners[1], &deposit(1, None)).unwrap();
+        assert_eq!(relayer.id, "sepolia_relayer");
+    }
+}
@@ -0,0 +1,355 @@
+// Copyright 2020-2024 Trust Computing GmbH.
+// This file is part of Litentry.
+//
+// Litentry is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// Litentry is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with Litentry.  If not, see <https://www.gnu.org/licenses/>.
+
+use crate::generate_config::{read_config, specific_config};
+use alloy::primitives::Address;
+use alloy::signers::local::PrivateKeySigner;
+use async_trait::async_trait;
+use bridge_core::config::{BridgeConfig, Listener};
+use clap::Args;
+use ethereum_cli::PayInCmdConf;
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
+use std::time::Duration;
+use substrate_cli::PayInConf;
+use subxt::utils::AccountId32;
+use subxt::{OnlineClient, PolkadotConfig};
+use subxt_signer::sr25519::Keypair;
+use tokio::time::{sleep, Instant};
+
+#[cfg(test)]
+use mockall::automock;
+
+const POLL_INTERVAL: Duration = Duration::from_secs(6);
+
+#[derive(Args)]
+pub struct SelfTestArgs {
+    #[arg(long)]
+    config: String,
+    /// Amount transferred in each direction
+    #[arg(long)]
+    amount: u128,
+    /// Hex encoded private key of ethereum test account, it pays in on ethereum and receives substrate pay in
+    #[arg(long, env = "SELF_TEST_ETHEREUM_KEY")]
+    ethereum_key: String,
+    /// Hex encoded sr25519 secret key of substrate test account, it receives ethereum pay in and pays in on substrate
+    #[arg(long, env = "SELF_TEST_SUBSTRATE_KEY")]
+    substrate_key: String,
+    /// Ethereum chain id substrate pay in is sent to
+    #[arg(long, default_value = "0")]
+    ethereum_id: u32,
+    /// Seconds each leg may take until the transferred amount arrives
+    #[arg(long, default_value = "300")]
+    timeout_secs: u64,
+}
+
+/// Reads balance of the account receiving a leg's transfer.
+#[cfg_attr(test, automock)]
+#[async_trait]
+pub trait BalanceReader: Send + Sync {
+    async fn balance(&self) -> Result<u128, String>;
+}
+
+struct Erc20Balance {
+    account: Address,
+    token_address: String,
+    rpc_url: String,
+}
+
+#[async_trait]
+impl BalanceReader for Erc20Balance {
+    async fn balance(&self) -> Result<u128, String> {
+        let balance = ethereum_cli::query_hei_token_amount(self.account, &self.token_address, &self.rpc_url)
+            .await
+            .map_err(|e| e.to_string())?;
+        u128::try_from(balance).map_err(|e| e.to_string())
+    }
+}
+
+struct SubstrateBalance {
+    api: OnlineClient<PolkadotConfig>,
+    account: AccountId32,
+}
+
+#[async_trait]
+impl BalanceReader for SubstrateBalance {
+    async fn balance(&self) -> Result<u128, String> {
+        substrate_cli::free_balance(&self.api, self.account.clone())
+            .await
+            .map_err(|e| e.to_string())
+    }
+}
+
+/// Outcome of a transfer in one direction, amount received if it arrived in time.
+pub struct LegReport {
+    pub name: &'static str,
+    pub start_balance: u128,
+    pub elapsed: Duration,
+    pub result: Result<u128, String>,
+}
+
+pub struct SelfTestReport {
+    pub legs: Vec<LegReport>,
+}
+
+impl SelfTestReport {
+    pub fn passed(&self) -> bool {
+        !self.legs.is_empty() && self.legs.iter().all(|leg| leg.result.is_ok())
+    }
+}
+
+impl Display for SelfTestReport {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        for leg in self.legs.iter() {
+            match leg.result {
+                Ok(received) => writeln!(
+                    f,
+                    "{}: PASS in {:.1}s, received {} (starting balance {})",
+                    leg.name,
+                    leg.elapsed.as_secs_f64(),
+                    received,
+                    leg.start_balance
+                )?,
+                Err(ref e) => writeln!(f, "{}: FAIL after {:.1}s, {}", leg.name, leg.elapsed.as_secs_f64(), e)?,
+            }
+        }
+        write!(f, "Self-test {}", if self.passed() { "passed" } else { "failed" })
+    }
+}
+
+/// Transfers `args.amount` from ethereum to substrate test account and back, waiting for each transfer to arrive.
+pub async fn handle(args: &SelfTestArgs) -> Result<SelfTestReport, String> {
+    let config = read_config(&args.config)?;
+    let ethereum: ethereum_listener::listener::ListenerConfig =
+        listener_config(&config, "ethereum").and_then(|l| specific_config(&l.config, "listener", &l.id))?;
+    let substrate: substrate_listener::listener::ListenerConfig =
+        listener_config(&config, "substrate").and_then(|l| specific_config(&l.config, "listener", &l.id))?;
+
+    let ethereum_signer =
+        PrivateKeySigner::from_str(&args.ethereum_key).map_err(|e| format!("Invalid ethereum key: {}", e))?;
+    let substrate_key = hex::decode(args.substrate_key.trim_start_matches("0x"))
+        .map_err(|e| format!("Substrate key is not hex encoded: {}", e))?;
+    let substrate_signer = substrate_key
+        .try_into()
+        .map_err(|_| "Substrate key must be 32 bytes".to_string())
+        .and_then(|key| Keypair::from_secret_key(key).map_err(|e| format!("Invalid substrate key: {:?}", e)))?;
+    let substrate_account = substrate_signer.public_key().to_account_id();
+
+    let ethereum_pay_in = PayInCmdConf {
+        dest_address: substrate_account.to_string(),
+        amount: args.amount.to_string(),
+        user_private_key: args.ethereum_key.clone(),
+        bridge_address: ethereum.bridge_contract_address.clone(),
+        ..Default::default()
+    };
+    let substrate_pay_in = PayInConf {
+        dest_address: hex::encode(ethereum_signer.address()),
+        amount: args.amount,
+        ethereum_id: args.ethereum_id,
+        ..Default::default()
+    };
+
+    let api = substrate_cli::connect(&substrate.ws_rpc_endpoint)
+        .await
+        .map_err(|e| e.to_string())?;
+    let substrate_balance = SubstrateBalance { api: api.clone(), account: substrate_account };
+    let erc20_balance = Erc20Balance {
+        account: ethereum_signer.address(),
+        token_address: ethereum_pay_in.hei_token_address.clone(),
+        rpc_url: ethereum.node_rpc_url.clone(),
+    };
+    let substrate_start_balance = substrate_balance.balance().await?;
+    let erc20_start_balance = erc20_balance.balance().await?;
+    let timeout = Duration::from_secs(args.timeout_secs);
+
+    let mut legs = vec![];
+    legs.push(
+        run_leg(
+            "ethereum -> substrate",
+            async {
+                ethereum_cli::pay_in(&ethereum_pay_in, &ethereum.node_rpc_url)
+                    .await
+                    .map(|_| ())
+                    .map_err(|e| format!("Could not pay in: {}", e))
+            },
+            &substrate_balance,
+            substrate_start_balance,
+            timeout,
+        )
+        .await,
+    );
+    // no point in waiting for the second timeout if relaying doesn't work
+    if legs[0].result.is_ok() {
+        legs.push(
+            run_leg(
+                "substrate -> ethereum",
+                async {
+                    substrate_cli::pay_in(&api, &substrate_pay_in, &substrate_signer)
+                        .await
+                        .map(|_| ())
+                        .map_err(|e| format!("Could not pay in: {}", e))
+                },
+                &erc20_balance,
+                erc20_start_balance,
+                timeout,
+            )
+            .await,
+        );
+    }
+    Ok(SelfTestReport { legs })
+}
+
+fn listener_config<'a>(config: &'a BridgeConfig, listener_type: &str) -> Result<&'a Listener, String> {
+    config
+        .listeners
+        .iter()
+        .find(|listener| listener.listener_type == listener_type)
+        .ok_or_else(|| format!("No {} listener in config", listener_type))
+}
+
+async fn run_leg<R: BalanceReader>(
+    name: &'static str,
+    pay_in: impl std::future::Future<Output = Result<(), String>>,
+    reader: &R,
+    start_balance: u128,
+    timeout: Duration,
+) -> LegReport {
+    let started = Instant::now();
+    let result = match pay_in.await {
+        Ok(()) => await_balance_increase(reader, start_balance, timeout, POLL_INTERVAL)
+            .await
+            .map(|balance| balance - start_balance),
+        Err(e) => Err(e),
+    };
+    LegReport { name, start_balance, elapsed: started.elapsed(), result }
+}
+
+/// Polls `reader` until the balance exceeds `start_balance`, returns the increased balance.
+/// Read errors are retried, the node may be briefly unavailable.
+pub async fn await_balance_increase<R: BalanceReader>(
+    reader: &R,
+    start_balance: u128,
+    timeout: Duration,
+    poll_interval: Duration,
+) -> Result<u128, String> {
+    let deadline = Instant::now() + timeout;
+    loop {
+        match reader.balance().await {
+            Ok(balance) if balance > start_balance => return Ok(balance),
+            Ok(_) => {},
+            Err(e) => eprintln!("Could not read balance: {}", e),
+        }
+        if Instant::now() + poll_interval > deadline {
+            return Err(format!("Balance did not increase within {}s", timeout.as_secs()));
+        }
+        sleep(poll_interval).await;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicU32, Ordering};
+    use std::sync::Arc;
+
+    // returns `balances` on subsequent reads, the last one repeatedly
+    fn reader(balances: Vec<Result<u128, String>>) -> (MockBalanceReader, Arc<AtomicU32>) {
+        let reads = Arc::new(AtomicU32::new(0));
+        let counter = reads.clone();
+        let mut reader = MockBalanceReader::new();
+        reader.expect_balance().returning(move || {
+            let read = counter.fetch_add(1, Ordering::SeqCst) as usize;
+            balances[read.min(balances.len() - 1)].clone()
+        });
+        (reader, reads)
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn increased_balance_is_returned() {
+        let (reader, reads) = reader(vec![Ok(100), Ok(100), Ok(150)]);
+
+        let balance = await_balance_increase(&reader, 100, Duration::from_secs(60), Duration::from_secs(6)).await;
+
+        assert_eq!(balance, Ok(150));
+        assert_eq!(reads.load(Ordering::SeqCst), 3);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn read_errors_are_retried() {
+        let (reader, _) = reader(vec![Err("connection refused".to_string()), Ok(150)]);
+
+        let balance = await_balance_increase(&reader, 100, Duration::from_secs(60), Duration::from_secs(6)).await;
+
+        assert_eq!(balance, Ok(150));
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn polling_times_out_if_balance_does_not_increase() {
+        let (reader, reads) = reader(vec![Ok(100)]);
+        let started = Instant::now();
+
+        let balance = await_balance_increase(&reader, 100, Duration::from_secs(60), Duration::from_secs(6)).await;
+
+        assert_eq!(balance, Err("Balance did not increase within 60s".to_string()));
+        assert_eq!(reads.load(Ordering::SeqCst), 11);
+        assert!(started.elapsed() <= Duration::from_secs(60));
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn leg_reports_received_amount() {
+        let (reader, _) = reader(vec![Ok(100), Ok(1100)]);
+
+        let leg = run_leg("ethereum -> substrate", async { Ok(()) }, &reader, 100, Duration::from_secs(60)).await;
+
+        assert_eq!(leg.result, Ok(1000));
+        assert_eq!(leg.elapsed, POLL_INTERVAL);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn failed_pay_in_fails_leg_without_polling() {
+        let mut reader = MockBalanceReader::new();
+        reader.expect_balance().never();
+
+        let leg = run_leg(
+            "ethereum -> substrate",
+            async { Err("Could not pay in: reverted".to_string()) },
+            &reader,
+            100,
+            Duration::from_secs(60),
+        )
+        .await;
+
+        assert_eq!(leg.result, Err("Could not pay in: reverted".to_string()));
+    }
+
+    #[test]
+    fn report_fails_if_any_leg_fails() {
+        let leg = |name, result| LegReport { name, start_balance: 0, elapsed: Duration::from_secs(3), result };
+        let passed =
+            SelfTestReport { legs: vec![leg("ethereum -> substrate", Ok(10)), leg("substrate -> ethereum", Ok(10))] };
+        let failed = SelfTestReport {
+            legs: vec![leg("ethereum -> substrate", Err("Balance did not increase within 60s".to_string()))],
+        };
+
+        assert!(passed.passed());
+        assert!(!failed.passed());
+        assert!(!SelfTestReport { legs: vec![] }.passed());
+        assert_eq!(
+            failed.to_string(),
+            "ethereum -> substrate: FAIL after 3.0s, Balance did not increase within 60s\nSelf-test failed"
+        );
+    }
+}
@@ -0,0 +1,94 @@
+// Copyright 2020-2024 Trust Computing GmbH.
+// This file is part of Litentry.
+//
+// Litentry is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// Litentry is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with Litentry.  If not, see <https://www.gnu.org/licenses/>.
+
+use bridge_core::shielding::{max_oaep_len, oaep_encrypt, ShieldingPublicKey};
+use clap::Args;
+use std::fs;
+
+#[derive(Args)]
+pub struct ShieldKeyArgs {
+    /// Shielding public key JSON, as returned by `hm_getShieldingKey`
+    #[arg(long)]
+    pubkey: String,
+    /// Hex encoded relayer key
+    #[arg(long)]
+    key: String,
+}
+
+/// Returns hex encoded relayer key shielded with RSA-OAEP, ready for `hm_importRelayerKey` payload.
+pub fn handle(args: &ShieldKeyArgs) -> Result<String, String> {
+    let public_key = fs::read(&args.pubkey).map_err(|e| format!("Could not read {}: {}", args.pubkey, e))?;
+    let public_key: ShieldingPublicKey =
+        serde_json::from_slice(&public_key).map_err(|e| format!("Could not parse shielding key: {}", e))?;
+    let public_key = public_key.to_rsa().map_err(|_| "Invalid shielding key".to_string())?;
+
+    let key = fs::read_to_string(&args.key).map_err(|e| format!("Could not read {}: {}", args.key, e))?;
+    let key = hex::decode(key.trim().trim_start_matches("0x")).map_err(|e| format!("Key is not hex encoded: {}", e))?;
+    if key.len() > max_oaep_len(&public_key) {
+        return Err(format!(
+            "Key of {} bytes exceeds {} bytes allowed by shielding key",
+            key.len(),
+            max_oaep_len(&public_key)
+        ));
+    }
+
+    oaep_encrypt(&public_key, &key)
+        .map(hex::encode)
+        .map_err(|_| "Could not shield key".to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rsa::rand_core::OsRng;
+    use rsa::{Oaep, RsaPrivateKey};
+    use sha2::Sha256;
+
+    fn args(dir: &tempfile::TempDir, private_key: &RsaPrivateKey, key: &str) -> ShieldKeyArgs {
+        let pubkey = dir.path().join("shielding_key.json");
+        let public_key = ShieldingPublicKey::from_rsa(&private_key.to_public_key());
+        fs::write(&pubkey, serde_json::to_vec(&public_key).unwrap()).unwrap();
+        let key_path = dir.path().join("relayer_key");
+        fs::write(&key_path, key).unwrap();
+        ShieldKeyArgs { pubkey: pubkey.to_str().unwrap().to_string(), key: key_path.to_str().unwrap().to_string() }
+    }
+
+    #[test]
+    pub fn shielded_key_is_decryptable_with_shielding_key() {
+        let dir = tempfile::tempdir().unwrap();
+        let private_key = RsaPrivateKey::new(&mut OsRng, 2048).unwrap();
+        let key = "e5be9a5092b81bca64be81d212e7f2f9eba183bb7a90954f7b76361f6edb5c0a";
+
+        let shielded = handle(&args(&dir, &private_key, &format!("{}\n", key))).unwrap();
+
+        let unshielded = private_key
+            .decrypt(Oaep::new::<Sha256>(), &hex::decode(shielded).unwrap())
+            .unwrap();
+        assert_eq!(hex::encode(unshielded), key);
+    }
+
+    #[test]
+    pub fn key_exceeding_modulus_is_rejected() {
+        let dir = tempfile::tempdir().unwrap();
+        let private_key = RsaPrivateKey::new(&mut OsRng, 1024).unwrap();
+        // 1024 bit modulus fits at most 62 bytes
+        let key = "00".repeat(63);
+
+        assert!(handle(&args(&dir, &private_key, &key))
+            .unwrap_err()
+            .contains("exceeds 62 bytes"));
+    }
+}
@@ -0,0 +1,115 @@
+// Copyright 2020-2024 Trust Computing GmbH.
+// This file is part of Litentry.
+//
+// Litentry is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// Litentry is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with Litentry.  If not, see <https://www.gnu.org/licenses/>.
+
+use clap::Args;
+use std::fs;
+use std::path::Path;
+use substrate_relayer::offline_signing::{sign_request, signature_path, SigningRequest};
+
+#[derive(Args)]
+pub struct SignPayOutArgs {
+    /// Signing request file, as queued by relayer in `offline_signing.queue_dir`
+    #[arg(long)]
+    request: String,
+    /// Hex encoded sr25519 secret key of relayer account
+    #[arg(long)]
+    key: String,
+}
+
+/// Signs the queued request and writes the signature next to it, returns the signature path.
+pub fn handle(args: &SignPayOutArgs) -> Result<String, String> {
+    let request_path = Path::new(&args.request);
+    let signature_path =
+        signature_path(request_path).ok_or_else(|| format!("{} is not a signing request file", args.request))?;
+    let request = fs::read(request_path).map_err(|e| format!("Could not read {}: {}", args.request, e))?;
+    let request: SigningRequest =
+        serde_json::from_slice(&request).map_err(|e| format!("Could not parse signing request: {}", e))?;
+
+    let key = fs::read_to_string(&args.key).map_err(|e| format!("Could not read {}: {}", args.key, e))?;
+    let key = hex::decode(key.trim().trim_start_matches("0x")).map_err(|e| format!("Key is not hex encoded: {}", e))?;
+    let key: [u8; 32] = key
+        .try_into()
+        .map_err(|key: Vec<u8>| format!("Key must be 32 bytes, got {}", key.len()))?;
+
+    let signed = sign_request(&request, key)?;
+    fs::write(&signature_path, serde_json::to_vec(&signed).unwrap())
+        .map_err(|e| format!("Could not write {}: {}", signature_path.display(), e))?;
+    Ok(signature_path.display().to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use substrate_relayer::offline_signing::SignedPayload;
+    use subxt_signer::sr25519::Keypair;
+
+    // dev key of local keystore
+    const KEY: &str = "e5be9a5092b81bca64be81d212e7f2f9eba183bb7a90954f7b76361f6edb5c0a";
+
+    fn signer() -> String {
+        let key: [u8; 32] = hex::decode(KEY).unwrap().try_into().unwrap();
+        Keypair::from_secret_key(key).unwrap().public_key().to_account_id().to_string()
+    }
+
+    fn args(dir: &tempfile::TempDir, request_file: &str, signer: &str) -> SignPayOutArgs {
+        let request_path = dir.path().join(request_file);
+        let request = SigningRequest {
+            chain: "local".to_string(),
+            nonce: 1,
+            signer: signer.to_string(),
+            call_data: "0102".to_string(),
+            signer_payload: "0a0b0c".to_string(),
+        };
+        fs::write(&request_path, serde_json::to_vec(&request).unwrap()).unwrap();
+        let key_path = dir.path().join("relayer_key");
+        fs::write(&key_path, format!("0x{}\n", KEY)).unwrap();
+        SignPayOutArgs {
+            request: request_path.to_str().unwrap().to_string(),
+            key: key_path.to_str().unwrap().to_string(),
+        }
+    }
+
+    #[test]
+    pub fn signature_is_written_next_to_request() {
+        let dir = tempfile::tempdir().unwrap();
+
+        let path = handle(&args(&dir, "local-1.request.json", &signer())).unwrap();
+
+        assert_eq!(path, dir.path().join("local-1.signature.json").display().to_string());
+        let signed: SignedPayload = serde_json::from_slice(&fs::read(path).unwrap()).unwrap();
+        assert_eq!(signed.signer_payload, "0a0b0c");
+        assert_eq!(hex::decode(signed.signature).unwrap().len(), 64);
+    }
+
+    #[test]
+    pub fn file_other_than_request_is_rejected() {
+        let dir = tempfile::tempdir().unwrap();
+
+        assert!(handle(&args(&dir, "local-1.json", &signer()))
+            .unwrap_err()
+            .contains("is not a signing request file"));
+    }
+
+    #[test]
+    pub fn request_of_other_signer_is_rejected() {
+        let dir = tempfile::tempdir().unwrap();
+        let bob = "5FHneW46xGXgs5mUiveU4sbTyGBzmstUspZC92UhjJM694ty";
+
+        assert!(handle(&args(&dir, "local-1.request.json", bob))
+            .unwrap_err()
+            .contains("is to be signed by"));
+    }
+}
@@ -0,0 +1,151 @@
+// Copyright 2020-2024 Trust Computing GmbH.
+// This file is part of Litentry.
+//
+// Litentry is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// Litentry is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with Litentry.  If not, see <https://www.gnu.org/licenses/>.
+
+use alloy::primitives::Address;
+use alloy::signers::local::PrivateKeySigner;
+use clap::{Args, ValueEnum};
+use std::fs;
+use std::str::FromStr;
+use subxt::utils::AccountId32;
+use subxt_signer::sr25519::Keypair;
+
+#[derive(Clone, Copy, Debug, ValueEnum)]
+pub enum KeyKind {
+    Ethereum,
+    Substrate,
+}
+
+#[derive(Args)]
+pub struct VerifyKeyArgs {
+    /// Type of relayer the key belongs to
+    #[arg(long = "type", value_enum)]
+    key_type: KeyKind,
+    /// Relayer key, either raw 32 bytes as in keystore `<id>.bin` or hex encoded
+    #[arg(long)]
+    key: String,
+    /// Address the key is expected to control, hex for ethereum and SS58 for substrate
+    #[arg(long)]
+    expect: String,
+}
+
+/// Derives address of the relayer key and returns it if it's the expected one.
+pub fn handle(args: &VerifyKeyArgs) -> Result<String, String> {
+    let key = fs::read(&args.key).map_err(|e| format!("Could not read {}: {}", args.key, e))?;
+    let key = parse_key(key)?;
+
+    let (derived, matches) = match args.key_type {
+        KeyKind::Ethereum => {
+            let expected = Address::from_str(&args.expect)
+                .map_err(|e| format!("Invalid ethereum address {}: {}", args.expect, e))?;
+            let derived = PrivateKeySigner::from_slice(&key)
+                .map_err(|e| format!("Invalid ethereum key: {}", e))?
+                .address();
+            (derived.to_string(), derived == expected)
+        },
+        KeyKind::Substrate => {
+            let expected = AccountId32::from_str(&args.expect)
+                .map_err(|e| format!("Invalid substrate address {}: {:?}", args.expect, e))?;
+            let derived = Keypair::from_secret_key(key)
+                .map_err(|e| format!("Invalid substrate key: {:?}", e))?
+                .public_key()
+                .to_account_id();
+            (derived.to_string(), derived == expected)
+        },
+    };
+    if !matches {
+        return Err(format!("Key controls {}, expected {}", derived, args.expect));
+    }
+    Ok(derived)
+}
+
+// hex encoded key files are accepted too, like for `shield-key`
+fn parse_key(content: Vec<u8>) -> Result<[u8; 32], String> {
+    let decoded = std::str::from_utf8(&content)
+        .ok()
+        .and_then(|text| hex::decode(text.trim().trim_start_matches("0x")).ok());
+    decoded
+        .unwrap_or(content)
+        .try_into()
+        .map_err(|key: Vec<u8>| format!("Key must be 32 bytes, got {}", key.len()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ETHEREUM_KEY: &str = "ac0974bec39a17e36ba4a4b4d238ff944bacb478cbed5efcae784d7bf4f2ff80";
+    const ETHEREUM_ADDRESS: &str = "0x8f96af7907c4B559530020fcA7c287b2a8FEfDCD";
+    // Alice
+    const SUBSTRATE_KEY: &str = "e5be9a5092b81bca64be81d212e7f2f9eba183bb7a90954f7b76361f6edb5c0a";
+    const SUBSTRATE_ADDRESS: &str = "5GrwvaEF5zXb26Fz9rcQpDWS57CtERHpNehXCPcNoHGKutQY";
+
+    fn args(dir: &tempfile::TempDir, key_type: KeyKind, key: &[u8], expect: &str) -> VerifyKeyArgs {
+        let key_path = dir.path().join("relayer.bin");
+        fs::write(&key_path, key).unwrap();
+        VerifyKeyArgs { key_type, key: key_path.to_str().unwrap().to_string(), expect: expect.to_string() }
+    }
+
+    #[test]
+    pub fn ethereum_key_matching_address_is_verified() {
+        let dir = tempfile::tempdir().unwrap();
+        let key = hex::decode(ETHEREUM_KEY).unwrap();
+
+        let verified = handle(&args(&dir, KeyKind::Ethereum, &key, &ETHEREUM_ADDRESS.to_lowercase())).unwrap();
+        assert_eq!(verified, ETHEREUM_ADDRESS);
+
+        let hex_key = format!("0x{}\n", ETHEREUM_KEY);
+        assert!(handle(&args(&dir, KeyKind::Ethereum, hex_key.as_bytes(), ETHEREUM_ADDRESS)).is_ok());
+    }
+
+    #[test]
+    pub fn ethereum_key_not_matching_address_is_rejected() {
+        let dir = tempfile::tempdir().unwrap();
+        let key = hex::decode(ETHEREUM_KEY).unwrap();
+
+        // anvil's second dev account
+        let error =
+            handle(&args(&dir, KeyKind::Ethereum, &key, "0x70997970C51812dc3A010C7d01b50e0d17dc79C8")).unwrap_err();
+        assert!(error.contains(ETHEREUM_ADDRESS));
+    }
+
+    #[test]
+    pub fn substrate_key_matching_address_is_verified() {
+        let dir = tempfile::tempdir().unwrap();
+        let key = hex::decode(SUBSTRATE_KEY).unwrap();
+
+        let verified = handle(&args(&dir, KeyKind::Substrate, &key, SUBSTRATE_ADDRESS)).unwrap();
+        assert_eq!(verified, SUBSTRATE_ADDRESS);
+    }
+
+    #[test]
+    pub fn substrate_key_not_matching_address_is_rejected() {
+        let dir = tempfile::tempdir().unwrap();
+        let key = hex::decode(SUBSTRATE_KEY).unwrap();
+
+        // Bob
+        let error = handle(&args(&dir, KeyKind::Substrate, &key, "5FHneW46xGXgs5mUiveU4sbTyGBzmstUspZC92UhjJM694ty"))
+            .unwrap_err();
+        assert!(error.contains(SUBSTRATE_ADDRESS));
+    }
+
+    #[test]
+    pub fn key_of_wrong_length_is_rejected() {
+        let dir = tempfile::tempdir().unwrap();
+
+        let error = handle(&args(&dir, KeyKind::Substrate, &[1; 31], SUBSTRATE_ADDRESS)).unwrap_err();
+        assert_eq!(error, "Key must be 32 bytes, got 31");
+    }
+}
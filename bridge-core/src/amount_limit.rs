@@ -0,0 +1,388 @@
+// Copyright 2020-2024 Trust Computing GmbH.
+// This file is part of Litentry.
+//
+// Litentry is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// Litentry is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with Litentry.  If not, see <https://www.gnu.org/licenses/>.
+
+use parity_scale_codec::{Decode, Encode};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::collections::HashMap;
+use std::fs;
+use std::io::ErrorKind;
+use std::sync::Mutex;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+pub const DEFAULT_WINDOW_SECS: u64 = 24 * 60 * 60;
+
+/// File payouts of relayer `relayer_id` are persisted in unless configured otherwise
+pub fn default_payouts_file(relayer_id: &str) -> String {
+    format!("data/{}_relayer_payouts.bin", relayer_id)
+}
+
+/// Caps on amounts paid out by a relayer for a single resource id. Amounts may be given as json numbers
+/// or decimal strings, the latter is required for values not fitting into `u64`.
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
+pub struct AmountLimit {
+    #[serde(default, deserialize_with = "deserialize_amount", serialize_with = "serialize_amount")]
+    pub max_single_amount: Option<u128>,
+    #[serde(default, deserialize_with = "deserialize_amount", serialize_with = "serialize_amount")]
+    pub max_window_amount: Option<u128>,
+    #[serde(default = "default_window_secs")]
+    pub window_secs: u64,
+}
+
+fn default_window_secs() -> u64 {
+    DEFAULT_WINDOW_SECS
+}
+
+fn serialize_amount<S: Serializer>(amount: &Option<u128>, serializer: S) -> Result<S::Ok, S::Error> {
+    amount.map(|amount| amount.to_string()).serialize(serializer)
+}
+
+fn deserialize_amount<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Option<u128>, D::Error> {
+    match Option::<serde_json::Value>::deserialize(deserializer)? {
+        None => Ok(None),
+        Some(serde_json::Value::Number(n)) => n
+            .as_u64()
+            .map(|n| Some(n.into()))
+            .ok_or_else(|| serde::de::Error::custom("amount must be unsigned integer")),
+        Some(serde_json::Value::String(s)) => s.parse().map(Some).map_err(serde::de::Error::custom),
+        Some(_) => Err(serde::de::Error::custom("amount must be number or decimal string")),
+    }
+}
+
+/// Bounds on amount of every request relayed by a relayer, regardless of resource id. Dust below `min_amount`
+/// is not worth relaying, amounts above `max_amount` are treated as misconfiguration and require manual review.
+#[derive(Clone, Copy, Debug, Default, Deserialize, Serialize, PartialEq)]
+pub struct AmountBounds {
+    #[serde(default, deserialize_with = "deserialize_amount", serialize_with = "serialize_amount")]
+    pub min_amount: Option<u128>,
+    #[serde(default, deserialize_with = "deserialize_amount", serialize_with = "serialize_amount")]
+    pub max_amount: Option<u128>,
+}
+
+#[derive(Debug, PartialEq)]
+pub enum AmountOutOfBounds {
+    BelowMin { min: u128 },
+    AboveMax { max: u128 },
+}
+
+impl AmountBounds {
+    pub fn check(&self, amount: u128) -> Result<(), AmountOutOfBounds> {
+        if let Some(min) = self.min_amount {
+            if amount < min {
+                return Err(AmountOutOfBounds::BelowMin { min });
+            }
+        }
+        if let Some(max) = self.max_amount {
+            if amount > max {
+                return Err(AmountOutOfBounds::AboveMax { max });
+            }
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub enum LimitExceeded {
+    SingleAmount { max: u128 },
+    WindowAmount { max: u128, window_total: u128 },
+}
+
+// (unix timestamp in seconds, amount) of payouts within the window
+type Payouts = Vec<(u64, u128)>;
+
+#[derive(Default, Encode, Decode)]
+struct PayoutWindows {
+    // resource id -> payouts within the window
+    payouts: Vec<([u8; 32], Payouts)>,
+}
+
+/// Enforces `AmountLimit`s per resource id. Payouts counted towards rolling windows are persisted
+/// in `file_name` (if set), so the windows survive restarts.
+pub struct AmountLimiter {
+    limits: HashMap<[u8; 32], AmountLimit>,
+    payouts: Mutex<HashMap<[u8; 32], Payouts>>,
+    file_name: Option<String>,
+}
+
+impl AmountLimiter {
+    /// Limiter which accepts any amount
+    pub fn unlimited() -> Self {
+        Self { limits: HashMap::new(), payouts: Mutex::new(HashMap::new()), file_name: None }
+    }
+
+    /// `limits` are keyed by hex encoded resource id
+    #[allow(clippy::result_unit_err)]
+    pub fn new(limits: &HashMap<String, AmountLimit>, file_name: Option<String>) -> Result<Self, ()> {
+        let mut parsed_limits = HashMap::new();
+        for (resource_id, limit) in limits {
+            let resource_id: [u8; 32] = hex::decode(resource_id.trim_start_matches("0x"))
+                .ok()
+                .and_then(|bytes| bytes.try_into().ok())
+                .ok_or_else(|| log::error!("Invalid resource id in amount limits: {}", resource_id))?;
+            parsed_limits.insert(resource_id, limit.clone());
+        }
+
+        let payouts = match file_name {
+            Some(ref file_name) => match fs::read(file_name) {
+                Ok(content) => PayoutWindows::decode(&mut content.as_slice())
+                    .map_err(|e| log::error!("Could not decode payout windows: {:?}", e))?
+                    .payouts
+                    .into_iter()
+                    .collect(),
+                Err(e) if e.kind() == ErrorKind::NotFound => HashMap::new(),
+                Err(e) => {
+                    log::error!("Could not read payout windows from {}: {:?}", file_name, e);
+                    return Err(());
+                },
+            },
+            None => HashMap::new(),
+        };
+
+        Ok(Self { limits: parsed_limits, payouts: Mutex::new(payouts), file_name })
+    }
+
+    /// Checks whether paying out `amount` at `now` (unix timestamp in seconds) stays within limits
+    pub fn check(&self, resource_id: &[u8; 32], amount: u128, now: u64) -> Result<(), LimitExceeded> {
+        let payouts = self.payouts.lock().unwrap();
+        self.check_locked(&payouts, resource_id, amount, now)
+    }
+
+    /// Checks `amount` and counts it towards the rolling window under a single lock, so concurrent
+    /// requests can't both pass the check. The amount is uncounted again unless the reservation is committed.
+    pub fn reserve(&self, resource_id: &[u8; 32], amount: u128, now: u64) -> Result<Reservation<'_>, LimitExceeded> {
+        let mut payouts = self.payouts.lock().unwrap();
+        self.check_locked(&payouts, resource_id, amount, now)?;
+        Ok(self.reserve_locked(&mut payouts, resource_id, amount, now))
+    }
+
+    /// Counts `amount` towards the rolling window without checking limits, used by manually re-driven requests
+    pub fn reserve_unchecked(&self, resource_id: &[u8; 32], amount: u128, now: u64) -> Reservation<'_> {
+        let mut payouts = self.payouts.lock().unwrap();
+        self.reserve_locked(&mut payouts, resource_id, amount, now)
+    }
+
+    /// Counts successfully relayed `amount` towards the rolling window
+    pub fn record(&self, resource_id: &[u8; 32], amount: u128, now: u64) {
+        self.reserve_unchecked(resource_id, amount, now).commit();
+    }
+
+    fn check_locked(
+        &self,
+        payouts: &HashMap<[u8; 32], Payouts>,
+        resource_id: &[u8; 32],
+        amount: u128,
+        now: u64,
+    ) -> Result<(), LimitExceeded> {
+        let Some(limit) = self.limits.get(resource_id) else {
+            return Ok(());
+        };
+        if let Some(max) = limit.max_single_amount {
+            if amount > max {
+                return Err(LimitExceeded::SingleAmount { max });
+            }
+        }
+        if let Some(max) = limit.max_window_amount {
+            let window_total = window_total(payouts.get(resource_id), limit.window_secs, now);
+            if window_total.saturating_add(amount) > max {
+                return Err(LimitExceeded::WindowAmount { max, window_total });
+            }
+        }
+        Ok(())
+    }
+
+    fn reserve_locked(
+        &self,
+        payouts: &mut HashMap<[u8; 32], Payouts>,
+        resource_id: &[u8; 32],
+        amount: u128,
+        now: u64,
+    ) -> Reservation<'_> {
+        let payout = self
+            .limits
+            .get(resource_id)
+            .filter(|limit| limit.max_window_amount.is_some())
+            .map(|limit| {
+                let resource_payouts = payouts.entry(*resource_id).or_default();
+                resource_payouts.retain(|(timestamp, _)| in_window(*timestamp, limit.window_secs, now));
+                resource_payouts.push((now, amount));
+                (now, amount)
+            });
+        Reservation { limiter: self, resource_id: *resource_id, payout }
+    }
+
+    fn persist(&self, payouts: &HashMap<[u8; 32], Payouts>) {
+        if let Some(ref file_name) = self.file_name {
+            let windows = PayoutWindows { payouts: payouts.iter().map(|(k, v)| (*k, v.clone())).collect() };
+            if let Err(e) = fs::write(file_name, windows.encode()) {
+                log::error!("Could not persist payout windows to {}: {:?}", file_name, e);
+            }
+        }
+    }
+}
+
+/// Amount counted towards the rolling window by `AmountLimiter::reserve`, uncounted on drop unless committed.
+pub struct Reservation<'a> {
+    limiter: &'a AmountLimiter,
+    resource_id: [u8; 32],
+    // `None` if the resource has no window limit
+    payout: Option<(u64, u128)>,
+}
+
+impl Reservation<'_> {
+    /// Keeps the amount counted and persists it, called once the request is relayed
+    pub fn commit(mut self) {
+        if self.payout.take().is_some() {
+            self.limiter.persist(&self.limiter.payouts.lock().unwrap());
+        }
+    }
+}
+
+impl Drop for Reservation<'_> {
+    fn drop(&mut self) {
+        let Some(payout) = self.payout else {
+            return;
+        };
+        let mut payouts = self.limiter.payouts.lock().unwrap();
+        if let Some(resource_payouts) = payouts.get_mut(&self.resource_id) {
+            if let Some(position) = resource_payouts.iter().position(|p| *p == payout) {
+                resource_payouts.remove(position);
+            }
+        }
+    }
+}
+
+fn window_total(payouts: Option<&Payouts>, window_secs: u64, now: u64) -> u128 {
+    payouts
+        .map(|payouts| {
+            payouts
+                .iter()
+                .filter(|(timestamp, _)| in_window(*timestamp, window_secs, now))
+                .fold(0u128, |total, (_, amount)| total.saturating_add(*amount))
+        })
+        .unwrap_or(0)
+}
+
+fn in_window(timestamp: u64, window_secs: u64, now: u64) -> bool {
+    timestamp.saturating_add(window_secs) > now
+}
+
+pub fn unix_now() -> u64 {
+    SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_secs()).unwrap_or(0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const RESOURCE_ID: [u8; 32] = [1; 32];
+
+    fn limits(max_single_amount: Option<u128>, max_window_amount: Option<u128>) -> HashMap<String, AmountLimit> {
+        HashMap::from([(
+            hex::encode(RESOURCE_ID),
+            AmountLimit { max_single_amount, max_window_amount, window_secs: 100 },
+        )])
+    }
+
+    #[test]
+    fn single_amount_limit_is_enforced() {
+        let limiter = AmountLimiter::new(&limits(Some(10), None), None).unwrap();
+        assert!(limiter.check(&RESOURCE_ID, 10, 0).is_ok());
+        assert_eq!(limiter.check(&RESOURCE_ID, 11, 0), Err(LimitExceeded::SingleAmount { max: 10 }));
+        // other resources are not limited
+        assert!(limiter.check(&[2; 32], 11, 0).is_ok());
+    }
+
+    #[test]
+    fn window_limit_is_enforced_and_expires() {
+        let limiter = AmountLimiter::new(&limits(None, Some(10)), None).unwrap();
+        limiter.record(&RESOURCE_ID, 6, 1000);
+        limiter.record(&RESOURCE_ID, 4, 1050);
+
+        assert_eq!(
+            limiter.check(&RESOURCE_ID, 1, 1099),
+            Err(LimitExceeded::WindowAmount { max: 10, window_total: 10 })
+        );
+        // first payout left the window
+        assert!(limiter.check(&RESOURCE_ID, 6, 1100).is_ok());
+        assert!(limiter.check(&RESOURCE_ID, 7, 1100).is_err());
+        // both payouts left the window
+        assert!(limiter.check(&RESOURCE_ID, 10, 1150).is_ok());
+    }
+
+    #[test]
+    fn window_survives_restart() {
+        let file_name = "window_survives_restart.bin".to_string();
+        let _ = fs::remove_file(&file_name);
+
+        let limiter = AmountLimiter::new(&limits(None, Some(10)), Some(file_name.clone())).unwrap();
+        limiter.record(&RESOURCE_ID, 8, 1000);
+        drop(limiter);
+
+        let limiter = AmountLimiter::new(&limits(None, Some(10)), Some(file_name.clone())).unwrap();
+        assert!(limiter.check(&RESOURCE_ID, 3, 1050).is_err());
+        assert!(limiter.check(&RESOURCE_ID, 2, 1050).is_ok());
+        assert!(limiter.check(&RESOURCE_ID, 10, 1100).is_ok());
+
+        fs::remove_file(file_name).unwrap();
+    }
+
+    #[test]
+    fn reservation_counts_towards_window_until_dropped() {
+        let limiter = AmountLimiter::new(&limits(None, Some(10)), None).unwrap();
+
+        let reservation = limiter.reserve(&RESOURCE_ID, 6, 1000).unwrap();
+        // concurrent request sees the pending amount
+        assert_eq!(
+            limiter.reserve(&RESOURCE_ID, 5, 1000).err(),
+            Some(LimitExceeded::WindowAmount { max: 10, window_total: 6 })
+        );
+        // failed relay releases the amount
+        drop(reservation);
+        limiter.reserve(&RESOURCE_ID, 10, 1000).unwrap().commit();
+        assert!(limiter.check(&RESOURCE_ID, 1, 1000).is_err());
+    }
+
+    #[test]
+    fn unchecked_reservation_bypasses_limits() {
+        let limiter = AmountLimiter::new(&limits(Some(10), Some(10)), None).unwrap();
+
+        limiter.reserve_unchecked(&RESOURCE_ID, 20, 1000).commit();
+        // the amount is counted nevertheless
+        assert_eq!(
+            limiter.check(&RESOURCE_ID, 1, 1000),
+            Err(LimitExceeded::WindowAmount { max: 10, window_total: 20 })
+        );
+    }
+
+    #[test]
+    fn amounts_can_be_configured_as_strings() {
+        let limit: AmountLimit =
+            serde_json::from_str(r#"{"max_single_amount": "1000000000000000000000000", "max_window_amount": 5}"#)
+                .unwrap();
+        assert_eq!(limit.max_single_amount, Some(1_000_000_000_000_000_000_000_000));
+        assert_eq!(limit.max_window_amount, Some(5));
+        assert_eq!(limit.window_secs, DEFAULT_WINDOW_SECS);
+    }
+
+    #[test]
+    fn amount_bounds_are_enforced() {
+        let bounds = AmountBounds { min_amount: Some(10), max_amount: Some(100) };
+        assert_eq!(bounds.check(9), Err(AmountOutOfBounds::BelowMin { min: 10 }));
+        assert!(bounds.check(10).is_ok());
+        assert!(bounds.check(100).is_ok());
+        assert_eq!(bounds.check(101), Err(AmountOutOfBounds::AboveMax { max: 100 }));
+        assert!(AmountBounds::default().check(u128::MAX).is_ok());
+    }
+}
@@ -14,23 +14,44 @@
 // You should have received a copy of the GNU General Public License
 // along with Litentry.  If not, see <https://www.gnu.org/licenses/>.
 
-use std::collections::HashSet;
-
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::path::Path;
+
+use crate::listener::{DEFAULT_FETCH_TIMEOUT, DEFAULT_POLL_INTERVAL};
+use crate::notification::NotificationsConfig;
+use crate::primitives::metric_name;
+use crate::redaction::redact;
+use crate::resource_registry::{parse_resource_id, AssetInfo};
 use itertools::Itertools;
 use serde::de::DeserializeOwned;
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 use thiserror::Error;
 
-#[derive(Deserialize)]
+const MAX_ID_LEN: usize = 64;
+
+// relayer specific settings keyed by hex encoded resource id
+const RESOURCE_KEYED_RELAYER_SETTINGS: [&str; 2] = ["amount_limits", "assets"];
+
+#[derive(Deserialize, Serialize)]
 pub struct BridgeConfig {
     pub listeners: Vec<Listener>,
     pub relayers: Vec<Relayer>,
+    // bridged assets keyed by hex encoded resource id
+    #[serde(default)]
+    pub resources: HashMap<String, AssetInfo>,
+    #[serde(default)]
+    pub notifications: Option<NotificationsConfig>,
 }
 
 #[derive(Debug, Error)]
 pub enum ConfigError {
     #[error("Listener ids are not unique")]
     ListenerIdNotUnique,
+    #[error("Id {0} is invalid, only up to 64 alphanumeric characters, dashes and underscores are allowed")]
+    InvalidId(String),
+    #[error("Listener id {0} collides with another listener id in file or metric names")]
+    ListenerIdCollision(String),
     #[error("Listener chain ids are not unique")]
     ListenerChainIdNotUnique,
     #[error("Listener relayer array is empty")]
@@ -47,9 +68,47 @@ pub enum ConfigError {
     RelayerNotUsed,
     #[error("Relayer type is unknown")]
     RelayerTypeUnknown,
+    #[error("Poll interval of listener {0} must be greater than 0")]
+    ListenerPollIntervalZero(String),
+    #[error("Resource id {0} is invalid, 32 hex encoded bytes are expected")]
+    InvalidResourceId(String),
+    #[error("Resource id {1} used by listener {0} is not registered in resources")]
+    ResourceNotRegistered(String, String),
+}
+
+/// Errors of reading `BridgeConfig` from file, parse errors include position of the failure.
+#[derive(Debug, Error)]
+pub enum ConfigFileError {
+    #[error("Could not read config {0}: {1}")]
+    Read(String, std::io::Error),
+    #[error("Could not parse config {0}: {1}")]
+    Parse(String, String),
+    #[error("Unsupported config format of {0}, expected .json, .yaml, .yml or .toml")]
+    UnsupportedFormat(String),
 }
 
 impl BridgeConfig {
+    /// Reads config in format detected by file extension. Listener and relayer specific `config` values
+    /// are converted to `serde_json::Value` regardless of the format.
+    pub fn from_file(path: &str) -> Result<Self, ConfigFileError> {
+        let extension = Path::new(path).extension().and_then(|extension| extension.to_str());
+        let parse: fn(&str) -> Result<Self, String> = match extension {
+            Some("json") => |content| serde_json::from_str(content).map_err(|e| e.to_string()),
+            Some("yaml") | Some("yml") => |content| serde_yaml::from_str(content).map_err(|e| e.to_string()),
+            Some("toml") => |content| toml::from_str(content).map_err(|e| e.to_string()),
+            _ => return Err(ConfigFileError::UnsupportedFormat(path.to_string())),
+        };
+        let content = fs::read_to_string(path).map_err(|e| ConfigFileError::Read(path.to_string(), e))?;
+        parse(&content).map_err(|e| ConfigFileError::Parse(path.to_string(), e))
+    }
+
+    /// Config as JSON with secrets redacted, safe to be shown to operators.
+    pub fn redacted(&self) -> serde_json::Value {
+        let mut config = serde_json::to_value(self).expect("config is always serializable; qed");
+        redact(&mut config);
+        config
+    }
+
     pub fn get_listener_config<T: DeserializeOwned>(&self, index: usize) -> T {
         let listener = self.listeners.get(index).unwrap().clone();
         let config: T = serde_json::from_value(listener.config.clone()).unwrap();
@@ -57,17 +116,52 @@ impl BridgeConfig {
     }
 
     pub fn validate(&self) -> Result<(), ConfigError> {
+        self.check_id_format()?;
         self.check_listener_id_uniqueness()?;
+        self.check_listener_id_collisions()?;
         self.check_listener_type()?;
+        self.check_listener_poll_intervals()?;
         self.check_listeners_relayer_arr_not_empty()?;
         self.check_relayer_id_uniqueness()?;
         self.check_relayer_type()?;
         self.check_relayer_destination_id_uniqueness()?;
         self.check_used_relayer_ids()?;
+        self.check_resources()?;
 
         Ok(())
     }
 
+    // once any resource is registered, all resource ids configured for relayers of a listener must be
+    fn check_resources(&self) -> Result<(), ConfigError> {
+        let mut registered = HashSet::new();
+        for resource_id in self.resources.keys() {
+            registered.insert(
+                parse_resource_id(resource_id).ok_or_else(|| ConfigError::InvalidResourceId(resource_id.clone()))?,
+            );
+        }
+        if registered.is_empty() {
+            return Ok(());
+        }
+        for listener in &self.listeners {
+            let referenced = self
+                .relayers
+                .iter()
+                .filter(|relayer| listener.relayers.contains(&relayer.id))
+                .flat_map(|relayer| {
+                    RESOURCE_KEYED_RELAYER_SETTINGS
+                        .iter()
+                        .filter_map(|setting| relayer.config.get(setting).and_then(|value| value.as_object()))
+                        .flat_map(|settings| settings.keys())
+                });
+            for resource_id in referenced {
+                if !parse_resource_id(resource_id).is_some_and(|resource_id| registered.contains(&resource_id)) {
+                    return Err(ConfigError::ResourceNotRegistered(listener.id.clone(), resource_id.clone()));
+                }
+            }
+        }
+        Ok(())
+    }
+
     fn check_listener_id_uniqueness(&self) -> Result<(), ConfigError> {
         if !self.listeners.iter().map(|listener| listener.id.as_str()).all_unique() {
             return Err(ConfigError::ListenerIdNotUnique);
@@ -75,6 +169,44 @@ impl BridgeConfig {
         Ok(())
     }
 
+    // ids are used in file and metric names
+    fn check_id_format(&self) -> Result<(), ConfigError> {
+        let ids = self
+            .listeners
+            .iter()
+            .map(|listener| &listener.id)
+            .chain(self.relayers.iter().map(|relayer| &relayer.id));
+        for id in ids {
+            let valid = !id.is_empty()
+                && id.len() <= MAX_ID_LEN
+                && id.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_');
+            if !valid {
+                return Err(ConfigError::InvalidId(id.clone()));
+            }
+        }
+        Ok(())
+    }
+
+    // distinct ids may still map to the same metric name (`-` becomes `_`) or the same file on
+    // case insensitive file systems
+    fn check_listener_id_collisions(&self) -> Result<(), ConfigError> {
+        let mut seen = HashSet::new();
+        for listener in &self.listeners {
+            if !seen.insert(metric_name(&listener.id).to_lowercase()) {
+                return Err(ConfigError::ListenerIdCollision(listener.id.clone()));
+            }
+        }
+        Ok(())
+    }
+
+    // listener polling without pause would flood the node with requests once caught up
+    fn check_listener_poll_intervals(&self) -> Result<(), ConfigError> {
+        match self.listeners.iter().find(|listener| listener.poll_interval_ms == 0) {
+            Some(listener) => Err(ConfigError::ListenerPollIntervalZero(listener.id.clone())),
+            None => Ok(()),
+        }
+    }
+
     fn check_listeners_relayer_arr_not_empty(&self) -> Result<(), ConfigError> {
         if self.listeners.iter().any(|listener| listener.relayers.is_empty()) {
             return Err(ConfigError::ListenerRelayersEmpty);
@@ -152,15 +284,76 @@ impl BridgeConfig {
     }
 }
 
-#[derive(Clone, Deserialize)]
+#[derive(Clone, Deserialize, Serialize)]
 pub struct Listener {
     pub listener_type: String,
     pub id: String,
     pub relayers: Vec<String>,
     pub chain_id: u32,
+    #[serde(default)]
+    pub checkpoint_batching: CheckpointBatching,
+    // continue syncing after writing a dead-letter record for event which exhausted relaying attempts
+    #[serde(default)]
+    pub skip_failed_events: bool,
+    // fetching from source chain exceeding the timeout is treated as failed fetch and retried
+    #[serde(default = "default_fetch_timeout_secs")]
+    pub fetch_timeout_secs: u64,
+    // stop syncing on deposit nonce gap for manual investigation, gaps are only reported otherwise
+    #[serde(default)]
+    pub halt_on_nonce_gap: bool,
+    // listener without start block override or checkpoint starts here, earlier overrides are clamped to it
+    #[serde(default)]
+    pub deployed_at_block: Option<u64>,
+    // events with lower deposit nonce were handled out-of-band, they are skipped instead of relayed
+    #[serde(default)]
+    pub start_nonce: Option<u64>,
+    // pause between sync iterations once caught up with the finalized head
+    #[serde(default = "default_poll_interval_ms")]
+    pub poll_interval_ms: u64,
+    // pause between sync iterations while catching up, 0 syncs blocks back to back
+    #[serde(default)]
+    pub fast_sync_interval_ms: u64,
     pub config: serde_json::Value,
 }
 
+/// Controls how often block checkpoints are persisted, whichever of `max_blocks` and `max_interval_secs`
+/// is reached first. Checkpoints of relayed events are always persisted before moving to the next event,
+/// so no event is skipped or relayed twice. The trade-off is that a crash may cause refetching of up to
+/// `max_blocks` blocks, pending checkpoint is flushed when the listener stops.
+#[derive(Clone, Copy, Debug, Deserialize, Serialize, PartialEq)]
+pub struct CheckpointBatching {
+    #[serde(default = "default_checkpoint_batch_max_blocks")]
+    pub max_blocks: u64,
+    #[serde(default = "default_checkpoint_batch_max_interval_secs")]
+    pub max_interval_secs: u64,
+}
+
+impl Default for CheckpointBatching {
+    fn default() -> Self {
+        Self {
+            max_blocks: default_checkpoint_batch_max_blocks(),
+            max_interval_secs: default_checkpoint_batch_max_interval_secs(),
+        }
+    }
+}
+
+// every block is persisted unless configured otherwise
+fn default_checkpoint_batch_max_blocks() -> u64 {
+    1
+}
+
+fn default_checkpoint_batch_max_interval_secs() -> u64 {
+    60
+}
+
+fn default_fetch_timeout_secs() -> u64 {
+    DEFAULT_FETCH_TIMEOUT.as_secs()
+}
+
+fn default_poll_interval_ms() -> u64 {
+    DEFAULT_POLL_INTERVAL.as_millis() as u64
+}
+
 impl Listener {
     pub fn to_specific_config<T: DeserializeOwned>(&self) -> T {
         let config: T = serde_json::from_value(self.config.clone()).unwrap();
@@ -168,7 +361,7 @@ impl Listener {
     }
 }
 
-#[derive(Deserialize)]
+#[derive(Clone, Deserialize, Serialize)]
 pub struct Relayer {
     pub relayer_type: String,
     pub destination_id: String,
@@ -185,7 +378,10 @@ impl Relayer {
 
 #[cfg(test)]
 pub mod tests {
-    use crate::config::{BridgeConfig, ConfigError};
+    use crate::config::{BridgeConfig, CheckpointBatching, ConfigError};
+    use crate::listener::{DEFAULT_FETCH_TIMEOUT, DEFAULT_POLL_INTERVAL};
+    use crate::resource_registry::AssetInfo;
+    use std::collections::HashMap;
     use std::fs;
 
     use super::{Listener, Relayer};
@@ -205,6 +401,14 @@ pub mod tests {
             id: id.to_string(),
             chain_id,
             listener_type: listener_type.to_string(),
+            checkpoint_batching: CheckpointBatching::default(),
+            skip_failed_events: false,
+            fetch_timeout_secs: DEFAULT_FETCH_TIMEOUT.as_secs(),
+            halt_on_nonce_gap: false,
+            deployed_at_block: None,
+            start_nonce: None,
+            poll_interval_ms: DEFAULT_POLL_INTERVAL.as_millis() as u64,
+            fast_sync_interval_ms: 0,
             config: serde_json::Value::default(),
             relayers,
         }
@@ -227,15 +431,67 @@ pub mod tests {
                 create_listener(LISTENER_1_ID, CHAIN_1_ID, LISTNER_TYPE, vec![RELAYER_1_ID.to_string()]),
             ],
             relayers: vec![create_relayer(RELAYER_1_ID, DESTINATION_ID_1, RELAYER_TYPE)],
+            resources: HashMap::new(),
+            notifications: None,
         };
         assert!(matches!(config.validate(), Err(ConfigError::ListenerIdNotUnique)))
     }
 
+    #[test]
+    pub fn validate_id_format() {
+        for invalid_id in ["sepolia/1", "sepolia 1", "", &"a".repeat(65)] {
+            let config = BridgeConfig {
+                listeners: vec![create_listener(invalid_id, CHAIN_0_ID, LISTNER_TYPE, vec![RELAYER_1_ID.to_string()])],
+                relayers: vec![create_relayer(RELAYER_1_ID, DESTINATION_ID_1, RELAYER_TYPE)],
+                resources: HashMap::new(),
+                notifications: None,
+            };
+            assert!(matches!(config.validate(), Err(ConfigError::InvalidId(id)) if id == invalid_id));
+        }
+
+        let config = BridgeConfig {
+            listeners: vec![create_listener("sepolia-1_a", CHAIN_0_ID, LISTNER_TYPE, vec!["../relayer".to_string()])],
+            relayers: vec![create_relayer("../relayer", DESTINATION_ID_1, RELAYER_TYPE)],
+            resources: HashMap::new(),
+            notifications: None,
+        };
+        assert!(matches!(config.validate(), Err(ConfigError::InvalidId(id)) if id == "../relayer"));
+    }
+
+    #[test]
+    pub fn validate_listener_id_collisions() {
+        let config = BridgeConfig {
+            listeners: vec![
+                create_listener("sepolia-1", CHAIN_0_ID, LISTNER_TYPE, vec![RELAYER_1_ID.to_string()]),
+                create_listener("Sepolia_1", CHAIN_1_ID, LISTNER_TYPE, vec![RELAYER_1_ID.to_string()]),
+            ],
+            relayers: vec![create_relayer(RELAYER_1_ID, DESTINATION_ID_1, RELAYER_TYPE)],
+            resources: HashMap::new(),
+            notifications: None,
+        };
+        assert!(matches!(config.validate(), Err(ConfigError::ListenerIdCollision(id)) if id == "Sepolia_1"));
+    }
+
+    #[test]
+    pub fn validate_listener_poll_interval() {
+        let mut listener = create_listener(LISTENER_1_ID, CHAIN_0_ID, LISTNER_TYPE, vec![RELAYER_1_ID.to_string()]);
+        listener.poll_interval_ms = 0;
+        let config = BridgeConfig {
+            listeners: vec![listener],
+            relayers: vec![create_relayer(RELAYER_1_ID, DESTINATION_ID_1, RELAYER_TYPE)],
+            resources: HashMap::new(),
+            notifications: None,
+        };
+        assert!(matches!(config.validate(), Err(ConfigError::ListenerPollIntervalZero(id)) if id == LISTENER_1_ID));
+    }
+
     #[test]
     pub fn validate_listener_type() {
         let config = BridgeConfig {
             listeners: vec![create_listener(LISTENER_1_ID, CHAIN_0_ID, "invalid", vec![RELAYER_1_ID.to_string()])],
             relayers: vec![create_relayer(RELAYER_1_ID, DESTINATION_ID_1, RELAYER_TYPE)],
+            resources: HashMap::new(),
+            notifications: None,
         };
         assert!(matches!(config.validate(), Err(ConfigError::ListenerTypeUnknown)))
     }
@@ -245,6 +501,8 @@ pub mod tests {
         let config = BridgeConfig {
             listeners: vec![create_listener(LISTENER_1_ID, CHAIN_0_ID, LISTNER_TYPE, vec![RELAYER_1_ID.to_string()])],
             relayers: vec![create_relayer(RELAYER_2_ID, DESTINATION_ID_1, RELAYER_TYPE)],
+            resources: HashMap::new(),
+            notifications: None,
         };
         assert!(matches!(config.validate(), Err(ConfigError::ListenerRelayerNotDefined)))
     }
@@ -254,6 +512,8 @@ pub mod tests {
         let config = BridgeConfig {
             listeners: vec![create_listener(LISTENER_1_ID, CHAIN_0_ID, LISTNER_TYPE, vec![])],
             relayers: vec![],
+            resources: HashMap::new(),
+            notifications: None,
         };
         assert!(matches!(config.validate(), Err(ConfigError::ListenerRelayersEmpty)))
     }
@@ -266,6 +526,8 @@ pub mod tests {
                 create_relayer(RELAYER_1_ID, DESTINATION_ID_1, RELAYER_TYPE),
                 create_relayer(RELAYER_1_ID, DESTINATION_ID_2, RELAYER_TYPE),
             ],
+            resources: HashMap::new(),
+            notifications: None,
         };
         assert!(matches!(config.validate(), Err(ConfigError::RelayerIdNotUnique)))
     }
@@ -275,6 +537,8 @@ pub mod tests {
         let config = BridgeConfig {
             listeners: vec![create_listener(LISTENER_1_ID, CHAIN_0_ID, LISTNER_TYPE, vec![RELAYER_1_ID.to_string()])],
             relayers: vec![create_relayer(RELAYER_1_ID, DESTINATION_ID_1, "invalid")],
+            resources: HashMap::new(),
+            notifications: None,
         };
         assert!(matches!(config.validate(), Err(ConfigError::RelayerTypeUnknown)))
     }
@@ -287,6 +551,8 @@ pub mod tests {
                 create_relayer(RELAYER_1_ID, DESTINATION_ID_1, RELAYER_TYPE),
                 create_relayer(RELAYER_2_ID, DESTINATION_ID_1, RELAYER_TYPE),
             ],
+            resources: HashMap::new(),
+            notifications: None,
         };
         assert!(matches!(config.validate(), Err(ConfigError::RelayerDestinationIdNotUnique)))
     }
@@ -299,10 +565,95 @@ pub mod tests {
                 create_relayer(RELAYER_1_ID, DESTINATION_ID_1, RELAYER_TYPE),
                 create_relayer(RELAYER_2_ID, DESTINATION_ID_2, RELAYER_TYPE),
             ],
+            resources: HashMap::new(),
+            notifications: None,
         };
         assert!(matches!(config.validate(), Err(ConfigError::RelayerNotUsed)))
     }
 
+    #[test]
+    pub fn validate_resources_used_by_listeners_are_registered() {
+        let registered = format!("0x{}", hex::encode([1; 32]));
+        let unregistered = hex::encode([2; 32]);
+        let config = |resources: Vec<&str>, limited: &str| {
+            let mut relayer = create_relayer(RELAYER_1_ID, DESTINATION_ID_1, RELAYER_TYPE);
+            relayer.config = serde_json::json!({"amount_limits": {limited: {"max_amount": "100"}}});
+            BridgeConfig {
+                listeners: vec![create_listener(
+                    LISTENER_1_ID,
+                    CHAIN_0_ID,
+                    LISTNER_TYPE,
+                    vec![RELAYER_1_ID.to_string()],
+                )],
+                relayers: vec![relayer],
+                resources: resources
+                    .into_iter()
+                    .map(|resource_id| {
+                        (resource_id.to_string(), AssetInfo { name: "LIT".to_string(), decimals: 18, chains: vec![] })
+                    })
+                    .collect(),
+                notifications: None,
+            }
+        };
+
+        // registry is opt-in
+        assert!(config(vec![], &unregistered).validate().is_ok());
+        assert!(config(vec![registered.as_str()], &registered).validate().is_ok());
+        // same resource id written without prefix
+        assert!(config(vec![registered.as_str()], registered.trim_start_matches("0x"))
+            .validate()
+            .is_ok());
+        assert!(matches!(
+            config(vec![registered.as_str()], &unregistered).validate(),
+            Err(ConfigError::ResourceNotRegistered(listener, resource_id))
+                if listener == LISTENER_1_ID && resource_id == unregistered
+        ));
+        assert!(matches!(
+            config(vec!["0x01"], &registered).validate(),
+            Err(ConfigError::InvalidResourceId(resource_id)) if resource_id == "0x01"
+        ));
+    }
+
+    #[test]
+    pub fn sample_config_is_equal_in_all_formats() {
+        let json = BridgeConfig::from_file("../local/config.json").unwrap();
+        let yaml = BridgeConfig::from_file("../local/config.yaml").unwrap();
+        let toml = BridgeConfig::from_file("../local/config.toml").unwrap();
+
+        json.validate().unwrap();
+        yaml.validate().unwrap();
+        toml.validate().unwrap();
+        assert_eq!(serde_json::to_value(&json).unwrap(), serde_json::to_value(&yaml).unwrap());
+        assert_eq!(serde_json::to_value(&json).unwrap(), serde_json::to_value(&toml).unwrap());
+
+        let sepolia_config: ethereum_listener::listener::ListenerConfig = toml.get_listener_config(0);
+        assert_eq!(sepolia_config.finalization_gap, 6);
+    }
+
+    #[test]
+    pub fn config_parse_errors_include_position() {
+        for (file_name, content) in [
+            ("config_parse_error.json", "{\n  \"listeners\": [\n    {\"id\": }\n  ]\n}"),
+            ("config_parse_error.yaml", "listeners:\n  - id: [\n"),
+            ("config_parse_error.toml", "listeners = [\n  { id = }\n]\n"),
+        ] {
+            fs::write(file_name, content).unwrap();
+            let error = BridgeConfig::from_file(file_name).err().unwrap().to_string();
+            fs::remove_file(file_name).unwrap();
+
+            assert!(error.contains(file_name), "{}", error);
+            assert!(error.contains("line"), "{}", error);
+        }
+    }
+
+    #[test]
+    pub fn unknown_config_format_is_rejected() {
+        assert!(matches!(
+            BridgeConfig::from_file("../local/config.ini"),
+            Err(super::ConfigFileError::UnsupportedFormat(_))
+        ));
+    }
+
     #[test]
     pub fn deserialize_sample_config() {
         let config = fs::read("../local/config.json").unwrap();
@@ -365,4 +716,27 @@ pub mod tests {
 
         assert_eq!(rococo_relayer_config.ws_rpc_endpoint, "ws://heima-node:9944");
     }
+
+    #[test]
+    pub fn redacted_config_hides_relayer_secrets() {
+        let mut relayer = create_relayer(RELAYER_1_ID, DESTINATION_ID_1, RELAYER_TYPE);
+        relayer.config = serde_json::json!({
+            "node_rpc_url": "https://eth.example.com/rpc?api_key=abc123",
+            "rpc_auth": {"type": "bearer", "token": "secret"},
+        });
+        let config = BridgeConfig {
+            listeners: vec![create_listener(LISTENER_1_ID, CHAIN_0_ID, LISTNER_TYPE, vec![RELAYER_1_ID.to_string()])],
+            relayers: vec![relayer],
+            resources: HashMap::new(),
+            notifications: None,
+        };
+
+        let redacted = config.redacted();
+
+        assert_eq!(redacted["listeners"][0]["id"], LISTENER_1_ID);
+        assert_eq!(redacted["relayers"][0]["destination_id"], DESTINATION_ID_1);
+        assert_eq!(redacted["relayers"][0]["config"]["node_rpc_url"], "https://eth.example.com/rpc?api_key=***");
+        assert_eq!(redacted["relayers"][0]["config"]["rpc_auth"]["type"], "bearer");
+        assert_eq!(redacted["relayers"][0]["config"]["rpc_auth"]["token"], "***");
+    }
 }
@@ -0,0 +1,166 @@
+// Copyright 2020-2024 Trust Computing GmbH.
+// This file is part of Litentry.
+//
+// Litentry is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// Litentry is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with Litentry.  If not, see <https://www.gnu.org/licenses/>.
+
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::fs::{self, OpenOptions};
+use std::io::{ErrorKind, Write};
+use std::sync::Mutex;
+
+/// Reason of records of events exceeding relayer's amount limits, re-driven with the limits skipped once reviewed
+pub const LIMIT_EXCEEDED_REASON: &str = "Amount limit exceeded";
+
+/// Event which could not be relayed, kept for inspection and manual re-drive.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct DeadLetterRecord {
+    pub listener_id: String,
+    pub event_id: String,
+    pub destination_id: Option<String>,
+    pub nonce: u64,
+    // serialized as string, json numbers can't safely hold u128
+    #[serde(serialize_with = "serialize_u128", deserialize_with = "deserialize_u128")]
+    pub amount: u128,
+    // hex encoded
+    pub resource_id: String,
+    // hex encoded
+    pub data: String,
+    // hex encoded reference of the originating transaction, missing in records written by older versions
+    #[serde(default)]
+    pub source_ref: String,
+    pub reason: String,
+    pub timestamp: u64,
+}
+
+fn serialize_u128<S: Serializer>(value: &u128, serializer: S) -> Result<S::Ok, S::Error> {
+    serializer.serialize_str(&value.to_string())
+}
+
+fn deserialize_u128<'de, D: Deserializer<'de>>(deserializer: D) -> Result<u128, D::Error> {
+    String::deserialize(deserializer)?.parse().map_err(serde::de::Error::custom)
+}
+
+/// Stores records of events which exhausted relaying attempts. Records are appended to `file_name`
+/// (if set) as JSON lines.
+pub struct DeadLetterLog {
+    file_name: Option<String>,
+    records: Mutex<Vec<DeadLetterRecord>>,
+}
+
+impl DeadLetterLog {
+    pub fn in_memory() -> Self {
+        Self { file_name: None, records: Mutex::new(vec![]) }
+    }
+
+    pub fn new(file_name: &str) -> Self {
+        Self { file_name: Some(file_name.to_string()), records: Mutex::new(vec![]) }
+    }
+
+    #[allow(clippy::result_unit_err)]
+    pub fn write(&self, record: DeadLetterRecord) -> Result<(), ()> {
+        log::error!("Writing dead-letter record: {:?}", record);
+        let mut records = self.records.lock().unwrap();
+        if let Some(ref file_name) = self.file_name {
+            let mut line = serde_json::to_string(&record).map_err(|e| {
+                log::error!("Could not serialize dead-letter record: {:?}", e);
+            })?;
+            line.push('\n');
+            OpenOptions::new()
+                .create(true)
+                .append(true)
+                .open(file_name)
+                .and_then(|mut file| file.write_all(line.as_bytes()))
+                .map_err(|e| {
+                    log::error!("Could not write dead-letter record to {}: {:?}", file_name, e);
+                })?;
+        } else {
+            records.push(record);
+        }
+        Ok(())
+    }
+
+    #[allow(clippy::result_unit_err)]
+    pub fn records(&self) -> Result<Vec<DeadLetterRecord>, ()> {
+        let Some(ref file_name) = self.file_name else {
+            return Ok(self.records.lock().unwrap().clone());
+        };
+        match fs::read_to_string(file_name) {
+            Ok(content) => content
+                .lines()
+                .filter(|line| !line.trim().is_empty())
+                .map(|line| {
+                    serde_json::from_str(line).map_err(|e| {
+                        log::error!("Could not decode dead-letter record: {:?}", e);
+                    })
+                })
+                .collect(),
+            Err(e) if e.kind() == ErrorKind::NotFound => Ok(vec![]),
+            Err(e) => {
+                log::error!("Could not read dead-letter records from {}: {:?}", file_name, e);
+                Err(())
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn record(nonce: u64) -> DeadLetterRecord {
+        DeadLetterRecord {
+            listener_id: "test".to_string(),
+            event_id: "1".to_string(),
+            destination_id: Some("02".to_string()),
+            nonce,
+            amount: u128::MAX,
+            resource_id: hex::encode([1; 32]),
+            data: hex::encode([2; 20]),
+            source_ref: hex::encode([3; 32]),
+            reason: "Exceeded maximum number of relaying attempts".to_string(),
+            timestamp: 1,
+        }
+    }
+
+    #[test]
+    fn records_are_appended_to_file() {
+        let file_name = "records_are_appended_to_file.jsonl";
+        let _ = fs::remove_file(file_name);
+
+        let log = DeadLetterLog::new(file_name);
+        log.write(record(1)).unwrap();
+        log.write(record(2)).unwrap();
+        drop(log);
+
+        let log = DeadLetterLog::new(file_name);
+        assert_eq!(log.records(), Ok(vec![record(1), record(2)]));
+
+        fs::remove_file(file_name).unwrap();
+    }
+
+    #[test]
+    fn records_without_source_ref_are_readable() {
+        let file_name = "records_without_source_ref_are_readable.jsonl";
+        fs::write(
+            file_name,
+            r#"{"listener_id":"test","event_id":"1","destination_id":null,"nonce":1,"amount":"1","resource_id":"","data":"","reason":"","timestamp":1}"#,
+        )
+        .unwrap();
+
+        let records = DeadLetterLog::new(file_name).records().unwrap();
+        assert_eq!(records[0].source_ref, "");
+
+        fs::remove_file(file_name).unwrap();
+    }
+}
@@ -0,0 +1,64 @@
+// Copyright 2020-2024 Trust Computing GmbH.
+// This file is part of Litentry.
+//
+// Litentry is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// Litentry is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with Litentry.  If not, see <https://www.gnu.org/licenses/>.
+
+use std::collections::HashSet;
+
+/// Destination accounts a relayer may pay out to, used by bridges serving only known counterparties.
+/// Accounts are compared as raw recipient bytes, every destination is allowed if the allowlist isn't set.
+#[derive(Debug, Default)]
+pub struct DestinationAllowlist {
+    allowed: Option<HashSet<Vec<u8>>>,
+}
+
+impl DestinationAllowlist {
+    pub fn unrestricted() -> Self {
+        Self { allowed: None }
+    }
+
+    pub fn new(allowed: impl IntoIterator<Item = Vec<u8>>) -> Self {
+        Self { allowed: Some(allowed.into_iter().collect()) }
+    }
+
+    pub fn is_allowed(&self, recipient: &[u8]) -> bool {
+        self.allowed.as_ref().is_none_or(|allowed| allowed.contains(recipient))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn unrestricted_allowlist_allows_everything() {
+        assert!(DestinationAllowlist::unrestricted().is_allowed(&[1; 20]));
+        assert!(DestinationAllowlist::unrestricted().is_allowed(&[]));
+    }
+
+    #[test]
+    fn only_listed_destinations_are_allowed() {
+        let allowlist = DestinationAllowlist::new(vec![vec![1; 20], vec![2; 32]]);
+
+        assert!(allowlist.is_allowed(&[1; 20]));
+        assert!(allowlist.is_allowed(&[2; 32]));
+        assert!(!allowlist.is_allowed(&[1; 32]));
+        assert!(!allowlist.is_allowed(&[3; 20]));
+    }
+
+    #[test]
+    fn empty_allowlist_allows_nothing() {
+        assert!(!DestinationAllowlist::new(vec![]).is_allowed(&[1; 20]));
+    }
+}
@@ -31,3 +31,13 @@ pub trait LastFinalizedBlockNumFetcher {
 pub trait BlockPayInEventsFetcher<Id: Clone, DestinationId: Clone> {
     async fn get_block_pay_in_events(&mut self, block_num: u64) -> Result<Vec<PayIn<Id, DestinationId>>, ()>;
 }
+
+/// Used to detect chain reorganizations affecting already synced blocks.
+/// Returns the block number from which syncing should be resumed if a reorg was detected.
+/// Chains with deterministic finality can rely on the default implementation.
+#[async_trait]
+pub trait ReorgDetector: Send {
+    async fn detect_reorg(&mut self, _block_num: u64) -> Result<Option<u64>, ()> {
+        Ok(None)
+    }
+}
@@ -14,9 +14,30 @@
 // You should have received a copy of the GNU General Public License
 // along with Litentry.  If not, see <https://www.gnu.org/licenses/>.
 
+pub mod amount_limit;
 pub mod config;
+pub mod dead_letter;
+pub mod destination_allowlist;
 pub mod fetcher;
 pub mod key_store;
 pub mod listener;
+pub mod listener_control;
+pub mod listener_lock;
+pub mod listener_status;
+pub mod nonce_tracker;
+pub mod notification;
+pub mod primitives;
+pub mod redaction;
+pub mod redis_checkpoint_repository;
 pub mod relay;
+pub mod relay_queue;
+pub mod relay_stats;
+pub mod resource_allowlist;
+pub mod resource_registry;
+pub mod rpc_auth;
+pub mod runtime_registry;
+pub mod shielding;
+pub mod start_block;
 pub mod sync_checkpoint_repository;
+pub mod timestamp_cache;
+pub mod validation;
@@ -14,17 +14,35 @@
 // You should have received a copy of the GNU General Public License
 // along with Litentry.  If not, see <https://www.gnu.org/licenses/>.
 
-use metrics::{describe_gauge, gauge};
+use async_trait::async_trait;
+use metrics::{
+    counter, describe_counter, describe_gauge, describe_histogram, gauge, histogram, Counter, Gauge, Histogram,
+};
 use serde::de::DeserializeOwned;
 use std::collections::HashMap;
 use std::fmt::Debug;
+use std::future::Future;
+use std::pin::Pin;
 use std::sync::Arc;
-use std::{hash::Hash, marker::PhantomData, thread::sleep, time::Duration};
+use std::time::Instant;
+use std::{hash::Hash, marker::PhantomData, time::Duration};
 use tokio::{runtime::Handle, sync::oneshot::Receiver};
 
-use crate::config::BridgeConfig;
-use crate::fetcher::{BlockPayInEventsFetcher, LastFinalizedBlockNumFetcher};
-use crate::relay::RelayError;
+use crate::amount_limit::unix_now;
+use crate::config::{BridgeConfig, CheckpointBatching};
+use crate::dead_letter::{DeadLetterLog, DeadLetterRecord, LIMIT_EXCEEDED_REASON};
+use crate::fetcher::{BlockPayInEventsFetcher, LastFinalizedBlockNumFetcher, ReorgDetector};
+use crate::listener_control::ListenerControl;
+use crate::listener_lock::ListenerLock;
+use crate::listener_status::ListenerStatus;
+use crate::nonce_tracker::{NonceCheck, NonceTracker, DEPOSIT_NONCE_GAP_COUNTER};
+use crate::notification::{Notification, NotificationType, Notifier};
+use crate::primitives::metric_name;
+use crate::relay::{Recipient, RelayError, RelayRequest, Relayer};
+use crate::relay_stats::RelayStats;
+use crate::resource_registry::ResourceRegistry;
+use crate::start_block::{StartBlockOverride, StartBlockSettings};
+use crate::validation::{ValidationError, Validator};
 use crate::{
     relay::Relay,
     sync_checkpoint_repository::{Checkpoint, CheckpointRepository},
@@ -32,6 +50,85 @@ use crate::{
 
 pub const RELAY_MAX_ATTEMPTS: u8 = 10;
 
+pub const DEFAULT_FETCH_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Pause between sync iterations once the listener caught up with the finalized head
+pub const DEFAULT_POLL_INTERVAL: Duration = Duration::from_secs(2);
+
+// pause before retrying failed fetch or checking paused listener again
+const RETRY_INTERVAL: Duration = Duration::from_secs(1);
+
+// pause before retrying relay to a destination paused by admin
+const PAUSED_RETRY_INTERVAL: Duration = Duration::from_secs(5);
+
+pub const RELAY_ATTEMPTS_COUNTER: &str = "relay_attempts_total";
+pub const RELAY_MAX_ATTEMPTS_EXCEEDED_COUNTER: &str = "relay_max_attempts_exceeded_total";
+pub const RELAY_DURATION_HISTOGRAM: &str = "relay_duration_seconds";
+pub const RELAY_ATTEMPTS_HISTOGRAM: &str = "relay_attempts";
+pub const EVENT_AGE_HISTOGRAM: &str = "event_age_seconds";
+pub const CHECKPOINT_REGRESSION_COUNTER: &str = "checkpoint_regression_total";
+
+/// Terminal outcome of relaying an event, used as `outcome` label of relay histograms.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum RelayOutcome {
+    Success,
+    AlreadyRelayed,
+    DeadLetter,
+    LimitExceeded,
+    BelowMinAmount,
+    AboveMaxAmount,
+    Filtered,
+}
+
+impl RelayOutcome {
+    const ALL: [RelayOutcome; 7] = [
+        RelayOutcome::Success,
+        RelayOutcome::AlreadyRelayed,
+        RelayOutcome::DeadLetter,
+        RelayOutcome::LimitExceeded,
+        RelayOutcome::BelowMinAmount,
+        RelayOutcome::AboveMaxAmount,
+        RelayOutcome::Filtered,
+    ];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            RelayOutcome::Success => "success",
+            RelayOutcome::AlreadyRelayed => "already_relayed",
+            RelayOutcome::DeadLetter => "dead_letter",
+            RelayOutcome::LimitExceeded => "limit_exceeded",
+            RelayOutcome::BelowMinAmount => "below_min_amount",
+            RelayOutcome::AboveMaxAmount => "above_max_amount",
+            RelayOutcome::Filtered => "filtered",
+        }
+    }
+}
+
+// relay histograms of a single destination, registered upfront like other listener metrics
+struct RelayHistograms {
+    duration: HashMap<RelayOutcome, Histogram>,
+    attempts: Histogram,
+    event_age: Histogram,
+}
+
+impl RelayHistograms {
+    fn new(listener_id: &str, destination: &str) -> Self {
+        let labels = [("listener", listener_id.to_string()), ("destination", destination.to_string())];
+        Self {
+            duration: RelayOutcome::ALL
+                .iter()
+                .map(|outcome| {
+                    let mut labels = labels.to_vec();
+                    labels.push(("outcome", outcome.label().to_string()));
+                    (*outcome, histogram!(RELAY_DURATION_HISTOGRAM, &labels))
+                })
+                .collect(),
+            attempts: histogram!(RELAY_ATTEMPTS_HISTOGRAM, &labels),
+            event_age: histogram!(EVENT_AGE_HISTOGRAM, &labels),
+        }
+    }
+}
+
 /// Represents `PayIn` event emitted on one side of the bridge.
 #[derive(Clone, Debug, PartialEq)]
 pub struct PayIn<Id: Clone, DestinationId: Clone> {
@@ -40,7 +137,13 @@ pub struct PayIn<Id: Clone, DestinationId: Clone> {
     amount: u128,
     nonce: u64,
     resource_id: [u8; 32],
-    data: Vec<u8>,
+    recipient: Recipient,
+    // opaque reference of the originating transaction (e.g. its hash), empty if unknown
+    source_ref: Vec<u8>,
+    // unix timestamp in seconds of the source block, if known
+    timestamp: Option<u64>,
+    // emitter with its own nonce sequence, e.g. address of a routed contract, empty for listener's main source
+    source: Vec<u8>,
 }
 
 impl<Id: Clone, DestinationId: Clone> PayIn<Id, DestinationId> {
@@ -50,55 +153,203 @@ impl<Id: Clone, DestinationId: Clone> PayIn<Id, DestinationId> {
         amount: u128,
         nonce: u64,
         resource_id: [u8; 32],
-        data: Vec<u8>,
+        recipient: Recipient,
     ) -> Self {
-        Self { id, maybe_destination_id, amount, nonce, resource_id, data }
+        Self {
+            id,
+            maybe_destination_id,
+            amount,
+            nonce,
+            resource_id,
+            recipient,
+            source_ref: vec![],
+            timestamp: None,
+            source: vec![],
+        }
+    }
+
+    pub fn with_source(mut self, source: Vec<u8>) -> Self {
+        self.source = source;
+        self
+    }
+
+    pub fn with_source_ref(mut self, source_ref: Vec<u8>) -> Self {
+        self.source_ref = source_ref;
+        self
+    }
+
+    pub fn with_timestamp(mut self, timestamp: Option<u64>) -> Self {
+        self.timestamp = timestamp;
+        self
+    }
+
+    /// Overrides destination decoded from the event, e.g. when it's routed by its emitter
+    pub fn with_destination_id(mut self, destination_id: DestinationId) -> Self {
+        self.maybe_destination_id = Some(destination_id);
+        self
+    }
+
+    pub fn id(&self) -> &Id {
+        &self.id
+    }
+
+    pub fn destination_id(&self) -> Option<&DestinationId> {
+        self.maybe_destination_id.as_ref()
+    }
+
+    pub fn amount(&self) -> u128 {
+        self.amount
+    }
+
+    pub fn nonce(&self) -> u64 {
+        self.nonce
+    }
+
+    pub fn resource_id(&self) -> &[u8; 32] {
+        &self.resource_id
     }
+
+    pub fn recipient(&self) -> &Recipient {
+        &self.recipient
+    }
+
+    pub fn source_ref(&self) -> &[u8] {
+        &self.source_ref
+    }
+
+    pub fn timestamp(&self) -> Option<u64> {
+        self.timestamp
+    }
+
+    pub fn source(&self) -> &[u8] {
+        &self.source
+    }
+
+    /// Request to relay this event, `source_chain_id` is the chain id of the listener that fetched it
+    pub fn relay_request(&self, source_chain_id: u32) -> RelayRequest {
+        RelayRequest {
+            amount: self.amount,
+            nonce: self.nonce,
+            resource_id: self.resource_id,
+            recipient: self.recipient.clone(),
+            source_chain_id,
+            skip_amount_limits: false,
+        }
+    }
+
+    /// Hex encoded source reference, used in logs and management outputs
+    pub fn source_ref_hex(&self) -> String {
+        format!("0x{}", hex::encode(&self.source_ref))
+    }
+}
+
+/// Suspends listener's sync loop, replaceable so tests can observe its cadence without waiting.
+#[async_trait]
+pub trait Sleeper: Send + Sync {
+    async fn sleep(&self, duration: Duration);
 }
 
-pub struct StartBlock {
-    pub listener_id: String,
-    pub block_num: u64,
+/// Blocks listener's own sync thread, used by `Listener::sync`.
+pub struct ThreadSleeper;
+
+#[async_trait]
+impl Sleeper for ThreadSleeper {
+    async fn sleep(&self, duration: Duration) {
+        std::thread::sleep(duration)
+    }
 }
 
-impl TryFrom<&String> for StartBlock {
-    type Error = ();
+/// Yields to other tasks of the runtime, used by `Listener::sync_async`.
+pub struct TokioSleeper;
 
-    fn try_from(value: &String) -> Result<Self, Self::Error> {
-        let values: Vec<&str> = value.split(":").collect();
-        let block_num = (values.get(1).ok_or(())?).parse::<u64>().map_err(|_| ())?;
-        Ok(StartBlock { listener_id: values.first().unwrap().to_string(), block_num })
+#[async_trait]
+impl Sleeper for TokioSleeper {
+    async fn sleep(&self, duration: Duration) {
+        tokio::time::sleep(duration).await
     }
 }
 
+#[allow(clippy::type_complexity)]
 pub struct ListenerContext<T> {
     pub id: String,
     pub config: T,
-    pub start_block: u64,
+    pub start_block: StartBlockSettings,
     pub chain_id: u32,
-    pub relayers: HashMap<String, Arc<Box<dyn crate::relay::Relayer<String>>>>,
+    // ordered relayers keyed by destination id
+    pub relayers: HashMap<String, Vec<Arc<Box<dyn crate::relay::Relayer<String>>>>>,
+    // destination ids of listener's relayers keyed by relayer id
+    pub relayer_destinations: HashMap<String, String>,
+    pub control: Arc<ListenerControl>,
+    pub status: Arc<ListenerStatus>,
+    pub checkpoint_batching: CheckpointBatching,
+    pub dead_letter_log: Arc<DeadLetterLog>,
+    pub skip_failed_events: bool,
+    // keyed by destination id
+    pub validators: HashMap<String, Arc<dyn Validator>>,
+    pub fetch_timeout: Duration,
+    pub catch_up_only: bool,
+    pub nonce_tracker: Arc<NonceTracker>,
+    pub halt_on_nonce_gap: bool,
+    pub start_nonce: Option<u64>,
+    pub poll_interval: Duration,
+    pub fast_sync_interval: Duration,
+    pub resource_registry: Arc<ResourceRegistry>,
+    pub notifier: Arc<Notifier>,
+    // held as long as the context lives, listener is restarted from the same context
+    pub data_lock: Option<ListenerLock>,
 }
 
-#[allow(clippy::type_complexity)]
+/// Fails if data files of any listener are locked by another process.
+#[allow(clippy::type_complexity, clippy::result_unit_err)]
 pub fn prepare_listener_context<T: DeserializeOwned>(
     config: &BridgeConfig,
     listener_type: &str,
     relayers: &HashMap<String, HashMap<String, Arc<Box<dyn crate::relay::Relayer<String>>>>>,
-    start_blocks: &HashMap<String, u64>,
-) -> Vec<ListenerContext<T>> {
+    validators: &HashMap<String, Arc<dyn Validator>>,
+    start_blocks: &HashMap<String, StartBlockOverride>,
+    force_start_block: bool,
+    notifier: &Arc<Notifier>,
+) -> Result<Vec<ListenerContext<T>>, ()> {
+    // resource ids are checked by `BridgeConfig::validate`, contexts may be prepared from unvalidated config though
+    let resource_registry = Arc::new(
+        ResourceRegistry::new(&config.resources)
+            .map_err(|resource_id| log::error!("Invalid resource id {} in config", resource_id))?,
+    );
     let mut components = vec![];
     for listener_config in config.listeners.iter().filter(|l| l.listener_type == listener_type) {
+        // taken before any data file is read, they may be written by another worker
+        let data_lock = ListenerLock::acquire(&format!("data/{}.lock", listener_config.id))?;
         let ethereum_listener_config: T = listener_config.to_specific_config();
-        let mut listener_relayers: HashMap<String, Arc<Box<dyn crate::relay::Relayer<String>>>> = HashMap::new();
+        // relayers of the same destination are kept in configured order, used for failover
+        let mut listener_relayers: HashMap<String, Vec<Arc<Box<dyn crate::relay::Relayer<String>>>>> = HashMap::new();
         for relayer_id in listener_config.relayers.iter() {
             for relayers in relayers.values() {
                 if let Some(relayer) = relayers.get(relayer_id) {
-                    listener_relayers.insert(relayer.destination_id(), relayer.clone());
+                    listener_relayers
+                        .entry(relayer.destination_id())
+                        .or_default()
+                        .push(relayer.clone());
                 }
             }
         }
+        let mut listener_validators: HashMap<String, Arc<dyn Validator>> = HashMap::new();
+        let mut relayer_destinations: HashMap<String, String> = HashMap::new();
+        for relayer_config in config.relayers.iter().filter(|r| listener_config.relayers.contains(&r.id)) {
+            if let Some(validator) = validators.get(&relayer_config.id) {
+                listener_validators.insert(relayer_config.destination_id.clone(), validator.clone());
+            }
+            relayer_destinations.insert(relayer_config.id.clone(), relayer_config.destination_id.clone());
+        }
 
-        let start_block = *start_blocks.get(&listener_config.id).unwrap_or(&0);
+        let start_block = StartBlockSettings {
+            block: start_blocks.get(&listener_config.id).copied(),
+            deployed_at_block: listener_config.deployed_at_block,
+            force: force_start_block,
+        };
+        let control = ListenerControl::new(&format!("data/{}_control.bin", listener_config.id))
+            .map_err(|_| log::error!("Could not read control state of listener {}", listener_config.id))?;
+        let nonce_tracker = NonceTracker::new(&format!("data/{}_nonces.bin", listener_config.id))
+            .map_err(|_| log::error!("Could not read deposit nonces of listener {}", listener_config.id))?;
 
         components.push(ListenerContext {
             id: listener_config.id.clone(),
@@ -106,9 +357,26 @@ pub fn prepare_listener_context<T: DeserializeOwned>(
             start_block,
             chain_id: listener_config.chain_id,
             relayers: listener_relayers,
+            relayer_destinations,
+            control: Arc::new(control),
+            status: Arc::new(ListenerStatus::new(&listener_config.id, listener_config.chain_id)),
+            checkpoint_batching: listener_config.checkpoint_batching,
+            dead_letter_log: Arc::new(DeadLetterLog::new(&format!("data/{}_failed.jsonl", listener_config.id))),
+            skip_failed_events: listener_config.skip_failed_events,
+            validators: listener_validators,
+            fetch_timeout: Duration::from_secs(listener_config.fetch_timeout_secs),
+            catch_up_only: false,
+            nonce_tracker: Arc::new(nonce_tracker),
+            halt_on_nonce_gap: listener_config.halt_on_nonce_gap,
+            start_nonce: listener_config.start_nonce,
+            poll_interval: Duration::from_millis(listener_config.poll_interval_ms),
+            fast_sync_interval: Duration::from_millis(listener_config.fast_sync_interval_ms),
+            resource_registry: resource_registry.clone(),
+            notifier: notifier.clone(),
+            data_lock: Some(data_lock),
         });
     }
-    components
+    Ok(components)
 }
 
 /// Core component, used to listen to source chain and relay bridge request to destination chain.
@@ -126,14 +394,50 @@ pub struct Listener<DestinationId, Fetcher, Checkpoint, CheckpointRepository, Pa
     start_block: u64,
     chain_id: u32,
     max_relay_retry_attempts: u8,
+    relay_stats: Arc<RelayStats>,
+    control: Arc<ListenerControl>,
+    status: Arc<ListenerStatus>,
+    checkpoint_batching: CheckpointBatching,
+    dead_letter_log: Arc<DeadLetterLog>,
+    skip_failed_events: bool,
+    // keyed by destination id
+    validators: HashMap<String, Arc<dyn Validator>>,
+    fetch_timeout: Duration,
+    catch_up_only: bool,
+    nonce_tracker: Arc<NonceTracker>,
+    halt_on_nonce_gap: bool,
+    // events below it were handled out-of-band and are skipped
+    start_nonce: Option<u64>,
+    poll_interval: Duration,
+    // zero syncs blocks back to back while catching up
+    fast_sync_interval: Duration,
+    // `ThreadSleeper` or `TokioSleeper` depending on how the listener is run, unless set
+    sleeper: Option<Arc<dyn Sleeper>>,
+    resource_registry: Arc<ResourceRegistry>,
+    notifier: Arc<Notifier>,
+    // last synced block whose checkpoint is not persisted yet
+    unsaved_block: Option<u64>,
+    unsaved_blocks: u64,
+    last_checkpoint_save: Instant,
+    // block of the last checkpoint saved by the listener, stored checkpoint should never be behind it
+    checkpointed_block: Option<u64>,
+    synced_block_gauge: Gauge,
+    finalized_block_gauge: Gauge,
+    fetch_timeout_counter: Counter,
+    relay_attempts_counter: Counter,
+    relay_max_attempts_exceeded_counter: Counter,
+    nonce_gap_counter: Counter,
+    checkpoint_regression_counter: Counter,
+    // keyed by destination id
+    relay_histograms: HashMap<String, RelayHistograms>,
     _phantom: PhantomData<(Checkpoint, PayInEventId)>,
 }
 
 #[allow(clippy::result_unit_err, clippy::too_many_arguments)]
 impl<
-        DestinationId: Hash + Eq + Clone + Debug + Send + Sync,
-        PayInEventId: Into<CheckpointT> + Clone,
-        Fetcher: LastFinalizedBlockNumFetcher + BlockPayInEventsFetcher<PayInEventId, DestinationId>,
+        DestinationId: Hash + Eq + Clone + Debug + ToString + Send + Sync,
+        PayInEventId: Into<CheckpointT> + Clone + Debug,
+        Fetcher: LastFinalizedBlockNumFetcher + BlockPayInEventsFetcher<PayInEventId, DestinationId> + ReorgDetector,
         CheckpointT: PartialOrd + Checkpoint + From<u64>,
         CheckpointRepositoryT: CheckpointRepository<CheckpointT>,
     > Listener<DestinationId, Fetcher, CheckpointT, CheckpointRepositoryT, PayInEventId>
@@ -150,6 +454,27 @@ impl<
         max_relay_retry_attempts: u8,
     ) -> Result<Self, ()> {
         describe_gauge!(synced_block_gauge_name(id), "Last synced block");
+        describe_gauge!(finalized_block_gauge_name(id), "Last finalized block of source chain");
+        describe_counter!(limit_exceeded_counter_name(id), "Events skipped due to exceeded amount limits");
+        describe_counter!(fetch_timeout_counter_name(id), "Timed out fetches from source chain");
+        describe_counter!(RELAY_ATTEMPTS_COUNTER, "Relaying retries after failed attempts");
+        describe_counter!(RELAY_MAX_ATTEMPTS_EXCEEDED_COUNTER, "Events which exhausted relaying attempts");
+        describe_counter!(DEPOSIT_NONCE_GAP_COUNTER, "Gaps in observed deposit nonces, most likely missed events");
+        describe_counter!(CHECKPOINT_REGRESSION_COUNTER, "Stored checkpoints found behind the last saved one");
+        describe_histogram!(RELAY_DURATION_HISTOGRAM, "Time from the first relay attempt to the terminal outcome");
+        describe_histogram!(RELAY_ATTEMPTS_HISTOGRAM, "Relay attempts needed to reach the terminal outcome");
+        describe_histogram!(EVENT_AGE_HISTOGRAM, "Time from the source block to successful relay");
+        let destinations: Vec<String> = match relay {
+            Relay::Single(ref relayer) => vec![relayer.destination_id().to_string()],
+            Relay::Multi(ref relayers) => relayers.keys().map(|destination| destination.to_string()).collect(),
+        };
+        let relay_histograms = destinations
+            .into_iter()
+            .map(|destination| {
+                let histograms = RelayHistograms::new(id, &destination);
+                (destination, histograms)
+            })
+            .collect();
         Ok(Self {
             id: id.to_string(),
             handle,
@@ -160,44 +485,232 @@ impl<
             start_block,
             chain_id,
             max_relay_retry_attempts,
+            relay_stats: Arc::new(RelayStats::in_memory(id)),
+            control: Arc::new(ListenerControl::in_memory()),
+            status: Arc::new(ListenerStatus::new(id, chain_id)),
+            checkpoint_batching: CheckpointBatching::default(),
+            dead_letter_log: Arc::new(DeadLetterLog::in_memory()),
+            skip_failed_events: false,
+            validators: HashMap::new(),
+            fetch_timeout: DEFAULT_FETCH_TIMEOUT,
+            catch_up_only: false,
+            nonce_tracker: Arc::new(NonceTracker::in_memory()),
+            halt_on_nonce_gap: false,
+            start_nonce: None,
+            poll_interval: DEFAULT_POLL_INTERVAL,
+            fast_sync_interval: Duration::ZERO,
+            sleeper: None,
+            resource_registry: Arc::new(ResourceRegistry::default()),
+            notifier: Arc::new(Notifier::disabled()),
+            unsaved_block: None,
+            unsaved_blocks: 0,
+            last_checkpoint_save: Instant::now(),
+            checkpointed_block: None,
+            synced_block_gauge: gauge!(synced_block_gauge_name(id)),
+            finalized_block_gauge: gauge!(finalized_block_gauge_name(id)),
+            fetch_timeout_counter: counter!(fetch_timeout_counter_name(id)),
+            relay_attempts_counter: counter!(RELAY_ATTEMPTS_COUNTER, "listener" => id.to_string()),
+            relay_max_attempts_exceeded_counter: counter!(
+                RELAY_MAX_ATTEMPTS_EXCEEDED_COUNTER,
+                "listener" => id.to_string()
+            ),
+            nonce_gap_counter: counter!(DEPOSIT_NONCE_GAP_COUNTER, "listener" => id.to_string()),
+            checkpoint_regression_counter: counter!(CHECKPOINT_REGRESSION_COUNTER, "listener" => id.to_string()),
+            relay_histograms,
             _phantom: PhantomData,
         })
     }
 
+    /// Replaces in-memory relay stats, e.g. with persisted ones
+    pub fn with_relay_stats(mut self, relay_stats: Arc<RelayStats>) -> Self {
+        self.relay_stats = relay_stats;
+        self
+    }
+
+    pub fn relay_stats(&self) -> Arc<RelayStats> {
+        self.relay_stats.clone()
+    }
+
+    /// Applies state shared with management interfaces and listener specific settings from `context`
+    pub fn with_context<T>(self, context: &ListenerContext<T>) -> Self {
+        self.with_control(context.control.clone())
+            .with_status(context.status.clone())
+            .with_checkpoint_batching(context.checkpoint_batching)
+            .with_dead_letter_log(context.dead_letter_log.clone())
+            .with_skip_failed_events(context.skip_failed_events)
+            .with_validators(context.validators.clone())
+            .with_fetch_timeout(context.fetch_timeout)
+            .with_catch_up_only(context.catch_up_only)
+            .with_nonce_tracker(context.nonce_tracker.clone())
+            .with_halt_on_nonce_gap(context.halt_on_nonce_gap)
+            .with_start_nonce(context.start_nonce)
+            .with_poll_intervals(context.poll_interval, context.fast_sync_interval)
+            .with_resource_registry(context.resource_registry.clone())
+            .with_notifier(context.notifier.clone())
+    }
+
+    /// Replaces in-memory control state with the one shared with management interfaces
+    pub fn with_control(mut self, control: Arc<ListenerControl>) -> Self {
+        self.control = control;
+        self
+    }
+
+    /// Replaces sync status, e.g. with the one kept across listener restarts
+    pub fn with_status(mut self, status: Arc<ListenerStatus>) -> Self {
+        self.status = status;
+        self
+    }
+
+    pub fn status(&self) -> Arc<ListenerStatus> {
+        self.status.clone()
+    }
+
+    /// Defers persisting checkpoints of blocks without events
+    pub fn with_checkpoint_batching(mut self, checkpoint_batching: CheckpointBatching) -> Self {
+        self.checkpoint_batching = checkpoint_batching;
+        self
+    }
+
+    /// Replaces in-memory dead-letter log, e.g. with file based one
+    pub fn with_dead_letter_log(mut self, dead_letter_log: Arc<DeadLetterLog>) -> Self {
+        self.dead_letter_log = dead_letter_log;
+        self
+    }
+
+    /// Skips events which exhausted relaying attempts instead of stopping the listener
+    pub fn with_skip_failed_events(mut self, skip_failed_events: bool) -> Self {
+        self.skip_failed_events = skip_failed_events;
+        self
+    }
+
+    /// Bounds every fetch from source chain, so unresponsive node can't freeze the sync
+    pub fn with_fetch_timeout(mut self, fetch_timeout: Duration) -> Self {
+        self.fetch_timeout = fetch_timeout;
+        self
+    }
+
+    /// Makes `sync` return once all finalized blocks are synced instead of waiting for new ones, e.g. for backfills
+    pub fn with_catch_up_only(mut self, catch_up_only: bool) -> Self {
+        self.catch_up_only = catch_up_only;
+        self
+    }
+
+    /// Replaces in-memory tracker of seen deposit nonces, e.g. with file based one
+    pub fn with_nonce_tracker(mut self, nonce_tracker: Arc<NonceTracker>) -> Self {
+        self.nonce_tracker = nonce_tracker;
+        self
+    }
+
+    /// Stops syncing on deposit nonce gap instead of only reporting it
+    pub fn with_halt_on_nonce_gap(mut self, halt_on_nonce_gap: bool) -> Self {
+        self.halt_on_nonce_gap = halt_on_nonce_gap;
+        self
+    }
+
+    /// Skips events with deposit nonce below `start_nonce`, independently of the block they are in
+    pub fn with_start_nonce(mut self, start_nonce: Option<u64>) -> Self {
+        self.start_nonce = start_nonce;
+        self
+    }
+
+    /// Sets pauses between sync iterations once caught up with the finalized head and while catching up
+    pub fn with_poll_intervals(mut self, poll_interval: Duration, fast_sync_interval: Duration) -> Self {
+        self.poll_interval = poll_interval;
+        self.fast_sync_interval = fast_sync_interval;
+        self
+    }
+
+    pub fn with_sleeper(mut self, sleeper: Arc<dyn Sleeper>) -> Self {
+        self.sleeper = Some(sleeper);
+        self
+    }
+
+    /// Sets registry used to describe relayed amounts in asset units
+    pub fn with_resource_registry(mut self, resource_registry: Arc<ResourceRegistry>) -> Self {
+        self.resource_registry = resource_registry;
+        self
+    }
+
+    /// Sets notifier of dead-lettered events and listener halts
+    pub fn with_notifier(mut self, notifier: Arc<Notifier>) -> Self {
+        self.notifier = notifier;
+        self
+    }
+
+    /// Sets validators run before relaying to the destination they are keyed by
+    pub fn with_validators(mut self, validators: HashMap<String, Arc<dyn Validator>>) -> Self {
+        self.validators = validators;
+        self
+    }
+
     /// Start syncing. It's a long-running blocking operation - should be started in dedicated thread.
     pub fn sync(&mut self) -> Result<(), ()> {
+        let sleeper = self.sleeper.clone().unwrap_or_else(|| Arc::new(ThreadSleeper));
+        self.handle.clone().block_on(self.run_and_notify(sleeper))
+    }
+
+    /// Start syncing as a task of the current runtime, so many listeners can share it instead of
+    /// occupying a thread each.
+    pub async fn sync_async(&mut self) -> Result<(), ()> {
+        let sleeper = self.sleeper.clone().unwrap_or_else(|| Arc::new(TokioSleeper));
+        self.run_and_notify(sleeper).await
+    }
+
+    async fn run_and_notify(&mut self, sleeper: Arc<dyn Sleeper>) -> Result<(), ()> {
+        let result = self.run(sleeper).await;
+        if result.is_err() {
+            let error = self
+                .status
+                .snapshot()
+                .last_error
+                .unwrap_or_else(|| "Listener stopped syncing".to_string());
+            self.notifier
+                .notify(Notification::new(NotificationType::ListenerHalted, &error).with_listener(&self.id));
+        }
+        result
+    }
+
+    async fn run(&mut self, sleeper: Arc<dyn Sleeper>) -> Result<(), ()> {
         log::info!("Starting {} network sync, start block: {}", self.id, self.start_block);
-        let mut block_number_to_sync =
-            if let Some(ref checkpoint) = self.checkpoint_repository.get().expect("Could not read checkpoint") {
-                let last_block_num = checkpoint.get_block_num();
-
-                // Ensure `start_block` overrides only if it's valid
-                if self.start_block > last_block_num {
-                    self.start_block
-                } else if checkpoint.just_block_num() {
-                    // Start syncing from the next block as we processed the previous one fully
-                    last_block_num + 1
-                } else {
-                    // Reprocess the last block if interrupted
-                    last_block_num
-                }
-            } else {
-                // Default to start_block if no checkpoint exists
+        let stored_checkpoint = self.checkpoint_repository.get().expect("Could not read checkpoint");
+        let mut block_number_to_sync = if let Some(ref checkpoint) = stored_checkpoint {
+            let last_block_num = checkpoint.get_block_num();
+
+            // Ensure `start_block` overrides only if it's valid
+            if self.start_block > last_block_num {
                 self.start_block
-            };
+            } else if checkpoint.just_block_num() {
+                // Start syncing from the next block as we processed the previous one fully
+                last_block_num + 1
+            } else {
+                // Reprocess the last block if interrupted
+                last_block_num
+            }
+        } else {
+            // Default to start_block if no checkpoint exists
+            self.start_block
+        };
         log::debug!("Starting sync from {:?}", block_number_to_sync);
 
         loop {
             log::debug!("Starting syncing block: {}", block_number_to_sync);
             if self.stop_signal.try_recv().is_ok() {
+                self.flush_checkpoint();
                 return Ok(());
             }
 
-            let maybe_last_finalized_block = match self.handle.block_on(self.fetcher.get_last_finalized_block_num()) {
+            let maybe_last_finalized_block = match with_fetch_timeout(
+                self.fetch_timeout,
+                &self.fetch_timeout_counter,
+                self.fetcher.get_last_finalized_block_num(),
+            )
+            .await
+            {
                 Ok(maybe_block) => maybe_block,
                 Err(_) => {
                     log::debug!("Could not get last finalized block number");
-                    sleep(Duration::from_secs(1));
+                    self.status.set_last_error("Could not get last finalized block number");
+                    sleeper.sleep(RETRY_INTERVAL).await;
                     continue;
                 },
             };
@@ -206,12 +719,26 @@ impl<
                 Some(v) => v,
                 None => {
                     log::debug!("Waiting for finalized block, block to sync {}", block_number_to_sync);
-                    sleep(Duration::from_secs(1));
+                    sleeper.sleep(RETRY_INTERVAL).await;
                     continue;
                 },
             };
 
             log::trace!("Last finalized block: {}, block to sync {}", last_finalized_block, block_number_to_sync);
+            self.finalized_block_gauge.set(last_finalized_block as f64);
+            self.status.set_finalized_block(last_finalized_block);
+
+            if self.control.is_paused() {
+                log::debug!("Listener {} is paused, block to sync {}", self.id, block_number_to_sync);
+                sleeper.sleep(RETRY_INTERVAL).await;
+                continue;
+            }
+
+            if self.catch_up_only && block_number_to_sync > last_finalized_block {
+                log::info!("Listener {} caught up with finalized block {}, stopping", self.id, last_finalized_block);
+                self.flush_checkpoint();
+                return Ok(());
+            }
 
             //we know there are more block waiting for sync so let's skip sleep
             let fast = match last_finalized_block.checked_sub(block_number_to_sync) {
@@ -220,152 +747,470 @@ impl<
             };
 
             if last_finalized_block >= block_number_to_sync {
-                match self.handle.block_on(self.fetcher.get_block_pay_in_events(block_number_to_sync)) {
+                // blocks without events never read the checkpoint below, so check it before each block
+                if let Some(ref checkpoint) = self.checkpoint_repository.get().expect("Could not read checkpoint") {
+                    self.check_checkpoint_regression(checkpoint);
+                }
+                match with_fetch_timeout(
+                    self.fetch_timeout,
+                    &self.fetch_timeout_counter,
+                    self.fetcher.detect_reorg(block_number_to_sync),
+                )
+                .await
+                {
+                    Ok(Some(resume_block)) => {
+                        log::warn!(
+                            "Detected reorg while syncing block {}, resuming from block {}",
+                            block_number_to_sync,
+                            resume_block
+                        );
+                        // mark the last canonical block as processed so events after it are relayed again
+                        if let Some(last_canonical_block) = resume_block.checked_sub(1) {
+                            self.rewind_checkpoint(CheckpointT::from(last_canonical_block));
+                        }
+                        block_number_to_sync = resume_block;
+                        continue;
+                    },
+                    Ok(None) => {},
+                    Err(_) => {
+                        log::debug!("Could not check block {} for reorg", block_number_to_sync);
+                        sleeper.sleep(RETRY_INTERVAL).await;
+                        continue;
+                    },
+                }
+
+                match with_fetch_timeout(
+                    self.fetch_timeout,
+                    &self.fetch_timeout_counter,
+                    self.fetcher.get_block_pay_in_events(block_number_to_sync),
+                )
+                .await
+                {
                     Ok(events) => {
                         for event in events {
-                            let maybe_relayer = match self.relay {
-                                Relay::Single(ref relay) => Some(relay),
+                            if self.start_nonce.is_some_and(|start_nonce| event.nonce < start_nonce) {
+                                log::info!("Skipping event with nonce: {} below start nonce", event.nonce);
+                                self.save_checkpoint(event.id.into());
+                                continue;
+                            }
+                            let maybe_relayers = match self.relay {
+                                Relay::Single(ref relay) => Some(std::slice::from_ref(relay)),
                                 Relay::Multi(ref relayers) => {
-                                    if let Some(destination_id) = event.maybe_destination_id {
-                                        relayers.get(&destination_id)
+                                    if let Some(ref destination_id) = event.maybe_destination_id {
+                                        relayers.get(destination_id).map(|relayers| relayers.as_slice())
                                     } else {
                                         None
                                     }
                                 },
                             };
-                            if let Some(relayer) = maybe_relayer {
-                                if let Some(ref checkpoint) =
-                                    self.checkpoint_repository.get().expect("Could not read checkpoint")
-                                {
-                                    if checkpoint.lt(&event.id.clone().into()) {
-                                        let mut attempt = 1;
-                                        'relay: loop {
-                                            log::info!("Relaying attempt: {}", attempt);
-
-                                            if attempt > self.max_relay_retry_attempts {
-                                                log::error!("Exceeded maximum number of relaying attempts");
-                                                return Err(());
-                                            }
-
-                                            match self.handle.block_on(relayer.relay(
-                                                event.amount,
-                                                event.nonce,
-                                                &event.resource_id,
-                                                &event.data,
-                                                self.chain_id,
-                                            )) {
-                                                Err(RelayError::TransportError) => {
-                                                    log::info!(
-                                                        "Could not relay due to TransportError, will try again..."
-                                                    );
-                                                    sleep(Duration::from_secs(1));
-                                                    attempt += 1;
-                                                    continue 'relay;
-                                                },
-                                                Err(RelayError::Other) => {
-                                                    log::error!("Unexpected error occurred during relaying");
-                                                    return Err(());
-                                                },
-                                                Err(RelayError::WatchError) => {
-                                                    // retry the same event again
-                                                    attempt += 1;
-                                                    continue 'relay;
-                                                },
-                                                Err(RelayError::AlreadyRelayed) => {
-                                                    log::error!("Already relayed");
-                                                    break 'relay;
-                                                },
-                                                _ => break 'relay,
-                                            }
-                                        }
-                                    } else {
-                                        log::debug!("Skipping event");
-                                    }
+                            if let Some(relayers) = maybe_relayers.filter(|relayers| !relayers.is_empty()) {
+                                let relayers = relayers.to_vec();
+                                let checkpoint = self.checkpoint_repository.get().expect("Could not read checkpoint");
+                                if let Some(ref checkpoint) = checkpoint {
+                                    self.check_checkpoint_regression(checkpoint);
+                                }
+                                if checkpoint.is_some_and(|checkpoint| !checkpoint.lt(&event.id.clone().into())) {
+                                    log::debug!("Skipping event");
                                 } else {
-                                    let mut attempt = 1;
-                                    'relay: loop {
-                                        log::info!("Relaying attempt: {}", attempt);
-
-                                        if attempt > self.max_relay_retry_attempts {
-                                            log::error!("Exceeded maximum number of relaying attempts");
-                                            return Err(());
-                                        }
-
-                                        match self.handle.block_on(relayer.relay(
-                                            event.amount,
-                                            event.nonce,
-                                            &event.resource_id,
-                                            &event.data,
-                                            self.chain_id,
-                                        )) {
-                                            Err(RelayError::TransportError) => {
-                                                log::info!("Could not relay due to TransportError, will try again...");
-                                                sleep(Duration::from_secs(1));
-                                                attempt += 1;
-                                                continue 'relay;
-                                            },
-                                            Err(RelayError::Other) => {
-                                                log::error!("Unexpected error occurred during relaying");
-                                                return Err(());
-                                            },
-                                            Err(RelayError::WatchError) => {
-                                                // retry the same event again
-                                                attempt += 1;
-                                                continue 'relay;
-                                            },
-                                            Err(RelayError::AlreadyRelayed) => {
-                                                log::error!("Already relayed");
-                                                break 'relay;
-                                            },
-                                            _ => break 'relay,
-                                        }
+                                    if self.check_nonce(&relayers[0].destination_id().to_string(), &event).is_err() {
+                                        return Err(());
+                                    }
+                                    if let Err(e) = self.validate(&relayers[0], &event) {
+                                        self.reject_invalid_event(&event, e);
+                                    } else if self.relay_event(&relayers, &event, &sleeper).await?.is_none() {
+                                        self.flush_checkpoint();
+                                        return Ok(());
                                     }
                                 }
                             }
-                            self.checkpoint_repository
-                                .save(event.id.into())
-                                .expect("Could not save checkpoint");
+                            self.save_checkpoint(event.id.into());
                         }
                         // we processed block completely so store new checkpoint
-                        self.checkpoint_repository
-                            .save(CheckpointT::from(block_number_to_sync))
-                            .expect("Could not save checkpoint");
-                        gauge!(synced_block_gauge_name(&self.id)).set(block_number_to_sync as f64);
+                        self.save_block_checkpoint(block_number_to_sync);
+                        self.synced_block_gauge.set(block_number_to_sync as f64);
+                        self.status.set_synced_block(block_number_to_sync);
                         log::info!("Finished syncing block: {}", block_number_to_sync);
                         block_number_to_sync += 1;
                     },
                     Err(e) => {
                         log::error!("Could not get events: {:?}", e);
-                        sleep(Duration::from_secs(1));
+                        self.status.set_last_error("Could not get events");
+                        sleeper.sleep(RETRY_INTERVAL).await;
                     },
                 }
             }
 
             if !fast {
-                sleep(Duration::from_secs(2))
+                sleeper.sleep(self.poll_interval).await
+            } else if !self.fast_sync_interval.is_zero() {
+                sleeper.sleep(self.fast_sync_interval).await
             } else {
-                log::trace!("Fast sync skipping 1s wait");
+                log::trace!("Fast sync skipping wait");
+            }
+        }
+    }
+
+    // relays the event with retries, failing over between relayers of its destination, returns `None` if
+    // the listener was stopped while waiting for the destination to be unpaused
+    async fn relay_event(
+        &mut self,
+        relayers: &[Arc<Box<dyn Relayer<DestinationId>>>],
+        event: &PayIn<PayInEventId, DestinationId>,
+        sleeper: &Arc<dyn Sleeper>,
+    ) -> Result<Option<RelayOutcome>, ()> {
+        let mut attempt = 1;
+        let mut relayer_index = 0;
+        let relay_started = Instant::now();
+        let outcome = loop {
+            log::info!(
+                "Relaying attempt: {} of event with nonce: {}, source: {}, amount: {}",
+                attempt,
+                event.nonce,
+                event.source_ref_hex(),
+                self.resource_registry.describe(&event.resource_id, event.amount)
+            );
+
+            if attempt > self.max_relay_retry_attempts {
+                log::error!("Exceeded maximum number of relaying attempts");
+                self.relay_max_attempts_exceeded_counter.increment(1);
+                self.status
+                    .set_last_event_error("Exceeded maximum number of relaying attempts", &event.source_ref_hex());
+                self.write_dead_letter(event, "Exceeded maximum number of relaying attempts");
+                if self.skip_failed_events {
+                    break RelayOutcome::DeadLetter;
+                }
+                self.record_relay(&relayers[0], event, RelayOutcome::DeadLetter, relay_started, attempt);
+                return Err(());
+            }
+
+            let relayer = &relayers[relayer_index];
+            match relayer.relay(&event.relay_request(self.chain_id)).await {
+                Err(RelayError::TransportError) => {
+                    // fail over to the next relayer of the destination, start over once all of them failed
+                    if relayer_index + 1 < relayers.len() {
+                        log::info!("Could not relay due to TransportError, failing over to the next relayer...");
+                        relayer_index += 1;
+                        continue;
+                    }
+                    log::info!("Could not relay due to TransportError, will try again...");
+                    relayer_index = 0;
+                    sleeper.sleep(RETRY_INTERVAL).await;
+                    self.relay_attempts_counter.increment(1);
+                    attempt += 1;
+                },
+                Err(RelayError::Other) => {
+                    log::error!("Unexpected error occurred during relaying");
+                    self.status
+                        .set_last_event_error("Unexpected error occurred during relaying", &event.source_ref_hex());
+                    self.write_dead_letter(event, "Unexpected error occurred during relaying");
+                    self.record_relay(&relayers[0], event, RelayOutcome::DeadLetter, relay_started, attempt);
+                    return Err(());
+                },
+                Err(RelayError::Paused) => {
+                    // pausing is intentional, waiting for unpause doesn't count as an attempt
+                    if self.stop_signal.try_recv().is_ok() {
+                        return Ok(None);
+                    }
+                    log::info!("Could not relay, destination is paused, will try again...");
+                    sleeper.sleep(PAUSED_RETRY_INTERVAL).await;
+                },
+                Err(RelayError::WatchError) => {
+                    // retry the same event again
+                    self.relay_attempts_counter.increment(1);
+                    attempt += 1;
+                },
+                Err(RelayError::AlreadyRelayed) => {
+                    log::error!("Already relayed");
+                    break RelayOutcome::AlreadyRelayed;
+                },
+                Err(RelayError::BelowMinAmount) => {
+                    log::info!("Skipping event with nonce: {} below minimum amount", event.nonce);
+                    break RelayOutcome::BelowMinAmount;
+                },
+                Err(RelayError::Filtered) => {
+                    log::info!("Skipping event with nonce: {} with resource id not on relayer allowlist", event.nonce);
+                    break RelayOutcome::Filtered;
+                },
+                Err(RelayError::AboveMaxAmount) => {
+                    log::error!(
+                        "ALERT: Event with nonce: {}, source: {} exceeds maximum amount",
+                        event.nonce,
+                        event.source_ref_hex()
+                    );
+                    self.status
+                        .set_last_event_error("Event exceeds maximum amount", &event.source_ref_hex());
+                    self.record_relay(&relayers[0], event, RelayOutcome::AboveMaxAmount, relay_started, attempt);
+                    return Err(());
+                },
+                Err(RelayError::LimitExceeded) => {
+                    self.report_limit_exceeded(event);
+                    break RelayOutcome::LimitExceeded;
+                },
+                Ok(()) => {
+                    self.relay_stats.record(
+                        &relayer.destination_id().to_string(),
+                        event.nonce,
+                        event.amount,
+                        &event.source_ref_hex(),
+                    );
+                    break RelayOutcome::Success;
+                },
+            }
+        };
+        self.record_relay(&relayers[0], event, outcome, relay_started, attempt);
+        Ok(Some(outcome))
+    }
+
+    // block checkpoints are saved in batches, relayed events are already covered by their own checkpoints
+    // so reprocessing blocks after a crash relays nothing twice and skips nothing
+    fn save_block_checkpoint(&mut self, block_num: u64) {
+        self.unsaved_blocks += 1;
+        if self.unsaved_blocks >= self.checkpoint_batching.max_blocks
+            || self.last_checkpoint_save.elapsed() >= Duration::from_secs(self.checkpoint_batching.max_interval_secs)
+        {
+            self.save_checkpoint(CheckpointT::from(block_num));
+        } else {
+            self.unsaved_block = Some(block_num);
+        }
+    }
+
+    fn save_checkpoint(&mut self, checkpoint: CheckpointT) {
+        self.checkpointed_block = Some(checkpoint.get_block_num());
+        self.checkpoint_repository.save(checkpoint).expect("Could not save checkpoint");
+        self.unsaved_block = None;
+        self.unsaved_blocks = 0;
+        self.last_checkpoint_save = Instant::now();
+    }
+
+    // checkpoint is moved behind the stored one, repositories may reject that for regular saves
+    fn rewind_checkpoint(&mut self, checkpoint: CheckpointT) {
+        self.checkpointed_block = Some(checkpoint.get_block_num());
+        self.checkpoint_repository
+            .rewind(checkpoint)
+            .expect("Could not rewind checkpoint");
+        self.unsaved_block = None;
+        self.unsaved_blocks = 0;
+        self.last_checkpoint_save = Instant::now();
+    }
+
+    // stored checkpoint should never be behind the last one this listener saved, otherwise it was edited or the
+    // repository returned a stale value and events after it would be relayed again
+    fn check_checkpoint_regression(&self, checkpoint: &CheckpointT) {
+        let stored_block = checkpoint.get_block_num();
+        if let Some(last_saved_block) = self.checkpointed_block.filter(|block| stored_block < *block) {
+            log::warn!(
+                "Listener {} read stored checkpoint at block {}, behind last saved block {}",
+                self.id,
+                stored_block,
+                last_saved_block
+            );
+            self.checkpoint_regression_counter.increment(1);
+        }
+    }
+
+    fn flush_checkpoint(&mut self) {
+        if let Some(block_num) = self.unsaved_block.take() {
+            self.save_checkpoint(CheckpointT::from(block_num));
+        }
+    }
+
+    fn write_dead_letter(&self, event: &PayIn<PayInEventId, DestinationId>, reason: &str) {
+        let record = DeadLetterRecord {
+            listener_id: self.id.clone(),
+            event_id: format!("{:?}", event.id),
+            destination_id: event.maybe_destination_id.as_ref().map(|id| id.to_string()),
+            nonce: event.nonce,
+            amount: event.amount,
+            resource_id: hex::encode(event.resource_id),
+            data: hex::encode(event.recipient.as_bytes()),
+            source_ref: hex::encode(&event.source_ref),
+            reason: reason.to_string(),
+            timestamp: unix_now(),
+        };
+        if self.dead_letter_log.write(record).is_err() {
+            log::error!("Could not write dead-letter record of event with nonce: {}", event.nonce);
+        }
+        self.notifier.notify(
+            Notification::new(NotificationType::DeadLetter, reason)
+                .with_listener(&self.id)
+                .with_deposit(event.nonce, event.amount),
+        );
+    }
+
+    fn validate(
+        &self,
+        relayer: &Arc<Box<dyn Relayer<DestinationId>>>,
+        event: &PayIn<PayInEventId, DestinationId>,
+    ) -> Result<(), ValidationError> {
+        match self.validators.get(&relayer.destination_id().to_string()) {
+            Some(validator) => validator.validate(event.amount, &event.resource_id, event.recipient.as_bytes()),
+            None => Ok(()),
+        }
+    }
+
+    fn record_relay(
+        &self,
+        relayer: &Arc<Box<dyn Relayer<DestinationId>>>,
+        event: &PayIn<PayInEventId, DestinationId>,
+        outcome: RelayOutcome,
+        started: Instant,
+        attempts: u8,
+    ) {
+        let Some(histograms) = self.relay_histograms.get(&relayer.destination_id().to_string()) else {
+            return;
+        };
+        if let Some(duration) = histograms.duration.get(&outcome) {
+            duration.record(started.elapsed().as_secs_f64());
+        }
+        // attempt counter is past the limit when attempts are exhausted
+        histograms.attempts.record(attempts.min(self.max_relay_retry_attempts) as f64);
+        if let (RelayOutcome::Success, Some(timestamp)) = (outcome, event.timestamp) {
+            histograms.event_age.record(unix_now().saturating_sub(timestamp) as f64);
+        }
+    }
+
+    // reports nonce gap since the last event seen for `destination`, fails if listener should halt on it
+    fn check_nonce(&self, destination: &str, event: &PayIn<PayInEventId, DestinationId>) -> Result<(), ()> {
+        if let NonceCheck::Gap { from, to } = self.nonce_tracker.check(&event.source, destination, event.nonce) {
+            log::error!(
+                "ALERT: Deposit nonces {}..={} to destination {} were not seen before nonce {}, source: {}",
+                from,
+                to,
+                destination,
+                event.nonce,
+                event.source_ref_hex()
+            );
+            self.nonce_gap_counter.increment(1);
+            if self.halt_on_nonce_gap {
+                self.status.set_last_event_error(
+                    &format!("Deposit nonce gap {}..={} to destination {}", from, to, destination),
+                    &event.source_ref_hex(),
+                );
+                return Err(());
             }
         }
+        self.nonce_tracker.record(&event.source, destination, event.nonce);
+        Ok(())
+    }
+
+    // invalid events are never relayed, they are skipped and left for manual review
+    fn reject_invalid_event(&self, event: &PayIn<PayInEventId, DestinationId>, error: ValidationError) {
+        log::error!(
+            "Event with nonce: {}, source: {} failed validation: {}",
+            event.nonce,
+            event.source_ref_hex(),
+            error
+        );
+        self.status
+            .set_last_event_error(&format!("Event failed validation: {}", error), &event.source_ref_hex());
+        self.write_dead_letter(event, &error.to_string());
+    }
+
+    // events exceeding amount caps are skipped and left for manual review
+    fn report_limit_exceeded(&self, event: &PayIn<PayInEventId, DestinationId>) {
+        log::error!(
+            "ALERT: Amount limit exceeded on {} listener, skipping event with nonce: {}, source: {}, amount: {}, destination: {:?}",
+            self.id,
+            event.nonce,
+            event.source_ref_hex(),
+            event.amount,
+            event.maybe_destination_id
+        );
+        counter!(limit_exceeded_counter_name(&self.id)).increment(1);
+        self.write_dead_letter(event, LIMIT_EXCEEDED_REASON);
+    }
+}
+
+/// Type erased `Listener`, used where concrete listener type is chosen at runtime, e.g. by chain name.
+#[allow(clippy::result_unit_err)]
+pub trait SyncListener: Send {
+    /// See `Listener::sync`
+    fn sync(&mut self) -> Result<(), ()>;
+    /// See `Listener::sync_async`
+    fn sync_async(&mut self) -> Pin<Box<dyn Future<Output = Result<(), ()>> + Send + '_>>;
+    fn relay_stats(&self) -> Arc<RelayStats>;
+}
+
+impl<
+        DestinationId: Hash + Eq + Clone + Debug + ToString + Send + Sync,
+        PayInEventId: Into<CheckpointT> + Clone + Debug + Send + Sync,
+        Fetcher: LastFinalizedBlockNumFetcher + BlockPayInEventsFetcher<PayInEventId, DestinationId> + ReorgDetector + Send,
+        CheckpointT: PartialOrd + Checkpoint + From<u64> + Send + Sync,
+        CheckpointRepositoryT: CheckpointRepository<CheckpointT> + Send,
+    > SyncListener for Listener<DestinationId, Fetcher, CheckpointT, CheckpointRepositoryT, PayInEventId>
+where
+    Self: Send,
+{
+    fn sync(&mut self) -> Result<(), ()> {
+        Listener::sync(self)
+    }
+
+    fn sync_async(&mut self) -> Pin<Box<dyn Future<Output = Result<(), ()>> + Send + '_>> {
+        Box::pin(Listener::sync_async(self))
     }
+
+    fn relay_stats(&self) -> Arc<RelayStats> {
+        Listener::relay_stats(self)
+    }
+}
+
+// sync loop would wait forever on unresponsive node otherwise
+async fn with_fetch_timeout<T>(
+    timeout: Duration,
+    timeout_counter: &Counter,
+    future: impl Future<Output = Result<T, ()>>,
+) -> Result<T, ()> {
+    tokio::time::timeout(timeout, future).await.map_err(|_| {
+        log::error!("Fetching from source chain timed out after {:?}", timeout);
+        timeout_counter.increment(1);
+    })?
 }
 
 fn synced_block_gauge_name(listener_id: &str) -> String {
-    format!("{}_synced_block", listener_id)
+    metric_name(&format!("{}_synced_block", listener_id))
+}
+
+fn finalized_block_gauge_name(listener_id: &str) -> String {
+    metric_name(&format!("{}_finalized_block", listener_id))
+}
+
+fn limit_exceeded_counter_name(listener_id: &str) -> String {
+    metric_name(&format!("{}_limit_exceeded", listener_id))
+}
+
+fn fetch_timeout_counter_name(listener_id: &str) -> String {
+    metric_name(&format!("{}_fetch_timeouts", listener_id))
 }
 
 #[cfg(test)]
 pub mod tests {
-    use crate::fetcher::{BlockPayInEventsFetcher, LastFinalizedBlockNumFetcher};
-    use crate::listener::{Listener, PayIn, RELAY_MAX_ATTEMPTS};
-    use crate::relay::{MockRelayer, Relay, RelayError};
-    use crate::sync_checkpoint_repository::{Checkpoint, InMemoryCheckpointRepository};
+    use crate::amount_limit::unix_now;
+    use crate::config::{BridgeConfig, CheckpointBatching};
+    use crate::dead_letter::{DeadLetterLog, LIMIT_EXCEEDED_REASON};
+    use crate::fetcher::{BlockPayInEventsFetcher, LastFinalizedBlockNumFetcher, ReorgDetector};
+    use crate::listener::{
+        prepare_listener_context, Listener, PayIn, Sleeper, CHECKPOINT_REGRESSION_COUNTER, EVENT_AGE_HISTOGRAM,
+        PAUSED_RETRY_INTERVAL, RELAY_ATTEMPTS_COUNTER, RELAY_ATTEMPTS_HISTOGRAM, RELAY_DURATION_HISTOGRAM,
+        RELAY_MAX_ATTEMPTS, RELAY_MAX_ATTEMPTS_EXCEEDED_COUNTER,
+    };
+    use crate::listener_control::ListenerControl;
+    use crate::nonce_tracker::{NonceCheck, NonceTracker, DEPOSIT_NONCE_GAP_COUNTER};
+    use crate::notification::{NotificationsConfig, Notifier, Severity, WebhookClient};
+    use crate::relay::{MockRelayer, Recipient, Relay, RelayError, Relayer};
+    use crate::sync_checkpoint_repository::{Checkpoint, CheckpointRepository, InMemoryCheckpointRepository};
+    use crate::validation::{ValidationError, Validator};
     use async_trait::async_trait;
-    use mockall::predicate::{always, eq};
+    use metrics::{SharedString, Unit};
+    use metrics_util::debugging::{DebugValue, DebuggingRecorder, Snapshotter};
+    use metrics_util::CompositeKey;
+    use mockall::predicate::eq;
     use mockall::*;
     use std::cmp::Ordering;
-    use std::sync::Arc;
+    use std::collections::HashMap;
+    use std::sync::atomic::{self, AtomicUsize};
+    use std::sync::{Arc, Mutex};
     use std::thread;
+    use std::time::Duration;
     use tokio::runtime::Handle;
 
     mock! {
@@ -378,6 +1223,7 @@ pub mod tests {
         impl BlockPayInEventsFetcher<u64, String> for Fetcher {
             async fn get_block_pay_in_events(&mut self, block_num: u64) -> Result<Vec<PayIn<u64, String>>, ()>;
         }
+        impl ReorgDetector for Fetcher {}
     }
 
     #[derive(Clone, Debug)]
@@ -423,10 +1269,11 @@ pub mod tests {
     pub async fn sync_should_start_syncing_from_last_saved_log() {
         let handle = Handle::current();
         let mut relayer = MockRelayer::new();
+        relayer.expect_destination_id().returning(|| "test".to_string());
         relayer
             .expect_relay()
             .times(2)
-            .returning(|_, _, _, _, _| Box::pin(futures::future::ready(Ok(()))));
+            .returning(|_| Box::pin(futures::future::ready(Ok(()))));
         let relay = Relay::Single(Arc::new(Box::new(relayer)));
         let mut fetcher = MockFetcher::new();
         fetcher.expect_get_last_finalized_block_num().times(3).returning(|| Ok(Some(3)));
@@ -434,22 +1281,22 @@ pub mod tests {
             .expect_get_block_pay_in_events()
             .with(eq(0))
             .times(0)
-            .returning(|_| Ok(vec![PayIn::new(0, None, 0, 0, [0; 32], vec![])]));
+            .returning(|_| Ok(vec![PayIn::new(0, None, 0, 0, [0; 32], Recipient::Raw(vec![]))]));
         fetcher
             .expect_get_block_pay_in_events()
             .with(eq(1))
             .times(0)
-            .returning(|_| Ok(vec![PayIn::new(1, None, 0, 0, [0; 32], vec![])]));
+            .returning(|_| Ok(vec![PayIn::new(1, None, 0, 0, [0; 32], Recipient::Raw(vec![]))]));
         fetcher
             .expect_get_block_pay_in_events()
             .with(eq(2))
             .times(1)
-            .returning(|_| Ok(vec![PayIn::new(2, None, 0, 0, [0; 32], vec![])]));
+            .returning(|_| Ok(vec![PayIn::new(2, None, 0, 0, [0; 32], Recipient::Raw(vec![]))]));
         fetcher
             .expect_get_block_pay_in_events()
             .with(eq(3))
             .times(1)
-            .returning(|_| Ok(vec![PayIn::new(3, None, 0, 0, [0; 32], vec![])]));
+            .returning(|_| Ok(vec![PayIn::new(3, None, 0, 0, [0; 32], Recipient::Raw(vec![]))]));
 
         let (tx, rx) = tokio::sync::oneshot::channel();
 
@@ -477,10 +1324,11 @@ pub mod tests {
     pub async fn sync_should_keep_on_syncing_in_case_of_already_relayed_error() {
         let handle = Handle::current();
         let mut relayer = MockRelayer::new();
+        relayer.expect_destination_id().returning(|| "test".to_string());
         relayer
             .expect_relay()
             .times(2)
-            .returning(|_, _, _, _, _| Box::pin(futures::future::ready(Err(RelayError::AlreadyRelayed))));
+            .returning(|_| Box::pin(futures::future::ready(Err(RelayError::AlreadyRelayed))));
         let relay = Relay::Single(Arc::new(Box::new(relayer)));
         let mut fetcher = MockFetcher::new();
         fetcher.expect_get_last_finalized_block_num().times(3).returning(|| Ok(Some(3)));
@@ -488,22 +1336,22 @@ pub mod tests {
             .expect_get_block_pay_in_events()
             .with(eq(0))
             .times(0)
-            .returning(|_| Ok(vec![PayIn::new(0, None, 0, 0, [0; 32], vec![])]));
+            .returning(|_| Ok(vec![PayIn::new(0, None, 0, 0, [0; 32], Recipient::Raw(vec![]))]));
         fetcher
             .expect_get_block_pay_in_events()
             .with(eq(1))
             .times(0)
-            .returning(|_| Ok(vec![PayIn::new(1, None, 0, 0, [0; 32], vec![])]));
+            .returning(|_| Ok(vec![PayIn::new(1, None, 0, 0, [0; 32], Recipient::Raw(vec![]))]));
         fetcher
             .expect_get_block_pay_in_events()
             .with(eq(2))
             .times(1)
-            .returning(|_| Ok(vec![PayIn::new(2, None, 0, 0, [0; 32], vec![])]));
+            .returning(|_| Ok(vec![PayIn::new(2, None, 0, 0, [0; 32], Recipient::Raw(vec![]))]));
         fetcher
             .expect_get_block_pay_in_events()
             .with(eq(3))
             .times(1)
-            .returning(|_| Ok(vec![PayIn::new(3, None, 0, 0, [0; 32], vec![])]));
+            .returning(|_| Ok(vec![PayIn::new(3, None, 0, 0, [0; 32], Recipient::Raw(vec![]))]));
 
         let (tx, rx) = tokio::sync::oneshot::channel();
 
@@ -527,15 +1375,80 @@ pub mod tests {
         handle.join().unwrap();
     }
 
+    #[tokio::test]
+    pub async fn sync_should_skip_event_in_case_of_limit_exceeded_error() {
+        let handle = Handle::current();
+        let mut relayer = MockRelayer::new();
+        relayer.expect_destination_id().returning(|| "test".to_string());
+        relayer
+            .expect_relay()
+            .times(2)
+            .returning(|_| Box::pin(futures::future::ready(Err(RelayError::LimitExceeded))));
+        let relay = Relay::Single(Arc::new(Box::new(relayer)));
+        let mut fetcher = MockFetcher::new();
+        fetcher.expect_get_last_finalized_block_num().times(3).returning(|| Ok(Some(3)));
+        fetcher
+            .expect_get_block_pay_in_events()
+            .with(eq(0))
+            .times(0)
+            .returning(|_| Ok(vec![PayIn::new(0, None, 0, 0, [0; 32], Recipient::Raw(vec![]))]));
+        fetcher
+            .expect_get_block_pay_in_events()
+            .with(eq(1))
+            .times(0)
+            .returning(|_| Ok(vec![PayIn::new(1, None, 0, 0, [0; 32], Recipient::Raw(vec![]))]));
+        fetcher
+            .expect_get_block_pay_in_events()
+            .with(eq(2))
+            .times(1)
+            .returning(|_| Ok(vec![PayIn::new(2, None, 0, 0, [0; 32], Recipient::Raw(vec![]))]));
+        fetcher
+            .expect_get_block_pay_in_events()
+            .with(eq(3))
+            .times(1)
+            .returning(|_| Ok(vec![PayIn::new(3, None, 0, 0, [0; 32], Recipient::Raw(vec![]))]));
+
+        let (tx, rx) = tokio::sync::oneshot::channel();
+
+        let checkpoint_repository: InMemoryCheckpointRepository<SimpleCheckpoint> =
+            InMemoryCheckpointRepository::new(Some(SimpleCheckpoint { block_num: 1 }));
+
+        let dead_letter_log = Arc::new(DeadLetterLog::in_memory());
+
+        let mut listener =
+            Listener::new("test", handle, fetcher, relay, rx, checkpoint_repository, 0, 0, RELAY_MAX_ATTEMPTS)
+                .unwrap()
+                .with_dead_letter_log(dead_letter_log.clone());
+
+        let handle = thread::spawn(move || {
+            let result = listener.sync();
+            assert!(result.is_ok());
+        });
+
+        // give a listener some time to make a couple of tries
+        thread::sleep(std::time::Duration::from_secs(5));
+
+        // stop listener
+        tx.send(()).unwrap();
+
+        handle.join().unwrap();
+
+        // skipped events are kept for review and re-drive
+        let records = dead_letter_log.records().unwrap();
+        assert_eq!(records.iter().map(|record| record.event_id.as_str()).collect::<Vec<_>>(), vec!["2", "3"]);
+        assert!(records.iter().all(|record| record.reason == LIMIT_EXCEEDED_REASON));
+    }
+
     #[tokio::test]
     pub async fn sync_should_stop_in_case_of_relaying_other_error() {
         let handle = Handle::current();
 
         let mut relayer = MockRelayer::new();
+        relayer.expect_destination_id().returning(|| "test".to_string());
         relayer
             .expect_relay()
             .times(1)
-            .returning(|_, _, _, _, _| Box::pin(futures::future::ready(Err(RelayError::Other))));
+            .returning(|_| Box::pin(futures::future::ready(Err(RelayError::Other))));
         let relay = Relay::Single(Arc::new(Box::new(relayer)));
 
         let mut fetcher = MockFetcher::new();
@@ -544,7 +1457,7 @@ pub mod tests {
             .expect_get_block_pay_in_events()
             .with(eq(0))
             .times(1)
-            .returning(|_| Ok(vec![PayIn::new(0, None, 0, 0, [0; 32], vec![])]));
+            .returning(|_| Ok(vec![PayIn::new(0, None, 0, 0, [0; 32], Recipient::Raw(vec![]))]));
 
         let (_, rx) = tokio::sync::oneshot::channel();
 
@@ -571,25 +1484,29 @@ pub mod tests {
         let handle = Handle::current();
 
         let mut relayer = MockRelayer::new();
+        relayer.expect_destination_id().returning(|| "test".to_string());
 
         relayer
             .expect_relay()
-            .with(always(), eq(0), always(), always(), always())
+            .withf(|request| request.nonce == 0)
             .times(1)
-            .returning(|_, _, _, _, _| Box::pin(futures::future::ready(Ok(()))));
+            .returning(|_| Box::pin(futures::future::ready(Ok(()))));
 
         relayer
             .expect_relay()
-            .with(always(), eq(1), always(), always(), always())
+            .withf(|request| request.nonce == 1)
             .times(RELAY_MAX_ATTEMPTS as usize)
-            .returning(|_, _, _, _, _| Box::pin(futures::future::ready(Err(RelayError::TransportError))));
+            .returning(|_| Box::pin(futures::future::ready(Err(RelayError::TransportError))));
 
         let relay = Relay::Single(Arc::new(Box::new(relayer)));
 
         let mut fetcher = MockFetcher::new();
         fetcher.expect_get_last_finalized_block_num().times(1).returning(|| Ok(Some(3)));
         fetcher.expect_get_block_pay_in_events().with(eq(0)).times(1).returning(|_| {
-            Ok(vec![PayIn::new(0, None, 0, 0, [0; 32], vec![]), PayIn::new(1, None, 0, 1, [0; 32], vec![])])
+            Ok(vec![
+                PayIn::new(0, None, 0, 0, [0; 32], Recipient::Raw(vec![])),
+                PayIn::new(1, None, 0, 1, [0; 32], Recipient::Raw(vec![])),
+            ])
         });
 
         let (tx, rx) = tokio::sync::oneshot::channel();
@@ -615,35 +1532,288 @@ pub mod tests {
         handle.join().unwrap();
     }
 
-    #[tokio::test]
-    pub async fn sync_should_retry_relaying_in_case_of_relaying_watch_error() {
-        let handle = Handle::current();
+    fn counter_value(snapshotter: &Snapshotter, name: &str, listener_id: &str) -> Option<u64> {
+        snapshotter.snapshot().into_vec().into_iter().find_map(|(key, _, _, value)| {
+            let key = key.key();
+            let labelled = key
+                .labels()
+                .any(|label| label.key() == "listener" && label.value() == listener_id);
+            match value {
+                DebugValue::Counter(value) if key.name() == name && labelled => Some(value),
+                _ => None,
+            }
+        })
+    }
 
-        let mut relayer = MockRelayer::new();
+    fn histogram_samples(
+        snapshot: &[(CompositeKey, Option<Unit>, Option<SharedString>, DebugValue)],
+        name: &str,
+        labels: &[(&str, &str)],
+    ) -> Vec<f64> {
+        snapshot
+            .iter()
+            .filter_map(|(key, _, _, value)| {
+                let key = key.key();
+                let labelled = labels
+                    .iter()
+                    .all(|(name, value)| key.labels().any(|label| label.key() == *name && label.value() == *value));
+                match value {
+                    DebugValue::Histogram(samples) if key.name() == name && labelled => {
+                        Some(samples.iter().map(|sample| sample.0).collect::<Vec<_>>())
+                    },
+                    _ => None,
+                }
+            })
+            .flatten()
+            .collect()
+    }
 
+    #[tokio::test]
+    pub async fn sync_should_record_relay_durations_attempts_and_event_age() {
+        let mut relayer = MockRelayer::new();
+        relayer.expect_destination_id().returning(|| "test".to_string());
         relayer
             .expect_relay()
-            .with(always(), eq(0), always(), always(), always())
-            .times(1)
-            .returning(|_, _, _, _, _| Box::pin(futures::future::ready(Ok(()))));
-
+            .withf(|request| request.nonce == 1)
+            .returning(|_| Box::pin(futures::future::ready(Ok(()))));
         relayer
             .expect_relay()
-            .with(always(), eq(1), always(), always(), always())
-            .times(RELAY_MAX_ATTEMPTS as usize)
-            .returning(|_, _, _, _, _| Box::pin(futures::future::ready(Err(RelayError::WatchError))));
-
+            .withf(|request| request.nonce == 2)
+            .returning(|_| Box::pin(futures::future::ready(Err(RelayError::AlreadyRelayed))));
         let relay = Relay::Single(Arc::new(Box::new(relayer)));
 
         let mut fetcher = MockFetcher::new();
-        fetcher.expect_get_last_finalized_block_num().times(1).returning(|| Ok(Some(3)));
-        fetcher.expect_get_block_pay_in_events().with(eq(0)).times(1).returning(|_| {
-            Ok(vec![PayIn::new(0, None, 0, 0, [0; 32], vec![]), PayIn::new(1, None, 0, 1, [0; 32], vec![])])
+        fetcher.expect_get_last_finalized_block_num().returning(|| Ok(Some(0)));
+        fetcher.expect_get_block_pay_in_events().with(eq(0)).returning(|_| {
+            Ok(vec![
+                PayIn::new(0, None, 0, 1, [0; 32], Recipient::Raw(vec![])).with_timestamp(Some(unix_now() - 60)),
+                PayIn::new(1, None, 0, 2, [0; 32], Recipient::Raw(vec![])).with_timestamp(Some(unix_now() - 60)),
+            ])
         });
 
-        let (_, rx) = tokio::sync::oneshot::channel();
+        let (_tx, rx) = tokio::sync::oneshot::channel();
+        let recorder = DebuggingRecorder::new();
+        let snapshotter = recorder.snapshotter();
+        let mut listener = metrics::with_local_recorder(&recorder, || {
+            Listener::new(
+                "timed",
+                Handle::current(),
+                fetcher,
+                relay,
+                rx,
+                InMemoryCheckpointRepository::<SimpleCheckpoint>::new(None),
+                0,
+                0,
+                2,
+            )
+            .unwrap()
+            .with_catch_up_only(true)
+        });
 
-        let checkpoint_repository: InMemoryCheckpointRepository<SimpleCheckpoint> =
+        let handle = thread::spawn(move || listener.sync());
+        assert!(handle.join().unwrap().is_ok());
+
+        let snapshot = snapshotter.snapshot().into_vec();
+        let labels = [("listener", "timed"), ("destination", "test")];
+        let outcome_samples = |outcome: &str| {
+            let mut labels = labels.to_vec();
+            labels.push(("outcome", outcome));
+            histogram_samples(&snapshot, RELAY_DURATION_HISTOGRAM, &labels).len()
+        };
+        assert_eq!(outcome_samples("success"), 1);
+        assert_eq!(outcome_samples("already_relayed"), 1);
+        assert_eq!(outcome_samples("dead_letter"), 0);
+        assert_eq!(histogram_samples(&snapshot, RELAY_ATTEMPTS_HISTOGRAM, &labels), vec![1.0, 1.0]);
+        // only successfully relayed events are aged
+        let event_age = histogram_samples(&snapshot, EVENT_AGE_HISTOGRAM, &labels);
+        assert_eq!(event_age.len(), 1);
+        assert!(event_age[0] >= 60.0);
+    }
+
+    #[tokio::test]
+    pub async fn sync_should_count_relaying_retries_and_exhausted_attempts_on_transport_error() {
+        let handle = Handle::current();
+
+        let mut relayer = MockRelayer::new();
+        relayer.expect_destination_id().returning(|| "test".to_string());
+        relayer
+            .expect_relay()
+            .withf(|request| request.nonce == 1)
+            .times(2)
+            .returning(|_| Box::pin(futures::future::ready(Err(RelayError::TransportError))));
+        let relay = Relay::Single(Arc::new(Box::new(relayer)));
+
+        let mut fetcher = MockFetcher::new();
+        fetcher.expect_get_last_finalized_block_num().returning(|| Ok(Some(1)));
+        fetcher
+            .expect_get_block_pay_in_events()
+            .with(eq(0))
+            .returning(|_| Ok(vec![PayIn::new(0, None, 0, 1, [0; 32], Recipient::Raw(vec![]))]));
+
+        let (_tx, rx) = tokio::sync::oneshot::channel();
+
+        let recorder = DebuggingRecorder::new();
+        let snapshotter = recorder.snapshotter();
+        let checkpoint_repository: InMemoryCheckpointRepository<SimpleCheckpoint> =
+            InMemoryCheckpointRepository::new(None);
+        let mut listener = metrics::with_local_recorder(&recorder, || {
+            Listener::new("counted", handle, fetcher, relay, rx, checkpoint_repository, 0, 0, 2).unwrap()
+        });
+
+        let handle = thread::spawn(move || {
+            let result = listener.sync();
+            assert!(result.is_err());
+        });
+        handle.join().unwrap();
+
+        assert_eq!(counter_value(&snapshotter, RELAY_ATTEMPTS_COUNTER, "counted"), Some(2));
+        assert_eq!(counter_value(&snapshotter, RELAY_MAX_ATTEMPTS_EXCEEDED_COUNTER, "counted"), Some(1));
+    }
+
+    fn nonce_gap_listener_setup(
+        handle: Handle,
+        nonces: Vec<u64>,
+        relayed: Arc<Mutex<Vec<u64>>>,
+    ) -> Listener<String, MockFetcher, SimpleCheckpoint, InMemoryCheckpointRepository<SimpleCheckpoint>, u64> {
+        let mut relayer = MockRelayer::new();
+        relayer.expect_destination_id().returning(|| "test".to_string());
+        relayer.expect_relay().returning(move |request| {
+            relayed.lock().unwrap().push(request.nonce);
+            Box::pin(futures::future::ready(Ok(())))
+        });
+        let relay = Relay::Single(Arc::new(Box::new(relayer)));
+
+        let mut fetcher = MockFetcher::new();
+        fetcher.expect_get_last_finalized_block_num().returning(|| Ok(Some(0)));
+        fetcher.expect_get_block_pay_in_events().with(eq(0)).returning(move |_| {
+            Ok(nonces
+                .iter()
+                .enumerate()
+                .map(|(id, nonce)| PayIn::new(id as u64, None, 0, *nonce, [0; 32], Recipient::Raw(vec![])))
+                .collect())
+        });
+
+        let (_tx, rx) = tokio::sync::oneshot::channel();
+        Listener::new("nonce_gap", handle, fetcher, relay, rx, InMemoryCheckpointRepository::new(None), 0, 0, 2)
+            .unwrap()
+            .with_catch_up_only(true)
+    }
+
+    #[tokio::test]
+    pub async fn sync_should_report_deposit_nonce_gaps() {
+        let relayed = Arc::new(Mutex::new(vec![]));
+        let recorder = DebuggingRecorder::new();
+        let snapshotter = recorder.snapshotter();
+        let mut listener = metrics::with_local_recorder(&recorder, || {
+            nonce_gap_listener_setup(Handle::current(), vec![100, 101, 103, 102, 106], relayed.clone())
+        });
+
+        let handle = thread::spawn(move || listener.sync());
+        assert!(handle.join().unwrap().is_ok());
+
+        // gaps are reported, events are relayed anyway
+        assert_eq!(*relayed.lock().unwrap(), vec![100, 101, 103, 102, 106]);
+        assert_eq!(counter_value(&snapshotter, DEPOSIT_NONCE_GAP_COUNTER, "nonce_gap"), Some(2));
+    }
+
+    #[tokio::test]
+    pub async fn sync_should_halt_on_deposit_nonce_gap_if_configured() {
+        let relayed = Arc::new(Mutex::new(vec![]));
+        let nonce_tracker = Arc::new(NonceTracker::in_memory());
+        let mut listener = nonce_gap_listener_setup(Handle::current(), vec![100, 102], relayed.clone())
+            .with_nonce_tracker(nonce_tracker.clone())
+            .with_halt_on_nonce_gap(true);
+        let status = listener.status();
+
+        let handle = thread::spawn(move || listener.sync());
+        assert!(handle.join().unwrap().is_err());
+
+        assert_eq!(*relayed.lock().unwrap(), vec![100]);
+        assert_eq!(status.snapshot().last_error, Some("Deposit nonce gap 101..=101 to destination test".to_string()));
+        // gapped nonce is not recorded, so the gap is detected again after restart
+        assert_eq!(nonce_tracker.check(&[], "test", 102), NonceCheck::Gap { from: 101, to: 101 });
+    }
+
+    #[tokio::test]
+    pub async fn sync_should_skip_events_below_start_nonce() {
+        let relayed = Arc::new(Mutex::new(vec![]));
+        let relayed_clone = relayed.clone();
+        let mut relayer = MockRelayer::new();
+        relayer.expect_destination_id().returning(|| "test".to_string());
+        relayer.expect_relay().returning(move |request| {
+            relayed_clone.lock().unwrap().push(request.nonce);
+            Box::pin(futures::future::ready(Ok(())))
+        });
+        let relay = Relay::Single(Arc::new(Box::new(relayer)));
+
+        // start nonce falls in the middle of the block
+        let mut fetcher = MockFetcher::new();
+        fetcher.expect_get_last_finalized_block_num().returning(|| Ok(Some(0)));
+        fetcher.expect_get_block_pay_in_events().with(eq(0)).returning(|_| {
+            Ok((0..4)
+                .map(|id| PayIn::new(id, None, 0, id + 5, [0; 32], Recipient::Raw(vec![])))
+                .collect())
+        });
+
+        let (_tx, rx) = tokio::sync::oneshot::channel();
+        let saved = Arc::new(Mutex::new(vec![]));
+        let checkpoint_repository = RecordingCheckpointRepository { saved: saved.clone() };
+        let mut listener = Listener::new(
+            "test",
+            Handle::current(),
+            fetcher,
+            relay,
+            rx,
+            checkpoint_repository,
+            0,
+            0,
+            RELAY_MAX_ATTEMPTS,
+        )
+        .unwrap()
+        .with_catch_up_only(true)
+        .with_start_nonce(Some(7));
+
+        let handle = thread::spawn(move || listener.sync());
+        assert!(handle.join().unwrap().is_ok());
+
+        assert_eq!(*relayed.lock().unwrap(), vec![7, 8]);
+        // skipped events are checkpointed as well
+        assert_eq!(saved.lock().unwrap()[..4], [0, 1, 2, 3]);
+    }
+
+    #[tokio::test]
+    pub async fn sync_should_retry_relaying_in_case_of_relaying_watch_error() {
+        let handle = Handle::current();
+
+        let mut relayer = MockRelayer::new();
+        relayer.expect_destination_id().returning(|| "test".to_string());
+
+        relayer
+            .expect_relay()
+            .withf(|request| request.nonce == 0)
+            .times(1)
+            .returning(|_| Box::pin(futures::future::ready(Ok(()))));
+
+        relayer
+            .expect_relay()
+            .withf(|request| request.nonce == 1)
+            .times(RELAY_MAX_ATTEMPTS as usize)
+            .returning(|_| Box::pin(futures::future::ready(Err(RelayError::WatchError))));
+
+        let relay = Relay::Single(Arc::new(Box::new(relayer)));
+
+        let mut fetcher = MockFetcher::new();
+        fetcher.expect_get_last_finalized_block_num().times(1).returning(|| Ok(Some(3)));
+        fetcher.expect_get_block_pay_in_events().with(eq(0)).times(1).returning(|_| {
+            Ok(vec![
+                PayIn::new(0, None, 0, 0, [0; 32], Recipient::Raw(vec![])),
+                PayIn::new(1, None, 0, 1, [0; 32], Recipient::Raw(vec![])),
+            ])
+        });
+
+        let (_, rx) = tokio::sync::oneshot::channel();
+
+        let checkpoint_repository: InMemoryCheckpointRepository<SimpleCheckpoint> =
             InMemoryCheckpointRepository::new(None);
 
         let mut listener =
@@ -661,7 +1831,8 @@ pub mod tests {
     #[tokio::test]
     pub async fn sync_should_retry_in_case_of_events_fetch_error() {
         let handle = Handle::current();
-        let relayer = MockRelayer::new();
+        let mut relayer = MockRelayer::new();
+        relayer.expect_destination_id().returning(|| "test".to_string());
         let relay = Relay::Single(Arc::new(Box::new(relayer)));
 
         let mut fetcher = MockFetcher::new();
@@ -694,4 +1865,1312 @@ pub mod tests {
 
         handle.join().unwrap();
     }
+
+    #[tokio::test]
+    pub async fn sync_should_not_fetch_nor_relay_while_paused() {
+        let handle = Handle::current();
+
+        let relayed = Arc::new(AtomicUsize::new(0));
+        let relayed_clone = relayed.clone();
+        let mut relayer = MockRelayer::new();
+        relayer.expect_destination_id().returning(|| "test".to_string());
+        relayer
+            .expect_relay()
+            .withf(|request| request.nonce == 2)
+            .times(1)
+            .returning(move |_| {
+                relayed_clone.fetch_add(1, atomic::Ordering::SeqCst);
+                Box::pin(futures::future::ready(Ok(())))
+            });
+        let relay = Relay::Single(Arc::new(Box::new(relayer)));
+
+        let mut fetcher = MockFetcher::new();
+        fetcher.expect_get_last_finalized_block_num().returning(|| Ok(Some(2)));
+        fetcher
+            .expect_get_block_pay_in_events()
+            .with(eq(2))
+            .times(1)
+            .returning(|_| Ok(vec![PayIn::new(2, None, 0, 2, [0; 32], Recipient::Raw(vec![]))]));
+
+        let (tx, rx) = tokio::sync::oneshot::channel();
+
+        let checkpoint_repository: InMemoryCheckpointRepository<SimpleCheckpoint> =
+            InMemoryCheckpointRepository::new(Some(SimpleCheckpoint { block_num: 1 }));
+
+        let control = Arc::new(ListenerControl::in_memory());
+        control.pause().unwrap();
+
+        let mut listener =
+            Listener::new("test", handle, fetcher, relay, rx, checkpoint_repository, 0, 0, RELAY_MAX_ATTEMPTS)
+                .unwrap()
+                .with_control(control.clone());
+
+        let handle = thread::spawn(move || {
+            let result = listener.sync();
+            assert!(result.is_ok());
+        });
+
+        // give a paused listener some time to poll finalized block
+        thread::sleep(std::time::Duration::from_secs(3));
+        assert_eq!(relayed.load(atomic::Ordering::SeqCst), 0);
+
+        control.resume().unwrap();
+        thread::sleep(std::time::Duration::from_secs(3));
+        assert_eq!(relayed.load(atomic::Ordering::SeqCst), 1);
+
+        // stop listener
+        tx.send(()).unwrap();
+
+        handle.join().unwrap();
+    }
+
+    #[tokio::test]
+    pub async fn sync_should_not_advance_checkpoint_while_paused() {
+        let mut relayer = MockRelayer::new();
+        relayer.expect_destination_id().returning(|| "test".to_string());
+        let relay = Relay::Single(Arc::new(Box::new(relayer)));
+
+        let mut fetcher = MockFetcher::new();
+        fetcher.expect_get_last_finalized_block_num().returning(|| Ok(Some(3)));
+        fetcher.expect_get_block_pay_in_events().returning(|_| Ok(vec![]));
+
+        let (tx, rx) = tokio::sync::oneshot::channel();
+        let saved = Arc::new(Mutex::new(vec![]));
+        let checkpoint_repository = RecordingCheckpointRepository { saved: saved.clone() };
+
+        let control = Arc::new(ListenerControl::in_memory());
+        control.pause().unwrap();
+
+        let mut listener = Listener::new(
+            "test",
+            Handle::current(),
+            fetcher,
+            relay,
+            rx,
+            checkpoint_repository,
+            0,
+            0,
+            RELAY_MAX_ATTEMPTS,
+        )
+        .unwrap()
+        .with_control(control.clone());
+
+        let handle = thread::spawn(move || {
+            let result = listener.sync();
+            assert!(result.is_ok());
+        });
+
+        thread::sleep(std::time::Duration::from_secs(2));
+        assert!(saved.lock().unwrap().is_empty());
+
+        control.resume().unwrap();
+        thread::sleep(std::time::Duration::from_secs(3));
+        assert_eq!(saved.lock().unwrap().last(), Some(&3));
+
+        // stop listener
+        tx.send(()).unwrap();
+
+        handle.join().unwrap();
+    }
+
+    struct RecordingCheckpointRepository {
+        saved: Arc<Mutex<Vec<u64>>>,
+    }
+
+    impl CheckpointRepository<SimpleCheckpoint> for RecordingCheckpointRepository {
+        fn get(&self) -> Result<Option<SimpleCheckpoint>, ()> {
+            Ok(self
+                .saved
+                .lock()
+                .unwrap()
+                .last()
+                .map(|block_num| SimpleCheckpoint { block_num: *block_num }))
+        }
+
+        fn save(&mut self, checkpoint: SimpleCheckpoint) -> Result<(), ()> {
+            self.saved.lock().unwrap().push(checkpoint.block_num);
+            Ok(())
+        }
+    }
+
+    // ignores saves, like a repository returning stale value
+    struct StaleCheckpointRepository {
+        stale: u64,
+    }
+
+    impl CheckpointRepository<SimpleCheckpoint> for StaleCheckpointRepository {
+        fn get(&self) -> Result<Option<SimpleCheckpoint>, ()> {
+            Ok(Some(SimpleCheckpoint { block_num: self.stale }))
+        }
+
+        fn save(&mut self, _checkpoint: SimpleCheckpoint) -> Result<(), ()> {
+            Ok(())
+        }
+    }
+
+    #[tokio::test]
+    pub async fn sync_should_count_stored_checkpoint_regression() {
+        let mut relayer = MockRelayer::new();
+        relayer.expect_destination_id().returning(|| "test".to_string());
+        relayer
+            .expect_relay()
+            .times(2)
+            .returning(|_| Box::pin(futures::future::ready(Ok(()))));
+        let relay = Relay::Single(Arc::new(Box::new(relayer)));
+
+        let mut fetcher = MockFetcher::new();
+        fetcher.expect_get_last_finalized_block_num().returning(|| Ok(Some(1)));
+        fetcher.expect_get_block_pay_in_events().returning(|block_num| match block_num {
+            1 => Ok(vec![
+                PayIn::new(5, None, 100, 1, [0; 32], vec![2; 20].into()),
+                PayIn::new(6, None, 100, 2, [0; 32], vec![2; 20].into()),
+            ]),
+            _ => Ok(vec![]),
+        });
+
+        let (_tx, rx) = tokio::sync::oneshot::channel();
+        let recorder = DebuggingRecorder::new();
+        let snapshotter = recorder.snapshotter();
+        let mut listener = metrics::with_local_recorder(&recorder, || {
+            Listener::new(
+                "stale",
+                Handle::current(),
+                fetcher,
+                relay,
+                rx,
+                StaleCheckpointRepository { stale: 0 },
+                0,
+                0,
+                RELAY_MAX_ATTEMPTS,
+            )
+            .unwrap()
+            .with_catch_up_only(true)
+        });
+
+        // freshly created listener saves checkpoint of the first event, then reads the stale one behind it
+        let handle = thread::spawn(move || {
+            assert!(listener.sync().is_ok());
+        });
+        handle.join().unwrap();
+
+        assert_eq!(counter_value(&snapshotter, CHECKPOINT_REGRESSION_COUNTER, "stale"), Some(1));
+    }
+
+    #[tokio::test]
+    pub async fn sync_should_count_stored_checkpoint_regression_of_blocks_without_events() {
+        let mut relayer = MockRelayer::new();
+        relayer.expect_destination_id().returning(|| "test".to_string());
+        relayer.expect_relay().never();
+        let relay = Relay::Single(Arc::new(Box::new(relayer)));
+
+        let mut fetcher = MockFetcher::new();
+        fetcher.expect_get_last_finalized_block_num().returning(|| Ok(Some(3)));
+        fetcher.expect_get_block_pay_in_events().returning(|_| Ok(vec![]));
+
+        let (_tx, rx) = tokio::sync::oneshot::channel();
+        let recorder = DebuggingRecorder::new();
+        let snapshotter = recorder.snapshotter();
+        let mut listener = metrics::with_local_recorder(&recorder, || {
+            Listener::new(
+                "stale_empty",
+                Handle::current(),
+                fetcher,
+                relay,
+                rx,
+                StaleCheckpointRepository { stale: 0 },
+                0,
+                0,
+                RELAY_MAX_ATTEMPTS,
+            )
+            .unwrap()
+            .with_catch_up_only(true)
+        });
+
+        // checkpoint of block 1 is saved, then the stale one is read before syncing blocks 2 and 3
+        let handle = thread::spawn(move || {
+            assert!(listener.sync().is_ok());
+        });
+        handle.join().unwrap();
+
+        assert_eq!(counter_value(&snapshotter, CHECKPOINT_REGRESSION_COUNTER, "stale_empty"), Some(2));
+    }
+
+    #[tokio::test]
+    pub async fn sync_should_resume_from_stored_checkpoint_ahead_of_start_block() {
+        let mut relayer = MockRelayer::new();
+        relayer.expect_destination_id().returning(|| "test".to_string());
+        relayer
+            .expect_relay()
+            .withf(|request| request.nonce == 4)
+            .times(1)
+            .returning(|_| Box::pin(futures::future::ready(Ok(()))));
+        let relay = Relay::Single(Arc::new(Box::new(relayer)));
+
+        let mut fetcher = MockFetcher::new();
+        fetcher.expect_get_last_finalized_block_num().returning(|| Ok(Some(4)));
+        fetcher
+            .expect_get_block_pay_in_events()
+            .with(eq(4))
+            .times(1)
+            .returning(|_| Ok(vec![PayIn::new(4, None, 100, 4, [0; 32], vec![2; 20].into())]));
+
+        let (_tx, rx) = tokio::sync::oneshot::channel();
+        let recorder = DebuggingRecorder::new();
+        let snapshotter = recorder.snapshotter();
+        let mut listener = metrics::with_local_recorder(&recorder, || {
+            Listener::new(
+                "ahead",
+                Handle::current(),
+                fetcher,
+                relay,
+                rx,
+                InMemoryCheckpointRepository::new(Some(SimpleCheckpoint { block_num: 3 })),
+                1,
+                0,
+                RELAY_MAX_ATTEMPTS,
+            )
+            .unwrap()
+            .with_catch_up_only(true)
+        });
+
+        let handle = thread::spawn(move || listener.sync());
+        assert!(handle.join().unwrap().is_ok());
+
+        // configured start block is ignored, sync doesn't move behind the stored checkpoint
+        assert_eq!(counter_value(&snapshotter, CHECKPOINT_REGRESSION_COUNTER, "ahead"), Some(0));
+    }
+
+    #[derive(Default)]
+    struct RecordingSleeper {
+        slept: Mutex<Vec<Duration>>,
+    }
+
+    #[async_trait]
+    impl Sleeper for RecordingSleeper {
+        async fn sleep(&self, duration: Duration) {
+            self.slept.lock().unwrap().push(duration);
+        }
+    }
+
+    #[tokio::test]
+    pub async fn sync_should_wait_for_paused_destination_without_counting_attempts() {
+        let paused_attempts = RELAY_MAX_ATTEMPTS as usize + 2;
+        let relayed = Arc::new(AtomicUsize::new(0));
+        let relayed_clone = relayed.clone();
+        let mut relayer = MockRelayer::new();
+        relayer.expect_destination_id().returning(|| "test".to_string());
+        relayer.expect_relay().times(paused_attempts + 1).returning(move |_| {
+            let result = if relayed_clone.fetch_add(1, atomic::Ordering::SeqCst) < paused_attempts {
+                Err(RelayError::Paused)
+            } else {
+                Ok(())
+            };
+            Box::pin(futures::future::ready(result))
+        });
+        let relay = Relay::Single(Arc::new(Box::new(relayer)));
+
+        let mut fetcher = MockFetcher::new();
+        fetcher.expect_get_last_finalized_block_num().returning(|| Ok(Some(0)));
+        fetcher
+            .expect_get_block_pay_in_events()
+            .returning(|_| Ok(vec![PayIn::new(0, None, 0, 1, [0; 32], Recipient::Raw(vec![]))]));
+
+        let (_tx, rx) = tokio::sync::oneshot::channel();
+        let sleeper = Arc::new(RecordingSleeper::default());
+        let mut listener = Listener::new(
+            "test",
+            Handle::current(),
+            fetcher,
+            relay,
+            rx,
+            InMemoryCheckpointRepository::<SimpleCheckpoint>::new(None),
+            0,
+            0,
+            RELAY_MAX_ATTEMPTS,
+        )
+        .unwrap()
+        .with_catch_up_only(true)
+        .with_sleeper(sleeper.clone());
+
+        let handle = thread::spawn(move || listener.sync());
+        assert!(handle.join().unwrap().is_ok());
+
+        assert_eq!(relayed.load(atomic::Ordering::SeqCst), paused_attempts + 1);
+        let slept = sleeper.slept.lock().unwrap().clone();
+        assert_eq!(slept.iter().filter(|duration| **duration == PAUSED_RETRY_INTERVAL).count(), paused_attempts);
+    }
+
+    // syncs blocks 0 to 3, the first two are synced while catching up
+    fn sync_with_poll_intervals(
+        handle: Handle,
+        poll_interval: Duration,
+        fast_sync_interval: Duration,
+    ) -> Vec<Duration> {
+        let mut relayer = MockRelayer::new();
+        relayer.expect_destination_id().returning(|| "test".to_string());
+        let relay = Relay::Single(Arc::new(Box::new(relayer)));
+
+        let mut fetcher = MockFetcher::new();
+        fetcher.expect_get_last_finalized_block_num().returning(|| Ok(Some(3)));
+        fetcher.expect_get_block_pay_in_events().returning(|_| Ok(vec![]));
+
+        let (_tx, rx) = tokio::sync::oneshot::channel();
+        let sleeper = Arc::new(RecordingSleeper::default());
+        let mut listener = Listener::new(
+            "test",
+            handle,
+            fetcher,
+            relay,
+            rx,
+            InMemoryCheckpointRepository::<SimpleCheckpoint>::new(None),
+            0,
+            0,
+            2,
+        )
+        .unwrap()
+        .with_catch_up_only(true)
+        .with_poll_intervals(poll_interval, fast_sync_interval)
+        .with_sleeper(sleeper.clone());
+
+        let handle = thread::spawn(move || listener.sync());
+        assert!(handle.join().unwrap().is_ok());
+        let slept = sleeper.slept.lock().unwrap().clone();
+        slept
+    }
+
+    #[tokio::test]
+    pub async fn sync_should_wait_configured_poll_interval() {
+        let poll_interval = Duration::from_millis(250);
+
+        let slept = sync_with_poll_intervals(Handle::current(), poll_interval, Duration::ZERO);
+
+        // catching up doesn't wait by default
+        assert_eq!(slept, vec![poll_interval, poll_interval]);
+    }
+
+    #[tokio::test]
+    pub async fn sync_should_wait_configured_fast_sync_interval_while_catching_up() {
+        let poll_interval = Duration::from_secs(12);
+        let fast_sync_interval = Duration::from_millis(100);
+
+        let slept = sync_with_poll_intervals(Handle::current(), poll_interval, fast_sync_interval);
+
+        assert_eq!(slept, vec![fast_sync_interval, fast_sync_interval, poll_interval, poll_interval]);
+    }
+
+    fn sync_with_checkpoint_batching(
+        handle: Handle,
+        relay: Relay<String>,
+        fetcher: MockFetcher,
+        checkpoint_batching: CheckpointBatching,
+    ) -> Vec<u64> {
+        let (tx, rx) = tokio::sync::oneshot::channel();
+        let saved = Arc::new(Mutex::new(vec![]));
+        let checkpoint_repository = RecordingCheckpointRepository { saved: saved.clone() };
+
+        let mut listener =
+            Listener::new("test", handle, fetcher, relay, rx, checkpoint_repository, 0, 0, RELAY_MAX_ATTEMPTS)
+                .unwrap()
+                .with_checkpoint_batching(checkpoint_batching);
+
+        let handle = thread::spawn(move || {
+            let result = listener.sync();
+            assert!(result.is_ok());
+        });
+
+        // give a listener some time to sync all finalized blocks
+        thread::sleep(std::time::Duration::from_secs(5));
+
+        // stop listener
+        tx.send(()).unwrap();
+
+        handle.join().unwrap();
+        let saved = saved.lock().unwrap().clone();
+        saved
+    }
+
+    #[tokio::test]
+    pub async fn sync_should_return_once_caught_up_in_catch_up_only_mode() {
+        let mut relayer = MockRelayer::new();
+        relayer.expect_destination_id().returning(|| "test".to_string());
+        let relay = Relay::Single(Arc::new(Box::new(relayer)));
+
+        let mut fetcher = MockFetcher::new();
+        fetcher.expect_get_last_finalized_block_num().returning(|| Ok(Some(2)));
+        for block_num in 0..=2 {
+            fetcher
+                .expect_get_block_pay_in_events()
+                .with(eq(block_num))
+                .times(1)
+                .returning(|_| Ok(vec![]));
+        }
+
+        let (_tx, rx) = tokio::sync::oneshot::channel();
+        let saved = Arc::new(Mutex::new(vec![]));
+        let checkpoint_repository = RecordingCheckpointRepository { saved: saved.clone() };
+
+        let mut listener = Listener::new(
+            "test",
+            Handle::current(),
+            fetcher,
+            relay,
+            rx,
+            checkpoint_repository,
+            0,
+            0,
+            RELAY_MAX_ATTEMPTS,
+        )
+        .unwrap()
+        .with_checkpoint_batching(CheckpointBatching { max_blocks: 100, max_interval_secs: 3600 })
+        .with_catch_up_only(true);
+
+        // returns without stop signal
+        let handle = thread::spawn(move || listener.sync());
+        assert!(handle.join().unwrap().is_ok());
+
+        // pending checkpoint of the last synced block is flushed
+        assert_eq!(*saved.lock().unwrap(), vec![2]);
+    }
+
+    #[tokio::test]
+    pub async fn sync_should_save_event_checkpoints_immediately() {
+        let mut relayer = MockRelayer::new();
+        relayer.expect_destination_id().returning(|| "test".to_string());
+        relayer
+            .expect_relay()
+            .times(1)
+            .returning(|_| Box::pin(futures::future::ready(Ok(()))));
+        let relay = Relay::Single(Arc::new(Box::new(relayer)));
+
+        let mut fetcher = MockFetcher::new();
+        fetcher.expect_get_last_finalized_block_num().returning(|| Ok(Some(5)));
+        fetcher.expect_get_block_pay_in_events().returning(|block_num| match block_num {
+            2 => Ok(vec![PayIn::new(2, None, 0, 0, [0; 32], Recipient::Raw(vec![]))]),
+            _ => Ok(vec![]),
+        });
+
+        let saved = sync_with_checkpoint_batching(
+            Handle::current(),
+            relay,
+            fetcher,
+            CheckpointBatching { max_blocks: 100, max_interval_secs: 3600 },
+        );
+
+        // event is persisted immediately, its block is batched and last synced block is flushed on stop
+        assert_eq!(saved, vec![2, 5]);
+    }
+
+    #[tokio::test]
+    pub async fn sync_should_resume_after_crash_with_unsaved_block_checkpoints() {
+        let fetcher = || {
+            let mut fetcher = MockFetcher::new();
+            fetcher.expect_get_last_finalized_block_num().returning(|| Ok(Some(5)));
+            fetcher.expect_get_block_pay_in_events().returning(|block_num| match block_num {
+                2 => Ok(vec![PayIn::new(2, None, 0, 0, [0; 32], Recipient::Raw(vec![]))]),
+                4 => Ok(vec![PayIn::new(4, None, 0, 1, [0; 32], Recipient::Raw(vec![]))]),
+                _ => Ok(vec![]),
+            });
+            fetcher
+        };
+        let saved = Arc::new(Mutex::new(vec![]));
+        let batching = CheckpointBatching { max_blocks: 100, max_interval_secs: 3600 };
+
+        // relaying of the second event fails unexpectedly, listener exits without flushing checkpoints
+        let mut relayer = MockRelayer::new();
+        relayer.expect_destination_id().returning(|| "test".to_string());
+        relayer
+            .expect_relay()
+            .withf(|request| request.nonce == 0)
+            .times(1)
+            .returning(|_| Box::pin(futures::future::ready(Ok(()))));
+        relayer
+            .expect_relay()
+            .withf(|request| request.nonce == 1)
+            .times(1)
+            .returning(|_| Box::pin(futures::future::ready(Err(RelayError::Other))));
+        let (_tx, rx) = tokio::sync::oneshot::channel();
+        let mut listener = Listener::new(
+            "test",
+            Handle::current(),
+            fetcher(),
+            Relay::Single(Arc::new(Box::new(relayer))),
+            rx,
+            RecordingCheckpointRepository { saved: saved.clone() },
+            0,
+            0,
+            RELAY_MAX_ATTEMPTS,
+        )
+        .unwrap()
+        .with_checkpoint_batching(batching);
+        assert!(thread::spawn(move || listener.sync()).join().unwrap().is_err());
+        assert_eq!(*saved.lock().unwrap(), vec![2]);
+
+        // restarted listener relays only the event which was not relayed before
+        let mut relayer = MockRelayer::new();
+        relayer.expect_destination_id().returning(|| "test".to_string());
+        relayer
+            .expect_relay()
+            .withf(|request| request.nonce == 1)
+            .times(1)
+            .returning(|_| Box::pin(futures::future::ready(Ok(()))));
+        let (_tx, rx) = tokio::sync::oneshot::channel();
+        let mut listener = Listener::new(
+            "test",
+            Handle::current(),
+            fetcher(),
+            Relay::Single(Arc::new(Box::new(relayer))),
+            rx,
+            RecordingCheckpointRepository { saved: saved.clone() },
+            0,
+            0,
+            RELAY_MAX_ATTEMPTS,
+        )
+        .unwrap()
+        .with_checkpoint_batching(batching)
+        .with_catch_up_only(true);
+        assert!(thread::spawn(move || listener.sync()).join().unwrap().is_ok());
+        assert_eq!(*saved.lock().unwrap(), vec![2, 4, 5]);
+    }
+
+    #[tokio::test]
+    pub async fn sync_should_resume_from_saved_checkpoint_after_clean_restart() {
+        let fetcher = |finalized_block: u64| {
+            let mut fetcher = MockFetcher::new();
+            fetcher
+                .expect_get_last_finalized_block_num()
+                .returning(move || Ok(Some(finalized_block)));
+            fetcher.expect_get_block_pay_in_events().returning(|block_num| match block_num {
+                2 => Ok(vec![PayIn::new(2, None, 0, 0, [0; 32], Recipient::Raw(vec![]))]),
+                4 => Ok(vec![PayIn::new(4, None, 0, 1, [0; 32], Recipient::Raw(vec![]))]),
+                _ => Ok(vec![]),
+            });
+            fetcher
+        };
+        let saved = Arc::new(Mutex::new(vec![]));
+
+        // listener is stopped once it caught up with the finalized head
+        let mut relayer = MockRelayer::new();
+        relayer.expect_destination_id().returning(|| "test".to_string());
+        relayer
+            .expect_relay()
+            .withf(|request| request.nonce == 0)
+            .times(1)
+            .returning(|_| Box::pin(futures::future::ready(Ok(()))));
+        let (tx, rx) = tokio::sync::oneshot::channel();
+        let mut listener = Listener::new(
+            "test",
+            Handle::current(),
+            fetcher(3),
+            Relay::Single(Arc::new(Box::new(relayer))),
+            rx,
+            RecordingCheckpointRepository { saved: saved.clone() },
+            0,
+            0,
+            RELAY_MAX_ATTEMPTS,
+        )
+        .unwrap()
+        .with_poll_intervals(Duration::from_millis(10), Duration::ZERO);
+        let status = listener.status();
+        let handle = thread::spawn(move || listener.sync());
+        while status.snapshot().synced_block != Some(3) {
+            thread::sleep(Duration::from_millis(10));
+        }
+        tx.send(()).unwrap();
+        assert!(handle.join().unwrap().is_ok());
+        assert_eq!(saved.lock().unwrap().last(), Some(&3));
+
+        // restarted listener continues after the saved checkpoint and relays only new events
+        let mut relayer = MockRelayer::new();
+        relayer.expect_destination_id().returning(|| "test".to_string());
+        relayer
+            .expect_relay()
+            .withf(|request| request.nonce == 1)
+            .times(1)
+            .returning(|_| Box::pin(futures::future::ready(Ok(()))));
+        let (_tx, rx) = tokio::sync::oneshot::channel();
+        let mut listener = Listener::new(
+            "test",
+            Handle::current(),
+            fetcher(5),
+            Relay::Single(Arc::new(Box::new(relayer))),
+            rx,
+            RecordingCheckpointRepository { saved: saved.clone() },
+            0,
+            0,
+            RELAY_MAX_ATTEMPTS,
+        )
+        .unwrap()
+        .with_poll_intervals(Duration::from_millis(10), Duration::ZERO)
+        .with_catch_up_only(true);
+        assert!(thread::spawn(move || listener.sync()).join().unwrap().is_ok());
+        assert_eq!(saved.lock().unwrap().last(), Some(&5));
+    }
+
+    #[tokio::test]
+    pub async fn sync_should_save_checkpoints_of_empty_blocks_periodically() {
+        let mut relayer = MockRelayer::new();
+        relayer.expect_destination_id().returning(|| "test".to_string());
+        let relay = Relay::Single(Arc::new(Box::new(relayer)));
+
+        let mut fetcher = MockFetcher::new();
+        fetcher.expect_get_last_finalized_block_num().returning(|| Ok(Some(5)));
+        fetcher.expect_get_block_pay_in_events().returning(|_| Ok(vec![]));
+
+        let saved = sync_with_checkpoint_batching(
+            Handle::current(),
+            relay,
+            fetcher,
+            CheckpointBatching { max_blocks: 2, max_interval_secs: 3600 },
+        );
+
+        assert_eq!(saved, vec![1, 3, 5]);
+    }
+
+    #[allow(clippy::type_complexity)]
+    fn failing_listener_setup(
+        handle: Handle,
+        relayed: Arc<AtomicUsize>,
+    ) -> (
+        Listener<String, MockFetcher, SimpleCheckpoint, InMemoryCheckpointRepository<SimpleCheckpoint>, u64>,
+        Arc<DeadLetterLog>,
+        tokio::sync::oneshot::Sender<()>,
+    ) {
+        let mut relayer = MockRelayer::new();
+        relayer.expect_destination_id().returning(|| "test".to_string());
+        relayer
+            .expect_relay()
+            .withf(|request| request.nonce == 1)
+            .times(2)
+            .returning(|_| Box::pin(futures::future::ready(Err(RelayError::WatchError))));
+        relayer.expect_relay().withf(|request| request.nonce == 2).returning(move |_| {
+            relayed.fetch_add(1, atomic::Ordering::SeqCst);
+            Box::pin(futures::future::ready(Ok(())))
+        });
+        let relay = Relay::Single(Arc::new(Box::new(relayer)));
+
+        let mut fetcher = MockFetcher::new();
+        fetcher.expect_get_last_finalized_block_num().returning(|| Ok(Some(1)));
+        fetcher.expect_get_block_pay_in_events().returning(|block_num| match block_num {
+            0 => Ok(vec![
+                PayIn::new(0, Some("02".to_string()), 100, 1, [1; 32], vec![2; 20].into()).with_source_ref(vec![7; 32]),
+                PayIn::new(1, Some("02".to_string()), 100, 2, [1; 32], vec![2; 20].into()),
+            ]),
+            _ => Ok(vec![]),
+        });
+
+        let (tx, rx) = tokio::sync::oneshot::channel();
+        let dead_letter_log = Arc::new(DeadLetterLog::in_memory());
+
+        let listener =
+            Listener::new("test", handle, fetcher, relay, rx, InMemoryCheckpointRepository::new(None), 0, 0, 2)
+                .unwrap()
+                .with_dead_letter_log(dead_letter_log.clone());
+        (listener, dead_letter_log, tx)
+    }
+
+    #[tokio::test]
+    pub async fn sync_should_write_dead_letter_record_when_relaying_attempts_exhausted() {
+        let relayed = Arc::new(AtomicUsize::new(0));
+        let (mut listener, dead_letter_log, _tx) = failing_listener_setup(Handle::current(), relayed.clone());
+        let status = listener.status();
+
+        let handle = thread::spawn(move || {
+            let result = listener.sync();
+            assert!(result.is_err());
+        });
+        handle.join().unwrap();
+
+        let records = dead_letter_log.records().unwrap();
+        assert_eq!(records.len(), 1);
+        assert_eq!(records[0].listener_id, "test");
+        assert_eq!(records[0].event_id, "0");
+        assert_eq!(records[0].destination_id, Some("02".to_string()));
+        assert_eq!(records[0].nonce, 1);
+        assert_eq!(records[0].amount, 100);
+        assert_eq!(records[0].resource_id, hex::encode([1; 32]));
+        assert_eq!(records[0].reason, "Exceeded maximum number of relaying attempts");
+        assert_eq!(records[0].source_ref, hex::encode([7; 32]));
+        assert_eq!(status.snapshot().last_error_source_ref, Some(format!("0x{}", hex::encode([7; 32]))));
+        assert_eq!(relayed.load(atomic::Ordering::SeqCst), 0);
+    }
+
+    #[derive(Default)]
+    struct RecordingWebhookClient {
+        payloads: Mutex<Vec<String>>,
+    }
+
+    #[async_trait]
+    impl WebhookClient for RecordingWebhookClient {
+        async fn post(&self, _url: &str, payload: &str) -> Result<(), ()> {
+            self.payloads.lock().unwrap().push(payload.to_string());
+            Ok(())
+        }
+    }
+
+    #[tokio::test]
+    pub async fn sync_should_notify_dead_letter_record_and_halt() {
+        let relayed = Arc::new(AtomicUsize::new(0));
+        let (listener, _dead_letter_log, _tx) = failing_listener_setup(Handle::current(), relayed);
+        let client = Arc::new(RecordingWebhookClient::default());
+        let config = NotificationsConfig {
+            webhook_url: "https://hooks.example.com".to_string(),
+            min_severity: Severity::Warning,
+            timeout_ms: 1000,
+        };
+        let mut listener = listener.with_notifier(Arc::new(Notifier::new(&config, client.clone())));
+
+        let handle = thread::spawn(move || {
+            let result = listener.sync();
+            assert!(result.is_err());
+        });
+        handle.join().unwrap();
+        // let the notifier deliver queued notifications
+        tokio::time::sleep(Duration::from_millis(100)).await;
+
+        let payloads = client.payloads.lock().unwrap().clone();
+        assert_eq!(payloads.len(), 2);
+        assert!(payloads[0].contains(r#""event_type":"dead_letter""#));
+        assert!(payloads[0].contains(r#""nonce":1"#));
+        assert!(payloads[1].contains(r#""event_type":"listener_halted""#));
+        assert!(payloads[1].contains(r#""listener_id":"test""#));
+    }
+
+    #[tokio::test]
+    pub async fn sync_should_skip_failed_event_if_configured() {
+        let relayed = Arc::new(AtomicUsize::new(0));
+        let (listener, dead_letter_log, tx) = failing_listener_setup(Handle::current(), relayed.clone());
+        let mut listener = listener.with_skip_failed_events(true);
+
+        let handle = thread::spawn(move || {
+            let result = listener.sync();
+            assert!(result.is_ok());
+        });
+
+        // give a listener some time to relay the next event
+        thread::sleep(std::time::Duration::from_secs(2));
+
+        // stop listener
+        tx.send(()).unwrap();
+
+        handle.join().unwrap();
+
+        assert_eq!(dead_letter_log.records().unwrap().len(), 1);
+        assert_eq!(relayed.load(atomic::Ordering::SeqCst), 1);
+    }
+
+    struct ZeroRecipientValidator;
+
+    impl Validator for ZeroRecipientValidator {
+        fn validate(&self, _amount: u128, _resource_id: &[u8; 32], data: &[u8]) -> Result<(), ValidationError> {
+            if data.iter().all(|b| *b == 0) {
+                return Err(ValidationError::ZeroRecipient);
+            }
+            Ok(())
+        }
+    }
+
+    #[tokio::test]
+    pub async fn sync_should_write_dead_letter_record_of_invalid_event_and_continue() {
+        let relayed = Arc::new(AtomicUsize::new(0));
+        let relayed_clone = relayed.clone();
+        let mut relayer = MockRelayer::new();
+        relayer.expect_destination_id().returning(|| "test".to_string());
+        relayer.expect_relay().withf(|request| request.nonce == 1).never();
+        relayer
+            .expect_relay()
+            .withf(|request| request.nonce == 2)
+            .times(1)
+            .returning(move |_| {
+                relayed_clone.fetch_add(1, atomic::Ordering::SeqCst);
+                Box::pin(futures::future::ready(Ok(())))
+            });
+        let relay = Relay::Single(Arc::new(Box::new(relayer)));
+
+        let mut fetcher = MockFetcher::new();
+        fetcher.expect_get_last_finalized_block_num().returning(|| Ok(Some(1)));
+        fetcher.expect_get_block_pay_in_events().returning(|block_num| match block_num {
+            0 => Ok(vec![
+                PayIn::new(0, Some("02".to_string()), 100, 1, [1; 32], vec![0; 20].into()),
+                PayIn::new(1, Some("02".to_string()), 100, 2, [1; 32], vec![2; 20].into()),
+            ]),
+            _ => Ok(vec![]),
+        });
+
+        let (tx, rx) = tokio::sync::oneshot::channel();
+        let dead_letter_log = Arc::new(DeadLetterLog::in_memory());
+        let mut validators: HashMap<String, Arc<dyn Validator>> = HashMap::new();
+        validators.insert("test".to_string(), Arc::new(ZeroRecipientValidator));
+
+        let mut listener = Listener::new(
+            "test",
+            Handle::current(),
+            fetcher,
+            relay,
+            rx,
+            InMemoryCheckpointRepository::<SimpleCheckpoint>::new(None),
+            0,
+            0,
+            2,
+        )
+        .unwrap()
+        .with_dead_letter_log(dead_letter_log.clone())
+        .with_validators(validators);
+
+        let handle = thread::spawn(move || {
+            let result = listener.sync();
+            assert!(result.is_ok());
+        });
+
+        // give a listener some time to relay the next event
+        thread::sleep(std::time::Duration::from_secs(2));
+
+        // stop listener
+        tx.send(()).unwrap();
+
+        handle.join().unwrap();
+
+        let records = dead_letter_log.records().unwrap();
+        assert_eq!(records.len(), 1);
+        assert_eq!(records[0].nonce, 1);
+        assert_eq!(records[0].reason, "Recipient is zero address");
+        assert_eq!(relayed.load(atomic::Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    #[allow(clippy::type_complexity)]
+    pub async fn sync_should_fail_over_to_next_relayer_on_transport_error() {
+        let relayed = Arc::new(AtomicUsize::new(0));
+        let relayed_clone = relayed.clone();
+
+        let mut failing_relayer = MockRelayer::new();
+        failing_relayer.expect_destination_id().returning(|| "02".to_string());
+        failing_relayer
+            .expect_relay()
+            .times(1)
+            .returning(|_| Box::pin(futures::future::ready(Err(RelayError::TransportError))));
+
+        let mut backup_relayer = MockRelayer::new();
+        backup_relayer.expect_destination_id().returning(|| "02".to_string());
+        backup_relayer.expect_relay().times(1).returning(move |_| {
+            relayed_clone.fetch_add(1, atomic::Ordering::SeqCst);
+            Box::pin(futures::future::ready(Ok(())))
+        });
+
+        let mut relayers: HashMap<String, Vec<Arc<Box<dyn Relayer<String>>>>> = HashMap::new();
+        relayers
+            .insert("02".to_string(), vec![Arc::new(Box::new(failing_relayer)), Arc::new(Box::new(backup_relayer))]);
+        let relay = Relay::Multi(relayers);
+
+        let mut fetcher = MockFetcher::new();
+        fetcher.expect_get_last_finalized_block_num().returning(|| Ok(Some(1)));
+        fetcher.expect_get_block_pay_in_events().returning(|block_num| match block_num {
+            0 => Ok(vec![PayIn::new(0, Some("02".to_string()), 100, 1, [1; 32], vec![2; 20].into())]),
+            _ => Ok(vec![]),
+        });
+
+        let (tx, rx) = tokio::sync::oneshot::channel();
+
+        let mut listener = Listener::new(
+            "test",
+            Handle::current(),
+            fetcher,
+            relay,
+            rx,
+            InMemoryCheckpointRepository::<SimpleCheckpoint>::new(None),
+            0,
+            0,
+            2,
+        )
+        .unwrap();
+
+        let handle = thread::spawn(move || {
+            let result = listener.sync();
+            assert!(result.is_ok());
+        });
+
+        // give a listener some time to relay the event
+        thread::sleep(std::time::Duration::from_secs(2));
+
+        // stop listener
+        tx.send(()).unwrap();
+
+        handle.join().unwrap();
+
+        assert_eq!(relayed.load(atomic::Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    pub async fn sync_should_stop_without_advancing_checkpoint_if_amount_above_maximum() {
+        let mut relayer = MockRelayer::new();
+        relayer.expect_destination_id().returning(|| "test".to_string());
+        relayer
+            .expect_relay()
+            .times(1)
+            .returning(|_| Box::pin(futures::future::ready(Err(RelayError::AboveMaxAmount))));
+        let relay = Relay::Single(Arc::new(Box::new(relayer)));
+        let mut fetcher = MockFetcher::new();
+        fetcher.expect_get_last_finalized_block_num().returning(|| Ok(Some(3)));
+        fetcher
+            .expect_get_block_pay_in_events()
+            .with(eq(2))
+            .times(1)
+            .returning(|_| Ok(vec![PayIn::new(2, None, 0, 0, [0; 32], Recipient::Raw(vec![]))]));
+
+        let (_tx, rx) = tokio::sync::oneshot::channel();
+
+        let checkpoint_repository: InMemoryCheckpointRepository<SimpleCheckpoint> =
+            InMemoryCheckpointRepository::new(Some(SimpleCheckpoint { block_num: 1 }));
+
+        let mut listener = Listener::new(
+            "test",
+            Handle::current(),
+            fetcher,
+            relay,
+            rx,
+            checkpoint_repository,
+            0,
+            0,
+            RELAY_MAX_ATTEMPTS,
+        )
+        .unwrap();
+
+        let handle = thread::spawn(move || {
+            let result = listener.sync();
+            assert!(result.is_err());
+            assert_eq!(listener.checkpoint_repository.get().unwrap(), Some(SimpleCheckpoint { block_num: 1 }));
+        });
+        handle.join().unwrap();
+    }
+
+    // hangs on the first attempt to fetch events, as unresponsive node keeping connection open would
+    struct HangingFetcher {
+        calls: Arc<AtomicUsize>,
+    }
+
+    #[async_trait]
+    impl LastFinalizedBlockNumFetcher for HangingFetcher {
+        async fn get_last_finalized_block_num(&mut self) -> Result<Option<u64>, ()> {
+            Ok(Some(1))
+        }
+    }
+
+    #[async_trait]
+    impl BlockPayInEventsFetcher<u64, String> for HangingFetcher {
+        async fn get_block_pay_in_events(&mut self, block_num: u64) -> Result<Vec<PayIn<u64, String>>, ()> {
+            if self.calls.fetch_add(1, atomic::Ordering::SeqCst) == 0 {
+                std::future::pending::<()>().await;
+            }
+            match block_num {
+                0 => Ok(vec![PayIn::new(0, None, 100, 1, [1; 32], vec![2; 20].into())]),
+                _ => Ok(vec![]),
+            }
+        }
+    }
+
+    impl ReorgDetector for HangingFetcher {}
+
+    #[tokio::test(flavor = "multi_thread")]
+    pub async fn sync_should_continue_after_fetch_timeout() {
+        let relayed = Arc::new(AtomicUsize::new(0));
+        let relayed_clone = relayed.clone();
+        let mut relayer = MockRelayer::new();
+        relayer.expect_destination_id().returning(|| "test".to_string());
+        relayer.expect_relay().times(1).returning(move |_| {
+            relayed_clone.fetch_add(1, atomic::Ordering::SeqCst);
+            Box::pin(futures::future::ready(Ok(())))
+        });
+        let relay = Relay::Single(Arc::new(Box::new(relayer)));
+        let calls = Arc::new(AtomicUsize::new(0));
+        let fetcher = HangingFetcher { calls: calls.clone() };
+
+        let (tx, rx) = tokio::sync::oneshot::channel();
+
+        let mut listener: Listener<String, _, SimpleCheckpoint, _, u64> = Listener::new(
+            "test",
+            Handle::current(),
+            fetcher,
+            relay,
+            rx,
+            InMemoryCheckpointRepository::new(None),
+            0,
+            0,
+            RELAY_MAX_ATTEMPTS,
+        )
+        .unwrap()
+        .with_fetch_timeout(std::time::Duration::from_millis(100))
+        .with_poll_intervals(std::time::Duration::from_millis(100), std::time::Duration::ZERO);
+
+        let handle = thread::spawn(move || {
+            let result = listener.sync();
+            assert!(result.is_ok());
+        });
+
+        // give a listener some time to time out and fetch again
+        thread::sleep(std::time::Duration::from_secs(3));
+
+        // stop listener
+        tx.send(()).unwrap();
+
+        handle.join().unwrap();
+
+        assert!(calls.load(atomic::Ordering::SeqCst) >= 2);
+        assert_eq!(relayed.load(atomic::Ordering::SeqCst), 1);
+    }
+
+    fn relayed_blocks_fetcher() -> MockFetcher {
+        let mut fetcher = MockFetcher::new();
+        fetcher.expect_get_last_finalized_block_num().returning(|| Ok(Some(3)));
+        fetcher.expect_get_block_pay_in_events().returning(|block_num| {
+            Ok(vec![PayIn::new(block_num, None, 0, block_num, [0; 32], Recipient::Raw(vec![]))])
+        });
+        fetcher
+    }
+
+    // time is paused, so waits of the listener task and the test advance the clock without waiting
+    #[tokio::test(start_paused = true)]
+    pub async fn sync_async_should_start_syncing_from_last_saved_log() {
+        let mut relayer = MockRelayer::new();
+        relayer.expect_destination_id().returning(|| "test".to_string());
+        relayer
+            .expect_relay()
+            .withf(|request| request.nonce >= 2)
+            .times(2)
+            .returning(|_| Box::pin(futures::future::ready(Ok(()))));
+        let relay = Relay::Single(Arc::new(Box::new(relayer)));
+
+        let (tx, rx) = tokio::sync::oneshot::channel();
+        let checkpoint_repository: InMemoryCheckpointRepository<SimpleCheckpoint> =
+            InMemoryCheckpointRepository::new(Some(SimpleCheckpoint { block_num: 1 }));
+        let mut listener = Listener::new(
+            "test",
+            Handle::current(),
+            relayed_blocks_fetcher(),
+            relay,
+            rx,
+            checkpoint_repository,
+            0,
+            0,
+            RELAY_MAX_ATTEMPTS,
+        )
+        .unwrap();
+
+        let task = tokio::spawn(async move { listener.sync_async().await });
+
+        // give a listener some time to sync all finalized blocks
+        tokio::time::sleep(Duration::from_secs(5)).await;
+
+        // stop listener
+        tx.send(()).unwrap();
+
+        assert!(task.await.unwrap().is_ok());
+    }
+
+    #[tokio::test(start_paused = true)]
+    pub async fn sync_async_should_stop_in_case_of_relaying_other_error() {
+        let mut relayer = MockRelayer::new();
+        relayer.expect_destination_id().returning(|| "test".to_string());
+        relayer
+            .expect_relay()
+            .times(1)
+            .returning(|_| Box::pin(futures::future::ready(Err(RelayError::Other))));
+        let relay = Relay::Single(Arc::new(Box::new(relayer)));
+
+        let (_tx, rx) = tokio::sync::oneshot::channel();
+        let checkpoint_repository: InMemoryCheckpointRepository<SimpleCheckpoint> =
+            InMemoryCheckpointRepository::new(None);
+        let mut listener = Listener::new(
+            "test",
+            Handle::current(),
+            relayed_blocks_fetcher(),
+            relay,
+            rx,
+            checkpoint_repository,
+            0,
+            0,
+            RELAY_MAX_ATTEMPTS,
+        )
+        .unwrap();
+
+        let result = tokio::spawn(async move { listener.sync_async().await }).await.unwrap();
+        assert!(result.is_err());
+    }
+
+    #[tokio::test(start_paused = true)]
+    pub async fn sync_async_should_retry_relaying_in_case_of_relaying_transport_error() {
+        let mut relayer = MockRelayer::new();
+        relayer.expect_destination_id().returning(|| "test".to_string());
+        relayer
+            .expect_relay()
+            .times(RELAY_MAX_ATTEMPTS as usize)
+            .returning(|_| Box::pin(futures::future::ready(Err(RelayError::TransportError))));
+        let relay = Relay::Single(Arc::new(Box::new(relayer)));
+
+        let (_tx, rx) = tokio::sync::oneshot::channel();
+        let checkpoint_repository: InMemoryCheckpointRepository<SimpleCheckpoint> =
+            InMemoryCheckpointRepository::new(None);
+        let mut listener = Listener::new(
+            "test",
+            Handle::current(),
+            relayed_blocks_fetcher(),
+            relay,
+            rx,
+            checkpoint_repository,
+            0,
+            0,
+            RELAY_MAX_ATTEMPTS,
+        )
+        .unwrap();
+
+        let started = tokio::time::Instant::now();
+        let result = tokio::spawn(async move { listener.sync_async().await }).await.unwrap();
+
+        // it will error because of retry attempts exceed
+        assert!(result.is_err());
+        // every failed attempt waits before the next one
+        assert!(started.elapsed() >= Duration::from_secs(RELAY_MAX_ATTEMPTS as u64));
+    }
+
+    #[tokio::test(start_paused = true)]
+    pub async fn sync_async_should_continue_after_fetch_timeout() {
+        let mut relayer = MockRelayer::new();
+        relayer.expect_destination_id().returning(|| "test".to_string());
+        relayer
+            .expect_relay()
+            .times(1)
+            .returning(|_| Box::pin(futures::future::ready(Ok(()))));
+        let relay = Relay::Single(Arc::new(Box::new(relayer)));
+        let calls = Arc::new(AtomicUsize::new(0));
+        let fetcher = HangingFetcher { calls: calls.clone() };
+
+        let (_tx, rx) = tokio::sync::oneshot::channel();
+        let mut listener: Listener<String, _, SimpleCheckpoint, _, u64> = Listener::new(
+            "test",
+            Handle::current(),
+            fetcher,
+            relay,
+            rx,
+            InMemoryCheckpointRepository::new(None),
+            0,
+            0,
+            RELAY_MAX_ATTEMPTS,
+        )
+        .unwrap()
+        .with_fetch_timeout(Duration::from_millis(100))
+        .with_catch_up_only(true);
+
+        let result = tokio::spawn(async move { listener.sync_async().await }).await.unwrap();
+
+        assert!(result.is_ok());
+        assert!(calls.load(atomic::Ordering::SeqCst) >= 2);
+    }
+
+    #[tokio::test(start_paused = true)]
+    pub async fn sync_async_should_wait_configured_poll_interval() {
+        let mut relayer = MockRelayer::new();
+        relayer.expect_destination_id().returning(|| "test".to_string());
+        let relay = Relay::Single(Arc::new(Box::new(relayer)));
+
+        let mut fetcher = MockFetcher::new();
+        fetcher.expect_get_last_finalized_block_num().returning(|| Ok(Some(3)));
+        fetcher.expect_get_block_pay_in_events().returning(|_| Ok(vec![]));
+
+        let (_tx, rx) = tokio::sync::oneshot::channel();
+        let sleeper = Arc::new(RecordingSleeper::default());
+        let mut listener = Listener::new(
+            "test",
+            Handle::current(),
+            fetcher,
+            relay,
+            rx,
+            InMemoryCheckpointRepository::<SimpleCheckpoint>::new(None),
+            0,
+            0,
+            2,
+        )
+        .unwrap()
+        .with_catch_up_only(true)
+        .with_poll_intervals(Duration::from_millis(250), Duration::ZERO)
+        .with_sleeper(sleeper.clone());
+
+        let result = tokio::spawn(async move { listener.sync_async().await }).await.unwrap();
+
+        assert!(result.is_ok());
+        assert_eq!(*sleeper.slept.lock().unwrap(), vec![Duration::from_millis(250), Duration::from_millis(250)]);
+    }
+
+    // listeners share the single thread of the runtime instead of blocking a thread each
+    #[tokio::test]
+    pub async fn sync_async_listeners_should_share_current_thread_runtime() {
+        let mut tasks = vec![];
+        for id in ["first", "second", "third"] {
+            let mut relayer = MockRelayer::new();
+            relayer.expect_destination_id().returning(|| "test".to_string());
+            relayer
+                .expect_relay()
+                .times(4)
+                .returning(|_| Box::pin(futures::future::ready(Ok(()))));
+            let relay = Relay::Single(Arc::new(Box::new(relayer)));
+
+            let (_tx, rx) = tokio::sync::oneshot::channel();
+            let checkpoint_repository: InMemoryCheckpointRepository<SimpleCheckpoint> =
+                InMemoryCheckpointRepository::new(None);
+            let mut listener = Listener::new(
+                id,
+                Handle::current(),
+                relayed_blocks_fetcher(),
+                relay,
+                rx,
+                checkpoint_repository,
+                0,
+                0,
+                RELAY_MAX_ATTEMPTS,
+            )
+            .unwrap()
+            .with_catch_up_only(true)
+            .with_poll_intervals(Duration::from_millis(10), Duration::ZERO);
+            tasks.push(tokio::spawn(async move { listener.sync_async().await }));
+        }
+
+        for task in tasks {
+            assert!(task.await.unwrap().is_ok());
+        }
+    }
+
+    #[test]
+    pub fn second_instance_fails_to_prepare_listener_context() {
+        std::fs::create_dir_all("data").unwrap();
+        let config: BridgeConfig = serde_json::from_value(serde_json::json!({
+            "listeners": [{
+                "listener_type": "ethereum",
+                "id": "second_instance_listener",
+                "relayers": [],
+                "chain_id": 1,
+                "config": null
+            }],
+            "relayers": []
+        }))
+        .unwrap();
+        let prepare = || {
+            prepare_listener_context::<serde_json::Value>(
+                &config,
+                "ethereum",
+                &HashMap::new(),
+                &HashMap::new(),
+                &HashMap::new(),
+                false,
+                &Arc::new(Notifier::disabled()),
+            )
+        };
+
+        let first_instance = prepare().unwrap();
+        assert!(prepare().is_err());
+
+        drop(first_instance);
+        assert!(prepare().is_ok());
+
+        for file in ["control.bin", "nonces.bin", "failed.jsonl"] {
+            let _ = std::fs::remove_file(format!("data/second_instance_listener_{}", file));
+        }
+        std::fs::remove_file("data/second_instance_listener.lock").unwrap();
+    }
 }
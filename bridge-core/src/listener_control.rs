@@ -0,0 +1,90 @@
+// Copyright 2020-2024 Trust Computing GmbH.
+// This file is part of Litentry.
+//
+// Litentry is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// Litentry is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with Litentry.  If not, see <https://www.gnu.org/licenses/>.
+
+use std::fs;
+use std::io::ErrorKind;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+/// Runtime control state of a `Listener`, shared between the listener and management interfaces.
+/// Pause state is persisted in `file_name` (if set) so a restarted listener doesn't silently resume.
+pub struct ListenerControl {
+    paused: AtomicBool,
+    file_name: Option<String>,
+}
+
+impl ListenerControl {
+    pub fn in_memory() -> Self {
+        Self { paused: AtomicBool::new(false), file_name: None }
+    }
+
+    #[allow(clippy::result_unit_err)]
+    pub fn new(file_name: &str) -> Result<Self, ()> {
+        let paused = match fs::read(file_name) {
+            Ok(content) => content.first() == Some(&1),
+            Err(e) if e.kind() == ErrorKind::NotFound => false,
+            Err(e) => {
+                log::error!("Could not read listener control state from {}: {:?}", file_name, e);
+                return Err(());
+            },
+        };
+        Ok(Self { paused: AtomicBool::new(paused), file_name: Some(file_name.to_string()) })
+    }
+
+    pub fn is_paused(&self) -> bool {
+        self.paused.load(Ordering::SeqCst)
+    }
+
+    #[allow(clippy::result_unit_err)]
+    pub fn pause(&self) -> Result<(), ()> {
+        self.set_paused(true)
+    }
+
+    #[allow(clippy::result_unit_err)]
+    pub fn resume(&self) -> Result<(), ()> {
+        self.set_paused(false)
+    }
+
+    fn set_paused(&self, paused: bool) -> Result<(), ()> {
+        if let Some(ref file_name) = self.file_name {
+            fs::write(file_name, [paused as u8]).map_err(|e| {
+                log::error!("Could not persist listener control state to {}: {:?}", file_name, e);
+            })?;
+        }
+        self.paused.store(paused, Ordering::SeqCst);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn pause_state_survives_restart() {
+        let file_name = "pause_state_survives_restart.bin";
+        let _ = fs::remove_file(file_name);
+
+        let control = ListenerControl::new(file_name).unwrap();
+        assert!(!control.is_paused());
+        control.pause().unwrap();
+        assert!(ListenerControl::new(file_name).unwrap().is_paused());
+
+        control.resume().unwrap();
+        assert!(!ListenerControl::new(file_name).unwrap().is_paused());
+
+        fs::remove_file(file_name).unwrap();
+    }
+}
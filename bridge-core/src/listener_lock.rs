@@ -0,0 +1,130 @@
+// Copyright 2020-2024 Trust Computing GmbH.
+// This file is part of Litentry.
+//
+// Litentry is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// Litentry is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with Litentry.  If not, see <https://www.gnu.org/licenses/>.
+
+use std::fs::{File, OpenOptions, TryLockError};
+use std::io::{Read, Seek, SeekFrom, Write};
+use std::process;
+
+/// Advisory lock of listener's data files, so two processes sharing a data directory don't advance
+/// the same checkpoint. Holder's pid is written into the lock file to name it in errors. The lock is
+/// released by the OS when the holding process exits, so it doesn't outlive a crashed process.
+pub struct ListenerLock {
+    file: File,
+    file_name: String,
+}
+
+impl ListenerLock {
+    /// Fails if the lock is held by another process (or another `ListenerLock` of this one).
+    #[allow(clippy::result_unit_err)]
+    pub fn acquire(file_name: &str) -> Result<Self, ()> {
+        let mut file = OpenOptions::new()
+            .read(true)
+            .write(true)
+            .create(true)
+            .truncate(false)
+            .open(file_name)
+            .map_err(|e| {
+                log::error!("Could not open lock file {}: {:?}", file_name, e);
+            })?;
+        let holder = read_pid(&mut file);
+        match file.try_lock() {
+            Ok(()) => {},
+            Err(TryLockError::WouldBlock) => {
+                match holder {
+                    Some(pid) => log::error!("Lock file {} is held by process {}, is another worker running with the same data directory?", file_name, pid),
+                    None => log::error!("Lock file {} is held by another process, is another worker running with the same data directory?", file_name),
+                }
+                return Err(());
+            },
+            Err(TryLockError::Error(e)) => {
+                log::error!("Could not lock {}: {:?}", file_name, e);
+                return Err(());
+            },
+        }
+        if let Some(pid) = holder {
+            log::warn!(
+                "Lock file {} was left by process {} which is no longer running, taking it over",
+                file_name,
+                pid
+            );
+        }
+        write_pid(&mut file, Some(process::id())).map_err(|e| {
+            log::error!("Could not write pid to lock file {}: {:?}", file_name, e);
+        })?;
+        Ok(Self { file, file_name: file_name.to_string() })
+    }
+}
+
+impl Drop for ListenerLock {
+    fn drop(&mut self) {
+        // cleared pid marks graceful release, so it isn't reported as left by a crashed process
+        if let Err(e) = write_pid(&mut self.file, None) {
+            log::error!("Could not clear pid in lock file {}: {:?}", self.file_name, e);
+        }
+        if let Err(e) = self.file.unlock() {
+            log::error!("Could not unlock {}: {:?}", self.file_name, e);
+        }
+    }
+}
+
+fn read_pid(file: &mut File) -> Option<u32> {
+    let mut content = String::new();
+    file.read_to_string(&mut content).ok()?;
+    content.trim().parse().ok()
+}
+
+fn write_pid(file: &mut File, pid: Option<u32>) -> std::io::Result<()> {
+    file.set_len(0)?;
+    file.seek(SeekFrom::Start(0))?;
+    if let Some(pid) = pid {
+        write!(file, "{}", pid)?;
+    }
+    file.sync_all()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    #[test]
+    fn second_instance_fails_to_acquire_lock_until_released() {
+        let file_name = "second_instance_fails_to_acquire_lock_until_released.lock";
+        let _ = fs::remove_file(file_name);
+
+        let lock = ListenerLock::acquire(file_name).unwrap();
+        assert_eq!(fs::read_to_string(file_name).unwrap(), process::id().to_string());
+        assert!(ListenerLock::acquire(file_name).is_err());
+
+        drop(lock);
+        assert_eq!(fs::read_to_string(file_name).unwrap(), "");
+        assert!(ListenerLock::acquire(file_name).is_ok());
+
+        fs::remove_file(file_name).unwrap();
+    }
+
+    #[test]
+    fn lock_left_by_crashed_process_is_taken_over() {
+        let file_name = "lock_left_by_crashed_process_is_taken_over.lock";
+        // pid is left behind, but the lock itself was released by the OS
+        fs::write(file_name, "4194305").unwrap();
+
+        let _lock = ListenerLock::acquire(file_name).unwrap();
+        assert_eq!(fs::read_to_string(file_name).unwrap(), process::id().to_string());
+
+        fs::remove_file(file_name).unwrap();
+    }
+}
@@ -0,0 +1,103 @@
+// Copyright 2020-2024 Trust Computing GmbH.
+// This file is part of Litentry.
+//
+// Litentry is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// Litentry is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with Litentry.  If not, see <https://www.gnu.org/licenses/>.
+
+use serde::{Deserialize, Serialize};
+use std::sync::Mutex;
+
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct ListenerStatusSnapshot {
+    pub id: String,
+    pub chain: u32,
+    pub synced_block: Option<u64>,
+    pub finalized_block: Option<u64>,
+    // number of finalized blocks waiting for sync
+    pub lag: Option<u64>,
+    pub last_error: Option<String>,
+    // hex encoded source reference of the event which caused `last_error`
+    pub last_error_source_ref: Option<String>,
+}
+
+/// Sync progress of a `Listener`, published for management interfaces.
+pub struct ListenerStatus {
+    state: Mutex<ListenerStatusSnapshot>,
+}
+
+impl ListenerStatus {
+    pub fn new(id: &str, chain: u32) -> Self {
+        Self { state: Mutex::new(ListenerStatusSnapshot { id: id.to_string(), chain, ..Default::default() }) }
+    }
+
+    pub fn set_synced_block(&self, block_num: u64) {
+        self.state.lock().unwrap().synced_block = Some(block_num);
+    }
+
+    pub fn set_finalized_block(&self, block_num: u64) {
+        self.state.lock().unwrap().finalized_block = Some(block_num);
+    }
+
+    pub fn set_last_error(&self, error: &str) {
+        let mut state = self.state.lock().unwrap();
+        state.last_error = Some(error.to_string());
+        state.last_error_source_ref = None;
+    }
+
+    pub fn set_last_event_error(&self, error: &str, source_ref: &str) {
+        let mut state = self.state.lock().unwrap();
+        state.last_error = Some(error.to_string());
+        state.last_error_source_ref = Some(source_ref.to_string());
+    }
+
+    pub fn snapshot(&self) -> ListenerStatusSnapshot {
+        let mut snapshot = self.state.lock().unwrap().clone();
+        snapshot.lag = snapshot
+            .finalized_block
+            .map(|finalized_block| finalized_block.saturating_sub(snapshot.synced_block.unwrap_or(0)));
+        snapshot
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn snapshot_calculates_lag() {
+        let status = ListenerStatus::new("ethereum", 1);
+        assert_eq!(status.snapshot().lag, None);
+
+        status.set_finalized_block(10);
+        status.set_synced_block(7);
+        status.set_last_error("Could not get events");
+
+        let snapshot = status.snapshot();
+        assert_eq!(snapshot.id, "ethereum");
+        assert_eq!(snapshot.chain, 1);
+        assert_eq!(snapshot.lag, Some(3));
+        assert_eq!(snapshot.last_error, Some("Could not get events".to_string()));
+        assert_eq!(snapshot.last_error_source_ref, None);
+    }
+
+    #[test]
+    fn event_error_carries_source_ref() {
+        let status = ListenerStatus::new("ethereum", 1);
+
+        status.set_last_event_error("Event exceeds maximum amount", "0x01");
+        assert_eq!(status.snapshot().last_error_source_ref, Some("0x01".to_string()));
+
+        status.set_last_error("Could not get events");
+        assert_eq!(status.snapshot().last_error_source_ref, None);
+    }
+}
@@ -0,0 +1,167 @@
+// Copyright 2020-2024 Trust Computing GmbH.
+// This file is part of Litentry.
+//
+// Litentry is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// Litentry is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with Litentry.  If not, see <https://www.gnu.org/licenses/>.
+
+use parity_scale_codec::{Decode, Encode};
+use std::collections::HashMap;
+use std::fs;
+use std::io::ErrorKind;
+use std::sync::RwLock;
+
+pub const DEPOSIT_NONCE_GAP_COUNTER: &str = "deposit_nonce_gap_total";
+
+/// Result of comparing observed deposit nonce with the last one seen for the same source and destination.
+#[derive(Debug, PartialEq)]
+pub enum NonceCheck {
+    // no nonce seen for the destination yet
+    First,
+    Sequential,
+    // nonces `from..=to` were never seen, events were most likely missed
+    Gap { from: u64, to: u64 },
+    // nonce not above the last seen one, e.g. block reprocessed after restart or reorg
+    NotAbove { last: u64 },
+}
+
+/// Tracks the last seen deposit nonce per event source and destination of a listener to detect missed events.
+/// Bridge contract and pallet assign nonces sequentially per destination, shared by all resource ids, so resource id
+/// is not part of the key. Contracts routed as additional event sources have sequences of their own. Nonces are
+/// persisted in `file_name` (if set), so gaps spanning restarts are detected too.
+pub struct NonceTracker {
+    file_name: Option<String>,
+    last_seen: RwLock<HashMap<String, u64>>,
+}
+
+impl NonceTracker {
+    pub fn in_memory() -> Self {
+        Self { file_name: None, last_seen: RwLock::new(HashMap::new()) }
+    }
+
+    #[allow(clippy::result_unit_err)]
+    pub fn new(file_name: &str) -> Result<Self, ()> {
+        let last_seen: HashMap<String, u64> = match fs::read(file_name) {
+            Ok(content) => Vec::<(String, u64)>::decode(&mut content.as_slice())
+                .map_err(|e| log::error!("Could not decode deposit nonces: {:?}", e))?
+                .into_iter()
+                .collect(),
+            Err(e) if e.kind() == ErrorKind::NotFound => HashMap::new(),
+            Err(e) => {
+                log::error!("Could not read deposit nonces from {}: {:?}", file_name, e);
+                return Err(());
+            },
+        };
+        Ok(Self { file_name: Some(file_name.to_string()), last_seen: RwLock::new(last_seen) })
+    }
+
+    pub fn check(&self, source: &[u8], destination: &str, nonce: u64) -> NonceCheck {
+        match self.last_seen.read().unwrap().get(&key(source, destination)) {
+            None => NonceCheck::First,
+            Some(last) if nonce <= *last => NonceCheck::NotAbove { last: *last },
+            Some(last) if nonce == last + 1 => NonceCheck::Sequential,
+            Some(last) => NonceCheck::Gap { from: last + 1, to: nonce - 1 },
+        }
+    }
+
+    /// Records observed nonce. The last seen nonce never moves backwards.
+    pub fn record(&self, source: &[u8], destination: &str, nonce: u64) {
+        let mut last_seen = self.last_seen.write().unwrap();
+        let last = last_seen.entry(key(source, destination)).or_insert(nonce);
+        if nonce < *last {
+            return;
+        }
+        *last = nonce;
+
+        if let Some(ref file_name) = self.file_name {
+            let encoded = last_seen.iter().map(|(k, v)| (k.clone(), *v)).collect::<Vec<_>>().encode();
+            if let Err(e) = fs::write(file_name, encoded) {
+                log::error!("Could not persist deposit nonces to {}: {:?}", file_name, e);
+            }
+        }
+    }
+}
+
+// main source is keyed by destination only, keeping nonces persisted before sources were tracked
+fn key(source: &[u8], destination: &str) -> String {
+    if source.is_empty() {
+        destination.to_string()
+    } else {
+        format!("0x{}/{}", hex::encode(source), destination)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sequential_nonces_are_not_gaps() {
+        let tracker = NonceTracker::in_memory();
+
+        assert_eq!(tracker.check(&[], "02", 100), NonceCheck::First);
+        tracker.record(&[], "02", 100);
+        assert_eq!(tracker.check(&[], "02", 101), NonceCheck::Sequential);
+        tracker.record(&[], "02", 101);
+        // other destination is tracked separately
+        assert_eq!(tracker.check(&[], "01", 5), NonceCheck::First);
+    }
+
+    #[test]
+    fn sources_to_same_destination_are_tracked_separately() {
+        let tracker = NonceTracker::in_memory();
+        let routed = [0x97; 20];
+        tracker.record(&[], "02", 100);
+        tracker.record(&routed, "02", 5);
+
+        assert_eq!(tracker.check(&[], "02", 101), NonceCheck::Sequential);
+        assert_eq!(tracker.check(&routed, "02", 6), NonceCheck::Sequential);
+        tracker.record(&routed, "02", 6);
+        assert_eq!(tracker.check(&[], "02", 101), NonceCheck::Sequential);
+        assert_eq!(tracker.check(&routed, "02", 8), NonceCheck::Gap { from: 7, to: 7 });
+    }
+
+    #[test]
+    fn gap_reports_missing_range() {
+        let tracker = NonceTracker::in_memory();
+        tracker.record(&[], "02", 100);
+
+        assert_eq!(tracker.check(&[], "02", 102), NonceCheck::Gap { from: 101, to: 101 });
+        assert_eq!(tracker.check(&[], "02", 110), NonceCheck::Gap { from: 101, to: 109 });
+    }
+
+    #[test]
+    fn last_seen_nonce_never_moves_backwards() {
+        let tracker = NonceTracker::in_memory();
+        tracker.record(&[], "02", 100);
+        tracker.record(&[], "02", 99);
+
+        assert_eq!(tracker.check(&[], "02", 99), NonceCheck::NotAbove { last: 100 });
+        assert_eq!(tracker.check(&[], "02", 100), NonceCheck::NotAbove { last: 100 });
+        assert_eq!(tracker.check(&[], "02", 101), NonceCheck::Sequential);
+    }
+
+    #[test]
+    fn nonces_survive_restart() {
+        let file_name = "nonces_survive_restart.bin";
+        let _ = fs::remove_file(file_name);
+
+        let tracker = NonceTracker::new(file_name).unwrap();
+        tracker.record(&[], "02", 100);
+        drop(tracker);
+
+        let tracker = NonceTracker::new(file_name).unwrap();
+        assert_eq!(tracker.check(&[], "02", 102), NonceCheck::Gap { from: 101, to: 101 });
+
+        fs::remove_file(file_name).unwrap();
+    }
+}
@@ -0,0 +1,390 @@
+// Copyright 2020-2024 Trust Computing GmbH.
+// This file is part of Litentry.
+//
+// Litentry is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// Litentry is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with Litentry.  If not, see <https://www.gnu.org/licenses/>.
+
+use crate::amount_limit::unix_now;
+use async_trait::async_trait;
+use metrics::counter;
+use serde::{Deserialize, Serialize, Serializer};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::mpsc;
+
+pub const NOTIFICATIONS_DROPPED_COUNTER: &str = "notifications_dropped";
+pub const NOTIFICATIONS_FAILED_COUNTER: &str = "notifications_failed";
+
+pub const DEFAULT_NOTIFICATION_TIMEOUT: Duration = Duration::from_secs(5);
+
+// notifications waiting for delivery, new ones are dropped once it's full so notifying never blocks
+pub const NOTIFICATION_QUEUE_SIZE: usize = 64;
+pub const NOTIFICATION_MAX_ATTEMPTS: u32 = 3;
+// multiplied by the number of failed attempts
+const RETRY_BACKOFF: Duration = Duration::from_secs(1);
+
+#[derive(Clone, Copy, Debug, Deserialize, Serialize, PartialEq, Eq, PartialOrd, Ord)]
+#[serde(rename_all = "lowercase")]
+pub enum Severity {
+    Warning,
+    Critical,
+}
+
+/// Webhook (e.g. Slack or PagerDuty) pushed to when an event requires immediate operator's attention.
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
+pub struct NotificationsConfig {
+    pub webhook_url: String,
+    // less severe notifications are not sent
+    #[serde(default = "default_min_severity")]
+    pub min_severity: Severity,
+    // delivery attempt exceeding the timeout is treated as failed and retried
+    #[serde(default = "default_timeout_ms")]
+    pub timeout_ms: u64,
+}
+
+fn default_min_severity() -> Severity {
+    Severity::Warning
+}
+
+fn default_timeout_ms() -> u64 {
+    DEFAULT_NOTIFICATION_TIMEOUT.as_millis() as u64
+}
+
+#[derive(Clone, Copy, Debug, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum NotificationType {
+    DeadLetter,
+    ListenerHalted,
+    RelayerNotRegistered,
+    LowBalance,
+}
+
+impl NotificationType {
+    pub fn severity(&self) -> Severity {
+        match self {
+            Self::LowBalance => Severity::Warning,
+            Self::DeadLetter | Self::ListenerHalted | Self::RelayerNotRegistered => Severity::Critical,
+        }
+    }
+}
+
+/// Payload posted to the webhook as JSON.
+#[derive(Clone, Debug, Serialize, PartialEq)]
+pub struct Notification {
+    pub event_type: NotificationType,
+    pub severity: Severity,
+    pub listener_id: Option<String>,
+    pub relayer_id: Option<String>,
+    pub nonce: Option<u64>,
+    // serialized as string, json numbers can't safely hold u128
+    #[serde(serialize_with = "serialize_amount")]
+    pub amount: Option<u128>,
+    pub error: String,
+    pub timestamp: u64,
+}
+
+fn serialize_amount<S: Serializer>(amount: &Option<u128>, serializer: S) -> Result<S::Ok, S::Error> {
+    match amount {
+        Some(amount) => serializer.serialize_str(&amount.to_string()),
+        None => serializer.serialize_none(),
+    }
+}
+
+impl Notification {
+    pub fn new(event_type: NotificationType, error: &str) -> Self {
+        Self {
+            event_type,
+            severity: event_type.severity(),
+            listener_id: None,
+            relayer_id: None,
+            nonce: None,
+            amount: None,
+            error: error.to_string(),
+            timestamp: unix_now(),
+        }
+    }
+
+    pub fn with_listener(mut self, listener_id: &str) -> Self {
+        self.listener_id = Some(listener_id.to_string());
+        self
+    }
+
+    pub fn with_relayer(mut self, relayer_id: &str) -> Self {
+        self.relayer_id = Some(relayer_id.to_string());
+        self
+    }
+
+    pub fn with_deposit(mut self, nonce: u64, amount: u128) -> Self {
+        self.nonce = Some(nonce);
+        self.amount = Some(amount);
+        self
+    }
+}
+
+/// Used to post notification payloads to the webhook.
+#[async_trait]
+pub trait WebhookClient: Send + Sync {
+    async fn post(&self, url: &str, payload: &str) -> Result<(), ()>;
+}
+
+#[derive(Default)]
+pub struct HttpWebhookClient {
+    client: reqwest::Client,
+}
+
+#[async_trait]
+impl WebhookClient for HttpWebhookClient {
+    async fn post(&self, url: &str, payload: &str) -> Result<(), ()> {
+        let response = self
+            .client
+            .post(url)
+            .header(reqwest::header::CONTENT_TYPE, "application/json")
+            .body(payload.to_string())
+            .send()
+            .await
+            .map_err(|e| log::warn!("Could not post notification: {:?}", e))?;
+        if !response.status().is_success() {
+            log::warn!("Notification webhook responded with status {}", response.status());
+            return Err(());
+        }
+        Ok(())
+    }
+}
+
+/// Pushes notifications to the webhook from a background task. Notifying never blocks, notifications are
+/// dropped if delivery can't keep up, e.g. when the webhook is unresponsive.
+pub struct Notifier {
+    sender: Option<mpsc::Sender<Notification>>,
+    min_severity: Severity,
+}
+
+impl Default for Notifier {
+    fn default() -> Self {
+        Self::disabled()
+    }
+}
+
+impl Notifier {
+    /// Sends nothing, used when notifications are not configured
+    pub fn disabled() -> Self {
+        Self { sender: None, min_severity: Severity::Critical }
+    }
+
+    /// Spawns delivery task, must be called within tokio runtime.
+    pub fn new(config: &NotificationsConfig, client: Arc<dyn WebhookClient>) -> Self {
+        let (sender, receiver) = mpsc::channel(NOTIFICATION_QUEUE_SIZE);
+        tokio::spawn(deliver(receiver, client, config.webhook_url.clone(), Duration::from_millis(config.timeout_ms)));
+        Self { sender: Some(sender), min_severity: config.min_severity }
+    }
+
+    pub fn from_config(config: Option<&NotificationsConfig>) -> Self {
+        match config {
+            Some(config) => Self::new(config, Arc::new(HttpWebhookClient::default())),
+            None => Self::disabled(),
+        }
+    }
+
+    pub fn notify(&self, notification: Notification) {
+        let Some(ref sender) = self.sender else {
+            return;
+        };
+        if notification.severity < self.min_severity {
+            return;
+        }
+        if let Err(e) = sender.try_send(notification) {
+            log::warn!("Could not queue notification: {:?}", e);
+            counter!(NOTIFICATIONS_DROPPED_COUNTER).increment(1);
+        }
+    }
+}
+
+async fn deliver(
+    mut receiver: mpsc::Receiver<Notification>,
+    client: Arc<dyn WebhookClient>,
+    url: String,
+    timeout: Duration,
+) {
+    while let Some(notification) = receiver.recv().await {
+        let payload = match serde_json::to_string(&notification) {
+            Ok(payload) => payload,
+            Err(e) => {
+                log::error!("Could not serialize notification: {:?}", e);
+                continue;
+            },
+        };
+        for attempt in 1..=NOTIFICATION_MAX_ATTEMPTS {
+            match tokio::time::timeout(timeout, client.post(&url, &payload)).await {
+                Ok(Ok(())) => break,
+                Ok(Err(())) => log::warn!("Notification delivery attempt {} failed", attempt),
+                Err(_) => log::warn!("Notification delivery attempt {} timed out after {:?}", attempt, timeout),
+            }
+            if attempt == NOTIFICATION_MAX_ATTEMPTS {
+                log::error!("Could not deliver notification: {}", payload);
+                counter!(NOTIFICATIONS_FAILED_COUNTER).increment(1);
+            } else {
+                tokio::time::sleep(RETRY_BACKOFF * attempt).await;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Mutex;
+
+    const WEBHOOK_URL: &str = "https://hooks.example.com/notify";
+
+    fn config(min_severity: Severity) -> NotificationsConfig {
+        NotificationsConfig { webhook_url: WEBHOOK_URL.to_string(), min_severity, timeout_ms: 1000 }
+    }
+
+    #[derive(Default)]
+    struct RecordingClient {
+        posted: Mutex<Vec<(String, String)>>,
+    }
+
+    #[async_trait]
+    impl WebhookClient for RecordingClient {
+        async fn post(&self, url: &str, payload: &str) -> Result<(), ()> {
+            self.posted.lock().unwrap().push((url.to_string(), payload.to_string()));
+            Ok(())
+        }
+    }
+
+    // fails first `failures` attempts
+    struct FailingClient {
+        failures: usize,
+        attempts: AtomicUsize,
+    }
+
+    #[async_trait]
+    impl WebhookClient for FailingClient {
+        async fn post(&self, _url: &str, _payload: &str) -> Result<(), ()> {
+            if self.attempts.fetch_add(1, Ordering::SeqCst) < self.failures {
+                Err(())
+            } else {
+                Ok(())
+            }
+        }
+    }
+
+    #[derive(Default)]
+    struct HangingClient {
+        attempts: AtomicUsize,
+    }
+
+    #[async_trait]
+    impl WebhookClient for HangingClient {
+        async fn post(&self, _url: &str, _payload: &str) -> Result<(), ()> {
+            self.attempts.fetch_add(1, Ordering::SeqCst);
+            std::future::pending().await
+        }
+    }
+
+    // lets the delivery task run, time is paused so pending timeouts and backoffs elapse instantly
+    async fn settle() {
+        tokio::time::sleep(Duration::from_secs(60)).await;
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn posts_notification_payload() {
+        let client = Arc::new(RecordingClient::default());
+        let notifier = Notifier::new(&config(Severity::Warning), client.clone());
+
+        notifier.notify(
+            Notification::new(NotificationType::DeadLetter, "Exceeded maximum number of relaying attempts")
+                .with_listener("sepolia")
+                .with_deposit(5, u128::MAX),
+        );
+        settle().await;
+
+        let posted = client.posted.lock().unwrap().clone();
+        assert_eq!(posted.len(), 1);
+        assert_eq!(posted[0].0, WEBHOOK_URL);
+        let payload: serde_json::Value = serde_json::from_str(&posted[0].1).unwrap();
+        let timestamp = payload["timestamp"].as_u64().unwrap();
+        assert!(timestamp > 0);
+        assert_eq!(
+            payload,
+            serde_json::json!({
+                "event_type": "dead_letter",
+                "severity": "critical",
+                "listener_id": "sepolia",
+                "relayer_id": null,
+                "nonce": 5,
+                "amount": u128::MAX.to_string(),
+                "error": "Exceeded maximum number of relaying attempts",
+                "timestamp": timestamp,
+            })
+        );
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn notifications_below_min_severity_are_not_sent() {
+        let client = Arc::new(RecordingClient::default());
+        let notifier = Notifier::new(&config(Severity::Critical), client.clone());
+
+        notifier
+            .notify(Notification::new(NotificationType::LowBalance, "Balance is below threshold").with_relayer("r"));
+        notifier.notify(Notification::new(NotificationType::ListenerHalted, "Halted").with_listener("l"));
+        settle().await;
+
+        let posted = client.posted.lock().unwrap().clone();
+        assert_eq!(posted.len(), 1);
+        assert!(posted[0].1.contains("listener_halted"));
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn failed_delivery_is_retried() {
+        let client = Arc::new(FailingClient { failures: 2, attempts: AtomicUsize::new(0) });
+        let notifier = Notifier::new(&config(Severity::Warning), client.clone());
+
+        notifier.notify(Notification::new(NotificationType::ListenerHalted, "Halted"));
+        notifier.notify(Notification::new(NotificationType::ListenerHalted, "Halted again"));
+        settle().await;
+
+        // the first notification is delivered on the last attempt, the second one right away
+        assert_eq!(client.attempts.load(Ordering::SeqCst), NOTIFICATION_MAX_ATTEMPTS as usize + 1);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn hanging_webhook_does_not_block_notifying() {
+        let client = Arc::new(HangingClient::default());
+        let notifier = Notifier::new(&config(Severity::Warning), client.clone());
+
+        // returns right away even though nothing is delivered and the queue overflows
+        for nonce in 0..(NOTIFICATION_QUEUE_SIZE as u64 * 2) {
+            notifier.notify(Notification::new(NotificationType::DeadLetter, "Failed").with_deposit(nonce, 1));
+        }
+        settle().await;
+
+        // every attempt times out, the first notification is retried before moving on
+        assert!(client.attempts.load(Ordering::SeqCst) > 1);
+    }
+
+    #[test]
+    fn notifications_config_defaults() {
+        let config: NotificationsConfig =
+            serde_json::from_value(serde_json::json!({"webhook_url": WEBHOOK_URL})).unwrap();
+        assert_eq!(
+            config,
+            NotificationsConfig {
+                webhook_url: WEBHOOK_URL.to_string(),
+                min_severity: Severity::Warning,
+                timeout_ms: DEFAULT_NOTIFICATION_TIMEOUT.as_millis() as u64,
+            }
+        );
+    }
+}
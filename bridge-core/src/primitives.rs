@@ -0,0 +1,189 @@
+// Copyright 2020-2024 Trust Computing GmbH.
+// This file is part of Litentry.
+//
+// Litentry is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// Litentry is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with Litentry.  If not, see <https://www.gnu.org/licenses/>.
+
+const WORD_LEN: usize = 32;
+
+/// Decoded `data` of bridge `Deposit` handled by ERC20Handler.
+#[derive(Clone, Debug, PartialEq)]
+pub struct DepositData {
+    pub amount: u128,
+    // raw recipient bytes, e.g. 20 bytes EVM address or 32 bytes substrate account
+    pub recipient: Vec<u8>,
+}
+
+/// Encodes `data` of bridge `Deposit` and proposals handled by ERC20Handler:
+/// `amount(32) || recipient_len(32) || recipient(32)`, every part is 32 bytes ABI word and
+/// the recipient is right padded.
+#[allow(clippy::result_unit_err)]
+pub fn encode_deposit_data(amount: u128, recipient: &[u8]) -> Result<Vec<u8>, ()> {
+    if recipient.len() > WORD_LEN {
+        log::error!("Deposit recipient is longer than {} bytes: {}", WORD_LEN, recipient.len());
+        return Err(());
+    }
+    let mut data = vec![0; 3 * WORD_LEN];
+    data[WORD_LEN - 16..WORD_LEN].copy_from_slice(&amount.to_be_bytes());
+    data[2 * WORD_LEN - 8..2 * WORD_LEN].copy_from_slice(&(recipient.len() as u64).to_be_bytes());
+    data[2 * WORD_LEN..2 * WORD_LEN + recipient.len()].copy_from_slice(recipient);
+    Ok(data)
+}
+
+/// Decodes `data` of bridge `Deposit` handled by ERC20Handler. Recipient of `recipient_len` bytes is read
+/// right after the length word, both packed (as emitted by the solidity handler) and right padded data is accepted.
+#[allow(clippy::result_unit_err)]
+pub fn decode_deposit_data(data: &[u8]) -> Result<DepositData, ()> {
+    let amount = decode_word(data, 0).ok_or_else(|| log::error!("Deposit data too short to hold amount"))?;
+    let recipient_len =
+        decode_word(data, WORD_LEN).ok_or_else(|| log::error!("Deposit data too short to hold recipient length"))?;
+    if recipient_len > WORD_LEN as u128 {
+        log::error!("Deposit recipient is longer than {} bytes: {}", WORD_LEN, recipient_len);
+        return Err(());
+    }
+    let recipient = data
+        .get(2 * WORD_LEN..2 * WORD_LEN + recipient_len as usize)
+        .ok_or_else(|| log::error!("Deposit data too short to hold recipient of {} bytes", recipient_len))?;
+    Ok(DepositData { amount, recipient: recipient.to_vec() })
+}
+
+// reads big endian ABI word at `offset`, `None` if it's out of bounds or exceeds u128
+fn decode_word(data: &[u8], offset: usize) -> Option<u128> {
+    let word = data.get(offset..offset + WORD_LEN)?;
+    if word[..WORD_LEN - 16].iter().any(|b| *b != 0) {
+        log::error!("Deposit data word at {} exceeds u128", offset);
+        return None;
+    }
+    Some(u128::from_be_bytes(word[WORD_LEN - 16..].try_into().ok()?))
+}
+
+/// Turns `name` into a valid Prometheus metric name by replacing unsupported characters with `_`.
+/// Names starting with a digit are prefixed with `_`.
+pub fn metric_name(name: &str) -> String {
+    let mut sanitized: String = name
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() || c == '_' || c == ':' { c } else { '_' })
+        .collect();
+    if sanitized.chars().next().is_none_or(|c| c.is_ascii_digit()) {
+        sanitized.insert(0, '_');
+    }
+    sanitized
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy::primitives::{FixedBytes, U256};
+    use alloy::sol_types::{sol_data, SolType, SolValue};
+
+    type DepositData = (sol_data::Uint<256>, sol_data::Uint<256>, sol_data::FixedBytes<32>);
+
+    #[test]
+    fn encode_deposit_data_works() {
+        let amount = 100_000_000_000_000_000_000u128;
+        let recipient = [1u8; 20];
+
+        let data = encode_deposit_data(amount, &recipient).unwrap();
+
+        let (decoded_amount, recipient_len, decoded_recipient) = DepositData::abi_decode(&data, true).unwrap();
+        assert_eq!(decoded_amount, U256::from(amount));
+        assert_eq!(recipient_len, U256::from(20));
+        assert_eq!(&decoded_recipient[0..20], &recipient);
+        assert_eq!(&decoded_recipient[20..], &[0; 12]);
+
+        // listener's fetcher reads amount from the first word
+        assert_eq!(U256::abi_decode(&data[0..32], false).unwrap(), U256::from(amount));
+    }
+
+    #[test]
+    fn encode_deposit_data_matches_abi_encoding() {
+        let amount = u128::MAX;
+        let recipient = [2u8; 32];
+
+        let data = encode_deposit_data(amount, &recipient).unwrap();
+
+        let expected = (U256::from(amount), U256::from(32), FixedBytes::<32>::from(recipient)).abi_encode();
+        assert_eq!(data, expected);
+    }
+
+    #[test]
+    fn encode_deposit_data_rejects_too_long_recipient() {
+        assert_eq!(encode_deposit_data(1, &[0; 33]), Err(()));
+    }
+
+    // produced by ERC20Handler deposit of 100 tokens (18 decimals) to 20 bytes EVM address, data is packed
+    const EVM_RECIPIENT_DEPOSIT: &str = "0000000000000000000000000000000000000000000000056bc75e2d63100000\
+                                         0000000000000000000000000000000000000000000000000000000000000014\
+                                         70997970c51812dc3a010c7d01b50e0d17dc79c8";
+    // produced by ERC20Handler deposit of 1 token to 32 bytes substrate account
+    const SUBSTRATE_RECIPIENT_DEPOSIT: &str = "0000000000000000000000000000000000000000000000000de0b6b3a7640000\
+                                               0000000000000000000000000000000000000000000000000000000000000020\
+                                               d43593c715fdd31c61141abd04a99fd6822c8558854ccde39a5684e7a56da27d";
+
+    #[test]
+    fn decode_deposit_data_of_evm_recipient() {
+        let data = hex::decode(EVM_RECIPIENT_DEPOSIT).unwrap();
+
+        let decoded = decode_deposit_data(&data).unwrap();
+
+        assert_eq!(decoded.amount, 100_000_000_000_000_000_000);
+        assert_eq!(decoded.recipient, hex::decode("70997970c51812dc3a010c7d01b50e0d17dc79c8").unwrap());
+    }
+
+    #[test]
+    fn decode_deposit_data_of_substrate_recipient() {
+        let data = hex::decode(SUBSTRATE_RECIPIENT_DEPOSIT).unwrap();
+
+        let decoded = decode_deposit_data(&data).unwrap();
+
+        assert_eq!(decoded.amount, 1_000_000_000_000_000_000);
+        assert_eq!(
+            decoded.recipient,
+            hex::decode("d43593c715fdd31c61141abd04a99fd6822c8558854ccde39a5684e7a56da27d").unwrap()
+        );
+        assert_eq!(encode_deposit_data(decoded.amount, &decoded.recipient).unwrap(), data);
+    }
+
+    #[test]
+    fn decode_deposit_data_of_padded_data() {
+        for recipient in [vec![1; 20], vec![2; 32], vec![]] {
+            let data = encode_deposit_data(10, &recipient).unwrap();
+
+            assert_eq!(decode_deposit_data(&data), Ok(super::DepositData { amount: 10, recipient }));
+        }
+    }
+
+    #[test]
+    fn decode_deposit_data_rejects_malformed_data() {
+        let mut too_long_recipient = encode_deposit_data(10, &[1; 32]).unwrap();
+        too_long_recipient[2 * WORD_LEN - 1] = 33;
+        let mut amount_above_u128 = encode_deposit_data(10, &[1; 20]).unwrap();
+        amount_above_u128[WORD_LEN - 17] = 1;
+
+        // too short to hold amount
+        assert_eq!(decode_deposit_data(&[1, 2, 3]), Err(()));
+        // missing recipient length
+        assert_eq!(decode_deposit_data(&U256::from(10).abi_encode()), Err(()));
+        // recipient shorter than its length
+        assert_eq!(decode_deposit_data(&hex::decode(EVM_RECIPIENT_DEPOSIT).unwrap()[..83]), Err(()));
+        assert_eq!(decode_deposit_data(&too_long_recipient), Err(()));
+        assert_eq!(decode_deposit_data(&amount_above_u128), Err(()));
+    }
+
+    #[test]
+    fn metric_name_is_sanitized() {
+        assert_eq!(metric_name("ethereum_synced_block"), "ethereum_synced_block");
+        assert_eq!(metric_name("sepolia-1 test/x_synced_block"), "sepolia_1_test_x_synced_block");
+        assert_eq!(metric_name("0xab_eth_balance"), "_0xab_eth_balance");
+    }
+}
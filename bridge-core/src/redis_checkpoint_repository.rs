@@ -0,0 +1,289 @@
+// Copyright 2020-2024 Trust Computing GmbH.
+// This file is part of Litentry.
+//
+// Litentry is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// Litentry is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with Litentry.  If not, see <https://www.gnu.org/licenses/>.
+
+use crate::sync_checkpoint_repository::CheckpointRepository;
+use parity_scale_codec::{Decode, Encode};
+use std::fmt::Debug;
+use std::marker::PhantomData;
+use std::sync::Mutex;
+
+// concurrent saves of other instances after which saving is given up
+const MAX_SAVE_ATTEMPTS: u8 = 10;
+
+/// Redis commands used by `RedisCheckpointRepository`, implemented for `redis::Connection` with `redis` feature.
+#[allow(clippy::result_unit_err)]
+pub trait RedisConnection {
+    fn get(&mut self, key: &str) -> Result<Option<Vec<u8>>, ()>;
+    fn set(&mut self, key: &str, value: &[u8]) -> Result<(), ()>;
+    /// `WATCH`es the key and returns its value
+    fn watch_get(&mut self, key: &str) -> Result<Option<Vec<u8>>, ()>;
+    /// Sets the value in `MULTI`/`EXEC`, returns false if the watched key was modified in the meantime
+    fn set_if_unwatched(&mut self, key: &str, value: &[u8]) -> Result<bool, ()>;
+    fn unwatch(&mut self) -> Result<(), ()>;
+}
+
+#[cfg(feature = "redis")]
+impl RedisConnection for redis::Connection {
+    fn get(&mut self, key: &str) -> Result<Option<Vec<u8>>, ()> {
+        redis::cmd("GET").arg(key).query(self).map_err(|e| {
+            log::error!("Could not get {} from redis: {:?}", key, e);
+        })
+    }
+
+    fn set(&mut self, key: &str, value: &[u8]) -> Result<(), ()> {
+        redis::cmd("SET").arg(key).arg(value).query::<()>(self).map_err(|e| {
+            log::error!("Could not set {} in redis: {:?}", key, e);
+        })
+    }
+
+    fn watch_get(&mut self, key: &str) -> Result<Option<Vec<u8>>, ()> {
+        redis::cmd("WATCH").arg(key).query::<()>(self).map_err(|e| {
+            log::error!("Could not watch {} in redis: {:?}", key, e);
+        })?;
+        RedisConnection::get(self, key)
+    }
+
+    fn set_if_unwatched(&mut self, key: &str, value: &[u8]) -> Result<bool, ()> {
+        // aborted transaction replies with nil
+        let result: Option<()> = redis::pipe().atomic().set(key, value).ignore().query(self).map_err(|e| {
+            log::error!("Could not set {} in redis: {:?}", key, e);
+        })?;
+        Ok(result.is_some())
+    }
+
+    fn unwatch(&mut self) -> Result<(), ()> {
+        redis::cmd("UNWATCH").query(self).map_err(|e| {
+            log::error!("Could not unwatch redis keys: {:?}", e);
+        })
+    }
+}
+
+/// Redis based `CheckpointRepository`, shared by worker instances syncing the same listener. Checkpoint
+/// is only saved if it's ahead of the stored one, so an instance lagging behind doesn't regress progress
+/// of the others. Moving the checkpoint backwards, e.g. on reorg, requires `rewind`.
+pub struct RedisCheckpointRepository<Connection, Checkpoint> {
+    connection: Mutex<Connection>,
+    key: String,
+    _phantom: PhantomData<Checkpoint>,
+}
+
+impl<Connection, Checkpoint> RedisCheckpointRepository<Connection, Checkpoint> {
+    pub fn new(connection: Connection, key: &str) -> Self {
+        Self { connection: Mutex::new(connection), key: key.to_string(), _phantom: PhantomData }
+    }
+}
+
+#[cfg(feature = "redis")]
+impl<Checkpoint> RedisCheckpointRepository<redis::Connection, Checkpoint> {
+    /// Connects to redis at `url`, e.g. `redis://127.0.0.1:6379`
+    #[allow(clippy::result_unit_err)]
+    pub fn connect(url: &str, key: &str) -> Result<Self, ()> {
+        let connection = redis::Client::open(url)
+            .and_then(|client| client.get_connection())
+            .map_err(|e| {
+                log::error!("Could not connect to redis: {:?}", e);
+            })?;
+        Ok(Self::new(connection, key))
+    }
+}
+
+fn decode<Checkpoint: Decode>(key: &str, content: Option<Vec<u8>>) -> Result<Option<Checkpoint>, ()> {
+    content
+        .map(|content| {
+            Checkpoint::decode(&mut content.as_slice()).map_err(|e| {
+                log::error!("Could not decode checkpoint {}: {:?}", key, e);
+            })
+        })
+        .transpose()
+}
+
+impl<Connection, Checkpoint> CheckpointRepository<Checkpoint> for RedisCheckpointRepository<Connection, Checkpoint>
+where
+    Connection: RedisConnection,
+    Checkpoint: Encode + Decode + PartialOrd + Debug,
+{
+    fn get(&self) -> Result<Option<Checkpoint>, ()> {
+        let content = self.connection.lock().unwrap().get(&self.key)?;
+        decode(&self.key, content)
+    }
+
+    fn save(&mut self, checkpoint: Checkpoint) -> Result<(), ()> {
+        log::trace!("Saving checkpoint: {:?}", checkpoint);
+        let connection = self.connection.get_mut().unwrap();
+        for _ in 0..MAX_SAVE_ATTEMPTS {
+            let stored: Option<Checkpoint> = decode(&self.key, connection.watch_get(&self.key)?)?;
+            if stored.as_ref().is_some_and(|stored| *stored >= checkpoint) {
+                log::debug!("Not saving checkpoint {:?}, {:?} is already stored", checkpoint, stored);
+                return connection.unwatch();
+            }
+            if connection.set_if_unwatched(&self.key, &checkpoint.encode())? {
+                return Ok(());
+            }
+        }
+        log::error!("Could not save checkpoint {}, it kept being modified concurrently", self.key);
+        Err(())
+    }
+
+    // blocks after the checkpoint were replaced, so progress of other instances is invalid as well
+    fn rewind(&mut self, checkpoint: Checkpoint) -> Result<(), ()> {
+        log::info!("Rewinding checkpoint {} to {:?}", self.key, checkpoint);
+        self.connection.get_mut().unwrap().set(&self.key, &checkpoint.encode())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+    use std::sync::Arc;
+
+    // in-memory redis, each key has a version bumped on every write, watched version is checked on set
+    #[derive(Clone, Default)]
+    #[allow(clippy::type_complexity)]
+    struct InMemoryRedis {
+        keys: Arc<Mutex<HashMap<String, (u64, Vec<u8>)>>>,
+        // written by "another instance" right after the next `watch_get`
+        interleaved_writes: Arc<Mutex<Vec<Vec<u8>>>>,
+    }
+
+    struct InMemoryConnection {
+        redis: InMemoryRedis,
+        watched: Option<(String, u64)>,
+    }
+
+    impl InMemoryRedis {
+        fn connect(&self) -> InMemoryConnection {
+            InMemoryConnection { redis: self.clone(), watched: None }
+        }
+
+        fn write(&self, key: &str, value: Vec<u8>) {
+            let mut keys = self.keys.lock().unwrap();
+            let version = keys.get(key).map(|(version, _)| version + 1).unwrap_or_default();
+            keys.insert(key.to_string(), (version, value));
+        }
+
+        fn version(&self, key: &str) -> Option<u64> {
+            self.keys.lock().unwrap().get(key).map(|(version, _)| *version)
+        }
+    }
+
+    impl RedisConnection for InMemoryConnection {
+        fn get(&mut self, key: &str) -> Result<Option<Vec<u8>>, ()> {
+            Ok(self.redis.keys.lock().unwrap().get(key).map(|(_, value)| value.clone()))
+        }
+
+        fn set(&mut self, key: &str, value: &[u8]) -> Result<(), ()> {
+            self.redis.write(key, value.to_vec());
+            Ok(())
+        }
+
+        fn watch_get(&mut self, key: &str) -> Result<Option<Vec<u8>>, ()> {
+            self.watched = self.redis.version(key).map(|version| (key.to_string(), version));
+            let value = self.get(key);
+            if let Some(write) = self.redis.interleaved_writes.lock().unwrap().pop() {
+                self.redis.write(key, write);
+            }
+            value
+        }
+
+        fn set_if_unwatched(&mut self, key: &str, value: &[u8]) -> Result<bool, ()> {
+            let watched = self.watched.take().map(|(_, version)| version);
+            if watched != self.redis.version(key) {
+                return Ok(false);
+            }
+            self.redis.write(key, value.to_vec());
+            Ok(true)
+        }
+
+        fn unwatch(&mut self) -> Result<(), ()> {
+            self.watched = None;
+            Ok(())
+        }
+    }
+
+    fn repository(redis: &InMemoryRedis) -> RedisCheckpointRepository<InMemoryConnection, u64> {
+        RedisCheckpointRepository::new(redis.connect(), "checkpoint:sepolia")
+    }
+
+    #[test]
+    fn checkpoint_is_shared_between_instances() {
+        let redis = InMemoryRedis::default();
+        let mut first = repository(&redis);
+        let second = repository(&redis);
+        assert_eq!(second.get(), Ok(None));
+
+        first.save(10).unwrap();
+
+        assert_eq!(second.get(), Ok(Some(10)));
+    }
+
+    #[test]
+    fn lagging_instance_does_not_regress_checkpoint() {
+        let redis = InMemoryRedis::default();
+        let mut first = repository(&redis);
+        let mut second = repository(&redis);
+
+        first.save(10).unwrap();
+        second.save(5).unwrap();
+
+        assert_eq!(first.get(), Ok(Some(10)));
+        second.save(11).unwrap();
+        assert_eq!(first.get(), Ok(Some(11)));
+    }
+
+    #[test]
+    fn reorg_rewinds_checkpoint_behind_stored_one() {
+        let redis = InMemoryRedis::default();
+        let mut repository = repository(&redis);
+        repository.save(12).unwrap();
+
+        // blocks after 9 were replaced, events of canonical block 10 must not be skipped
+        repository.rewind(9).unwrap();
+        assert_eq!(repository.get(), Ok(Some(9)));
+
+        repository.save(10).unwrap();
+        assert_eq!(repository.get(), Ok(Some(10)));
+    }
+
+    #[test]
+    fn save_is_retried_after_concurrent_write() {
+        let redis = InMemoryRedis::default();
+        let mut repository = repository(&redis);
+        repository.save(1).unwrap();
+
+        // other instance saves 7 while this one compares against 1
+        redis.interleaved_writes.lock().unwrap().push(7u64.encode());
+        repository.save(5).unwrap();
+        assert_eq!(repository.get(), Ok(Some(7)));
+
+        redis.interleaved_writes.lock().unwrap().push(8u64.encode());
+        repository.save(9).unwrap();
+        assert_eq!(repository.get(), Ok(Some(9)));
+    }
+
+    #[test]
+    fn save_fails_if_key_keeps_being_modified() {
+        let redis = InMemoryRedis::default();
+        let mut repository = repository(&redis);
+        redis
+            .interleaved_writes
+            .lock()
+            .unwrap()
+            .extend((0..MAX_SAVE_ATTEMPTS as u64).map(|checkpoint| checkpoint.encode()));
+
+        assert_eq!(repository.save(100), Err(()));
+    }
+}
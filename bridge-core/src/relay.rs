@@ -15,39 +15,97 @@
 // along with Litentry.  If not, see <https://www.gnu.org/licenses/>.
 
 use async_trait::async_trait;
+use metrics::counter;
 use std::collections::HashMap;
+use std::future::Future;
 use std::sync::Arc;
+use std::time::Duration;
 
 #[cfg(test)]
 use mockall::automock;
 
+/// Waiting for transaction inclusion/finalization exceeding the timeout is treated as `RelayError::WatchError`.
+/// Must be lower than relay timeout, otherwise relaying attempt times out first.
+pub const DEFAULT_WATCH_TIMEOUT: Duration = Duration::from_secs(90);
+
+pub const RELAY_TIMEOUTS_COUNTER: &str = "relay_timeouts";
+pub const WATCH_TIMEOUTS_COUNTER: &str = "relay_watch_timeouts";
+
 /// Represents relayers assigned to `Listener` instance. For example PayIns from different smart contracts deployed on same EVM
 /// network may be relayed to different destination chains. Strictly speaking there is a correlation between event emitter and relayer.
+/// Destination may have multiple relayers, next one is tried if relaying fails due to `RelayError::TransportError`.
+#[allow(clippy::type_complexity)]
 pub enum Relay<DestinationId> {
     Single(Arc<Box<dyn Relayer<DestinationId>>>),
-    Multi(HashMap<DestinationId, Arc<Box<dyn Relayer<DestinationId>>>>),
+    Multi(HashMap<DestinationId, Vec<Arc<Box<dyn Relayer<DestinationId>>>>>),
+}
+
+/// Recipient of bridging request, typed by the fetcher since only it knows the source chain's deposit format.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Recipient {
+    Evm([u8; 20]),
+    Substrate([u8; 32]),
+    // recipient of unrecognized format, relayers reject it
+    Raw(Vec<u8>),
+}
+
+impl Recipient {
+    pub fn as_bytes(&self) -> &[u8] {
+        match self {
+            Self::Evm(address) => address,
+            Self::Substrate(account) => account,
+            Self::Raw(bytes) => bytes,
+        }
+    }
+}
+
+/// Recognizes recipient by its length, 20 bytes is an EVM address and 32 bytes a substrate account.
+impl From<Vec<u8>> for Recipient {
+    fn from(bytes: Vec<u8>) -> Self {
+        match bytes.len() {
+            20 => Self::Evm(bytes.try_into().expect("length is checked; qed")),
+            32 => Self::Substrate(bytes.try_into().expect("length is checked; qed")),
+            _ => Self::Raw(bytes),
+        }
+    }
+}
+
+/// Bridging request to be relayed to destination chain
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RelayRequest {
+    pub amount: u128,
+    pub nonce: u64,
+    pub resource_id: [u8; 32],
+    pub recipient: Recipient,
+    // todo: chain id should represent chain_type + index instead of just index
+    pub source_chain_id: u32,
+    // set for manually re-driven requests which were reviewed after exceeding amount limits
+    pub skip_amount_limits: bool,
 }
 
 /// Used to relay bridging request to destination chain
 #[async_trait]
 #[cfg_attr(test, automock)]
 pub trait Relayer<DestinationId: Send + Sync>: Send + Sync {
-    // todo: chain id should represent chain_type + index instead of just index
-    async fn relay(
-        &self,
-        amount: u128,
-        nonce: u64,
-        resource_id: &[u8; 32],
-        data: &[u8],
-        chain_id: u32,
-    ) -> Result<(), RelayError>;
+    async fn relay(&self, request: &RelayRequest) -> Result<(), RelayError>;
     fn destination_id(&self) -> DestinationId;
 }
 
+#[derive(Debug)]
 pub enum RelayError {
     TransportError,
     WatchError,
     AlreadyRelayed,
+    // relayed amount exceeds configured caps, requires manual review
+    LimitExceeded,
+    // dust not worth relaying, request is skipped
+    BelowMinAmount,
+    // implausibly large amount, request is not relayed until manually reviewed
+    AboveMaxAmount,
+    // resource id is not on relayer's allowlist, request is skipped
+    Filtered,
+    // destination bridge is paused by admin, request is retried until it's unpaused
+    Paused,
     Other,
 }
 
@@ -56,3 +114,61 @@ impl RelayError {
         matches!(self, Self::TransportError)
     }
 }
+
+/// Bounds relaying attempt, unresponsive node would block listener's sync otherwise.
+/// Timeout is treated as `RelayError::TransportError` so the attempt is retried.
+pub async fn with_relay_timeout<T>(
+    timeout: Duration,
+    future: impl Future<Output = Result<T, RelayError>>,
+) -> Result<T, RelayError> {
+    tokio::time::timeout(timeout, future).await.map_err(|_| {
+        log::error!("Relaying timed out after {:?}", timeout);
+        counter!(RELAY_TIMEOUTS_COUNTER).increment(1);
+        RelayError::TransportError
+    })?
+}
+
+/// Bounds waiting for transaction inclusion/finalization, timeout is treated as `RelayError::WatchError`.
+pub async fn with_watch_timeout<T>(
+    timeout: Duration,
+    future: impl Future<Output = Result<T, RelayError>>,
+) -> Result<T, RelayError> {
+    tokio::time::timeout(timeout, future).await.map_err(|_| {
+        log::error!("Watching transaction timed out after {:?}", timeout);
+        counter!(WATCH_TIMEOUTS_COUNTER).increment(1);
+        RelayError::WatchError
+    })?
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test(start_paused = true)]
+    async fn relay_timeout_is_transport_error() {
+        let result = with_relay_timeout(Duration::from_secs(5), std::future::pending::<Result<(), RelayError>>()).await;
+        assert!(matches!(result, Err(RelayError::TransportError)));
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn watch_timeout_is_watch_error() {
+        let result = with_watch_timeout(Duration::from_secs(5), std::future::pending::<Result<(), RelayError>>()).await;
+        assert!(matches!(result, Err(RelayError::WatchError)));
+    }
+
+    #[test]
+    fn recipient_is_recognized_by_length() {
+        assert_eq!(Recipient::from(vec![1; 20]), Recipient::Evm([1; 20]));
+        assert_eq!(Recipient::from(vec![2; 32]), Recipient::Substrate([2; 32]));
+        assert_eq!(Recipient::from(vec![3; 96]), Recipient::Raw(vec![3; 96]));
+        assert_eq!(Recipient::from(vec![]), Recipient::Raw(vec![]));
+        assert_eq!(Recipient::Substrate([2; 32]).as_bytes(), &[2; 32]);
+    }
+
+    #[tokio::test]
+    async fn result_is_returned_within_timeout() {
+        let result =
+            with_watch_timeout(Duration::from_secs(5), async { Err::<(), _>(RelayError::AlreadyRelayed) }).await;
+        assert!(matches!(result, Err(RelayError::AlreadyRelayed)));
+    }
+}
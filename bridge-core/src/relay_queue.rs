@@ -0,0 +1,198 @@
+// Copyright 2020-2024 Trust Computing GmbH.
+// This file is part of Litentry.
+//
+// Litentry is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// Litentry is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with Litentry.  If not, see <https://www.gnu.org/licenses/>.
+
+use std::collections::{BTreeMap, VecDeque};
+use std::ops::Bound;
+use std::sync::Mutex;
+use tokio::sync::oneshot;
+
+pub const DEFAULT_MAX_QUEUED_PER_SOURCE: usize = 8;
+
+/// Serializes relaying through a shared relayer, granting turns round-robin across sources (listeners
+/// identified by their chain id), so a source with a long backlog doesn't starve the others. At most
+/// `max_queued_per_source` requests of a single source wait for their turn, further ones are rejected.
+pub struct RelayQueue {
+    state: Mutex<QueueState>,
+    max_queued_per_source: usize,
+}
+
+#[derive(Default)]
+struct QueueState {
+    busy: bool,
+    waiting: BTreeMap<u32, VecDeque<oneshot::Sender<()>>>,
+    last_served: Option<u32>,
+}
+
+impl RelayQueue {
+    pub fn new(max_queued_per_source: usize) -> Self {
+        Self { state: Mutex::new(QueueState::default()), max_queued_per_source }
+    }
+
+    /// Waits for the turn of `source`, which lasts until returned `RelayTurn` is dropped. Fails if too
+    /// many requests of `source` are already waiting.
+    #[allow(clippy::result_unit_err)]
+    pub async fn turn(&self, source: u32) -> Result<RelayTurn<'_>, ()> {
+        let receiver = {
+            let mut state = self.state.lock().unwrap();
+            if !state.busy {
+                state.busy = true;
+                state.last_served = Some(source);
+                return Ok(RelayTurn { queue: self });
+            }
+            let waiting = state.waiting.entry(source).or_default();
+            // waiters gone before their turn, e.g. due to relay timeout
+            waiting.retain(|sender| !sender.is_closed());
+            if waiting.len() >= self.max_queued_per_source {
+                log::error!("Too many requests of source {} waiting for relayer", source);
+                return Err(());
+            }
+            let (sender, receiver) = oneshot::channel();
+            waiting.push_back(sender);
+            receiver
+        };
+        let mut pending = PendingTurn { queue: self, receiver: Some(receiver) };
+        let granted = pending.receiver.as_mut().expect("receiver is taken only here or on drop").await;
+        pending.receiver = None;
+        granted.map_err(|_| ())?;
+        Ok(RelayTurn { queue: self })
+    }
+
+    fn pass_on(&self) {
+        let mut state = self.state.lock().unwrap();
+        loop {
+            let next = match state.last_served {
+                Some(last) => state
+                    .waiting
+                    .range((Bound::Excluded(last), Bound::Unbounded))
+                    .chain(state.waiting.range(..=last))
+                    .find(|(_, waiting)| !waiting.is_empty())
+                    .map(|(source, _)| *source),
+                None => state
+                    .waiting
+                    .iter()
+                    .find(|(_, waiting)| !waiting.is_empty())
+                    .map(|(source, _)| *source),
+            };
+            let Some(source) = next else {
+                state.waiting.clear();
+                state.busy = false;
+                return;
+            };
+            let sender = state.waiting.get_mut(&source).and_then(|waiting| waiting.pop_front());
+            // failed send means the waiter is gone, turn goes to the next one
+            if sender.is_some_and(|sender| sender.send(()).is_ok()) {
+                state.last_served = Some(source);
+                return;
+            }
+        }
+    }
+}
+
+impl Default for RelayQueue {
+    fn default() -> Self {
+        Self::new(DEFAULT_MAX_QUEUED_PER_SOURCE)
+    }
+}
+
+/// Turn to relay, passed on to the next waiting source on drop.
+pub struct RelayTurn<'a> {
+    queue: &'a RelayQueue,
+}
+
+impl Drop for RelayTurn<'_> {
+    fn drop(&mut self) {
+        self.queue.pass_on();
+    }
+}
+
+// passes the turn on if it was granted after the waiter was cancelled
+struct PendingTurn<'a> {
+    queue: &'a RelayQueue,
+    receiver: Option<oneshot::Receiver<()>>,
+}
+
+impl Drop for PendingTurn<'_> {
+    fn drop(&mut self) {
+        if let Some(mut receiver) = self.receiver.take() {
+            receiver.close();
+            if receiver.try_recv().is_ok() {
+                self.queue.pass_on();
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+    use std::time::Duration;
+
+    #[tokio::test(start_paused = true)]
+    async fn turns_are_granted_round_robin_across_sources() {
+        let queue = Arc::new(RelayQueue::new(8));
+        let served = Arc::new(Mutex::new(vec![]));
+        let first = queue.turn(1).await.unwrap();
+
+        let mut handles = vec![];
+        for source in [1, 1, 1, 2, 2, 3] {
+            let queue = queue.clone();
+            let served = served.clone();
+            handles.push(tokio::spawn(async move {
+                let _turn = queue.turn(source).await.unwrap();
+                served.lock().unwrap().push(source);
+                tokio::time::sleep(Duration::from_millis(10)).await;
+            }));
+            tokio::task::yield_now().await;
+        }
+        drop(first);
+        for handle in handles {
+            handle.await.unwrap();
+        }
+
+        assert_eq!(*served.lock().unwrap(), vec![2, 3, 1, 2, 1, 1]);
+    }
+
+    #[tokio::test]
+    async fn turn_fails_if_too_many_requests_of_source_are_waiting() {
+        let queue = Arc::new(RelayQueue::new(1));
+        let _turn = queue.turn(1).await.unwrap();
+
+        let waiting = tokio::spawn({
+            let queue = queue.clone();
+            async move { queue.turn(1).await.map(|_| ()) }
+        });
+        tokio::task::yield_now().await;
+
+        assert!(queue.turn(1).await.is_err());
+        waiting.abort();
+    }
+
+    #[tokio::test]
+    async fn cancelled_waiter_does_not_block_queue() {
+        let queue = RelayQueue::new(8);
+        let turn = queue.turn(1).await.unwrap();
+
+        let cancelled = tokio::time::timeout(Duration::from_millis(1), queue.turn(2)).await;
+        assert!(cancelled.is_err());
+        drop(turn);
+
+        let _turn = tokio::time::timeout(Duration::from_secs(1), queue.turn(3))
+            .await
+            .unwrap()
+            .unwrap();
+    }
+}
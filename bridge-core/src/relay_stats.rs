@@ -0,0 +1,165 @@
+// Copyright 2020-2024 Trust Computing GmbH.
+// This file is part of Litentry.
+//
+// Litentry is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// Litentry is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with Litentry.  If not, see <https://www.gnu.org/licenses/>.
+
+use metrics::gauge;
+use parity_scale_codec::{Decode, Encode};
+use serde::{Serialize, Serializer};
+use std::collections::HashMap;
+use std::fs;
+use std::io::ErrorKind;
+use std::sync::RwLock;
+
+pub const RELAYED_NONCE_HIGH_WATERMARK_GAUGE: &str = "relayed_nonce_high_watermark";
+pub const RELAYED_AMOUNT_TOTAL_GAUGE: &str = "relayed_amount_total";
+
+/// Reconciliation data of a single destination
+#[derive(Clone, Debug, Default, PartialEq, Encode, Decode, Serialize)]
+pub struct DestinationStats {
+    pub nonce_high_watermark: u64,
+    // serialized as string, json numbers can't safely hold u128
+    #[serde(serialize_with = "serialize_u128")]
+    pub relayed_amount_total: u128,
+    // hex encoded source reference of the last relayed event, not persisted to keep stats files compatible
+    #[codec(skip)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub last_source_ref: Option<String>,
+}
+
+fn serialize_u128<S: Serializer>(value: &u128, serializer: S) -> Result<S::Ok, S::Error> {
+    serializer.serialize_str(&value.to_string())
+}
+
+/// Tracks relayed nonces and amounts per destination of a listener. Stats are persisted in `file_name`
+/// (if set), so reconciliation baselines survive restarts.
+pub struct RelayStats {
+    listener_id: String,
+    file_name: Option<String>,
+    stats: RwLock<HashMap<String, DestinationStats>>,
+}
+
+impl RelayStats {
+    pub fn in_memory(listener_id: &str) -> Self {
+        Self { listener_id: listener_id.to_string(), file_name: None, stats: RwLock::new(HashMap::new()) }
+    }
+
+    #[allow(clippy::result_unit_err)]
+    pub fn new(listener_id: &str, file_name: &str) -> Result<Self, ()> {
+        let stats: HashMap<String, DestinationStats> = match fs::read(file_name) {
+            Ok(content) => Vec::<(String, DestinationStats)>::decode(&mut content.as_slice())
+                .map_err(|e| log::error!("Could not decode relay stats: {:?}", e))?
+                .into_iter()
+                .collect(),
+            Err(e) if e.kind() == ErrorKind::NotFound => HashMap::new(),
+            Err(e) => {
+                log::error!("Could not read relay stats from {}: {:?}", file_name, e);
+                return Err(());
+            },
+        };
+
+        for (destination, destination_stats) in stats.iter() {
+            set_gauges(listener_id, destination, destination_stats);
+        }
+
+        Ok(Self {
+            listener_id: listener_id.to_string(),
+            file_name: Some(file_name.to_string()),
+            stats: RwLock::new(stats),
+        })
+    }
+
+    /// Records successfully relayed event. Nonce watermark never moves backwards.
+    pub fn record(&self, destination: &str, nonce: u64, amount: u128, source_ref: &str) {
+        let mut stats = self.stats.write().unwrap();
+        let destination_stats = stats.entry(destination.to_string()).or_default();
+        destination_stats.nonce_high_watermark = destination_stats.nonce_high_watermark.max(nonce);
+        destination_stats.relayed_amount_total = destination_stats.relayed_amount_total.saturating_add(amount);
+        destination_stats.last_source_ref = Some(source_ref.to_string());
+        set_gauges(&self.listener_id, destination, destination_stats);
+
+        if let Some(ref file_name) = self.file_name {
+            let encoded = stats.iter().map(|(k, v)| (k.clone(), v.clone())).collect::<Vec<_>>().encode();
+            if let Err(e) = fs::write(file_name, encoded) {
+                log::error!("Could not persist relay stats to {}: {:?}", file_name, e);
+            }
+        }
+    }
+
+    pub fn snapshot(&self) -> HashMap<String, DestinationStats> {
+        self.stats.read().unwrap().clone()
+    }
+}
+
+fn set_gauges(listener_id: &str, destination: &str, stats: &DestinationStats) {
+    gauge!(RELAYED_NONCE_HIGH_WATERMARK_GAUGE, "listener" => listener_id.to_string(), "destination" => destination.to_string())
+        .set(stats.nonce_high_watermark as f64);
+    gauge!(RELAYED_AMOUNT_TOTAL_GAUGE, "listener" => listener_id.to_string(), "destination" => destination.to_string())
+        .set(stats.relayed_amount_total as f64);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn watermark_only_moves_forward() {
+        let stats = RelayStats::in_memory("test");
+        stats.record("02", 5, 10, "0x05");
+        stats.record("02", 3, 10, "0x03");
+        stats.record("01", 1, 1, "0x01");
+
+        let snapshot = stats.snapshot();
+        assert_eq!(
+            snapshot["02"],
+            DestinationStats {
+                nonce_high_watermark: 5,
+                relayed_amount_total: 20,
+                last_source_ref: Some("0x03".to_string())
+            }
+        );
+        assert_eq!(
+            snapshot["01"],
+            DestinationStats {
+                nonce_high_watermark: 1,
+                relayed_amount_total: 1,
+                last_source_ref: Some("0x01".to_string())
+            }
+        );
+    }
+
+    #[test]
+    fn stats_survive_restart() {
+        let file_name = "stats_survive_restart.bin";
+        let _ = fs::remove_file(file_name);
+
+        let stats = RelayStats::new("test", file_name).unwrap();
+        stats.record("02", 5, 10, "0x05");
+        drop(stats);
+
+        let stats = RelayStats::new("test", file_name).unwrap();
+        assert_eq!(stats.snapshot()["02"].last_source_ref, None);
+        stats.record("02", 4, 10, "0x04");
+        assert_eq!(
+            stats.snapshot()["02"],
+            DestinationStats {
+                nonce_high_watermark: 5,
+                relayed_amount_total: 20,
+                last_source_ref: Some("0x04".to_string())
+            }
+        );
+
+        fs::remove_file(file_name).unwrap();
+    }
+}
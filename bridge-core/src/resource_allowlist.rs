@@ -0,0 +1,81 @@
+// Copyright 2020-2024 Trust Computing GmbH.
+// This file is part of Litentry.
+//
+// Litentry is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// Litentry is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with Litentry.  If not, see <https://www.gnu.org/licenses/>.
+
+use crate::resource_registry::parse_resource_id;
+use std::collections::HashSet;
+
+pub const FILTERED_RESOURCE_COUNTER: &str = "relay_filtered_resource_total";
+
+/// Resource ids a relayer relays, guards against deposits of unregistered assets emitted by misconfigured contracts.
+/// Every resource id is allowed if the allowlist is empty.
+#[derive(Debug, Default)]
+pub struct ResourceAllowlist {
+    allowed: HashSet<[u8; 32]>,
+}
+
+impl ResourceAllowlist {
+    pub fn unrestricted() -> Self {
+        Self { allowed: HashSet::new() }
+    }
+
+    pub fn new(allowed: impl IntoIterator<Item = [u8; 32]>) -> Self {
+        Self { allowed: allowed.into_iter().collect() }
+    }
+
+    /// Parses hex encoded resource ids, fails on the first invalid one.
+    #[allow(clippy::result_unit_err)]
+    pub fn from_hex(resource_ids: &[String]) -> Result<Self, ()> {
+        let allowed = resource_ids
+            .iter()
+            .map(|resource_id| {
+                parse_resource_id(resource_id)
+                    .ok_or_else(|| log::error!("Invalid allowed resource id: {}", resource_id))
+            })
+            .collect::<Result<Vec<_>, ()>>()?;
+        Ok(Self::new(allowed))
+    }
+
+    pub fn is_allowed(&self, resource_id: &[u8; 32]) -> bool {
+        self.allowed.is_empty() || self.allowed.contains(resource_id)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn unrestricted_allowlist_allows_everything() {
+        assert!(ResourceAllowlist::unrestricted().is_allowed(&[1; 32]));
+        assert!(ResourceAllowlist::new(vec![]).is_allowed(&[1; 32]));
+    }
+
+    #[test]
+    fn only_listed_resource_ids_are_allowed() {
+        let allowlist =
+            ResourceAllowlist::from_hex(&[format!("0x{}", hex::encode([1; 32])), hex::encode([2; 32])]).unwrap();
+
+        assert!(allowlist.is_allowed(&[1; 32]));
+        assert!(allowlist.is_allowed(&[2; 32]));
+        assert!(!allowlist.is_allowed(&[3; 32]));
+    }
+
+    #[test]
+    fn invalid_resource_id_is_rejected() {
+        assert!(ResourceAllowlist::from_hex(&["0xzz".to_string()]).is_err());
+        assert!(ResourceAllowlist::from_hex(&[hex::encode([1; 20])]).is_err());
+    }
+}
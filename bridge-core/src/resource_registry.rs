@@ -0,0 +1,118 @@
+// Copyright 2020-2024 Trust Computing GmbH.
+// This file is part of Litentry.
+//
+// Litentry is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// Litentry is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with Litentry.  If not, see <https://www.gnu.org/licenses/>.
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// Asset bridged under a resource id
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
+pub struct AssetInfo {
+    pub name: String,
+    pub decimals: u8,
+    // chain ids the asset is bridged between
+    #[serde(default)]
+    pub chains: Vec<u32>,
+}
+
+/// Maps resource ids to assets they represent, so amounts can be shown in asset units.
+#[derive(Clone, Debug, Default)]
+pub struct ResourceRegistry {
+    assets: HashMap<[u8; 32], AssetInfo>,
+}
+
+impl ResourceRegistry {
+    /// Creates registry from assets keyed by hex encoded resource id, returns the first invalid id on failure.
+    pub fn new(resources: &HashMap<String, AssetInfo>) -> Result<Self, String> {
+        let assets = resources
+            .iter()
+            .map(|(resource_id, asset)| {
+                parse_resource_id(resource_id)
+                    .map(|parsed| (parsed, asset.clone()))
+                    .ok_or_else(|| resource_id.clone())
+            })
+            .collect::<Result<_, _>>()?;
+        Ok(Self { assets })
+    }
+
+    pub fn get(&self, resource_id: &[u8; 32]) -> Option<&AssetInfo> {
+        self.assets.get(resource_id)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.assets.is_empty()
+    }
+
+    /// Human readable amount, e.g. `100 LIT`, or raw amount with resource id for unregistered resources
+    pub fn describe(&self, resource_id: &[u8; 32], amount: u128) -> String {
+        match self.get(resource_id) {
+            Some(asset) => format!("{} {}", format_amount(amount, asset.decimals), asset.name),
+            None => format!("{} of resource 0x{}", amount, hex::encode(resource_id)),
+        }
+    }
+}
+
+pub fn parse_resource_id(resource_id: &str) -> Option<[u8; 32]> {
+    hex::decode(resource_id.trim_start_matches("0x"))
+        .ok()
+        .and_then(|bytes| bytes.try_into().ok())
+}
+
+/// Formats `amount` of smallest units as decimal number of whole units, without trailing zeros
+pub fn format_amount(amount: u128, decimals: u8) -> String {
+    let Some(unit) = 10u128.checked_pow(decimals.into()) else {
+        return amount.to_string();
+    };
+    let fraction = amount % unit;
+    if fraction == 0 {
+        return (amount / unit).to_string();
+    }
+    let fraction = format!("{:0width$}", fraction, width = decimals as usize);
+    format!("{}.{}", amount / unit, fraction.trim_end_matches('0'))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{format_amount, AssetInfo, ResourceRegistry};
+    use std::collections::HashMap;
+
+    fn lit() -> AssetInfo {
+        AssetInfo { name: "LIT".to_string(), decimals: 18, chains: vec![0, 1] }
+    }
+
+    #[test]
+    fn amounts_are_formatted_in_whole_units() {
+        assert_eq!(format_amount(100_000_000_000_000_000_000, 18), "100");
+        assert_eq!(format_amount(1_500_000_000_000_000_000, 18), "1.5");
+        assert_eq!(format_amount(1, 18), "0.000000000000000001");
+        assert_eq!(format_amount(42, 0), "42");
+        assert_eq!(format_amount(42, 40), "42");
+    }
+
+    #[test]
+    fn registered_amounts_are_described_with_asset_name() {
+        let registry = ResourceRegistry::new(&HashMap::from([(format!("0x{}", hex::encode([1; 32])), lit())])).unwrap();
+
+        assert_eq!(registry.describe(&[1; 32], 100_000_000_000_000_000_000), "100 LIT");
+        assert_eq!(registry.describe(&[2; 32], 100), format!("100 of resource 0x{}", hex::encode([2; 32])));
+    }
+
+    #[test]
+    fn invalid_resource_id_is_reported() {
+        let result = ResourceRegistry::new(&HashMap::from([("0x01".to_string(), lit())]));
+
+        assert_eq!(result.unwrap_err(), "0x01");
+    }
+}
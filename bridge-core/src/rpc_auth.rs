@@ -0,0 +1,77 @@
+// Copyright 2020-2024 Trust Computing GmbH.
+// This file is part of Litentry.
+//
+// Litentry is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// Litentry is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with Litentry.  If not, see <https://www.gnu.org/licenses/>.
+
+use base64::prelude::BASE64_STANDARD;
+use base64::Engine;
+use serde::{Deserialize, Serialize};
+
+/// Credentials sent in `Authorization` header to RPC endpoints behind an auth proxy.
+/// Values in `${VAR}` form are read from environment variable `VAR` so secrets don't have to be
+/// stored in config file.
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
+#[serde(tag = "type", rename_all = "lowercase")]
+pub enum RpcAuth {
+    Basic { username: String, password: String },
+    Bearer { token: String },
+}
+
+impl RpcAuth {
+    #[allow(clippy::result_unit_err)]
+    pub fn authorization_header(&self) -> Result<String, ()> {
+        match self {
+            RpcAuth::Basic { username, password } => {
+                let credentials = format!("{}:{}", resolve(username)?, resolve(password)?);
+                Ok(format!("Basic {}", BASE64_STANDARD.encode(credentials)))
+            },
+            RpcAuth::Bearer { token } => Ok(format!("Bearer {}", resolve(token)?)),
+        }
+    }
+}
+
+fn resolve(value: &str) -> Result<String, ()> {
+    match value.strip_prefix("${").and_then(|value| value.strip_suffix('}')) {
+        Some(var) => std::env::var(var).map_err(|e| {
+            log::error!("Could not read rpc auth value from environment variable {}: {:?}", var, e);
+        }),
+        None => Ok(value.to_string()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn authorization_header_works() {
+        let basic: RpcAuth =
+            serde_json::from_str(r#"{"type": "basic", "username": "user", "password": "pass"}"#).unwrap();
+        assert_eq!(basic.authorization_header(), Ok("Basic dXNlcjpwYXNz".to_string()));
+
+        let bearer: RpcAuth = serde_json::from_str(r#"{"type": "bearer", "token": "secret"}"#).unwrap();
+        assert_eq!(bearer.authorization_header(), Ok("Bearer secret".to_string()));
+    }
+
+    #[test]
+    fn authorization_header_reads_values_from_environment() {
+        std::env::set_var("RPC_AUTH_TEST_TOKEN", "secret");
+
+        let bearer = RpcAuth::Bearer { token: "${RPC_AUTH_TEST_TOKEN}".to_string() };
+        assert_eq!(bearer.authorization_header(), Ok("Bearer secret".to_string()));
+
+        let missing = RpcAuth::Bearer { token: "${RPC_AUTH_TEST_MISSING_TOKEN}".to_string() };
+        assert_eq!(missing.authorization_header(), Err(()));
+    }
+}
@@ -0,0 +1,218 @@
+// Copyright 2020-2024 Trust Computing GmbH.
+// This file is part of Litentry.
+//
+// Litentry is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// Litentry is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with Litentry.  If not, see <https://www.gnu.org/licenses/>.
+
+use async_trait::async_trait;
+use std::ops::RangeInclusive;
+
+/// Runtime spec name and version reported by a substrate node.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RuntimeVersion {
+    pub spec_name: String,
+    pub spec_version: u32,
+}
+
+/// Queries runtime version of the node before connecting with generated metadata.
+#[async_trait]
+pub trait RuntimeVersionFetcher: Send + Sync {
+    async fn runtime_version(&self) -> Result<RuntimeVersion, ()>;
+}
+
+/// Generated metadata module of a substrate runtime along with `value` using it, e.g. listener factory.
+pub struct RegisteredRuntime<T> {
+    /// Chain name used in configuration
+    pub chain: &'static str,
+    pub spec_name: &'static str,
+    /// Spec versions the generated metadata is known to match
+    pub spec_versions: RangeInclusive<u32>,
+    pub value: T,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum RuntimeSelectionError {
+    UnknownChain(String),
+    UnknownSpecName(String),
+    // configured chain is registered for other spec name than the node's one
+    ChainMismatch { chain: String, spec_name: String },
+    // chain has to be configured to pick one of runtimes sharing the spec name
+    Ambiguous(Vec<&'static str>),
+    UnsupportedSpecVersion { chain: &'static str, spec_version: u32 },
+    // node is unreachable and chain is not configured
+    VersionUnavailable,
+}
+
+/// Selects runtime registered for the node's spec name. Configured chain is validated against the node and
+/// picks among runtimes sharing the spec name, spec version outside of the supported range is rejected.
+pub fn select_runtime<'a, T>(
+    registry: &'a [RegisteredRuntime<T>],
+    version: &RuntimeVersion,
+    configured_chain: Option<&str>,
+) -> Result<&'a RegisteredRuntime<T>, RuntimeSelectionError> {
+    let runtime = match configured_chain {
+        Some(chain) => {
+            let runtime = find_runtime(registry, chain)?;
+            if runtime.spec_name != version.spec_name {
+                return Err(RuntimeSelectionError::ChainMismatch {
+                    chain: chain.to_string(),
+                    spec_name: version.spec_name.clone(),
+                });
+            }
+            runtime
+        },
+        None => {
+            let candidates: Vec<&RegisteredRuntime<T>> = registry
+                .iter()
+                .filter(|runtime| runtime.spec_name == version.spec_name)
+                .collect();
+            match candidates.as_slice() {
+                [] => return Err(RuntimeSelectionError::UnknownSpecName(version.spec_name.clone())),
+                [runtime] => *runtime,
+                _ => {
+                    return Err(RuntimeSelectionError::Ambiguous(
+                        candidates.iter().map(|runtime| runtime.chain).collect(),
+                    ))
+                },
+            }
+        },
+    };
+    if !runtime.spec_versions.contains(&version.spec_version) {
+        return Err(RuntimeSelectionError::UnsupportedSpecVersion {
+            chain: runtime.chain,
+            spec_version: version.spec_version,
+        });
+    }
+    Ok(runtime)
+}
+
+/// Finds runtime registered under configured chain name.
+pub fn find_runtime<'a, T>(
+    registry: &'a [RegisteredRuntime<T>],
+    chain: &str,
+) -> Result<&'a RegisteredRuntime<T>, RuntimeSelectionError> {
+    registry
+        .iter()
+        .find(|runtime| runtime.chain == chain)
+        .ok_or_else(|| RuntimeSelectionError::UnknownChain(chain.to_string()))
+}
+
+/// Selects runtime of the node behind `fetcher`, see `select_runtime`. Configured chain is used as is if the
+/// node is unreachable, its runtime is checked again once connected.
+pub async fn resolve_runtime<'a, T>(
+    registry: &'a [RegisteredRuntime<T>],
+    fetcher: &dyn RuntimeVersionFetcher,
+    configured_chain: Option<&str>,
+) -> Result<&'a RegisteredRuntime<T>, RuntimeSelectionError> {
+    // typos are reported without waiting for the node
+    if let Some(chain) = configured_chain {
+        find_runtime(registry, chain)?;
+    }
+    match (fetcher.runtime_version().await, configured_chain) {
+        (Ok(version), configured_chain) => select_runtime(registry, &version, configured_chain),
+        (Err(()), Some(chain)) => {
+            log::warn!("Could not get runtime version of the node, using configured chain {}", chain);
+            find_runtime(registry, chain)
+        },
+        (Err(()), None) => Err(RuntimeSelectionError::VersionUnavailable),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn registry() -> Vec<RegisteredRuntime<()>> {
+        vec![
+            RegisteredRuntime { chain: "local", spec_name: "paseo-parachain", spec_versions: 9241..=9299, value: () },
+            RegisteredRuntime { chain: "paseo", spec_name: "paseo-parachain", spec_versions: 9241..=9299, value: () },
+            RegisteredRuntime {
+                chain: "heima",
+                spec_name: "litentry-parachain",
+                spec_versions: 9241..=9299,
+                value: (),
+            },
+        ]
+    }
+
+    fn version(spec_name: &str, spec_version: u32) -> RuntimeVersion {
+        RuntimeVersion { spec_name: spec_name.to_string(), spec_version }
+    }
+
+    struct FakeRuntimeVersionFetcher(Option<RuntimeVersion>);
+
+    #[async_trait]
+    impl RuntimeVersionFetcher for FakeRuntimeVersionFetcher {
+        async fn runtime_version(&self) -> Result<RuntimeVersion, ()> {
+            self.0.clone().ok_or(())
+        }
+    }
+
+    #[test]
+    fn runtime_is_selected_by_spec_name() {
+        let registry = registry();
+        let selected = select_runtime(&registry, &version("litentry-parachain", 9241), None).unwrap();
+        assert_eq!(selected.chain, "heima");
+    }
+
+    #[test]
+    fn configured_chain_picks_among_runtimes_sharing_spec_name() {
+        let registry = registry();
+        assert_eq!(
+            select_runtime(&registry, &version("paseo-parachain", 9241), None).err(),
+            Some(RuntimeSelectionError::Ambiguous(vec!["local", "paseo"]))
+        );
+        let selected = select_runtime(&registry, &version("paseo-parachain", 9241), Some("paseo")).unwrap();
+        assert_eq!(selected.chain, "paseo");
+    }
+
+    #[test]
+    fn configured_chain_must_match_node() {
+        let registry = registry();
+        assert_eq!(
+            select_runtime(&registry, &version("litentry-parachain", 9241), Some("paseo")).err(),
+            Some(RuntimeSelectionError::ChainMismatch {
+                chain: "paseo".to_string(),
+                spec_name: "litentry-parachain".to_string()
+            })
+        );
+    }
+
+    #[test]
+    fn unknown_spec_name_and_unsupported_spec_version_are_rejected() {
+        let registry = registry();
+        assert_eq!(
+            select_runtime(&registry, &version("polkadot", 1_003_000), None).err(),
+            Some(RuntimeSelectionError::UnknownSpecName("polkadot".to_string()))
+        );
+        assert_eq!(
+            select_runtime(&registry, &version("litentry-parachain", 9300), None).err(),
+            Some(RuntimeSelectionError::UnsupportedSpecVersion { chain: "heima", spec_version: 9300 })
+        );
+    }
+
+    #[tokio::test]
+    async fn configured_chain_is_used_if_node_is_unreachable() {
+        let registry = registry();
+        let fetcher = FakeRuntimeVersionFetcher(None);
+        assert_eq!(resolve_runtime(&registry, &fetcher, Some("heima")).await.unwrap().chain, "heima");
+        assert_eq!(
+            resolve_runtime(&registry, &fetcher, None).await.err(),
+            Some(RuntimeSelectionError::VersionUnavailable)
+        );
+        assert_eq!(
+            resolve_runtime(&registry, &fetcher, Some("rococo")).await.err(),
+            Some(RuntimeSelectionError::UnknownChain("rococo".to_string()))
+        );
+    }
+}
@@ -0,0 +1,67 @@
+// Copyright 2020-2024 Trust Computing GmbH.
+// This file is part of Litentry.
+//
+// Litentry is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// Litentry is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with Litentry.  If not, see <https://www.gnu.org/licenses/>.
+
+use log::error;
+use rsa::rand_core::OsRng;
+use rsa::traits::PublicKeyParts;
+use rsa::{BigUint, Oaep, RsaPublicKey};
+use serde::{Deserialize, Serialize};
+use serde_with::serde_as;
+use sha2::Sha256;
+
+/// OAEP padding overhead with SHA-256
+pub const OAEP_OVERHEAD: usize = 2 * 32 + 2;
+
+/// Worker's RSA public key used to shield imported relayer keys, as returned by `hm_getShieldingKey`.
+/// Modulus and exponent are little endian.
+#[serde_as]
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct ShieldingPublicKey {
+    #[serde_as(as = "serde_with::hex::Hex")]
+    pub n: Vec<u8>,
+    #[serde_as(as = "serde_with::hex::Hex")]
+    pub e: Vec<u8>,
+}
+
+impl ShieldingPublicKey {
+    pub fn from_rsa(public_key: &RsaPublicKey) -> Self {
+        Self { n: public_key.n().to_bytes_le(), e: public_key.e().to_bytes_le() }
+    }
+
+    #[allow(clippy::result_unit_err)]
+    pub fn to_rsa(&self) -> Result<RsaPublicKey, ()> {
+        RsaPublicKey::new(BigUint::from_bytes_le(&self.n), BigUint::from_bytes_le(&self.e)).map_err(|e| {
+            error!("Invalid shielding key: {:?}", e);
+        })
+    }
+}
+
+/// Longest value which can be RSA-OAEP encrypted with `public_key`.
+pub fn max_oaep_len(public_key: &RsaPublicKey) -> usize {
+    public_key.size().saturating_sub(OAEP_OVERHEAD)
+}
+
+/// Encrypts `value` with RSA-OAEP (SHA-256), fails if it doesn't fit the modulus.
+#[allow(clippy::result_unit_err)]
+pub fn oaep_encrypt(public_key: &RsaPublicKey, value: &[u8]) -> Result<Vec<u8>, ()> {
+    if value.len() > max_oaep_len(public_key) {
+        error!("Value of {} bytes exceeds {} bytes allowed by shielding key", value.len(), max_oaep_len(public_key));
+        return Err(());
+    }
+    public_key.encrypt(&mut OsRng, Oaep::new::<Sha256>(), value).map_err(|e| {
+        error!("Could not shield value: {:?}", e);
+    })
+}
@@ -0,0 +1,225 @@
+// Copyright 2020-2024 Trust Computing GmbH.
+// This file is part of Litentry.
+//
+// Litentry is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// Litentry is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with Litentry.  If not, see <https://www.gnu.org/licenses/>.
+
+use crate::fetcher::LastFinalizedBlockNumFetcher;
+use log::{error, info, warn};
+
+/// Start block override of a listener given as `<listener id>:<block>`, or `<listener id>:-<blocks>`
+/// to start given number of blocks behind the finalized head.
+#[derive(Debug, PartialEq)]
+pub struct StartBlock {
+    pub listener_id: String,
+    pub block: StartBlockOverride,
+}
+
+impl TryFrom<&String> for StartBlock {
+    type Error = ();
+
+    fn try_from(value: &String) -> Result<Self, Self::Error> {
+        let values: Vec<&str> = value.split(":").collect();
+        let block = values.get(1).ok_or(())?;
+        let block = match block.strip_prefix('-') {
+            Some(behind_head) => StartBlockOverride::BehindHead(behind_head.parse::<u64>().map_err(|_| ())?),
+            None => StartBlockOverride::Absolute(block.parse::<u64>().map_err(|_| ())?),
+        };
+        Ok(StartBlock { listener_id: values.first().unwrap().to_string(), block })
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum StartBlockOverride {
+    Absolute(u64),
+    // number of blocks behind the finalized head at startup
+    BehindHead(u64),
+}
+
+/// Where a listener starts syncing if there is no checkpoint ahead of it. Resolved against the chain once the
+/// listener's fetcher is available, an override beyond the finalized head would leave the listener silently waiting
+/// and one before the bridge contract deployment would scan empty history.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct StartBlockSettings {
+    pub block: Option<StartBlockOverride>,
+    // first block worth syncing, e.g. block the bridge contract was deployed at
+    pub deployed_at_block: Option<u64>,
+    // accept override beyond the finalized head or before `deployed_at_block`, only warn about it
+    pub force: bool,
+}
+
+impl StartBlockSettings {
+    #[allow(clippy::result_unit_err)]
+    pub async fn resolve<F: LastFinalizedBlockNumFetcher + Send>(&self, id: &str, fetcher: &mut F) -> Result<u64, ()> {
+        let Some(block) = self.block else {
+            return Ok(self.deployed_at_block.unwrap_or(0));
+        };
+        match fetcher.get_last_finalized_block_num().await {
+            Ok(Some(finalized_block)) => self.check(id, block, finalized_block),
+            result => match block {
+                StartBlockOverride::Absolute(block) if self.force => {
+                    warn!("Could not check start block {} of {} listener against finalized block", block, id);
+                    Ok(block)
+                },
+                _ => {
+                    error!("Could not get finalized block of {} listener to check start block: {:?}", id, result);
+                    Err(())
+                },
+            },
+        }
+    }
+
+    #[allow(clippy::result_unit_err)]
+    pub fn check(&self, id: &str, block: StartBlockOverride, finalized_block: u64) -> Result<u64, ()> {
+        let start_block = match block {
+            StartBlockOverride::Absolute(block) if block > finalized_block => {
+                if !self.force {
+                    error!(
+                        "Start block {} of {} listener is beyond finalized block {}, use --force to start anyway",
+                        block, id, finalized_block
+                    );
+                    return Err(());
+                }
+                warn!("Start block {} of {} listener is beyond finalized block {}", block, id, finalized_block);
+                block
+            },
+            StartBlockOverride::Absolute(block) => block,
+            StartBlockOverride::BehindHead(blocks) => finalized_block.saturating_sub(blocks),
+        };
+        match self.deployed_at_block {
+            Some(deployed_at_block) if start_block < deployed_at_block && !self.force => {
+                warn!(
+                    "Start block {} of {} listener is before deployment block {}, starting from deployment block",
+                    start_block, id, deployed_at_block
+                );
+                Ok(deployed_at_block)
+            },
+            Some(deployed_at_block) if start_block < deployed_at_block => {
+                warn!(
+                    "Start block {} of {} listener is before deployment block {}",
+                    start_block, id, deployed_at_block
+                );
+                Ok(start_block)
+            },
+            _ => {
+                info!("Listener {} starts from block {}", id, start_block);
+                Ok(start_block)
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use async_trait::async_trait;
+
+    struct FinalizedBlock(Result<Option<u64>, ()>);
+
+    #[async_trait]
+    impl LastFinalizedBlockNumFetcher for FinalizedBlock {
+        async fn get_last_finalized_block_num(&mut self) -> Result<Option<u64>, ()> {
+            self.0
+        }
+    }
+
+    fn settings(block: StartBlockOverride, deployed_at_block: Option<u64>, force: bool) -> StartBlockSettings {
+        StartBlockSettings { block: Some(block), deployed_at_block, force }
+    }
+
+    #[test]
+    fn start_block_is_parsed() {
+        let parse = |value: &str| StartBlock::try_from(&value.to_string());
+
+        assert_eq!(
+            parse("sepolia:100"),
+            Ok(StartBlock { listener_id: "sepolia".to_string(), block: StartBlockOverride::Absolute(100) })
+        );
+        assert_eq!(
+            parse("sepolia:-1000"),
+            Ok(StartBlock { listener_id: "sepolia".to_string(), block: StartBlockOverride::BehindHead(1000) })
+        );
+        assert!(parse("sepolia").is_err());
+        assert!(parse("sepolia:").is_err());
+        assert!(parse("sepolia:-").is_err());
+        assert!(parse("sepolia:--1").is_err());
+    }
+
+    #[test]
+    fn start_block_beyond_finalized_block_is_rejected_unless_forced() {
+        let block = StartBlockOverride::Absolute(1001);
+
+        assert_eq!(settings(block, None, false).check("sepolia", block, 1000), Err(()));
+        assert_eq!(settings(block, None, true).check("sepolia", block, 1000), Ok(1001));
+        assert_eq!(settings(block, None, false).check("sepolia", StartBlockOverride::Absolute(1000), 1000), Ok(1000));
+    }
+
+    #[test]
+    fn relative_start_block_is_counted_back_from_finalized_block() {
+        let block = StartBlockOverride::BehindHead(100);
+
+        assert_eq!(settings(block, None, false).check("sepolia", block, 1000), Ok(900));
+        assert_eq!(settings(block, None, false).check("sepolia", block, 50), Ok(0));
+    }
+
+    #[test]
+    fn start_block_before_deployment_is_clamped_unless_forced() {
+        let block = StartBlockOverride::Absolute(100);
+
+        assert_eq!(settings(block, Some(500), false).check("sepolia", block, 1000), Ok(500));
+        assert_eq!(settings(block, Some(500), true).check("sepolia", block, 1000), Ok(100));
+        assert_eq!(settings(block, Some(50), false).check("sepolia", block, 1000), Ok(100));
+        let block = StartBlockOverride::BehindHead(900);
+        assert_eq!(settings(block, Some(500), false).check("sepolia", block, 1000), Ok(500));
+    }
+
+    #[tokio::test]
+    async fn start_block_is_resolved_against_fetched_finalized_block() {
+        let block = StartBlockOverride::BehindHead(10);
+
+        assert_eq!(
+            settings(block, None, false)
+                .resolve("sepolia", &mut FinalizedBlock(Ok(Some(100))))
+                .await,
+            Ok(90)
+        );
+        assert_eq!(
+            settings(block, None, true)
+                .resolve("sepolia", &mut FinalizedBlock(Ok(None)))
+                .await,
+            Err(())
+        );
+        let block = StartBlockOverride::Absolute(10);
+        assert_eq!(
+            settings(block, None, false)
+                .resolve("sepolia", &mut FinalizedBlock(Err(())))
+                .await,
+            Err(())
+        );
+        assert_eq!(
+            settings(block, None, true)
+                .resolve("sepolia", &mut FinalizedBlock(Err(())))
+                .await,
+            Ok(10)
+        );
+    }
+
+    #[tokio::test]
+    async fn listener_without_override_starts_from_deployment_block() {
+        let mut unreachable = FinalizedBlock(Err(()));
+
+        assert_eq!(StartBlockSettings::default().resolve("sepolia", &mut unreachable).await, Ok(0));
+        let settings = StartBlockSettings { deployed_at_block: Some(500), ..Default::default() };
+        assert_eq!(settings.resolve("sepolia", &mut unreachable).await, Ok(500));
+    }
+}
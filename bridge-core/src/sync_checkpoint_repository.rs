@@ -33,6 +33,11 @@ pub trait Checkpoint {
 pub trait CheckpointRepository<Checkpoint> {
     fn get(&self) -> Result<Option<Checkpoint>, ()>;
     fn save(&mut self, checkpoint: Checkpoint) -> Result<(), ()>;
+    /// Saves checkpoint behind the stored one, used to reprocess blocks replaced by a reorg. Differs from `save`
+    /// only for repositories which don't let the checkpoint move backwards.
+    fn rewind(&mut self, checkpoint: Checkpoint) -> Result<(), ()> {
+        self.save(checkpoint)
+    }
 }
 
 /// Simple `CheckpointRepository`. Checkpoints are not persisted across restarts.
@@ -0,0 +1,98 @@
+// Copyright 2020-2024 Trust Computing GmbH.
+// This file is part of Litentry.
+//
+// Litentry is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// Litentry is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with Litentry.  If not, see <https://www.gnu.org/licenses/>.
+
+use std::collections::VecDeque;
+
+pub const DEFAULT_TIMESTAMP_CACHE_CAPACITY: usize = 256;
+
+/// Recently fetched block timestamps, export and fetchers ask for the same block repeatedly.
+/// Least recently used entry is evicted once the cache is full. Keyed by block number, so a timestamp
+/// of a reorganized block may be stale, which is acceptable for event age and reporting.
+#[derive(Debug)]
+pub struct BlockTimestampCache {
+    capacity: usize,
+    // most recently used at the back
+    entries: VecDeque<(u64, u64)>,
+}
+
+impl Default for BlockTimestampCache {
+    fn default() -> Self {
+        Self::new(DEFAULT_TIMESTAMP_CACHE_CAPACITY)
+    }
+}
+
+impl BlockTimestampCache {
+    pub fn new(capacity: usize) -> Self {
+        Self { capacity, entries: VecDeque::with_capacity(capacity) }
+    }
+
+    pub fn get(&mut self, block_num: u64) -> Option<u64> {
+        let position = self.entries.iter().position(|(cached, _)| *cached == block_num)?;
+        let entry = self.entries.remove(position)?;
+        self.entries.push_back(entry);
+        Some(entry.1)
+    }
+
+    pub fn insert(&mut self, block_num: u64, timestamp: u64) {
+        if self.capacity == 0 {
+            return;
+        }
+        if let Some(position) = self.entries.iter().position(|(cached, _)| *cached == block_num) {
+            self.entries.remove(position);
+        } else if self.entries.len() == self.capacity {
+            self.entries.pop_front();
+        }
+        self.entries.push_back((block_num, timestamp));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn cached_timestamp_is_returned() {
+        let mut cache = BlockTimestampCache::new(2);
+        assert_eq!(cache.get(1), None);
+
+        cache.insert(1, 100);
+        assert_eq!(cache.get(1), Some(100));
+        cache.insert(1, 101);
+        assert_eq!(cache.get(1), Some(101));
+    }
+
+    #[test]
+    fn least_recently_used_entry_is_evicted() {
+        let mut cache = BlockTimestampCache::new(2);
+        cache.insert(1, 100);
+        cache.insert(2, 200);
+        // block 2 becomes least recently used
+        assert_eq!(cache.get(1), Some(100));
+
+        cache.insert(3, 300);
+
+        assert_eq!(cache.get(2), None);
+        assert_eq!(cache.get(1), Some(100));
+        assert_eq!(cache.get(3), Some(300));
+    }
+
+    #[test]
+    fn zero_capacity_cache_stores_nothing() {
+        let mut cache = BlockTimestampCache::new(0);
+        cache.insert(1, 100);
+        assert_eq!(cache.get(1), None);
+    }
+}
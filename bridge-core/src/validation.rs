@@ -0,0 +1,42 @@
+// Copyright 2020-2024 Trust Computing GmbH.
+// This file is part of Litentry.
+//
+// Litentry is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// Litentry is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with Litentry.  If not, see <https://www.gnu.org/licenses/>.
+
+use std::fmt::{Display, Formatter};
+
+/// Reason why bridge request must not be relayed.
+#[derive(Debug, PartialEq)]
+pub enum ValidationError {
+    InvalidRecipientLength(usize),
+    ZeroRecipient,
+    // hex encoded recipient
+    DeniedRecipient(String),
+}
+
+impl Display for ValidationError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ValidationError::InvalidRecipientLength(len) => write!(f, "Invalid recipient length: {}", len),
+            ValidationError::ZeroRecipient => write!(f, "Recipient is zero address"),
+            ValidationError::DeniedRecipient(recipient) => write!(f, "Recipient is denied: {}", recipient),
+        }
+    }
+}
+
+/// Checks bridge request against destination chain specific rules before it's relayed.
+/// Requests failing validation are never relayed.
+pub trait Validator: Send + Sync {
+    fn validate(&self, amount: u128, resource_id: &[u8; 32], data: &[u8]) -> Result<(), ValidationError>;
+}
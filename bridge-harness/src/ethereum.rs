@@ -0,0 +1,245 @@
+// Copyright 2020-2024 Trust Computing GmbH.
+// This file is part of Litentry.
+//
+// Litentry is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// Litentry is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with Litentry.  If not, see <https://www.gnu.org/licenses/>.
+
+use alloy::dyn_abi::DynSolValue;
+use alloy::primitives::{keccak256, Address, Bytes, FixedBytes, B256, U256};
+use async_trait::async_trait;
+use bridge_core::primitives::encode_deposit_data;
+use bridge_core::relay::RelayError;
+use ethereum_listener::fetcher::EVENT_TOPIC;
+use ethereum_listener::primitives::{BlockHeader, Log, LogId};
+use ethereum_listener::rpc_client::{EthereumRpcClient, FinalizedBlockError};
+use ethereum_relayer::{BridgeInterface, ProposalStatus, RelayerBalance};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+/// In-memory Ethereum node. Clones share the same chain state so the test can keep a copy
+/// to inject events after handing the client over to the listener.
+#[derive(Clone)]
+pub struct InMemoryEthereumRpcClient {
+    state: Arc<Mutex<EthereumChainState>>,
+}
+
+struct EthereumChainState {
+    chain_id: u64,
+    block_number: u64,
+    logs: HashMap<u64, Vec<Log>>,
+    code: HashMap<Address, Bytes>,
+}
+
+impl InMemoryEthereumRpcClient {
+    pub fn new(chain_id: u64) -> Self {
+        Self {
+            state: Arc::new(Mutex::new(EthereumChainState {
+                chain_id,
+                block_number: 0,
+                logs: HashMap::new(),
+                code: HashMap::new(),
+            })),
+        }
+    }
+
+    pub fn set_block_number(&self, block_number: u64) {
+        self.state.lock().unwrap().block_number = block_number;
+    }
+
+    pub fn deploy(&self, address: Address, code: Bytes) {
+        self.state.lock().unwrap().code.insert(address, code);
+    }
+
+    /// Emits bridge `Deposit` event from `bridge_address` in `block_num`
+    pub fn deposit(
+        &self,
+        block_num: u64,
+        bridge_address: Address,
+        destination_domain_id: u8,
+        resource_id: [u8; 32],
+        nonce: u64,
+        data: Vec<u8>,
+    ) {
+        let mut state = self.state.lock().unwrap();
+        let block_logs = state.logs.entry(block_num).or_default();
+        let log_idx = block_logs.len() as u64;
+        block_logs.push(Log {
+            id: LogId::new(block_num, log_idx, log_idx),
+            address: bridge_address,
+            topics: vec![keccak256(EVENT_TOPIC.as_bytes())],
+            data: Bytes::from(
+                DynSolValue::Tuple(vec![
+                    DynSolValue::Uint(U256::from(destination_domain_id), 8),
+                    DynSolValue::FixedBytes(B256::from(resource_id), 32),
+                    DynSolValue::Uint(U256::from(nonce), 64),
+                    DynSolValue::Bytes(data),
+                    DynSolValue::Bytes(vec![]),
+                ])
+                .abi_encode_params(),
+            ),
+            tx_hash: Some(Self::tx_hash(block_num, log_idx)),
+        });
+    }
+
+    fn block_hash(block_num: u64) -> B256 {
+        keccak256(block_num.to_be_bytes())
+    }
+
+    /// Hash of the transaction which emitted `log_idx` log of `block_num`
+    pub fn tx_hash(block_num: u64, log_idx: u64) -> [u8; 32] {
+        keccak256([block_num.to_be_bytes(), log_idx.to_be_bytes()].concat()).0
+    }
+}
+
+#[async_trait]
+impl EthereumRpcClient for InMemoryEthereumRpcClient {
+    async fn get_block_number(&self) -> Result<u64, ()> {
+        Ok(self.state.lock().unwrap().block_number)
+    }
+
+    // behaves like dev chains without the `finalized` tag
+    async fn get_finalized_block_number(&self) -> Result<Option<u64>, FinalizedBlockError> {
+        Err(FinalizedBlockError::Unsupported)
+    }
+
+    async fn get_block_logs(&self, block_number: u64, addresses: Vec<Address>, _event: &str) -> Result<Vec<Log>, ()> {
+        Ok(self
+            .state
+            .lock()
+            .unwrap()
+            .logs
+            .get(&block_number)
+            .map(|logs| logs.iter().filter(|log| addresses.contains(&log.address)).cloned().collect())
+            .unwrap_or_default())
+    }
+
+    async fn get_block_header(&self, block_number: u64) -> Result<Option<BlockHeader>, ()> {
+        if block_number > self.state.lock().unwrap().block_number {
+            return Ok(None);
+        }
+        Ok(Some(BlockHeader {
+            hash: Self::block_hash(block_number),
+            parent_hash: block_number.checked_sub(1).map(Self::block_hash).unwrap_or_default(),
+        }))
+    }
+
+    // one block every 12 seconds since unix epoch
+    async fn get_block_timestamp(&self, block_number: u64) -> Result<Option<u64>, ()> {
+        if block_number > self.state.lock().unwrap().block_number {
+            return Ok(None);
+        }
+        Ok(Some(block_number * 12))
+    }
+
+    async fn get_chain_id(&self) -> Result<u64, ()> {
+        Ok(self.state.lock().unwrap().chain_id)
+    }
+
+    async fn get_code(&self, address: Address) -> Result<Bytes, ()> {
+        Ok(self.state.lock().unwrap().code.get(&address).cloned().unwrap_or_default())
+    }
+}
+
+/// Builds ERC20Handler deposit data: amount, recipient length and recipient, each padded to 32 bytes
+pub fn deposit_data(amount: u128, recipient: &[u8]) -> Vec<u8> {
+    encode_deposit_data(amount, recipient).unwrap()
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct ProposalVote {
+    pub domain_id: u8,
+    pub deposit_nonce: u64,
+    pub resource_id: FixedBytes<32>,
+    pub call_data: Bytes,
+}
+
+/// In-memory bridge contract, records proposal votes instead of sending transactions.
+#[derive(Clone, Default)]
+pub struct InMemoryBridge {
+    votes: Arc<Mutex<Vec<ProposalVote>>>,
+}
+
+impl InMemoryBridge {
+    pub fn votes(&self) -> Vec<ProposalVote> {
+        self.votes.lock().unwrap().clone()
+    }
+}
+
+#[async_trait]
+impl BridgeInterface for InMemoryBridge {
+    async fn vote_proposal(
+        &self,
+        domain_id: u8,
+        deposit_nonce: u64,
+        resource_id: FixedBytes<32>,
+        call_data: Bytes,
+    ) -> Result<(), RelayError> {
+        let mut votes = self.votes.lock().unwrap();
+        if votes
+            .iter()
+            .any(|vote| vote.domain_id == domain_id && vote.deposit_nonce == deposit_nonce)
+        {
+            return Err(RelayError::AlreadyRelayed);
+        }
+        votes.push(ProposalVote { domain_id, deposit_nonce, resource_id, call_data });
+        Ok(())
+    }
+
+    // proposals are executed by the vote, so they are never `Passed` and execution reverts
+    async fn execute_proposal(
+        &self,
+        _domain_id: u8,
+        _deposit_nonce: u64,
+        _call_data: Bytes,
+        _resource_id: FixedBytes<32>,
+    ) -> Result<(), RelayError> {
+        Err(RelayError::AlreadyRelayed)
+    }
+
+    async fn is_registered_relayer(&self) -> Result<bool, ()> {
+        Ok(true)
+    }
+
+    // relayers under test are not pinned to a chain
+    async fn get_chain_id(&self) -> Result<u64, ()> {
+        Ok(1)
+    }
+
+    async fn is_paused(&self) -> Result<bool, ()> {
+        Ok(false)
+    }
+
+    // behaves like a bridge with threshold of one vote, so voted proposals are executed
+    async fn get_proposal_status(
+        &self,
+        domain_id: u8,
+        deposit_nonce: u64,
+        _resource_id: FixedBytes<32>,
+        _call_data: Bytes,
+    ) -> Result<ProposalStatus, ()> {
+        let executed = self
+            .votes
+            .lock()
+            .unwrap()
+            .iter()
+            .any(|vote| vote.domain_id == domain_id && vote.deposit_nonce == deposit_nonce);
+        Ok(if executed { ProposalStatus::Executed } else { ProposalStatus::Inactive })
+    }
+}
+
+#[async_trait]
+impl RelayerBalance for InMemoryBridge {
+    async fn get_balance(&self) -> Result<U256, ()> {
+        Ok(U256::ZERO)
+    }
+}
@@ -0,0 +1,275 @@
+// Copyright 2020-2024 Trust Computing GmbH.
+// This file is part of Litentry.
+//
+// Litentry is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// Litentry is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with Litentry.  If not, see <https://www.gnu.org/licenses/>.
+
+//! In-memory implementations of chain clients and relay sinks, used to run listeners and relayers
+//! end to end without a node.
+
+use bridge_core::fetcher::{BlockPayInEventsFetcher, LastFinalizedBlockNumFetcher, ReorgDetector};
+use bridge_core::listener::{Listener, RELAY_MAX_ATTEMPTS};
+use bridge_core::relay::{Relay, Relayer};
+use bridge_core::sync_checkpoint_repository::{Checkpoint, InMemoryCheckpointRepository};
+use ethereum::InMemoryEthereumRpcClient;
+use ethereum_listener::fetcher::Fetcher as EthereumFetcher;
+use ethereum_listener::listener::EthereumListener;
+use ethereum_listener::primitives::SyncCheckpoint as EthereumSyncCheckpoint;
+use std::collections::HashMap;
+use std::fmt::Debug;
+use std::hash::Hash;
+use std::sync::Arc;
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant};
+use substrate::InMemorySubstrateRpcClient;
+use substrate_listener::fetcher::Fetcher as SubstrateFetcher;
+use substrate_listener::listener::SubstrateListener;
+use substrate_listener::primitives::SyncCheckpoint as SubstrateSyncCheckpoint;
+use tokio::runtime::Handle;
+use tokio::sync::oneshot;
+
+pub mod ethereum;
+pub mod substrate;
+
+pub type InMemoryEthereumListener =
+    EthereumListener<InMemoryEthereumRpcClient, InMemoryCheckpointRepository<EthereumSyncCheckpoint>>;
+
+pub type InMemorySubstrateListener = SubstrateListener<
+    InMemorySubstrateRpcClient,
+    InMemorySubstrateRpcClient,
+    InMemoryCheckpointRepository<SubstrateSyncCheckpoint>,
+>;
+
+/// Listener running in dedicated thread, stopped on drop.
+pub struct RunningListener {
+    stop_signal: Option<oneshot::Sender<()>>,
+    thread: Option<JoinHandle<Result<(), ()>>>,
+}
+
+impl RunningListener {
+    /// Starts syncing `listener` in dedicated thread.
+    pub fn spawn<DestinationId, Fetcher, CheckpointT, PayInEventId>(
+        mut listener: Listener<
+            DestinationId,
+            Fetcher,
+            CheckpointT,
+            InMemoryCheckpointRepository<CheckpointT>,
+            PayInEventId,
+        >,
+        stop_signal: oneshot::Sender<()>,
+    ) -> Self
+    where
+        DestinationId: Hash + Eq + Clone + Debug + ToString + Send + Sync + 'static,
+        PayInEventId: Into<CheckpointT> + Clone + Debug + Send + 'static,
+        Fetcher: LastFinalizedBlockNumFetcher
+            + BlockPayInEventsFetcher<PayInEventId, DestinationId>
+            + ReorgDetector
+            + Send
+            + 'static,
+        CheckpointT: PartialOrd + Checkpoint + From<u64> + Clone + Send + 'static,
+    {
+        let thread = thread::spawn(move || listener.sync());
+        Self { stop_signal: Some(stop_signal), thread: Some(thread) }
+    }
+
+    /// Stops the listener and returns result of its sync loop.
+    #[allow(clippy::result_unit_err)]
+    pub fn stop(mut self) -> Result<(), ()> {
+        self.stop_inner()
+    }
+
+    fn stop_inner(&mut self) -> Result<(), ()> {
+        if let Some(stop_signal) = self.stop_signal.take() {
+            let _ = stop_signal.send(());
+        }
+        match self.thread.take() {
+            Some(thread) => thread.join().map_err(|_| ())?,
+            None => Ok(()),
+        }
+    }
+}
+
+impl Drop for RunningListener {
+    fn drop(&mut self) {
+        let _ = self.stop_inner();
+    }
+}
+
+/// Creates ethereum listener syncing `client` from `start_block`, relaying to `relayers` keyed by destination id.
+pub fn ethereum_listener(
+    handle: Handle,
+    client: InMemoryEthereumRpcClient,
+    bridge_contract_address: alloy::primitives::Address,
+    start_block: u64,
+    chain_id: u32,
+    relayers: HashMap<String, Arc<Box<dyn Relayer<String>>>>,
+) -> (InMemoryEthereumListener, oneshot::Sender<()>) {
+    let (stop_sender, stop_receiver) = oneshot::channel();
+    let fetcher = EthereumFetcher::new(0, client, [bridge_contract_address].into());
+    let listener = Listener::new(
+        "ethereum",
+        handle,
+        fetcher,
+        Relay::Multi(
+            relayers
+                .into_iter()
+                .map(|(destination_id, relayer)| (destination_id, vec![relayer]))
+                .collect(),
+        ),
+        stop_receiver,
+        InMemoryCheckpointRepository::new(None),
+        start_block,
+        chain_id,
+        RELAY_MAX_ATTEMPTS,
+    )
+    .unwrap();
+    (listener, stop_sender)
+}
+
+/// Creates substrate listener syncing `client` from `start_block`, relaying to `relayers` keyed by destination id.
+pub fn substrate_listener(
+    handle: Handle,
+    client: InMemorySubstrateRpcClient,
+    start_block: u64,
+    chain_id: u32,
+    relayers: HashMap<String, Arc<Box<dyn Relayer<String>>>>,
+) -> (InMemorySubstrateListener, oneshot::Sender<()>) {
+    let (stop_sender, stop_receiver) = oneshot::channel();
+    let listener = Listener::new(
+        "substrate",
+        handle,
+        SubstrateFetcher::new(client),
+        Relay::Multi(
+            relayers
+                .into_iter()
+                .map(|(destination_id, relayer)| (destination_id, vec![relayer]))
+                .collect(),
+        ),
+        stop_receiver,
+        InMemoryCheckpointRepository::new(None),
+        start_block,
+        chain_id,
+        RELAY_MAX_ATTEMPTS,
+    )
+    .unwrap();
+    (listener, stop_sender)
+}
+
+/// Polls `condition` until it holds or `timeout` elapses.
+pub fn wait_until(timeout: Duration, condition: impl Fn() -> bool) -> bool {
+    let deadline = Instant::now() + timeout;
+    while Instant::now() < deadline {
+        if condition() {
+            return true;
+        }
+        thread::sleep(Duration::from_millis(10));
+    }
+    condition()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ethereum::{deposit_data, InMemoryBridge};
+    use crate::substrate::{InMemoryExtrinsicSubmitter, PayOutRequestRecord, RecordingPayOutRequestCallFactory};
+    use alloy::primitives::{Address, Bytes, U160};
+    use bridge_core::relay_stats::RelayStats;
+    use ethereum_relayer::EthereumRelayer;
+    use substrate_relayer::{LocalPayOutRequestCallFactory, SubstrateRelayer};
+    use subxt::utils::AccountId32;
+
+    // keeps the listeners in catch up mode, so they react to stop signal without sleeping
+    const CHAIN_HEAD: u64 = u32::MAX as u64;
+    const TIMEOUT: Duration = Duration::from_secs(5);
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn ethereum_deposit_is_relayed_to_substrate() {
+        let bridge_contract_address = Address::from(U160::from(150));
+        let ethereum = InMemoryEthereumRpcClient::new(1);
+        ethereum.deploy(bridge_contract_address, Bytes::from(vec![0x60, 0x80]));
+        ethereum.set_block_number(CHAIN_HEAD);
+        ethereum.deposit(1, bridge_contract_address, 2, [1; 32], 1, deposit_data(100, &[7; 32]));
+
+        let payout_request_call_factory = RecordingPayOutRequestCallFactory::new(LocalPayOutRequestCallFactory {});
+        let records = payout_request_call_factory.records();
+        let submitter = InMemoryExtrinsicSubmitter::default();
+        let relayer: Box<dyn Relayer<String>> =
+            Box::new(SubstrateRelayer::new("02".to_string(), payout_request_call_factory, submitter.clone()));
+
+        let relay_stats = Arc::new(RelayStats::in_memory("ethereum"));
+        let (listener, stop_signal) = ethereum_listener(
+            Handle::current(),
+            ethereum,
+            bridge_contract_address,
+            1,
+            0,
+            HashMap::from([("02".to_string(), Arc::new(relayer))]),
+        );
+        let running = RunningListener::spawn(listener.with_relay_stats(relay_stats.clone()), stop_signal);
+
+        assert!(wait_until(TIMEOUT, || submitter.submitted() == 1));
+        assert!(running.stop().is_ok());
+        assert_eq!(
+            relay_stats.snapshot()["02"].last_source_ref,
+            Some(format!("0x{}", hex::encode(InMemoryEthereumRpcClient::tx_hash(1, 0))))
+        );
+        assert_eq!(
+            *records.lock().unwrap(),
+            vec![PayOutRequestRecord {
+                amount: 100,
+                nonce: 1,
+                resource_id: [1; 32],
+                account: AccountId32::from([7; 32]),
+                chain_id: 0,
+                asset: None,
+            }]
+        );
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn substrate_pay_in_is_relayed_to_ethereum() {
+        let substrate = InMemorySubstrateRpcClient::default();
+        substrate.set_finalized_block_num(CHAIN_HEAD);
+        // scale encoded `ChainType::Ethereum(0)`
+        let dest_chain = vec![1, 0, 0, 0, 0];
+        substrate.pay_in(1, 100, 1, [1; 32], vec![7; 20], dest_chain);
+
+        let bridge = InMemoryBridge::default();
+        let relayer: Box<dyn Relayer<String>> = Box::new(
+            EthereumRelayer::new("ethereum".to_string(), "0x00".to_string(), bridge.clone(), "0100000000".to_string())
+                .await
+                .unwrap(),
+        );
+
+        let relay_stats = Arc::new(RelayStats::in_memory("substrate"));
+        let (listener, stop_signal) = substrate_listener(
+            Handle::current(),
+            substrate,
+            1,
+            0,
+            HashMap::from([("0100000000".to_string(), Arc::new(relayer))]),
+        );
+        let running = RunningListener::spawn(listener.with_relay_stats(relay_stats.clone()), stop_signal);
+
+        assert!(wait_until(TIMEOUT, || bridge.votes().len() == 1));
+        assert!(running.stop().is_ok());
+        assert_eq!(
+            relay_stats.snapshot()["0100000000"].last_source_ref,
+            Some(format!("0x{}", hex::encode(InMemorySubstrateRpcClient::extrinsic_hash(1, 0))))
+        );
+        let vote = &bridge.votes()[0];
+        assert_eq!(vote.domain_id, 0);
+        assert_eq!(vote.deposit_nonce, 1);
+        assert_eq!(vote.call_data[64..84], [7; 20]);
+    }
+}
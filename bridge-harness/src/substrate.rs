@@ -0,0 +1,219 @@
+// Copyright 2020-2024 Trust Computing GmbH.
+// This file is part of Litentry.
+//
+// Litentry is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// Litentry is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with Litentry.  If not, see <https://www.gnu.org/licenses/>.
+
+use async_trait::async_trait;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use substrate_listener::primitives::EventId;
+use substrate_listener::rpc_client::{
+    BlockEvent, PaidInEvent, PaidOutEvent, SubstrateRpcClient, SubstrateRpcClientFactory,
+};
+use substrate_relayer::asset::PayOutAsset;
+use substrate_relayer::submit_failure::SubmitFailure;
+use substrate_relayer::{ExtrinsicSubmitter, InvalidPayOutAmount, PayOutRequestCallFactory};
+use subxt::utils::AccountId32;
+
+#[derive(Clone)]
+struct PaidIn {
+    amount: u128,
+    nonce: u64,
+    resource_id: [u8; 32],
+    dest_account: Vec<u8>,
+    dest_chain: Vec<u8>,
+}
+
+/// In-memory Substrate node. Clones share the same chain state.
+#[derive(Clone, Default)]
+pub struct InMemorySubstrateRpcClient {
+    state: Arc<Mutex<SubstrateChainState>>,
+}
+
+#[derive(Default)]
+struct SubstrateChainState {
+    finalized_block_num: u64,
+    events: HashMap<u64, Vec<PaidIn>>,
+}
+
+impl InMemorySubstrateRpcClient {
+    pub fn set_finalized_block_num(&self, block_num: u64) {
+        self.state.lock().unwrap().finalized_block_num = block_num;
+    }
+
+    /// Hash of the extrinsic which emitted `index` pay in of `block_num`
+    pub fn extrinsic_hash(block_num: u64, index: u64) -> [u8; 32] {
+        let mut hash = [0; 32];
+        hash[..8].copy_from_slice(&block_num.to_be_bytes());
+        hash[8..16].copy_from_slice(&index.to_be_bytes());
+        hash
+    }
+
+    /// Emits omni-bridge `PaidIn` event in `block_num`. `dest_chain` is scale encoded `ChainType`.
+    pub fn pay_in(
+        &self,
+        block_num: u64,
+        amount: u128,
+        nonce: u64,
+        resource_id: [u8; 32],
+        dest_account: Vec<u8>,
+        dest_chain: Vec<u8>,
+    ) {
+        self.state.lock().unwrap().events.entry(block_num).or_default().push(PaidIn {
+            amount,
+            nonce,
+            resource_id,
+            dest_account,
+            dest_chain,
+        });
+    }
+}
+
+#[async_trait]
+impl SubstrateRpcClient for InMemorySubstrateRpcClient {
+    async fn get_last_finalized_block_num(&mut self) -> Result<u64, ()> {
+        Ok(self.state.lock().unwrap().finalized_block_num)
+    }
+
+    async fn get_block_pay_in_events(&mut self, block_num: u64) -> Result<Vec<BlockEvent<PaidInEvent>>, ()> {
+        Ok(self
+            .state
+            .lock()
+            .unwrap()
+            .events
+            .get(&block_num)
+            .cloned()
+            .unwrap_or_default()
+            .into_iter()
+            .enumerate()
+            .map(|(i, event)| {
+                BlockEvent::new(
+                    EventId::new(block_num, i as u64),
+                    PaidInEvent {
+                        amount: event.amount,
+                        nonce: event.nonce,
+                        resource_id: event.resource_id,
+                        data: event.dest_account,
+                        dest_chain: event.dest_chain,
+                    },
+                )
+                .with_extrinsic_hash(Some(Self::extrinsic_hash(block_num, i as u64)))
+            })
+            .collect())
+    }
+
+    async fn get_block_paid_out_events(&mut self, _block_num: u64) -> Result<Vec<BlockEvent<PaidOutEvent>>, ()> {
+        Ok(vec![])
+    }
+
+    // one block every 6 seconds since unix epoch
+    async fn get_block_timestamp(&mut self, block_num: u64) -> Result<Option<u64>, ()> {
+        Ok(Some(block_num * 6))
+    }
+
+    async fn get_genesis_hash(&mut self) -> Result<Vec<u8>, ()> {
+        Ok(vec![0; 32])
+    }
+
+    async fn get_spec_name(&mut self) -> Result<String, ()> {
+        Ok("in-memory".to_string())
+    }
+}
+
+#[async_trait]
+impl SubstrateRpcClientFactory<InMemorySubstrateRpcClient> for InMemorySubstrateRpcClient {
+    async fn new_client(&self) -> Result<InMemorySubstrateRpcClient, ()> {
+        Ok(self.clone())
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct PayOutRequestRecord {
+    pub amount: u128,
+    pub nonce: u64,
+    pub resource_id: [u8; 32],
+    pub account: AccountId32,
+    pub chain_id: u32,
+    pub asset: Option<PayOutAsset>,
+}
+
+/// Wraps chain specific `PayOutRequestCallFactory` and records every requested pay out.
+pub struct RecordingPayOutRequestCallFactory<F: PayOutRequestCallFactory> {
+    inner: F,
+    records: Arc<Mutex<Vec<PayOutRequestRecord>>>,
+}
+
+impl<F: PayOutRequestCallFactory> RecordingPayOutRequestCallFactory<F> {
+    pub fn new(inner: F) -> Self {
+        Self { inner, records: Arc::new(Mutex::new(vec![])) }
+    }
+
+    pub fn records(&self) -> Arc<Mutex<Vec<PayOutRequestRecord>>> {
+        self.records.clone()
+    }
+}
+
+impl<F: PayOutRequestCallFactory> PayOutRequestCallFactory for RecordingPayOutRequestCallFactory<F> {
+    type PayOutRequestCallType = F::PayOutRequestCallType;
+    type RelayerStorageAddress = F::RelayerStorageAddress;
+
+    const MAX_AMOUNT: u128 = F::MAX_AMOUNT;
+
+    fn create(
+        &self,
+        amount: u128,
+        nonce: u64,
+        resource_id: [u8; 32],
+        account: AccountId32,
+        chain_id: u32,
+        asset: Option<PayOutAsset>,
+    ) -> Result<Self::PayOutRequestCallType, InvalidPayOutAmount> {
+        self.records.lock().unwrap().push(PayOutRequestRecord {
+            amount,
+            nonce,
+            resource_id,
+            account: account.clone(),
+            chain_id,
+            asset,
+        });
+        self.inner.create(amount, nonce, resource_id, account, chain_id, asset)
+    }
+
+    fn relayer_storage_address(&self, account: AccountId32) -> Self::RelayerStorageAddress {
+        self.inner.relayer_storage_address(account)
+    }
+}
+
+/// Accepts every extrinsic without talking to a node.
+#[derive(Clone, Default)]
+pub struct InMemoryExtrinsicSubmitter {
+    submitted: Arc<Mutex<usize>>,
+}
+
+impl InMemoryExtrinsicSubmitter {
+    pub fn submitted(&self) -> usize {
+        *self.submitted.lock().unwrap()
+    }
+}
+
+#[async_trait]
+impl<Call: Sync> ExtrinsicSubmitter<Call> for InMemoryExtrinsicSubmitter {
+    // account nonce follows the number of submitted extrinsics
+    async fn submit(&self, _call: &Call, min_nonce: Option<u64>) -> Result<u64, SubmitFailure> {
+        let mut submitted = self.submitted.lock().unwrap();
+        let nonce = min_nonce.map_or(*submitted as u64, |min_nonce| min_nonce.max(*submitted as u64));
+        *submitted += 1;
+        Ok(nonce)
+    }
+}
@@ -14,7 +14,12 @@
 // You should have received a copy of the GNU General Public License
 // along with Litentry.  If not, see <https://www.gnu.org/licenses/>.
 
+use bridge_worker::key_import::{load_manifest, RelayerKeyEntry, RelayerKind};
+use bridge_worker::logging::LogFormat;
+use bridge_worker::rpc::auth::AuthScheme;
+use bridge_worker::shielding_key::{PublicKeyEncoding, DEFAULT_SHIELDING_KEY_BITS};
 use clap::{Args, Parser, Subcommand};
+use std::net::SocketAddr;
 
 pub const SHIELDING_KEY_PATH: &str = "shielding_key.bin";
 pub const AUTH_KEY_SEED_PATH: &str = "auth_key_seed.bin";
@@ -28,6 +33,9 @@ pub const ETHEREUM_RELAYER_KEY_PATH: &str = "ethereum_relayer_key.bin";
 pub struct Cli {
     #[command(subcommand)]
     pub command: Commands,
+
+    #[arg(long, global = true, value_enum, default_value_t = LogFormat::Text)]
+    pub log_format: LogFormat,
 }
 
 #[derive(Subcommand)]
@@ -45,6 +53,9 @@ pub enum Commands {
 pub struct GenerateArgs {
     #[arg(short, long, value_name = "generate folder path")]
     pub generate_path: Option<String>,
+
+    #[arg(long, value_enum, default_value_t = AuthScheme::Ecdsa)]
+    pub scheme: AuthScheme,
 }
 
 #[derive(Args)]
@@ -52,23 +63,73 @@ pub struct RunArgs {
     #[arg(short, long, default_value = "keystore", value_name = "keystore folder path")]
     pub keystore_dir: String,
 
-    #[arg(short, long, default_value = "config.json", value_name = "bridge config file path")]
+    #[arg(
+        short,
+        long,
+        default_value = "config.json",
+        value_name = "bridge config file path (.json, .yaml, .yml or .toml)"
+    )]
     pub config: String,
 
+    /// Start block override as `<listener id>:<block>`, or `<listener id>:-<blocks>` to start given number of
+    /// blocks behind the finalized head. Checked against the finalized head and listener's `deployed_at_block`
     #[arg(short, long, value_name = "listeners start block")]
     pub start_block: Vec<String>,
 
+    /// Start listeners even if their start block is beyond the finalized head or before `deployed_at_block`
+    #[arg(long)]
+    pub force: bool,
+
     #[arg(short, long, default_value = "9090", value_name = "metrics port")]
     pub metrics_port: String,
+
+    /// Port of the HTTP server serving listeners' sync status on `GET /status`
+    #[arg(long, default_value = "9091", value_name = "status port")]
+    pub status_port: String,
+
+    /// Address of the admin JSON-RPC server (e.g. listener restarts), disabled if not set
+    #[arg(long, value_name = "addr:port")]
+    pub admin_rpc_address: Option<SocketAddr>,
+
+    #[arg(long, default_value = AUTH_KEY_PUB_PATH)]
+    pub auth_pub_key_path: String,
+
+    /// Address of the key import JSON-RPC server, consider binding to 127.0.0.1 as it handles key material
+    #[arg(long, default_value = "0.0.0.0:2000", value_name = "addr:port")]
+    pub rpc_bind: SocketAddr,
+
+    /// Stop listeners once they are synced to the current finalized block, worker exits when all of them stopped
+    #[arg(long, alias = "once")]
+    pub catch_up_only: bool,
+
+    /// Run listeners as tasks of a shared runtime instead of a dedicated thread each, suited for many chains
+    #[arg(long)]
+    pub shared_runtime: bool,
 }
 
 #[derive(Args)]
 pub struct ImportArgs {
+    /// Relayer key to import, can be repeated
+    #[arg(long = "relayer", value_name = "id=<id>,type=<ethereum|substrate>,key_path=<path>")]
+    pub relayers: Vec<RelayerKeyEntry>,
+
+    /// JSON or YAML file listing relayer keys to import under `relayers`
+    #[arg(long, value_name = "manifest file path")]
+    pub manifest: Option<String>,
+
+    /// Bridge config the relayer ids and types are checked against
+    #[arg(long, value_name = "bridge config file path (.json, .yaml, .yml or .toml)")]
+    pub config: Option<String>,
+
+    /// Writes all import commands to a shell script as well
+    #[arg(long, value_name = "script path")]
+    pub out: Option<String>,
+
     #[arg(long)]
-    pub substrate_id: String,
+    pub substrate_id: Option<String>,
 
     #[arg(long)]
-    pub ethereum_id: String,
+    pub ethereum_id: Option<String>,
 
     #[arg(long, default_value = SUBSTRATE_RELAYER_KEY_PATH)]
     pub substrate_relayer_key_path: String,
@@ -79,10 +140,40 @@ pub struct ImportArgs {
     #[arg(long, default_value = AUTH_KEY_SEED_PATH)]
     pub auth_key_path: String,
 
+    /// Signature scheme of the auth key
+    #[arg(long, value_enum, default_value_t = AuthScheme::Ecdsa)]
+    pub scheme: AuthScheme,
+
     #[arg(long, default_value = SHIELDING_KEY_PATH)]
     pub shielding_key_path: String,
 }
 
+impl ImportArgs {
+    /// Relayer keys from `--relayer`, the manifest and legacy `--substrate-id` / `--ethereum-id` arguments
+    pub fn relayer_keys(&self) -> Result<Vec<RelayerKeyEntry>, String> {
+        let mut entries = vec![];
+        if let Some(ref id) = self.substrate_id {
+            entries.push(RelayerKeyEntry {
+                id: id.clone(),
+                relayer_type: RelayerKind::Substrate,
+                key_path: self.substrate_relayer_key_path.clone(),
+            });
+        }
+        if let Some(ref id) = self.ethereum_id {
+            entries.push(RelayerKeyEntry {
+                id: id.clone(),
+                relayer_type: RelayerKind::Ethereum,
+                key_path: self.ethereum_relayer_key_path.clone(),
+            });
+        }
+        entries.extend(self.relayers.iter().cloned());
+        if let Some(ref manifest) = self.manifest {
+            entries.extend(load_manifest(manifest)?);
+        }
+        Ok(entries)
+    }
+}
+
 #[derive(Args)]
 pub struct AwaitImportArgs {
     #[arg(short, long, default_value = "keystore", value_name = "keystore folder path")]
@@ -90,4 +181,89 @@ pub struct AwaitImportArgs {
 
     #[arg(long, default_value = AUTH_KEY_PUB_PATH)]
     pub auth_pub_key_path: String,
+
+    /// Address of the key import JSON-RPC server, consider binding to 127.0.0.1 as it handles key material
+    #[arg(long, default_value = "0.0.0.0:2000", value_name = "addr:port")]
+    pub rpc_bind: SocketAddr,
+
+    /// Size of the RSA key imported relayer keys are shielded with
+    #[arg(long, default_value_t = DEFAULT_SHIELDING_KEY_BITS)]
+    pub shielding_key_bits: usize,
+
+    /// Also writes shielding public key to given file as SPKI, for use with openssl or other RSA tooling.
+    /// The file is re-written whenever the key is rotated with `hm_rotateShieldingKey`
+    #[arg(long, value_name = "file path")]
+    pub shielding_key_out: Option<String>,
+
+    #[arg(long, value_enum, default_value_t = PublicKeyEncoding::Pem, requires = "shielding_key_out")]
+    pub shielding_key_format: PublicKeyEncoding,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn import_args(args: &[&str]) -> ImportArgs {
+        let cli = Cli::try_parse_from([&["bridge-worker", "build-keystore-import"][..], args].concat()).unwrap();
+        match cli.command {
+            Commands::BuildKeystoreImport(args) => args,
+            _ => panic!("unexpected command"),
+        }
+    }
+
+    #[test]
+    pub fn relayer_argument_can_be_repeated() {
+        let args = import_args(&[
+            "--relayer",
+            "id=ethereum_sepolia,type=ethereum,key_path=sepolia.bin",
+            "--relayer",
+            "id=ethereum_bsc,type=ethereum,key_path=bsc.bin",
+            "--substrate-id",
+            "heima",
+        ]);
+
+        let ids: Vec<_> = args
+            .relayer_keys()
+            .unwrap()
+            .into_iter()
+            .map(|entry| (entry.id, entry.key_path))
+            .collect();
+        assert_eq!(
+            ids,
+            vec![
+                ("heima".to_string(), SUBSTRATE_RELAYER_KEY_PATH.to_string()),
+                ("ethereum_sepolia".to_string(), "sepolia.bin".to_string()),
+                ("ethereum_bsc".to_string(), "bsc.bin".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    pub fn malformed_relayer_argument_is_rejected() {
+        assert!(Cli::try_parse_from(["bridge-worker", "build-keystore-import", "--relayer", "id=heima"]).is_err());
+    }
+
+    #[test]
+    pub fn shielding_key_format_requires_output_file() {
+        assert!(
+            Cli::try_parse_from(["bridge-worker", "await-keystore-import", "--shielding-key-format", "der"]).is_err()
+        );
+
+        let cli = Cli::try_parse_from([
+            "bridge-worker",
+            "await-keystore-import",
+            "--shielding-key-out",
+            "shielding_key.der",
+            "--shielding-key-format",
+            "der",
+        ])
+        .unwrap();
+        match cli.command {
+            Commands::AwaitKeystoreImport(args) => {
+                assert_eq!(args.shielding_key_out.as_deref(), Some("shielding_key.der"));
+                assert_eq!(args.shielding_key_format, PublicKeyEncoding::Der);
+            },
+            _ => panic!("unexpected command"),
+        }
+    }
 }
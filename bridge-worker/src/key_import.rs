@@ -0,0 +1,240 @@
+// Copyright 2020-2024 Trust Computing GmbH.
+// This file is part of Litentry.
+//
+// Litentry is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// Litentry is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with Litentry.  If not, see <https://www.gnu.org/licenses/>.
+
+//! Relayer keys handled by a single keystore import ceremony.
+
+use crate::keystore::KeyType;
+use bridge_core::config::BridgeConfig;
+use serde::Deserialize;
+use std::collections::HashSet;
+use std::fmt::{Display, Formatter};
+use std::path::Path;
+use std::str::FromStr;
+
+#[derive(Clone, Copy, Debug, Deserialize, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum RelayerKind {
+    Ethereum,
+    Substrate,
+}
+
+impl FromStr for RelayerKind {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "ethereum" => Ok(RelayerKind::Ethereum),
+            "substrate" => Ok(RelayerKind::Substrate),
+            _ => Err(format!("Unknown relayer type: {}, expected ethereum or substrate", s)),
+        }
+    }
+}
+
+impl RelayerKind {
+    pub fn key_type(&self) -> KeyType {
+        match self {
+            RelayerKind::Ethereum => KeyType::Ecdsa,
+            RelayerKind::Substrate => KeyType::Sr25519,
+        }
+    }
+}
+
+impl Display for RelayerKind {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            RelayerKind::Ethereum => write!(f, "ethereum"),
+            RelayerKind::Substrate => write!(f, "substrate"),
+        }
+    }
+}
+
+/// Relayer key to be imported, parsed from `id=<id>,type=<ethereum|substrate>,key_path=<path>`
+#[derive(Clone, Debug, Deserialize, PartialEq)]
+pub struct RelayerKeyEntry {
+    pub id: String,
+    #[serde(rename = "type")]
+    pub relayer_type: RelayerKind,
+    pub key_path: String,
+}
+
+impl FromStr for RelayerKeyEntry {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (mut id, mut relayer_type, mut key_path) = (None, None, None);
+        for part in s.split(',') {
+            let (key, value) = part
+                .split_once('=')
+                .ok_or_else(|| format!("Expected key=value, got: {}", part))?;
+            let slot = match key.trim() {
+                "id" => &mut id,
+                "type" => &mut relayer_type,
+                "key_path" => &mut key_path,
+                other => return Err(format!("Unknown relayer key entry field: {}", other)),
+            };
+            if slot.replace(value.trim().to_string()).is_some() {
+                return Err(format!("Duplicated relayer key entry field: {}", key.trim()));
+            }
+        }
+        Ok(RelayerKeyEntry {
+            id: id.filter(|id| !id.is_empty()).ok_or("Missing relayer id")?,
+            relayer_type: relayer_type.ok_or("Missing relayer type")?.parse()?,
+            key_path: key_path.filter(|path| !path.is_empty()).ok_or("Missing relayer key path")?,
+        })
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct Manifest {
+    relayers: Vec<RelayerKeyEntry>,
+}
+
+/// Loads relayer key entries from `relayers` list of .json, .yaml or .yml manifest
+pub fn load_manifest(path: &str) -> Result<Vec<RelayerKeyEntry>, String> {
+    let content = std::fs::read_to_string(path).map_err(|e| format!("Could not read manifest {}: {}", path, e))?;
+    let manifest: Manifest = match Path::new(path).extension().and_then(|ext| ext.to_str()) {
+        Some("json") => serde_json::from_str(&content).map_err(|e| e.to_string()),
+        Some("yaml") | Some("yml") => serde_yaml::from_str(&content).map_err(|e| e.to_string()),
+        _ => Err("expected .json, .yaml or .yml file".to_string()),
+    }
+    .map_err(|e| format!("Could not parse manifest {}: {}", path, e))?;
+    Ok(manifest.relayers)
+}
+
+/// Checks that entries are unique and, if `config` is given, that they refer to its relayers,
+/// so typos are caught before the import ceremony.
+pub fn check_entries(entries: &[RelayerKeyEntry], config: Option<&BridgeConfig>) -> Result<(), String> {
+    if entries.is_empty() {
+        return Err("No relayer keys to import".to_string());
+    }
+    let mut ids = HashSet::new();
+    for entry in entries {
+        if !ids.insert(entry.id.as_str()) {
+            return Err(format!("Relayer {} is listed more than once", entry.id));
+        }
+        if let Some(config) = config {
+            let relayer = config
+                .relayers
+                .iter()
+                .find(|relayer| relayer.id == entry.id)
+                .ok_or_else(|| format!("Relayer {} is not defined in config", entry.id))?;
+            if relayer.relayer_type != entry.relayer_type.to_string() {
+                return Err(format!(
+                    "Relayer {} is configured as {}, not {}",
+                    entry.id, relayer.relayer_type, entry.relayer_type
+                ));
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Shell script running all import commands, stops on the first failed one
+pub fn import_script(commands: &[String]) -> String {
+    let mut script = "#!/bin/sh\nset -e\n".to_string();
+    for command in commands {
+        script.push('\n');
+        script.push_str(command);
+        script.push('\n');
+    }
+    script
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(id: &str, relayer_type: RelayerKind, key_path: &str) -> RelayerKeyEntry {
+        RelayerKeyEntry { id: id.to_string(), relayer_type, key_path: key_path.to_string() }
+    }
+
+    #[test]
+    pub fn relayer_key_entry_is_parsed() {
+        assert_eq!(
+            "id=ethereum_sepolia,type=ethereum,key_path=keys/sepolia.bin".parse(),
+            Ok(entry("ethereum_sepolia", RelayerKind::Ethereum, "keys/sepolia.bin"))
+        );
+        assert_eq!(
+            "type=substrate, key_path=heima.bin, id=heima".parse(),
+            Ok(entry("heima", RelayerKind::Substrate, "heima.bin"))
+        );
+        assert!("id=heima,type=substrate".parse::<RelayerKeyEntry>().is_err());
+        assert!("id=heima,type=bitcoin,key_path=heima.bin".parse::<RelayerKeyEntry>().is_err());
+        assert!("id=heima,type=substrate,key_path=heima.bin,id=other"
+            .parse::<RelayerKeyEntry>()
+            .is_err());
+        assert!("id=heima,type=substrate,key_path=heima.bin,port=1"
+            .parse::<RelayerKeyEntry>()
+            .is_err());
+        assert!("heima".parse::<RelayerKeyEntry>().is_err());
+    }
+
+    #[test]
+    pub fn manifest_is_loaded_from_json_and_yaml() {
+        let dir = tempfile::tempdir().unwrap();
+        let json = dir.path().join("keys.json");
+        std::fs::write(
+            &json,
+            r#"{"relayers": [
+                {"id": "ethereum_sepolia", "type": "ethereum", "key_path": "sepolia.bin"},
+                {"id": "heima", "type": "substrate", "key_path": "heima.bin"}
+            ]}"#,
+        )
+        .unwrap();
+        let yaml = dir.path().join("keys.yml");
+        std::fs::write(
+            &yaml,
+            "relayers:\n  - id: ethereum_sepolia\n    type: ethereum\n    key_path: sepolia.bin\n  - id: heima\n    type: substrate\n    key_path: heima.bin\n",
+        )
+        .unwrap();
+
+        let expected = vec![
+            entry("ethereum_sepolia", RelayerKind::Ethereum, "sepolia.bin"),
+            entry("heima", RelayerKind::Substrate, "heima.bin"),
+        ];
+        assert_eq!(load_manifest(json.to_str().unwrap()).unwrap(), expected);
+        assert_eq!(load_manifest(yaml.to_str().unwrap()).unwrap(), expected);
+
+        let toml = dir.path().join("keys.toml");
+        std::fs::write(&toml, "").unwrap();
+        assert!(load_manifest(toml.to_str().unwrap()).is_err());
+    }
+
+    #[test]
+    pub fn entries_are_checked_against_config() {
+        let config = BridgeConfig::from_file("../local/config.json").unwrap();
+        let ethereum = &config.relayers.iter().find(|r| r.relayer_type == "ethereum").unwrap().id;
+        let substrate = &config.relayers.iter().find(|r| r.relayer_type == "substrate").unwrap().id;
+
+        let entries = vec![
+            entry(ethereum, RelayerKind::Ethereum, "ethereum.bin"),
+            entry(substrate, RelayerKind::Substrate, "substrate.bin"),
+        ];
+        assert!(check_entries(&entries, Some(&config)).is_ok());
+        assert!(check_entries(&[entry("typo", RelayerKind::Ethereum, "ethereum.bin")], None).is_ok());
+
+        assert!(check_entries(&[], None).is_err());
+        assert!(check_entries(&[entries[0].clone(), entries[0].clone()], None).is_err());
+        assert!(check_entries(&[entry("typo", RelayerKind::Ethereum, "ethereum.bin")], Some(&config)).is_err());
+        assert!(check_entries(&[entry(ethereum, RelayerKind::Substrate, "ethereum.bin")], Some(&config)).is_err());
+    }
+
+    #[test]
+    pub fn import_script_runs_all_commands() {
+        let script = import_script(&["curl first".to_string(), "curl second".to_string()]);
+        assert_eq!(script, "#!/bin/sh\nset -e\n\ncurl first\n\ncurl second\n");
+    }
+}
@@ -0,0 +1,65 @@
+use super::*;
+use std::collections::HashMap;
+
+// Keeps the keys in memory only, they are lost once the worker stops.
+// Useful for tests and ephemeral workers which get their keys imported on every start.
+#[derive(Default)]
+pub struct InMemoryKeystore {
+    vault: HashMap<String, Vec<u8>>,
+}
+
+impl InMemoryKeystore {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn get_key(&self, id: &str) -> Option<&Vec<u8>> {
+        self.vault.get(id)
+    }
+}
+
+impl KeyStore for InMemoryKeystore {
+    fn set_key(&mut self, id: &str, key: Vec<u8>) -> Result<()> {
+        self.vault.insert(id.to_string(), key);
+        Ok(())
+    }
+
+    fn sign_ecdsa(&self, id: &str, msg: &[u8; 32]) -> Result<sp_core::ecdsa::Signature> {
+        sign_ecdsa_with(self.vault.get(id), msg)
+    }
+
+    fn sign_sr25519(&self, id: &str, msg: &[u8]) -> Result<sp_core::sr25519::Signature> {
+        sign_sr25519_with(self.vault.get(id), msg)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    const SR25519_SEED: &str = "e5be9a5092b81bca64be81d212e7f2f9eba183bb7a90954f7b76361f6edb5c0a";
+    const MSG: [u8; 32] = [0u8; 32];
+
+    #[test]
+    fn set_key_works() {
+        let mut keystore = InMemoryKeystore::new();
+        assert!(keystore.get_key("sr25519").is_none());
+
+        keystore.set_key("sr25519", hex::decode(SR25519_SEED).unwrap()).unwrap();
+
+        assert_eq!(hex::encode(keystore.get_key("sr25519").unwrap()), SR25519_SEED);
+    }
+
+    #[test]
+    fn sign_sr25519_works() {
+        let mut keystore = InMemoryKeystore::new();
+        keystore.set_key("sr25519", hex::decode(SR25519_SEED).unwrap()).unwrap();
+
+        let public = sp_core::sr25519::Pair::from_seed_slice(&hex::decode(SR25519_SEED).unwrap())
+            .unwrap()
+            .public();
+        let sig = keystore.sign_sr25519("sr25519", &MSG).unwrap();
+        assert!(sp_core::sr25519::Pair::verify(&sig, MSG, &public));
+        assert!(matches!(keystore.sign_sr25519("unknown", &MSG), Err(Error::ParseSr25519Pair)));
+    }
+}
@@ -1,5 +1,30 @@
 mod local;
+mod memory;
 pub use local::*;
+pub use memory::*;
+
+use alloy::signers::local::PrivateKeySigner;
+use serde::{Deserialize, Serialize};
+use sp_core::crypto::Ss58Codec;
+use sp_core::Pair;
+use std::fmt::{Display, Formatter};
+
+/// Curve of relayer key, sr25519 for substrate and ecdsa (secp256k1) for ethereum relayers
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum KeyType {
+    Sr25519,
+    Ecdsa,
+}
+
+impl Display for KeyType {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            KeyType::Sr25519 => write!(f, "sr25519"),
+            KeyType::Ecdsa => write!(f, "ecdsa"),
+        }
+    }
+}
 
 #[derive(Debug, thiserror::Error)]
 pub enum Error {
@@ -15,6 +40,9 @@ pub enum Error {
     #[error("Failed to parse as SR25519 pair")]
     ParseSr25519Pair,
 
+    #[error("Invalid {0} key material")]
+    InvalidKeyMaterial(KeyType),
+
     #[error(transparent)]
     Other(#[from] Box<dyn std::error::Error + Sync + Send + 'static>),
 }
@@ -32,4 +60,38 @@ pub trait KeyStore: Send + Sync + 'static {
 
     /// Sign the `msg` with the sr25519 private key identified by `id`
     fn sign_sr25519(&self, id: &str, msg: &[u8]) -> Result<sp_core::sr25519::Signature>;
+
+    /// Check that `key` can be used by relayer with `key_type` key and return address derived from it:
+    /// SS58 encoded account for sr25519, checksummed ethereum address for ecdsa
+    fn validate(key_type: KeyType, key: &[u8]) -> Result<String>
+    where
+        Self: Sized,
+    {
+        // relayers read keys as 32 bytes secrets, shorter ecdsa keys would be silently zero padded
+        if key.len() != 32 {
+            return Err(Error::InvalidKeyMaterial(key_type));
+        }
+        match key_type {
+            KeyType::Sr25519 => sp_core::sr25519::Pair::from_seed_slice(key)
+                .map(|pair| pair.public().to_ss58check())
+                .map_err(|_| Error::InvalidKeyMaterial(key_type)),
+            KeyType::Ecdsa => PrivateKeySigner::from_slice(key)
+                .map(|signer| signer.address().to_string())
+                .map_err(|_| Error::InvalidKeyMaterial(key_type)),
+        }
+    }
+}
+
+fn sign_ecdsa_with(key: Option<&Vec<u8>>, msg: &[u8; 32]) -> Result<sp_core::ecdsa::Signature> {
+    let p = key
+        .map(|k| sp_core::ecdsa::Pair::from_seed_slice(k).map_err(|_| Error::ParseEcdsaPair))
+        .ok_or(Error::ParseEcdsaPair)??;
+    Ok(p.sign_prehashed(msg))
+}
+
+fn sign_sr25519_with(key: Option<&Vec<u8>>, msg: &[u8]) -> Result<sp_core::sr25519::Signature> {
+    let p = key
+        .map(|k| sp_core::sr25519::Pair::from_seed_slice(k).map_err(|_| Error::ParseSr25519Pair))
+        .ok_or(Error::ParseSr25519Pair)??;
+    Ok(p.sign(msg))
 }
@@ -0,0 +1,45 @@
+// Copyright 2020-2024 Trust Computing GmbH.
+// This file is part of Litentry.
+//
+// Litentry is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// Litentry is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with Litentry.  If not, see <https://www.gnu.org/licenses/>.
+
+//! Bridge worker orchestration, exposed as a library so it can be embedded in other binaries.
+//! See `BridgeWorker` for the entry point.
+
+pub mod key_import;
+pub mod keystore;
+pub mod listeners;
+pub mod logging;
+pub mod rpc;
+pub mod shielding_key;
+pub mod worker;
+
+pub use worker::{AdminRpcOptions, BridgeWorker, WorkerHandle, WorkerOptions};
+
+#[cfg(test)]
+use crate::rpc::auth::AuthSigner;
+
+#[cfg(test)]
+fn alice_signer() -> AuthSigner {
+    use sp_core::Pair;
+    let key = sp_core::ecdsa::Pair::from_string("//Alice", None).unwrap();
+    AuthSigner::Ecdsa(key.public().0)
+}
+
+#[cfg(test)]
+fn alice_sr25519_signer() -> AuthSigner {
+    use sp_core::Pair;
+    let key = sp_core::sr25519::Pair::from_string("//Alice", None).unwrap();
+    AuthSigner::Sr25519(key.public().0)
+}
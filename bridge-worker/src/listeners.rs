@@ -0,0 +1,343 @@
+// Copyright 2020-2024 Trust Computing GmbH.
+// This file is part of Litentry.
+//
+// Litentry is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// Litentry is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with Litentry.  If not, see <https://www.gnu.org/licenses/>.
+
+use bridge_core::listener::{ListenerContext, SyncListener};
+use bridge_core::listener_control::ListenerControl;
+use bridge_core::listener_status::{ListenerStatus, ListenerStatusSnapshot};
+use bridge_core::relay_stats::{DestinationStats, RelayStats};
+use bridge_core::start_block::StartBlockOverride;
+use ethereum_listener::create_listener;
+use ethereum_listener::listener::ListenerConfig as EthereumListenerConfig;
+use log::*;
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::thread::{self, JoinHandle};
+use substrate_listener::listener::ListenerConfig as SubstrateListenerConfig;
+use substrate_listener::CustomConfig;
+use tokio::runtime::Handle;
+use tokio::sync::{oneshot, Mutex};
+
+/// Creates listener of a chain not supported by the worker out of the box, e.g. provided by embedding binary.
+pub type CreateListenerFn = Arc<
+    dyn Fn(&ListenerContext<serde_json::Value>, oneshot::Receiver<()>) -> Result<Box<dyn SyncListener>, ()>
+        + Send
+        + Sync,
+>;
+
+pub enum ListenerDefinition {
+    Ethereum(ListenerContext<EthereumListenerConfig>),
+    Substrate(ListenerContext<SubstrateListenerConfig>),
+    Custom(ListenerContext<serde_json::Value>, CreateListenerFn),
+}
+
+impl ListenerDefinition {
+    pub fn id(&self) -> &str {
+        match self {
+            ListenerDefinition::Ethereum(context) => &context.id,
+            ListenerDefinition::Substrate(context) => &context.id,
+            ListenerDefinition::Custom(context, _) => &context.id,
+        }
+    }
+
+    fn control(&self) -> &ListenerControl {
+        match self {
+            ListenerDefinition::Ethereum(context) => &context.control,
+            ListenerDefinition::Substrate(context) => &context.control,
+            ListenerDefinition::Custom(context, _) => &context.control,
+        }
+    }
+
+    fn status(&self) -> &ListenerStatus {
+        match self {
+            ListenerDefinition::Ethereum(context) => &context.status,
+            ListenerDefinition::Substrate(context) => &context.status,
+            ListenerDefinition::Custom(context, _) => &context.status,
+        }
+    }
+
+    pub(crate) fn set_catch_up_only(&mut self, catch_up_only: bool) {
+        match self {
+            ListenerDefinition::Ethereum(context) => context.catch_up_only = catch_up_only,
+            ListenerDefinition::Substrate(context) => context.catch_up_only = catch_up_only,
+            ListenerDefinition::Custom(context, _) => context.catch_up_only = catch_up_only,
+        }
+    }
+
+    fn set_start_block(&mut self, start_block: u64) {
+        let start_block = Some(StartBlockOverride::Absolute(start_block));
+        match self {
+            ListenerDefinition::Ethereum(context) => context.start_block.block = start_block,
+            ListenerDefinition::Substrate(context) => context.start_block.block = start_block,
+            ListenerDefinition::Custom(context, _) => context.start_block.block = start_block,
+        }
+    }
+
+    async fn spawn(
+        &self,
+        stop_receiver: oneshot::Receiver<()>,
+        shared_runtime: bool,
+    ) -> Result<(SyncHandle, Arc<RelayStats>), ()> {
+        let listener = match self {
+            ListenerDefinition::Ethereum(context) => create_ethereum_listener(context, stop_receiver).await?,
+            ListenerDefinition::Substrate(context) => {
+                substrate_listener::create_listener_for_chain::<CustomConfig>(context, Handle::current(), stop_receiver)
+                    .await?
+            },
+            ListenerDefinition::Custom(context, create) => create(context, stop_receiver)?,
+        };
+        if shared_runtime {
+            Ok(spawn_sync_task(listener))
+        } else {
+            Ok(spawn_sync_thread(self.id(), listener))
+        }
+    }
+}
+
+// listener syncs either in its own thread or as a task of the shared runtime
+enum SyncHandle {
+    Thread(JoinHandle<()>),
+    Task(tokio::task::JoinHandle<()>),
+}
+
+impl SyncHandle {
+    fn is_finished(&self) -> bool {
+        match self {
+            SyncHandle::Thread(handle) => handle.is_finished(),
+            SyncHandle::Task(handle) => handle.is_finished(),
+        }
+    }
+
+    // returns false if the listener panicked
+    async fn join(self) -> bool {
+        match self {
+            SyncHandle::Thread(handle) => {
+                matches!(tokio::task::spawn_blocking(move || handle.join()).await, Ok(Ok(())))
+            },
+            SyncHandle::Task(handle) => handle.await.is_ok(),
+        }
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub enum ListenerControlError {
+    UnknownListener,
+    StartFailed,
+    StateWriteFailed,
+    Restarting,
+}
+
+struct RunningListener {
+    // shared with restart in progress, which runs without holding the registry lock
+    definition: Arc<ListenerDefinition>,
+    stop_sender: Option<oneshot::Sender<()>>,
+    handle: Option<SyncHandle>,
+    relay_stats: Arc<RelayStats>,
+    restarting: bool,
+}
+
+/// Keeps track of listeners' sync threads, so they can be restarted individually at runtime.
+#[derive(Default)]
+pub struct ListenerRegistry {
+    listeners: Mutex<HashMap<String, RunningListener>>,
+    // listeners sync as tasks of the current runtime instead of a thread each if set
+    shared_runtime: bool,
+}
+
+impl ListenerRegistry {
+    /// Runs listeners started from now on as tasks of the current runtime, so many of them can share
+    /// it instead of occupying a thread each.
+    pub fn with_shared_runtime(mut self, shared_runtime: bool) -> Self {
+        self.shared_runtime = shared_runtime;
+        self
+    }
+
+    pub async fn start(&self, definition: ListenerDefinition) -> Result<(), ()> {
+        let (stop_sender, stop_receiver) = oneshot::channel();
+        let (handle, relay_stats) = definition.spawn(stop_receiver, self.shared_runtime).await?;
+        self.listeners.lock().await.insert(
+            definition.id().to_string(),
+            RunningListener {
+                definition: Arc::new(definition),
+                stop_sender: Some(stop_sender),
+                handle: Some(handle),
+                relay_stats,
+                restarting: false,
+            },
+        );
+        Ok(())
+    }
+
+    /// Stops listener's sync thread and starts it again from the last saved checkpoint.
+    /// `start_block` overrides listener's start block, it's applied only if it's ahead of the checkpoint.
+    /// Registry is not locked while the listener stops and starts again, other listeners stay controllable.
+    pub async fn restart(&self, id: &str, start_block: Option<u64>) -> Result<(), ListenerControlError> {
+        let (definition, stop_sender, handle) = {
+            let mut listeners = self.listeners.lock().await;
+            let listener = listeners.get_mut(id).ok_or(ListenerControlError::UnknownListener)?;
+            if listener.restarting {
+                return Err(ListenerControlError::Restarting);
+            }
+            if let Some(start_block) = start_block {
+                // definition is shared only while restarting
+                Arc::get_mut(&mut listener.definition)
+                    .ok_or(ListenerControlError::Restarting)?
+                    .set_start_block(start_block);
+            }
+            listener.restarting = true;
+            (listener.definition.clone(), listener.stop_sender.take(), listener.handle.take())
+        };
+
+        info!("Restarting {} listener", id);
+        if let Some(stop_sender) = stop_sender {
+            // listener might have already stopped on its own
+            let _ = stop_sender.send(());
+        }
+        if let Some(handle) = handle {
+            if !handle.join().await {
+                error!("Sync thread of {} listener panicked", id);
+            }
+        }
+
+        let (stop_sender, stop_receiver) = oneshot::channel();
+        let spawned = definition.spawn(stop_receiver, self.shared_runtime).await;
+
+        let mut listeners = self.listeners.lock().await;
+        let listener = listeners.get_mut(id).ok_or(ListenerControlError::UnknownListener)?;
+        listener.restarting = false;
+        let (handle, relay_stats) = spawned.map_err(|_| {
+            error!("Could not start {} listener", id);
+            ListenerControlError::StartFailed
+        })?;
+        listener.stop_sender = Some(stop_sender);
+        listener.handle = Some(handle);
+        listener.relay_stats = relay_stats;
+        info!("Listener {} restarted", id);
+        Ok(())
+    }
+
+    /// Pauses listener's sync, it keeps polling finalized block but neither fetches events nor relays.
+    /// Pause state is persisted, so the listener stays paused after restart.
+    pub async fn pause(&self, id: &str) -> Result<(), ListenerControlError> {
+        self.set_paused(id, true).await
+    }
+
+    pub async fn resume(&self, id: &str) -> Result<(), ListenerControlError> {
+        self.set_paused(id, false).await
+    }
+
+    async fn set_paused(&self, id: &str, paused: bool) -> Result<(), ListenerControlError> {
+        let listeners = self.listeners.lock().await;
+        let control = listeners
+            .get(id)
+            .ok_or(ListenerControlError::UnknownListener)?
+            .definition
+            .control();
+        let result = if paused { control.pause() } else { control.resume() };
+        result.map_err(|_| ListenerControlError::StateWriteFailed)?;
+        info!("Listener {} {}", id, if paused { "paused" } else { "resumed" });
+        Ok(())
+    }
+
+    /// Returns relay stats per listener and destination
+    pub async fn relay_stats(&self) -> HashMap<String, HashMap<String, DestinationStats>> {
+        self.listeners
+            .lock()
+            .await
+            .iter()
+            .map(|(id, listener)| (id.clone(), listener.relay_stats.snapshot()))
+            .collect()
+    }
+
+    /// Returns sync status of every listener, ordered by listener id
+    pub async fn status(&self) -> Vec<ListenerStatusSnapshot> {
+        let mut status: Vec<ListenerStatusSnapshot> = self
+            .listeners
+            .lock()
+            .await
+            .values()
+            .map(|listener| listener.definition.status().snapshot())
+            .collect();
+        status.sort_by(|a, b| a.id.cmp(&b.id));
+        status
+    }
+
+    /// Stops sync threads of all listeners and waits until they finish, without holding the registry lock
+    pub async fn stop_all(&self) {
+        let mut handles = vec![];
+        for (id, listener) in self.listeners.lock().await.iter_mut() {
+            if let Some(stop_sender) = listener.stop_sender.take() {
+                // listener might have already stopped on its own
+                let _ = stop_sender.send(());
+            }
+            handles.push((id.clone(), listener.handle.take()));
+        }
+        for (id, handle) in handles {
+            if let Some(handle) = handle {
+                if !handle.join().await {
+                    error!("Sync thread of {} listener panicked", id);
+                }
+            }
+            info!("Listener {} stopped", id);
+        }
+    }
+
+    /// Returns true if none of the listeners is syncing
+    pub async fn all_stopped(&self) -> bool {
+        self.listeners
+            .lock()
+            .await
+            .values()
+            .all(|listener| listener.handle.as_ref().map(|handle| handle.is_finished()).unwrap_or(true))
+    }
+}
+
+async fn create_ethereum_listener(
+    context: &ListenerContext<EthereumListenerConfig>,
+    stop_receiver: oneshot::Receiver<()>,
+) -> Result<Box<dyn SyncListener>, ()> {
+    let listener = create_listener(
+        &context.id,
+        Handle::current(),
+        &context.config,
+        &context.start_block,
+        context.chain_id,
+        context.relayers.clone(),
+        &context.relayer_destinations,
+        stop_receiver,
+    )
+    .await?
+    .with_context(context);
+    Ok(Box::new(listener))
+}
+
+fn spawn_sync_thread(id: &str, mut listener: Box<dyn SyncListener>) -> (SyncHandle, Arc<RelayStats>) {
+    let relay_stats = listener.relay_stats();
+    let handle = thread::Builder::new()
+        .name(format!("{}_sync", id))
+        .spawn(move || {
+            let _ = listener.sync();
+        })
+        .unwrap();
+    (SyncHandle::Thread(handle), relay_stats)
+}
+
+fn spawn_sync_task(mut listener: Box<dyn SyncListener>) -> (SyncHandle, Arc<RelayStats>) {
+    let relay_stats = listener.relay_stats();
+    let handle = tokio::spawn(async move {
+        let _ = listener.sync_async().await;
+    });
+    (SyncHandle::Task(handle), relay_stats)
+}
@@ -0,0 +1,89 @@
+// Copyright 2020-2024 Trust Computing GmbH.
+// This file is part of Litentry.
+//
+// Litentry is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// Litentry is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with Litentry.  If not, see <https://www.gnu.org/licenses/>.
+
+use clap::ValueEnum;
+use log::Record;
+use std::io::Write;
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, ValueEnum)]
+pub enum LogFormat {
+    /// Human readable lines
+    #[default]
+    Text,
+    /// One JSON object per line, for log aggregation
+    Json,
+}
+
+pub fn init(format: LogFormat) {
+    let mut builder = env_logger::builder();
+    match format {
+        LogFormat::Text => builder.format(|buf, record| {
+            let ts = buf.timestamp_micros();
+            writeln!(
+                buf,
+                "{} [{}][{}][{}]: {}",
+                ts,
+                record.level(),
+                std::thread::current().name().unwrap_or("none"),
+                record.target(),
+                record.args(),
+            )
+        }),
+        LogFormat::Json => builder.format(|buf, record| {
+            let ts = buf.timestamp_micros().to_string();
+            writeln!(buf, "{}", json_line(&ts, std::thread::current().name().unwrap_or("none"), record))
+        }),
+    };
+    builder.init();
+}
+
+fn json_line(timestamp: &str, thread: &str, record: &Record) -> String {
+    serde_json::json!({
+        "timestamp": timestamp,
+        "level": record.level().as_str(),
+        "target": record.target(),
+        "thread": thread,
+        "message": record.args().to_string(),
+    })
+    .to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use log::Level;
+
+    #[test]
+    fn json_line_contains_all_fields() {
+        let line = json_line(
+            "2024-01-01T00:00:00.000000Z",
+            "eth_sync",
+            &Record::builder()
+                .args(format_args!("Relaying \"nonce\" {}", 1))
+                .level(Level::Info)
+                .target("bridge_core::listener")
+                .build(),
+        );
+
+        let value: serde_json::Value = serde_json::from_str(&line).unwrap();
+        assert_eq!(value["timestamp"], "2024-01-01T00:00:00.000000Z");
+        assert_eq!(value["level"], "INFO");
+        assert_eq!(value["target"], "bridge_core::listener");
+        assert_eq!(value["thread"], "eth_sync");
+        assert_eq!(value["message"], "Relaying \"nonce\" 1");
+        assert!(!line.contains('\n'));
+    }
+}
@@ -15,77 +15,47 @@
 // along with Litentry.  If not, see <https://www.gnu.org/licenses/>.
 
 use crate::cli::*;
-use crate::keystore::LocalKeystore;
-use crate::rpc::methods::{ImportRelayerKeyPayload, SignedParams};
-use crate::shielding_key::ShieldingKey;
+use bridge_worker::key_import::{check_entries, import_script};
+use bridge_worker::keystore::{KeyType, LocalKeystore};
+use bridge_worker::logging;
+use bridge_worker::rpc::auth::{AuthKey, AuthSigner};
+use bridge_worker::rpc::methods::{ImportRelayerKeyPayload, SignedParams};
+use bridge_worker::rpc::server::start_server;
+use bridge_worker::shielding_key::{shield, RotatingShieldingKey, ShieldingKey};
+use bridge_worker::{AdminRpcOptions, BridgeWorker, WorkerOptions};
 
 use bridge_core::config::BridgeConfig;
-use bridge_core::listener::{prepare_listener_context, ListenerContext, StartBlock};
-use bridge_core::relay::Relayer;
+use bridge_core::shielding::ShieldingPublicKey;
+use bridge_core::start_block::StartBlock;
 use clap::Parser;
-use ethereum_listener::create_listener;
-use ethereum_listener::listener::ListenerConfig as EthereumListenerConfig;
 use jsonrpsee_types::Id;
 use log::*;
 use metrics_exporter_prometheus::PrometheusBuilder;
 use rand::rngs::OsRng;
 use rand::Rng;
-use rpc::server::start_server;
-use rsa::traits::PublicKeyParts;
-use rsa::{BigUint, Oaep, RsaPublicKey};
+use rsa::RsaPublicKey;
 use serde_json::value::RawValue;
-use sha2::Sha256;
-use sp_core::{keccak_256, ByteArray, Pair};
-use std::collections::HashMap;
+use std::fs;
 use std::fs::create_dir;
 use std::net::SocketAddr;
 use std::path::Path;
 use std::str::FromStr;
-use std::thread::JoinHandle;
-use std::{fs, io::Write};
-use std::{
-    sync::{Arc, RwLock},
-    thread,
-};
-use substrate_listener::listener::ListenerConfig as SubstrateListenerConfig;
-use substrate_listener::CustomConfig;
-use tokio::{runtime::Handle, signal, sync::oneshot};
+use std::sync::{Arc, RwLock};
+use tokio::{runtime::Handle, signal};
 
 mod cli;
-mod keystore;
-mod rpc;
-mod shielding_key;
-
-#[cfg(test)]
-fn alice_signer() -> [u8; 33] {
-    let key = sp_core::ecdsa::Pair::from_string("//Alice", None).unwrap();
-    key.public().0
-}
 
 #[tokio::main]
 async fn main() -> Result<(), ()> {
     let cli = Cli::parse();
 
-    env_logger::builder()
-        .format(|buf, record| {
-            let ts = buf.timestamp_micros();
-            writeln!(
-                buf,
-                "{} [{}][{}][{}]: {}",
-                ts,
-                record.level(),
-                std::thread::current().name().unwrap_or("none"),
-                record.target(),
-                record.args(),
-            )
-        })
-        .init();
+    logging::init(cli.log_format);
 
     match &cli.command {
         Commands::Run(arg) => run(arg).await?,
         Commands::AwaitKeystoreImport(arg) => await_import(arg).await,
         Commands::GenerateAuthKey(arg) => generate_auth_key(arg),
-        Commands::BuildKeystoreImport(arg) => build_import(arg),
+        Commands::BuildKeystoreImport(arg) => build_import(arg)?,
     }
 
     Ok(())
@@ -95,8 +65,6 @@ async fn run(arg: &RunArgs) -> Result<(), ()> {
     let config_file = arg.config.clone();
     let keystore_dir = arg.keystore_dir.clone();
 
-    let mut handles = vec![];
-
     let builder = PrometheusBuilder::new();
 
     let address = SocketAddr::from_str(&format!("0.0.0.0:{}", arg.metrics_port)).unwrap();
@@ -105,65 +73,52 @@ async fn run(arg: &RunArgs) -> Result<(), ()> {
         .install()
         .expect("failed to install Prometheus recorder");
 
-    let config: String = fs::read_to_string(config_file).unwrap();
-    let config: BridgeConfig = serde_json::from_str(&config).unwrap();
-
-    config.validate().map_err(|e| {
-        error!("Config validation error: {:?}", e);
+    let config = BridgeConfig::from_file(&config_file).map_err(|e| {
+        error!("{}", e);
     })?;
 
-    #[allow(clippy::type_complexity)]
-    let mut relayers: HashMap<String, HashMap<String, Arc<Box<dyn Relayer<String>>>>> = HashMap::new();
-
-    // substrate relayers
-    let substrate_relayers: HashMap<String, Arc<Box<dyn Relayer<String>>>> =
-        substrate_relayer::create_from_config::<CustomConfig>(keystore_dir.clone(), &config.relayers);
-    relayers.insert("substrate".to_string(), substrate_relayers);
-
-    // ethereum relayers
-    let ethereum_relayers: HashMap<String, Arc<Box<dyn Relayer<String>>>> =
-        ethereum_relayer::create_from_config(keystore_dir, &config).await;
-    relayers.insert("ethereum".to_string(), ethereum_relayers);
-
-    let mut start_blocks: HashMap<String, u64> = HashMap::new();
-
-    arg.start_block
+    let start_blocks = arg
+        .start_block
         .iter()
         .map(|s| {
-            let start_block: StartBlock = s.try_into().unwrap();
-            start_block
+            let start_block: StartBlock = s.try_into().map_err(|_| error!("Invalid start block: {}", s))?;
+            Ok((start_block.listener_id, start_block.block))
         })
-        .for_each(|start_block| {
-            start_blocks.insert(start_block.listener_id, start_block.block_num);
-        });
-
-    // start ethereum listeners
-    let ethereum_listener_contexts: Vec<ListenerContext<EthereumListenerConfig>> =
-        prepare_listener_context(&config, "ethereum", &relayers, &start_blocks);
-    for ethereum_listener_context in ethereum_listener_contexts {
-        handles.push(sync_ethereum(ethereum_listener_context).unwrap());
-    }
-
-    // start substrate listeners
-    let substrate_listener_contexts: Vec<ListenerContext<SubstrateListenerConfig>> =
-        prepare_listener_context(&config, "substrate", &relayers, &start_blocks);
-    for substrate_listener_context in substrate_listener_contexts {
-        // todo: remove unwrap ??
-        handles.push(sync_substrate(substrate_listener_context).await.unwrap())
-    }
-
-    for handle in handles {
-        handle.join().unwrap()
-    }
+        .collect::<Result<_, ()>>()?;
+
+    let admin_rpc = match arg.admin_rpc_address {
+        Some(address) => Some(AdminRpcOptions { address, auth_signer: read_auth_signer(&arg.auth_pub_key_path)? }),
+        None => None,
+    };
+
+    let options = WorkerOptions {
+        keystore_dir,
+        start_blocks,
+        force_start_block: arg.force,
+        status_address: Some(SocketAddr::from_str(&format!("0.0.0.0:{}", arg.status_port)).unwrap()),
+        admin_rpc,
+        catch_up_only: arg.catch_up_only,
+        shared_runtime: arg.shared_runtime,
+    };
+
+    let worker = BridgeWorker::from_config(config, options).start().await?;
+    worker.wait().await;
 
     Ok(())
 }
 
+// public key file holds hex encoded ecdsa (33 bytes) or sr25519 (32 bytes) key
+fn read_auth_signer(path: &str) -> Result<AuthSigner, ()> {
+    let public = fs::read(path).map_err(|e| error!("Could not read auth public key from {}: {:?}", path, e))?;
+    let public = hex::decode(public.trim_ascii()).map_err(|e| error!("Could not decode auth public key: {:?}", e))?;
+    AuthSigner::from_public(&public)
+}
+
 fn generate_auth_key(arg: &GenerateArgs) {
     println!("Generating auth key ...");
     let mut seed = [0u8; 32];
     OsRng.fill(&mut seed);
-    let pair = sp_core::ecdsa::Pair::from_seed_slice(&seed).unwrap();
+    let auth_key = AuthKey::from_seed(arg.scheme, &seed).unwrap();
 
     if let Some(ref path) = arg.generate_path {
         if !Path::new(path).exists() {
@@ -182,119 +137,65 @@ fn generate_auth_key(arg: &GenerateArgs) {
         .map(|path| Path::new(path).join(AUTH_KEY_PUB_PATH))
         .unwrap_or(Path::new(AUTH_KEY_PUB_PATH).to_path_buf());
 
-    fs::write(&auth_key_seed_path, hex::encode(pair.seed().as_slice())).unwrap();
-    fs::write(auth_key_pub_path, hex::encode(pair.public().as_slice())).unwrap();
+    fs::write(&auth_key_seed_path, hex::encode(seed)).unwrap();
+    fs::write(auth_key_pub_path, hex::encode(auth_key.public())).unwrap();
 
-    println!("Auth public key in hex: {}", hex::encode(pair.public().as_slice()));
+    println!("Auth {:?} public key in hex: {}", arg.scheme, hex::encode(auth_key.public()));
     println!("Auth private key saved to file: {:?} ", auth_key_seed_path);
 }
 
-fn build_import(arg: &ImportArgs) {
+fn build_import(arg: &ImportArgs) -> Result<(), ()> {
+    let relayer_keys = arg.relayer_keys().map_err(|e| error!("{}", e))?;
+    let config = match arg.config {
+        Some(ref path) => Some(BridgeConfig::from_file(path).map_err(|e| error!("{}", e))?),
+        None => None,
+    };
+    check_entries(&relayer_keys, config.as_ref()).map_err(|e| error!("{}", e))?;
+
     println!("Generating import relayer key command ...");
     let shielding_key = fs::read(arg.shielding_key_path.clone()).unwrap();
-    let shielding_key: rpc::methods::ShieldingKey = serde_json::from_slice(shielding_key.as_slice()).unwrap();
-    let shielding_key =
-        RsaPublicKey::new(BigUint::from_bytes_le(&shielding_key.n), BigUint::from_bytes_le(&shielding_key.e)).unwrap();
+    let shielding_key: ShieldingPublicKey = serde_json::from_slice(shielding_key.as_slice()).unwrap();
+    let shielding_key = shielding_key.to_rsa().unwrap();
 
     let auth_key = fs::read(arg.auth_key_path.clone()).unwrap();
-    let auth_key = sp_core::ecdsa::Pair::from_seed_slice(&hex::decode(&auth_key).unwrap()).unwrap();
-
-    build_import_internal(arg.substrate_id.clone(), arg.substrate_relayer_key_path.clone(), &shielding_key, &auth_key);
-    build_import_internal(arg.ethereum_id.clone(), arg.ethereum_relayer_key_path.clone(), &shielding_key, &auth_key);
-}
-
-async fn sync_substrate(context: ListenerContext<SubstrateListenerConfig>) -> Result<JoinHandle<()>, ()> {
-    let (_sub_stop_sender, sub_stop_receiver) = oneshot::channel();
-
-    match context.config.chain.as_str() {
-        "local" => {
-            let mut listener = substrate_listener::create_local_listener::<CustomConfig>(
-                &context.id,
-                Handle::current(),
-                &context.config,
-                context.start_block,
-                context.chain_id,
-                context.relayers,
-                sub_stop_receiver,
-            )
-            .await?;
-            Ok(thread::Builder::new()
-                .name(format!("{}_sync", &context.id).to_string())
-                .spawn(move || {
-                    let _ = listener.sync();
-                })
-                .unwrap())
-        },
-        "paseo" => {
-            let mut listener = substrate_listener::create_paseo_listener::<CustomConfig>(
-                &context.id,
-                Handle::current(),
-                &context.config,
-                context.start_block,
-                context.chain_id,
-                context.relayers,
-                sub_stop_receiver,
-            )
-            .await?;
-            Ok(thread::Builder::new()
-                .name(format!("{}_sync", &context.id).to_string())
-                .spawn(move || {
-                    let _ = listener.sync();
-                })
-                .unwrap())
-        },
-        "heima" => {
-            let mut listener = substrate_listener::create_heima_listener::<CustomConfig>(
-                &context.id,
-                Handle::current(),
-                &context.config,
-                context.start_block,
-                context.chain_id,
-                context.relayers,
-                sub_stop_receiver,
-            )
-            .await?;
-            Ok(thread::Builder::new()
-                .name(format!("{}_sync", &context.id).to_string())
-                .spawn(move || {
-                    let _ = listener.sync();
-                })
-                .unwrap())
-        },
-        _ => panic!("Unknown chain: {}", context.config.chain),
+    let auth_key = AuthKey::from_seed(arg.scheme, &hex::decode(&auth_key).unwrap()).unwrap();
+
+    let mut commands = vec![];
+    for entry in relayer_keys {
+        let command = build_import_internal(
+            entry.id.clone(),
+            entry.relayer_type.key_type(),
+            entry.key_path,
+            &shielding_key,
+            &auth_key,
+        );
+        println!("\nImport {} {} relayer key cmd:", entry.id, entry.relayer_type);
+        println!("{}", command);
+        commands.push(command);
     }
-}
 
-fn sync_ethereum(context: ListenerContext<EthereumListenerConfig>) -> Result<JoinHandle<()>, ()> {
-    let (_stop_sender, stop_receiver) = oneshot::channel();
-    let mut eth_listener = create_listener(
-        &context.id,
-        Handle::current(),
-        &context.config,
-        context.start_block,
-        context.chain_id,
-        context.relayers,
-        stop_receiver,
-    )?;
-
-    Ok(thread::Builder::new()
-        .name(format!("{}_sync", &context.id).to_string())
-        .spawn(move || {
-            let _ = eth_listener.sync();
-        })
-        .unwrap())
+    if let Some(ref out) = arg.out {
+        fs::write(out, import_script(&commands))
+            .map_err(|e| error!("Could not write import script to {}: {:?}", out, e))?;
+        println!("\nImport script saved to file: {}", out);
+    }
+    Ok(())
 }
 
-fn build_import_internal(id: String, key_path: String, shielding_key: &RsaPublicKey, auth_key: &sp_core::ecdsa::Pair) {
+fn build_import_internal(
+    id: String,
+    key_type: KeyType,
+    key_path: String,
+    shielding_key: &RsaPublicKey,
+    auth_key: &AuthKey,
+) -> String {
     let relayer_key = fs::read(key_path).unwrap();
     let relayer_key = hex::decode(&relayer_key).unwrap();
 
-    let shielded_relayer_key = shielding_key.encrypt(&mut OsRng, Oaep::new::<Sha256>(), &relayer_key).unwrap();
+    let shielded_relayer_key = shield(shielding_key, &relayer_key).unwrap();
 
-    let import_payload = ImportRelayerKeyPayload { id: id.clone(), key: shielded_relayer_key };
-    let import_signature = auth_key
-        .sign_prehashed(&keccak_256(&serde_json::to_vec(&import_payload).unwrap()))
-        .to_raw();
+    let import_payload = ImportRelayerKeyPayload { id, key_type, key: shielded_relayer_key };
+    let import_signature = auth_key.sign(&serde_json::to_vec(&import_payload).unwrap());
     let import_signed_params = SignedParams { payload: import_payload, signature: import_signature };
     let import_request = jsonrpsee_types::RequestSer::owned(
         Id::Number(0),
@@ -302,34 +203,36 @@ fn build_import_internal(id: String, key_path: String, shielding_key: &RsaPublic
         Some(RawValue::from_string(serde_json::to_string(&import_signed_params).unwrap()).unwrap()),
     );
 
-    println!("\nImport {} relayer key cmd:", id);
-    println!(
+    format!(
         "curl -X POST -H 'Content-Type: application/json' -d '{}' http://127.0.0.1:2000",
         serde_json::to_string(&import_request).unwrap()
-    );
+    )
 }
 
 async fn await_import(arg: &AwaitImportArgs) {
     println!("Generating shielding key ...");
-    let shielding_key = Arc::new(ShieldingKey::new());
+    let mut shielding_key = RotatingShieldingKey::new(ShieldingKey::with_bits(arg.shielding_key_bits));
     println!(
         "Shielding key: {}",
-        serde_json::to_string(&rpc::methods::ShieldingKey {
-            n: shielding_key.public_key().n().to_bytes_le(),
-            e: shielding_key.public_key().e().to_bytes_le()
-        })
-        .unwrap()
+        serde_json::to_string(&ShieldingPublicKey::from_rsa(&shielding_key.current().public_key())).unwrap()
     );
+    if let Some(ref path) = arg.shielding_key_out {
+        shielding_key
+            .current()
+            .write_public_key(path, arg.shielding_key_format)
+            .unwrap();
+        println!("Shielding key written to {} as {:?}", path, arg.shielding_key_format);
+        // rotated keys are published to the same file
+        shielding_key = shielding_key.with_public_key_out(path.clone(), arg.shielding_key_format);
+    }
+    let shielding_key = Arc::new(shielding_key);
 
-    let import_keystore_signer: [u8; 33] = hex::decode(fs::read(&arg.auth_pub_key_path).unwrap())
-        .unwrap()
-        .try_into()
-        .unwrap();
+    let import_keystore_signer = read_auth_signer(&arg.auth_pub_key_path).unwrap();
     let keystore = Arc::new(RwLock::new(LocalKeystore::open(arg.keystore_dir.clone().into()).unwrap()));
 
     println!("Start server and wait for keystore import ...");
 
-    start_server("0.0.0.0:2000", Handle::current(), import_keystore_signer, keystore, shielding_key).await;
+    start_server(arg.rpc_bind, Handle::current(), import_keystore_signer, keystore, shielding_key).await;
 
     await_signal().await;
     println!("Bridge worker stopped");
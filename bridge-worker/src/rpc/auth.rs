@@ -0,0 +1,158 @@
+// Copyright 2020-2024 Trust Computing GmbH.
+// This file is part of Litentry.
+//
+// Litentry is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// Litentry is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with Litentry.  If not, see <https://www.gnu.org/licenses/>.
+
+use clap::ValueEnum;
+use sp_core::{ecdsa, keccak_256, sr25519, Pair};
+
+/// Signature scheme of the key authorizing JSON-RPC requests.
+#[derive(Clone, Copy, Debug, Default, PartialEq, ValueEnum)]
+pub enum AuthScheme {
+    /// Signs keccak256 hash of the payload
+    #[default]
+    Ecdsa,
+    /// Signs the raw payload
+    Sr25519,
+}
+
+/// Public key of authorized requests signer, the scheme is recognized by the key length.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum AuthSigner {
+    Ecdsa([u8; 33]),
+    Sr25519([u8; 32]),
+}
+
+impl AuthSigner {
+    #[allow(clippy::result_unit_err)]
+    pub fn from_public(public: &[u8]) -> Result<Self, ()> {
+        if let Ok(public) = public.try_into() {
+            return Ok(AuthSigner::Ecdsa(public));
+        }
+        if let Ok(public) = public.try_into() {
+            return Ok(AuthSigner::Sr25519(public));
+        }
+        log::error!("Unsupported auth public key length: {}", public.len());
+        Err(())
+    }
+
+    pub fn verify(&self, msg: &[u8], signature: &[u8]) -> bool {
+        match self {
+            AuthSigner::Ecdsa(public) => {
+                let Ok(signature) = signature.try_into() else {
+                    return false;
+                };
+                ecdsa::Pair::verify_prehashed(
+                    &ecdsa::Signature::from_raw(signature),
+                    &keccak_256(msg),
+                    &ecdsa::Public::from_raw(*public),
+                )
+            },
+            AuthSigner::Sr25519(public) => {
+                let Ok(signature) = signature.try_into() else {
+                    return false;
+                };
+                sr25519::Pair::verify(
+                    &sr25519::Signature::from_raw(signature),
+                    msg,
+                    &sr25519::Public::from_raw(*public),
+                )
+            },
+        }
+    }
+}
+
+/// Private key used to sign authorized requests.
+pub enum AuthKey {
+    Ecdsa(ecdsa::Pair),
+    Sr25519(sr25519::Pair),
+}
+
+impl AuthKey {
+    #[allow(clippy::result_unit_err)]
+    pub fn from_seed(scheme: AuthScheme, seed: &[u8]) -> Result<Self, ()> {
+        match scheme {
+            AuthScheme::Ecdsa => ecdsa::Pair::from_seed_slice(seed).map(AuthKey::Ecdsa),
+            AuthScheme::Sr25519 => sr25519::Pair::from_seed_slice(seed).map(AuthKey::Sr25519),
+        }
+        .map_err(|e| log::error!("Invalid auth key seed: {:?}", e))
+    }
+
+    pub fn public(&self) -> Vec<u8> {
+        match self {
+            AuthKey::Ecdsa(pair) => pair.public().0.to_vec(),
+            AuthKey::Sr25519(pair) => pair.public().0.to_vec(),
+        }
+    }
+
+    pub fn sign(&self, msg: &[u8]) -> Vec<u8> {
+        match self {
+            AuthKey::Ecdsa(pair) => pair.sign_prehashed(&keccak_256(msg)).0.to_vec(),
+            AuthKey::Sr25519(pair) => pair.sign(msg).0.to_vec(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SEED: [u8; 32] = [7; 32];
+
+    #[test]
+    fn signer_is_recognized_by_public_key_length() {
+        assert!(matches!(AuthSigner::from_public(&[2; 33]), Ok(AuthSigner::Ecdsa(_))));
+        assert!(matches!(AuthSigner::from_public(&[2; 32]), Ok(AuthSigner::Sr25519(_))));
+        assert!(AuthSigner::from_public(&[2; 20]).is_err());
+    }
+
+    #[test]
+    fn ecdsa_signature_of_prehashed_payload_is_verified() {
+        let key = AuthKey::from_seed(AuthScheme::Ecdsa, &SEED).unwrap();
+        let signer = AuthSigner::from_public(&key.public()).unwrap();
+
+        let signature = key.sign(b"payload");
+
+        assert_eq!(signature.len(), 65);
+        assert!(signer.verify(b"payload", &signature));
+        assert!(!signer.verify(b"other payload", &signature));
+        let AuthKey::Ecdsa(ref pair) = key else { unreachable!() };
+        assert_eq!(signature, pair.sign_prehashed(&keccak_256(b"payload")).0.to_vec());
+    }
+
+    #[test]
+    fn sr25519_signature_of_raw_payload_is_verified() {
+        let key = AuthKey::from_seed(AuthScheme::Sr25519, &SEED).unwrap();
+        let signer = AuthSigner::from_public(&key.public()).unwrap();
+
+        let signature = key.sign(b"payload");
+
+        assert_eq!(signature.len(), 64);
+        assert!(signer.verify(b"payload", &signature));
+        assert!(!signer.verify(b"other payload", &signature));
+        // raw payload is signed, not its hash
+        assert!(!signer.verify(&keccak_256(b"payload"), &signature));
+    }
+
+    #[test]
+    fn signature_of_other_scheme_is_rejected() {
+        let ecdsa_key = AuthKey::from_seed(AuthScheme::Ecdsa, &SEED).unwrap();
+        let sr25519_key = AuthKey::from_seed(AuthScheme::Sr25519, &SEED).unwrap();
+        let ecdsa_signer = AuthSigner::from_public(&ecdsa_key.public()).unwrap();
+        let sr25519_signer = AuthSigner::from_public(&sr25519_key.public()).unwrap();
+
+        assert!(!ecdsa_signer.verify(b"payload", &sr25519_key.sign(b"payload")));
+        assert!(!sr25519_signer.verify(b"payload", &ecdsa_key.sign(b"payload")));
+    }
+}
@@ -2,5 +2,20 @@
 // see https://www.jsonrpc.org/specification#error_object
 
 pub const UNAUTHORIZED_REQUEST_CODE: i32 = -32000;
+// keystore file could not be written, e.g. disk is full, import may be retried
 pub const KEYSTORE_WRITE_ERROR_CODE: i32 = -32001;
 pub const SHIELDED_VALUE_DECRYPTION_ERROR_CODE: i32 = -32002;
+pub const LISTENER_NOT_FOUND_CODE: i32 = -32003;
+pub const LISTENER_START_ERROR_CODE: i32 = -32004;
+pub const LISTENER_STATE_WRITE_ERROR_CODE: i32 = -32005;
+pub const SHIELDING_KEY_ROTATION_ERROR_CODE: i32 = -32006;
+pub const KEYSTORE_SERIALIZATION_ERROR_CODE: i32 = -32007;
+// imported key is not a valid seed of the expected key type, key needs to be fixed
+pub const INVALID_ECDSA_KEY_CODE: i32 = -32008;
+pub const INVALID_SR25519_KEY_CODE: i32 = -32009;
+pub const KEYSTORE_ERROR_CODE: i32 = -32010;
+pub const INVALID_KEY_MATERIAL_CODE: i32 = -32011;
+// shielded value is longer than the shielding key modulus, rejected before decryption
+pub const SHIELDED_VALUE_TOO_LARGE_CODE: i32 = -32012;
+// listener is being restarted by another request, restart may be retried once it's done
+pub const LISTENER_RESTARTING_CODE: i32 = -32013;
@@ -1,19 +1,36 @@
-use crate::keystore::KeyStore as KeyStoreT;
+use crate::keystore::{Error as KeyStoreError, KeyStore as KeyStoreT, KeyType};
+use crate::listeners::ListenerControlError;
+use crate::rpc::auth::AuthSigner;
 use crate::rpc::error_code::*;
-use crate::rpc::server::RpcContext;
+use crate::rpc::server::{AdminRpcContext, RpcContext, StatusRpcContext};
+use crate::shielding_key::{MAX_SHIELDING_KEY_BITS, MIN_SHIELDING_KEY_BITS};
+use bridge_core::shielding::ShieldingPublicKey;
 use jsonrpsee::types::{ErrorObject, Params};
 use jsonrpsee::RpcModule;
 use log::{error, info};
-use rsa::traits::PublicKeyParts;
-use rsa::Oaep;
+use metrics::counter;
 use serde::{Deserialize, Serialize};
 use serde_with::serde_as;
-use sha2::Sha256;
-use sp_core::{ecdsa, keccak_256};
 use std::sync::Arc;
 
+pub const GET_SHIELDING_KEY_METHOD: &str = "hm_getShieldingKey";
+pub const IMPORT_RELAYER_KEY_METHOD: &str = "hm_importRelayerKey";
+pub const RESTART_LISTENER_METHOD: &str = "hm_restartListener";
+pub const BRIDGE_STATS_METHOD: &str = "hm_bridgeStats";
+pub const PAUSE_LISTENER_METHOD: &str = "hm_pauseListener";
+pub const RESUME_LISTENER_METHOD: &str = "hm_resumeListener";
+pub const LISTENER_STATUS_METHOD: &str = "hm_listenerStatus";
+pub const GET_CONFIG_METHOD: &str = "hm_getConfig";
+pub const ROTATE_SHIELDING_KEY_METHOD: &str = "hm_rotateShieldingKey";
+
+pub const RPC_REQUEST_TOTAL_COUNTER: &str = "rpc_request_total";
+pub const RPC_ERROR_TOTAL_COUNTER: &str = "rpc_error_total";
+
+// seeds of both supported relayer key types
+const RELAYER_KEY_LEN: usize = 32;
+
 impl<P: Serialize + std::fmt::Debug> SignedParams<P> {
-    pub fn verify_signature(&self, signer: &[u8; 33]) -> bool {
+    pub fn verify_signature(&self, signer: &AuthSigner) -> bool {
         let msg = match serde_json::to_vec(&self.payload) {
             Ok(msg) => msg,
             Err(e) => {
@@ -22,44 +39,58 @@ impl<P: Serialize + std::fmt::Debug> SignedParams<P> {
             },
         };
 
-        let digest = keccak_256(&msg);
-
-        ecdsa::Pair::verify_prehashed(
-            &ecdsa::Signature::from_raw(self.signature),
-            &digest,
-            &ecdsa::Public::from_raw(*signer),
-        )
+        signer.verify(&msg, &self.signature)
     }
 }
 
+/// Request params signed by authorized key. Signed message is JSON serialized `payload`:
+/// - ecdsa (33 bytes public key) signs keccak256 hash of the message, signature is 65 bytes
+/// - sr25519 (32 bytes public key) signs the raw message, signature is 64 bytes
 #[serde_as]
 #[derive(Debug, Serialize, Deserialize)]
 pub struct SignedParams<P> {
     pub payload: P,
     #[serde_as(as = "serde_with::hex::Hex")]
-    pub signature: [u8; 65],
+    pub signature: Vec<u8>,
 }
 
 #[serde_as]
 #[derive(Serialize, Deserialize, Debug)]
 pub struct ImportRelayerKeyPayload {
     pub id: String,
+    // decrypted key is checked to be a valid key of this type before it's stored
+    pub key_type: KeyType,
     #[serde_as(as = "serde_with::hex::Hex")]
     pub key: Vec<u8>,
 }
 
-#[serde_as]
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct ImportedRelayerKey {
+    // derived from imported key, to confirm the right key was imported
+    pub address: String,
+}
+
 #[derive(Serialize, Deserialize, Debug)]
-pub struct ShieldingKey {
-    #[serde_as(as = "serde_with::hex::Hex")]
-    pub n: Vec<u8>,
-    #[serde_as(as = "serde_with::hex::Hex")]
-    pub e: Vec<u8>,
+pub struct RotateShieldingKeyPayload {
+    // size of the new key, defaults to size of the current one
+    pub bits: Option<usize>,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct RestartListenerPayload {
+    pub id: String,
+    // overrides listener's start block if it's ahead of the checkpoint
+    pub start_block: Option<u64>,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct ListenerIdPayload {
+    pub id: String,
 }
 
 fn ensure_authorized_request<'a, P: Serialize + std::fmt::Debug>(
     params: &SignedParams<P>,
-    signers: &[&[u8; 33]],
+    signers: &[&AuthSigner],
 ) -> Result<(), ErrorObject<'a>> {
     if signers.iter().any(|signer| params.verify_signature(signer)) {
         Ok(())
@@ -72,11 +103,11 @@ fn ensure_authorized_request<'a, P: Serialize + std::fmt::Debug>(
 pub fn register_get_shielding_key<KeyStore: KeyStoreT>(module: &mut RpcModule<RpcContext<KeyStore>>) {
     module
         .register_async_method(
-            "hm_getShieldingKey",
+            GET_SHIELDING_KEY_METHOD,
             |_params: Params, rpc_context: Arc<RpcContext<KeyStore>>, _| async move {
-                let public_key = rpc_context.shielding_key.public_key();
-                serde_json::to_value(ShieldingKey { n: public_key.n().to_bytes_le(), e: public_key.e().to_bytes_le() })
-                    .unwrap()
+                counter!(RPC_REQUEST_TOTAL_COUNTER, "method" => GET_SHIELDING_KEY_METHOD).increment(1);
+                let public_key = rpc_context.shielding_key.current().public_key();
+                serde_json::to_value(ShieldingPublicKey::from_rsa(&public_key)).unwrap()
             },
         )
         .unwrap();
@@ -85,33 +116,324 @@ pub fn register_get_shielding_key<KeyStore: KeyStoreT>(module: &mut RpcModule<Rp
 pub fn register_import_relayer_key<KeyStore: KeyStoreT>(module: &mut RpcModule<RpcContext<KeyStore>>) {
     module
         .register_async_method(
-            "hm_importRelayerKey",
+            IMPORT_RELAYER_KEY_METHOD,
             |params: Params, rpc_context: Arc<RpcContext<KeyStore>>, _| async move {
-                let params = params.parse::<SignedParams<ImportRelayerKeyPayload>>()?;
+                let result = async {
+                    let params = params.parse::<SignedParams<ImportRelayerKeyPayload>>()?;
 
-                ensure_authorized_request(&params, &[&rpc_context.import_keystore_signer])?;
+                    ensure_authorized_request(&params, &[&rpc_context.import_keystore_signer])?;
 
-                let decrypted = rpc_context
-                    .shielding_key
-                    .private_key()
-                    .decrypt(Oaep::new::<Sha256>(), &params.payload.key)
-                    .map_err(|_| {
+                    let shielding_key = rpc_context.shielding_key.current();
+                    // relayer keys are always shielded directly, so anything longer than the modulus is not worth
+                    // decrypting
+                    let max_len = shielding_key.bits() / 8;
+                    if params.payload.key.len() > max_len {
+                        error!(
+                            "Shielded key with id {} is too large: {} bytes, max {}",
+                            params.payload.id,
+                            params.payload.key.len(),
+                            max_len
+                        );
+                        return Err(ErrorObject::owned::<()>(
+                            SHIELDED_VALUE_TOO_LARGE_CODE,
+                            format!("Shielded value exceeds {} bytes", max_len),
+                            None,
+                        ));
+                    }
+
+                    let decrypted = shielding_key.unshield(&params.payload.key).map_err(|_| {
                         ErrorObject::owned::<()>(
                             SHIELDED_VALUE_DECRYPTION_ERROR_CODE,
                             "Shielded value decryption failed",
                             None,
                         )
                     })?;
+                    if decrypted.len() != RELAYER_KEY_LEN {
+                        return Err(keystore_error(
+                            KeyStoreError::InvalidKeyMaterial(params.payload.key_type),
+                            &params.payload.id,
+                        ));
+                    }
+
+                    let address = KeyStore::validate(params.payload.key_type, &decrypted)
+                        .map_err(|e| keystore_error(e, &params.payload.id))?;
+                    rpc_context
+                        .keystore
+                        .write()
+                        .unwrap()
+                        .set_key(&params.payload.id, decrypted)
+                        .map_err(|e| keystore_error(e, &params.payload.id))?;
+                    info!(
+                        "Successfully imported {} relayer key with id {}, address {}",
+                        params.payload.key_type, params.payload.id, address
+                    );
+                    Ok::<ImportedRelayerKey, ErrorObject>(ImportedRelayerKey { address })
+                }
+                .await;
+                observe_request(IMPORT_RELAYER_KEY_METHOD, result)
+            },
+        )
+        .unwrap();
+}
+
+// replaces shielding key with a newly generated one and returns its public key, values shielded with the previous key
+// can't be imported afterwards
+pub fn register_rotate_shielding_key<KeyStore: KeyStoreT>(module: &mut RpcModule<RpcContext<KeyStore>>) {
+    module
+        .register_async_method(
+            ROTATE_SHIELDING_KEY_METHOD,
+            |params: Params, rpc_context: Arc<RpcContext<KeyStore>>, _| async move {
+                let result = async {
+                    let params = params.parse::<SignedParams<RotateShieldingKeyPayload>>()?;
+
+                    ensure_authorized_request(&params, &[&rpc_context.import_keystore_signer])?;
+
+                    let bits = params
+                        .payload
+                        .bits
+                        .unwrap_or_else(|| rpc_context.shielding_key.current().bits());
+                    if bits < MIN_SHIELDING_KEY_BITS {
+                        return Err(ErrorObject::owned::<()>(
+                            SHIELDING_KEY_ROTATION_ERROR_CODE,
+                            format!("Shielding key must have at least {} bits", MIN_SHIELDING_KEY_BITS),
+                            None,
+                        ));
+                    }
+                    if bits > MAX_SHIELDING_KEY_BITS {
+                        return Err(ErrorObject::owned::<()>(
+                            SHIELDING_KEY_ROTATION_ERROR_CODE,
+                            format!("Shielding key must have at most {} bits", MAX_SHIELDING_KEY_BITS),
+                            None,
+                        ));
+                    }
+                    // key generation takes seconds, keep it off the async runtime
+                    let shielding_key = rpc_context.shielding_key.clone();
+                    let rotated = tokio::task::spawn_blocking(move || shielding_key.rotate(bits))
+                        .await
+                        .map_err(|e| error!("Shielding key generation failed: {:?}", e))
+                        .and_then(|rotated| rotated)
+                        .map_err(|_| {
+                            ErrorObject::owned::<()>(
+                                SHIELDING_KEY_ROTATION_ERROR_CODE,
+                                "Could not rotate shielding key",
+                                None,
+                            )
+                        })?;
+                    info!("Shielding key rotated, new key has {} bits", bits);
+                    Ok::<ShieldingPublicKey, ErrorObject>(ShieldingPublicKey::from_rsa(&rotated.public_key()))
+                }
+                .await;
+                observe_request(ROTATE_SHIELDING_KEY_METHOD, result)
+            },
+        )
+        .unwrap();
+}
+
+// stops listener's sync thread and starts it again from the last checkpoint
+pub fn register_restart_listener(module: &mut RpcModule<AdminRpcContext>) {
+    module
+        .register_async_method(
+            RESTART_LISTENER_METHOD,
+            |params: Params, rpc_context: Arc<AdminRpcContext>, _| async move {
+                let result = async {
+                    let params = params.parse::<SignedParams<RestartListenerPayload>>()?;
+
+                    ensure_authorized_request(&params, &[&rpc_context.auth_signer])?;
 
-                rpc_context
-                    .keystore
-                    .write()
-                    .unwrap()
-                    .set_key(&params.payload.id, decrypted)
-                    .map_err(|e| ErrorObject::owned::<()>(KEYSTORE_WRITE_ERROR_CODE, e.to_string(), None))?;
-                info!("Successfully imported relayer key with id {}", params.payload.id);
-                Ok::<(), ErrorObject>(())
+                    rpc_context
+                        .listeners
+                        .restart(&params.payload.id, params.payload.start_block)
+                        .await
+                        .map_err(|e| listener_control_error(e, &params.payload.id))?;
+                    Ok::<(), ErrorObject>(())
+                }
+                .await;
+                observe_request(RESTART_LISTENER_METHOD, result)
             },
         )
         .unwrap();
 }
+
+// pauses listener's sync, pause state survives restarts
+pub fn register_pause_listener(module: &mut RpcModule<AdminRpcContext>) {
+    module
+        .register_async_method(
+            PAUSE_LISTENER_METHOD,
+            |params: Params, rpc_context: Arc<AdminRpcContext>, _| async move {
+                let result = async {
+                    let params = params.parse::<SignedParams<ListenerIdPayload>>()?;
+
+                    ensure_authorized_request(&params, &[&rpc_context.auth_signer])?;
+
+                    rpc_context
+                        .listeners
+                        .pause(&params.payload.id)
+                        .await
+                        .map_err(|e| listener_control_error(e, &params.payload.id))?;
+                    Ok::<(), ErrorObject>(())
+                }
+                .await;
+                observe_request(PAUSE_LISTENER_METHOD, result)
+            },
+        )
+        .unwrap();
+}
+
+// resumes listener's sync from the last checkpoint
+pub fn register_resume_listener(module: &mut RpcModule<AdminRpcContext>) {
+    module
+        .register_async_method(
+            RESUME_LISTENER_METHOD,
+            |params: Params, rpc_context: Arc<AdminRpcContext>, _| async move {
+                let result = async {
+                    let params = params.parse::<SignedParams<ListenerIdPayload>>()?;
+
+                    ensure_authorized_request(&params, &[&rpc_context.auth_signer])?;
+
+                    rpc_context
+                        .listeners
+                        .resume(&params.payload.id)
+                        .await
+                        .map_err(|e| listener_control_error(e, &params.payload.id))?;
+                    Ok::<(), ErrorObject>(())
+                }
+                .await;
+                observe_request(RESUME_LISTENER_METHOD, result)
+            },
+        )
+        .unwrap();
+}
+
+// returns highest relayed nonce and relayed amount per listener and destination
+pub fn register_bridge_stats(module: &mut RpcModule<AdminRpcContext>) {
+    module
+        .register_async_method(
+            BRIDGE_STATS_METHOD,
+            |_params: Params, rpc_context: Arc<AdminRpcContext>, _| async move {
+                counter!(RPC_REQUEST_TOTAL_COUNTER, "method" => BRIDGE_STATS_METHOD).increment(1);
+                serde_json::to_value(rpc_context.listeners.relay_stats().await).unwrap()
+            },
+        )
+        .unwrap();
+}
+
+// returns config the worker was started with, secrets are redacted
+pub fn register_get_config(module: &mut RpcModule<AdminRpcContext>) {
+    module
+        .register_async_method(GET_CONFIG_METHOD, |_params: Params, rpc_context: Arc<AdminRpcContext>, _| async move {
+            counter!(RPC_REQUEST_TOTAL_COUNTER, "method" => GET_CONFIG_METHOD).increment(1);
+            rpc_context.config.clone()
+        })
+        .unwrap();
+}
+
+// returns sync progress of every listener, served also as `GET /status`
+pub fn register_listener_status(module: &mut RpcModule<StatusRpcContext>) {
+    module
+        .register_async_method(
+            LISTENER_STATUS_METHOD,
+            |_params: Params, rpc_context: Arc<StatusRpcContext>, _| async move {
+                counter!(RPC_REQUEST_TOTAL_COUNTER, "method" => LISTENER_STATUS_METHOD).increment(1);
+                serde_json::to_value(rpc_context.listeners.status().await).unwrap()
+            },
+        )
+        .unwrap();
+}
+
+fn listener_control_error<'a>(e: ListenerControlError, id: &str) -> ErrorObject<'a> {
+    match e {
+        ListenerControlError::UnknownListener => {
+            ErrorObject::owned::<()>(LISTENER_NOT_FOUND_CODE, format!("Unknown listener: {}", id), None)
+        },
+        ListenerControlError::StartFailed => {
+            ErrorObject::owned::<()>(LISTENER_START_ERROR_CODE, format!("Could not start listener: {}", id), None)
+        },
+        ListenerControlError::StateWriteFailed => ErrorObject::owned::<()>(
+            LISTENER_STATE_WRITE_ERROR_CODE,
+            format!("Could not persist listener state: {}", id),
+            None,
+        ),
+        ListenerControlError::Restarting => {
+            ErrorObject::owned::<()>(LISTENER_RESTARTING_CODE, format!("Listener is restarting: {}", id), None)
+        },
+    }
+}
+
+fn keystore_error<'a>(e: KeyStoreError, id: &str) -> ErrorObject<'a> {
+    error!("Could not import relayer key with id {}: {:?}", id, e);
+    let code = match e {
+        KeyStoreError::Io(_) => KEYSTORE_WRITE_ERROR_CODE,
+        KeyStoreError::Json(_) => KEYSTORE_SERIALIZATION_ERROR_CODE,
+        KeyStoreError::ParseEcdsaPair => INVALID_ECDSA_KEY_CODE,
+        KeyStoreError::ParseSr25519Pair => INVALID_SR25519_KEY_CODE,
+        KeyStoreError::InvalidKeyMaterial(_) => INVALID_KEY_MATERIAL_CODE,
+        KeyStoreError::Other(_) => KEYSTORE_ERROR_CODE,
+    };
+    ErrorObject::owned::<()>(code, e.to_string(), None)
+}
+
+/// Updates request and error counters of the given `method` based on handler's `result`
+fn observe_request<'a, T>(method: &'static str, result: Result<T, ErrorObject<'a>>) -> Result<T, ErrorObject<'a>> {
+    counter!(RPC_REQUEST_TOTAL_COUNTER, "method" => method).increment(1);
+    if let Err(ref e) = result {
+        counter!(RPC_ERROR_TOTAL_COUNTER, "method" => method, "code" => e.code().to_string()).increment(1);
+    }
+    result
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use metrics_exporter_prometheus::PrometheusBuilder;
+
+    #[test]
+    pub fn keystore_errors_are_mapped_to_distinct_codes() {
+        let io = std::io::Error::new(std::io::ErrorKind::StorageFull, "disk full");
+        let json = serde_json::from_str::<serde_json::Value>("{").unwrap_err();
+        let other: Box<dyn std::error::Error + Sync + Send> = "other".into();
+
+        let codes: Vec<i32> = [
+            KeyStoreError::Io(io),
+            KeyStoreError::Json(json),
+            KeyStoreError::ParseEcdsaPair,
+            KeyStoreError::ParseSr25519Pair,
+            KeyStoreError::InvalidKeyMaterial(KeyType::Ecdsa),
+            KeyStoreError::Other(other),
+        ]
+        .into_iter()
+        .map(|e| keystore_error(e, "rococo").code())
+        .collect();
+
+        assert_eq!(
+            codes,
+            vec![
+                KEYSTORE_WRITE_ERROR_CODE,
+                KEYSTORE_SERIALIZATION_ERROR_CODE,
+                INVALID_ECDSA_KEY_CODE,
+                INVALID_SR25519_KEY_CODE,
+                INVALID_KEY_MATERIAL_CODE,
+                KEYSTORE_ERROR_CODE
+            ]
+        );
+        let message = keystore_error(KeyStoreError::ParseEcdsaPair, "rococo").message().to_string();
+        assert_eq!(message, "Failed to parse as ECDSA pair");
+    }
+
+    #[test]
+    pub fn observe_request_updates_counters() {
+        let recorder = PrometheusBuilder::new().build_recorder();
+        let handle = recorder.handle();
+
+        metrics::with_local_recorder(&recorder, || {
+            let _ = observe_request(IMPORT_RELAYER_KEY_METHOD, Ok(()));
+            let _ = observe_request::<()>(
+                IMPORT_RELAYER_KEY_METHOD,
+                Err(ErrorObject::owned::<()>(UNAUTHORIZED_REQUEST_CODE, "Unauthorized request", None)),
+            );
+        });
+
+        let rendered = handle.render();
+        assert!(rendered.contains(r#"rpc_request_total{method="hm_importRelayerKey"} 2"#));
+        assert!(rendered.contains(r#"rpc_error_total{method="hm_importRelayerKey",code="-32000"} 1"#));
+    }
+}
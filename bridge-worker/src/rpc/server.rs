@@ -1,38 +1,50 @@
 use crate::keystore::KeyStore as KeyStoreT;
+use crate::listeners::ListenerRegistry;
+use crate::rpc::auth::AuthSigner;
 use crate::rpc::methods::*;
-use crate::shielding_key::ShieldingKey;
+use crate::shielding_key::RotatingShieldingKey;
+use jsonrpsee::server::middleware::http::ProxyGetRequestLayer;
 use jsonrpsee::server::tracing::info;
 use jsonrpsee::server::Server;
 use jsonrpsee::RpcModule;
 use std::net::SocketAddr;
 use std::sync::{Arc, RwLock};
 use tokio::runtime::Handle;
+use tower::ServiceBuilder;
 
 pub struct RpcContext<KeyStore: KeyStoreT> {
-    pub import_keystore_signer: [u8; 33],
+    pub import_keystore_signer: AuthSigner,
     pub keystore: Arc<RwLock<KeyStore>>,
-    pub shielding_key: Arc<ShieldingKey>,
+    pub shielding_key: Arc<RotatingShieldingKey>,
+}
+
+pub struct AdminRpcContext {
+    pub auth_signer: AuthSigner,
+    pub listeners: Arc<ListenerRegistry>,
+    // redacted bridge config served by `hm_getConfig`
+    pub config: serde_json::Value,
+}
+
+pub struct StatusRpcContext {
+    pub listeners: Arc<ListenerRegistry>,
 }
 
 // pass server context here
 pub async fn start_server<KeyStore: KeyStoreT>(
-    address: &str,
+    address: SocketAddr,
     handle: Handle,
-    import_keystore_signer: [u8; 33],
+    import_keystore_signer: AuthSigner,
     keystore: Arc<RwLock<KeyStore>>,
-    shielding_key: Arc<ShieldingKey>,
+    shielding_key: Arc<RotatingShieldingKey>,
 ) -> SocketAddr {
-    let server = Server::builder()
-        .custom_tokio_runtime(handle)
-        .build(address.parse::<SocketAddr>().unwrap())
-        .await
-        .unwrap();
+    let server = Server::builder().custom_tokio_runtime(handle).build(address).await.unwrap();
 
     let context = RpcContext { import_keystore_signer, keystore, shielding_key };
     let mut module = RpcModule::new(context);
 
     register_get_shielding_key(&mut module);
     register_import_relayer_key(&mut module);
+    register_rotate_shielding_key(&mut module);
 
     let addr = server.local_addr().unwrap();
     info!("Server listening on {}", addr);
@@ -42,18 +54,73 @@ pub async fn start_server<KeyStore: KeyStoreT>(
     addr
 }
 
+// serves methods used to manage running worker
+pub async fn start_admin_server(
+    address: SocketAddr,
+    handle: Handle,
+    auth_signer: AuthSigner,
+    listeners: Arc<ListenerRegistry>,
+    config: serde_json::Value,
+) -> SocketAddr {
+    let server = Server::builder().custom_tokio_runtime(handle).build(address).await.unwrap();
+
+    let context = AdminRpcContext { auth_signer, listeners: listeners.clone(), config };
+    let mut module = RpcModule::new(context);
+
+    register_restart_listener(&mut module);
+    register_pause_listener(&mut module);
+    register_resume_listener(&mut module);
+    register_bridge_stats(&mut module);
+    register_get_config(&mut module);
+    module.merge(status_module(listeners)).unwrap();
+
+    let addr = server.local_addr().unwrap();
+    info!("Admin server listening on {}", addr);
+    let handle = server.start(module);
+    tokio::spawn(handle.stopped());
+
+    addr
+}
+
+// serves listeners' sync status for orchestrators, also as plain `GET /status`
+pub async fn start_status_server(address: SocketAddr, handle: Handle, listeners: Arc<ListenerRegistry>) -> SocketAddr {
+    let http_middleware =
+        ServiceBuilder::new().layer(ProxyGetRequestLayer::new("/status", LISTENER_STATUS_METHOD).unwrap());
+    let server = Server::builder()
+        .set_http_middleware(http_middleware)
+        .custom_tokio_runtime(handle)
+        .build(address)
+        .await
+        .unwrap();
+
+    let addr = server.local_addr().unwrap();
+    info!("Status server listening on {}", addr);
+    let handle = server.start(status_module(listeners));
+    tokio::spawn(handle.stopped());
+
+    addr
+}
+
+fn status_module(listeners: Arc<ListenerRegistry>) -> RpcModule<StatusRpcContext> {
+    let mut module = RpcModule::new(StatusRpcContext { listeners });
+    register_listener_status(&mut module);
+    module
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
-    use crate::alice_signer;
-    use crate::keystore::LocalKeystore;
+    use crate::keystore::{InMemoryKeystore, KeyType, LocalKeystore};
     use crate::rpc::error_code::*;
-    use crate::shielding_key::ShieldingKey;
+    use crate::shielding_key::{shield, ShieldingKey, MAX_SHIELDING_KEY_BITS};
+    use crate::{alice_signer, alice_sr25519_signer};
+    use bridge_core::shielding::ShieldingPublicKey;
     use jsonrpsee::types::{Response, ResponsePayload};
     use jsonrpsee_core::JsonRawValue;
     use rand::rngs::OsRng;
     use rsa::Oaep;
     use rsa::RsaPrivateKey;
+    use serde::Serialize;
     use sha2::Sha256;
     use sp_core::{keccak_256, Pair};
     use std::fs;
@@ -64,16 +131,16 @@ mod test {
     struct GlobalContext;
 
     impl GlobalContext {
-        fn setup() -> Arc<ShieldingKey> {
+        fn setup() -> Arc<RotatingShieldingKey> {
             let key: RsaPrivateKey = serde_json::from_str(r#"{"pubkey_components":{"n":[2902428985,3030084423,71537824,330754855,3088797530,1333155746,3859933877,998043133,4055167788,3389012306,414103315,3855441301,2334833976,3369046619,3538498605,2746527914,505459908,845990733,1298711580,190369169,1328267733,1696672341,1495772496,1511448680,4145071828,140608394,2297500098,2984248725,1912305352,1483157512,3692838439,2977072868,676024582,1379582626,667314503,3651057317,2069712232,4158124598,4059421562,1359740247,2022094059,2750077769,2174451857,1688571952,2058140893,511747482,3614418731,2787560495,4148688666,76513831,4186445569,3537487226,1286425607,1012062486,3900813829,1860007907,2348321810,3120665752,447535708,2179351338,3487052681,451136099,2305104172,2992699609],"e":[65537]},"d":[1851887737,788315507,4013827738,4104826729,3054521037,3276705785,2951892502,3039679951,804862396,1332155767,2271144796,1641949574,231874588,3561673887,1619692480,2505224679,433961249,4235940283,2484132214,3219441937,1510111112,291682213,3952000133,170983406,953118686,3426738443,269314623,2219322476,1788928358,1460965968,328690546,1375179643,672540328,457232945,569667616,14044575,1608034390,117000477,3878825344,3977344438,2944131697,1920048131,4111418776,473833721,793268564,354634409,188675642,2521946821,2355115849,2943664041,1331219024,1632940625,374491971,3030516214,1886173359,3886763897,2782445697,1076627759,1130238476,1546950846,1690429799,132713663,1515254134,2110694132],"primes":[[3402250683,3294881972,4169609523,3070676552,1390461968,3135233523,1387965320,2921458458,113033400,861030721,1895694789,164820657,2056610536,3025931362,2880155889,2648713933,202802821,1895399657,221009069,464126633,2350011559,313845561,4204037651,1170988860,3674462967,1098213417,1448933802,170906521,967995194,3055210519,2974236951,3400220960],[4185504667,958320937,2880429174,11763834,1858530037,2599253162,4079864083,90305400,3470432005,2746586703,21726232,473851216,207680176,46359070,3665997773,1779833430,3773343740,1973821220,2298822812,1534145284,774424605,2950971609,1067680631,786009521,3551602666,1688612497,3482722671,429126127,723782424,3574166391,1318821239,3780209315]]}"#).unwrap();
-            Arc::new(ShieldingKey::init_with(key))
+            Arc::new(RotatingShieldingKey::new(ShieldingKey::init_with(key)))
         }
     }
 
     #[test]
     pub fn print_sig() {
         let key = sp_core::ecdsa::Pair::from_string("//Alice", None).unwrap();
-        let w = ImportRelayerKeyPayload { id: "rococo".to_string(), key: hex::decode("3bac64ca36d1a64c0c70ff4759f47246253d4fab94e1316e98fb038b7a55bb95fd741f38bbd779ed6b8c0264789f9fac398aba8071c68aa17ee23251eb1e12dd90f92ea9942ee9018075a9c317353b51ceb545caa210d8deb47de356912def894bbb2c77159054fe04f55c661cee218abe7b51e8c37d122a51fd88645664e167b3827a324c37a9d557cc6200f78941a6e225735a441c17d2a1e48c494c32b7317f08b2ff461ef5e8caa9e92960b79a559c0a7b3eff954528bad87f2ffc92fe2ca57bc43c59b48a88f7b4f2f5dd4bcacaec1565967e9eb8131f8db5b69606920560d441de41402e6e0526733ac6f4a1f970b103f62739cf8c4c038376e8ff4100").unwrap() };
+        let w = ImportRelayerKeyPayload { id: "rococo".to_string(), key_type: KeyType::Sr25519, key: hex::decode("3bac64ca36d1a64c0c70ff4759f47246253d4fab94e1316e98fb038b7a55bb95fd741f38bbd779ed6b8c0264789f9fac398aba8071c68aa17ee23251eb1e12dd90f92ea9942ee9018075a9c317353b51ceb545caa210d8deb47de356912def894bbb2c77159054fe04f55c661cee218abe7b51e8c37d122a51fd88645664e167b3827a324c37a9d557cc6200f78941a6e225735a441c17d2a1e48c494c32b7317f08b2ff461ef5e8caa9e92960b79a559c0a7b3eff954528bad87f2ffc92fe2ca57bc43c59b48a88f7b4f2f5dd4bcacaec1565967e9eb8131f8db5b69606920560d441de41402e6e0526733ac6f4a1f970b103f62739cf8c4c038376e8ff4100").unwrap() };
         let data = serde_json::to_vec(&w).unwrap();
         let sig = key.sign_prehashed(&keccak_256(&data)).0;
         println!("payload is: {}, sig is {}", serde_json::to_string(&w).unwrap(), hex::encode(sig));
@@ -86,7 +153,9 @@ mod test {
         fs::create_dir_all(&data_dir).unwrap();
         let keystore = Arc::new(RwLock::new(LocalKeystore::open(data_dir.clone()).unwrap()));
 
-        let address = start_server("127.0.0.1:2003", Handle::current(), alice_signer(), keystore, shielding_key).await;
+        let address =
+            start_server("127.0.0.1:2003".parse().unwrap(), Handle::current(), alice_signer(), keystore, shielding_key)
+                .await;
 
         let client = reqwest::Client::new();
 
@@ -95,7 +164,7 @@ mod test {
             "jsonrpc": "2.0",
             "method": "hm_importRelayerKey",
             "params": {
-                "payload": {"id":"rococo", "key":"3bac64ca36d1a64c0c70ff4759f47246253d4fab94e1316e98fb038b7a55bb95fd741f38bbd779ed6b8c0264789f9fac398aba8071c68aa17ee23251eb1e12dd90f92ea9942ee9018075a9c317353b51ceb545caa210d8deb47de356912def894bbb2c77159054fe04f55c661cee218abe7b51e8c37d122a51fd88645664e167b3827a324c37a9d557cc6200f78941a6e225735a441c17d2a1e48c494c32b7317f08b2ff461ef5e8caa9e92960b79a559c0a7b3eff954528bad87f2ffc92fe2ca57bc43c59b48a88f7b4f2f5dd4bcacaec1565967e9eb8131f8db5b69606920560d441de41402e6e0526733ac6f4a1f970b103f62739cf8c4c038376e8ff4100"},
+                "payload": {"id":"rococo", "key_type":"sr25519", "key":"3bac64ca36d1a64c0c70ff4759f47246253d4fab94e1316e98fb038b7a55bb95fd741f38bbd779ed6b8c0264789f9fac398aba8071c68aa17ee23251eb1e12dd90f92ea9942ee9018075a9c317353b51ceb545caa210d8deb47de356912def894bbb2c77159054fe04f55c661cee218abe7b51e8c37d122a51fd88645664e167b3827a324c37a9d557cc6200f78941a6e225735a441c17d2a1e48c494c32b7317f08b2ff461ef5e8caa9e92960b79a559c0a7b3eff954528bad87f2ffc92fe2ca57bc43c59b48a88f7b4f2f5dd4bcacaec1565967e9eb8131f8db5b69606920560d441de41402e6e0526733ac6f4a1f970b103f62739cf8c4c038376e8ff4100"},
                 "signature": "0000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000"
             },
             "id": "5"
@@ -103,7 +172,7 @@ mod test {
         "#;
 
         let response = client
-            .post(format!("http://{}", address.to_string()))
+            .post(format!("http://{}", address))
             .body(body)
             .header("Content-Type", "application/json")
             .send()
@@ -111,8 +180,7 @@ mod test {
             .unwrap();
 
         let response_bytes = &response.bytes().await.unwrap();
-        let json_rpc_response =
-            Response::try_from(serde_json::from_slice::<Response<&JsonRawValue>>(response_bytes).unwrap()).unwrap();
+        let json_rpc_response = serde_json::from_slice::<Response<&JsonRawValue>>(response_bytes).unwrap();
 
         assert!(
             matches!(json_rpc_response.payload, ResponsePayload::Error(e) if e.code() == UNAUTHORIZED_REQUEST_CODE )
@@ -123,11 +191,11 @@ mod test {
     #[tokio::test]
     pub async fn get_shielding_key_works() {
         let shielding_key = GlobalContext::setup();
-        let data_dir: PathBuf = "get_shielding_key_works".into();
-        fs::create_dir_all(&data_dir).unwrap();
-        let keystore = Arc::new(RwLock::new(LocalKeystore::open(data_dir.clone()).unwrap()));
+        let keystore = Arc::new(RwLock::new(InMemoryKeystore::new()));
 
-        let address = start_server("127.0.0.1:2004", Handle::current(), alice_signer(), keystore, shielding_key).await;
+        let address =
+            start_server("127.0.0.1:2004".parse().unwrap(), Handle::current(), alice_signer(), keystore, shielding_key)
+                .await;
 
         let client = reqwest::Client::new();
 
@@ -141,7 +209,7 @@ mod test {
         "#;
 
         let response = client
-            .post(format!("http://{}", address.to_string()))
+            .post(format!("http://{}", address))
             .body(body)
             .header("Content-Type", "application/json")
             .send()
@@ -149,46 +217,358 @@ mod test {
             .unwrap();
 
         let response_bytes = &response.bytes().await.unwrap();
-        let json_rpc_response =
-            Response::try_from(serde_json::from_slice::<Response<&JsonRawValue>>(response_bytes).unwrap()).unwrap();
+        let json_rpc_response = serde_json::from_slice::<Response<&JsonRawValue>>(response_bytes).unwrap();
 
         assert!(matches!(
           json_rpc_response.payload,
           ResponsePayload::Success(b) if b.get() == r#"{"e":"010001","n":"398dffac476b9bb4a094430427ebb6135a4f1bb8a257764fb5ea11e6fded7c3b2cf3b4f1523900ca13b7ae18955dcde538bd2a8b5b92cfc82d34e9d2aab0b4a3c4b4201e4dcb6c321cc4684d91cd580bd5c12b4f552a216550ad275968e0165ad4c610f78a836108c211f1889505e0b1c876fb7108306758273e1cdce48672b106514b28a2c23a524769c627a5b69ed9684d5d7b36f2d7f77adbf5f157fd0b51ebb4867849dbeaa391809b813090a564ddbcac7a9aa5801e2ba76fd72fcc26a61af747f727828f04011788f97ac5d9d2074cad4c16d9523c05b281e8e377dd6e128cf88b989401ba5cdaac1a2a43e6818933d8cf63cae31a2c196589d9f860b2"}"#
         ));
-        fs::remove_dir_all(data_dir).unwrap();
     }
 
     #[tokio::test]
     pub async fn import_relayer_key_works() {
         let shielding_key = GlobalContext::setup();
-        let data_dir: PathBuf = "import_relayer_key_works".into();
-        fs::create_dir_all(&data_dir).unwrap();
-        let keystore = Arc::new(RwLock::new(LocalKeystore::open(data_dir.clone()).unwrap()));
+        let keystore = Arc::new(RwLock::new(InMemoryKeystore::new()));
+
+        let shielded_key = shield(&shielding_key.current().public_key(), &hex::decode(SR25519_SEED).unwrap()).unwrap();
+
+        let address = start_server(
+            "127.0.0.1:2005".parse().unwrap(),
+            Handle::current(),
+            alice_signer(),
+            keystore.clone(),
+            shielding_key,
+        )
+        .await;
+
+        let payload =
+            ImportRelayerKeyPayload { id: "rococo".to_string(), key_type: KeyType::Sr25519, key: shielded_key };
+        let imported = send_signed(address, IMPORT_RELAYER_KEY_METHOD, payload).await.unwrap();
+
+        // Alice
+        assert_eq!(
+            serde_json::from_value::<ImportedRelayerKey>(imported).unwrap(),
+            ImportedRelayerKey { address: "5GrwvaEF5zXb26Fz9rcQpDWS57CtERHpNehXCPcNoHGKutQY".to_string() }
+        );
+        assert_eq!(keystore.read().unwrap().get_key("rococo").unwrap(), &hex::decode(SR25519_SEED).unwrap());
+    }
+
+    #[tokio::test]
+    pub async fn import_relayer_key_should_reject_invalid_key_material() {
+        let shielding_key = GlobalContext::setup();
+        let keystore = Arc::new(RwLock::new(InMemoryKeystore::new()));
+        let public_key = shielding_key.current().public_key();
+
+        let address = start_server(
+            "127.0.0.1:2013".parse().unwrap(),
+            Handle::current(),
+            alice_signer(),
+            keystore.clone(),
+            shielding_key,
+        )
+        .await;
+
+        let cases = [
+            // wrong length
+            (KeyType::Sr25519, vec![]),
+            (KeyType::Sr25519, vec![1; 31]),
+            (KeyType::Sr25519, vec![1; 64]),
+            (KeyType::Ecdsa, vec![1; 33]),
+            // any 32 bytes are sr25519 seed, but not a secp256k1 scalar
+            (KeyType::Ecdsa, vec![0xff; 32]),
+            (KeyType::Ecdsa, vec![0; 32]),
+        ];
+        for (key_type, key) in cases {
+            let payload =
+                ImportRelayerKeyPayload { id: "rococo".to_string(), key_type, key: shield(&public_key, &key).unwrap() };
+            let response = send_signed(address, IMPORT_RELAYER_KEY_METHOD, payload).await;
+            assert_eq!(response, Err(INVALID_KEY_MATERIAL_CODE), "{:?} key {}", key_type, hex::encode(key));
+        }
+        assert!(keystore.read().unwrap().get_key("rococo").is_none());
+    }
+
+    #[tokio::test]
+    pub async fn import_relayer_key_should_reject_oversize_payload() {
+        let shielding_key = GlobalContext::setup();
+        let keystore = Arc::new(RwLock::new(InMemoryKeystore::new()));
+        let public_key = shielding_key.current().public_key();
+        let key_size = shielding_key.current().bits() / 8;
+
+        let address = start_server(
+            "127.0.0.1:2014".parse().unwrap(),
+            Handle::current(),
+            alice_signer(),
+            keystore.clone(),
+            shielding_key,
+        )
+        .await;
+
+        let cases = [
+            vec![1; key_size + 1],
+            vec![1; 1024 * 1024],
+            // hybrid shielded values are longer than the modulus even if they decrypt fine
+            shield(&public_key, &[1; 4096]).unwrap(),
+        ];
+        for key in cases {
+            let len = key.len();
+            let payload = ImportRelayerKeyPayload { id: "rococo".to_string(), key_type: KeyType::Sr25519, key };
+            let response = send_signed(address, IMPORT_RELAYER_KEY_METHOD, payload).await;
+            assert_eq!(response, Err(SHIELDED_VALUE_TOO_LARGE_CODE), "{} bytes", len);
+        }
+        assert!(keystore.read().unwrap().get_key("rococo").is_none());
+    }
+
+    // posts `method` with params signed by Alice's ecdsa key, returns result or error code
+    async fn send_signed<P: Serialize + std::fmt::Debug>(
+        address: SocketAddr,
+        method: &str,
+        payload: P,
+    ) -> Result<serde_json::Value, i32> {
+        let key = sp_core::ecdsa::Pair::from_string("//Alice", None).unwrap();
+        let signature = key
+            .sign_prehashed(&keccak_256(&serde_json::to_vec(&payload).unwrap()))
+            .0
+            .to_vec();
+        let body = serde_json::json!({
+            "jsonrpc": "2.0",
+            "method": method,
+            "params": SignedParams { payload, signature },
+            "id": "5"
+        });
+
+        let response = reqwest::Client::new()
+            .post(format!("http://{}", address))
+            .body(body.to_string())
+            .header("Content-Type", "application/json")
+            .send()
+            .await
+            .unwrap();
+
+        let response_bytes = &response.bytes().await.unwrap();
+        match serde_json::from_slice::<Response<serde_json::Value>>(response_bytes)
+            .unwrap()
+            .payload
+        {
+            ResponsePayload::Success(result) => Ok(result.into_owned()),
+            ResponsePayload::Error(e) => Err(e.code()),
+        }
+    }
+
+    #[tokio::test]
+    pub async fn rotated_shielding_key_replaces_previous_one() {
+        let shielding_key = GlobalContext::setup();
+        let keystore = Arc::new(RwLock::new(InMemoryKeystore::new()));
+        let previous_key = shielding_key.current().public_key();
+
+        let address = start_server(
+            "127.0.0.1:2012".parse().unwrap(),
+            Handle::current(),
+            alice_signer(),
+            keystore.clone(),
+            shielding_key,
+        )
+        .await;
+
+        let rotated_key = send_signed(address, ROTATE_SHIELDING_KEY_METHOD, RotateShieldingKeyPayload { bits: None })
+            .await
+            .unwrap();
+        let rotated_key = serde_json::from_value::<ShieldingPublicKey>(rotated_key)
+            .unwrap()
+            .to_rsa()
+            .unwrap();
+        assert_ne!(rotated_key, previous_key);
+
+        let seed = hex::decode(SR25519_SEED).unwrap();
+        let payload = ImportRelayerKeyPayload {
+            id: "old".to_string(),
+            key_type: KeyType::Sr25519,
+            key: shield(&previous_key, &seed).unwrap(),
+        };
+        // rotated key keeps the size, so value shielded with the previous one fits but can't be decrypted
+        let response = send_signed(address, IMPORT_RELAYER_KEY_METHOD, payload).await;
+        assert_eq!(response, Err(SHIELDED_VALUE_DECRYPTION_ERROR_CODE));
+
+        let payload = ImportRelayerKeyPayload {
+            id: "new".to_string(),
+            key_type: KeyType::Sr25519,
+            key: shield(&rotated_key, &seed).unwrap(),
+        };
+        assert!(send_signed(address, IMPORT_RELAYER_KEY_METHOD, payload).await.is_ok());
+
+        assert!(keystore.read().unwrap().get_key("old").is_none());
+        assert_eq!(keystore.read().unwrap().get_key("new").unwrap(), &seed);
+    }
+
+    #[tokio::test]
+    pub async fn rotate_shielding_key_should_reject_too_large_key() {
+        let shielding_key = GlobalContext::setup();
+        let previous_key = shielding_key.current().public_key();
+
+        let address = start_server(
+            "127.0.0.1:2015".parse().unwrap(),
+            Handle::current(),
+            alice_signer(),
+            Arc::new(RwLock::new(InMemoryKeystore::new())),
+            shielding_key.clone(),
+        )
+        .await;
+
+        let payload = RotateShieldingKeyPayload { bits: Some(MAX_SHIELDING_KEY_BITS + 1) };
+        let response = send_signed(address, ROTATE_SHIELDING_KEY_METHOD, payload).await;
+        assert_eq!(response, Err(SHIELDING_KEY_ROTATION_ERROR_CODE));
+        assert_eq!(shielding_key.current().public_key(), previous_key);
+    }
+
+    #[tokio::test]
+    pub async fn restart_unknown_listener_should_fail() {
+        let listeners = Arc::new(ListenerRegistry::default());
+        let address = start_admin_server(
+            "127.0.0.1:2006".parse().unwrap(),
+            Handle::current(),
+            alice_signer(),
+            listeners,
+            serde_json::Value::Null,
+        )
+        .await;
+
+        let key = sp_core::ecdsa::Pair::from_string("//Alice", None).unwrap();
+        let payload = RestartListenerPayload { id: "unknown".to_string(), start_block: None };
+        let signature = key
+            .sign_prehashed(&keccak_256(&serde_json::to_vec(&payload).unwrap()))
+            .0
+            .to_vec();
+        let body = serde_json::json!({
+            "jsonrpc": "2.0",
+            "method": "hm_restartListener",
+            "params": SignedParams { payload, signature },
+            "id": "5"
+        });
+
+        let response = reqwest::Client::new()
+            .post(format!("http://{}", address))
+            .body(body.to_string())
+            .header("Content-Type", "application/json")
+            .send()
+            .await
+            .unwrap();
 
-        let _shielded_key = shielding_key
+        let response_bytes = &response.bytes().await.unwrap();
+        let json_rpc_response = serde_json::from_slice::<Response<&JsonRawValue>>(response_bytes).unwrap();
+
+        assert!(matches!(json_rpc_response.payload, ResponsePayload::Error(e) if e.code() == LISTENER_NOT_FOUND_CODE));
+    }
+
+    #[tokio::test]
+    pub async fn import_relayer_key_with_sr25519_signer_works() {
+        let shielding_key = GlobalContext::setup();
+        let keystore = Arc::new(RwLock::new(InMemoryKeystore::new()));
+
+        let shielded_key = shielding_key
+            .current()
             .public_key()
             .encrypt(&mut OsRng, Oaep::new::<Sha256>(), hex::decode(SR25519_SEED).unwrap().as_slice())
             .unwrap();
 
-        let address = start_server("127.0.0.1:2005", Handle::current(), alice_signer(), keystore, shielding_key).await;
+        let address = start_server(
+            "127.0.0.1:2009".parse().unwrap(),
+            Handle::current(),
+            alice_sr25519_signer(),
+            keystore.clone(),
+            shielding_key,
+        )
+        .await;
+
+        // sr25519 signs raw payload bytes, no prehashing
+        let key = sp_core::sr25519::Pair::from_string("//Alice", None).unwrap();
+        let payload =
+            ImportRelayerKeyPayload { id: "rococo".to_string(), key_type: KeyType::Sr25519, key: shielded_key };
+        let signature = key.sign(&serde_json::to_vec(&payload).unwrap()).0.to_vec();
+        let body = serde_json::json!({
+            "jsonrpc": "2.0",
+            "method": "hm_importRelayerKey",
+            "params": SignedParams { payload, signature },
+            "id": "5"
+        });
 
-        let client = reqwest::Client::new();
+        let response = reqwest::Client::new()
+            .post(format!("http://{}", address))
+            .body(body.to_string())
+            .header("Content-Type", "application/json")
+            .send()
+            .await
+            .unwrap();
+
+        let response_bytes = &response.bytes().await.unwrap();
+        let json_rpc_response = serde_json::from_slice::<Response<&JsonRawValue>>(response_bytes).unwrap();
+        assert!(matches!(json_rpc_response.payload, ResponsePayload::Success(_)));
+
+        assert_eq!(keystore.read().unwrap().get_key("rococo").unwrap(), &hex::decode(SR25519_SEED).unwrap());
+    }
+
+    #[tokio::test]
+    pub async fn ecdsa_signature_should_not_authorize_sr25519_signer() {
+        let listeners = Arc::new(ListenerRegistry::default());
+        let address = start_admin_server(
+            "127.0.0.1:2010".parse().unwrap(),
+            Handle::current(),
+            alice_sr25519_signer(),
+            listeners,
+            serde_json::Value::Null,
+        )
+        .await;
+
+        let key = sp_core::ecdsa::Pair::from_string("//Alice", None).unwrap();
+        let payload = RestartListenerPayload { id: "unknown".to_string(), start_block: None };
+        let signature = key
+            .sign_prehashed(&keccak_256(&serde_json::to_vec(&payload).unwrap()))
+            .0
+            .to_vec();
+        let body = serde_json::json!({
+            "jsonrpc": "2.0",
+            "method": "hm_restartListener",
+            "params": SignedParams { payload, signature },
+            "id": "5"
+        });
+
+        let response = reqwest::Client::new()
+            .post(format!("http://{}", address))
+            .body(body.to_string())
+            .header("Content-Type", "application/json")
+            .send()
+            .await
+            .unwrap();
+
+        let response_bytes = &response.bytes().await.unwrap();
+        let json_rpc_response = serde_json::from_slice::<Response<&JsonRawValue>>(response_bytes).unwrap();
+
+        assert!(
+            matches!(json_rpc_response.payload, ResponsePayload::Error(e) if e.code() == UNAUTHORIZED_REQUEST_CODE)
+        );
+    }
+
+    #[tokio::test]
+    pub async fn bridge_stats_works() {
+        let listeners = Arc::new(ListenerRegistry::default());
+        let address = start_admin_server(
+            "127.0.0.1:2007".parse().unwrap(),
+            Handle::current(),
+            alice_signer(),
+            listeners,
+            serde_json::Value::Null,
+        )
+        .await;
 
         let body = r#"
         {
             "jsonrpc": "2.0",
-            "method": "hm_importRelayerKey",
-            "params": {
-                "payload": {"id":"rococo", "key":"3bac64ca36d1a64c0c70ff4759f47246253d4fab94e1316e98fb038b7a55bb95fd741f38bbd779ed6b8c0264789f9fac398aba8071c68aa17ee23251eb1e12dd90f92ea9942ee9018075a9c317353b51ceb545caa210d8deb47de356912def894bbb2c77159054fe04f55c661cee218abe7b51e8c37d122a51fd88645664e167b3827a324c37a9d557cc6200f78941a6e225735a441c17d2a1e48c494c32b7317f08b2ff461ef5e8caa9e92960b79a559c0a7b3eff954528bad87f2ffc92fe2ca57bc43c59b48a88f7b4f2f5dd4bcacaec1565967e9eb8131f8db5b69606920560d441de41402e6e0526733ac6f4a1f970b103f62739cf8c4c038376e8ff4100"},
-                "signature": "6f3b1b29361cfddbc84a6ae6d192e983a20c73e6f6aad3942c234d9f99e218fd129796424864c56b1263cc9246c18cfa21965045a2f5c9f8c1527dc309bfbbbd01"
-            },
+            "method": "hm_bridgeStats",
+            "params": {},
             "id": "5"
         }
         "#;
 
-        let response = client
-            .post(format!("http://{}", address.to_string()))
+        let response = reqwest::Client::new()
+            .post(format!("http://{}", address))
             .body(body)
             .header("Content-Type", "application/json")
             .send()
@@ -196,15 +576,59 @@ mod test {
             .unwrap();
 
         let response_bytes = &response.bytes().await.unwrap();
+        let json_rpc_response = serde_json::from_slice::<Response<&JsonRawValue>>(response_bytes).unwrap();
 
-        let json_rpc_response =
-            Response::try_from(serde_json::from_slice::<Response<&JsonRawValue>>(response_bytes).unwrap()).unwrap();
-        assert!(matches!(json_rpc_response.payload, ResponsePayload::Success(_)));
+        assert!(matches!(json_rpc_response.payload, ResponsePayload::Success(b) if b.get() == "{}"));
+    }
 
-        let path: PathBuf = data_dir.join("rococo.bin");
-        assert!(path.is_file());
-        let read_key = fs::read(path).unwrap();
-        assert_eq!(read_key, hex::decode(SR25519_SEED).unwrap());
-        fs::remove_dir_all(data_dir).unwrap();
+    #[tokio::test]
+    pub async fn get_config_works() {
+        let listeners = Arc::new(ListenerRegistry::default());
+        let config =
+            serde_json::json!({"listeners": [], "relayers": [{"id": "sepolia", "config": {"rpc_auth": "***"}}]});
+        let address = start_admin_server(
+            "127.0.0.1:2011".parse().unwrap(),
+            Handle::current(),
+            alice_signer(),
+            listeners,
+            config.clone(),
+        )
+        .await;
+
+        let body = serde_json::json!({
+            "jsonrpc": "2.0",
+            "method": "hm_getConfig",
+            "params": {},
+            "id": "5"
+        });
+
+        let response = reqwest::Client::new()
+            .post(format!("http://{}", address))
+            .body(body.to_string())
+            .header("Content-Type", "application/json")
+            .send()
+            .await
+            .unwrap();
+
+        let response_bytes = &response.bytes().await.unwrap();
+        let json_rpc_response = serde_json::from_slice::<Response<&JsonRawValue>>(response_bytes).unwrap();
+
+        assert!(matches!(json_rpc_response.payload, ResponsePayload::Success(b)
+            if serde_json::from_str::<serde_json::Value>(b.get()).unwrap() == config));
+    }
+
+    #[tokio::test]
+    pub async fn status_endpoint_works() {
+        let listeners = Arc::new(ListenerRegistry::default());
+        let address = start_status_server("127.0.0.1:2008".parse().unwrap(), Handle::current(), listeners).await;
+
+        let response = reqwest::Client::new()
+            .get(format!("http://{}/status", address))
+            .send()
+            .await
+            .unwrap();
+
+        assert!(response.status().is_success());
+        assert_eq!(response.text().await.unwrap(), "[]");
     }
 }
@@ -14,17 +14,49 @@
 // You should have received a copy of the GNU General Public License
 // along with Litentry.  If not, see <https://www.gnu.org/licenses/>.
 
-use rsa::{RsaPrivateKey, RsaPublicKey};
+use aes_gcm::aead::{Aead, AeadCore, KeyInit, OsRng};
+use aes_gcm::{Aes256Gcm, Key, Nonce};
+use bridge_core::shielding::{max_oaep_len, oaep_encrypt};
+use clap::ValueEnum;
+use log::error;
+use rsa::pkcs8::{EncodePublicKey, LineEnding};
+use rsa::traits::PublicKeyParts;
+use rsa::{Oaep, RsaPrivateKey, RsaPublicKey};
+use sha2::Sha256;
+use std::sync::{Arc, RwLock};
+
+const AES_NONCE_LEN: usize = 12;
+
+pub const DEFAULT_SHIELDING_KEY_BITS: usize = 3072;
+pub const MIN_SHIELDING_KEY_BITS: usize = 1024;
+pub const MAX_SHIELDING_KEY_BITS: usize = 8192;
+
+/// Standard encodings of the shielding public key, both wrap the key in SubjectPublicKeyInfo.
+#[derive(Clone, Copy, Debug, Default, PartialEq, ValueEnum)]
+pub enum PublicKeyEncoding {
+    #[default]
+    Pem,
+    Der,
+}
 
 pub struct ShieldingKey {
     key: RsaPrivateKey,
 }
 
+impl Default for ShieldingKey {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl ShieldingKey {
     pub fn new() -> Self {
-        // create new
+        Self::with_bits(DEFAULT_SHIELDING_KEY_BITS)
+    }
+
+    /// Generates key of given size, larger keys take noticeably longer to generate.
+    pub fn with_bits(bits: usize) -> Self {
         let mut rng = rand::thread_rng();
-        let bits = 3072;
         let key = RsaPrivateKey::new(&mut rng, bits).expect("failed to generate a key");
         Self { key }
     }
@@ -38,7 +70,230 @@ impl ShieldingKey {
         self.key.to_public_key()
     }
 
-    pub fn private_key(&self) -> &RsaPrivateKey {
-        &self.key
+    pub fn bits(&self) -> usize {
+        self.key.size() * 8
+    }
+
+    /// Encodes public key as SPKI PEM, e.g. for `openssl pkeyutl -encrypt -pubin`.
+    #[allow(clippy::result_unit_err)]
+    pub fn to_pem(&self) -> Result<String, ()> {
+        self.public_key().to_public_key_pem(LineEnding::LF).map_err(|e| {
+            error!("Could not encode shielding key as PEM: {:?}", e);
+        })
+    }
+
+    #[allow(clippy::result_unit_err)]
+    pub fn to_der(&self) -> Result<Vec<u8>, ()> {
+        self.public_key()
+            .to_public_key_der()
+            .map(|der| der.as_bytes().to_vec())
+            .map_err(|e| {
+                error!("Could not encode shielding key as DER: {:?}", e);
+            })
+    }
+
+    #[allow(clippy::result_unit_err)]
+    pub fn write_public_key(&self, path: &str, encoding: PublicKeyEncoding) -> Result<(), ()> {
+        let encoded = match encoding {
+            PublicKeyEncoding::Pem => self.to_pem()?.into_bytes(),
+            PublicKeyEncoding::Der => self.to_der()?,
+        };
+        std::fs::write(path, encoded).map_err(|e| {
+            error!("Could not write shielding key to {}: {:?}", path, e);
+        })
+    }
+
+    /// Decrypts value shielded with `shield`, both direct and hybrid modes are supported.
+    #[allow(clippy::result_unit_err)]
+    pub fn unshield(&self, shielded: &[u8]) -> Result<Vec<u8>, ()> {
+        let key_size = self.key.size();
+        if shielded.len() == key_size {
+            return self.key.decrypt(Oaep::new::<Sha256>(), shielded).map_err(|e| {
+                error!("Could not decrypt shielded value: {:?}", e);
+            });
+        }
+        if shielded.len() < key_size + AES_NONCE_LEN {
+            error!("Shielded value has invalid length: {}", shielded.len());
+            return Err(());
+        }
+
+        let (wrapped_key, rest) = shielded.split_at(key_size);
+        let (nonce, ciphertext) = rest.split_at(AES_NONCE_LEN);
+        let aes_key = self.key.decrypt(Oaep::new::<Sha256>(), wrapped_key).map_err(|e| {
+            error!("Could not unwrap AES key: {:?}", e);
+        })?;
+        if aes_key.len() != 32 {
+            error!("Unwrapped AES key has invalid length: {}", aes_key.len());
+            return Err(());
+        }
+        Aes256Gcm::new(Key::<Aes256Gcm>::from_slice(&aes_key))
+            .decrypt(Nonce::from_slice(nonce), ciphertext)
+            .map_err(|e| {
+                error!("Could not decrypt shielded value: {:?}", e);
+            })
+    }
+}
+
+/// Shielding key which can be replaced while the worker runs, e.g. when the private key is suspected compromised.
+/// Requests take the current key once, so imports in flight during rotation complete with the key they started with.
+pub struct RotatingShieldingKey {
+    current: RwLock<Arc<ShieldingKey>>,
+    // public key is re-written here after every rotation
+    public_key_out: Option<(String, PublicKeyEncoding)>,
+}
+
+impl RotatingShieldingKey {
+    pub fn new(key: ShieldingKey) -> Self {
+        Self { current: RwLock::new(Arc::new(key)), public_key_out: None }
+    }
+
+    pub fn with_public_key_out(mut self, path: String, encoding: PublicKeyEncoding) -> Self {
+        self.public_key_out = Some((path, encoding));
+        self
+    }
+
+    pub fn current(&self) -> Arc<ShieldingKey> {
+        self.current.read().unwrap().clone()
+    }
+
+    /// Generates new key of given size and swaps it in once it's published, the previous key stays in use until then.
+    /// Like the key generated at startup, the rotated key is kept only in memory, so it does not survive a restart and
+    /// values shielded with it can't be decrypted after one.
+    #[allow(clippy::result_unit_err)]
+    pub fn rotate(&self, bits: usize) -> Result<Arc<ShieldingKey>, ()> {
+        let key = Arc::new(ShieldingKey::with_bits(bits));
+        if let Some((ref path, encoding)) = self.public_key_out {
+            key.write_public_key(path, encoding)?;
+        }
+        *self.current.write().unwrap() = key.clone();
+        Ok(key)
+    }
+}
+
+/// Shields `value` with RSA-OAEP if it fits, otherwise with AES-256-GCM using a random key which is RSA-OAEP wrapped.
+/// Hybrid wire format: `wrapped AES key (RSA key size) || nonce (12 bytes) || AES ciphertext with tag`.
+#[allow(clippy::result_unit_err)]
+pub fn shield(public_key: &RsaPublicKey, value: &[u8]) -> Result<Vec<u8>, ()> {
+    if value.len() <= max_oaep_len(public_key) {
+        return oaep_encrypt(public_key, value);
+    }
+
+    let aes_key = Aes256Gcm::generate_key(&mut OsRng);
+    let nonce = Aes256Gcm::generate_nonce(&mut OsRng);
+    let ciphertext = Aes256Gcm::new(&aes_key).encrypt(&nonce, value).map_err(|e| {
+        error!("Could not encrypt value: {:?}", e);
+    })?;
+    let wrapped_key = public_key
+        .encrypt(&mut OsRng, Oaep::new::<Sha256>(), aes_key.as_slice())
+        .map_err(|e| {
+            error!("Could not wrap AES key: {:?}", e);
+        })?;
+
+    let mut shielded = wrapped_key;
+    shielded.extend_from_slice(nonce.as_slice());
+    shielded.extend(ciphertext);
+    Ok(shielded)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // small key keeps the tests fast, shielding works alike for every size
+    const TEST_KEY_BITS: usize = 1024;
+
+    #[test]
+    pub fn small_value_is_shielded_directly() {
+        let shielding_key = ShieldingKey::with_bits(TEST_KEY_BITS);
+        let value = [7u8; 32];
+
+        let shielded = shield(&shielding_key.public_key(), &value).unwrap();
+
+        assert_eq!(shielded.len(), shielding_key.public_key().size());
+        assert_eq!(shielding_key.unshield(&shielded).unwrap(), value);
+    }
+
+    #[test]
+    pub fn large_value_is_shielded_with_hybrid_scheme() {
+        let shielding_key = ShieldingKey::with_bits(TEST_KEY_BITS);
+        let value: Vec<u8> = (0..4096).map(|i| i as u8).collect();
+
+        let shielded = shield(&shielding_key.public_key(), &value).unwrap();
+
+        assert!(shielded.len() > shielding_key.public_key().size());
+        assert_eq!(shielding_key.unshield(&shielded).unwrap(), value);
+    }
+
+    #[test]
+    pub fn key_is_generated_with_requested_size() {
+        assert_eq!(ShieldingKey::with_bits(TEST_KEY_BITS).public_key().size() * 8, TEST_KEY_BITS);
+    }
+
+    #[test]
+    pub fn public_key_is_encoded_as_spki() {
+        use rsa::pkcs8::DecodePublicKey;
+
+        let shielding_key = ShieldingKey::with_bits(TEST_KEY_BITS);
+
+        let pem = shielding_key.to_pem().unwrap();
+        assert!(pem.starts_with("-----BEGIN PUBLIC KEY-----"));
+        assert_eq!(RsaPublicKey::from_public_key_pem(&pem).unwrap(), shielding_key.public_key());
+        assert_eq!(
+            RsaPublicKey::from_public_key_der(&shielding_key.to_der().unwrap()).unwrap(),
+            shielding_key.public_key()
+        );
+    }
+
+    #[test]
+    pub fn public_key_is_written_in_requested_encoding() {
+        use rsa::pkcs8::DecodePublicKey;
+
+        let dir = tempfile::tempdir().unwrap();
+        let shielding_key = ShieldingKey::with_bits(TEST_KEY_BITS);
+        let pem_path = dir.path().join("shielding_key.pem");
+        let der_path = dir.path().join("shielding_key.der");
+
+        shielding_key
+            .write_public_key(pem_path.to_str().unwrap(), PublicKeyEncoding::Pem)
+            .unwrap();
+        shielding_key
+            .write_public_key(der_path.to_str().unwrap(), PublicKeyEncoding::Der)
+            .unwrap();
+
+        let pem = std::fs::read_to_string(pem_path).unwrap();
+        assert_eq!(RsaPublicKey::from_public_key_pem(&pem).unwrap(), shielding_key.public_key());
+        let der = std::fs::read(der_path).unwrap();
+        assert_eq!(RsaPublicKey::from_public_key_der(&der).unwrap(), shielding_key.public_key());
+    }
+
+    #[test]
+    pub fn rotation_replaces_key_and_publishes_it() {
+        use rsa::pkcs8::DecodePublicKey;
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("shielding_key.pem").to_str().unwrap().to_string();
+        let shielding_key = RotatingShieldingKey::new(ShieldingKey::with_bits(TEST_KEY_BITS))
+            .with_public_key_out(path.clone(), PublicKeyEncoding::Pem);
+        let previous = shielding_key.current();
+
+        let rotated = shielding_key.rotate(TEST_KEY_BITS).unwrap();
+
+        assert_ne!(rotated.public_key(), previous.public_key());
+        assert_eq!(shielding_key.current().public_key(), rotated.public_key());
+        assert_eq!(rotated.bits(), TEST_KEY_BITS);
+        let pem = std::fs::read_to_string(path).unwrap();
+        assert_eq!(RsaPublicKey::from_public_key_pem(&pem).unwrap(), rotated.public_key());
+    }
+
+    #[test]
+    pub fn tampered_hybrid_value_is_rejected() {
+        let shielding_key = ShieldingKey::with_bits(TEST_KEY_BITS);
+        let value = vec![1u8; 1024];
+
+        let mut shielded = shield(&shielding_key.public_key(), &value).unwrap();
+        let last = shielded.len() - 1;
+        shielded[last] ^= 1;
+
+        assert!(shielding_key.unshield(&shielded).is_err());
     }
 }
@@ -0,0 +1,392 @@
+// Copyright 2020-2024 Trust Computing GmbH.
+// This file is part of Litentry.
+//
+// Litentry is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// Litentry is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with Litentry.  If not, see <https://www.gnu.org/licenses/>.
+
+use crate::listeners::{ListenerDefinition, ListenerRegistry};
+use crate::rpc::auth::AuthSigner;
+use crate::rpc::server::{start_admin_server, start_status_server};
+use bridge_core::config::BridgeConfig;
+use bridge_core::listener::{prepare_listener_context, ListenerContext};
+use bridge_core::notification::Notifier;
+use bridge_core::relay::Relayer;
+use bridge_core::start_block::StartBlockOverride;
+use bridge_core::validation::Validator;
+use ethereum_listener::listener::ListenerConfig as EthereumListenerConfig;
+use log::*;
+use std::collections::HashMap;
+use std::net::SocketAddr;
+use std::sync::Arc;
+use std::time::Duration;
+use substrate_listener::listener::ListenerConfig as SubstrateListenerConfig;
+use substrate_listener::CustomConfig;
+use tokio::runtime::Handle;
+
+pub struct AdminRpcOptions {
+    pub address: SocketAddr,
+    pub auth_signer: AuthSigner,
+}
+
+/// Runtime options of the worker which are not part of the bridge config
+#[derive(Default)]
+pub struct WorkerOptions {
+    pub keystore_dir: String,
+    // start block overrides keyed by listener id
+    pub start_blocks: HashMap<String, StartBlockOverride>,
+    // start listeners even if their start block override fails sanity checks
+    pub force_start_block: bool,
+    pub status_address: Option<SocketAddr>,
+    pub admin_rpc: Option<AdminRpcOptions>,
+    pub catch_up_only: bool,
+    // listeners sync as tasks of the worker's runtime instead of a thread each
+    pub shared_runtime: bool,
+}
+
+/// Creates relayers and listeners described by bridge config and runs them, optionally with status and admin servers.
+pub struct BridgeWorker {
+    config: BridgeConfig,
+    options: WorkerOptions,
+    custom_listeners: Vec<ListenerDefinition>,
+}
+
+impl BridgeWorker {
+    pub fn from_config(config: BridgeConfig, options: WorkerOptions) -> Self {
+        Self { config, options, custom_listeners: vec![] }
+    }
+
+    /// Runs additional listener, not described by bridge config, alongside configured ones.
+    pub fn with_listener(mut self, definition: ListenerDefinition) -> Self {
+        self.custom_listeners.push(definition);
+        self
+    }
+
+    pub async fn start(self) -> Result<WorkerHandle, ()> {
+        let config = self.config;
+        let options = self.options;
+
+        config.validate().map_err(|e| {
+            error!("Config validation error: {:?}", e);
+        })?;
+        let redacted_config = config.redacted();
+
+        #[allow(clippy::type_complexity)]
+        let mut relayers: HashMap<String, HashMap<String, Arc<Box<dyn Relayer<String>>>>> = HashMap::new();
+
+        // shared by relayers and listeners, disabled without `notifications` config
+        let notifier = Arc::new(Notifier::from_config(config.notifications.as_ref()));
+
+        // substrate relayers
+        let substrate_relayers: HashMap<String, Arc<Box<dyn Relayer<String>>>> =
+            substrate_relayer::create_from_config::<CustomConfig>(
+                options.keystore_dir.clone(),
+                &config.relayers,
+                notifier.clone(),
+            )
+            .await?;
+        relayers.insert("substrate".to_string(), substrate_relayers);
+
+        // ethereum relayers
+        let ethereum_relayers: HashMap<String, Arc<Box<dyn Relayer<String>>>> =
+            ethereum_relayer::create_from_config(options.keystore_dir.clone(), &config, notifier.clone()).await?;
+        relayers.insert("ethereum".to_string(), ethereum_relayers);
+
+        // validators of relayed requests, keyed by relayer id
+        let validators: HashMap<String, Arc<dyn Validator>> = ethereum_relayer::create_validators(&config)?;
+
+        let mut definitions = vec![];
+
+        let ethereum_listener_contexts: Vec<ListenerContext<EthereumListenerConfig>> = prepare_listener_context(
+            &config,
+            "ethereum",
+            &relayers,
+            &validators,
+            &options.start_blocks,
+            options.force_start_block,
+            &notifier,
+        )?;
+        definitions.extend(ethereum_listener_contexts.into_iter().map(ListenerDefinition::Ethereum));
+
+        let substrate_listener_contexts: Vec<ListenerContext<SubstrateListenerConfig>> = prepare_listener_context(
+            &config,
+            "substrate",
+            &relayers,
+            &validators,
+            &options.start_blocks,
+            options.force_start_block,
+            &notifier,
+        )?;
+        definitions.extend(substrate_listener_contexts.into_iter().map(ListenerDefinition::Substrate));
+
+        definitions.extend(self.custom_listeners);
+
+        let listeners = Arc::new(ListenerRegistry::default().with_shared_runtime(options.shared_runtime));
+        for mut definition in definitions {
+            if options.catch_up_only {
+                definition.set_catch_up_only(true);
+            }
+            let id = definition.id().to_string();
+            if listeners.start(definition).await.is_err() {
+                error!("Could not start {} listener", id);
+                listeners.stop_all().await;
+                return Err(());
+            }
+        }
+
+        let status_address = match options.status_address {
+            Some(address) => Some(start_status_server(address, Handle::current(), listeners.clone()).await),
+            None => None,
+        };
+
+        let admin_rpc_address = match options.admin_rpc {
+            Some(admin_rpc) => Some(
+                start_admin_server(
+                    admin_rpc.address,
+                    Handle::current(),
+                    admin_rpc.auth_signer,
+                    listeners.clone(),
+                    redacted_config,
+                )
+                .await,
+            ),
+            None => None,
+        };
+
+        Ok(WorkerHandle { listeners, status_address, admin_rpc_address })
+    }
+}
+
+/// Handle of started worker. Servers keep running until the process exits, only listeners are stopped by `stop`.
+pub struct WorkerHandle {
+    listeners: Arc<ListenerRegistry>,
+    status_address: Option<SocketAddr>,
+    admin_rpc_address: Option<SocketAddr>,
+}
+
+impl WorkerHandle {
+    pub fn listeners(&self) -> Arc<ListenerRegistry> {
+        self.listeners.clone()
+    }
+
+    // bound addresses, useful when servers were started on port 0
+    pub fn status_address(&self) -> Option<SocketAddr> {
+        self.status_address
+    }
+
+    pub fn admin_rpc_address(&self) -> Option<SocketAddr> {
+        self.admin_rpc_address
+    }
+
+    /// Waits until all listeners stop syncing, e.g. because they caught up in catch-up-only mode
+    pub async fn wait(&self) {
+        while !self.listeners.all_stopped().await {
+            tokio::time::sleep(Duration::from_secs(1)).await;
+        }
+    }
+
+    pub async fn stop(self) {
+        self.listeners.stop_all().await;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::listeners::{CreateListenerFn, ListenerControlError};
+    use bridge_core::dead_letter::DeadLetterLog;
+    use bridge_core::listener::{SyncListener, DEFAULT_FETCH_TIMEOUT, DEFAULT_POLL_INTERVAL};
+    use bridge_core::listener_control::ListenerControl;
+    use bridge_core::listener_status::ListenerStatus;
+    use bridge_core::nonce_tracker::NonceTracker;
+    use bridge_core::relay_stats::RelayStats;
+    use std::future::Future;
+    use std::pin::Pin;
+    use std::sync::atomic::{AtomicU32, Ordering};
+    use tokio::sync::oneshot::error::TryRecvError;
+    use tokio::sync::oneshot::Receiver;
+
+    // syncs until stop signal is received or, in catch-up-only mode, returns immediately
+    struct MockListener {
+        stop_receiver: Receiver<()>,
+        catch_up_only: bool,
+        relay_stats: Arc<RelayStats>,
+        // time it takes to stop after stop signal, like a listener in the middle of relaying
+        stop_delay: Duration,
+    }
+
+    impl SyncListener for MockListener {
+        fn sync(&mut self) -> Result<(), ()> {
+            while !self.catch_up_only && matches!(self.stop_receiver.try_recv(), Err(TryRecvError::Empty)) {
+                std::thread::sleep(Duration::from_millis(10));
+            }
+            std::thread::sleep(self.stop_delay);
+            Ok(())
+        }
+
+        fn sync_async(&mut self) -> Pin<Box<dyn Future<Output = Result<(), ()>> + Send + '_>> {
+            Box::pin(async move {
+                while !self.catch_up_only && matches!(self.stop_receiver.try_recv(), Err(TryRecvError::Empty)) {
+                    tokio::time::sleep(Duration::from_millis(10)).await;
+                }
+                tokio::time::sleep(self.stop_delay).await;
+                Ok(())
+            })
+        }
+
+        fn relay_stats(&self) -> Arc<RelayStats> {
+            self.relay_stats.clone()
+        }
+    }
+
+    fn empty_config() -> BridgeConfig {
+        BridgeConfig { listeners: vec![], relayers: vec![], resources: Default::default(), notifications: None }
+    }
+
+    fn mock_listener(id: &str, created: Arc<AtomicU32>) -> ListenerDefinition {
+        slow_mock_listener(id, created, Duration::ZERO)
+    }
+
+    fn slow_mock_listener(id: &str, created: Arc<AtomicU32>, stop_delay: Duration) -> ListenerDefinition {
+        let context = ListenerContext {
+            id: id.to_string(),
+            config: serde_json::Value::Null,
+            start_block: Default::default(),
+            chain_id: 0,
+            relayers: HashMap::new(),
+            relayer_destinations: HashMap::new(),
+            control: Arc::new(ListenerControl::in_memory()),
+            status: Arc::new(ListenerStatus::new(id, 0)),
+            checkpoint_batching: Default::default(),
+            dead_letter_log: Arc::new(DeadLetterLog::in_memory()),
+            skip_failed_events: false,
+            validators: HashMap::new(),
+            fetch_timeout: DEFAULT_FETCH_TIMEOUT,
+            catch_up_only: false,
+            nonce_tracker: Arc::new(NonceTracker::in_memory()),
+            halt_on_nonce_gap: false,
+            start_nonce: None,
+            poll_interval: DEFAULT_POLL_INTERVAL,
+            fast_sync_interval: Duration::ZERO,
+            resource_registry: Default::default(),
+            notifier: Default::default(),
+            data_lock: None,
+        };
+        let create: CreateListenerFn = Arc::new(
+            move |context: &ListenerContext<serde_json::Value>,
+                  stop_receiver: Receiver<()>|
+                  -> Result<Box<dyn SyncListener>, ()> {
+                created.fetch_add(1, Ordering::SeqCst);
+                Ok(Box::new(MockListener {
+                    stop_receiver,
+                    catch_up_only: context.catch_up_only,
+                    relay_stats: Arc::new(RelayStats::in_memory(&context.id)),
+                    stop_delay,
+                }))
+            },
+        );
+        ListenerDefinition::Custom(context, create)
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn should_start_and_stop_listeners() {
+        let created = Arc::new(AtomicU32::new(0));
+        let options = WorkerOptions { status_address: Some("127.0.0.1:0".parse().unwrap()), ..Default::default() };
+
+        let worker = BridgeWorker::from_config(empty_config(), options)
+            .with_listener(mock_listener("mock_1", created.clone()))
+            .with_listener(mock_listener("mock_2", created.clone()));
+        let handle = worker.start().await.unwrap();
+
+        assert_eq!(created.load(Ordering::SeqCst), 2);
+        assert!(handle.status_address().is_some());
+        assert!(handle.admin_rpc_address().is_none());
+        let status = handle.listeners().status().await;
+        assert_eq!(status.iter().map(|s| s.id.as_str()).collect::<Vec<_>>(), vec!["mock_1", "mock_2"]);
+        assert!(!handle.listeners().all_stopped().await);
+
+        let listeners = handle.listeners();
+        handle.stop().await;
+
+        assert!(listeners.all_stopped().await);
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn should_finish_once_listeners_caught_up() {
+        let created = Arc::new(AtomicU32::new(0));
+        let options = WorkerOptions { catch_up_only: true, ..Default::default() };
+
+        let handle = BridgeWorker::from_config(empty_config(), options)
+            .with_listener(mock_listener("mock", created.clone()))
+            .start()
+            .await
+            .unwrap();
+
+        tokio::time::timeout(Duration::from_secs(5), handle.wait()).await.unwrap();
+        assert_eq!(created.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn should_start_and_stop_listeners_on_shared_runtime() {
+        let created = Arc::new(AtomicU32::new(0));
+        let options = WorkerOptions { shared_runtime: true, ..Default::default() };
+
+        let handle = BridgeWorker::from_config(empty_config(), options)
+            .with_listener(mock_listener("mock_1", created.clone()))
+            .with_listener(mock_listener("mock_2", created.clone()))
+            .start()
+            .await
+            .unwrap();
+
+        assert_eq!(created.load(Ordering::SeqCst), 2);
+        let listeners = handle.listeners();
+        assert!(!listeners.all_stopped().await);
+        listeners.restart("mock_1", None).await.unwrap();
+        assert_eq!(created.load(Ordering::SeqCst), 3);
+
+        tokio::time::timeout(Duration::from_secs(5), handle.stop()).await.unwrap();
+        assert!(listeners.all_stopped().await);
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn status_should_not_wait_for_restarting_listener() {
+        let created = Arc::new(AtomicU32::new(0));
+        let listeners = Arc::new(ListenerRegistry::default());
+        listeners
+            .start(slow_mock_listener("slow", created.clone(), Duration::from_secs(2)))
+            .await
+            .unwrap();
+        listeners.start(mock_listener("mock", created.clone())).await.unwrap();
+
+        let restart = tokio::spawn({
+            let listeners = listeners.clone();
+            async move { listeners.restart("slow", None).await }
+        });
+        tokio::time::sleep(Duration::from_millis(200)).await;
+
+        // slow listener is still stopping, the others stay controllable
+        let status = tokio::time::timeout(Duration::from_millis(500), listeners.status())
+            .await
+            .unwrap();
+        assert_eq!(status.len(), 2);
+        tokio::time::timeout(Duration::from_millis(500), listeners.pause("mock"))
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(listeners.restart("slow", Some(10)).await, Err(ListenerControlError::Restarting));
+
+        restart.await.unwrap().unwrap();
+        assert_eq!(created.load(Ordering::SeqCst), 3);
+        listeners.stop_all().await;
+        assert!(listeners.all_stopped().await);
+    }
+}
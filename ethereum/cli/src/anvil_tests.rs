@@ -0,0 +1,217 @@
+// Copyright 2020-2024 Trust Computing GmbH.
+// This file is part of Litentry.
+//
+// Litentry is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// Litentry is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with Litentry.  If not, see <https://www.gnu.org/licenses/>.
+
+// Runs against a local anvil node, requires `anvil` binary in PATH and built chainbridge contracts
+// (`make build-evm-contracts`). Run with `cargo test -p ethereum-cli --features anvil-tests -- --ignored`.
+
+use super::*;
+use alloy::node_bindings::{Anvil, AnvilInstance};
+
+struct Deployment {
+    bridge: Address,
+    erc20_handler: Address,
+    lit_token: Address,
+    hei_token: Address,
+}
+
+fn spawn_anvil() -> AnvilInstance {
+    Anvil::new().try_spawn().expect("Could not spawn anvil, is it installed?")
+}
+
+fn private_key(anvil: &AnvilInstance, index: usize) -> String {
+    alloy::hex::encode_prefixed(anvil.keys()[index].to_bytes())
+}
+
+// deploys contracts the same way as docker/deployers.yml does
+async fn deploy(anvil: &AnvilInstance) -> Deployment {
+    let signer = PrivateKeySigner::from(anvil.keys()[0].clone());
+    let admin = signer.address();
+    let provider = ProviderBuilder::new()
+        .with_recommended_fillers()
+        .wallet(EthereumWallet::from(signer))
+        .on_http(anvil.endpoint_url());
+
+    let bridge = Bridge::deploy(&provider, 0, vec![], U256::ZERO, U256::ZERO, U256::ZERO)
+        .await
+        .unwrap();
+    let erc20_handler = ERC20Handler::deploy(&provider, *bridge.address()).await.unwrap();
+    let lit_token = LITToken::deploy(&provider).await.unwrap();
+    let hei_token = HEIToken::deploy(&provider, *lit_token.address(), "Heima".to_string(), "HEI".to_string(), admin)
+        .await
+        .unwrap();
+
+    Deployment {
+        bridge: *bridge.address(),
+        erc20_handler: *erc20_handler.address(),
+        lit_token: *lit_token.address(),
+        hei_token: *hei_token.address(),
+    }
+}
+
+// resource `setup-bridge` wires up without resources file
+fn hei_resource(deployment: &Deployment) -> BridgeResource {
+    BridgeResource {
+        resource_id: FixedBytes(HEI_RESOURCE_ID),
+        handler: deployment.erc20_handler,
+        token: deployment.hei_token,
+        burnable: true,
+    }
+}
+
+#[tokio::test]
+#[ignore]
+async fn resource_mapping_reflects_bridge_setup() {
+    let anvil = spawn_anvil();
+    let deployment = deploy(&anvil).await;
+    let rpc_url = anvil.endpoint();
+    let resource_id = FixedBytes(HEI_RESOURCE_ID);
+
+    let mapping = query_resource_mapping(resource_id, deployment.bridge, &rpc_url).await.unwrap();
+    assert_eq!(mapping, ResourceMapping { handler: Address::ZERO.to_string(), token: None });
+
+    setup_bridge(&private_key(&anvil, 0), &deployment.bridge.to_string(), &[hei_resource(&deployment)], None, &rpc_url)
+        .await
+        .unwrap();
+
+    let mapping = query_resource_mapping(resource_id, deployment.bridge, &rpc_url).await.unwrap();
+    assert_eq!(
+        mapping,
+        ResourceMapping {
+            handler: deployment.erc20_handler.to_string(),
+            token: Some(deployment.hei_token.to_string())
+        }
+    );
+}
+
+#[tokio::test]
+#[ignore]
+async fn every_resource_is_mapped_by_bridge_setup() {
+    let anvil = spawn_anvil();
+    let deployment = deploy(&anvil).await;
+    let rpc_url = anvil.endpoint();
+    let lit_resource = BridgeResource {
+        resource_id: FixedBytes([1; 32]),
+        handler: deployment.erc20_handler,
+        token: deployment.lit_token,
+        burnable: false,
+    };
+
+    let receipts = setup_bridge(
+        &private_key(&anvil, 0),
+        &deployment.bridge.to_string(),
+        &[hei_resource(&deployment), lit_resource],
+        None,
+        &rpc_url,
+    )
+    .await
+    .unwrap();
+
+    // HEI resource is made burnable and its handler minter, LIT is only mapped
+    assert_eq!(receipts.len(), 4);
+    for (resource_id, token) in
+        [(FixedBytes(HEI_RESOURCE_ID), deployment.hei_token), (FixedBytes([1; 32]), deployment.lit_token)]
+    {
+        let mapping = query_resource_mapping(resource_id, deployment.bridge, &rpc_url).await.unwrap();
+        assert_eq!(
+            mapping,
+            ResourceMapping { handler: deployment.erc20_handler.to_string(), token: Some(token.to_string()) }
+        );
+    }
+}
+
+#[tokio::test]
+#[ignore]
+async fn bridge_info_reflects_added_relayers() {
+    let anvil = spawn_anvil();
+    let deployment = deploy(&anvil).await;
+    let rpc_url = anvil.endpoint();
+
+    let info = query_bridge_info(deployment.bridge, &rpc_url).await.unwrap();
+    assert_eq!(info, BridgeInfo { relayer_threshold: 0, relayer_count: 0, paused: false });
+
+    let relayer = PrivateKeySigner::from_str(&private_key(&anvil, 1)).unwrap().address();
+    add_relayer(&private_key(&anvil, 0), &deployment.bridge.to_string(), relayer, &rpc_url)
+        .await
+        .unwrap();
+
+    let info = query_bridge_info(deployment.bridge, &rpc_url).await.unwrap();
+    assert_eq!(info, BridgeInfo { relayer_threshold: 0, relayer_count: 1, paused: false });
+
+    setup_bridge(
+        &private_key(&anvil, 0),
+        &deployment.bridge.to_string(),
+        &[hei_resource(&deployment)],
+        Some(1),
+        &rpc_url,
+    )
+    .await
+    .unwrap();
+
+    let info = query_bridge_info(deployment.bridge, &rpc_url).await.unwrap();
+    assert_eq!(info, BridgeInfo { relayer_threshold: 1, relayer_count: 1, paused: false });
+}
+
+#[tokio::test]
+#[ignore]
+async fn pay_in_emits_deposit_event() {
+    let anvil = spawn_anvil();
+    let deployment = deploy(&anvil).await;
+    let rpc_url = anvil.endpoint();
+    let bridge_private_key = private_key(&anvil, 0);
+
+    setup_bridge(&bridge_private_key, &deployment.bridge.to_string(), &[hei_resource(&deployment)], None, &rpc_url)
+        .await
+        .unwrap();
+
+    let dest_address = "5GrwvaEF5zXb26Fz9rcQpDWS57CtERHpNehXCPcNoHGKutQY";
+    let amount = U256::from(100_000_000_000_000_000_000u128);
+    let conf = PayInCmdConf {
+        dest_address: dest_address.to_string(),
+        amount: amount.to_string(),
+        user_private_key: private_key(&anvil, 1),
+        bridge_private_key,
+        lit_token_address: deployment.lit_token.to_string(),
+        bridge_address: deployment.bridge.to_string(),
+        bridge_erc20_handler_address: deployment.erc20_handler.to_string(),
+        hei_token_address: deployment.hei_token.to_string(),
+        port: anvil.port() as u128,
+    };
+
+    // first deposit
+    let receipts = pay_in(&conf, &rpc_url).await.unwrap();
+    let deposit_receipt = receipts.last().unwrap();
+    let deposit = deposit_receipt
+        .inner
+        .logs()
+        .iter()
+        .find_map(|log| log.log_decode::<Bridge::Deposit>().ok())
+        .unwrap()
+        .inner
+        .data;
+
+    assert_eq!(deposit.destinationDomainID, 2);
+    assert_eq!(deposit.depositNonce, 1);
+    assert_eq!(deposit.user, PrivateKeySigner::from_str(&conf.user_private_key).unwrap().address());
+    // amount, destination address length, destination address
+    assert_eq!(deposit.data.len(), 96);
+    assert_eq!(U256::from_be_slice(&deposit.data[0..32]), amount);
+    assert_eq!(U256::from_be_slice(&deposit.data[32..64]), U256::from(32));
+    assert_eq!(&deposit.data[64..96], AccountId32::from_str(dest_address).unwrap().0.as_slice());
+
+    // nonce is incremented on next deposit
+    let receipts = pay_in(&conf, &rpc_url).await.unwrap();
+    assert_eq!(deposit_nonce(receipts.last().unwrap().inner.logs()), Some(2));
+}
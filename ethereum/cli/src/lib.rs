@@ -18,16 +18,22 @@ use std::str::FromStr;
 use crate::HEIToken::HEITokenInstance;
 use crate::LITToken::LITTokenInstance;
 use alloy::dyn_abi::DynSolValue;
-use alloy::hex::{decode, FromHex};
+use alloy::hex::decode;
 use alloy::network::{Ethereum, EthereumWallet};
-use alloy::primitives::{Address, Bytes, FixedBytes, B256, U256};
+use alloy::primitives::{Address, Bytes, FixedBytes, U256};
 use alloy::providers::fillers::{ChainIdFiller, FillProvider, GasFiller, JoinFill, NonceFiller, WalletFiller};
 use alloy::providers::{Identity, ProviderBuilder, RootProvider};
+use alloy::rpc::types::{Log, TransactionReceipt};
 use alloy::signers::local::PrivateKeySigner;
 use alloy::sol;
+use alloy::transports::http::reqwest::Url;
 use alloy::transports::http::{Client, Http};
-use clap::{Args, Subcommand};
-use log::info;
+use bridge_core::primitives::encode_deposit_data;
+use clap::{Args, FromArgMatches, Subcommand};
+use log::{info, warn};
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::fmt::{Display, Formatter};
 use subxt_core::utils::AccountId32;
 
 #[derive(Subcommand)]
@@ -36,6 +42,35 @@ pub enum EthereumCommand {
     AddRelayer(AddRelayerCmdConf),
     PayIn(PayInCmdConf),
     Balance(BalanceCmdConf),
+    ResourceMapping(ResourceMappingCmdConf),
+    BridgeInfo(BridgeInfoCmdConf),
+}
+
+/// Resource id of HEI token wired up by `setup-bridge`
+const HEI_RESOURCE_ID: [u8; 32] = [
+    158, 230, 223, 182, 26, 47, 185, 3, 223, 72, 124, 64, 22, 99, 130, 86, 67, 187, 130, 93, 65, 105, 94, 99, 223, 138,
+    246, 22, 42, 177, 69, 166,
+];
+
+#[derive(Args)]
+pub struct ResourceMappingCmdConf {
+    #[arg(long, default_value = "0x9ee6dfb61a2fb903df487c401663825643bb825d41695e63df8af6162ab145a6")]
+    resource_id: String,
+    #[arg(long, default_value = "0x5FbDB2315678afecb367f032d93F642f64180aa3")]
+    bridge_address: String,
+    #[arg(long, default_value = "8545")]
+    port: u128,
+}
+
+#[derive(Args)]
+pub struct BridgeInfoCmdConf {
+    #[arg(long, default_value = "0x5FbDB2315678afecb367f032d93F642f64180aa3")]
+    bridge_address: String,
+    /// Also prints handler and token the resource id is mapped to
+    #[arg(long)]
+    resource_id: Option<String>,
+    #[arg(long, default_value = "8545")]
+    port: u128,
 }
 
 #[derive(Args)]
@@ -44,6 +79,15 @@ pub struct BalanceCmdConf {
     token_address: String,
     #[arg(long, default_value = "0x70997970C51812dc3A010C7d01b50e0d17dc79C8")]
     account: String,
+    /// Token decimals, read from the contract if not set
+    #[arg(long)]
+    decimals: Option<u8>,
+    /// Token symbol, read from the contract if not set
+    #[arg(long)]
+    symbol: Option<String>,
+    /// Print only the raw `balanceOf` value
+    #[arg(long)]
+    raw: bool,
     #[arg(long, default_value = "8545")]
     port: u128,
 }
@@ -52,23 +96,31 @@ pub struct BalanceCmdConf {
 // default values works for docker-compose setup
 pub struct PayInCmdConf {
     #[arg(long, default_value = "5GrwvaEF5zXb26Fz9rcQpDWS57CtERHpNehXCPcNoHGKutQY")]
-    dest_address: String,
+    pub dest_address: String,
     #[arg(long, default_value = "100000000000000000000")]
-    amount: String,
+    pub amount: String,
     #[arg(long, default_value = "0x59c6995e998f97a5a0044966f0945389dc9e86dae88c7a8412f4603b6b78690d")]
-    user_private_key: String,
+    pub user_private_key: String,
     #[arg(long, default_value = "0xac0974bec39a17e36ba4a6b4d238ff944bacb478cbed5efcae784d7bf4f2ff80")]
-    bridge_private_key: String,
+    pub bridge_private_key: String,
     #[arg(long, default_value = "0xDc64a140Aa3E981100a9becA4E685f962f0cF6C9")]
-    lit_token_address: String,
+    pub lit_token_address: String,
     #[arg(long, default_value = "0x5FbDB2315678afecb367f032d93F642f64180aa3")]
-    bridge_address: String,
+    pub bridge_address: String,
     #[arg(long, default_value = "0xe7f1725E7734CE288F8367e1Bb143E90bb3F0512")]
-    bridge_erc20_handler_address: String,
+    pub bridge_erc20_handler_address: String,
     #[arg(long, default_value = "0x5FC8d32690cc91D4c39d9d3abcBD16989F875707")]
-    hei_token_address: String,
+    pub hei_token_address: String,
     #[arg(long, default_value = "8545")]
-    port: u128,
+    pub port: u128,
+}
+
+impl Default for PayInCmdConf {
+    // defaults declared on the args
+    fn default() -> Self {
+        let matches = Self::augment_args(clap::Command::new("pay-in")).get_matches_from(["pay-in"]);
+        Self::from_arg_matches(&matches).expect("defaults are valid; qed")
+    }
 }
 
 #[derive(Args)]
@@ -81,10 +133,61 @@ pub struct SetupBridgeCmdConf {
     bridge_erc20_handler_address: String,
     #[arg(long, default_value = "0x5FC8d32690cc91D4c39d9d3abcBD16989F875707")]
     hei_token_address: String,
+    /// JSON file with a list of `resource_id`, `handler_address`, `token_address` and `burnable` entries to set up
+    /// instead of the HEI resource on `bridge_erc20_handler_address`
+    #[arg(long)]
+    resources_file: Option<String>,
+    /// Votes required to execute a proposal, relayer threshold is left unchanged if not set
+    #[arg(long)]
+    vote_threshold: Option<u8>,
+    /// Only warn if the relayer threshold exceeds registered relayers
+    #[arg(long)]
+    force: bool,
     #[arg(long, default_value = "8545")]
     port: u128,
 }
 
+impl SetupBridgeCmdConf {
+    // resources of the file if set, otherwise the burnable HEI token
+    fn resources(&self) -> Result<Vec<BridgeResource>, CliError> {
+        let entries = match self.resources_file {
+            Some(ref path) => {
+                let content = std::fs::read_to_string(path)
+                    .map_err(|e| CliError::InvalidArgument("resources file", format!("{}: {}", path, e)))?;
+                serde_json::from_str(&content)
+                    .map_err(|e| CliError::InvalidArgument("resources file", format!("{}: {}", path, e)))?
+            },
+            None => vec![ResourceEntry {
+                resource_id: FixedBytes(HEI_RESOURCE_ID).to_string(),
+                handler_address: self.bridge_erc20_handler_address.clone(),
+                token_address: self.hei_token_address.clone(),
+                burnable: true,
+            }],
+        };
+        parse_resources(&entries)
+    }
+}
+
+/// Asset wired up by `setup-bridge`, as read from resources file
+#[derive(Debug, Deserialize)]
+pub struct ResourceEntry {
+    pub resource_id: String,
+    pub handler_address: String,
+    pub token_address: String,
+    // burnable tokens are minted by the handler on pay out, others are locked in and released from it
+    #[serde(default)]
+    pub burnable: bool,
+}
+
+/// Validated `ResourceEntry`
+#[derive(Debug, PartialEq)]
+pub struct BridgeResource {
+    pub resource_id: FixedBytes<32>,
+    pub handler: Address,
+    pub token: Address,
+    pub burnable: bool,
+}
+
 #[derive(Args)]
 pub struct AddRelayerCmdConf {
     #[arg(long, default_value = "0x9965507D1a55bcC2695C58ba16FB37d819B0A4dc")]
@@ -103,6 +206,12 @@ sol!(
     Bridge,
     "../chainbridge-contracts/out/Bridge.sol/Bridge.json"
 );
+sol!(
+    #[allow(missing_docs)]
+    #[sol(rpc)]
+    ERC20Handler,
+    "../chainbridge-contracts/out/ERC20Handler.sol/ERC20Handler.json"
+);
 sol!(
     #[allow(missing_docs)]
     #[sol(rpc)]
@@ -116,79 +225,305 @@ sol!(
     "artifacts/HEI.json"
 );
 
-pub async fn handle(command: &EthereumCommand) {
+#[derive(Debug, thiserror::Error)]
+pub enum CliError {
+    #[error("Invalid {0}: {1}")]
+    InvalidArgument(&'static str, String),
+    #[error("Rpc error: {0}")]
+    Rpc(String),
+    #[error("Transaction {0} reverted")]
+    Reverted(String),
+    #[error("Deposit event not found in transaction {0}")]
+    MissingDepositEvent(String),
+    #[error("Relayer threshold {0} exceeds {1} registered relayers, proposals would never be executed")]
+    UnreachableThreshold(u8, u64),
+}
+
+/// Handler and token contracts a resource id is mapped to, zero addresses if not set
+#[derive(Debug, PartialEq, Serialize)]
+pub struct ResourceMapping {
+    pub handler: String,
+    pub token: Option<String>,
+}
+
+/// Relayer quorum configuration and state of the bridge contract
+#[derive(Debug, PartialEq, Serialize)]
+pub struct BridgeInfo {
+    pub relayer_threshold: u8,
+    pub relayer_count: u64,
+    pub paused: bool,
+}
+
+#[derive(Debug, PartialEq, Serialize)]
+pub struct TxOutput {
+    pub hash: String,
+    pub block_number: Option<u64>,
+}
+
+impl From<&TransactionReceipt> for TxOutput {
+    fn from(receipt: &TransactionReceipt) -> Self {
+        Self { hash: receipt.transaction_hash.to_string(), block_number: receipt.block_number }
+    }
+}
+
+/// Result of a command, printed as text or JSON.
+#[derive(Debug, Default, Serialize)]
+pub struct CommandOutput {
+    pub transactions: Vec<TxOutput>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub deposit_nonce: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub balance: Option<String>,
+    // balance in whole tokens followed by the symbol
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub formatted_balance: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub resource_mapping: Option<ResourceMapping>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub bridge_info: Option<BridgeInfo>,
+}
+
+impl Display for CommandOutput {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        for tx in self.transactions.iter() {
+            match tx.block_number {
+                Some(block_number) => writeln!(f, "Transaction {} included in block {}", tx.hash, block_number)?,
+                None => writeln!(f, "Transaction {} submitted", tx.hash)?,
+            }
+        }
+        if let Some(deposit_nonce) = self.deposit_nonce {
+            writeln!(f, "Deposit nonce: {}", deposit_nonce)?;
+        }
+        if let Some(ref resource_mapping) = self.resource_mapping {
+            writeln!(f, "Handler: {}", resource_mapping.handler)?;
+            match resource_mapping.token {
+                Some(ref token) => writeln!(f, "Token: {}", token)?,
+                None => writeln!(f, "Token: none, resource id is not mapped to a handler")?,
+            }
+        }
+        if let Some(ref bridge_info) = self.bridge_info {
+            writeln!(f, "Relayer threshold: {}", bridge_info.relayer_threshold)?;
+            writeln!(f, "Relayers: {}", bridge_info.relayer_count)?;
+            writeln!(f, "Paused: {}", bridge_info.paused)?;
+        }
+        // printed alone unless formatted, scripts compare it directly
+        match (&self.balance, &self.formatted_balance) {
+            (Some(balance), Some(formatted_balance)) => write!(f, "{} (raw: {})", formatted_balance, balance)?,
+            (Some(balance), None) => write!(f, "{}", balance)?,
+            _ => {},
+        }
+        Ok(())
+    }
+}
+
+pub async fn handle(command: &EthereumCommand) -> Result<CommandOutput, CliError> {
     // this is the first private key printed out by anvil during startup
     match command {
         EthereumCommand::PayIn(conf) => {
             let rpc_url = format!("http://localhost:{}", conf.port);
-            let erc_20_handler_address = Address::from_slice(&decode(&conf.bridge_erc20_handler_address).unwrap());
-            let hei_address = Address::from_slice(&decode(&conf.hei_token_address).unwrap());
-
-            let user_signer =
-                alloy::signers::local::PrivateKeySigner::from_str(conf.user_private_key.as_str()).unwrap();
-            let address = user_signer.address();
-
-            // transfer some tokens to user
-            transfer_lit_to(&conf.bridge_private_key, address, &conf.amount, &conf.lit_token_address, &rpc_url).await;
-            // approve lit spending to HEI contract
-            approve_lit_to(
-                conf.user_private_key.as_str(),
-                hei_address,
-                &conf.amount,
-                &conf.lit_token_address,
-                &rpc_url,
-            )
-            .await;
-
-            // approve HEI spending to ERC-20 handler contract
-            approve_hei_to(
-                conf.user_private_key.as_str(),
-                erc_20_handler_address,
-                &conf.amount,
-                &conf.hei_token_address,
-                &rpc_url,
-            )
-            .await;
-
-            // wrap some LIT tokens to HEI tokens
-            wrap_to(conf.user_private_key.as_str(), address, &conf.amount, &conf.hei_token_address, &rpc_url).await;
-
-            // deposit on bridge instance
-            bridge_deposit(
-                conf.user_private_key.as_str(),
-                &conf.amount,
-                conf.dest_address.to_owned(),
-                &conf.bridge_address,
-                &rpc_url,
-            )
-            .await;
+            let receipts = pay_in(conf, &rpc_url).await?;
+            let deposit_receipt = receipts.last().expect("pay in always submits deposit; qed");
+            let deposit_nonce = deposit_nonce(deposit_receipt.inner.logs())
+                .ok_or_else(|| CliError::MissingDepositEvent(deposit_receipt.transaction_hash.to_string()))?;
+            Ok(CommandOutput {
+                transactions: receipts.iter().map(TxOutput::from).collect(),
+                deposit_nonce: Some(deposit_nonce),
+                ..Default::default()
+            })
         },
         EthereumCommand::AddRelayer(conf) => {
             let rpc_url = format!("http://localhost:{}", conf.port);
-            add_relayer(
+            let receipt = add_relayer(
                 &conf.bridge_private_key,
                 &conf.bridge_address,
-                Address::from_slice(&decode(&conf.relayer_address).unwrap()),
+                parse_address("relayer address", &conf.relayer_address)?,
                 &rpc_url,
             )
-            .await;
+            .await?;
+            Ok(CommandOutput { transactions: vec![TxOutput::from(&receipt)], ..Default::default() })
         },
         EthereumCommand::SetupBridge(conf) => {
             let rpc_url = format!("http://localhost:{}", conf.port);
-            setup_bridge(
+            let receipts = setup_bridge(
                 &conf.bridge_private_key,
                 &conf.bridge_address,
-                &conf.bridge_erc20_handler_address,
-                &conf.hei_token_address,
+                &conf.resources()?,
+                conf.vote_threshold,
                 &rpc_url,
             )
-            .await;
+            .await?;
+            let bridge_info =
+                query_bridge_info(parse_address("bridge address", &conf.bridge_address)?, &rpc_url).await?;
+            check_relayer_threshold(&bridge_info, conf.force)?;
+            Ok(CommandOutput {
+                transactions: receipts.iter().map(TxOutput::from).collect(),
+                bridge_info: Some(bridge_info),
+                ..Default::default()
+            })
         },
         EthereumCommand::Balance(conf) => {
             let rpc_url = format!("http://localhost:{}", conf.port);
-            let address = Address::from_str(&conf.account).unwrap();
-            query_hei_token_amount(address, &conf.token_address, &rpc_url).await;
+            let address = parse_address("account", &conf.account)?;
+            let balance = query_hei_token_amount(address, &conf.token_address, &rpc_url).await?;
+            let formatted_balance = if conf.raw {
+                None
+            } else {
+                let decimals = match conf.decimals {
+                    Some(decimals) => decimals,
+                    None => query_token_decimals(&conf.token_address, &rpc_url).await?,
+                };
+                let symbol = match conf.symbol {
+                    Some(ref symbol) => symbol.clone(),
+                    None => query_token_symbol(&conf.token_address, &rpc_url).await?,
+                };
+                Some(format!("{} {}", format_units(balance, decimals), symbol))
+            };
+            Ok(CommandOutput { balance: Some(balance.to_string()), formatted_balance, ..Default::default() })
         },
+        EthereumCommand::ResourceMapping(conf) => {
+            let rpc_url = format!("http://localhost:{}", conf.port);
+            let resource_mapping = query_resource_mapping(
+                parse_resource_id(&conf.resource_id)?,
+                parse_address("bridge address", &conf.bridge_address)?,
+                &rpc_url,
+            )
+            .await?;
+            Ok(CommandOutput { resource_mapping: Some(resource_mapping), ..Default::default() })
+        },
+        EthereumCommand::BridgeInfo(conf) => {
+            let rpc_url = format!("http://localhost:{}", conf.port);
+            let bridge_address = parse_address("bridge address", &conf.bridge_address)?;
+            let bridge_info = query_bridge_info(bridge_address, &rpc_url).await?;
+            let resource_mapping = match conf.resource_id {
+                Some(ref resource_id) => {
+                    Some(query_resource_mapping(parse_resource_id(resource_id)?, bridge_address, &rpc_url).await?)
+                },
+                None => None,
+            };
+            Ok(CommandOutput { bridge_info: Some(bridge_info), resource_mapping, ..Default::default() })
+        },
+    }
+}
+
+/// Transfers, wraps and deposits given amount on the bridge, deposit receipt is the last one.
+pub async fn pay_in(conf: &PayInCmdConf, rpc_url: &str) -> Result<Vec<TransactionReceipt>, CliError> {
+    let erc_20_handler_address = parse_address("bridge erc20 handler address", &conf.bridge_erc20_handler_address)?;
+    let hei_address = parse_address("hei token address", &conf.hei_token_address)?;
+
+    let user_signer = PrivateKeySigner::from_str(conf.user_private_key.as_str())
+        .map_err(|e| CliError::InvalidArgument("user private key", e.to_string()))?;
+    let address = user_signer.address();
+
+    let mut receipts = vec![];
+
+    // transfer some tokens to user
+    receipts.push(
+        transfer_lit_to(&conf.bridge_private_key, address, &conf.amount, &conf.lit_token_address, rpc_url).await?,
+    );
+    // approve lit spending to HEI contract
+    receipts.push(
+        approve_lit_to(conf.user_private_key.as_str(), hei_address, &conf.amount, &conf.lit_token_address, rpc_url)
+            .await?,
+    );
+
+    // approve HEI spending to ERC-20 handler contract
+    receipts.push(
+        approve_hei_to(
+            conf.user_private_key.as_str(),
+            erc_20_handler_address,
+            &conf.amount,
+            &conf.hei_token_address,
+            rpc_url,
+        )
+        .await?,
+    );
+
+    // wrap some LIT tokens to HEI tokens
+    receipts
+        .push(wrap_to(conf.user_private_key.as_str(), address, &conf.amount, &conf.hei_token_address, rpc_url).await?);
+
+    // deposit on bridge instance
+    receipts.push(
+        bridge_deposit(
+            conf.user_private_key.as_str(),
+            &conf.amount,
+            conf.dest_address.to_owned(),
+            &conf.bridge_address,
+            rpc_url,
+        )
+        .await?,
+    );
+    Ok(receipts)
+}
+
+// returns nonce of the first bridge `Deposit` event found in logs
+fn deposit_nonce(logs: &[Log]) -> Option<u64> {
+    logs.iter()
+        .find_map(|log| log.log_decode::<Bridge::Deposit>().ok())
+        .map(|event| event.inner.data.depositNonce)
+}
+
+// proposals are executed once votes reach the threshold, so it can't exceed the number of relayers
+fn check_relayer_threshold(bridge_info: &BridgeInfo, force: bool) -> Result<(), CliError> {
+    if u64::from(bridge_info.relayer_threshold) <= bridge_info.relayer_count {
+        return Ok(());
+    }
+    let error = CliError::UnreachableThreshold(bridge_info.relayer_threshold, bridge_info.relayer_count);
+    if force {
+        warn!("{}", error);
+        Ok(())
+    } else {
+        Err(error)
+    }
+}
+
+fn parse_address(name: &'static str, address: &str) -> Result<Address, CliError> {
+    Address::from_str(address).map_err(|e| CliError::InvalidArgument(name, e.to_string()))
+}
+
+fn parse_resource_id(resource_id: &str) -> Result<FixedBytes<32>, CliError> {
+    FixedBytes::from_str(resource_id).map_err(|e| CliError::InvalidArgument("resource id", e.to_string()))
+}
+
+// validates all entries up front, so a bad one doesn't leave the bridge partially set up
+fn parse_resources(entries: &[ResourceEntry]) -> Result<Vec<BridgeResource>, CliError> {
+    if entries.is_empty() {
+        return Err(CliError::InvalidArgument("resources", "no resources to set up".to_string()));
+    }
+    let mut resource_ids = HashSet::new();
+    entries
+        .iter()
+        .map(|entry| {
+            let resource_id = parse_resource_id(&entry.resource_id)?;
+            if !resource_ids.insert(resource_id) {
+                return Err(CliError::InvalidArgument("resource id", format!("{} is set up twice", resource_id)));
+            }
+            let handler = parse_address("handler address", &entry.handler_address)?;
+            let token = parse_address("token address", &entry.token_address)?;
+            if handler == Address::ZERO || token == Address::ZERO {
+                return Err(CliError::InvalidArgument(
+                    "resources",
+                    format!("resource id {} is mapped to zero address", resource_id),
+                ));
+            }
+            Ok(BridgeResource { resource_id, handler, token, burnable: entry.burnable })
+        })
+        .collect()
+}
+
+fn parse_amount(amount: &str) -> Result<U256, CliError> {
+    U256::from_str_radix(amount, 10).map_err(|e| CliError::InvalidArgument("amount", e.to_string()))
+}
+
+fn rpc_error<E: Display>(e: E) -> CliError {
+    CliError::Rpc(e.to_string())
+}
+
+fn ensure_success(receipt: TransactionReceipt) -> Result<TransactionReceipt, CliError> {
+    if receipt.status() {
+        Ok(receipt)
+    } else {
+        Err(CliError::Reverted(receipt.transaction_hash.to_string()))
     }
 }
 
@@ -198,25 +533,84 @@ async fn transfer_lit_to(
     amount: &str,
     lit_token_address: &str,
     rpc_url: &str,
-) {
+) -> Result<TransactionReceipt, CliError> {
     info!("Transferring LIT amount {} to {}", amount, address);
-    let lit_token_instance = lit_token_instance(lit_token_address, bridge_owner_private_key, rpc_url).await;
-    let transfer_builder = lit_token_instance.transfer(address, U256::from_str_radix(amount, 10).unwrap());
-    transfer_builder.send().await.unwrap().watch().await.unwrap();
+    let lit_token_instance = lit_token_instance(lit_token_address, bridge_owner_private_key, rpc_url).await?;
+    let transfer_builder = lit_token_instance.transfer(address, parse_amount(amount)?);
+    ensure_success(
+        transfer_builder
+            .send()
+            .await
+            .map_err(rpc_error)?
+            .get_receipt()
+            .await
+            .map_err(rpc_error)?,
+    )
 }
 
-async fn wrap_to(owner_private_key: &str, address: Address, amount: &str, hei_token_address: &str, rpc_url: &str) {
+async fn wrap_to(
+    owner_private_key: &str,
+    address: Address,
+    amount: &str,
+    hei_token_address: &str,
+    rpc_url: &str,
+) -> Result<TransactionReceipt, CliError> {
     info!("Wrapping LIT amount {} to {}", amount, address);
-    let hei_token_instance = hei_token_instance(hei_token_address, owner_private_key, rpc_url).await;
-    let transfer_builder = hei_token_instance.depositFor(address, U256::from_str_radix(amount, 10).unwrap());
-    transfer_builder.send().await.unwrap().watch().await.unwrap();
+    let hei_token_instance = hei_token_instance(hei_token_address, owner_private_key, rpc_url).await?;
+    let transfer_builder = hei_token_instance.depositFor(address, parse_amount(amount)?);
+    ensure_success(
+        transfer_builder
+            .send()
+            .await
+            .map_err(rpc_error)?
+            .get_receipt()
+            .await
+            .map_err(rpc_error)?,
+    )
 }
 
-async fn query_hei_token_amount(address: Address, hei_token_address: &str, rpc_url: &str) {
+pub async fn query_hei_token_amount(
+    address: Address,
+    hei_token_address: &str,
+    rpc_url: &str,
+) -> Result<U256, CliError> {
     info!("Querying hei token amount on address {}", address);
+    let balance = call_hei_token(hei_token_address, "balanceOf", &[DynSolValue::Address(address)], rpc_url).await?;
+    balance
+        .first()
+        .and_then(|value| value.as_uint())
+        .map(|(balance, _)| balance)
+        .ok_or_else(|| CliError::Rpc("Unexpected balanceOf result".to_string()))
+}
+
+async fn query_token_decimals(token_address: &str, rpc_url: &str) -> Result<u8, CliError> {
+    let decimals = call_hei_token(token_address, "decimals", &[], rpc_url).await?;
+    decimals
+        .first()
+        .and_then(|value| value.as_uint())
+        .and_then(|(decimals, _)| u8::try_from(decimals).ok())
+        .ok_or_else(|| CliError::Rpc("Unexpected decimals result".to_string()))
+}
+
+async fn query_token_symbol(token_address: &str, rpc_url: &str) -> Result<String, CliError> {
+    let symbol = call_hei_token(token_address, "symbol", &[], rpc_url).await?;
+    symbol
+        .first()
+        .and_then(|value| value.as_str())
+        .map(|symbol| symbol.to_string())
+        .ok_or_else(|| CliError::Rpc("Unexpected symbol result".to_string()))
+}
+
+// calls view function of a token using HEI contract ABI, which covers the ERC20 interface
+async fn call_hei_token(
+    token_address: &str,
+    function: &str,
+    args: &[DynSolValue],
+    rpc_url: &str,
+) -> Result<Vec<DynSolValue>, CliError> {
     let provider = ProviderBuilder::new()
         .with_recommended_fillers()
-        .on_http(rpc_url.parse().unwrap());
+        .on_http(parse_rpc_url(rpc_url)?);
 
     let artifact = include_str!("../artifacts/HEI.json");
     let json: serde_json::Value = serde_json::from_str(artifact).unwrap();
@@ -225,15 +619,71 @@ async fn query_hei_token_amount(address: Address, hei_token_address: &str, rpc_u
     let abi = serde_json::from_str(&abi_value.to_string()).unwrap();
 
     let contract_instance =
-        ContractInstance::new(Address::from_str(hei_token_address).unwrap(), provider, Interface::new(abi));
+        ContractInstance::new(parse_address("token address", token_address)?, provider, Interface::new(abi));
 
-    let balance = contract_instance
-        .function("balanceOf", &[DynSolValue::Address(address)])
-        .unwrap()
+    contract_instance
+        .function(function, args)
+        .map_err(rpc_error)?
         .call()
         .await
-        .unwrap();
-    println!("{}", balance.first().unwrap().as_uint().unwrap().0);
+        .map_err(rpc_error)
+}
+
+/// Formats token amount in whole units, keeping all `decimals` fractional digits.
+pub fn format_units(amount: U256, decimals: u8) -> String {
+    let raw = amount.to_string();
+    let decimals = usize::from(decimals);
+    if decimals == 0 {
+        return raw;
+    }
+    let padded = format!("{:0>width$}", raw, width = decimals + 1);
+    let (whole, fraction) = padded.split_at(padded.len() - decimals);
+    format!("{}.{}", whole, fraction)
+}
+
+// reads mapping set by `adminSetResource`, token is looked up only if the resource id has a handler
+async fn query_resource_mapping(
+    resource_id: FixedBytes<32>,
+    bridge_address: Address,
+    rpc_url: &str,
+) -> Result<ResourceMapping, CliError> {
+    info!("Querying mapping of resource id {}", resource_id);
+    let provider = ProviderBuilder::new()
+        .with_recommended_fillers()
+        .on_http(parse_rpc_url(rpc_url)?);
+
+    let handler = Bridge::new(bridge_address, &provider)
+        ._resourceIDToHandlerAddress(resource_id)
+        .call()
+        .await
+        .map_err(rpc_error)?
+        ._0;
+    if handler == Address::ZERO {
+        return Ok(ResourceMapping { handler: handler.to_string(), token: None });
+    }
+
+    let token = ERC20Handler::new(handler, &provider)
+        ._resourceIDToTokenContractAddress(resource_id)
+        .call()
+        .await
+        .map_err(rpc_error)?
+        ._0;
+    Ok(ResourceMapping { handler: handler.to_string(), token: Some(token.to_string()) })
+}
+
+// reads relayer threshold, count and paused state, e.g. to verify quorum configured by `setup-bridge` and `add-relayer`
+async fn query_bridge_info(bridge_address: Address, rpc_url: &str) -> Result<BridgeInfo, CliError> {
+    info!("Querying bridge info of {}", bridge_address);
+    let provider = ProviderBuilder::new()
+        .with_recommended_fillers()
+        .on_http(parse_rpc_url(rpc_url)?);
+    let bridge = Bridge::new(bridge_address, &provider);
+
+    // calls fail to decode if there is no bridge contract deployed at the address
+    let relayer_threshold = bridge._relayerThreshold().call().await.map_err(rpc_error)?._0;
+    let relayer_count = bridge._totalRelayers().call().await.map_err(rpc_error)?._0;
+    let paused = bridge.paused().call().await.map_err(rpc_error)?._0;
+    Ok(BridgeInfo { relayer_threshold, relayer_count: u64::try_from(relayer_count).map_err(rpc_error)?, paused })
 }
 
 async fn approve_lit_to(
@@ -242,11 +692,19 @@ async fn approve_lit_to(
     amount: &str,
     lit_token_address: &str,
     rpc_url: &str,
-) {
+) -> Result<TransactionReceipt, CliError> {
     info!("Approving LIT amount {} to {}", amount, spender);
-    let lit_token_instance = lit_token_instance(lit_token_address, owner_private_key, rpc_url).await;
-    let approve_builder = lit_token_instance.approve(spender, U256::from_str_radix(amount, 10).unwrap());
-    approve_builder.send().await.unwrap().watch().await.unwrap();
+    let lit_token_instance = lit_token_instance(lit_token_address, owner_private_key, rpc_url).await?;
+    let approve_builder = lit_token_instance.approve(spender, parse_amount(amount)?);
+    ensure_success(
+        approve_builder
+            .send()
+            .await
+            .map_err(rpc_error)?
+            .get_receipt()
+            .await
+            .map_err(rpc_error)?,
+    )
 }
 
 async fn approve_hei_to(
@@ -255,159 +713,435 @@ async fn approve_hei_to(
     amount: &str,
     hei_token_address: &str,
     rpc_url: &str,
-) {
+) -> Result<TransactionReceipt, CliError> {
     info!("Approving HEI amount {} to {}", amount, spender);
-    let hei_token_instance = hei_token_instance(hei_token_address, owner_private_key, rpc_url).await;
-    let approve_builder = hei_token_instance.approve(spender, U256::from_str_radix(amount, 10).unwrap());
-    approve_builder.send().await.unwrap().watch().await.unwrap();
+    let hei_token_instance = hei_token_instance(hei_token_address, owner_private_key, rpc_url).await?;
+    let approve_builder = hei_token_instance.approve(spender, parse_amount(amount)?);
+    ensure_success(
+        approve_builder
+            .send()
+            .await
+            .map_err(rpc_error)?
+            .get_receipt()
+            .await
+            .map_err(rpc_error)?,
+    )
 }
-async fn add_relayer(by_private_key: &str, bridge_address: &str, relayer: Address, rpc_url: &str) {
+
+async fn add_relayer(
+    by_private_key: &str,
+    bridge_address: &str,
+    relayer: Address,
+    rpc_url: &str,
+) -> Result<TransactionReceipt, CliError> {
     info!("Adding relayer {}", relayer);
 
-    let bridge_instance = bridge_instance(bridge_address, by_private_key, rpc_url).await;
+    let bridge_instance = bridge_instance(bridge_address, by_private_key, rpc_url).await?;
     let builder = bridge_instance.adminAddRelayer(relayer);
-    builder.send().await.unwrap().watch().await.unwrap();
+    ensure_success(
+        builder
+            .send()
+            .await
+            .map_err(rpc_error)?
+            .get_receipt()
+            .await
+            .map_err(rpc_error)?,
+    )
 }
 
 async fn setup_bridge(
     by_private_key: &str,
     bridge_address: &str,
-    bridge_erc20_handler_address: &str,
-    hei_token_address: &str,
+    resources: &[BridgeResource],
+    vote_threshold: Option<u8>,
     rpc_url: &str,
-) {
+) -> Result<Vec<TransactionReceipt>, CliError> {
     info!("Setting up bridge");
-    let bridge_instance = bridge_instance(bridge_address, by_private_key, rpc_url).await;
-    let resource_id = FixedBytes([
-        158, 230, 223, 182, 26, 47, 185, 3, 223, 72, 124, 64, 22, 99, 130, 86, 67, 187, 130, 93, 65, 105, 94, 99, 223,
-        138, 246, 22, 42, 177, 69, 166,
-    ]);
-
-    let builder = bridge_instance.adminSetResource(
-        Address::from_hex(bridge_erc20_handler_address).unwrap(),
-        resource_id,
-        Address::from_hex(hei_token_address).unwrap(),
-    );
-    builder.send().await.unwrap().watch().await.unwrap();
-    let builder_2 = bridge_instance.adminSetBurnable(
-        Address::from_hex(bridge_erc20_handler_address).unwrap(),
-        Address::from_hex(hei_token_address).unwrap(),
-    );
-    builder_2.send().await.unwrap().watch().await.unwrap();
+    let bridge_instance = bridge_instance(bridge_address, by_private_key, rpc_url).await?;
 
-    info!("Adding MINTER role to ERC20Handler on HEI contract instance");
-    let hei_instance = hei_token_instance(hei_token_address, by_private_key, rpc_url).await;
-    hei_instance
-        .grantMinter(Address::from_hex(bridge_erc20_handler_address).unwrap())
-        .send()
-        .await
-        .unwrap()
-        .watch()
-        .await
-        .unwrap();
+    let mut receipts = vec![];
+    for resource in resources {
+        info!(
+            "Mapping resource id {} to handler {} and token {}",
+            resource.resource_id, resource.handler, resource.token
+        );
+        let builder = bridge_instance.adminSetResource(resource.handler, resource.resource_id, resource.token);
+        receipts.push(ensure_success(
+            builder
+                .send()
+                .await
+                .map_err(rpc_error)?
+                .get_receipt()
+                .await
+                .map_err(rpc_error)?,
+        )?);
+        if !resource.burnable {
+            continue;
+        }
+        let builder_2 = bridge_instance.adminSetBurnable(resource.handler, resource.token);
+        receipts.push(ensure_success(
+            builder_2
+                .send()
+                .await
+                .map_err(rpc_error)?
+                .get_receipt()
+                .await
+                .map_err(rpc_error)?,
+        )?);
+
+        info!("Adding MINTER role to handler {} on token {}", resource.handler, resource.token);
+        // token contracts are expected to expose HEI's `grantMinter`
+        let token_instance = hei_token_instance(&resource.token.to_string(), by_private_key, rpc_url).await?;
+        let builder_3 = token_instance.grantMinter(resource.handler);
+        receipts.push(ensure_success(
+            builder_3
+                .send()
+                .await
+                .map_err(rpc_error)?
+                .get_receipt()
+                .await
+                .map_err(rpc_error)?,
+        )?);
+    }
+
+    if let Some(vote_threshold) = vote_threshold {
+        info!("Changing relayer threshold to {}", vote_threshold);
+        let builder_4 = bridge_instance.adminChangeRelayerThreshold(U256::from(vote_threshold));
+        receipts.push(ensure_success(
+            builder_4
+                .send()
+                .await
+                .map_err(rpc_error)?
+                .get_receipt()
+                .await
+                .map_err(rpc_error)?,
+        )?);
+    }
+    Ok(receipts)
 }
 
-async fn bridge_deposit(by_private_key: &str, amount: &str, account: String, bridge_address: &str, rpc_url: &str) {
+async fn bridge_deposit(
+    by_private_key: &str,
+    amount: &str,
+    account: String,
+    bridge_address: &str,
+    rpc_url: &str,
+) -> Result<TransactionReceipt, CliError> {
     info!("Bridging deposit");
-    let bridge_instance = bridge_instance(bridge_address, by_private_key, rpc_url).await;
-    let resource_id = FixedBytes([
-        158, 230, 223, 182, 26, 47, 185, 3, 223, 72, 124, 64, 22, 99, 130, 86, 67, 187, 130, 93, 65, 105, 94, 99, 223,
-        138, 246, 22, 42, 177, 69, 166,
-    ]);
-    // 0x + amount + address len + address (all 32 bytes padded)
-    let amount = DynSolValue::Uint(U256::from_str_radix(amount, 10).unwrap(), 32).abi_encode();
-    let account_id = AccountId32::from_str(account.as_str()).unwrap();
-    let address_len = DynSolValue::Uint(U256::from(account_id.0.len()), 32).abi_encode();
-    let address = DynSolValue::FixedBytes(B256::new(account_id.0), 32).abi_encode();
-
-    let mut bytes = vec![];
-
-    bytes.extend(amount);
-    bytes.extend(address_len);
-    bytes.extend(address);
-
-    let call_data = Bytes::copy_from_slice(&bytes);
+    let bridge_instance = bridge_instance(bridge_address, by_private_key, rpc_url).await?;
+    let resource_id = FixedBytes(HEI_RESOURCE_ID);
+    let amount =
+        u128::try_from(parse_amount(amount)?).map_err(|e| CliError::InvalidArgument("amount", e.to_string()))?;
+    let account_id = AccountId32::from_str(account.as_str())
+        .map_err(|e| CliError::InvalidArgument("destination address", format!("{:?}", e)))?;
+    let call_data = Bytes::from(
+        encode_deposit_data(amount, &account_id.0)
+            .map_err(|_| CliError::InvalidArgument("destination address", account.clone()))?,
+    );
     let builder = bridge_instance.deposit(2, resource_id, call_data);
-    builder.send().await.unwrap().watch().await.unwrap();
+    ensure_success(
+        builder
+            .send()
+            .await
+            .map_err(rpc_error)?
+            .get_receipt()
+            .await
+            .map_err(rpc_error)?,
+    )
 }
 
 async fn bridge_instance(
     address: &str,
     private_key: &str,
     rpc_url: &str,
-) -> crate::Bridge::BridgeInstance<
-    Http<Client>,
-    FillProvider<
-        JoinFill<
-            JoinFill<JoinFill<JoinFill<Identity, GasFiller>, NonceFiller>, ChainIdFiller>,
-            WalletFiller<EthereumWallet>,
-        >,
-        RootProvider<Http<Client>>,
+) -> Result<
+    crate::Bridge::BridgeInstance<
         Http<Client>,
+        FillProvider<
+            JoinFill<
+                JoinFill<JoinFill<JoinFill<Identity, GasFiller>, NonceFiller>, ChainIdFiller>,
+                WalletFiller<EthereumWallet>,
+            >,
+            RootProvider<Http<Client>>,
+            Http<Client>,
+            Ethereum,
+        >,
         Ethereum,
     >,
-    Ethereum,
+    CliError,
 > {
-    let signer = PrivateKeySigner::from_slice(&decode(private_key).unwrap()).unwrap();
-    let wallet = EthereumWallet::from(signer);
+    let wallet = EthereumWallet::from(parse_signer(private_key)?);
     let provider = ProviderBuilder::new()
         .with_recommended_fillers()
         .wallet(wallet)
-        .on_http(rpc_url.parse().unwrap());
+        .on_http(parse_rpc_url(rpc_url)?);
 
-    Bridge::new(Address::from_slice(&decode(address).unwrap()), provider)
+    Ok(Bridge::new(parse_address("contract address", address)?, provider))
 }
 
 async fn lit_token_instance(
     address: &str,
     private_key: &str,
     rpc_url: &str,
-) -> LITTokenInstance<
-    Http<Client>,
-    FillProvider<
-        JoinFill<
-            JoinFill<JoinFill<JoinFill<Identity, GasFiller>, NonceFiller>, ChainIdFiller>,
-            WalletFiller<EthereumWallet>,
-        >,
-        RootProvider<Http<Client>>,
+) -> Result<
+    LITTokenInstance<
         Http<Client>,
+        FillProvider<
+            JoinFill<
+                JoinFill<JoinFill<JoinFill<Identity, GasFiller>, NonceFiller>, ChainIdFiller>,
+                WalletFiller<EthereumWallet>,
+            >,
+            RootProvider<Http<Client>>,
+            Http<Client>,
+            Ethereum,
+        >,
         Ethereum,
     >,
-    Ethereum,
+    CliError,
 > {
-    let signer = PrivateKeySigner::from_slice(&decode(private_key).unwrap()).unwrap();
-    let wallet = EthereumWallet::from(signer);
+    let wallet = EthereumWallet::from(parse_signer(private_key)?);
     let provider = ProviderBuilder::new()
         .with_recommended_fillers()
         .wallet(wallet)
-        .on_http(rpc_url.parse().unwrap());
+        .on_http(parse_rpc_url(rpc_url)?);
 
-    LITToken::new(Address::from_slice(&decode(address).unwrap()), provider)
+    Ok(LITToken::new(parse_address("contract address", address)?, provider))
 }
 
 async fn hei_token_instance(
     address: &str,
     private_key: &str,
     rpc_url: &str,
-) -> HEITokenInstance<
-    Http<Client>,
-    FillProvider<
-        JoinFill<
-            JoinFill<JoinFill<JoinFill<Identity, GasFiller>, NonceFiller>, ChainIdFiller>,
-            WalletFiller<EthereumWallet>,
-        >,
-        RootProvider<Http<Client>>,
+) -> Result<
+    HEITokenInstance<
         Http<Client>,
+        FillProvider<
+            JoinFill<
+                JoinFill<JoinFill<JoinFill<Identity, GasFiller>, NonceFiller>, ChainIdFiller>,
+                WalletFiller<EthereumWallet>,
+            >,
+            RootProvider<Http<Client>>,
+            Http<Client>,
+            Ethereum,
+        >,
         Ethereum,
     >,
-    Ethereum,
+    CliError,
 > {
-    let signer = PrivateKeySigner::from_slice(&decode(private_key).unwrap()).unwrap();
-    let wallet = EthereumWallet::from(signer);
+    let wallet = EthereumWallet::from(parse_signer(private_key)?);
     let provider = ProviderBuilder::new()
         .with_recommended_fillers()
         .wallet(wallet)
-        .on_http(rpc_url.parse().unwrap());
+        .on_http(parse_rpc_url(rpc_url)?);
+
+    Ok(HEITokenInstance::new(parse_address("contract address", address)?, provider))
+}
+
+fn parse_signer(private_key: &str) -> Result<PrivateKeySigner, CliError> {
+    let key = decode(private_key).map_err(|e| CliError::InvalidArgument("private key", e.to_string()))?;
+    PrivateKeySigner::from_slice(&key).map_err(|e| CliError::InvalidArgument("private key", e.to_string()))
+}
+
+fn parse_rpc_url(rpc_url: &str) -> Result<Url, CliError> {
+    Url::parse(rpc_url).map_err(|e| CliError::InvalidArgument("rpc url", e.to_string()))
+}
+
+#[cfg(all(test, feature = "anvil-tests"))]
+mod anvil_tests;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy::primitives::LogData;
+    use alloy::sol_types::SolEvent;
+
+    fn log(data: LogData) -> Log {
+        Log { inner: alloy::primitives::Log { address: Address::ZERO, data }, ..Default::default() }
+    }
+
+    #[test]
+    fn deposit_nonce_is_parsed_from_receipt_logs() {
+        let deposit = Bridge::Deposit {
+            destinationDomainID: 2,
+            resourceID: FixedBytes([1; 32]),
+            depositNonce: 7,
+            user: Address::ZERO,
+            data: Bytes::from(vec![1, 2, 3]),
+            handlerResponse: Bytes::new(),
+        };
+        let other = Bridge::RelayerAdded { relayer: Address::ZERO };
+        let logs = vec![log(other.encode_log_data()), log(deposit.encode_log_data())];
+
+        assert_eq!(deposit_nonce(&logs), Some(7));
+    }
+
+    #[test]
+    fn deposit_nonce_is_none_without_deposit_event() {
+        let other = Bridge::RelayerAdded { relayer: Address::ZERO };
+
+        assert_eq!(deposit_nonce(&[log(other.encode_log_data())]), None);
+    }
+
+    #[test]
+    fn default_resource_id_is_hei_resource_id() {
+        assert_eq!(
+            parse_resource_id("0x9ee6dfb61a2fb903df487c401663825643bb825d41695e63df8af6162ab145a6").unwrap(),
+            FixedBytes(HEI_RESOURCE_ID)
+        );
+        assert!(parse_resource_id("0x9ee6").is_err());
+    }
+
+    fn resource_entry(resource_id: &str, handler_address: &str, token_address: &str) -> ResourceEntry {
+        ResourceEntry {
+            resource_id: resource_id.to_string(),
+            handler_address: handler_address.to_string(),
+            token_address: token_address.to_string(),
+            burnable: false,
+        }
+    }
+
+    const HANDLER: &str = "0xe7f1725E7734CE288F8367e1Bb143E90bb3F0512";
+    const TOKEN: &str = "0x5FC8d32690cc91D4c39d9d3abcBD16989F875707";
+
+    #[test]
+    fn resources_are_read_from_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("resources.json");
+        std::fs::write(
+            &path,
+            format!(
+                r#"[
+                    {{"resource_id": "0x{}", "handler_address": "{}", "token_address": "{}", "burnable": true}},
+                    {{"resource_id": "0x{}", "handler_address": "{}", "token_address": "{}"}}
+                ]"#,
+                hex_id(1),
+                HANDLER,
+                TOKEN,
+                hex_id(2),
+                HANDLER,
+                "0xDc64a140Aa3E981100a9becA4E685f962f0cF6C9"
+            ),
+        )
+        .unwrap();
+        let conf = setup_bridge_conf(&["--resources-file", path.to_str().unwrap()]);
+
+        let resources = conf.resources().unwrap();
+
+        assert_eq!(resources.len(), 2);
+        assert_eq!(resources[0].resource_id, FixedBytes([1; 32]));
+        assert!(resources[0].burnable);
+        assert_eq!(resources[1].token, Address::from_str("0xDc64a140Aa3E981100a9becA4E685f962f0cF6C9").unwrap());
+        assert!(!resources[1].burnable);
+    }
+
+    #[test]
+    fn hei_resource_is_set_up_without_resources_file() {
+        let resources = setup_bridge_conf(&[]).resources().unwrap();
+
+        assert_eq!(
+            resources,
+            vec![BridgeResource {
+                resource_id: FixedBytes(HEI_RESOURCE_ID),
+                handler: Address::from_str(HANDLER).unwrap(),
+                token: Address::from_str(TOKEN).unwrap(),
+                burnable: true,
+            }]
+        );
+    }
+
+    #[test]
+    fn invalid_resources_are_rejected() {
+        let id = format!("0x{}", hex_id(1));
+        let cases = [
+            vec![],
+            vec![resource_entry("0x01", HANDLER, TOKEN)],
+            vec![resource_entry(&id, "not an address", TOKEN)],
+            vec![resource_entry(&id, HANDLER, "0x5FC8")],
+            vec![resource_entry(&id, HANDLER, &Address::ZERO.to_string())],
+            vec![resource_entry(&id, HANDLER, TOKEN), resource_entry(&id, HANDLER, TOKEN)],
+        ];
+        for entries in cases {
+            assert!(matches!(parse_resources(&entries), Err(CliError::InvalidArgument(..))), "{:?}", entries);
+        }
+
+        let path = tempfile::tempdir().unwrap().path().join("missing.json");
+        assert!(setup_bridge_conf(&["--resources-file", path.to_str().unwrap()])
+            .resources()
+            .is_err());
+    }
+
+    fn hex_id(byte: u8) -> String {
+        alloy::hex::encode([byte; 32])
+    }
+
+    fn setup_bridge_conf(args: &[&str]) -> SetupBridgeCmdConf {
+        let matches = SetupBridgeCmdConf::augment_args(clap::Command::new("setup-bridge"))
+            .get_matches_from(std::iter::once("setup-bridge").chain(args.iter().copied()));
+        SetupBridgeCmdConf::from_arg_matches(&matches).unwrap()
+    }
+
+    #[test]
+    fn amount_is_formatted_in_whole_units() {
+        assert_eq!(format_units(U256::from(100_000_000_000_000_000_000u128), 18), "100.000000000000000000");
+        assert_eq!(format_units(U256::from(1_500u64), 18), "0.000000000000001500");
+        assert_eq!(format_units(U256::ZERO, 6), "0.000000");
+        assert_eq!(format_units(U256::from(42u64), 0), "42");
+    }
 
-    HEITokenInstance::new(Address::from_slice(&decode(address).unwrap()), provider)
+    #[test]
+    fn formatted_balance_is_printed_with_raw_value() {
+        let output = CommandOutput {
+            balance: Some("100000000000000000000".to_string()),
+            formatted_balance: Some("100.000000000000000000 HEI".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(output.to_string(), "100.000000000000000000 HEI (raw: 100000000000000000000)");
+
+        let raw = CommandOutput { balance: Some("100000000000000000000".to_string()), ..Default::default() };
+        assert_eq!(raw.to_string(), "100000000000000000000");
+    }
+
+    #[test]
+    fn default_pay_in_conf_uses_arg_defaults() {
+        let conf = PayInCmdConf::default();
+        assert_eq!(conf.bridge_address, "0x5FbDB2315678afecb367f032d93F642f64180aa3");
+        assert_eq!(conf.port, 8545);
+    }
+
+    fn bridge_info(relayer_threshold: u8, relayer_count: u64) -> BridgeInfo {
+        BridgeInfo { relayer_threshold, relayer_count, paused: false }
+    }
+
+    #[test]
+    fn relayer_threshold_up_to_relayer_count_is_accepted() {
+        assert!(check_relayer_threshold(&bridge_info(0, 0), false).is_ok());
+        assert!(check_relayer_threshold(&bridge_info(1, 3), false).is_ok());
+        assert!(check_relayer_threshold(&bridge_info(3, 3), false).is_ok());
+    }
+
+    #[test]
+    fn relayer_threshold_exceeding_relayer_count_is_rejected_unless_forced() {
+        assert!(matches!(
+            check_relayer_threshold(&bridge_info(2, 1), false),
+            Err(CliError::UnreachableThreshold(2, 1))
+        ));
+        assert!(matches!(
+            check_relayer_threshold(&bridge_info(1, 0), false),
+            Err(CliError::UnreachableThreshold(1, 0))
+        ));
+        assert!(check_relayer_threshold(&bridge_info(2, 1), true).is_ok());
+    }
+
+    #[test]
+    fn unmapped_resource_is_reported() {
+        let output = CommandOutput {
+            resource_mapping: Some(ResourceMapping { handler: Address::ZERO.to_string(), token: None }),
+            ..Default::default()
+        };
+
+        assert_eq!(
+            output.to_string(),
+            "Handler: 0x0000000000000000000000000000000000000000\nToken: none, resource id is not mapped to a handler\n"
+        );
+    }
 }
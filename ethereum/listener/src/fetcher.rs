@@ -18,18 +18,32 @@
 // 0xb77cbea4b8f4d176b6999d0c22a9ce8e1303483d
 
 use crate::listener::{DestinationId, PayInEventId};
-use crate::rpc_client::EthereumRpcClient;
-use alloy::primitives::{keccak256, Address, B256, U256};
+use crate::primitives::{Log, LogId};
+use crate::rpc_client::{EthereumRpcClient, FinalizedBlockError, SKIPPED_LOGS_COUNTER};
+use alloy::primitives::{keccak256, Address, B256};
 use alloy::sol;
-use alloy::sol_types::{SolEvent, SolValue};
+use alloy::sol_types::SolEvent;
 use async_trait::async_trait;
-use bridge_core::fetcher::{BlockPayInEventsFetcher, LastFinalizedBlockNumFetcher};
+use bridge_core::fetcher::{BlockPayInEventsFetcher, LastFinalizedBlockNumFetcher, ReorgDetector};
 use bridge_core::listener::PayIn;
-use parity_scale_codec::Encode;
-use std::collections::HashSet;
+use bridge_core::primitives::decode_deposit_data;
+use bridge_core::relay::Recipient;
+use metrics::counter;
+use parity_scale_codec::{Decode, Encode};
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::fs;
+use std::io::ErrorKind;
 
 pub static EVENT_TOPIC: &str = "Deposit(uint8,bytes32,uint64,address,bytes,bytes)";
 
+pub static PROPOSAL_EVENT_TOPIC: &str = "ProposalEvent(uint8,uint64,uint8,bytes32)";
+
+// `ProposalStatus.Executed` of the bridge contract
+const PROPOSAL_STATUS_EXECUTED: u8 = 3;
+
+/// Number of recently synced block hashes kept for reorg detection
+pub const REORG_TRACKING_DEPTH: usize = 64;
+
 sol!(
     #[allow(missing_docs)]
     #[sol(rpc)]
@@ -37,6 +51,23 @@ sol!(
     "../chainbridge-contracts/out/Bridge.sol/Bridge.json"
 );
 
+sol!(
+    // `Deposit` of proxied bridge deployments whose emitter indexes the destination domain id. Topic stays
+    // the same as indexing doesn't change event signature, only data layout differs.
+    #[allow(missing_docs)]
+    #[derive(Debug)]
+    interface ProxiedBridge {
+        event Deposit(
+            uint8 indexed destinationDomainID,
+            bytes32 resourceID,
+            uint64 depositNonce,
+            address indexed user,
+            bytes data,
+            bytes handlerResponse
+        );
+    }
+);
+
 sol!(
     #[allow(missing_docs)]
     #[sol(rpc)]
@@ -44,28 +75,167 @@ sol!(
     "../chainbridge-contracts/out/ERC20Handler.sol/ERC20Handler.json"
 );
 
+/// Whether contract `code` can emit the `Deposit` event. Solidity pushes event topics as 32 byte constants,
+/// so emitting contract's bytecode contains it, unless it's a proxy delegating to its implementation.
+pub fn emits_deposit_event(code: &[u8]) -> bool {
+    let topic = keccak256(EVENT_TOPIC.as_bytes());
+    code.windows(topic.len()).any(|window| window == topic.as_slice())
+}
+
 /// Used for fetching data from ethereum based chains required by the `Listener`
 pub struct Fetcher<RpcClient> {
     finalization_gap_blocks: u64,
+    use_finalized_tag: bool,
     client: RpcClient,
     event_sources: HashSet<Address>,
+    // deposits of these sources are routed to given destination instead of the decoded one
+    source_destinations: HashMap<Address, DestinationId>,
     event_topic: B256,
+    strict_decode: bool,
+    synced_block_hashes: BTreeMap<u64, B256>,
+    // synced block hashes are persisted, so reorg of the last synced block is detected after restart
+    block_hashes_file: Option<String>,
 }
 
 impl<C> Fetcher<C> {
     pub fn new(finalization_gap_blocks: u64, client: C, event_sources: HashSet<Address>) -> Self {
-        Self { finalization_gap_blocks, client, event_sources, event_topic: keccak256(EVENT_TOPIC.as_bytes()) }
+        Self {
+            finalization_gap_blocks,
+            use_finalized_tag: false,
+            client,
+            event_sources,
+            source_destinations: HashMap::new(),
+            event_topic: keccak256(EVENT_TOPIC.as_bytes()),
+            strict_decode: true,
+            synced_block_hashes: BTreeMap::new(),
+            block_hashes_file: None,
+        }
+    }
+
+    /// Persists synced block hashes to `file_name`, hashes stored by previous run are loaded
+    #[allow(clippy::result_unit_err)]
+    pub fn with_block_hashes_file(mut self, file_name: &str) -> Result<Self, ()> {
+        self.synced_block_hashes = match fs::read(file_name) {
+            Ok(content) => Vec::<(u64, [u8; 32])>::decode(&mut content.as_slice())
+                .map_err(|e| log::error!("Could not decode synced block hashes: {:?}", e))?
+                .into_iter()
+                .map(|(block_num, hash)| (block_num, B256::from(hash)))
+                .collect(),
+            Err(e) if e.kind() == ErrorKind::NotFound => BTreeMap::new(),
+            Err(e) => {
+                log::error!("Could not read synced block hashes from {}: {:?}", file_name, e);
+                return Err(());
+            },
+        };
+        self.block_hashes_file = Some(file_name.to_string());
+        Ok(self)
+    }
+
+    fn persist_block_hashes(&self) {
+        if let Some(ref file_name) = self.block_hashes_file {
+            let hashes: Vec<(u64, [u8; 32])> = self
+                .synced_block_hashes
+                .iter()
+                .map(|(block_num, hash)| (*block_num, hash.0))
+                .collect();
+            if let Err(e) = fs::write(file_name, hashes.encode()) {
+                log::error!("Could not persist synced block hashes to {}: {:?}", file_name, e);
+            }
+        }
+    }
+
+    /// Follows node's `finalized` block tag, finalization gap is used only if the node doesn't support it
+    pub fn with_finalized_tag(mut self) -> Self {
+        self.use_finalized_tag = true;
+        self
+    }
+
+    /// With non strict decoding, deposits not matching bridge contract's layout are decoded with the
+    /// layout of proxied deployments as well
+    pub fn with_strict_decode(mut self, strict_decode: bool) -> Self {
+        self.strict_decode = strict_decode;
+        self
+    }
+
+    /// Watches given sources as well, routing their deposits to the mapped destination
+    pub fn with_source_destinations(mut self, source_destinations: HashMap<Address, DestinationId>) -> Self {
+        self.event_sources.extend(source_destinations.keys());
+        self.source_destinations = source_destinations;
+        self
     }
 }
 
 #[async_trait]
 impl<C: EthereumRpcClient + Sync + Send> LastFinalizedBlockNumFetcher for Fetcher<C> {
     async fn get_last_finalized_block_num(&mut self) -> Result<Option<u64>, ()> {
+        if self.use_finalized_tag {
+            match self.client.get_finalized_block_number().await {
+                Ok(finalized_block_number) => return Ok(finalized_block_number),
+                Err(FinalizedBlockError::Unsupported) => {
+                    log::warn!(
+                        "Node does not support finalized block tag, falling back to finalization gap of {} blocks",
+                        self.finalization_gap_blocks
+                    );
+                    self.use_finalized_tag = false;
+                },
+                Err(FinalizedBlockError::Rpc) => return Err(()),
+            }
+        }
         let last_block_number = self.client.get_block_number().await?;
         Ok(last_block_number.checked_sub(self.finalization_gap_blocks))
     }
 }
 
+/// Compares the parent hash of the block about to be synced with the hash recorded for the previous block.
+/// On mismatch it walks back through recorded hashes to find the last block still on the canonical chain.
+#[async_trait]
+impl<C: EthereumRpcClient + Sync + Send> ReorgDetector for Fetcher<C> {
+    async fn detect_reorg(&mut self, block_num: u64) -> Result<Option<u64>, ()> {
+        let header = self.client.get_block_header(block_num).await?.ok_or(())?;
+
+        let maybe_recorded_parent_hash = block_num
+            .checked_sub(1)
+            .and_then(|parent_num| self.synced_block_hashes.get(&parent_num));
+
+        if let Some(recorded_parent_hash) = maybe_recorded_parent_hash {
+            if *recorded_parent_hash != header.parent_hash {
+                log::warn!(
+                    "Parent hash mismatch at block {}, expected: {:?}, got: {:?}",
+                    block_num,
+                    recorded_parent_hash,
+                    header.parent_hash
+                );
+                let recorded: Vec<(u64, B256)> = self
+                    .synced_block_hashes
+                    .range(..block_num)
+                    .rev()
+                    .map(|(num, hash)| (*num, *hash))
+                    .collect();
+                // if none of the recorded blocks is canonical, resume from the oldest one we know about
+                let mut resume_block = recorded.last().map(|(num, _)| *num).unwrap_or(block_num);
+                for (num, hash) in recorded {
+                    if let Some(canonical) = self.client.get_block_header(num).await? {
+                        if canonical.hash == hash {
+                            resume_block = num + 1;
+                            break;
+                        }
+                    }
+                }
+                self.synced_block_hashes.split_off(&resume_block);
+                self.persist_block_hashes();
+                return Ok(Some(resume_block));
+            }
+        }
+
+        self.synced_block_hashes.insert(block_num, header.hash);
+        while self.synced_block_hashes.len() > REORG_TRACKING_DEPTH {
+            self.synced_block_hashes.pop_first();
+        }
+        self.persist_block_hashes();
+        Ok(None)
+    }
+}
+
 #[async_trait]
 impl<C: EthereumRpcClient + Sync + Send> BlockPayInEventsFetcher<PayInEventId, DestinationId> for Fetcher<C> {
     async fn get_block_pay_in_events(&mut self, block_num: u64) -> Result<Vec<PayIn<PayInEventId, DestinationId>>, ()> {
@@ -82,57 +252,154 @@ impl<C: EthereumRpcClient + Sync + Send> BlockPayInEventsFetcher<PayInEventId, D
 
         let deposit_events: Vec<_> = block_logs
             .into_iter()
-            .filter(|log| self.event_sources.contains(&log.address) && log.topics.contains(&self.event_topic))
-            .map(|log| {
-                let event = ChainBridge::Deposit::abi_decode_data(&log.data, false).unwrap();
-                log::debug!("Got contract events: {:?}", event);
-                let destination_id = event.0;
-                let resource_id = event.1;
-                let nonce = event.2;
-                let data = event.3;
-
-                let amount_bytes = &data[0..32];
-                let amount: U256 = U256::abi_decode(amount_bytes, false).unwrap();
-
-                PayIn::new(
-                    log.id,
-                    Some(hex::encode(destination_id.encode())),
-                    amount.try_into().unwrap(),
-                    nonce,
-                    resource_id.0,
-                    data.into(),
-                )
+            // other events may carry the topic in their indexed fields, only the signature topic identifies the event
+            .filter(|log| self.event_sources.contains(&log.address) && log.topics.first() == Some(&self.event_topic))
+            .filter_map(|log| {
+                let deposit =
+                    decode_deposit(&log).or_else(
+                        || {
+                            if self.strict_decode {
+                                None
+                            } else {
+                                decode_proxied_deposit(&log)
+                            }
+                        },
+                    );
+                if deposit.is_none() {
+                    log::warn!(
+                        "Skipping undecodable deposit log {} of tx {}, topics: {:?}, data: 0x{}",
+                        log.id,
+                        log.tx_hash
+                            .map(|hash| format!("0x{}", hex::encode(hash)))
+                            .unwrap_or_else(|| "unknown".to_string()),
+                        log.topics,
+                        hex::encode(&log.data)
+                    );
+                    counter!(SKIPPED_LOGS_COUNTER, "reason" => "undecodable").increment(1);
+                }
+                match self.source_destinations.get(&log.address) {
+                    Some(destination_id) => deposit.map(|deposit| {
+                        deposit
+                            .with_destination_id(destination_id.clone())
+                            .with_source(log.address.to_vec())
+                    }),
+                    None => deposit,
+                }
             })
             .collect();
 
         log::info!("Found {:?} Deposits on Ethereum", deposit_events.len());
-        Ok(deposit_events)
+        if deposit_events.is_empty() {
+            return Ok(deposit_events);
+        }
+        // timestamp is only used to measure event age, deposits are not held back if it's unavailable
+        let timestamp = self.client.get_block_timestamp(block_num).await.unwrap_or_else(|_| {
+            log::warn!("Could not get timestamp of block {}", block_num);
+            None
+        });
+        Ok(deposit_events
+            .into_iter()
+            .map(|event| event.with_timestamp(timestamp))
+            .collect())
+    }
+}
+
+// bridge contract's `Deposit` has only `user` indexed, extra topics mean the data layout differs as well
+const DEPOSIT_TOPICS_MAX_LEN: usize = 2;
+
+fn decode_deposit(log: &Log) -> Option<PayIn<PayInEventId, DestinationId>> {
+    if log.topics.len() > DEPOSIT_TOPICS_MAX_LEN {
+        return None;
     }
+    let event = ChainBridge::Deposit::abi_decode_data(&log.data, false).ok()?;
+    log::debug!("Got contract events: {:?}", event);
+    pay_in(log, event.0, event.1, event.2, &event.3)
+}
+
+fn decode_proxied_deposit(log: &Log) -> Option<PayIn<PayInEventId, DestinationId>> {
+    let event = ProxiedBridge::Deposit::decode_raw_log(log.topics.iter().copied(), &log.data, false).ok()?;
+    log::debug!("Got proxied contract events: {:?}", event);
+    pay_in(log, event.destinationDomainID, event.resourceID, event.depositNonce, &event.data)
+}
+
+fn pay_in(
+    log: &Log,
+    destination_id: u8,
+    resource_id: B256,
+    nonce: u64,
+    data: &[u8],
+) -> Option<PayIn<PayInEventId, DestinationId>> {
+    let deposit_data = decode_deposit_data(data).ok()?;
+
+    Some(
+        PayIn::new(
+            log.id.clone(),
+            Some(hex::encode(destination_id.encode())),
+            deposit_data.amount,
+            nonce,
+            resource_id.0,
+            Recipient::from(deposit_data.recipient),
+        )
+        .with_source_ref(log.tx_hash.map(|hash| hash.to_vec()).unwrap_or_default()),
+    )
+}
+
+/// Deposit executed on the destination bridge contract
+#[derive(Clone, Debug, PartialEq)]
+pub struct ExecutedProposal {
+    pub id: LogId,
+    pub origin_domain_id: u8,
+    pub deposit_nonce: u64,
+}
+
+/// Picks `ProposalEvent`s with executed status from logs of the bridge contract.
+pub fn executed_proposals(logs: Vec<Log>) -> Vec<ExecutedProposal> {
+    let topic = keccak256(PROPOSAL_EVENT_TOPIC.as_bytes());
+    logs.into_iter()
+        .filter(|log| log.topics.contains(&topic))
+        .filter_map(|log| match ChainBridge::ProposalEvent::abi_decode_data(&log.data, false) {
+            Ok((origin_domain_id, deposit_nonce, status, _)) if status == PROPOSAL_STATUS_EXECUTED => {
+                Some(ExecutedProposal { id: log.id, origin_domain_id, deposit_nonce })
+            },
+            Ok(_) => None,
+            Err(e) => {
+                log::error!("Could not decode proposal event {:?}: {:?}", log.id, e);
+                None
+            },
+        })
+        .collect()
 }
 
 #[cfg(test)]
 mod test {
-    use super::{Fetcher, EVENT_TOPIC};
+    use super::{
+        emits_deposit_event, executed_proposals, ChainBridge, ERC20Handler, ExecutedProposal, Fetcher, EVENT_TOPIC,
+        PROPOSAL_EVENT_TOPIC,
+    };
 
     use crate::listener::{EthereumPayInEvent, PayInEventId};
-    use crate::primitives::Log;
     use crate::primitives::LogId;
-    use crate::rpc_client::MockEthereumRpcClient;
+    use crate::primitives::{BlockHeader, Log};
+    use crate::rpc_client::{FinalizedBlockError, MockEthereumRpcClient};
     use alloy::dyn_abi::DynSolValue;
-    use alloy::primitives::{keccak256, Address, Bytes, U160, U256};
+    use alloy::primitives::{keccak256, Address, Bytes, B256, U160, U256};
     use alloy::sol_types::SolValue;
-    use bridge_core::fetcher::{BlockPayInEventsFetcher, LastFinalizedBlockNumFetcher};
+    use bridge_core::fetcher::{BlockPayInEventsFetcher, LastFinalizedBlockNumFetcher, ReorgDetector};
     use bridge_core::listener::PayIn;
+    use bridge_core::primitives::encode_deposit_data;
+    use bridge_core::relay::Recipient;
     use mockall::predicate::{always, eq};
     use std::collections::{HashMap, HashSet};
 
+    const BLOCK_TIMESTAMP: u64 = 1700000000;
+
     #[tokio::test]
     async fn it_should_return_contract_logs() {
         // given
         let source = Address::from(U160::from(150));
         let mut pay_in_events: HashMap<u64, Vec<EthereumPayInEvent>> = HashMap::new();
 
-        let event_data = U256::from(10).abi_encode();
+        let event_data = encode_deposit_data(10, &[7; 32]).unwrap();
 
         let block_1_logs: Vec<Log> = vec![Log {
             id: LogId::new(1, 1, 1),
@@ -148,11 +415,20 @@ mod test {
                 ])
                 .abi_encode_params(),
             ),
+            tx_hash: Some([5; 32]),
         }];
         let block_2_logs: Vec<Log> = vec![];
 
-        let block_1_pay_in_events: Vec<EthereumPayInEvent> =
-            vec![PayIn::new(PayInEventId::new(1, 1, 1), Some("00".to_string()), 10, 1, [0; 32], event_data)];
+        let block_1_pay_in_events: Vec<EthereumPayInEvent> = vec![PayIn::new(
+            PayInEventId::new(1, 1, 1),
+            Some("00".to_string()),
+            10,
+            1,
+            [0; 32],
+            Recipient::Substrate([7; 32]),
+        )
+        .with_source_ref(vec![5; 32])
+        .with_timestamp(Some(BLOCK_TIMESTAMP))];
         let block_2_pay_in_events: Vec<EthereumPayInEvent> = vec![];
 
         pay_in_events.insert(1, block_1_pay_in_events.clone());
@@ -172,6 +448,13 @@ mod test {
             .times(1)
             .returning(move |_, _, _| Box::pin(futures::future::ok(block_2_logs.clone())));
 
+        // fetched only for blocks with deposits
+        rpc_client
+            .expect_get_block_timestamp()
+            .with(eq(1))
+            .times(1)
+            .returning(|_| Box::pin(futures::future::ok(Some(BLOCK_TIMESTAMP))));
+
         let mut fetcher = Fetcher::new(0, rpc_client, HashSet::from_iter(vec![source]));
 
         // when and then -.-
@@ -179,6 +462,211 @@ mod test {
         assert_eq!(block_2_pay_in_events, fetcher.get_block_pay_in_events(2).await.unwrap());
     }
 
+    #[tokio::test]
+    async fn it_should_route_deposits_by_event_source() {
+        let bridge = Address::from(U160::from(150));
+        let routed_source = Address::from(U160::from(151));
+        let unwatched_source = Address::from(U160::from(152));
+        let deposit_log = |log_idx: u64, address: Address| Log {
+            id: LogId::new(1, 0, log_idx),
+            address,
+            topics: vec![keccak256(EVENT_TOPIC.as_bytes())],
+            data: Bytes::from(
+                DynSolValue::Tuple(vec![
+                    DynSolValue::Uint(U256::from(0), 8),
+                    DynSolValue::Uint(U256::from(0), 256),
+                    DynSolValue::Uint(U256::from(log_idx), 64),
+                    DynSolValue::Bytes(encode_deposit_data(10, &[1; 20]).unwrap()),
+                    DynSolValue::Uint(U256::from(10), 256),
+                ])
+                .abi_encode_params(),
+            ),
+            tx_hash: None,
+        };
+        let logs = vec![deposit_log(0, bridge), deposit_log(1, routed_source), deposit_log(2, unwatched_source)];
+
+        let mut rpc_client = MockEthereumRpcClient::new();
+        rpc_client
+            .expect_get_block_logs()
+            .withf(move |_, sources, _| {
+                sources.len() == 2 && sources.contains(&bridge) && sources.contains(&routed_source)
+            })
+            .returning(move |_, _, _| Box::pin(futures::future::ok(logs.clone())));
+        rpc_client
+            .expect_get_block_timestamp()
+            .returning(|_| Box::pin(futures::future::ok(None)));
+        let mut fetcher = Fetcher::new(0, rpc_client, HashSet::from_iter(vec![bridge]))
+            .with_source_destinations(HashMap::from([(routed_source, "02".to_string())]));
+
+        let events = fetcher.get_block_pay_in_events(1).await.unwrap();
+
+        assert_eq!(
+            events,
+            vec![
+                PayIn::new(PayInEventId::new(1, 0, 0), Some("00".to_string()), 10, 0, [0; 32], Recipient::Evm([1; 20])),
+                PayIn::new(PayInEventId::new(1, 0, 1), Some("02".to_string()), 10, 1, [0; 32], Recipient::Evm([1; 20]))
+                    .with_source(routed_source.to_vec()),
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn it_should_skip_undecodable_deposits() {
+        let source = Address::from(U160::from(150));
+        let deposit_log = |log_idx: u64, data: Bytes| Log {
+            id: LogId::new(1, 0, log_idx),
+            address: source,
+            topics: vec![keccak256(EVENT_TOPIC.as_bytes())],
+            data,
+            tx_hash: None,
+        };
+        let deposit_data = |deposit_data: Vec<u8>| {
+            Bytes::from(
+                DynSolValue::Tuple(vec![
+                    DynSolValue::Uint(U256::from(0), 8),
+                    DynSolValue::Uint(U256::from(0), 256),
+                    DynSolValue::Uint(U256::from(1), 64),
+                    DynSolValue::Bytes(deposit_data),
+                    DynSolValue::Uint(U256::from(10), 256),
+                ])
+                .abi_encode_params(),
+            )
+        };
+        let logs = vec![
+            deposit_log(0, Bytes::from(vec![0xde, 0xad])),
+            // deposit data too short to hold the amount
+            deposit_log(1, deposit_data(vec![1, 2, 3])),
+            // amount exceeding u128
+            deposit_log(2, deposit_data((U256::MAX, U256::from(20)).abi_encode())),
+            // recipient length without recipient
+            deposit_log(3, deposit_data((U256::from(10), U256::from(20)).abi_encode())),
+            deposit_log(4, deposit_data(encode_deposit_data(10, &[1; 20]).unwrap())),
+        ];
+
+        let mut rpc_client = MockEthereumRpcClient::new();
+        rpc_client
+            .expect_get_block_logs()
+            .returning(move |_, _, _| Box::pin(futures::future::ok(logs.clone())));
+        // missing timestamp doesn't hold deposits back
+        rpc_client
+            .expect_get_block_timestamp()
+            .returning(|_| Box::pin(futures::future::err(())));
+        let mut fetcher = Fetcher::new(0, rpc_client, HashSet::from_iter(vec![source]));
+
+        let events = fetcher.get_block_pay_in_events(1).await.unwrap();
+
+        assert_eq!(
+            events,
+            vec![PayIn::new(
+                PayInEventId::new(1, 0, 4),
+                Some("00".to_string()),
+                10,
+                1,
+                [0; 32],
+                Recipient::Evm([1; 20])
+            )]
+        );
+    }
+
+    fn proxied_deposit_log(source: Address) -> Log {
+        let user = Address::from(U160::from(7));
+        Log {
+            id: LogId::new(1, 0, 0),
+            address: source,
+            topics: vec![keccak256(EVENT_TOPIC.as_bytes()), B256::with_last_byte(2), user.into_word()],
+            data: Bytes::from(
+                DynSolValue::Tuple(vec![
+                    DynSolValue::FixedBytes(B256::repeat_byte(1), 32),
+                    DynSolValue::Uint(U256::from(5), 64),
+                    DynSolValue::Bytes(encode_deposit_data(10, &[1; 20]).unwrap()),
+                    DynSolValue::Bytes(vec![]),
+                ])
+                .abi_encode_params(),
+            ),
+            tx_hash: Some([3; 32]),
+        }
+    }
+
+    #[tokio::test]
+    async fn it_should_decode_proxied_deposits_only_if_not_strict() {
+        let source = Address::from(U160::from(150));
+        let logs = vec![proxied_deposit_log(source)];
+        let mut rpc_client = MockEthereumRpcClient::new();
+        rpc_client
+            .expect_get_block_logs()
+            .returning(move |_, _, _| Box::pin(futures::future::ok(logs.clone())));
+        rpc_client
+            .expect_get_block_timestamp()
+            .returning(|_| Box::pin(futures::future::ok(Some(BLOCK_TIMESTAMP))));
+        let mut fetcher = Fetcher::new(0, rpc_client, HashSet::from_iter(vec![source]));
+
+        assert_eq!(fetcher.get_block_pay_in_events(1).await.unwrap(), vec![]);
+
+        let mut fetcher = fetcher.with_strict_decode(false);
+        assert_eq!(
+            fetcher.get_block_pay_in_events(1).await.unwrap(),
+            vec![PayIn::new(
+                PayInEventId::new(1, 0, 0),
+                Some("02".to_string()),
+                10,
+                5,
+                [1; 32],
+                Recipient::Evm([1; 20])
+            )
+            .with_source_ref(vec![3; 32])
+            .with_timestamp(Some(BLOCK_TIMESTAMP))]
+        );
+    }
+
+    #[tokio::test]
+    async fn it_should_match_deposit_topic_as_signature_only() {
+        let source = Address::from(U160::from(150));
+        let mut log = proxied_deposit_log(source);
+        // other event carrying deposit topic in its indexed field
+        log.topics = vec![keccak256(PROPOSAL_EVENT_TOPIC.as_bytes()), keccak256(EVENT_TOPIC.as_bytes())];
+        let logs = vec![log];
+        let mut rpc_client = MockEthereumRpcClient::new();
+        rpc_client
+            .expect_get_block_logs()
+            .returning(move |_, _, _| Box::pin(futures::future::ok(logs.clone())));
+        let mut fetcher = Fetcher::new(0, rpc_client, HashSet::from_iter(vec![source])).with_strict_decode(false);
+
+        assert_eq!(fetcher.get_block_pay_in_events(1).await.unwrap(), vec![]);
+    }
+
+    #[test]
+    fn it_should_find_deposit_event_in_bridge_bytecode() {
+        assert!(emits_deposit_event(&ChainBridge::DEPLOYED_BYTECODE));
+        assert!(!emits_deposit_event(&ERC20Handler::DEPLOYED_BYTECODE));
+        assert!(!emits_deposit_event(&[0x60, 0x80]));
+    }
+
+    #[test]
+    fn it_should_pick_executed_proposals() {
+        let proposal_log = |log_idx: u64, nonce: u64, status: u8| Log {
+            id: LogId::new(5, 0, log_idx),
+            address: Address::ZERO,
+            topics: vec![keccak256(PROPOSAL_EVENT_TOPIC.as_bytes())],
+            data: Bytes::from(
+                DynSolValue::Tuple(vec![
+                    DynSolValue::Uint(U256::from(2), 8),
+                    DynSolValue::Uint(U256::from(nonce), 64),
+                    DynSolValue::Uint(U256::from(status), 8),
+                    DynSolValue::FixedBytes(B256::ZERO, 32),
+                ])
+                .abi_encode_params(),
+            ),
+            tx_hash: None,
+        };
+        // passed and executed proposals of the same deposit
+        let logs = vec![proposal_log(0, 7, 2), proposal_log(1, 7, 3)];
+
+        assert_eq!(
+            executed_proposals(logs),
+            vec![ExecutedProposal { id: LogId::new(5, 0, 1), origin_domain_id: 2, deposit_nonce: 7 }]
+        );
+    }
+
     #[tokio::test]
     async fn it_should_take_gap_when_calculating_finalized_block() {
         let mut rpc_client = MockEthereumRpcClient::new();
@@ -189,4 +677,121 @@ mod test {
 
         assert_eq!(fetcher.get_last_finalized_block_num().await, Ok(Some(4)));
     }
+
+    #[tokio::test]
+    async fn it_should_follow_finalized_tag() {
+        let mut rpc_client = MockEthereumRpcClient::new();
+        rpc_client
+            .expect_get_finalized_block_number()
+            .times(2)
+            .returning(|| Box::pin(futures::future::ok(Some(3))));
+        rpc_client.expect_get_block_number().times(0);
+        let mut fetcher = Fetcher::new(6, rpc_client, HashSet::from_iter(vec![])).with_finalized_tag();
+
+        assert_eq!(fetcher.get_last_finalized_block_num().await, Ok(Some(3)));
+        assert_eq!(fetcher.get_last_finalized_block_num().await, Ok(Some(3)));
+    }
+
+    #[tokio::test]
+    async fn it_should_fall_back_to_gap_when_finalized_tag_is_unsupported() {
+        let mut rpc_client = MockEthereumRpcClient::new();
+        rpc_client
+            .expect_get_finalized_block_number()
+            .times(1)
+            .returning(|| Box::pin(futures::future::err(FinalizedBlockError::Unsupported)));
+        rpc_client
+            .expect_get_block_number()
+            .times(2)
+            .returning(|| Box::pin(futures::future::ok(10)));
+        let mut fetcher = Fetcher::new(6, rpc_client, HashSet::from_iter(vec![])).with_finalized_tag();
+
+        assert_eq!(fetcher.get_last_finalized_block_num().await, Ok(Some(4)));
+        // tag is not queried again
+        assert_eq!(fetcher.get_last_finalized_block_num().await, Ok(Some(4)));
+    }
+
+    #[tokio::test]
+    async fn it_should_not_fall_back_to_gap_on_rpc_error() {
+        let mut rpc_client = MockEthereumRpcClient::new();
+        rpc_client
+            .expect_get_finalized_block_number()
+            .times(1)
+            .returning(|| Box::pin(futures::future::err(FinalizedBlockError::Rpc)));
+        rpc_client.expect_get_block_number().times(0);
+        let mut fetcher = Fetcher::new(6, rpc_client, HashSet::from_iter(vec![])).with_finalized_tag();
+
+        assert_eq!(fetcher.get_last_finalized_block_num().await, Err(()));
+    }
+
+    #[tokio::test]
+    async fn it_should_detect_reorg_and_rewind_to_last_canonical_block() {
+        let mut rpc_client = MockEthereumRpcClient::new();
+        rpc_client.expect_get_block_header().with(eq(9)).times(2).returning(|_| {
+            Box::pin(futures::future::ok(Some(BlockHeader { hash: B256::repeat_byte(9), parent_hash: B256::ZERO })))
+        });
+        rpc_client.expect_get_block_header().with(eq(10)).times(1).returning(|_| {
+            Box::pin(futures::future::ok(Some(BlockHeader {
+                hash: B256::repeat_byte(10),
+                parent_hash: B256::repeat_byte(9),
+            })))
+        });
+        // block 10 got replaced by a different one
+        rpc_client.expect_get_block_header().with(eq(11)).times(1).returning(|_| {
+            Box::pin(futures::future::ok(Some(BlockHeader {
+                hash: B256::repeat_byte(11),
+                parent_hash: B256::repeat_byte(100),
+            })))
+        });
+        rpc_client.expect_get_block_header().with(eq(10)).times(1).returning(|_| {
+            Box::pin(futures::future::ok(Some(BlockHeader {
+                hash: B256::repeat_byte(100),
+                parent_hash: B256::repeat_byte(9),
+            })))
+        });
+
+        let mut fetcher = Fetcher::new(0, rpc_client, HashSet::from_iter(vec![]));
+
+        assert_eq!(fetcher.detect_reorg(9).await, Ok(None));
+        assert_eq!(fetcher.detect_reorg(10).await, Ok(None));
+        assert_eq!(fetcher.detect_reorg(11).await, Ok(Some(10)));
+    }
+
+    #[tokio::test]
+    async fn it_should_detect_reorg_of_last_synced_block_after_restart() {
+        let file_name = "reorg_detected_after_restart.bin";
+        let _ = std::fs::remove_file(file_name);
+
+        let mut rpc_client = MockEthereumRpcClient::new();
+        rpc_client.expect_get_block_header().with(eq(10)).times(1).returning(|_| {
+            Box::pin(futures::future::ok(Some(BlockHeader {
+                hash: B256::repeat_byte(10),
+                parent_hash: B256::repeat_byte(9),
+            })))
+        });
+        let mut fetcher = Fetcher::new(0, rpc_client, HashSet::from_iter(vec![]))
+            .with_block_hashes_file(file_name)
+            .unwrap();
+        assert_eq!(fetcher.detect_reorg(10).await, Ok(None));
+
+        // block 10 got replaced while the listener was stopped
+        let mut rpc_client = MockEthereumRpcClient::new();
+        rpc_client.expect_get_block_header().with(eq(11)).times(1).returning(|_| {
+            Box::pin(futures::future::ok(Some(BlockHeader {
+                hash: B256::repeat_byte(11),
+                parent_hash: B256::repeat_byte(100),
+            })))
+        });
+        rpc_client.expect_get_block_header().with(eq(10)).times(1).returning(|_| {
+            Box::pin(futures::future::ok(Some(BlockHeader {
+                hash: B256::repeat_byte(100),
+                parent_hash: B256::repeat_byte(9),
+            })))
+        });
+        let mut restarted = Fetcher::new(0, rpc_client, HashSet::from_iter(vec![]))
+            .with_block_hashes_file(file_name)
+            .unwrap();
+
+        assert_eq!(restarted.detect_reorg(11).await, Ok(Some(10)));
+        std::fs::remove_file(file_name).unwrap();
+    }
 }
@@ -14,50 +14,72 @@
 // You should have received a copy of the GNU General Public License
 // along with Litentry.  If not, see <https://www.gnu.org/licenses/>.
 
-use crate::fetcher::Fetcher;
-use crate::listener::ListenerConfig;
+use crate::fetcher::{emits_deposit_event, Fetcher, EVENT_TOPIC};
+use crate::listener::{DestinationId, Finalization, ListenerConfig};
 use alloy::primitives::Address;
 use bridge_core::listener::RELAY_MAX_ATTEMPTS;
 use bridge_core::relay;
+use bridge_core::relay_stats::RelayStats;
+use bridge_core::start_block::StartBlockSettings;
 use bridge_core::sync_checkpoint_repository::FileCheckpointRepository;
 use bridge_core::{listener::Listener, relay::Relayer};
 use listener::EthereumListener;
-use log::error;
-use rpc_client::EthersRpcClient;
+use log::{error, warn};
+use rpc_client::{EthereumRpcClient, EthersRpcClient};
 use std::collections::{HashMap, HashSet};
 use std::str::FromStr;
 use std::sync::Arc;
 use tokio::{runtime::Handle, sync::oneshot::Receiver};
 
-mod fetcher;
+pub mod fetcher;
 pub mod listener;
-mod primitives;
-mod rpc_client;
+pub mod primitives;
+pub mod rpc_client;
 
-/// Creates ethereum based chain listener. `finalization_gap_blocks` represents the amount of blocks
-/// a listener will wait before it treat block as finalized. For example if `finalization_gap_blocks`
-/// is set to 6 then listener will process block after receiving block 7, `7-1 = 6`
-#[allow(clippy::result_unit_err)]
-pub fn create_listener(
+/// Creates ethereum based chain listener, blocks are treated as finalized according to `config.finalization()`,
+/// see `create_fetcher`.
+#[allow(clippy::result_unit_err, clippy::type_complexity, clippy::too_many_arguments)]
+pub async fn create_listener(
     id: &str,
     handle: Handle,
     config: &ListenerConfig,
-    start_block: u64,
+    start_block: &StartBlockSettings,
     chain_id: u32,
-    relayers: HashMap<String, Arc<Box<dyn Relayer<String>>>>,
+    relayers: HashMap<String, Vec<Arc<Box<dyn Relayer<String>>>>>,
+    relayer_destinations: &HashMap<String, String>,
     stop_signal: Receiver<()>,
 ) -> Result<EthereumListener<EthersRpcClient, FileCheckpointRepository>, ()> {
-    let client = EthersRpcClient::new(&config.node_rpc_url).map_err(|e| {
+    let client = EthersRpcClient::new(&config.node_rpc_url, config.rpc_auth.as_ref()).map_err(|e| {
         error!("Could not connect to rpc: {:?}", e);
     })?;
 
+    let bridge_contract_address = Address::from_str(&config.bridge_contract_address).map_err(|e| {
+        error!("Could not parse bridge contract address: {:?}", e);
+    })?;
+
+    let assigned_destinations: HashSet<String> = relayers.keys().cloned().collect();
+    let source_destinations =
+        resolve_source_destinations(&config.event_sources, relayer_destinations, &assigned_destinations)
+            .map_err(|_| error!("Invalid event sources of {} listener", id))?;
+
+    if !config.skip_startup_checks {
+        // listener's `chain_id` is bridge domain id, not comparable to the node's chain id
+        if config.evm_chain_id.is_none() {
+            warn!("Node chain id of {} listener is not checked, set evm_chain_id to check it", id);
+        }
+        for contract_address in std::iter::once(&bridge_contract_address).chain(source_destinations.keys()) {
+            ensure_node_matches_config(&client, config.evm_chain_id, *contract_address)
+                .await
+                .map_err(|_| error!("Startup checks of {} listener failed", id))?;
+        }
+    }
+
     let last_processed_log_repository = FileCheckpointRepository::new(&format!("data/{}_last_log.bin", id));
 
-    let fetcher: Fetcher<EthersRpcClient> = Fetcher::new(
-        config.finalization_gap,
-        client,
-        HashSet::from([Address::from_str(&config.bridge_contract_address).unwrap()]),
-    );
+    let mut fetcher = create_fetcher(config, client, bridge_contract_address, source_destinations)
+        .with_block_hashes_file(&format!("data/{}_block_hashes.bin", id))?;
+
+    let start_block = start_block.resolve(id, &mut fetcher).await?;
 
     let ethereum_listener: EthereumListener<EthersRpcClient, FileCheckpointRepository> = Listener::new(
         id,
@@ -72,5 +94,238 @@ pub fn create_listener(
     )
     .map_err(|e| error!("Error creating {} listener: {:?}", id, e))?;
 
-    Ok(ethereum_listener)
+    let relay_stats = RelayStats::new(id, &format!("data/{}_relay_stats.bin", id))?;
+
+    Ok(ethereum_listener.with_relay_stats(Arc::new(relay_stats)))
+}
+
+/// Creates fetcher following finalization of `config`. Finalization gap represents the amount of blocks
+/// a listener will wait before it treat block as finalized. For example if the gap is set to 6 then listener
+/// will process block after receiving block 7, `7-1 = 6`. With `tag` finalization the node's `finalized`
+/// block is followed instead and `finalization_gap` is used only if the node doesn't support it.
+pub fn create_fetcher<C: EthereumRpcClient>(
+    config: &ListenerConfig,
+    client: C,
+    bridge_contract_address: Address,
+    source_destinations: HashMap<Address, DestinationId>,
+) -> Fetcher<C> {
+    match config.finalization() {
+        Finalization::Tag => {
+            Fetcher::new(config.finalization_gap, client, HashSet::from([bridge_contract_address])).with_finalized_tag()
+        },
+        Finalization::Gap(gap) => Fetcher::new(gap, client, HashSet::from([bridge_contract_address])),
+    }
+    .with_strict_decode(config.strict_decode)
+    .with_source_destinations(source_destinations)
+}
+
+/// Maps configured event sources to destination ids of the relayers their deposits are routed to.
+/// Fails if a source address is invalid or its relayer is not assigned to the listener. Sources have independent
+/// nonce sequences, which would collide in relay requests of the same source chain, so each source needs a
+/// destination of its own.
+#[allow(clippy::result_unit_err)]
+pub fn resolve_source_destinations(
+    event_sources: &HashMap<String, String>,
+    relayer_destinations: &HashMap<String, String>,
+    assigned_destinations: &HashSet<String>,
+) -> Result<HashMap<Address, DestinationId>, ()> {
+    let source_destinations: HashMap<Address, DestinationId> = event_sources
+        .iter()
+        .map(|(source, relayer_id)| {
+            let address = Address::from_str(source).map_err(|e| {
+                error!("Could not parse event source address {}: {:?}", source, e);
+            })?;
+            let destination_id = relayer_destinations
+                .get(relayer_id)
+                .filter(|destination_id| assigned_destinations.contains(*destination_id))
+                .ok_or_else(|| {
+                    error!("Relayer {} of event source {} is not assigned to the listener", relayer_id, source);
+                })?;
+            Ok((address, destination_id.clone()))
+        })
+        .collect::<Result<_, ()>>()?;
+    let mut destinations = HashSet::new();
+    if !source_destinations
+        .values()
+        .all(|destination_id| destinations.insert(destination_id))
+    {
+        error!("Event sources are routed to the same destination, their deposit nonces would collide");
+        return Err(());
+    }
+    Ok(source_destinations)
+}
+
+/// Ensures the node behind `client` serves the expected chain, if given, and has the bridge contract deployed.
+/// Protects against misconfigured rpc urls, which would otherwise be synced without finding any events.
+/// Contracts which don't seem to emit the `Deposit` event are only warned about.
+#[allow(clippy::result_unit_err)]
+pub async fn ensure_node_matches_config<C: EthereumRpcClient>(
+    client: &C,
+    expected_chain_id: Option<u64>,
+    bridge_contract_address: Address,
+) -> Result<(), ()> {
+    if let Some(expected_chain_id) = expected_chain_id {
+        let chain_id = client.get_chain_id().await?;
+        if chain_id != expected_chain_id {
+            error!("Node chain id mismatch, expected: {}, got: {}", expected_chain_id, chain_id);
+            return Err(());
+        }
+    }
+
+    let code = client.get_code(bridge_contract_address).await?;
+    if code.is_empty() {
+        error!("No contract code found at bridge contract address {:?}", bridge_contract_address);
+        return Err(());
+    }
+    // not fatal, proxy contracts emit the event from their implementation's code
+    if !emits_deposit_event(&code) {
+        warn!(
+            "Contract at bridge contract address {:?} does not seem to emit {} event, no pay ins will be found \
+             unless it's a proxy of the bridge contract",
+            bridge_contract_address, EVENT_TOPIC
+        );
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{create_fetcher, ensure_node_matches_config, resolve_source_destinations};
+    use crate::listener::ListenerConfig;
+    use crate::rpc_client::MockEthereumRpcClient;
+    use alloy::primitives::{Address, Bytes, U160};
+    use bridge_core::fetcher::LastFinalizedBlockNumFetcher;
+    use std::collections::{HashMap, HashSet};
+
+    fn destinations(destination_ids: &[&str]) -> HashSet<String> {
+        destination_ids
+            .iter()
+            .map(|destination_id| destination_id.to_string())
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn fetcher_uses_configured_finalization_gap() {
+        let config: ListenerConfig = serde_json::from_str(
+            r#"{"node_rpc_url": "http://localhost:8545", "bridge_contract_address": "0x00", "finalization_gap": 6}"#,
+        )
+        .unwrap();
+        let mut client = MockEthereumRpcClient::new();
+        client
+            .expect_get_block_number()
+            .returning(|| Box::pin(futures::future::ok(100)));
+
+        let mut fetcher = create_fetcher(&config, client, Address::ZERO, HashMap::new());
+
+        assert_eq!(fetcher.get_last_finalized_block_num().await, Ok(Some(94)));
+    }
+
+    #[test]
+    fn event_sources_are_routed_to_destinations_of_their_relayers() {
+        let relayer_destinations =
+            HashMap::from([("heima".to_string(), "02".to_string()), ("paseo".to_string(), "03".to_string())]);
+        let event_sources = HashMap::from([
+            ("0x0000000000000000000000000000000000000097".to_string(), "heima".to_string()),
+            ("0x0000000000000000000000000000000000000098".to_string(), "paseo".to_string()),
+        ]);
+
+        let source_destinations =
+            resolve_source_destinations(&event_sources, &relayer_destinations, &destinations(&["02", "03"])).unwrap();
+
+        assert_eq!(
+            source_destinations,
+            HashMap::from([
+                (Address::from(U160::from(151)), "02".to_string()),
+                (Address::from(U160::from(152)), "03".to_string())
+            ])
+        );
+    }
+
+    #[test]
+    fn event_sources_with_unknown_relayer_or_invalid_address_are_rejected() {
+        let relayer_destinations = HashMap::from([("heima".to_string(), "02".to_string())]);
+        let source = |address: &str, relayer_id: &str| HashMap::from([(address.to_string(), relayer_id.to_string())]);
+
+        assert!(resolve_source_destinations(
+            &source("0x0000000000000000000000000000000000000097", "paseo"),
+            &relayer_destinations,
+            &destinations(&["02"])
+        )
+        .is_err());
+        assert!(resolve_source_destinations(
+            &source("0x0000000000000000000000000000000000000097", "heima"),
+            &relayer_destinations,
+            &destinations(&["03"])
+        )
+        .is_err());
+        assert!(resolve_source_destinations(&source("0xzz", "heima"), &relayer_destinations, &destinations(&["02"]))
+            .is_err());
+    }
+
+    #[test]
+    fn event_sources_routed_to_same_destination_are_rejected() {
+        let relayer_destinations =
+            HashMap::from([("heima".to_string(), "02".to_string()), ("paseo".to_string(), "03".to_string())]);
+        let event_sources = HashMap::from([
+            ("0x0000000000000000000000000000000000000097".to_string(), "heima".to_string()),
+            ("0x0000000000000000000000000000000000000098".to_string(), "heima".to_string()),
+        ]);
+
+        assert!(
+            resolve_source_destinations(&event_sources, &relayer_destinations, &destinations(&["02", "03"])).is_err()
+        );
+    }
+
+    #[tokio::test]
+    async fn node_matching_config_passes_checks() {
+        let mut rpc_client = MockEthereumRpcClient::new();
+        rpc_client.expect_get_chain_id().returning(|| Box::pin(futures::future::ok(1)));
+        rpc_client
+            .expect_get_code()
+            .returning(|_| Box::pin(futures::future::ok(Bytes::from(vec![0x60, 0x80]))));
+
+        assert!(ensure_node_matches_config(&rpc_client, Some(1), Address::from(U160::from(150)))
+            .await
+            .is_ok());
+    }
+
+    #[tokio::test]
+    async fn chain_id_is_not_checked_without_evm_chain_id() {
+        let mut rpc_client = MockEthereumRpcClient::new();
+        rpc_client.expect_get_chain_id().times(0);
+        rpc_client
+            .expect_get_code()
+            .returning(|_| Box::pin(futures::future::ok(Bytes::from(vec![0x60, 0x80]))));
+
+        assert!(ensure_node_matches_config(&rpc_client, None, Address::from(U160::from(150)))
+            .await
+            .is_ok());
+    }
+
+    #[tokio::test]
+    async fn chain_id_mismatch_fails_checks() {
+        let mut rpc_client = MockEthereumRpcClient::new();
+        rpc_client
+            .expect_get_chain_id()
+            .returning(|| Box::pin(futures::future::ok(11155111)));
+        rpc_client.expect_get_code().times(0);
+
+        assert!(ensure_node_matches_config(&rpc_client, Some(1), Address::from(U160::from(150)))
+            .await
+            .is_err());
+    }
+
+    #[tokio::test]
+    async fn missing_bridge_contract_fails_checks() {
+        let mut rpc_client = MockEthereumRpcClient::new();
+        rpc_client.expect_get_chain_id().returning(|| Box::pin(futures::future::ok(1)));
+        rpc_client
+            .expect_get_code()
+            .returning(|_| Box::pin(futures::future::ok(Bytes::new())));
+
+        assert!(ensure_node_matches_config(&rpc_client, Some(1), Address::from(U160::from(150)))
+            .await
+            .is_err());
+    }
 }
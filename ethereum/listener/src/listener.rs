@@ -17,18 +17,91 @@
 use crate::fetcher::Fetcher;
 use crate::primitives::{LogId, SyncCheckpoint};
 use bridge_core::listener::{Listener, PayIn};
+use bridge_core::rpc_auth::RpcAuth;
 use serde::Deserialize;
+use std::collections::HashMap;
 
 pub type PayInEventId = LogId;
 pub type DestinationId = String;
 pub type EthereumPayInEvent = PayIn<PayInEventId, DestinationId>;
 
+/// Determines when a block is treated as finalized
+#[derive(Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum Finalization {
+    // follow node's `finalized` block tag
+    Tag,
+    // wait for given amount of blocks
+    Gap(u64),
+}
+
 #[derive(Deserialize)]
 pub struct ListenerConfig {
     pub node_rpc_url: String,
     pub bridge_contract_address: String,
+    // used in `gap` finalization mode and as a fallback if node does not support the `finalized` tag
     pub finalization_gap: u64,
+    // defaults to `gap` mode with `finalization_gap`
+    #[serde(default)]
+    pub finalization: Option<Finalization>,
+    // EVM chain id expected to be reported by the node, not checked if not set
+    #[serde(default)]
+    pub evm_chain_id: Option<u64>,
+    #[serde(default)]
+    pub skip_startup_checks: bool,
+    #[serde(default)]
+    pub rpc_auth: Option<RpcAuth>,
+    // if false, deposits not decodable with bridge contract's layout are decoded with proxied deployments' layout
+    #[serde(default = "default_strict_decode")]
+    pub strict_decode: bool,
+    // additional bridge contracts watched by the listener, hex encoded address -> id of relayer their deposits are
+    // routed to regardless of the destination encoded in the deposit
+    #[serde(default)]
+    pub event_sources: HashMap<String, String>,
+}
+
+fn default_strict_decode() -> bool {
+    true
+}
+
+impl ListenerConfig {
+    pub fn finalization(&self) -> Finalization {
+        self.finalization.clone().unwrap_or(Finalization::Gap(self.finalization_gap))
+    }
 }
 
 pub type EthereumListener<RpcClient, CheckpointRepository> =
     Listener<DestinationId, Fetcher<RpcClient>, SyncCheckpoint, CheckpointRepository, PayInEventId>;
+
+#[cfg(test)]
+mod tests {
+    use super::{Finalization, ListenerConfig};
+
+    fn config(finalization: &str) -> ListenerConfig {
+        serde_json::from_str(&format!(
+            r#"{{"node_rpc_url": "http://localhost:8545", "bridge_contract_address": "0x00", "finalization_gap": 6 {} }}"#,
+            finalization
+        ))
+        .unwrap()
+    }
+
+    #[test]
+    fn finalization_modes_are_parsed() {
+        assert_eq!(config("").finalization(), Finalization::Gap(6));
+        assert_eq!(config(r#", "finalization": "tag""#).finalization(), Finalization::Tag);
+        assert_eq!(config(r#", "finalization": { "gap": 12 }"#).finalization(), Finalization::Gap(12));
+    }
+
+    #[test]
+    fn event_sources_are_parsed() {
+        assert!(config("").event_sources.is_empty());
+        let config = config(r#", "event_sources": { "0x5FbDB2315678afecb367f032d93F642f64180aa3": "heima" }"#);
+        assert_eq!(config.event_sources.get("0x5FbDB2315678afecb367f032d93F642f64180aa3"), Some(&"heima".to_string()));
+    }
+
+    #[test]
+    fn decode_is_strict_by_default() {
+        assert!(config("").strict_decode);
+        assert!(!config(r#", "strict_decode": false"#).strict_decode);
+    }
+}
@@ -17,6 +17,7 @@
 use alloy::primitives::{Address, Bytes, B256};
 use bridge_core::sync_checkpoint_repository::Checkpoint;
 use parity_scale_codec::{Decode, Encode};
+use std::fmt::{Display, Formatter};
 
 /// Represents ethereum based chain sync checkpoint.
 #[derive(Clone, Debug, PartialEq, Encode, Decode)]
@@ -92,6 +93,14 @@ pub struct Log {
     pub address: Address,
     pub topics: Vec<B256>,
     pub data: Bytes,
+    // hash of the transaction which emitted the log
+    pub tx_hash: Option<[u8; 32]>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct BlockHeader {
+    pub hash: B256,
+    pub parent_hash: B256,
 }
 
 #[derive(Clone, Debug, PartialEq)]
@@ -107,6 +116,12 @@ impl LogId {
     }
 }
 
+impl Display for LogId {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}-{}-{}", self.block_num, self.tx_idx, self.log_idx)
+    }
+}
+
 #[cfg(test)]
 mod tests {
 
@@ -15,17 +15,37 @@
 // along with Litentry.  If not, see <https://www.gnu.org/licenses/>.
 
 use alloy::network::Ethereum;
-use alloy::primitives::{Address, IntoLogData};
+use alloy::primitives::{Address, Bytes, IntoLogData};
 use async_trait::async_trait;
-use log::error;
+use log::{error, warn};
+use metrics::counter;
 
-use crate::primitives::{Log, LogId};
+use crate::primitives::{BlockHeader, Log, LogId};
 use alloy::providers::{Provider, ProviderBuilder, ReqwestProvider};
-use alloy::rpc::types::Filter;
+use alloy::rpc::client::RpcClient;
+use alloy::rpc::types::{BlockNumberOrTag, Filter};
+use alloy::transports::http::reqwest::header::{HeaderMap, HeaderValue, AUTHORIZATION};
+use alloy::transports::http::{reqwest, Http};
+use bridge_core::rpc_auth::RpcAuth;
+use bridge_core::timestamp_cache::BlockTimestampCache;
+use std::sync::Mutex;
 
 #[cfg(test)]
 use mockall::automock;
 
+pub const SKIPPED_LOGS_COUNTER: &str = "ethereum_skipped_logs";
+
+// json-rpc "method not found" and "invalid params" error codes
+const METHOD_NOT_FOUND_CODE: i64 = -32601;
+const INVALID_PARAMS_CODE: i64 = -32602;
+
+#[derive(Debug, PartialEq)]
+pub enum FinalizedBlockError {
+    // node doesn't know the `finalized` block tag (e.g. pre-merge or dev chains)
+    Unsupported,
+    Rpc,
+}
+
 sol!(
     #[allow(missing_docs)]
     #[sol(rpc)]
@@ -40,19 +60,41 @@ use alloy::sol;
 #[cfg_attr(test, automock)]
 pub trait EthereumRpcClient {
     async fn get_block_number(&self) -> Result<u64, ()>;
+    async fn get_finalized_block_number(&self) -> Result<Option<u64>, FinalizedBlockError>;
     async fn get_block_logs(&self, block_number: u64, addresses: Vec<Address>, event: &str) -> Result<Vec<Log>, ()>;
+    async fn get_block_header(&self, block_number: u64) -> Result<Option<BlockHeader>, ()>;
+    // unix timestamp in seconds
+    async fn get_block_timestamp(&self, block_number: u64) -> Result<Option<u64>, ()>;
+    async fn get_chain_id(&self) -> Result<u64, ()>;
+    async fn get_code(&self, address: Address) -> Result<Bytes, ()>;
 }
 
 pub struct EthersRpcClient {
     client: ReqwestProvider<Ethereum>,
+    timestamps: Mutex<BlockTimestampCache>,
 }
 
+#[allow(clippy::result_unit_err)]
 impl EthersRpcClient {
-    pub fn new(endpoint: &str) -> Result<Self, ()> {
-        let url = endpoint.parse().map_err(|_| ())?;
-        let provider = ProviderBuilder::new().on_http(url);
+    pub fn new(endpoint: &str, rpc_auth: Option<&RpcAuth>) -> Result<Self, ()> {
+        let url: reqwest::Url = endpoint.parse().map_err(|_| ())?;
+        let provider = match rpc_auth {
+            Some(rpc_auth) => {
+                let mut headers = HeaderMap::new();
+                let mut authorization = HeaderValue::from_str(&rpc_auth.authorization_header()?).map_err(|e| {
+                    error!("Invalid rpc authorization header: {:?}", e);
+                })?;
+                authorization.set_sensitive(true);
+                headers.insert(AUTHORIZATION, authorization);
+                let http_client = reqwest::Client::builder().default_headers(headers).build().map_err(|e| {
+                    error!("Could not build http client: {:?}", e);
+                })?;
+                ProviderBuilder::new().on_client(RpcClient::new(Http::with_client(http_client, url), false))
+            },
+            None => ProviderBuilder::new().on_http(url),
+        };
 
-        Ok(EthersRpcClient { client: provider })
+        Ok(EthersRpcClient { client: provider, timestamps: Mutex::new(BlockTimestampCache::default()) })
     }
 }
 
@@ -64,30 +106,226 @@ impl EthereumRpcClient for EthersRpcClient {
         })
     }
 
-    // TODO: Are there too many unwraps?
+    async fn get_finalized_block_number(&self) -> Result<Option<u64>, FinalizedBlockError> {
+        self.client
+            .get_block_by_number(BlockNumberOrTag::Finalized, false)
+            .await
+            .map(|maybe_block| maybe_block.map(|block| block.header.number))
+            .map_err(|e| match e.as_error_resp() {
+                Some(payload) if is_finalized_tag_unsupported(payload.code, &payload.message) => {
+                    FinalizedBlockError::Unsupported
+                },
+                _ => {
+                    error!("Could not get finalized block number: {:?}", e);
+                    FinalizedBlockError::Rpc
+                },
+            })
+    }
+
     async fn get_block_logs(&self, block_number: u64, addresses: Vec<Address>, event: &str) -> Result<Vec<Log>, ()> {
         let filter: Filter = Filter::new()
             .from_block(block_number)
             .to_block(block_number)
             .address(addresses)
             .event(event);
+        self.client.get_logs(&filter).await.map(|logs| map_logs(&logs)).map_err(|e| {
+            error!("Could not get block {} logs: {:?}", block_number, e);
+        })
+    }
+
+    async fn get_block_header(&self, block_number: u64) -> Result<Option<BlockHeader>, ()> {
         self.client
-            .get_logs(&filter)
+            .get_block_by_number(BlockNumberOrTag::Number(block_number), false)
             .await
-            .map(|logs| {
-                logs.iter()
-                    .map(|log| Log {
-                        id: LogId::new(
-                            log.block_number.unwrap(),
-                            log.transaction_index.unwrap(),
-                            log.log_index.unwrap(),
-                        ),
-                        address: log.address(),
-                        topics: log.topics().to_vec(),
-                        data: log.data().to_log_data().data,
-                    })
-                    .collect()
+            .map(|maybe_block| {
+                maybe_block.map(|block| BlockHeader { hash: block.header.hash, parent_hash: block.header.parent_hash })
             })
-            .map_err(|_| ())
+            .map_err(|e| {
+                error!("Could not get block {} header: {:?}", block_number, e);
+            })
+    }
+
+    async fn get_block_timestamp(&self, block_number: u64) -> Result<Option<u64>, ()> {
+        if let Some(timestamp) = self.timestamps.lock().unwrap().get(block_number) {
+            return Ok(Some(timestamp));
+        }
+        let timestamp = self
+            .client
+            .get_block_by_number(BlockNumberOrTag::Number(block_number), false)
+            .await
+            .map(|maybe_block| maybe_block.map(|block| block.header.timestamp))
+            .map_err(|e| {
+                error!("Could not get block {} timestamp: {:?}", block_number, e);
+            })?;
+        if let Some(timestamp) = timestamp {
+            self.timestamps.lock().unwrap().insert(block_number, timestamp);
+        }
+        Ok(timestamp)
+    }
+
+    async fn get_chain_id(&self) -> Result<u64, ()> {
+        self.client.get_chain_id().await.map_err(|e| {
+            error!("Could not get chain id: {:?}", e);
+        })
+    }
+
+    async fn get_code(&self, address: Address) -> Result<Bytes, ()> {
+        self.client.get_code_at(address).await.map_err(|e| {
+            error!("Could not get code at {:?}: {:?}", address, e);
+        })
+    }
+}
+
+/// Recognizes error responses of nodes which can't resolve the `finalized` block tag.
+pub fn is_finalized_tag_unsupported(code: i64, message: &str) -> bool {
+    let message = message.to_lowercase();
+    code == METHOD_NOT_FOUND_CODE
+        || (code == INVALID_PARAMS_CODE && message.contains("finalized"))
+        || message.contains("unknown block")
+        || message.contains("not supported")
+        || message.contains("unsupported")
+}
+
+/// Maps rpc logs skipping removed ones and those without identifiers, which some providers (e.g. non-archival
+/// replicas behind load balancers) return as null.
+pub fn map_logs(logs: &[alloy::rpc::types::Log]) -> Vec<Log> {
+    logs.iter()
+        .filter_map(|log| {
+            if log.removed {
+                warn!("Skipping removed log: {:?}", log);
+                counter!(SKIPPED_LOGS_COUNTER, "reason" => "removed").increment(1);
+                return None;
+            }
+            match (log.block_number, log.transaction_index, log.log_index) {
+                (Some(block_num), Some(tx_idx), Some(log_idx)) => Some(Log {
+                    id: LogId::new(block_num, tx_idx, log_idx),
+                    address: log.address(),
+                    topics: log.topics().to_vec(),
+                    data: log.data().to_log_data().data,
+                    tx_hash: log.transaction_hash.map(|hash| hash.0),
+                }),
+                _ => {
+                    warn!("Skipping log without identifiers: {:?}", log);
+                    counter!(SKIPPED_LOGS_COUNTER, "reason" => "missing_id").increment(1);
+                    None
+                },
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy::primitives::{LogData, B256};
+    use alloy::rpc::types::Log as RpcLog;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio::net::TcpListener;
+
+    // answers single `eth_blockNumber` request and returns received request head
+    async fn serve_block_number(listener: TcpListener) -> String {
+        let (mut stream, _) = listener.accept().await.unwrap();
+        let mut request = vec![];
+        let mut buf = [0; 1024];
+        let (head, body) = loop {
+            let read = stream.read(&mut buf).await.unwrap();
+            request.extend_from_slice(&buf[..read]);
+            let text = String::from_utf8_lossy(&request).to_string();
+            if let Some((head, body)) = text.split_once("\r\n\r\n") {
+                let content_length = head
+                    .lines()
+                    .find_map(|line| {
+                        line.to_lowercase()
+                            .strip_prefix("content-length:")
+                            .map(|v| v.trim().to_string())
+                    })
+                    .unwrap()
+                    .parse::<usize>()
+                    .unwrap();
+                if body.len() >= content_length {
+                    break (head.to_string(), body.to_string());
+                }
+            }
+        };
+        let id = serde_json::from_str::<serde_json::Value>(&body).unwrap()["id"].clone();
+        let response = serde_json::json!({"jsonrpc": "2.0", "id": id, "result": "0x10"}).to_string();
+        stream
+            .write_all(
+                format!(
+                    "HTTP/1.1 200 OK\r\ncontent-type: application/json\r\ncontent-length: {}\r\n\r\n{}",
+                    response.len(),
+                    response
+                )
+                .as_bytes(),
+            )
+            .await
+            .unwrap();
+        head
+    }
+
+    fn rpc_log(block_number: Option<u64>, transaction_index: Option<u64>, log_index: Option<u64>) -> RpcLog {
+        RpcLog {
+            inner: alloy::primitives::Log {
+                address: Address::ZERO,
+                data: LogData::new_unchecked(vec![B256::ZERO], Bytes::from(vec![1, 2, 3])),
+            },
+            block_number,
+            transaction_index,
+            log_index,
+            transaction_hash: Some(B256::repeat_byte(9)),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn logs_without_identifiers_are_skipped() {
+        let logs = vec![
+            rpc_log(None, Some(0), Some(0)),
+            rpc_log(Some(1), None, Some(0)),
+            rpc_log(Some(1), Some(0), None),
+            rpc_log(Some(1), Some(2), Some(3)),
+        ];
+
+        let mapped = map_logs(&logs);
+
+        assert_eq!(mapped.len(), 1);
+        assert_eq!(mapped[0].id, LogId::new(1, 2, 3));
+        assert_eq!(mapped[0].topics, vec![B256::ZERO]);
+        assert_eq!(mapped[0].data, Bytes::from(vec![1, 2, 3]));
+        assert_eq!(mapped[0].tx_hash, Some([9; 32]));
+    }
+
+    #[test]
+    fn removed_logs_are_skipped() {
+        let mut removed = rpc_log(Some(1), Some(0), Some(0));
+        removed.removed = true;
+
+        let mapped = map_logs(&[removed, rpc_log(Some(1), Some(0), Some(1))]);
+
+        assert_eq!(mapped.len(), 1);
+        assert_eq!(mapped[0].id, LogId::new(1, 0, 1));
+    }
+
+    #[test]
+    fn unsupported_finalized_tag_errors_are_recognized() {
+        assert!(is_finalized_tag_unsupported(-32602, "invalid block tag: finalized"));
+        assert!(is_finalized_tag_unsupported(-32000, "Unknown block"));
+        assert!(is_finalized_tag_unsupported(-32000, "finalized tag not supported"));
+        assert!(!is_finalized_tag_unsupported(-32000, "header not found"));
+        assert!(!is_finalized_tag_unsupported(429, "rate limit exceeded"));
+    }
+
+    #[tokio::test]
+    async fn should_send_authorization_header() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let endpoint = format!("http://{}", listener.local_addr().unwrap());
+        let server = tokio::spawn(serve_block_number(listener));
+
+        let rpc_auth = RpcAuth::Bearer { token: "secret".to_string() };
+        let client = EthersRpcClient::new(&endpoint, Some(&rpc_auth)).unwrap();
+
+        assert_eq!(client.get_block_number().await, Ok(16));
+        let head = server.await.unwrap().to_lowercase();
+        assert!(head.contains("authorization: bearer secret"));
     }
 }
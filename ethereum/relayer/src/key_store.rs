@@ -16,17 +16,68 @@
 
 use alloy::hex::decode;
 use alloy::signers::k256::ecdsa::SigningKey;
+use alloy::signers::local::PrivateKeySigner;
 use bridge_core::key_store::KeyStore;
+use log::error;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::Path;
 
-// TODO: Can this read key from file and ask for password?
-/// Generates and stores keys used by `EthereumRelayer`
+/// Source of the password of an encrypted JSON keystore.
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum KeystorePassword {
+    /// Name of environment variable holding the password
+    Env(String),
+    /// Path of file holding the password, trailing newline is ignored
+    File(String),
+}
+
+impl KeystorePassword {
+    #[allow(clippy::result_unit_err)]
+    pub fn read(&self) -> Result<String, ()> {
+        match self {
+            KeystorePassword::Env(name) => {
+                std::env::var(name).map_err(|e| error!("Could not read keystore password from env {}: {:?}", name, e))
+            },
+            KeystorePassword::File(path) => fs::read_to_string(path)
+                .map(|password| password.trim_end_matches(['\r', '\n']).to_string())
+                .map_err(|e| error!("Could not read keystore password from {}: {:?}", path, e)),
+        }
+    }
+}
+
+/// Generates and stores keys used by `EthereumRelayer`. Besides raw key files, reads encrypted
+/// web3 v3 JSON keystores (e.g. created by geth), detected by `.json` extension or JSON content.
 pub struct EthereumKeyStore {
     path: String,
+    password: Option<KeystorePassword>,
 }
 
 impl EthereumKeyStore {
     pub fn new(path: String) -> Self {
-        Self { path }
+        Self { path, password: None }
+    }
+
+    /// Sets password source of JSON keystore, not used for raw key files
+    pub fn with_password(mut self, password: Option<KeystorePassword>) -> Self {
+        self.password = password;
+        self
+    }
+
+    fn is_json_keystore(&self, content: &[u8]) -> bool {
+        Path::new(&self.path).extension().is_some_and(|extension| extension == "json")
+            || content.trim_ascii_start().starts_with(b"{")
+    }
+
+    fn decrypt(&self) -> Result<SigningKey, ()> {
+        let Some(ref password) = self.password else {
+            error!("Key store {} is encrypted JSON keystore, but no password is configured", self.path);
+            return Err(());
+        };
+        PrivateKeySigner::decrypt_keystore(&self.path, password.read()?)
+            .map(|signer| signer.into_credential())
+            .map_err(|e| error!("Could not decrypt key store {}: {:?}", self.path, e))
     }
 }
 
@@ -48,4 +99,92 @@ impl KeyStore<SigningKey> for EthereumKeyStore {
     fn path(&self) -> String {
         self.path.clone()
     }
+
+    fn read(&self) -> Result<SigningKey, ()> {
+        let content = fs::read(&self.path).map_err(|_| error!("Failed to read key store at: {}", self.path))?;
+        if self.is_json_keystore(&content) {
+            self.decrypt()
+        } else {
+            Self::deserialize(content)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy::primitives::address;
+
+    const KEYSTORE_FIXTURE: &str = "fixtures/keystore_v3.json";
+    const KEYSTORE_PASSWORD: &str = "testpassword";
+
+    fn signer_address(key: SigningKey) -> alloy::primitives::Address {
+        PrivateKeySigner::from(key).address()
+    }
+
+    #[test]
+    pub fn json_keystore_is_decrypted_with_password_from_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let password_path = dir.path().join("password");
+        fs::write(&password_path, format!("{}\n", KEYSTORE_PASSWORD)).unwrap();
+
+        let key_store = EthereumKeyStore::new(KEYSTORE_FIXTURE.to_string())
+            .with_password(Some(KeystorePassword::File(password_path.to_str().unwrap().to_string())));
+
+        assert_eq!(signer_address(key_store.read().unwrap()), address!("f39Fd6e51aad88F6F4ce6aB8827279cffFb92266"));
+    }
+
+    #[test]
+    pub fn json_keystore_is_detected_by_content_and_decrypted_with_password_from_env() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("relayer.key");
+        fs::copy(KEYSTORE_FIXTURE, &path).unwrap();
+        std::env::set_var("ETHEREUM_KEY_STORE_TEST_PASSWORD", KEYSTORE_PASSWORD);
+
+        let key_store = EthereumKeyStore::new(path.to_str().unwrap().to_string())
+            .with_password(Some(KeystorePassword::Env("ETHEREUM_KEY_STORE_TEST_PASSWORD".to_string())));
+
+        assert_eq!(signer_address(key_store.read().unwrap()), address!("f39Fd6e51aad88F6F4ce6aB8827279cffFb92266"));
+    }
+
+    #[test]
+    pub fn json_keystore_is_not_read_without_valid_password() {
+        let dir = tempfile::tempdir().unwrap();
+        let password_path = dir.path().join("password");
+        fs::write(&password_path, "wrong").unwrap();
+
+        assert!(EthereumKeyStore::new(KEYSTORE_FIXTURE.to_string()).read().is_err());
+        assert!(EthereumKeyStore::new(KEYSTORE_FIXTURE.to_string())
+            .with_password(Some(KeystorePassword::File(password_path.to_str().unwrap().to_string())))
+            .read()
+            .is_err());
+        assert!(EthereumKeyStore::new(KEYSTORE_FIXTURE.to_string())
+            .with_password(Some(KeystorePassword::Env("ETHEREUM_KEY_STORE_TEST_UNSET".to_string())))
+            .read()
+            .is_err());
+    }
+
+    #[test]
+    pub fn raw_key_file_is_still_supported() {
+        let dir = tempfile::tempdir().unwrap();
+        let key_store = EthereumKeyStore::new(dir.path().join("relayer.bin").to_str().unwrap().to_string())
+            .with_password(Some(KeystorePassword::Env("ETHEREUM_KEY_STORE_TEST_UNSET".to_string())));
+        let key = EthereumKeyStore::generate_key().unwrap();
+
+        key_store.write(&key).unwrap();
+
+        assert_eq!(key_store.read().unwrap(), key);
+    }
+
+    #[test]
+    pub fn keystore_password_is_deserialized_from_config() {
+        assert_eq!(
+            serde_json::from_str::<KeystorePassword>(r#"{"env": "RELAYER_PASSWORD"}"#).unwrap(),
+            KeystorePassword::Env("RELAYER_PASSWORD".to_string())
+        );
+        assert_eq!(
+            serde_json::from_str::<KeystorePassword>(r#"{"file": "/run/secrets/relayer"}"#).unwrap(),
+            KeystorePassword::File("/run/secrets/relayer".to_string())
+        );
+    }
 }
@@ -14,33 +14,75 @@
 // You should have received a copy of the GNU General Public License
 // along with Litentry.  If not, see <https://www.gnu.org/licenses/>.
 
-use crate::key_store::EthereumKeyStore;
+use crate::key_store::{EthereumKeyStore, KeystorePassword};
+use crate::validator::EthereumDestinationValidator;
 use crate::Bridge::BridgeInstance;
-use alloy::dyn_abi::DynSolValue;
 use alloy::hex::decode;
 use alloy::network::{Ethereum, EthereumWallet};
-use alloy::primitives::{Address, Bytes, FixedBytes, U256};
+use alloy::primitives::{keccak256, Address, Bytes, FixedBytes, U256};
 use alloy::providers::fillers::{ChainIdFiller, FillProvider, GasFiller, JoinFill, NonceFiller, WalletFiller};
 use alloy::providers::PendingTransactionError;
 use alloy::providers::{Identity, Provider, ProviderBuilder, RootProvider, WalletProvider};
+use alloy::rpc::client::RpcClient;
 use alloy::signers::k256::ecdsa::SigningKey;
 use alloy::signers::local::{LocalSigner, PrivateKeySigner};
 use alloy::sol;
-use alloy::transports::http::{Client, Http};
+use alloy::transports::http::reqwest::header::{HeaderMap, HeaderValue, AUTHORIZATION};
+use alloy::transports::http::{reqwest, Client, Http};
 use async_trait::async_trait;
+use bridge_core::amount_limit::{
+    default_payouts_file, unix_now, AmountBounds, AmountLimit, AmountLimiter, AmountOutOfBounds,
+};
 use bridge_core::config::BridgeConfig;
+use bridge_core::destination_allowlist::DestinationAllowlist;
 use bridge_core::key_store::KeyStore;
-use bridge_core::relay::{RelayError, Relayer};
-use log::{debug, error};
-use metrics::{describe_gauge, gauge};
+use bridge_core::notification::{Notification, NotificationType, Notifier};
+use bridge_core::primitives::{encode_deposit_data, metric_name};
+use bridge_core::relay::{
+    with_relay_timeout, with_watch_timeout, Recipient, RelayError, RelayRequest, Relayer, DEFAULT_WATCH_TIMEOUT,
+};
+use bridge_core::resource_allowlist::{ResourceAllowlist, FILTERED_RESOURCE_COUNTER};
+use bridge_core::rpc_auth::RpcAuth;
+use bridge_core::validation::Validator;
+use log::{debug, error, info};
+use metrics::{counter, describe_gauge, gauge};
 #[cfg(test)]
 use mockall::automock;
 use serde::Deserialize;
 use std::collections::HashMap;
-use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
 use std::time::Duration;
+use tokio::time::Instant;
 
 pub mod key_store;
+pub mod validator;
+
+pub const DEFAULT_RELAY_TIMEOUT: Duration = Duration::from_secs(120);
+
+// how often relayer role is re-checked, in case it's revoked at runtime
+pub const ROLE_CHECK_INTERVAL: Duration = Duration::from_secs(300);
+
+// info metric, always set to 1, used to join relayer metrics with human readable labels
+pub const RELAYER_INFO_GAUGE: &str = "relayer_info";
+
+// set to 1 when relayer balance drops below configured threshold, labelled by relayer id
+pub const RELAYER_BALANCE_LOW_GAUGE: &str = "relayer_balance_low";
+
+// set to 1 when node reports other chain id than the relayer is pinned to, labelled by relayer id
+pub const RELAYER_CHAIN_ID_MISMATCH_GAUGE: &str = "relayer_chain_id_mismatch";
+
+// how often chain id reported by the node is re-checked against the expected one
+pub const CHAIN_ID_CHECK_INTERVAL: Duration = Duration::from_secs(300);
+
+// set to 1 while the bridge contract is paused by admin, labelled by relayer id
+pub const DESTINATION_PAUSED_GAUGE: &str = "destination_paused";
+
+// how often bridge contract's paused state is re-checked, short so relaying resumes soon after unpause
+pub const PAUSE_CHECK_INTERVAL: Duration = Duration::from_secs(10);
+
+const ETHER_DECIMALS: u8 = 18;
+const GWEI_DECIMALS: u8 = 9;
 
 sol!(
     #[allow(missing_docs)]
@@ -59,12 +101,65 @@ pub trait BridgeInterface {
         resource_id: FixedBytes<32>,
         call_data: Bytes,
     ) -> Result<(), RelayError>;
+    // executes passed proposal, reverts unless the proposal is `Passed`
+    async fn execute_proposal(
+        &self,
+        domain_id: u8,
+        deposit_nonce: u64,
+        call_data: Bytes,
+        resource_id: FixedBytes<32>,
+    ) -> Result<(), RelayError>;
+    // checks whether signer has the relayer role granted on the bridge contract
+    async fn is_registered_relayer(&self) -> Result<bool, ()>;
+    // chain id reported by the node
+    async fn get_chain_id(&self) -> Result<u64, ()>;
+    // whether transfers are paused on the bridge contract, votes revert while paused
+    async fn is_paused(&self) -> Result<bool, ()>;
+    // status of the proposal a vote with given arguments counts towards
+    async fn get_proposal_status(
+        &self,
+        domain_id: u8,
+        deposit_nonce: u64,
+        resource_id: FixedBytes<32>,
+        call_data: Bytes,
+    ) -> Result<ProposalStatus, ()>;
+}
+
+/// Mirrors `Bridge.ProposalStatus` of the bridge contract.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ProposalStatus {
+    Inactive,
+    Active,
+    Passed,
+    Executed,
+    Cancelled,
+}
+
+impl TryFrom<u8> for ProposalStatus {
+    type Error = ();
+
+    fn try_from(status: u8) -> Result<Self, Self::Error> {
+        match status {
+            0 => Ok(Self::Inactive),
+            1 => Ok(Self::Active),
+            2 => Ok(Self::Passed),
+            3 => Ok(Self::Executed),
+            4 => Ok(Self::Cancelled),
+            _ => Err(()),
+        }
+    }
+}
+
+// proposals are keyed by `keccak256(abi.encodePacked(handler, data))`, same as in `Bridge.voteProposal`
+fn proposal_data_hash(handler: Address, call_data: &[u8]) -> FixedBytes<32> {
+    keccak256([handler.as_slice(), call_data].concat())
 }
 
 #[async_trait]
 #[cfg_attr(test, automock)]
 pub trait RelayerBalance {
-    async fn get_balance(&self) -> Result<u128, ()>;
+    // balance in wei
+    async fn get_balance(&self) -> Result<U256, ()>;
 }
 
 type BridgeInstanceType = BridgeInstance<
@@ -83,6 +178,7 @@ type BridgeInstanceType = BridgeInstance<
 #[allow(clippy::type_complexity)]
 pub struct BridgeContractWrapper {
     instance: BridgeInstanceType,
+    watch_timeout: Duration,
 }
 
 #[async_trait]
@@ -95,66 +191,171 @@ impl BridgeInterface for BridgeContractWrapper {
         call_data: Bytes,
     ) -> Result<(), RelayError> {
         let proposal_builder = self.instance.voteProposal(domain_id, deposit_nonce, resource_id, call_data);
-        let tx_hash = proposal_builder
+        let pending_tx = proposal_builder
             .send()
             .await
             .map_err(|e| {
                 error!("Could not send proposal vote: {:?}", e);
-                match e {
-                    alloy::contract::Error::TransportError(e) => {
-                        if e.is_transport_error() {
-                            RelayError::TransportError
-                        } else if e.is_error_resp() {
-                            if let Some(resp) = e.as_error_resp() {
-                                if resp.code == 3 {
-                                    RelayError::AlreadyRelayed
-                                } else {
-                                    RelayError::Other
-                                }
-                            } else {
-                                RelayError::Other
-                            }
-                        } else {
-                            RelayError::Other
-                        }
-                    },
-                    _ => RelayError::Other,
-                }
+                send_error_to_relay_error(e)
             })?
-            .with_timeout(Some(Duration::from_secs(30)))
-            .watch()
-            .await
-            .map_err(|e| {
+            .with_timeout(Some(self.watch_timeout));
+        // watcher's own timeout doesn't fire if node keeps connection open without responding
+        let tx_hash = with_watch_timeout(self.watch_timeout, async {
+            pending_tx.watch().await.map_err(|e| {
                 error!("Could not watch proposal vote: {:?}", e);
-                match e {
-                    PendingTransactionError::TransportError(e) => {
-                        if e.is_transport_error() {
-                            RelayError::TransportError
-                        } else {
-                            RelayError::Other
-                        }
-                    },
-                    PendingTransactionError::TxWatcher(_) => RelayError::WatchError,
-                    _ => RelayError::Other,
-                }
-            })?;
+                watch_error_to_relay_error(e)
+            })
+        })
+        .await?;
         log::debug!("Submitted vote proposal, tx_hash: {:?}", tx_hash);
         Ok(())
     }
-}
 
-#[async_trait]
-impl RelayerBalance for BridgeContractWrapper {
-    async fn get_balance(&self) -> Result<u128, ()> {
+    async fn execute_proposal(
+        &self,
+        domain_id: u8,
+        deposit_nonce: u64,
+        call_data: Bytes,
+        resource_id: FixedBytes<32>,
+    ) -> Result<(), RelayError> {
+        // handler failure reverts the execution, so it isn't marked as executed
+        let execute_builder = self
+            .instance
+            .executeProposal(domain_id, deposit_nonce, call_data, resource_id, true);
+        let pending_tx = execute_builder
+            .send()
+            .await
+            .map_err(|e| {
+                error!("Could not send proposal execution: {:?}", e);
+                send_error_to_relay_error(e)
+            })?
+            .with_timeout(Some(self.watch_timeout));
+        let tx_hash = with_watch_timeout(self.watch_timeout, async {
+            pending_tx.watch().await.map_err(|e| {
+                error!("Could not watch proposal execution: {:?}", e);
+                watch_error_to_relay_error(e)
+            })
+        })
+        .await?;
+        log::debug!("Submitted proposal execution, tx_hash: {:?}", tx_hash);
+        Ok(())
+    }
+
+    async fn is_registered_relayer(&self) -> Result<bool, ()> {
         let address = self.instance.provider().default_signer_address();
         self.instance
-            .provider()
-            .get_balance(address)
+            .isRelayer(address)
+            .call()
+            .await
+            .map_err(|e| {
+                log::error!("Could not check relayer role: {:?}", e);
+            })
+            .map(|result| result._0)
+    }
+
+    async fn get_chain_id(&self) -> Result<u64, ()> {
+        self.instance.provider().get_chain_id().await.map_err(|e| {
+            log::error!("Could not get chain id: {:?}", e);
+        })
+    }
+
+    async fn is_paused(&self) -> Result<bool, ()> {
+        self.instance
+            .paused()
+            .call()
             .await
             .map_err(|e| {
-                log::error!("Could not get relayer balance: {}", e);
+                log::error!("Could not check whether bridge is paused: {:?}", e);
             })
-            .map(|balance| balance.to())
+            .map(|result| result._0)
+    }
+
+    async fn get_proposal_status(
+        &self,
+        domain_id: u8,
+        deposit_nonce: u64,
+        resource_id: FixedBytes<32>,
+        call_data: Bytes,
+    ) -> Result<ProposalStatus, ()> {
+        let handler = self
+            .instance
+            ._resourceIDToHandlerAddress(resource_id)
+            .call()
+            .await
+            .map_err(|e| {
+                log::error!("Could not get handler of resource id {}: {:?}", resource_id, e);
+            })?
+            ._0;
+        let proposal = self
+            .instance
+            .getProposal(domain_id, deposit_nonce, proposal_data_hash(handler, &call_data))
+            .call()
+            .await
+            .map_err(|e| {
+                log::error!("Could not get proposal of nonce {}: {:?}", deposit_nonce, e);
+            })?
+            ._0;
+        ProposalStatus::try_from(proposal._status).map_err(|_| {
+            log::error!("Unknown status {} of proposal of nonce {}", proposal._status, deposit_nonce);
+        })
+    }
+}
+
+// node rejects a vote while the previous one with the same nonce is still pending
+const REPLACEMENT_UNDERPRICED_MESSAGE: &str = "replacement transaction underpriced";
+
+// revert reason of `whenNotPaused`, bridge may get paused after the last pause check
+const PAUSED_REVERT_MESSAGE: &str = "Pausable: paused";
+
+fn send_error_to_relay_error(e: alloy::contract::Error) -> RelayError {
+    match e {
+        alloy::contract::Error::TransportError(e) => {
+            if e.is_transport_error() {
+                RelayError::TransportError
+            } else if let Some(resp) = e.as_error_resp() {
+                // paused revert is reported with code 3 as well, so reasons are matched before the code
+                if resp.message.contains(PAUSED_REVERT_MESSAGE) {
+                    RelayError::Paused
+                } else if resp.message.contains(REPLACEMENT_UNDERPRICED_MESSAGE) {
+                    RelayError::WatchError
+                } else if resp.code == 3 {
+                    RelayError::AlreadyRelayed
+                } else {
+                    RelayError::Other
+                }
+            } else {
+                RelayError::Other
+            }
+        },
+        _ => RelayError::Other,
+    }
+}
+
+// Vote was submitted, failing to watch it doesn't mean it failed, so the event is retried. Reverted votes
+// are still mined and don't surface here. Match is exhaustive so alloy upgrades adding variants are reviewed.
+fn watch_error_to_relay_error(e: PendingTransactionError) -> RelayError {
+    match e {
+        PendingTransactionError::TransportError(e) => {
+            if e.is_transport_error() {
+                RelayError::TransportError
+            } else {
+                RelayError::WatchError
+            }
+        },
+        // receipt not seen within the timeout, e.g. transaction dropped from mempool
+        PendingTransactionError::TxWatcher(_) => RelayError::WatchError,
+        // heartbeat couldn't start watching or stopped before the transaction was confirmed
+        PendingTransactionError::FailedToRegister | PendingTransactionError::Recv(_) => RelayError::WatchError,
+    }
+}
+
+#[async_trait]
+impl RelayerBalance for BridgeContractWrapper {
+    async fn get_balance(&self) -> Result<U256, ()> {
+        let address = self.instance.provider().default_signer_address();
+        self.instance.provider().get_balance(address).await.map_err(|e| {
+            log::error!("Could not get relayer balance: {}", e);
+        })
     }
 }
 
@@ -162,30 +363,140 @@ impl RelayerBalance for BridgeContractWrapper {
 pub struct RelayerConfig {
     pub node_rpc_url: String,
     pub bridge_contract_address: String,
+    // keyed by hex encoded resource id
+    #[serde(default)]
+    pub amount_limits: HashMap<String, AmountLimit>,
+    // payouts counted towards `amount_limits` windows, `data/<relayer id>_relayer_payouts.bin` if not set
+    #[serde(default)]
+    pub payouts_file: Option<String>,
+    // relaying attempt exceeding the timeout is treated as transport error and retried
+    #[serde(default = "default_relay_timeout_secs")]
+    pub relay_timeout_secs: u64,
+    // waiting for vote transaction exceeding the timeout is treated as watch error and retried
+    #[serde(default = "default_watch_timeout_secs")]
+    pub watch_timeout_secs: u64,
+    // `min_amount` and `max_amount` of every relayed request
+    #[serde(flatten)]
+    pub amount_bounds: AmountBounds,
+    #[serde(default)]
+    pub rpc_auth: Option<RpcAuth>,
+    // hex encoded addresses which are never relayed to, e.g. token, handler and bridge contracts
+    #[serde(default)]
+    pub denylist_addresses: Vec<String>,
+    // balance in wei below which relayer is reported as running low on funds
+    #[serde(default)]
+    pub low_balance_threshold: Option<u128>,
+    // hex encoded recipients, if set requests to any other recipient are not relayed
+    #[serde(default)]
+    pub allowed_destinations: Option<Vec<String>>,
+    // if set, votes are signed for this chain only and not submitted while the node reports other chain id
+    #[serde(default)]
+    pub expected_chain_id: Option<u64>,
+    // password of `<id>.json` encrypted keystore, read from env variable or file
+    #[serde(default)]
+    pub keystore_password: Option<KeystorePassword>,
+    // hex encoded resource ids, if not empty requests of any other resource are skipped
+    #[serde(default)]
+    pub allowed_resource_ids: Vec<String>,
+    // executes proposals passed but not executed by the final vote, needed with vote threshold above one
+    #[serde(default)]
+    pub auto_execute: bool,
+}
+
+impl RelayerConfig {
+    #[allow(clippy::result_unit_err)]
+    pub fn destination_allowlist(&self) -> Result<DestinationAllowlist, ()> {
+        let Some(allowed_destinations) = &self.allowed_destinations else {
+            return Ok(DestinationAllowlist::unrestricted());
+        };
+        let allowed = allowed_destinations
+            .iter()
+            .map(|destination| {
+                decode(destination).map_err(|e| error!("Invalid allowed destination: {}, {:?}", destination, e))
+            })
+            .collect::<Result<Vec<_>, ()>>()?;
+        Ok(DestinationAllowlist::new(allowed))
+    }
+
+    #[allow(clippy::result_unit_err)]
+    pub fn resource_allowlist(&self) -> Result<ResourceAllowlist, ()> {
+        ResourceAllowlist::from_hex(&self.allowed_resource_ids)
+    }
 }
 
+fn default_relay_timeout_secs() -> u64 {
+    DEFAULT_RELAY_TIMEOUT.as_secs()
+}
+
+fn default_watch_timeout_secs() -> u64 {
+    DEFAULT_WATCH_TIMEOUT.as_secs()
+}
+
+// encrypted JSON keystore takes precedence over raw key file
+fn key_store_path(keystore_dir: &str, relayer_id: &str) -> String {
+    let json_path = format!("{}/{}.json", keystore_dir, relayer_id);
+    if std::path::Path::new(&json_path).exists() {
+        json_path
+    } else {
+        format!("{}/{}.bin", keystore_dir, relayer_id)
+    }
+}
+
+#[allow(clippy::result_unit_err)]
 pub async fn create_from_config(
     keystore_dir: String,
     config: &BridgeConfig,
-) -> HashMap<String, Arc<Box<dyn Relayer<String>>>> {
+    notifier: Arc<Notifier>,
+) -> Result<HashMap<String, Arc<Box<dyn Relayer<String>>>>, ()> {
     let mut relayers: HashMap<String, Arc<Box<dyn Relayer<String>>>> = HashMap::new();
     for relayer_config in config.relayers.iter().filter(|r| r.relayer_type == "ethereum") {
-        let key_store = EthereumKeyStore::new(format!("{}/{}.bin", keystore_dir, relayer_config.id));
-
         let substrate_relayer_config: RelayerConfig = relayer_config.to_specific_config();
 
-        let signer =
-            PrivateKeySigner::from(key_store.read().map_err(|e| error!("Can't read key store: {:?}", e)).unwrap());
+        let key_store = EthereumKeyStore::new(key_store_path(&keystore_dir, &relayer_config.id))
+            .with_password(substrate_relayer_config.keystore_password.clone());
+
+        let signer = PrivateKeySigner::from(
+            key_store
+                .read()
+                .map_err(|e| error!("Can't read key store of relayer {}: {:?}", relayer_config.id, e))?,
+        );
         let relayer_address = signer.address();
-        log::info!("Ethereum relayer address: {:?}", relayer_address);
 
         let bridge_instance = prepare_bridge_instance(
             signer,
             &substrate_relayer_config.node_rpc_url,
+            substrate_relayer_config.rpc_auth.as_ref(),
             &substrate_relayer_config.bridge_contract_address,
+            substrate_relayer_config.expected_chain_id,
+        )
+        .map_err(|_| error!("Could not prepare bridge instance of relayer {}", relayer_config.id))?;
+
+        let chain = match bridge_instance.provider().get_chain_id().await {
+            Ok(chain_id) => chain_id.to_string(),
+            Err(e) => {
+                error!("Could not get chain id of {} relayer: {:?}", relayer_config.id, e);
+                "unknown".to_string()
+            },
+        };
+        log::info!(
+            "Ethereum relayer {} address: {:?}, destination id: {}, chain id: {}",
+            relayer_config.id,
+            relayer_address,
+            relayer_config.destination_id,
+            chain
         );
+        gauge!(
+            RELAYER_INFO_GAUGE,
+            "address" => relayer_address.to_string(),
+            "destination_id" => relayer_config.destination_id.clone(),
+            "chain" => chain
+        )
+        .set(1.0);
 
-        let bridge_contract_wrapper = BridgeContractWrapper { instance: bridge_instance };
+        let bridge_contract_wrapper = BridgeContractWrapper {
+            instance: bridge_instance,
+            watch_timeout: Duration::from_secs(substrate_relayer_config.watch_timeout_secs),
+        };
 
         let relayer: EthereumRelayer<BridgeContractWrapper> = EthereumRelayer::new(
             relayer_config.id.clone(),
@@ -194,10 +505,52 @@ pub async fn create_from_config(
             relayer_config.destination_id.clone(),
         )
         .await
-        .unwrap();
+        .map_err(|_| error!("Could not create relayer {}", relayer_config.id))?
+        .with_amount_limiter(
+            AmountLimiter::new(
+                &substrate_relayer_config.amount_limits,
+                Some(
+                    substrate_relayer_config
+                        .payouts_file
+                        .clone()
+                        .unwrap_or_else(|| default_payouts_file(&relayer_config.id)),
+                ),
+            )
+            .map_err(|_| error!("Could not create amount limiter of relayer {}", relayer_config.id))?,
+        )
+        .with_amount_bounds(substrate_relayer_config.amount_bounds)
+        .with_relay_timeout(Duration::from_secs(substrate_relayer_config.relay_timeout_secs))
+        .with_notifier(notifier.clone())
+        .with_low_balance_threshold(substrate_relayer_config.low_balance_threshold.map(U256::from))
+        .with_destination_allowlist(
+            substrate_relayer_config
+                .destination_allowlist()
+                .map_err(|_| error!("Invalid allowed destinations of relayer {}", relayer_config.id))?,
+        )
+        .with_resource_allowlist(
+            substrate_relayer_config
+                .resource_allowlist()
+                .map_err(|_| error!("Invalid allowed resource ids of relayer {}", relayer_config.id))?,
+        )
+        .with_auto_execute(substrate_relayer_config.auto_execute)
+        .with_expected_chain_id(substrate_relayer_config.expected_chain_id)
+        .await
+        .map_err(|_| error!("Relayer {} is connected to unexpected chain", relayer_config.id))?;
         relayers.insert(relayer_config.id.to_string(), Arc::new(Box::new(relayer)));
     }
-    relayers
+    Ok(relayers)
+}
+
+/// Creates validators of ethereum relayers, keyed by relayer id.
+#[allow(clippy::result_unit_err)]
+pub fn create_validators(config: &BridgeConfig) -> Result<HashMap<String, Arc<dyn Validator>>, ()> {
+    let mut validators: HashMap<String, Arc<dyn Validator>> = HashMap::new();
+    for relayer_config in config.relayers.iter().filter(|r| r.relayer_type == "ethereum") {
+        let ethereum_relayer_config: RelayerConfig = relayer_config.to_specific_config();
+        let validator = EthereumDestinationValidator::new(&ethereum_relayer_config.denylist_addresses)?;
+        validators.insert(relayer_config.id.clone(), Arc::new(validator));
+    }
+    Ok(validators)
 }
 
 /// Relays bridge request to smart contracts deployed on ethereum based network.
@@ -207,66 +560,405 @@ pub struct EthereumRelayer<T: BridgeInterface + RelayerBalance> {
     address: String,
     bridge_instance: T,
     destination_id: String,
+    amount_limiter: AmountLimiter,
+    amount_bounds: AmountBounds,
+    relay_timeout: Duration,
+    // time of the last successful role check and its result
+    role_check: Mutex<(Option<Instant>, bool)>,
+    low_balance_threshold: Option<U256>,
+    destination_allowlist: DestinationAllowlist,
+    resource_allowlist: ResourceAllowlist,
+    // last fetched balance, so the low balance gauge can be set as soon as threshold is configured
+    last_balance: Mutex<Option<U256>>,
+    expected_chain_id: Option<u64>,
+    // time of the last successful chain id check and whether it matched
+    chain_id_check: Mutex<(Option<Instant>, bool)>,
+    // time of the last successful pause check and its result
+    pause_check: Mutex<(Option<Instant>, bool)>,
+    auto_execute: bool,
+    // whether the last reported balance was below `low_balance_threshold`, only crossing it is notified
+    balance_low: AtomicBool,
+    notifier: Arc<Notifier>,
 }
 
 // TODO: We need to configure gas options
 #[allow(clippy::result_unit_err)]
 impl<T: BridgeInterface + RelayerBalance> EthereumRelayer<T> {
+    /// Fails if signer is not registered as a relayer on the bridge contract.
     pub async fn new(id: String, address: String, bridge_instance: T, destination_id: String) -> Result<Self, ()> {
-        describe_gauge!(balance_gauge_name(&address, &id), "Ethereum relayer balance");
+        describe_gauge!(balance_gauge_name(&address, &id), "Ethereum relayer balance in ether");
+        describe_gauge!(gwei_balance_gauge_name(&address, &id), "Ethereum relayer balance in gwei");
+        describe_gauge!(RELAYER_BALANCE_LOW_GAUGE, "Relayer balance is below configured threshold");
+        describe_gauge!(registered_gauge_name(&address, &id), "Ethereum relayer has relayer role on the bridge");
+        describe_gauge!(RELAYER_CHAIN_ID_MISMATCH_GAUGE, "Node reports other chain id than relayer is pinned to");
+        describe_gauge!(DESTINATION_PAUSED_GAUGE, "Bridge contract of the destination is paused");
+
+        // role can't be verified if node is unreachable, in such case it's checked again on first relay
+        let role_check = match bridge_instance.is_registered_relayer().await {
+            Ok(registered) => {
+                gauge!(registered_gauge_name(&address, &id)).set(registered as u8 as f64);
+                if !registered {
+                    error!("Relayer {} address {} is not registered as a relayer on the bridge", id, address);
+                    return Err(());
+                }
+                (Some(Instant::now()), true)
+            },
+            Err(_) => (None, true),
+        };
+
+        let relayer = Self {
+            id,
+            address,
+            bridge_instance,
+            destination_id,
+            amount_limiter: AmountLimiter::unlimited(),
+            amount_bounds: AmountBounds::default(),
+            relay_timeout: DEFAULT_RELAY_TIMEOUT,
+            role_check: Mutex::new(role_check),
+            low_balance_threshold: None,
+            destination_allowlist: DestinationAllowlist::unrestricted(),
+            resource_allowlist: ResourceAllowlist::unrestricted(),
+            last_balance: Mutex::new(None),
+            expected_chain_id: None,
+            chain_id_check: Mutex::new((None, true)),
+            pause_check: Mutex::new((None, false)),
+            auto_execute: false,
+            balance_low: AtomicBool::new(false),
+            notifier: Arc::new(Notifier::disabled()),
+        };
 
         // initalize relayer's balance metric
-        if let Ok(balance) = bridge_instance.get_balance().await {
-            gauge!(balance_gauge_name(&address, &id)).set(balance as f64);
+        if let Ok(balance) = relayer.bridge_instance.get_balance().await {
+            relayer.report_balance(balance);
         }
-        Ok(Self { id, address, bridge_instance, destination_id })
+        Ok(relayer)
     }
-}
 
-#[async_trait]
-impl<T: BridgeInterface + RelayerBalance + Send + Sync> Relayer<String> for EthereumRelayer<T> {
-    async fn relay(
+    pub fn with_amount_limiter(mut self, amount_limiter: AmountLimiter) -> Self {
+        self.amount_limiter = amount_limiter;
+        self
+    }
+
+    pub fn with_amount_bounds(mut self, amount_bounds: AmountBounds) -> Self {
+        self.amount_bounds = amount_bounds;
+        self
+    }
+
+    pub fn with_relay_timeout(mut self, relay_timeout: Duration) -> Self {
+        self.relay_timeout = relay_timeout;
+        self
+    }
+
+    /// Sets notifier of lost relayer role and balance dropping below `low_balance_threshold`
+    pub fn with_notifier(mut self, notifier: Arc<Notifier>) -> Self {
+        self.notifier = notifier;
+        self
+    }
+
+    pub fn with_low_balance_threshold(mut self, low_balance_threshold: Option<U256>) -> Self {
+        self.low_balance_threshold = low_balance_threshold;
+        let last_balance = *self.last_balance.lock().unwrap();
+        if let Some(balance) = last_balance {
+            self.report_balance(balance);
+        }
+        self
+    }
+
+    pub fn with_destination_allowlist(mut self, destination_allowlist: DestinationAllowlist) -> Self {
+        self.destination_allowlist = destination_allowlist;
+        self
+    }
+
+    pub fn with_resource_allowlist(mut self, resource_allowlist: ResourceAllowlist) -> Self {
+        self.resource_allowlist = resource_allowlist;
+        self
+    }
+
+    pub fn with_auto_execute(mut self, auto_execute: bool) -> Self {
+        self.auto_execute = auto_execute;
+        self
+    }
+
+    /// Pins relayer to given chain, fails if the node reports other chain id. If the node is unreachable
+    /// the check is repeated before the first relay.
+    pub async fn with_expected_chain_id(mut self, expected_chain_id: Option<u64>) -> Result<Self, ()> {
+        self.expected_chain_id = expected_chain_id;
+        *self.chain_id_check.lock().unwrap() = (None, true);
+        if self.expected_chain_id.is_some() && !self.is_on_expected_chain().await {
+            return Err(());
+        }
+        Ok(self)
+    }
+
+    fn report_balance(&self, balance: U256) {
+        *self.last_balance.lock().unwrap() = Some(balance);
+        gauge!(balance_gauge_name(&self.address, &self.id)).set(to_units(balance, ETHER_DECIMALS));
+        gauge!(gwei_balance_gauge_name(&self.address, &self.id)).set(to_units(balance, GWEI_DECIMALS));
+        if let Some(threshold) = self.low_balance_threshold {
+            let low = balance < threshold;
+            if low {
+                error!(
+                    "Relayer {} address {} balance {} wei is below threshold {} wei",
+                    self.id, self.address, balance, threshold
+                );
+            }
+            let was_low = self.balance_low.swap(low, Ordering::Relaxed);
+            if low && !was_low {
+                self.notifier.notify(
+                    Notification::new(
+                        NotificationType::LowBalance,
+                        &format!("Balance {} wei is below threshold {} wei", balance, threshold),
+                    )
+                    .with_relayer(&self.id),
+                );
+            }
+            gauge!(RELAYER_BALANCE_LOW_GAUGE, "relayer" => self.id.clone()).set(low as u8 as f64);
+        }
+    }
+
+    // re-checks relayer role if the last check is older than `ROLE_CHECK_INTERVAL`,
+    // last known result is used if the check fails
+    async fn is_registered(&self) -> bool {
+        let (checked_at, registered) = *self.role_check.lock().unwrap();
+        if checked_at.is_some_and(|checked_at| checked_at.elapsed() < ROLE_CHECK_INTERVAL) {
+            return registered;
+        }
+        match self.bridge_instance.is_registered_relayer().await {
+            Ok(now_registered) => {
+                gauge!(registered_gauge_name(&self.address, &self.id)).set(now_registered as u8 as f64);
+                *self.role_check.lock().unwrap() = (Some(Instant::now()), now_registered);
+                if registered && !now_registered {
+                    self.notifier.notify(
+                        Notification::new(
+                            NotificationType::RelayerNotRegistered,
+                            &format!("Address {} is no longer registered as a relayer on the bridge", self.address),
+                        )
+                        .with_relayer(&self.id),
+                    );
+                }
+                now_registered
+            },
+            Err(_) => registered,
+        }
+    }
+
+    // executes the proposal if the vote passed it without executing, e.g. with vote threshold above one
+    async fn execute_if_passed(
         &self,
-        amount: u128,
         nonce: u64,
-        resource_id: &[u8; 32],
-        data: &[u8],
-        _chain_id: u32,
+        resource_id: FixedBytes<32>,
+        call_data: Bytes,
     ) -> Result<(), RelayError> {
-        debug!("Relaying amount: {} with nonce: {} to: {:?}", amount, nonce, Address::from_slice(data));
+        let status = self
+            .bridge_instance
+            .get_proposal_status(0, nonce, resource_id, call_data.clone())
+            .await
+            .map_err(|_| {
+                error!("Could not check whether proposal of nonce {} needs execution", nonce);
+                RelayError::WatchError
+            })?;
+        if status != ProposalStatus::Passed {
+            return Ok(());
+        }
+        match self.execute(nonce, resource_id, call_data).await {
+            Err(RelayError::AlreadyRelayed) => Ok(()),
+            result => result,
+        }
+    }
 
-        // resource id 0
-        let resource_id = FixedBytes::new(resource_id.to_owned());
+    // execution of proposal executed in the meantime by another relayer reverts, it's reported as already relayed
+    async fn execute(&self, nonce: u64, resource_id: FixedBytes<32>, call_data: Bytes) -> Result<(), RelayError> {
+        match self
+            .bridge_instance
+            .execute_proposal(0, nonce, call_data.clone(), resource_id)
+            .await
+        {
+            Err(RelayError::AlreadyRelayed) => {
+                match self.bridge_instance.get_proposal_status(0, nonce, resource_id, call_data).await {
+                    Ok(ProposalStatus::Executed) => {
+                        info!("Proposal of nonce {} was executed by another relayer", nonce);
+                        Err(RelayError::AlreadyRelayed)
+                    },
+                    status => {
+                        error!("Execution of proposal of nonce {} reverted, proposal status: {:?}", nonce, status);
+                        Err(RelayError::Other)
+                    },
+                }
+            },
+            Ok(()) => {
+                info!("Executed proposal of nonce {}", nonce);
+                Ok(())
+            },
+            result => result,
+        }
+    }
+
+    // re-checks chain id if the last check is older than `CHAIN_ID_CHECK_INTERVAL`,
+    // last known result is used if the check fails
+    async fn is_on_expected_chain(&self) -> bool {
+        let Some(expected_chain_id) = self.expected_chain_id else {
+            return true;
+        };
+        let (checked_at, matches) = *self.chain_id_check.lock().unwrap();
+        if checked_at.is_some_and(|checked_at| checked_at.elapsed() < CHAIN_ID_CHECK_INTERVAL) {
+            return matches;
+        }
+        match self.bridge_instance.get_chain_id().await {
+            Ok(chain_id) => {
+                let matches = chain_id == expected_chain_id;
+                if !matches {
+                    error!(
+                        "ALERT: Relayer {} node reports chain id {}, but relayer is pinned to chain id {}",
+                        self.id, chain_id, expected_chain_id
+                    );
+                }
+                gauge!(RELAYER_CHAIN_ID_MISMATCH_GAUGE, "relayer" => self.id.clone()).set(!matches as u8 as f64);
+                *self.chain_id_check.lock().unwrap() = (Some(Instant::now()), matches);
+                matches
+            },
+            Err(_) => matches,
+        }
+    }
+
+    // re-checks paused state if the last check is older than `PAUSE_CHECK_INTERVAL`,
+    // last known result is used if the check fails
+    async fn is_paused(&self) -> bool {
+        let (checked_at, paused) = *self.pause_check.lock().unwrap();
+        if checked_at.is_some_and(|checked_at| checked_at.elapsed() < PAUSE_CHECK_INTERVAL) {
+            return paused;
+        }
+        match self.bridge_instance.is_paused().await {
+            Ok(now_paused) => {
+                if now_paused != paused {
+                    info!("Bridge of relayer {} is {}", self.id, if now_paused { "paused" } else { "unpaused" });
+                }
+                gauge!(DESTINATION_PAUSED_GAUGE, "relayer" => self.id.clone()).set(now_paused as u8 as f64);
+                *self.pause_check.lock().unwrap() = (Some(Instant::now()), now_paused);
+                now_paused
+            },
+            Err(_) => paused,
+        }
+    }
+}
+
+#[async_trait]
+impl<T: BridgeInterface + RelayerBalance + Send + Sync> Relayer<String> for EthereumRelayer<T> {
+    async fn relay(&self, request: &RelayRequest) -> Result<(), RelayError> {
+        let RelayRequest { amount, nonce, ref resource_id, ref recipient, skip_amount_limits, .. } = *request;
+        debug!("Relaying amount: {} with nonce: {} to: 0x{}", amount, nonce, alloy::hex::encode(recipient.as_bytes()));
+
+        if !self.resource_allowlist.is_allowed(resource_id) {
+            info!(
+                "Skipping nonce {}, resource id 0x{} is not on allowlist of relayer {}",
+                nonce,
+                alloy::hex::encode(resource_id),
+                self.id
+            );
+            counter!(FILTERED_RESOURCE_COUNTER, "destination" => self.destination_id.clone()).increment(1);
+            return Err(RelayError::Filtered);
+        }
 
-        let amount = DynSolValue::Uint(U256::from(amount), 32).abi_encode();
-        let address_len = DynSolValue::Uint(U256::from(data.len()), 32).abi_encode();
+        // EVM address or 32 bytes identifier expected by destination contract
+        let recipient = match recipient {
+            Recipient::Evm(address) => address.as_slice(),
+            Recipient::Substrate(account) => account.as_slice(),
+            Recipient::Raw(bytes) => {
+                error!("Could not relay nonce {}, unsupported recipient: 0x{}", nonce, alloy::hex::encode(bytes));
+                return Err(RelayError::Other);
+            },
+        };
 
-        if data.len() != 20 {
-            error!("Could not relay due to wrong data length");
+        if !self.destination_allowlist.is_allowed(recipient) {
+            error!(
+                "Refusing to relay nonce {}, recipient 0x{} is not on allowlist of relayer {}",
+                nonce,
+                alloy::hex::encode(recipient),
+                self.id
+            );
             return Err(RelayError::Other);
         }
 
-        let mut address_bytes = [0; 32];
-        address_bytes[0..20].copy_from_slice(data);
+        match self.amount_bounds.check(amount) {
+            Ok(()) => {},
+            Err(AmountOutOfBounds::BelowMin { min }) => {
+                info!("Skipping nonce {}, amount {} is below minimum {}", nonce, amount, min);
+                return Err(RelayError::BelowMinAmount);
+            },
+            Err(AmountOutOfBounds::AboveMax { max }) => {
+                error!("ALERT: Refusing to relay nonce {}, amount {} is above maximum {}", nonce, amount, max);
+                return Err(RelayError::AboveMaxAmount);
+            },
+        }
+
+        // amount stays counted towards the limits only if the vote is submitted
+        let now = unix_now();
+        let reservation = if skip_amount_limits {
+            info!("Relaying nonce {} regardless of amount limits", nonce);
+            self.amount_limiter.reserve_unchecked(resource_id, amount, now)
+        } else {
+            self.amount_limiter.reserve(resource_id, amount, now).map_err(|e| {
+                error!("Could not relay nonce {} due to amount limit: {:?}", nonce, e);
+                RelayError::LimitExceeded
+            })?
+        };
 
-        let address = DynSolValue::FixedBytes(FixedBytes(address_bytes), 32).abi_encode();
+        if !self.is_registered().await {
+            error!(
+                "Could not relay nonce {}, relayer {} address {} is not registered on the bridge",
+                nonce, self.id, self.address
+            );
+            return Err(RelayError::Other);
+        }
 
-        debug!("Address bytes: {:?}", address);
+        if !self.is_on_expected_chain().await {
+            error!("Refusing to relay nonce {}, relayer {} is connected to unexpected chain", nonce, self.id);
+            return Err(RelayError::Other);
+        }
 
-        let mut bytes = vec![];
+        if self.is_paused().await {
+            debug!("Could not relay nonce {}, bridge of relayer {} is paused", nonce, self.id);
+            return Err(RelayError::Paused);
+        }
 
-        bytes.extend(amount);
-        bytes.extend(address_len);
-        bytes.extend(address);
+        // resource id 0
+        let resource_id = FixedBytes::new(resource_id.to_owned());
 
-        let call_data = Bytes::copy_from_slice(&bytes);
+        let call_data = Bytes::from(encode_deposit_data(amount, recipient).map_err(|_| RelayError::Other)?);
 
         debug!("Call data: {:?}", call_data);
 
         // domainId 0 - heima
-        self.bridge_instance.vote_proposal(0, nonce, resource_id, call_data).await?;
+        with_relay_timeout(self.relay_timeout, async {
+            // saves gas of votes rejected by the contract, e.g. during catch-up after a restart
+            match self
+                .bridge_instance
+                .get_proposal_status(0, nonce, resource_id, call_data.clone())
+                .await
+            {
+                Ok(ProposalStatus::Passed) if self.auto_execute => {
+                    info!("Proposal of nonce {} has passed but is not executed, executing", nonce);
+                    return self.execute(nonce, resource_id, call_data).await;
+                },
+                Ok(status @ (ProposalStatus::Passed | ProposalStatus::Executed)) => {
+                    info!("Skipping nonce {}, proposal is already {:?}", nonce, status);
+                    return Err(RelayError::AlreadyRelayed);
+                },
+                Ok(_) => {},
+                // vote is submitted anyway, status check is only an optimization
+                Err(_) => {},
+            }
+            self.bridge_instance
+                .vote_proposal(0, nonce, resource_id, call_data.clone())
+                .await?;
+            if self.auto_execute {
+                self.execute_if_passed(nonce, resource_id, call_data).await?;
+            }
+            Ok(())
+        })
+        .await?;
+        reservation.commit();
         if let Ok(balance) = self.bridge_instance.get_balance().await {
-            gauge!(balance_gauge_name(&self.address, &self.id)).set(balance as f64);
+            self.report_balance(balance);
         }
 
         debug!("Proposal relayed");
@@ -278,39 +970,89 @@ impl<T: BridgeInterface + RelayerBalance + Send + Sync> Relayer<String> for Ethe
     }
 }
 
+#[allow(clippy::result_unit_err)]
 pub fn prepare_bridge_instance(
     signer: LocalSigner<SigningKey>,
     rpc_url: &str,
+    rpc_auth: Option<&RpcAuth>,
     bridge_contract_address: &str,
-) -> BridgeInstanceType {
+    expected_chain_id: Option<u64>,
+) -> Result<BridgeInstanceType, ()> {
     let wallet = EthereumWallet::from(signer);
-    let provider = ProviderBuilder::new()
-        .with_recommended_fillers()
-        .wallet(wallet)
-        .on_http(rpc_url.parse().map_err(|_| error!("Could not parse rpc url")).unwrap());
-
-    Bridge::new(
-        Address::from_slice(
-            &decode(bridge_contract_address)
-                .map_err(|_| error!("Can't decode bridge address"))
-                .unwrap(),
-        ),
-        provider,
-    )
+    let url: reqwest::Url = rpc_url.parse().map_err(|_| error!("Could not parse rpc url {}", rpc_url))?;
+    // transactions signed for the expected chain id are rejected by nodes of other chains (EIP-155)
+    let builder = ProviderBuilder::new()
+        .filler(GasFiller)
+        .filler(NonceFiller::default())
+        .filler(ChainIdFiller::new(expected_chain_id))
+        .wallet(wallet);
+    let provider = match rpc_auth {
+        Some(rpc_auth) => {
+            let mut authorization = HeaderValue::from_str(&rpc_auth.authorization_header()?)
+                .map_err(|e| error!("Invalid rpc authorization header: {:?}", e))?;
+            authorization.set_sensitive(true);
+            let http_client = reqwest::Client::builder()
+                .default_headers(HeaderMap::from_iter([(AUTHORIZATION, authorization)]))
+                .build()
+                .map_err(|e| error!("Could not build http client: {:?}", e))?;
+            builder.on_client(RpcClient::new(Http::with_client(http_client, url), false))
+        },
+        None => builder.on_http(url),
+    };
+
+    let bridge_contract_address = decode(bridge_contract_address)
+        .map_err(|_| error!("Can't decode bridge address {}", bridge_contract_address))?;
+    Ok(Bridge::new(Address::from_slice(&bridge_contract_address), provider))
+}
+
+// converts wei amount to whole units with given decimals, saturating at u128::MAX units
+fn to_units(amount: U256, decimals: u8) -> f64 {
+    let unit = U256::from(10u64).pow(U256::from(decimals));
+    let whole = u128::try_from(amount / unit).unwrap_or(u128::MAX);
+    let fraction = (amount % unit).to::<u64>();
+    whole as f64 + fraction as f64 / unit.to::<u64>() as f64
 }
 
+// prefixed, as metric names can't start with the `0x` of the address
 fn balance_gauge_name(address: &str, id: &str) -> String {
-    format!("{}_{}_eth_balance", address, id)
+    metric_name(&format!("relayer_{}_{}_eth_balance", address, id))
+}
+
+fn gwei_balance_gauge_name(address: &str, id: &str) -> String {
+    metric_name(&format!("relayer_{}_{}_gwei_balance", address, id))
+}
+
+fn registered_gauge_name(address: &str, id: &str) -> String {
+    metric_name(&format!("relayer_{}_{}_registered", address, id))
 }
 
 #[cfg(test)]
 pub mod tests {
-    use crate::{prepare_bridge_instance, BridgeContractWrapper, BridgeInterface, EthereumRelayer, RelayerBalance};
-    use alloy::primitives::{Bytes, FixedBytes};
+    use crate::{
+        balance_gauge_name, gwei_balance_gauge_name, key_store_path, prepare_bridge_instance, registered_gauge_name,
+        send_error_to_relay_error, to_units, watch_error_to_relay_error, BridgeContractWrapper, BridgeInterface,
+        EthereumRelayer, ProposalStatus, RelayerBalance, RelayerConfig, CHAIN_ID_CHECK_INTERVAL,
+        DESTINATION_PAUSED_GAUGE, ETHER_DECIMALS, GWEI_DECIMALS, PAUSE_CHECK_INTERVAL, RELAYER_BALANCE_LOW_GAUGE,
+        RELAYER_CHAIN_ID_MISMATCH_GAUGE, ROLE_CHECK_INTERVAL,
+    };
+    use alloy::primitives::{Bytes, FixedBytes, U256};
+    use alloy::providers::{PendingTransactionError, WatchTxError};
+    use alloy::rpc::json_rpc::ErrorPayload;
     use alloy::signers::local::PrivateKeySigner;
+    use alloy::transports::{RpcError, TransportErrorKind};
     use async_trait::async_trait;
-    use bridge_core::relay::{RelayError, Relayer};
+    use bridge_core::amount_limit::{AmountBounds, AmountLimit, AmountLimiter};
+    use bridge_core::destination_allowlist::DestinationAllowlist;
+    use bridge_core::notification::{NotificationsConfig, Notifier, Severity, WebhookClient};
+    use bridge_core::primitives::encode_deposit_data;
+    use bridge_core::relay::{Recipient, RelayError, RelayRequest, Relayer, DEFAULT_WATCH_TIMEOUT};
+    use bridge_core::resource_allowlist::ResourceAllowlist;
+    use metrics_util::debugging::{DebugValue, DebuggingRecorder};
     use mockall::mock;
+    use std::collections::HashMap;
+    use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+    use std::sync::{Arc, Mutex};
+    use std::time::Duration;
 
     mock! {
         BridgeInstance {}
@@ -324,39 +1066,947 @@ pub mod tests {
                 resource_id: FixedBytes<32>,
                 call_data: Bytes,
             ) -> Result<(), RelayError>;
+            async fn execute_proposal(
+                &self,
+                domain_id: u8,
+                deposit_nonce: u64,
+                call_data: Bytes,
+                resource_id: FixedBytes<32>,
+            ) -> Result<(), RelayError>;
+            async fn is_registered_relayer(&self) -> Result<bool, ()>;
+            async fn get_chain_id(&self) -> Result<u64, ()>;
+            async fn is_paused(&self) -> Result<bool, ()>;
+            async fn get_proposal_status(
+                &self,
+                domain_id: u8,
+                deposit_nonce: u64,
+                resource_id: FixedBytes<32>,
+                call_data: Bytes,
+            ) -> Result<ProposalStatus, ()>;
         }
         #[async_trait]
         impl RelayerBalance for BridgeInstance {
-            async fn get_balance(&self) -> Result<u128, ()>;
+            async fn get_balance(&self) -> Result<U256, ()>;
+        }
+
+    }
+
+    #[derive(Default)]
+    struct RecordingWebhookClient {
+        payloads: Mutex<Vec<String>>,
+    }
+
+    #[async_trait]
+    impl WebhookClient for RecordingWebhookClient {
+        async fn post(&self, _url: &str, payload: &str) -> Result<(), ()> {
+            self.payloads.lock().unwrap().push(payload.to_string());
+            Ok(())
         }
+    }
+
+    fn recording_notifier() -> (Arc<Notifier>, Arc<RecordingWebhookClient>) {
+        let client = Arc::new(RecordingWebhookClient::default());
+        let config = NotificationsConfig {
+            webhook_url: "https://hooks.example.com".to_string(),
+            min_severity: Severity::Warning,
+            timeout_ms: 1000,
+        };
+        (Arc::new(Notifier::new(&config, client.clone())), client)
+    }
 
+    fn relay_request(amount: u128, nonce: u64, resource_id: [u8; 32], recipient: Recipient) -> RelayRequest {
+        RelayRequest { amount, nonce, resource_id, recipient, source_chain_id: 0, skip_amount_limits: false }
+    }
+
+    #[test]
+    pub fn gauge_names_are_valid_metric_names() {
+        let address = "0x70997970C51812dc3A010C7d01b50e0d17dc79C8";
+
+        assert_eq!(
+            balance_gauge_name(address, "sepolia-1"),
+            "relayer_0x70997970C51812dc3A010C7d01b50e0d17dc79C8_sepolia_1_eth_balance"
+        );
+        assert_eq!(
+            gwei_balance_gauge_name(address, "sepolia-1"),
+            "relayer_0x70997970C51812dc3A010C7d01b50e0d17dc79C8_sepolia_1_gwei_balance"
+        );
+        assert_eq!(
+            registered_gauge_name(address, "sepolia"),
+            "relayer_0x70997970C51812dc3A010C7d01b50e0d17dc79C8_sepolia_registered"
+        );
+    }
+
+    #[test]
+    pub fn balance_is_converted_to_whole_units() {
+        let ether = U256::from(10u64).pow(U256::from(18));
+
+        assert_eq!(to_units(U256::ZERO, ETHER_DECIMALS), 0.0);
+        assert_eq!(to_units(U256::from(1), ETHER_DECIMALS), 1e-18);
+        assert_eq!(to_units(U256::from(1), GWEI_DECIMALS), 1e-9);
+        assert_eq!(to_units(U256::from(1_000_000_000u64), GWEI_DECIMALS), 1.0);
+        assert_eq!(to_units(ether, ETHER_DECIMALS), 1.0);
+        assert_eq!(to_units(ether + U256::from(1_000_000_000u64), GWEI_DECIMALS), 1_000_000_001.0);
+        assert_eq!(to_units(ether * U256::from(3) / U256::from(2), ETHER_DECIMALS), 1.5);
+    }
+
+    #[test]
+    pub fn balance_above_u128_saturates() {
+        assert_eq!(to_units(U256::MAX, 0), u128::MAX as f64);
+        assert_eq!(to_units(U256::MAX, ETHER_DECIMALS), u128::MAX as f64);
+    }
+
+    fn low_balance_gauge(recorder: &DebuggingRecorder) -> Option<f64> {
+        recorder
+            .snapshotter()
+            .snapshot()
+            .into_vec()
+            .into_iter()
+            .find_map(|(key, _, _, value)| match value {
+                DebugValue::Gauge(value) if key.key().name() == RELAYER_BALANCE_LOW_GAUGE => Some(value.into_inner()),
+                _ => None,
+            })
     }
 
     #[tokio::test]
-    pub async fn should_return_error_if_wrong_address_len() {
+    pub async fn should_report_low_balance_below_threshold() {
+        let threshold = U256::from(1_000_000u64);
+        for (balance, low) in [
+            (U256::ZERO, Some(1.0)),
+            (threshold - U256::from(1), Some(1.0)),
+            (threshold, Some(0.0)),
+            (U256::MAX, Some(0.0)),
+        ] {
+            let mut bridge_instance = MockBridgeInstance::new();
+            bridge_instance.expect_get_balance().returning(move || Ok(balance));
+            bridge_instance.expect_is_registered_relayer().returning(|| Ok(true));
+
+            let recorder = DebuggingRecorder::new();
+            let relayer =
+                EthereumRelayer::new("test".to_string(), "0x".to_string(), bridge_instance, "0100000000".to_string())
+                    .await
+                    .unwrap();
+            metrics::with_local_recorder(&recorder, || {
+                let _relayer = relayer.with_low_balance_threshold(Some(threshold));
+            });
+
+            assert_eq!(low_balance_gauge(&recorder), low, "balance: {}", balance);
+        }
+    }
+
+    #[tokio::test]
+    pub async fn should_not_report_low_balance_without_threshold() {
         let mut bridge_instance = MockBridgeInstance::new();
-        bridge_instance.expect_get_balance().returning(|| Ok(1));
+        bridge_instance.expect_get_balance().returning(|| Ok(U256::ZERO));
+        bridge_instance.expect_is_registered_relayer().returning(|| Ok(true));
 
+        let recorder = DebuggingRecorder::new();
         let relayer =
             EthereumRelayer::new("test".to_string(), "0x".to_string(), bridge_instance, "0100000000".to_string())
                 .await
                 .unwrap();
+        metrics::with_local_recorder(&recorder, || {
+            let _relayer = relayer.with_low_balance_threshold(None);
+        });
 
-        let result = relayer.relay(100, 1, &[0; 32], &[0; 32], 0).await;
-        assert!(matches!(result, Err(RelayError::Other)));
+        assert_eq!(low_balance_gauge(&recorder), None);
+    }
+
+    #[tokio::test(start_paused = true)]
+    pub async fn should_notify_once_balance_drops_below_threshold() {
+        let threshold = U256::from(1_000_000u64);
+        let mut bridge_instance = MockBridgeInstance::new();
+        bridge_instance.expect_get_balance().returning(move || Ok(threshold));
+        bridge_instance.expect_is_registered_relayer().returning(|| Ok(true));
+        let (notifier, client) = recording_notifier();
+
+        let relayer =
+            EthereumRelayer::new("test".to_string(), "0x".to_string(), bridge_instance, "0100000000".to_string())
+                .await
+                .unwrap()
+                .with_notifier(notifier)
+                .with_low_balance_threshold(Some(threshold));
+        // the first drop is notified, balance staying low is not
+        relayer.report_balance(threshold - U256::from(1));
+        relayer.report_balance(threshold - U256::from(2));
+        relayer.report_balance(threshold);
+        relayer.report_balance(threshold - U256::from(1));
+        tokio::time::sleep(Duration::from_secs(1)).await;
+
+        let payloads = client.payloads.lock().unwrap().clone();
+        assert_eq!(payloads.len(), 2);
+        assert!(payloads.iter().all(|payload| payload.contains(r#""event_type":"low_balance""#)));
     }
 
     #[tokio::test]
-    pub async fn vote_proposal_should_return_transport_error_if_node_unreachable() {
-        let bridge_instance = prepare_bridge_instance(
+    pub async fn should_return_error_if_wrong_address_len() {
+        let mut bridge_instance = MockBridgeInstance::new();
+        bridge_instance.expect_get_balance().returning(|| Ok(U256::from(1)));
+        bridge_instance.expect_is_registered_relayer().returning(|| Ok(true));
+        bridge_instance.expect_is_paused().returning(|| Ok(false));
+
+        let relayer =
+            EthereumRelayer::new("test".to_string(), "0x".to_string(), bridge_instance, "0100000000".to_string())
+                .await
+                .unwrap();
+
+        for recipient in [vec![1; 19], vec![1; 21], vec![1; 33], vec![]] {
+            let result = relayer.relay(&relay_request(100, 1, [0; 32], Recipient::from(recipient))).await;
+            assert!(matches!(result, Err(RelayError::Other)));
+        }
+    }
+
+    #[tokio::test]
+    pub async fn should_return_limit_exceeded_error_if_amount_exceeds_cap() {
+        let mut bridge_instance = MockBridgeInstance::new();
+        bridge_instance.expect_get_balance().returning(|| Ok(U256::from(1)));
+        bridge_instance.expect_is_registered_relayer().returning(|| Ok(true));
+        bridge_instance.expect_is_paused().returning(|| Ok(false));
+        bridge_instance.expect_vote_proposal().times(0);
+
+        let limits = HashMap::from([(
+            alloy::hex::encode([1; 32]),
+            AmountLimit { max_single_amount: Some(10), max_window_amount: None, window_secs: 60 },
+        )]);
+        let relayer =
+            EthereumRelayer::new("test".to_string(), "0x".to_string(), bridge_instance, "0100000000".to_string())
+                .await
+                .unwrap()
+                .with_amount_limiter(AmountLimiter::new(&limits, None).unwrap());
+
+        let result = relayer.relay(&relay_request(11, 1, [1; 32], Recipient::Evm([0; 20]))).await;
+        assert!(matches!(result, Err(RelayError::LimitExceeded)));
+    }
+
+    #[tokio::test]
+    pub async fn should_skip_amount_below_minimum() {
+        let mut bridge_instance = MockBridgeInstance::new();
+        bridge_instance.expect_get_balance().returning(|| Ok(U256::from(1)));
+        bridge_instance.expect_is_registered_relayer().returning(|| Ok(true));
+        bridge_instance.expect_is_paused().returning(|| Ok(false));
+        bridge_instance.expect_vote_proposal().times(0);
+
+        let relayer =
+            EthereumRelayer::new("test".to_string(), "0x".to_string(), bridge_instance, "0100000000".to_string())
+                .await
+                .unwrap()
+                .with_amount_bounds(AmountBounds { min_amount: Some(10), max_amount: None });
+
+        let result = relayer.relay(&relay_request(9, 1, [1; 32], Recipient::Evm([1; 20]))).await;
+        assert!(matches!(result, Err(RelayError::BelowMinAmount)));
+    }
+
+    #[tokio::test]
+    pub async fn should_refuse_amount_above_maximum() {
+        let mut bridge_instance = MockBridgeInstance::new();
+        bridge_instance.expect_get_balance().returning(|| Ok(U256::from(1)));
+        bridge_instance.expect_is_registered_relayer().returning(|| Ok(true));
+        bridge_instance.expect_is_paused().returning(|| Ok(false));
+        bridge_instance.expect_vote_proposal().times(0);
+
+        let relayer =
+            EthereumRelayer::new("test".to_string(), "0x".to_string(), bridge_instance, "0100000000".to_string())
+                .await
+                .unwrap()
+                .with_amount_bounds(AmountBounds { min_amount: None, max_amount: Some(10) });
+
+        let result = relayer.relay(&relay_request(11, 1, [1; 32], Recipient::Evm([1; 20]))).await;
+        assert!(matches!(result, Err(RelayError::AboveMaxAmount)));
+    }
+
+    struct HangingBridgeInstance;
+
+    #[async_trait]
+    impl BridgeInterface for HangingBridgeInstance {
+        async fn vote_proposal(&self, _: u8, _: u64, _: FixedBytes<32>, _: Bytes) -> Result<(), RelayError> {
+            std::future::pending().await
+        }
+
+        async fn execute_proposal(&self, _: u8, _: u64, _: Bytes, _: FixedBytes<32>) -> Result<(), RelayError> {
+            std::future::pending().await
+        }
+
+        async fn is_registered_relayer(&self) -> Result<bool, ()> {
+            Ok(true)
+        }
+
+        async fn get_chain_id(&self) -> Result<u64, ()> {
+            Ok(1)
+        }
+
+        async fn is_paused(&self) -> Result<bool, ()> {
+            Ok(false)
+        }
+
+        async fn get_proposal_status(&self, _: u8, _: u64, _: FixedBytes<32>, _: Bytes) -> Result<ProposalStatus, ()> {
+            Ok(ProposalStatus::Inactive)
+        }
+    }
+
+    #[async_trait]
+    impl RelayerBalance for HangingBridgeInstance {
+        async fn get_balance(&self) -> Result<U256, ()> {
+            Ok(U256::from(1))
+        }
+    }
+
+    #[tokio::test]
+    pub async fn should_relay_to_evm_address_and_32_bytes_recipient() {
+        let mut bridge_instance = MockBridgeInstance::new();
+        bridge_instance.expect_get_balance().returning(|| Ok(U256::from(1)));
+        bridge_instance
+            .expect_get_proposal_status()
+            .returning(|_, _, _, _| Ok(ProposalStatus::Inactive));
+        bridge_instance.expect_is_registered_relayer().returning(|| Ok(true));
+        bridge_instance.expect_is_paused().returning(|| Ok(false));
+        bridge_instance
+            .expect_vote_proposal()
+            .withf(|_, nonce, _, call_data| *nonce == 1 && call_data[..] == encode_deposit_data(100, &[1; 20]).unwrap())
+            .times(1)
+            .returning(|_, _, _, _| Ok(()));
+        bridge_instance
+            .expect_vote_proposal()
+            .withf(|_, nonce, _, call_data| *nonce == 2 && call_data[..] == encode_deposit_data(100, &[2; 32]).unwrap())
+            .times(1)
+            .returning(|_, _, _, _| Ok(()));
+
+        let relayer =
+            EthereumRelayer::new("test".to_string(), "0x".to_string(), bridge_instance, "0100000000".to_string())
+                .await
+                .unwrap();
+
+        assert!(relayer
+            .relay(&relay_request(100, 1, [0; 32], Recipient::Evm([1; 20])))
+            .await
+            .is_ok());
+        assert!(relayer
+            .relay(&relay_request(100, 2, [0; 32], Recipient::Substrate([2; 32])))
+            .await
+            .is_ok());
+    }
+
+    #[tokio::test]
+    pub async fn should_relay_only_to_allowed_destinations() {
+        let mut bridge_instance = MockBridgeInstance::new();
+        bridge_instance.expect_get_balance().returning(|| Ok(U256::from(1)));
+        bridge_instance
+            .expect_get_proposal_status()
+            .returning(|_, _, _, _| Ok(ProposalStatus::Inactive));
+        bridge_instance.expect_is_registered_relayer().returning(|| Ok(true));
+        bridge_instance.expect_is_paused().returning(|| Ok(false));
+        bridge_instance
+            .expect_vote_proposal()
+            .withf(|_, nonce, _, _| *nonce == 1)
+            .times(1)
+            .returning(|_, _, _, _| Ok(()));
+
+        let relayer =
+            EthereumRelayer::new("test".to_string(), "0x".to_string(), bridge_instance, "0100000000".to_string())
+                .await
+                .unwrap()
+                .with_destination_allowlist(DestinationAllowlist::new(vec![vec![1; 20]]));
+
+        assert!(relayer
+            .relay(&relay_request(100, 1, [0; 32], Recipient::Evm([1; 20])))
+            .await
+            .is_ok());
+        let result = relayer.relay(&relay_request(100, 2, [0; 32], Recipient::Evm([2; 20]))).await;
+        assert!(matches!(result, Err(RelayError::Other)));
+    }
+
+    #[tokio::test]
+    pub async fn should_skip_resources_not_on_allowlist() {
+        let mut bridge_instance = MockBridgeInstance::new();
+        bridge_instance.expect_get_balance().returning(|| Ok(U256::from(1)));
+        bridge_instance
+            .expect_get_proposal_status()
+            .returning(|_, _, _, _| Ok(ProposalStatus::Inactive));
+        bridge_instance.expect_is_registered_relayer().returning(|| Ok(true));
+        bridge_instance.expect_is_paused().returning(|| Ok(false));
+        bridge_instance
+            .expect_vote_proposal()
+            .withf(|_, nonce, resource_id, _| *nonce == 1 && resource_id[..] == [1; 32])
+            .times(1)
+            .returning(|_, _, _, _| Ok(()));
+
+        let relayer =
+            EthereumRelayer::new("test".to_string(), "0x".to_string(), bridge_instance, "0100000000".to_string())
+                .await
+                .unwrap()
+                .with_resource_allowlist(ResourceAllowlist::new(vec![[1; 32]]));
+
+        assert!(relayer
+            .relay(&relay_request(100, 1, [1; 32], Recipient::Evm([1; 20])))
+            .await
+            .is_ok());
+        let result = relayer.relay(&relay_request(100, 2, [2; 32], Recipient::Evm([1; 20]))).await;
+        assert!(matches!(result, Err(RelayError::Filtered)));
+    }
+
+    #[tokio::test]
+    pub async fn should_relay_any_resource_without_allowlist() {
+        let mut bridge_instance = MockBridgeInstance::new();
+        bridge_instance.expect_get_balance().returning(|| Ok(U256::from(1)));
+        bridge_instance
+            .expect_get_proposal_status()
+            .returning(|_, _, _, _| Ok(ProposalStatus::Inactive));
+        bridge_instance.expect_is_registered_relayer().returning(|| Ok(true));
+        bridge_instance.expect_is_paused().returning(|| Ok(false));
+        bridge_instance.expect_vote_proposal().times(2).returning(|_, _, _, _| Ok(()));
+
+        let relayer =
+            EthereumRelayer::new("test".to_string(), "0x".to_string(), bridge_instance, "0100000000".to_string())
+                .await
+                .unwrap()
+                .with_resource_allowlist(ResourceAllowlist::new(vec![]));
+
+        assert!(relayer
+            .relay(&relay_request(100, 1, [1; 32], Recipient::Evm([1; 20])))
+            .await
+            .is_ok());
+        assert!(relayer
+            .relay(&relay_request(100, 2, [2; 32], Recipient::Evm([1; 20])))
+            .await
+            .is_ok());
+    }
+
+    #[tokio::test]
+    pub async fn should_not_vote_on_passed_or_executed_proposal() {
+        for status in [ProposalStatus::Passed, ProposalStatus::Executed] {
+            let mut bridge_instance = MockBridgeInstance::new();
+            bridge_instance.expect_get_balance().returning(|| Ok(U256::from(1)));
+            bridge_instance.expect_is_registered_relayer().returning(|| Ok(true));
+            bridge_instance.expect_is_paused().returning(|| Ok(false));
+            bridge_instance
+                .expect_get_proposal_status()
+                .withf(|domain_id, nonce, _, call_data| {
+                    *domain_id == 0 && *nonce == 1 && call_data[..] == encode_deposit_data(100, &[1; 20]).unwrap()
+                })
+                .returning(move |_, _, _, _| Ok(status));
+            bridge_instance.expect_vote_proposal().times(0);
+
+            let relayer =
+                EthereumRelayer::new("test".to_string(), "0x".to_string(), bridge_instance, "0100000000".to_string())
+                    .await
+                    .unwrap();
+
+            let result = relayer.relay(&relay_request(100, 1, [0; 32], Recipient::Evm([1; 20]))).await;
+            assert!(matches!(result, Err(RelayError::AlreadyRelayed)), "status: {:?}", status);
+        }
+    }
+
+    // proposal status is `Active` until the vote, then `after_vote`
+    fn voting_bridge_instance(after_vote: ProposalStatus) -> MockBridgeInstance {
+        let voted = Arc::new(AtomicBool::new(false));
+        let mut bridge_instance = MockBridgeInstance::new();
+        bridge_instance.expect_get_balance().returning(|| Ok(U256::from(1)));
+        bridge_instance.expect_is_registered_relayer().returning(|| Ok(true));
+        bridge_instance.expect_is_paused().returning(|| Ok(false));
+        bridge_instance.expect_get_proposal_status().returning({
+            let voted = voted.clone();
+            move |_, _, _, _| Ok(if voted.load(Ordering::SeqCst) { after_vote } else { ProposalStatus::Active })
+        });
+        bridge_instance.expect_vote_proposal().times(1).returning(move |_, _, _, _| {
+            voted.store(true, Ordering::SeqCst);
+            Ok(())
+        });
+        bridge_instance
+    }
+
+    #[tokio::test]
+    pub async fn should_execute_proposal_passed_by_vote() {
+        let mut bridge_instance = voting_bridge_instance(ProposalStatus::Passed);
+        bridge_instance
+            .expect_execute_proposal()
+            .withf(|domain_id, nonce, call_data, resource_id| {
+                *domain_id == 0
+                    && *nonce == 1
+                    && call_data[..] == encode_deposit_data(100, &[1; 20]).unwrap()
+                    && resource_id.0 == [2; 32]
+            })
+            .times(1)
+            .returning(|_, _, _, _| Ok(()));
+
+        let relayer =
+            EthereumRelayer::new("test".to_string(), "0x".to_string(), bridge_instance, "0100000000".to_string())
+                .await
+                .unwrap()
+                .with_auto_execute(true);
+
+        assert!(relayer
+            .relay(&relay_request(100, 1, [2; 32], Recipient::Evm([1; 20])))
+            .await
+            .is_ok());
+    }
+
+    #[tokio::test]
+    pub async fn should_not_execute_proposal_executed_by_vote() {
+        let mut bridge_instance = voting_bridge_instance(ProposalStatus::Executed);
+        bridge_instance.expect_execute_proposal().times(0);
+
+        let relayer =
+            EthereumRelayer::new("test".to_string(), "0x".to_string(), bridge_instance, "0100000000".to_string())
+                .await
+                .unwrap()
+                .with_auto_execute(true);
+
+        assert!(relayer
+            .relay(&relay_request(100, 1, [2; 32], Recipient::Evm([1; 20])))
+            .await
+            .is_ok());
+    }
+
+    #[tokio::test]
+    pub async fn should_succeed_if_proposal_is_executed_by_another_relayer_first() {
+        // 0 - before vote, 1 - passed by the vote, 2 - executed by another relayer
+        let stage = Arc::new(AtomicU64::new(0));
+        let mut bridge_instance = MockBridgeInstance::new();
+        bridge_instance.expect_get_balance().returning(|| Ok(U256::from(1)));
+        bridge_instance.expect_is_registered_relayer().returning(|| Ok(true));
+        bridge_instance.expect_is_paused().returning(|| Ok(false));
+        bridge_instance.expect_get_proposal_status().returning({
+            let stage = stage.clone();
+            move |_, _, _, _| {
+                Ok(match stage.load(Ordering::SeqCst) {
+                    0 => ProposalStatus::Active,
+                    1 => ProposalStatus::Passed,
+                    _ => ProposalStatus::Executed,
+                })
+            }
+        });
+        bridge_instance.expect_vote_proposal().times(1).returning({
+            let stage = stage.clone();
+            move |_, _, _, _| {
+                stage.store(1, Ordering::SeqCst);
+                Ok(())
+            }
+        });
+        // execution reverts as the proposal got executed in the meantime
+        bridge_instance.expect_execute_proposal().times(1).returning(move |_, _, _, _| {
+            stage.store(2, Ordering::SeqCst);
+            Err(RelayError::AlreadyRelayed)
+        });
+
+        let relayer =
+            EthereumRelayer::new("test".to_string(), "0x".to_string(), bridge_instance, "0100000000".to_string())
+                .await
+                .unwrap()
+                .with_auto_execute(true);
+
+        assert!(relayer
+            .relay(&relay_request(100, 1, [2; 32], Recipient::Evm([1; 20])))
+            .await
+            .is_ok());
+    }
+
+    #[tokio::test]
+    pub async fn should_execute_passed_proposal_without_voting() {
+        let mut bridge_instance = MockBridgeInstance::new();
+        bridge_instance.expect_get_balance().returning(|| Ok(U256::from(1)));
+        bridge_instance.expect_is_registered_relayer().returning(|| Ok(true));
+        bridge_instance.expect_is_paused().returning(|| Ok(false));
+        bridge_instance
+            .expect_get_proposal_status()
+            .returning(|_, _, _, _| Ok(ProposalStatus::Passed));
+        bridge_instance.expect_vote_proposal().times(0);
+        bridge_instance
+            .expect_execute_proposal()
+            .times(1)
+            .returning(|_, _, _, _| Ok(()));
+
+        let relayer =
+            EthereumRelayer::new("test".to_string(), "0x".to_string(), bridge_instance, "0100000000".to_string())
+                .await
+                .unwrap()
+                .with_auto_execute(true);
+
+        assert!(relayer
+            .relay(&relay_request(100, 1, [2; 32], Recipient::Evm([1; 20])))
+            .await
+            .is_ok());
+    }
+
+    #[tokio::test]
+    pub async fn should_fail_if_execution_reverts_without_being_executed() {
+        let mut bridge_instance = voting_bridge_instance(ProposalStatus::Passed);
+        bridge_instance
+            .expect_execute_proposal()
+            .times(1)
+            .returning(|_, _, _, _| Err(RelayError::AlreadyRelayed));
+
+        let relayer =
+            EthereumRelayer::new("test".to_string(), "0x".to_string(), bridge_instance, "0100000000".to_string())
+                .await
+                .unwrap()
+                .with_auto_execute(true);
+
+        let result = relayer.relay(&relay_request(100, 1, [2; 32], Recipient::Evm([1; 20]))).await;
+        assert!(matches!(result, Err(RelayError::Other)));
+    }
+
+    #[tokio::test]
+    pub async fn should_not_execute_passed_proposal_without_auto_execute() {
+        let mut bridge_instance = voting_bridge_instance(ProposalStatus::Passed);
+        bridge_instance.expect_execute_proposal().times(0);
+
+        let relayer =
+            EthereumRelayer::new("test".to_string(), "0x".to_string(), bridge_instance, "0100000000".to_string())
+                .await
+                .unwrap();
+
+        assert!(relayer
+            .relay(&relay_request(100, 1, [2; 32], Recipient::Evm([1; 20])))
+            .await
+            .is_ok());
+    }
+
+    #[tokio::test]
+    pub async fn should_vote_if_proposal_status_is_unknown() {
+        let mut bridge_instance = MockBridgeInstance::new();
+        bridge_instance.expect_get_balance().returning(|| Ok(U256::from(1)));
+        bridge_instance.expect_is_registered_relayer().returning(|| Ok(true));
+        bridge_instance.expect_is_paused().returning(|| Ok(false));
+        bridge_instance.expect_get_proposal_status().returning(|_, _, _, _| Err(()));
+        bridge_instance.expect_vote_proposal().times(1).returning(|_, _, _, _| Ok(()));
+
+        let relayer =
+            EthereumRelayer::new("test".to_string(), "0x".to_string(), bridge_instance, "0100000000".to_string())
+                .await
+                .unwrap();
+
+        assert!(relayer
+            .relay(&relay_request(100, 1, [0; 32], Recipient::Evm([1; 20])))
+            .await
+            .is_ok());
+    }
+
+    #[test]
+    pub fn proposal_status_is_decoded_from_contract_enum() {
+        assert_eq!(ProposalStatus::try_from(2), Ok(ProposalStatus::Passed));
+        assert_eq!(ProposalStatus::try_from(4), Ok(ProposalStatus::Cancelled));
+        assert_eq!(ProposalStatus::try_from(5), Err(()));
+    }
+
+    #[test]
+    pub fn destination_allowlist_is_parsed_from_config() {
+        let config = |allowed_destinations: serde_json::Value| -> RelayerConfig {
+            serde_json::from_value(serde_json::json!({
+                "node_rpc_url": "http://localhost:8545",
+                "bridge_contract_address": "0x5FbDB2315678afecb367f032d93F642f64180aa3",
+                "allowed_destinations": allowed_destinations,
+            }))
+            .unwrap()
+        };
+
+        let allowlist = config(serde_json::json!(["0x70997970C51812dc3A010C7d01b50e0d17dc79C8"]))
+            .destination_allowlist()
+            .unwrap();
+        assert!(allowlist.is_allowed(&alloy::hex::decode("70997970C51812dc3A010C7d01b50e0d17dc79C8").unwrap()));
+        assert!(!allowlist.is_allowed(&[1; 20]));
+
+        assert!(config(serde_json::Value::Null)
+            .destination_allowlist()
+            .unwrap()
+            .is_allowed(&[1; 20]));
+        assert!(config(serde_json::json!(["0xzz"])).destination_allowlist().is_err());
+    }
+
+    #[test]
+    pub fn resource_allowlist_is_parsed_from_config() {
+        let config = |allowed_resource_ids: serde_json::Value| -> RelayerConfig {
+            let mut config = serde_json::json!({
+                "node_rpc_url": "http://localhost:8545",
+                "bridge_contract_address": "0x5FbDB2315678afecb367f032d93F642f64180aa3",
+            });
+            if !allowed_resource_ids.is_null() {
+                config["allowed_resource_ids"] = allowed_resource_ids;
+            }
+            serde_json::from_value(config).unwrap()
+        };
+
+        let allowlist = config(serde_json::json!([format!("0x{}", alloy::hex::encode([1; 32]))]))
+            .resource_allowlist()
+            .unwrap();
+        assert!(allowlist.is_allowed(&[1; 32]));
+        assert!(!allowlist.is_allowed(&[2; 32]));
+
+        assert!(config(serde_json::Value::Null)
+            .resource_allowlist()
+            .unwrap()
+            .is_allowed(&[2; 32]));
+        assert!(config(serde_json::json!(["0xzz"])).resource_allowlist().is_err());
+    }
+
+    #[test]
+    pub fn json_keystore_takes_precedence_over_raw_key_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let keystore_dir = dir.path().to_str().unwrap();
+
+        assert_eq!(key_store_path(keystore_dir, "sepolia"), format!("{}/sepolia.bin", keystore_dir));
+
+        std::fs::write(dir.path().join("sepolia.json"), "{}").unwrap();
+        assert_eq!(key_store_path(keystore_dir, "sepolia"), format!("{}/sepolia.json", keystore_dir));
+        assert_eq!(key_store_path(keystore_dir, "holesky"), format!("{}/holesky.bin", keystore_dir));
+    }
+
+    #[tokio::test(start_paused = true)]
+    pub async fn should_return_transport_error_if_relay_times_out() {
+        let relayer =
+            EthereumRelayer::new("test".to_string(), "0x".to_string(), HangingBridgeInstance, "0100000000".to_string())
+                .await
+                .unwrap()
+                .with_relay_timeout(Duration::from_secs(5));
+
+        let result = relayer.relay(&relay_request(100, 1, [0; 32], Recipient::Evm([0; 20]))).await;
+        assert!(matches!(result, Err(RelayError::TransportError)));
+    }
+
+    #[tokio::test]
+    pub async fn should_not_create_relayer_if_not_registered() {
+        let mut bridge_instance = MockBridgeInstance::new();
+        bridge_instance.expect_get_balance().returning(|| Ok(U256::from(1)));
+        bridge_instance.expect_is_registered_relayer().returning(|| Ok(false));
+
+        let result =
+            EthereumRelayer::new("test".to_string(), "0x".to_string(), bridge_instance, "0100000000".to_string()).await;
+        assert!(result.is_err());
+    }
+
+    #[tokio::test(start_paused = true)]
+    pub async fn should_return_other_error_if_relayer_role_revoked() {
+        let registered = Arc::new(AtomicBool::new(true));
+        let registered_clone = registered.clone();
+        let mut bridge_instance = MockBridgeInstance::new();
+        bridge_instance.expect_get_balance().returning(|| Ok(U256::from(1)));
+        bridge_instance
+            .expect_get_proposal_status()
+            .returning(|_, _, _, _| Ok(ProposalStatus::Inactive));
+        bridge_instance
+            .expect_is_registered_relayer()
+            .returning(move || Ok(registered_clone.load(Ordering::SeqCst)));
+        bridge_instance.expect_is_paused().returning(|| Ok(false));
+        bridge_instance.expect_vote_proposal().times(1).returning(|_, _, _, _| Ok(()));
+
+        let (notifier, client) = recording_notifier();
+
+        let relayer =
+            EthereumRelayer::new("test".to_string(), "0x".to_string(), bridge_instance, "0100000000".to_string())
+                .await
+                .unwrap()
+                .with_notifier(notifier);
+
+        assert!(relayer
+            .relay(&relay_request(100, 1, [0; 32], Recipient::Evm([0; 20])))
+            .await
+            .is_ok());
+
+        registered.store(false, Ordering::SeqCst);
+        tokio::time::advance(ROLE_CHECK_INTERVAL).await;
+
+        let result = relayer.relay(&relay_request(100, 2, [0; 32], Recipient::Evm([0; 20]))).await;
+        assert!(matches!(result, Err(RelayError::Other)));
+        tokio::time::sleep(Duration::from_secs(1)).await;
+        let payloads = client.payloads.lock().unwrap().clone();
+        assert_eq!(payloads.len(), 1);
+        assert!(payloads[0].contains(r#""event_type":"relayer_not_registered""#));
+    }
+
+    fn destination_paused_gauge(recorder: &DebuggingRecorder) -> Option<f64> {
+        recorder
+            .snapshotter()
+            .snapshot()
+            .into_vec()
+            .into_iter()
+            .find_map(|(key, _, _, value)| match value {
+                DebugValue::Gauge(value) if key.key().name() == DESTINATION_PAUSED_GAUGE => Some(value.into_inner()),
+                _ => None,
+            })
+    }
+
+    #[tokio::test(start_paused = true)]
+    pub async fn should_return_paused_error_while_bridge_is_paused() {
+        let paused = Arc::new(AtomicBool::new(false));
+        let paused_clone = paused.clone();
+        let mut bridge_instance = MockBridgeInstance::new();
+        bridge_instance.expect_get_balance().returning(|| Ok(U256::from(1)));
+        bridge_instance
+            .expect_get_proposal_status()
+            .returning(|_, _, _, _| Ok(ProposalStatus::Inactive));
+        bridge_instance.expect_is_registered_relayer().returning(|| Ok(true));
+        bridge_instance
+            .expect_is_paused()
+            .times(3)
+            .returning(move || Ok(paused_clone.load(Ordering::SeqCst)));
+        bridge_instance.expect_vote_proposal().times(3).returning(|_, _, _, _| Ok(()));
+
+        let recorder = DebuggingRecorder::new();
+        let _guard = metrics::set_default_local_recorder(&recorder);
+        let relayer =
+            EthereumRelayer::new("test".to_string(), "0x".to_string(), bridge_instance, "0100000000".to_string())
+                .await
+                .unwrap();
+
+        assert!(relayer
+            .relay(&relay_request(100, 1, [0; 32], Recipient::Evm([0; 20])))
+            .await
+            .is_ok());
+        assert_eq!(destination_paused_gauge(&recorder), Some(0.0));
+
+        paused.store(true, Ordering::SeqCst);
+        // paused state is cached until the next check
+        assert!(relayer
+            .relay(&relay_request(100, 2, [0; 32], Recipient::Evm([0; 20])))
+            .await
+            .is_ok());
+        tokio::time::advance(PAUSE_CHECK_INTERVAL).await;
+
+        let result = relayer.relay(&relay_request(100, 3, [0; 32], Recipient::Evm([0; 20]))).await;
+        assert!(matches!(result, Err(RelayError::Paused)));
+        assert_eq!(destination_paused_gauge(&recorder), Some(1.0));
+
+        paused.store(false, Ordering::SeqCst);
+        let result = relayer.relay(&relay_request(100, 3, [0; 32], Recipient::Evm([0; 20]))).await;
+        assert!(matches!(result, Err(RelayError::Paused)));
+        tokio::time::advance(PAUSE_CHECK_INTERVAL).await;
+
+        // relaying resumes once unpause is seen
+        assert!(relayer
+            .relay(&relay_request(100, 3, [0; 32], Recipient::Evm([0; 20])))
+            .await
+            .is_ok());
+        assert_eq!(destination_paused_gauge(&recorder), Some(0.0));
+    }
+
+    #[tokio::test]
+    pub async fn should_not_create_relayer_connected_to_unexpected_chain() {
+        let mut bridge_instance = MockBridgeInstance::new();
+        bridge_instance.expect_get_balance().returning(|| Ok(U256::from(1)));
+        bridge_instance.expect_is_registered_relayer().returning(|| Ok(true));
+        bridge_instance.expect_get_chain_id().returning(|| Ok(11155111));
+        bridge_instance.expect_vote_proposal().times(0);
+
+        let recorder = DebuggingRecorder::new();
+        let _guard = metrics::set_default_local_recorder(&recorder);
+        let result =
+            EthereumRelayer::new("test".to_string(), "0x".to_string(), bridge_instance, "0100000000".to_string())
+                .await
+                .unwrap()
+                .with_expected_chain_id(Some(1))
+                .await;
+
+        assert!(result.is_err());
+        let mismatch = recorder
+            .snapshotter()
+            .snapshot()
+            .into_vec()
+            .into_iter()
+            .find_map(|(key, _, _, value)| match value {
+                DebugValue::Gauge(value) if key.key().name() == RELAYER_CHAIN_ID_MISMATCH_GAUGE => {
+                    Some(value.into_inner())
+                },
+                _ => None,
+            });
+        assert_eq!(mismatch, Some(1.0));
+    }
+
+    #[tokio::test(start_paused = true)]
+    pub async fn should_refuse_to_relay_once_node_reports_unexpected_chain() {
+        let chain_id = Arc::new(AtomicU64::new(1));
+        let chain_id_clone = chain_id.clone();
+        let mut bridge_instance = MockBridgeInstance::new();
+        bridge_instance.expect_get_balance().returning(|| Ok(U256::from(1)));
+        bridge_instance
+            .expect_get_proposal_status()
+            .returning(|_, _, _, _| Ok(ProposalStatus::Inactive));
+        bridge_instance.expect_is_registered_relayer().returning(|| Ok(true));
+        bridge_instance.expect_is_paused().returning(|| Ok(false));
+        bridge_instance
+            .expect_get_chain_id()
+            .returning(move || Ok(chain_id_clone.load(Ordering::SeqCst)));
+        bridge_instance.expect_vote_proposal().times(1).returning(|_, _, _, _| Ok(()));
+
+        let relayer =
+            EthereumRelayer::new("test".to_string(), "0x".to_string(), bridge_instance, "0100000000".to_string())
+                .await
+                .unwrap()
+                .with_expected_chain_id(Some(1))
+                .await
+                .unwrap();
+
+        assert!(relayer
+            .relay(&relay_request(100, 1, [0; 32], Recipient::Evm([0; 20])))
+            .await
+            .is_ok());
+
+        chain_id.store(11155111, Ordering::SeqCst);
+        tokio::time::advance(CHAIN_ID_CHECK_INTERVAL).await;
+
+        let result = relayer.relay(&relay_request(100, 2, [0; 32], Recipient::Evm([0; 20]))).await;
+        assert!(matches!(result, Err(RelayError::Other)));
+    }
+
+    #[tokio::test]
+    pub async fn vote_proposal_should_return_transport_error_if_node_unreachable() {
+        let bridge_instance = prepare_bridge_instance(
             PrivateKeySigner::random(),
             "http://localhost:8545",
+            None,
             "0x5FbDB2315678afecb367f032d93F642f64180aa3",
-        );
-        let wrapper = BridgeContractWrapper { instance: bridge_instance };
+            None,
+        )
+        .unwrap();
+        let wrapper = BridgeContractWrapper { instance: bridge_instance, watch_timeout: DEFAULT_WATCH_TIMEOUT };
         let result = wrapper
             .vote_proposal(0, 1, FixedBytes::from_slice(&[0u8; 32]), Bytes::from(vec![]))
             .await;
         assert!(matches!(result, Err(RelayError::TransportError)));
     }
+
+    fn error_resp(code: i64, message: &'static str) -> alloy::transports::TransportError {
+        RpcError::ErrorResp(ErrorPayload { code, message: message.into(), data: None })
+    }
+
+    #[tokio::test]
+    pub async fn watch_errors_are_retried_as_watch_error_unless_node_is_unreachable() {
+        let (sender, receiver) = tokio::sync::oneshot::channel::<()>();
+        drop(sender);
+        let recv_error = receiver.await.unwrap_err();
+
+        let cases = vec![
+            (PendingTransactionError::TransportError(TransportErrorKind::backend_gone()), RelayError::TransportError),
+            (PendingTransactionError::TransportError(error_resp(-32000, "header not found")), RelayError::WatchError),
+            (PendingTransactionError::TxWatcher(WatchTxError::Timeout), RelayError::WatchError),
+            (PendingTransactionError::FailedToRegister, RelayError::WatchError),
+            (PendingTransactionError::Recv(recv_error), RelayError::WatchError),
+        ];
+
+        for (error, expected) in cases {
+            let description = format!("{:?}", error);
+            let mapped = watch_error_to_relay_error(error);
+            assert_eq!(format!("{:?}", mapped), format!("{:?}", expected), "{}", description);
+        }
+    }
+
+    #[test]
+    pub fn send_errors_are_mapped_to_relay_errors() {
+        let cases = vec![
+            (alloy::contract::Error::TransportError(TransportErrorKind::backend_gone()), RelayError::TransportError),
+            (alloy::contract::Error::TransportError(error_resp(3, "execution reverted")), RelayError::AlreadyRelayed),
+            (
+                alloy::contract::Error::TransportError(error_resp(3, "execution reverted: Pausable: paused")),
+                RelayError::Paused,
+            ),
+            (
+                alloy::contract::Error::TransportError(error_resp(-32000, "replacement transaction underpriced")),
+                RelayError::WatchError,
+            ),
+            (alloy::contract::Error::TransportError(error_resp(-32000, "insufficient funds")), RelayError::Other),
+            (alloy::contract::Error::UnknownFunction("voteProposal".to_string()), RelayError::Other),
+        ];
+
+        for (error, expected) in cases {
+            let description = format!("{:?}", error);
+            let mapped = send_error_to_relay_error(error);
+            assert_eq!(format!("{:?}", mapped), format!("{:?}", expected), "{}", description);
+        }
+    }
 }
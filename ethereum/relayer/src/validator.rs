@@ -0,0 +1,98 @@
+// Copyright 2020-2024 Trust Computing GmbH.
+// This file is part of Litentry.
+//
+// Litentry is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// Litentry is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with Litentry.  If not, see <https://www.gnu.org/licenses/>.
+
+use alloy::primitives::Address;
+use bridge_core::validation::{ValidationError, Validator};
+use std::collections::HashSet;
+use std::str::FromStr;
+
+const ADDRESS_LEN: usize = 20;
+
+/// Validates that bridge request's recipient is a plausible EVM address.
+pub struct EthereumDestinationValidator {
+    denylist: HashSet<Address>,
+}
+
+impl EthereumDestinationValidator {
+    /// `denylist_addresses` are hex encoded addresses which must never receive funds, e.g. bridge or token contracts
+    #[allow(clippy::result_unit_err)]
+    pub fn new(denylist_addresses: &[String]) -> Result<Self, ()> {
+        let mut denylist = HashSet::new();
+        for address in denylist_addresses {
+            let address = Address::from_str(address)
+                .map_err(|e| log::error!("Invalid address in denylist: {}, {:?}", address, e))?;
+            denylist.insert(address);
+        }
+        Ok(Self { denylist })
+    }
+}
+
+impl Validator for EthereumDestinationValidator {
+    fn validate(&self, _amount: u128, _resource_id: &[u8; 32], data: &[u8]) -> Result<(), ValidationError> {
+        if data.len() != ADDRESS_LEN {
+            return Err(ValidationError::InvalidRecipientLength(data.len()));
+        }
+        let recipient = Address::from_slice(data);
+        if recipient.is_zero() {
+            return Err(ValidationError::ZeroRecipient);
+        }
+        if self.denylist.contains(&recipient) {
+            return Err(ValidationError::DeniedRecipient(recipient.to_string()));
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const BRIDGE_ADDRESS: &str = "0x5FbDB2315678afecb367f032d93F642f64180aa3";
+
+    fn validator() -> EthereumDestinationValidator {
+        EthereumDestinationValidator::new(&[BRIDGE_ADDRESS.to_string()]).unwrap()
+    }
+
+    #[test]
+    fn valid_recipient_passes_validation() {
+        assert_eq!(validator().validate(100, &[0; 32], &[1; 20]), Ok(()));
+    }
+
+    #[test]
+    fn recipient_of_invalid_length_is_rejected() {
+        assert_eq!(validator().validate(100, &[0; 32], &[1; 32]), Err(ValidationError::InvalidRecipientLength(32)));
+        assert_eq!(validator().validate(100, &[0; 32], &[]), Err(ValidationError::InvalidRecipientLength(0)));
+    }
+
+    #[test]
+    fn zero_recipient_is_rejected() {
+        assert_eq!(validator().validate(100, &[0; 32], &[0; 20]), Err(ValidationError::ZeroRecipient));
+    }
+
+    #[test]
+    fn denied_recipient_is_rejected() {
+        let recipient = Address::from_str(BRIDGE_ADDRESS).unwrap();
+        assert_eq!(
+            validator().validate(100, &[0; 32], recipient.as_slice()),
+            Err(ValidationError::DeniedRecipient(recipient.to_string()))
+        );
+    }
+
+    #[test]
+    fn invalid_denylist_address_fails_creation() {
+        assert!(EthereumDestinationValidator::new(&["0x1234".to_string()]).is_err());
+    }
+}
@@ -34,7 +34,7 @@ echo "wait for 18s ..."
 sleep 18
 
 echo "check if the bridge was ok ..."
-r=$($CLI ethereum balance --account 0x70997970C51812dc3A010C7d01b50e0d17dc79C8)
+r=$($CLI ethereum balance --raw --account 0x70997970C51812dc3A010C7d01b50e0d17dc79C8)
 if [ $r = "100000000000000000000" ]; then
   echo "balance ok"
 else
@@ -50,7 +50,7 @@ echo "wait for 18s ..."
 sleep 18
 
 echo "check if the bridge was ok ..."
-r=$($CLI ethereum balance --account 0x70997970C51812dc3A010C7d01b50e0d17dc79C8 --port 8546)
+r=$($CLI ethereum balance --raw --account 0x70997970C51812dc3A010C7d01b50e0d17dc79C8 --port 8546)
 if [ $r = "100000000000000000000" ]; then
   echo "balance ok"
 else
@@ -14,16 +14,25 @@
 // You should have received a copy of the GNU General Public License
 // along with Litentry.  If not, see <https://www.gnu.org/licenses/>.
 
+use crate::litentry_rococo::omni_bridge::calls::types::PayIn;
+use crate::litentry_rococo::omni_bridge::events::PaidIn;
 use crate::litentry_rococo::omni_bridge::Call;
+use crate::litentry_rococo::runtime_types::frame_support::traits::tokens::fungible::union_of::NativeOrWithId;
 use crate::litentry_rococo::system::events::ExtrinsicFailed;
 use crate::litentry_rococo::DispatchError;
-use clap::{Args, Subcommand};
+use clap::{Args, FromArgMatches, Subcommand};
 use hex::FromHex;
 use log::info;
+use serde::Serialize;
+use std::fmt::{Display, Formatter};
 use std::str::FromStr;
+use subxt::blocks::ExtrinsicEvents;
+use subxt::events::{Events, Phase};
+use subxt::ext::subxt_core::tx::payload::StaticPayload;
+use subxt::tx::Payload;
 use subxt::utils::AccountId32;
 use subxt::{OnlineClient, PolkadotConfig};
-use subxt_signer::sr25519::dev;
+use subxt_signer::sr25519::{dev, Keypair};
 
 #[subxt::subxt(runtime_metadata_path = "../artifacts/local.scale")]
 pub mod litentry_rococo {}
@@ -34,22 +43,70 @@ pub enum SubstrateCommand {
     PayIn(PayInConf),
     Balance(BalanceConf),
     FailedBridgeTx,
+    BridgeInfo,
 }
 
 #[derive(Args)]
 pub struct SetupBridgeConf {
     #[arg(long, default_value = "5GrwvaEF5zXb26Fz9rcQpDWS57CtERHpNehXCPcNoHGKutQY")]
     relayer_account: String,
+    // asset mapped to the resource id and paired with destination chains
+    #[arg(long, default_value = "native")]
+    asset: Asset,
+    #[arg(long, default_value = "9ee6dfb61a2fb903df487c401663825643bb825d41695e63df8af6162ab145a6")]
+    resource_id: String,
 }
 
 #[derive(Args)]
 pub struct PayInConf {
     #[arg(long, default_value = "70997970C51812dc3A010C7d01b50e0d17dc79C8")]
-    dest_address: String,
+    pub dest_address: String,
     #[arg(long, default_value = "100000000000000000000")] // 100 LIT
-    amount: u128,
+    pub amount: u128,
     #[arg(long, default_value = "0")] // ethereum main network
-    ethereum_id: u32,
+    pub ethereum_id: u32,
+    #[arg(long, default_value = "native")]
+    pub asset: Asset,
+}
+
+impl Default for PayInConf {
+    // defaults declared on the args
+    fn default() -> Self {
+        let matches = Self::augment_args(clap::Command::new("pay-in")).get_matches_from(["pay-in"]);
+        Self::from_arg_matches(&matches).expect("defaults are valid; qed")
+    }
+}
+
+/// Asset of pay in pair, `native` or `with_id:<asset id>` of an asset registered in the assets pallet
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Asset {
+    Native,
+    WithId(u32),
+}
+
+impl FromStr for Asset {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.split_once(':') {
+            None if s == "native" => Ok(Self::Native),
+            Some(("with_id", id)) => id
+                .parse()
+                .map(Self::WithId)
+                .map_err(|e| format!("invalid asset id {}: {}", id, e)),
+            _ => Err(format!("expected native or with_id:<u32>, got {}", s)),
+        }
+    }
+}
+
+impl Asset {
+    // asset id type is inferred from the call being built
+    fn runtime_asset<Id: From<u32>>(self) -> NativeOrWithId<Id> {
+        match self {
+            Self::Native => NativeOrWithId::Native,
+            Self::WithId(id) => NativeOrWithId::WithId(id.into()),
+        }
+    }
 }
 
 #[derive(Args)]
@@ -58,159 +115,168 @@ pub struct BalanceConf {
     account: String,
 }
 
-pub async fn handle(command: &SubstrateCommand) {
+#[derive(Debug, thiserror::Error)]
+pub enum CliError {
+    #[error("Invalid {0}: {1}")]
+    InvalidArgument(&'static str, String),
+    #[error("Rpc error: {0}")]
+    Rpc(String),
+    #[error("Extrinsic failed: {0}")]
+    ExtrinsicFailed(String),
+    #[error("PaidIn event not found in extrinsic {0}")]
+    MissingPaidInEvent(String),
+}
+
+#[derive(Debug, PartialEq, Serialize)]
+pub struct TxOutput {
+    pub hash: String,
+    pub block_hash: String,
+    pub block_number: u64,
+}
+
+/// Relayer quorum configuration of the omni-bridge pallet
+#[derive(Debug, PartialEq, Serialize)]
+pub struct BridgeInfo {
+    // not set until `set_relayer_threshold` is called
+    pub relayer_threshold: Option<u64>,
+    pub relayer_count: u64,
+}
+
+/// Result of a command, printed as text or JSON.
+#[derive(Debug, Default, Serialize)]
+pub struct CommandOutput {
+    pub extrinsics: Vec<TxOutput>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub pay_in_nonce: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub balance: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub failed_bridge_tx_count: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub bridge_info: Option<BridgeInfo>,
+}
+
+impl Display for CommandOutput {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        for extrinsic in self.extrinsics.iter() {
+            writeln!(
+                f,
+                "Extrinsic {} finalized in block {} ({})",
+                extrinsic.hash, extrinsic.block_number, extrinsic.block_hash
+            )?;
+        }
+        if let Some(pay_in_nonce) = self.pay_in_nonce {
+            writeln!(f, "Pay in nonce: {}", pay_in_nonce)?;
+        }
+        if let Some(ref bridge_info) = self.bridge_info {
+            match bridge_info.relayer_threshold {
+                Some(relayer_threshold) => writeln!(f, "Relayer threshold: {}", relayer_threshold)?,
+                None => writeln!(f, "Relayer threshold: not set")?,
+            }
+            writeln!(f, "Relayers: {}", bridge_info.relayer_count)?;
+        }
+        // printed alone, scripts compare them directly
+        if let Some(ref balance) = self.balance {
+            write!(f, "{}", balance)?;
+        }
+        if let Some(failed_bridge_tx_count) = self.failed_bridge_tx_count {
+            write!(f, "{}", failed_bridge_tx_count)?;
+        }
+        Ok(())
+    }
+}
+
+pub async fn handle(command: &SubstrateCommand) -> Result<CommandOutput, CliError> {
     let rpc_url = "ws://localhost:9944";
     let alice_signer = dev::alice();
 
-    let api = OnlineClient::<PolkadotConfig>::from_insecure_url(rpc_url).await.unwrap();
+    let api = connect(rpc_url).await?;
 
     match command {
         SubstrateCommand::SetupBridge(conf) => {
+            let mut output = CommandOutput::default();
             if conf.relayer_account.as_str() != "5GrwvaEF5zXb26Fz9rcQpDWS57CtERHpNehXCPcNoHGKutQY" {
                 let add_relayer_call =
                     crate::litentry_rococo::runtime_types::paseo_runtime::RuntimeCall::OmniBridge(Call::add_relayer {
-                        who: AccountId32::from_str(&conf.relayer_account).unwrap(),
+                        who: AccountId32::from_str(&conf.relayer_account)
+                            .map_err(|e| CliError::InvalidArgument("relayer account", format!("{:?}", e)))?,
                     });
 
                 let add_relayer_sudo_call = litentry_rococo::tx().sudo().sudo(add_relayer_call);
 
                 info!("Adding Relayer to the OmniBridge Pallet");
-                let hash = api
-                    .tx()
-                    .sign_and_submit_then_watch(&add_relayer_sudo_call, &alice_signer, Default::default())
-                    .await
-                    .unwrap();
-
-                hash.wait_for_finalized().await.unwrap();
+                let (extrinsic, _) = submit(&api, &add_relayer_sudo_call, &alice_signer).await?;
+                output.extrinsics.push(extrinsic);
             }
 
+            let resource_id = <[u8; 32]>::from_hex(conf.resource_id.trim_start_matches("0x"))
+                .map_err(|e| CliError::InvalidArgument("resource id", e.to_string()))?;
             let chain_asset = litentry_rococo::runtime_types::pallet_omni_bridge::ChainAsset {
                 chain: crate::litentry_rococo::runtime_types::core_primitives::omni::chain::ChainType::Heima,
-                asset: litentry_rococo::runtime_types::frame_support::traits::tokens::fungible::union_of::NativeOrWithId::Native
+                asset: conf.asset.runtime_asset(),
             };
 
             info!("Setting ResourceId on OmniBridge Pallet");
-            let set_resource_id_call = litentry_rococo::tx().omni_bridge().set_resource_id(
-                [
-                    158, 230, 223, 182, 26, 47, 185, 3, 223, 72, 124, 64, 22, 99, 130, 86, 67, 187, 130, 93, 65, 105,
-                    94, 99, 223, 138, 246, 22, 42, 177, 69, 166,
-                ],
-                chain_asset,
-            );
-
-            let hash = api
-                .tx()
-                .sign_and_submit_then_watch(&set_resource_id_call, &alice_signer, Default::default())
-                .await
-                .unwrap();
+            let set_resource_id_call = litentry_rococo::tx().omni_bridge().set_resource_id(resource_id, chain_asset);
 
-            hash.wait_for_finalized().await.unwrap();
+            let (extrinsic, _) = submit(&api, &set_resource_id_call, &alice_signer).await?;
+            output.extrinsics.push(extrinsic);
 
-            let asset_kind = litentry_rococo::runtime_types::frame_support::traits::tokens::fungible::union_of::NativeOrWithId::Native;
+            let asset_kind = conf.asset.runtime_asset();
             let dest_chain =
                 crate::litentry_rococo::runtime_types::core_primitives::omni::chain::ChainType::Ethereum(0);
 
             info!("Adding pay in pair on OmniBridgePallet");
             let add_pay_in_pair_call = litentry_rococo::tx().omni_bridge().add_pay_in_pair(asset_kind, dest_chain);
 
-            let hash = api
-                .tx()
-                .sign_and_submit_then_watch(&add_pay_in_pair_call, &alice_signer, Default::default())
-                .await
-                .unwrap();
-
-            hash.wait_for_finalized().await.unwrap();
+            let (extrinsic, _) = submit(&api, &add_pay_in_pair_call, &alice_signer).await?;
+            output.extrinsics.push(extrinsic);
 
-            let asset_kind = litentry_rococo::runtime_types::frame_support::traits::tokens::fungible::union_of::NativeOrWithId::Native;
+            let asset_kind = conf.asset.runtime_asset();
             let dest_chain =
                 crate::litentry_rococo::runtime_types::core_primitives::omni::chain::ChainType::Ethereum(56);
 
             info!("Adding pay in pair on OmniBridgePallet");
             let add_pay_in_pair_call = litentry_rococo::tx().omni_bridge().add_pay_in_pair(asset_kind, dest_chain);
 
-            let hash = api
-                .tx()
-                .sign_and_submit_then_watch(&add_pay_in_pair_call, &alice_signer, Default::default())
-                .await
-                .unwrap();
-
-            hash.wait_for_finalized().await.unwrap();
+            let (extrinsic, _) = submit(&api, &add_pay_in_pair_call, &alice_signer).await?;
+            output.extrinsics.push(extrinsic);
 
-            let asset_kind = litentry_rococo::runtime_types::frame_support::traits::tokens::fungible::union_of::NativeOrWithId::Native;
+            let asset_kind = conf.asset.runtime_asset();
             let dest_chain =
                 crate::litentry_rococo::runtime_types::core_primitives::omni::chain::ChainType::Ethereum(0);
 
             // set pay in fee
             info!("Setting pay in fee on OmniBridgePallet");
             let set_pay_in_fee = litentry_rococo::tx().omni_bridge().set_pay_in_fee(asset_kind, dest_chain, 0);
-            let hash = api
-                .tx()
-                .sign_and_submit_then_watch(&set_pay_in_fee, &alice_signer, Default::default())
-                .await
-                .unwrap();
-
-            hash.wait_for_finalized().await.unwrap();
+            let (extrinsic, _) = submit(&api, &set_pay_in_fee, &alice_signer).await?;
+            output.extrinsics.push(extrinsic);
 
-            let asset_kind = litentry_rococo::runtime_types::frame_support::traits::tokens::fungible::union_of::NativeOrWithId::Native;
+            let asset_kind = conf.asset.runtime_asset();
             let dest_chain =
                 crate::litentry_rococo::runtime_types::core_primitives::omni::chain::ChainType::Ethereum(56);
 
             // set pay in fee
             info!("Setting pay in fee on OmniBridgePallet");
             let set_pay_in_fee = litentry_rococo::tx().omni_bridge().set_pay_in_fee(asset_kind, dest_chain, 0);
-            let hash = api
-                .tx()
-                .sign_and_submit_then_watch(&set_pay_in_fee, &alice_signer, Default::default())
-                .await
-                .unwrap();
-
-            hash.wait_for_finalized().await.unwrap();
+            let (extrinsic, _) = submit(&api, &set_pay_in_fee, &alice_signer).await?;
+            output.extrinsics.push(extrinsic);
+            Ok(output)
         },
         SubstrateCommand::Balance(conf) => {
             // Query the account balance from the chain's `Balances` storage
-            let account: AccountId32 = AccountId32::from_str(conf.account.as_str()).unwrap();
-
-            let balances_storage_query = litentry_rococo::storage().system().account(account);
-            let balances_details = api
-                .storage()
-                .at_latest()
-                .await
-                .unwrap()
-                .fetch(&balances_storage_query)
-                .await
-                .unwrap()
-                .ok_or("There is no account with existential deposit");
-
-            if let Ok(details) = balances_details {
-                let free_balance = details.data.free;
-                println!("{:?}", free_balance)
-            } else {
-                println!("0");
-            }
+            let account: AccountId32 = AccountId32::from_str(conf.account.as_str())
+                .map_err(|e| CliError::InvalidArgument("account", format!("{:?}", e)))?;
+            let free_balance = free_balance(&api, account).await?;
+            Ok(CommandOutput { balance: Some(free_balance.to_string()), ..Default::default() })
         },
         SubstrateCommand::PayIn(conf) => {
-            let recipient_address = Vec::<u8>::from_hex(conf.dest_address.as_str()).expect("Failed to decode string");
-
-            let request = litentry_rococo::runtime_types::pallet_omni_bridge::PayInRequest {
-                asset: litentry_rococo::runtime_types::frame_support::traits::tokens::fungible::union_of::NativeOrWithId::Native,
-                dest_chain: crate::litentry_rococo::runtime_types::core_primitives::omni::chain::ChainType::Ethereum(conf.ethereum_id),
-                dest_account: recipient_address,
-                amount: conf.amount,
-            };
-
-            let transfer_assets_call = litentry_rococo::tx().omni_bridge().pay_in(request);
-
-            let hash = api
-                .tx()
-                .sign_and_submit_then_watch(&transfer_assets_call, &alice_signer, Default::default())
-                .await
-                .unwrap();
-
-            hash.wait_for_finalized().await.unwrap();
+            let (extrinsic, pay_in_nonce) = pay_in(&api, conf, &alice_signer).await?;
+            Ok(CommandOutput { extrinsics: vec![extrinsic], pay_in_nonce: Some(pay_in_nonce), ..Default::default() })
         },
         SubstrateCommand::FailedBridgeTx => {
             // Get the current finalized block number
-            let latest_block = api.blocks().at_latest().await.unwrap();
+            let latest_block = api.blocks().at_latest().await.map_err(rpc_error)?;
             let mut current_block_hash = Some(latest_block.hash());
 
             let mut count = 0;
@@ -218,12 +284,12 @@ pub async fn handle(command: &SubstrateCommand) {
             // Scan the last 20 blocks for failed tx extrinsic events
             for _ in 0..20 {
                 if let Some(block_hash) = current_block_hash {
-                    let block = api.blocks().at(block_hash).await.unwrap();
+                    let block = api.blocks().at(block_hash).await.map_err(rpc_error)?;
 
                     // Fetch all events in the block
-                    let events = block.events().await.unwrap();
+                    let events = block.events().await.map_err(rpc_error)?;
                     for event in events.iter() {
-                        let details = event.unwrap();
+                        let details = event.map_err(rpc_error)?;
                         if let Ok(Some(ExtrinsicFailed { dispatch_error: DispatchError::Module(error), .. })) =
                             details.as_event::<ExtrinsicFailed>()
                         {
@@ -237,7 +303,262 @@ pub async fn handle(command: &SubstrateCommand) {
                     current_block_hash = Some(block.header().parent_hash);
                 }
             }
-            println!("{}", count);
+            Ok(CommandOutput { failed_bridge_tx_count: Some(count), ..Default::default() })
+        },
+        SubstrateCommand::BridgeInfo => {
+            let storage = api.storage().at_latest().await.map_err(rpc_error)?;
+            let omni_bridge = litentry_rococo::storage().omni_bridge();
+
+            let relayer_threshold = storage
+                .fetch(&omni_bridge.relayer_threshold())
+                .await
+                .map_err(rpc_error)?
+                .map(u64::from);
+            // counter is removed from storage once the last relayer is removed
+            let relayer_count = storage
+                .fetch(&omni_bridge.counter_for_relayers())
+                .await
+                .map_err(rpc_error)?
+                .map(u64::from)
+                .unwrap_or_default();
+            Ok(CommandOutput {
+                bridge_info: Some(BridgeInfo { relayer_threshold, relayer_count }),
+                ..Default::default()
+            })
         },
     }
 }
+
+pub async fn connect(rpc_url: &str) -> Result<OnlineClient<PolkadotConfig>, CliError> {
+    OnlineClient::<PolkadotConfig>::from_insecure_url(rpc_url)
+        .await
+        .map_err(rpc_error)
+}
+
+/// Free balance of `account` read from the chain's `System` storage.
+pub async fn free_balance(api: &OnlineClient<PolkadotConfig>, account: AccountId32) -> Result<u128, CliError> {
+    let balances_storage_query = litentry_rococo::storage().system().account(account);
+    let balances_details = api
+        .storage()
+        .at_latest()
+        .await
+        .map_err(rpc_error)?
+        .fetch(&balances_storage_query)
+        .await
+        .map_err(rpc_error)?;
+
+    // there is no account with existential deposit
+    Ok(balances_details.map(|details| details.data.free).unwrap_or_default())
+}
+
+/// Pays in signed by `signer`, returns the extrinsic and nonce of emitted `PaidIn` event.
+pub async fn pay_in(
+    api: &OnlineClient<PolkadotConfig>,
+    conf: &PayInConf,
+    signer: &Keypair,
+) -> Result<(TxOutput, u64), CliError> {
+    let transfer_assets_call = pay_in_call(conf)?;
+
+    let (extrinsic, events) = submit(api, &transfer_assets_call, signer).await?;
+    let pay_in_nonce = pay_in_nonce(events.all_events_in_block(), events.extrinsic_index())?
+        .ok_or_else(|| CliError::MissingPaidInEvent(extrinsic.hash.clone()))?;
+    Ok((extrinsic, pay_in_nonce))
+}
+
+fn pay_in_call(conf: &PayInConf) -> Result<StaticPayload<PayIn>, CliError> {
+    let recipient_address = Vec::<u8>::from_hex(conf.dest_address.as_str())
+        .map_err(|e| CliError::InvalidArgument("destination address", e.to_string()))?;
+
+    let request = litentry_rococo::runtime_types::pallet_omni_bridge::PayInRequest {
+        asset: conf.asset.runtime_asset(),
+        dest_chain: crate::litentry_rococo::runtime_types::core_primitives::omni::chain::ChainType::Ethereum(
+            conf.ethereum_id,
+        ),
+        dest_account: recipient_address,
+        amount: conf.amount,
+    };
+    Ok(litentry_rococo::tx().omni_bridge().pay_in(request))
+}
+
+// signs and submits extrinsic, waiting for its successful finalization
+async fn submit<Call: Payload>(
+    api: &OnlineClient<PolkadotConfig>,
+    call: &Call,
+    signer: &Keypair,
+) -> Result<(TxOutput, ExtrinsicEvents<PolkadotConfig>), CliError> {
+    let in_block = api
+        .tx()
+        .sign_and_submit_then_watch(call, signer, Default::default())
+        .await
+        .map_err(rpc_error)?
+        .wait_for_finalized()
+        .await
+        .map_err(rpc_error)?;
+    let events = in_block
+        .wait_for_success()
+        .await
+        .map_err(|e| CliError::ExtrinsicFailed(e.to_string()))?;
+    let block = api.blocks().at(in_block.block_hash()).await.map_err(rpc_error)?;
+    let output = TxOutput {
+        hash: format!("{:?}", in_block.extrinsic_hash()),
+        block_hash: format!("{:?}", in_block.block_hash()),
+        block_number: block.number().into(),
+    };
+    Ok((output, events))
+}
+
+// returns nonce of the `PaidIn` event emitted by extrinsic with given index
+fn pay_in_nonce(events: &Events<PolkadotConfig>, extrinsic_index: u32) -> Result<Option<u64>, CliError> {
+    for event in events.iter() {
+        let event = event.map_err(rpc_error)?;
+        if event.phase() != Phase::ApplyExtrinsic(extrinsic_index) {
+            continue;
+        }
+        if let Some(paid_in) = event.as_event::<PaidIn>().map_err(rpc_error)? {
+            return Ok(Some(paid_in.nonce));
+        }
+    }
+    Ok(None)
+}
+
+fn rpc_error<E: Display>(e: E) -> CliError {
+    CliError::Rpc(e.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::litentry_rococo::runtime_types::core_primitives::omni::chain::ChainType;
+    use clap::Parser;
+    use subxt::ext::codec::{Compact, Decode, Encode};
+    use subxt::Metadata;
+
+    fn paid_in(nonce: u64) -> PaidIn {
+        PaidIn {
+            source_account: AccountId32::from([1; 32]),
+            nonce,
+            asset: NativeOrWithId::Native,
+            resource_id: [2; 32],
+            dest_chain: ChainType::Ethereum(0),
+            dest_account: vec![3; 20],
+            amount: 100,
+        }
+    }
+
+    // encodes `PaidIn` events emitted in given phases as block events
+    fn events(paid_in_events: Vec<(Phase, PaidIn)>) -> Events<PolkadotConfig> {
+        let metadata_bytes = std::fs::read("../artifacts/local.scale").unwrap();
+        let metadata = Metadata::decode(&mut &metadata_bytes[..]).unwrap();
+        let pallet = metadata.pallet_by_name("OmniBridge").unwrap();
+        let variant_index = pallet
+            .event_variants()
+            .unwrap()
+            .iter()
+            .find(|variant| variant.name == "PaidIn")
+            .unwrap()
+            .index;
+
+        let mut bytes = Compact(paid_in_events.len() as u32).encode();
+        for (phase, event) in paid_in_events {
+            phase.encode_to(&mut bytes);
+            bytes.push(pallet.index());
+            bytes.push(variant_index);
+            event.encode_to(&mut bytes);
+            Vec::<[u8; 32]>::new().encode_to(&mut bytes);
+        }
+        Events::decode_from(bytes, metadata)
+    }
+
+    #[derive(Parser)]
+    struct TestCli {
+        #[command(subcommand)]
+        command: SubstrateCommand,
+    }
+
+    fn parse_pay_in(args: &[&str]) -> PayInConf {
+        match TestCli::try_parse_from([&["cli", "pay-in"][..], args].concat())
+            .unwrap()
+            .command
+        {
+            SubstrateCommand::PayIn(conf) => conf,
+            _ => panic!("expected pay-in command"),
+        }
+    }
+
+    fn local_metadata() -> Metadata {
+        let metadata_bytes = std::fs::read("../artifacts/local.scale").unwrap();
+        Metadata::decode(&mut &metadata_bytes[..]).unwrap()
+    }
+
+    #[test]
+    fn asset_is_parsed() {
+        assert_eq!("native".parse(), Ok(Asset::Native));
+        assert_eq!("with_id:1984".parse(), Ok(Asset::WithId(1984)));
+        assert!("with_id:abc".parse::<Asset>().is_err());
+        assert!("foreign".parse::<Asset>().is_err());
+    }
+
+    #[test]
+    fn pay_in_asset_is_parsed_from_args() {
+        assert_eq!(parse_pay_in(&[]).asset, Asset::Native);
+        assert_eq!(parse_pay_in(&["--asset", "with_id:1984"]).asset, Asset::WithId(1984));
+        assert!(TestCli::try_parse_from(["cli", "pay-in", "--asset", "with_id"]).is_err());
+    }
+
+    #[test]
+    fn setup_bridge_asset_is_parsed_from_args() {
+        let cli = TestCli::try_parse_from(["cli", "setup-bridge", "--asset", "with_id:7"]).unwrap();
+
+        let SubstrateCommand::SetupBridge(conf) = cli.command else { panic!("expected setup-bridge command") };
+        assert_eq!(conf.asset, Asset::WithId(7));
+        assert_eq!(
+            <[u8; 32]>::from_hex(&conf.resource_id).unwrap(),
+            [
+                158, 230, 223, 182, 26, 47, 185, 3, 223, 72, 124, 64, 22, 99, 130, 86, 67, 187, 130, 93, 65, 105, 94,
+                99, 223, 138, 246, 22, 42, 177, 69, 166,
+            ]
+        );
+    }
+
+    #[test]
+    fn pay_in_call_carries_asset() {
+        let metadata = local_metadata();
+        let native = pay_in_call(&parse_pay_in(&[])).unwrap().encode_call_data(&metadata).unwrap();
+        let with_id = pay_in_call(&parse_pay_in(&["--asset", "with_id:1984"]))
+            .unwrap()
+            .encode_call_data(&metadata)
+            .unwrap();
+
+        assert_ne!(native, with_id);
+        // call data is pallet and call index followed by the request
+        let request = |call_data: &[u8]| PayIn::decode(&mut &call_data[2..]).unwrap().req;
+        assert!(matches!(request(&native).asset, NativeOrWithId::Native));
+        let NativeOrWithId::WithId(id) = request(&with_id).asset else { panic!("expected WithId asset") };
+        assert_eq!(id, 1984);
+        assert_eq!(request(&native).amount, request(&with_id).amount);
+    }
+
+    #[test]
+    fn pay_in_nonce_is_parsed_from_extrinsic_events() {
+        let events = events(vec![(Phase::ApplyExtrinsic(1), paid_in(5)), (Phase::ApplyExtrinsic(2), paid_in(6))]);
+
+        assert_eq!(pay_in_nonce(&events, 2).unwrap(), Some(6));
+    }
+
+    #[test]
+    fn bridge_info_without_threshold_is_reported() {
+        let output = CommandOutput {
+            bridge_info: Some(BridgeInfo { relayer_threshold: None, relayer_count: 0 }),
+            ..Default::default()
+        };
+
+        assert_eq!(output.to_string(), "Relayer threshold: not set\nRelayers: 0\n");
+    }
+
+    #[test]
+    fn pay_in_nonce_is_none_if_extrinsic_did_not_emit_event() {
+        let events = events(vec![(Phase::ApplyExtrinsic(1), paid_in(5))]);
+
+        assert_eq!(pay_in_nonce(&events, 3).unwrap(), None);
+    }
+}
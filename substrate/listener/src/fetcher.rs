@@ -15,8 +15,9 @@
 // along with Litentry.  If not, see <https://www.gnu.org/licenses/>.
 
 use async_trait::async_trait;
-use bridge_core::fetcher::{BlockPayInEventsFetcher, LastFinalizedBlockNumFetcher};
+use bridge_core::fetcher::{BlockPayInEventsFetcher, LastFinalizedBlockNumFetcher, ReorgDetector};
 use bridge_core::listener::PayIn;
+use bridge_core::relay::Recipient;
 use log::*;
 
 use crate::rpc_client::SubstrateRpcClientFactory;
@@ -26,16 +27,26 @@ use crate::{listener::PayInEventId, rpc_client::SubstrateRpcClient};
 pub struct Fetcher<RpcClient: SubstrateRpcClient, RpcClientFactory: SubstrateRpcClientFactory<RpcClient>> {
     client_factory: RpcClientFactory,
     client: Option<RpcClient>,
+    confirmation_blocks: u64,
 }
 
 impl<RpcClient: SubstrateRpcClient, RpcClientFactory: SubstrateRpcClientFactory<RpcClient>>
     Fetcher<RpcClient, RpcClientFactory>
 {
     pub fn new(client_factory: RpcClientFactory) -> Self {
-        Self { client: None, client_factory }
+        Self { client: None, client_factory, confirmation_blocks: 0 }
+    }
+
+    /// Reports finalized block `confirmation_blocks` behind the node's finalized head.
+    pub fn with_confirmation_blocks(mut self, confirmation_blocks: u64) -> Self {
+        self.confirmation_blocks = confirmation_blocks;
+        self
     }
 
     async fn connect_if_needed(&mut self) {
+        if self.client.as_ref().is_some_and(|client| client.is_stale()) {
+            self.client = None;
+        }
         if self.client.is_none() {
             match self.client_factory.new_client().await {
                 Ok(client) => self.client = Some(client),
@@ -56,7 +67,7 @@ impl<
 
         if let Some(ref mut client) = self.client {
             let block_num = client.get_last_finalized_block_num().await?;
-            Ok(Some(block_num))
+            Ok(block_num.checked_sub(self.confirmation_blocks))
         } else {
             Err(())
         }
@@ -73,23 +84,292 @@ impl<
         self.connect_if_needed().await;
 
         if let Some(ref mut client) = self.client {
-            client.get_block_pay_in_events(block_num).await.map(|events| {
-                events
-                    .into_iter()
-                    .map(|event| {
-                        PayIn::new(
-                            event.id,
-                            Some(hex::encode(event.event.dest_chain)),
-                            event.event.amount,
-                            event.event.nonce,
-                            event.event.resource_id,
-                            event.event.data,
-                        )
-                    })
-                    .collect()
-            })
+            let events = client.get_block_pay_in_events(block_num).await?;
+            // timestamp is only used to measure event age, events are not held back if it's unavailable
+            let timestamp = if events.is_empty() {
+                None
+            } else {
+                client.get_block_timestamp(block_num).await.unwrap_or_else(|_| {
+                    warn!("Could not get timestamp of block {}", block_num);
+                    None
+                })
+            };
+            Ok(events
+                .into_iter()
+                .map(|event| {
+                    PayIn::new(
+                        event.id,
+                        Some(hex::encode(event.event.dest_chain)),
+                        event.event.amount,
+                        event.event.nonce,
+                        event.event.resource_id,
+                        Recipient::from(event.event.data),
+                    )
+                    .with_source_ref(event.extrinsic_hash.map(|hash| hash.to_vec()).unwrap_or_default())
+                    .with_timestamp(timestamp)
+                })
+                .collect())
         } else {
+            // block must not be treated as empty, otherwise it would be skipped by the listener
+            error!("Could not fetch block {} events, client is not connected", block_num);
+            Err(())
+        }
+    }
+}
+
+/// Only blocks finalized by GRANDPA are synced so they can't be reorganized.
+impl<
+        RpcClient: SubstrateRpcClient + Sync + Send,
+        RpcClientFactory: SubstrateRpcClientFactory<RpcClient> + Sync + Send,
+    > ReorgDetector for Fetcher<RpcClient, RpcClientFactory>
+{
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Fetcher;
+    use crate::primitives::SyncCheckpoint;
+    use crate::rpc_client::{BlockEvent, PaidInEvent, PaidOutEvent, SubstrateRpcClient, SubstrateRpcClientFactory};
+    use async_trait::async_trait;
+    use bridge_core::fetcher::{BlockPayInEventsFetcher, LastFinalizedBlockNumFetcher, ReorgDetector};
+    use bridge_core::listener::{Listener, PayIn};
+    use bridge_core::relay::Relay;
+    use bridge_core::sync_checkpoint_repository::CheckpointRepository;
+    use std::collections::HashMap;
+    use std::sync::atomic::{AtomicU32, Ordering};
+    use std::sync::{Arc, Mutex};
+    use std::thread;
+    use std::time::Duration;
+    use tokio::runtime::Handle;
+
+    struct UnreachableRpcClient;
+
+    #[async_trait]
+    impl SubstrateRpcClient for UnreachableRpcClient {
+        async fn get_last_finalized_block_num(&mut self) -> Result<u64, ()> {
+            Err(())
+        }
+
+        async fn get_block_pay_in_events(&mut self, _block_num: u64) -> Result<Vec<BlockEvent<PaidInEvent>>, ()> {
+            Err(())
+        }
+
+        async fn get_block_paid_out_events(&mut self, _block_num: u64) -> Result<Vec<BlockEvent<PaidOutEvent>>, ()> {
+            Err(())
+        }
+
+        async fn get_block_timestamp(&mut self, _block_num: u64) -> Result<Option<u64>, ()> {
+            Err(())
+        }
+
+        async fn get_genesis_hash(&mut self) -> Result<Vec<u8>, ()> {
+            Err(())
+        }
+
+        async fn get_spec_name(&mut self) -> Result<String, ()> {
+            Err(())
+        }
+    }
+
+    struct FailingRpcClientFactory;
+
+    #[async_trait]
+    impl SubstrateRpcClientFactory<UnreachableRpcClient> for FailingRpcClientFactory {
+        async fn new_client(&self) -> Result<UnreachableRpcClient, ()> {
+            Err(())
+        }
+    }
+
+    // reports stale after runtime upgrade, as `RpcClient` does when spec version changes
+    struct UpgradedRpcClient;
+
+    #[async_trait]
+    impl SubstrateRpcClient for UpgradedRpcClient {
+        async fn get_last_finalized_block_num(&mut self) -> Result<u64, ()> {
+            Err(())
+        }
+
+        async fn get_block_pay_in_events(&mut self, _block_num: u64) -> Result<Vec<BlockEvent<PaidInEvent>>, ()> {
+            Err(())
+        }
+
+        async fn get_block_paid_out_events(&mut self, _block_num: u64) -> Result<Vec<BlockEvent<PaidOutEvent>>, ()> {
+            Err(())
+        }
+
+        async fn get_block_timestamp(&mut self, _block_num: u64) -> Result<Option<u64>, ()> {
+            Err(())
+        }
+
+        async fn get_genesis_hash(&mut self) -> Result<Vec<u8>, ()> {
+            Err(())
+        }
+
+        async fn get_spec_name(&mut self) -> Result<String, ()> {
+            Err(())
+        }
+
+        fn is_stale(&self) -> bool {
+            true
+        }
+    }
+
+    struct FinalizedRpcClient(u64);
+
+    #[async_trait]
+    impl SubstrateRpcClient for FinalizedRpcClient {
+        async fn get_last_finalized_block_num(&mut self) -> Result<u64, ()> {
+            Ok(self.0)
+        }
+
+        async fn get_block_pay_in_events(&mut self, _block_num: u64) -> Result<Vec<BlockEvent<PaidInEvent>>, ()> {
+            Ok(vec![])
+        }
+
+        async fn get_block_paid_out_events(&mut self, _block_num: u64) -> Result<Vec<BlockEvent<PaidOutEvent>>, ()> {
             Ok(vec![])
         }
+
+        async fn get_block_timestamp(&mut self, _block_num: u64) -> Result<Option<u64>, ()> {
+            Ok(None)
+        }
+
+        async fn get_genesis_hash(&mut self) -> Result<Vec<u8>, ()> {
+            Ok(vec![])
+        }
+
+        async fn get_spec_name(&mut self) -> Result<String, ()> {
+            Ok("local".to_string())
+        }
+    }
+
+    struct FinalizedRpcClientFactory(u64);
+
+    #[async_trait]
+    impl SubstrateRpcClientFactory<FinalizedRpcClient> for FinalizedRpcClientFactory {
+        async fn new_client(&self) -> Result<FinalizedRpcClient, ()> {
+            Ok(FinalizedRpcClient(self.0))
+        }
+    }
+
+    #[derive(Default)]
+    struct CountingRpcClientFactory {
+        created: AtomicU32,
+    }
+
+    #[async_trait]
+    impl SubstrateRpcClientFactory<UpgradedRpcClient> for CountingRpcClientFactory {
+        async fn new_client(&self) -> Result<UpgradedRpcClient, ()> {
+            self.created.fetch_add(1, Ordering::SeqCst);
+            Ok(UpgradedRpcClient)
+        }
+    }
+
+    // reports block 1 as finalized, so listener goes on to fetch its events through the failing fetcher
+    struct FinalizedBlockFetcher(Fetcher<UnreachableRpcClient, FailingRpcClientFactory>);
+
+    #[async_trait]
+    impl LastFinalizedBlockNumFetcher for FinalizedBlockFetcher {
+        async fn get_last_finalized_block_num(&mut self) -> Result<Option<u64>, ()> {
+            Ok(Some(1))
+        }
+    }
+
+    #[async_trait]
+    impl BlockPayInEventsFetcher<crate::listener::PayInEventId, String> for FinalizedBlockFetcher {
+        async fn get_block_pay_in_events(
+            &mut self,
+            block_num: u64,
+        ) -> Result<Vec<PayIn<crate::listener::PayInEventId, String>>, ()> {
+            self.0.get_block_pay_in_events(block_num).await
+        }
+    }
+
+    impl ReorgDetector for FinalizedBlockFetcher {}
+
+    #[derive(Clone, Default)]
+    struct RecordingCheckpointRepository {
+        saved: Arc<Mutex<Vec<SyncCheckpoint>>>,
+    }
+
+    impl CheckpointRepository<SyncCheckpoint> for RecordingCheckpointRepository {
+        fn get(&self) -> Result<Option<SyncCheckpoint>, ()> {
+            Ok(self.saved.lock().unwrap().last().cloned())
+        }
+
+        fn save(&mut self, checkpoint: SyncCheckpoint) -> Result<(), ()> {
+            self.saved.lock().unwrap().push(checkpoint);
+            Ok(())
+        }
+    }
+
+    #[tokio::test]
+    async fn it_should_fail_fetching_events_when_client_cannot_connect() {
+        let mut fetcher = Fetcher::new(FailingRpcClientFactory);
+
+        assert!(fetcher.get_block_pay_in_events(1).await.is_err());
+    }
+
+    // both paths must fail alike, otherwise listener could treat a block as empty while finalized num fetch fails
+    #[tokio::test]
+    async fn it_should_fail_all_fetches_alike_when_client_cannot_connect() {
+        let mut fetcher = Fetcher::new(FailingRpcClientFactory);
+
+        assert_eq!(fetcher.get_last_finalized_block_num().await, Err(()));
+        assert!(fetcher.get_block_pay_in_events(1).await.is_err());
+        assert_eq!(fetcher.get_last_finalized_block_num().await, Err(()));
+    }
+
+    #[tokio::test]
+    async fn it_should_report_finalized_block_behind_by_confirmation_blocks() {
+        let mut fetcher = Fetcher::new(FinalizedRpcClientFactory(100));
+        assert_eq!(fetcher.get_last_finalized_block_num().await, Ok(Some(100)));
+
+        let mut fetcher = Fetcher::new(FinalizedRpcClientFactory(100)).with_confirmation_blocks(10);
+        assert_eq!(fetcher.get_last_finalized_block_num().await, Ok(Some(90)));
+    }
+
+    #[tokio::test]
+    async fn it_should_report_no_finalized_block_while_chain_is_shorter_than_confirmation_blocks() {
+        let mut fetcher = Fetcher::new(FinalizedRpcClientFactory(5)).with_confirmation_blocks(10);
+        assert_eq!(fetcher.get_last_finalized_block_num().await, Ok(None));
+
+        let mut fetcher = Fetcher::new(FinalizedRpcClientFactory(10)).with_confirmation_blocks(10);
+        assert_eq!(fetcher.get_last_finalized_block_num().await, Ok(Some(0)));
+    }
+
+    #[tokio::test]
+    async fn it_should_recreate_stale_client() {
+        let mut fetcher = Fetcher::new(CountingRpcClientFactory::default());
+
+        assert_eq!(fetcher.get_last_finalized_block_num().await, Err(()));
+        assert_eq!(fetcher.get_last_finalized_block_num().await, Err(()));
+
+        assert_eq!(fetcher.client_factory.created.load(Ordering::SeqCst), 2);
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn checkpoint_should_not_advance_when_client_cannot_connect() {
+        let repository = RecordingCheckpointRepository::default();
+        let (stop_sender, stop_receiver) = tokio::sync::oneshot::channel();
+        let mut listener = Listener::new(
+            "substrate",
+            Handle::current(),
+            FinalizedBlockFetcher(Fetcher::new(FailingRpcClientFactory)),
+            Relay::<String>::Multi(HashMap::new()),
+            stop_receiver,
+            repository.clone(),
+            1,
+            0,
+            1,
+        )
+        .unwrap();
+
+        let handle = thread::spawn(move || listener.sync());
+        thread::sleep(Duration::from_millis(100));
+        stop_sender.send(()).unwrap();
+        assert!(handle.join().unwrap().is_ok());
+
+        assert!(repository.saved.lock().unwrap().is_empty());
     }
 }
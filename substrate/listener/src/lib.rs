@@ -14,21 +14,33 @@
 // You should have received a copy of the GNU General Public License
 // along with Litentry.  If not, see <https://www.gnu.org/licenses/>.
 
-mod fetcher;
+pub mod fetcher;
 pub mod listener;
-mod primitives;
-mod rpc_client;
+pub mod primitives;
+pub mod rpc_client;
+pub mod runtime_compat;
 
 use crate::fetcher::Fetcher;
 use crate::listener::{ListenerConfig, SubstrateListener};
-use crate::rpc_client::{RpcClient, RpcClientFactory};
-use bridge_core::listener::Listener;
+use crate::rpc_client::{RpcClient, RpcClientFactory, SubstrateRpcClient, SubstrateRpcClientFactory};
+use crate::runtime_compat::INCOMPATIBLE_RUNTIME_GAUGE;
 use bridge_core::listener::RELAY_MAX_ATTEMPTS;
+use bridge_core::listener::{Listener, ListenerContext, SyncListener};
 use bridge_core::relay::{Relay, Relayer};
+use bridge_core::relay_stats::RelayStats;
+use bridge_core::runtime_registry::{
+    find_runtime, resolve_runtime, RegisteredRuntime, RuntimeSelectionError, RuntimeVersionFetcher,
+};
+use bridge_core::start_block::StartBlockSettings;
 use bridge_core::sync_checkpoint_repository::FileCheckpointRepository;
+use log::error;
+use metrics::gauge;
 use parity_scale_codec::Encode;
 use scale_encode::EncodeAsType;
 use std::collections::HashMap;
+use std::future::Future;
+use std::ops::RangeInclusive;
+use std::pin::Pin;
 use std::sync::Arc;
 use subxt::config::signed_extensions;
 use subxt::events::StaticEvent;
@@ -76,62 +88,142 @@ impl Config for CustomConfig {
     type AssetId = u32;
 }
 
-/// Creates local substrate based chain listener.
-pub async fn create_local_listener<ChainConfig: Config>(
-    id: &str,
+/// Runtime metadata variant of substrate based chain, resolvable by chain name from configuration
+/// or by the node's runtime spec name.
+pub trait ChainRuntime {
+    /// Chain name used in configuration
+    const NAME: &'static str;
+    const SPEC_NAME: &'static str;
+    /// Spec versions the generated metadata is known to match, to be extended when metadata is regenerated
+    const SPEC_VERSIONS: RangeInclusive<u32>;
+    type PaidInEvent: PalletPaidInEvent + 'static;
+}
+
+pub type CreateListenerFn = for<'a> fn(
+    &'a ListenerContext<ListenerConfig>,
+    Handle,
+    Receiver<()>,
+) -> Pin<Box<dyn Future<Output = Result<Box<dyn SyncListener>, ()>> + Send + 'a>>;
+
+/// Returns listener factories of all supported chains.
+pub fn chain_runtimes<ChainConfig: Config>() -> Vec<RegisteredRuntime<CreateListenerFn>> {
+    vec![
+        registered::<LocalRuntime>(create_registered_listener::<ChainConfig, LocalRuntime>),
+        registered::<PaseoRuntime>(create_registered_listener::<ChainConfig, PaseoRuntime>),
+        registered::<HeimaRuntime>(create_registered_listener::<ChainConfig, HeimaRuntime>),
+    ]
+}
+
+fn registered<Runtime: ChainRuntime>(create: CreateListenerFn) -> RegisteredRuntime<CreateListenerFn> {
+    RegisteredRuntime {
+        chain: Runtime::NAME,
+        spec_name: Runtime::SPEC_NAME,
+        spec_versions: Runtime::SPEC_VERSIONS,
+        value: create,
+    }
+}
+
+/// Creates listener for chain of the node, fails if its runtime is not supported or doesn't match configured chain.
+pub async fn create_listener_for_chain<ChainConfig: Config>(
+    context: &ListenerContext<ListenerConfig>,
     handle: Handle,
-    config: &ListenerConfig,
-    start_block: u64,
-    chain_id: u32,
-    relayers: HashMap<String, Arc<Box<dyn Relayer<String>>>>,
     stop_signal: Receiver<()>,
-) -> Result<
-    SubstrateListener<
-        RpcClient<ChainConfig, LocalPaidInEvent>,
-        RpcClientFactory<ChainConfig>,
-        FileCheckpointRepository,
-    >,
-    (),
-> {
-    let client_factory: RpcClientFactory<ChainConfig> = RpcClientFactory::new(&config.ws_rpc_endpoint);
+) -> Result<Box<dyn SyncListener>, ()> {
+    let registry = chain_runtimes::<ChainConfig>();
+    let fetcher =
+        RpcClientFactory::<ChainConfig>::new(&context.config.ws_rpc_endpoint, context.config.rpc_auth.clone());
+    let runtime = select_chain_runtime(&context.id, &registry, &fetcher, &context.config).await?;
+    (runtime.value)(context, handle, stop_signal).await
+}
 
-    let fetcher = Fetcher::new(client_factory);
-    let last_processed_log_repository = FileCheckpointRepository::new(&format!("data/{}_last_log.bin", id));
+/// Resolves chain name of listener `config`, e.g. to pick generated metadata outside of the worker.
+#[allow(clippy::result_unit_err)]
+pub async fn resolve_chain<ChainConfig: Config>(id: &str, config: &ListenerConfig) -> Result<&'static str, ()> {
+    let registry = chain_runtimes::<ChainConfig>();
+    let fetcher = RpcClientFactory::<ChainConfig>::new(&config.ws_rpc_endpoint, config.rpc_auth.clone());
+    select_chain_runtime(id, &registry, &fetcher, config)
+        .await
+        .map(|runtime| runtime.chain)
+}
 
-    Listener::new(
-        id,
-        handle,
-        fetcher,
-        Relay::Multi(relayers),
-        stop_signal,
-        last_processed_log_repository,
-        start_block,
-        chain_id,
-        RELAY_MAX_ATTEMPTS,
-    )
+/// Selects runtime of the node from `registry`. Configured chain is used without asking the node if startup
+/// checks are skipped, spec version outside of the supported range marks the runtime incompatible.
+#[allow(clippy::result_unit_err)]
+pub async fn select_chain_runtime<'a, T>(
+    id: &str,
+    registry: &'a [RegisteredRuntime<T>],
+    fetcher: &dyn RuntimeVersionFetcher,
+    config: &ListenerConfig,
+) -> Result<&'a RegisteredRuntime<T>, ()> {
+    let result = match config.chain.as_deref() {
+        Some(chain) if config.skip_startup_checks => find_runtime(registry, chain),
+        configured_chain => resolve_runtime(registry, fetcher, configured_chain).await,
+    };
+    match result {
+        Ok(runtime) => Ok(runtime),
+        Err(RuntimeSelectionError::UnsupportedSpecVersion { chain, spec_version }) => {
+            error!(
+                "Runtime spec version {} of chain {} is not supported by generated metadata, stopping listener {}",
+                spec_version, chain, id
+            );
+            gauge!(INCOMPATIBLE_RUNTIME_GAUGE, "chain" => chain.to_string(), "component" => "listener").set(1.0);
+            Err(())
+        },
+        Err(e) => {
+            error!("Could not select runtime of listener {}: {:?}", id, e);
+            Err(())
+        },
+    }
+}
+
+#[allow(clippy::type_complexity)]
+fn create_registered_listener<ChainConfig: Config, Runtime: ChainRuntime>(
+    context: &ListenerContext<ListenerConfig>,
+    handle: Handle,
+    stop_signal: Receiver<()>,
+) -> Pin<Box<dyn Future<Output = Result<Box<dyn SyncListener>, ()>> + Send + '_>> {
+    Box::pin(async move {
+        let listener = create_listener::<ChainConfig, Runtime>(
+            &context.id,
+            handle,
+            &context.config,
+            &context.start_block,
+            context.chain_id,
+            context.relayers.clone(),
+            stop_signal,
+        )
+        .await?
+        .with_context(context);
+        Ok(Box::new(listener) as Box<dyn SyncListener>)
+    })
 }
 
-/// Creates Paseo chain listener.
-pub async fn create_paseo_listener<ChainConfig: Config>(
+/// Creates listener of substrate based chain with given runtime metadata.
+#[allow(clippy::type_complexity)]
+pub async fn create_listener<ChainConfig: Config, Runtime: ChainRuntime>(
     id: &str,
     handle: Handle,
     config: &ListenerConfig,
-    start_block: u64,
+    start_block: &StartBlockSettings,
     chain_id: u32,
-    relayers: HashMap<String, Arc<Box<dyn Relayer<String>>>>,
+    relayers: HashMap<String, Vec<Arc<Box<dyn Relayer<String>>>>>,
     stop_signal: Receiver<()>,
 ) -> Result<
     SubstrateListener<
-        RpcClient<ChainConfig, PaseoPaidInEvent>,
+        RpcClient<ChainConfig, Runtime::PaidInEvent>,
         RpcClientFactory<ChainConfig>,
         FileCheckpointRepository,
     >,
     (),
 > {
-    let client_factory: RpcClientFactory<ChainConfig> = RpcClientFactory::new(&config.ws_rpc_endpoint);
+    let client_factory: RpcClientFactory<ChainConfig> =
+        RpcClientFactory::new(&config.ws_rpc_endpoint, config.rpc_auth.clone());
+    validate_chain::<RpcClient<ChainConfig, Runtime::PaidInEvent>, _>(id, &client_factory, config).await?;
 
-    let fetcher = Fetcher::new(client_factory);
+    let mut fetcher = Fetcher::new(client_factory).with_confirmation_blocks(config.confirmation_blocks);
+    let start_block = start_block.resolve(id, &mut fetcher).await?;
     let last_processed_log_repository = FileCheckpointRepository::new(&format!("data/{}_last_log.bin", id));
+    let relay_stats = Arc::new(RelayStats::new(id, &format!("data/{}_relay_stats.bin", id))?);
 
     Listener::new(
         id,
@@ -144,45 +236,55 @@ pub async fn create_paseo_listener<ChainConfig: Config>(
         chain_id,
         RELAY_MAX_ATTEMPTS,
     )
+    .map(|listener| listener.with_relay_stats(relay_stats))
 }
 
-/// Creates Heima chain listener.
-pub async fn create_heima_listener<ChainConfig: Config>(
+async fn validate_chain<RpcClientT: SubstrateRpcClient, RpcClientFactoryT: SubstrateRpcClientFactory<RpcClientT>>(
     id: &str,
-    handle: Handle,
+    client_factory: &RpcClientFactoryT,
     config: &ListenerConfig,
-    start_block: u64,
-    chain_id: u32,
-    relayers: HashMap<String, Arc<Box<dyn Relayer<String>>>>,
-    stop_signal: Receiver<()>,
-) -> Result<
-    SubstrateListener<
-        RpcClient<ChainConfig, HeimaPaidInEvent>,
-        RpcClientFactory<ChainConfig>,
-        FileCheckpointRepository,
-    >,
-    (),
-> {
-    let client_factory: RpcClientFactory<ChainConfig> = RpcClientFactory::new(&config.ws_rpc_endpoint);
+) -> Result<(), ()> {
+    if config.skip_startup_checks || (config.expected_genesis_hash.is_none() && config.expected_spec_name.is_none()) {
+        return Ok(());
+    }
+    let mut client = client_factory.new_client().await?;
+    ensure_chain_matches_config(&mut client, config)
+        .await
+        .map_err(|_| error!("Startup checks of {} listener failed", id))
+}
 
-    let fetcher = Fetcher::new(client_factory);
-    let last_processed_log_repository = FileCheckpointRepository::new(&format!("data/{}_last_log.bin", id));
+/// Ensures the node behind `client` serves the chain expected by `config`.
+/// Only the values set in the config are checked.
+#[allow(clippy::result_unit_err)]
+pub async fn ensure_chain_matches_config<C: SubstrateRpcClient>(
+    client: &mut C,
+    config: &ListenerConfig,
+) -> Result<(), ()> {
+    if let Some(ref expected_genesis_hash) = config.expected_genesis_hash {
+        let genesis_hash = hex::encode(client.get_genesis_hash().await?);
+        if genesis_hash != expected_genesis_hash.trim_start_matches("0x").to_lowercase() {
+            error!("Node genesis hash mismatch, expected: {}, got: 0x{}", expected_genesis_hash, genesis_hash);
+            return Err(());
+        }
+    }
 
-    Listener::new(
-        id,
-        handle,
-        fetcher,
-        Relay::Multi(relayers),
-        stop_signal,
-        last_processed_log_repository,
-        start_block,
-        chain_id,
-        RELAY_MAX_ATTEMPTS,
-    )
+    if let Some(ref expected_spec_name) = config.expected_spec_name {
+        let spec_name = client.get_spec_name().await?;
+        if &spec_name != expected_spec_name {
+            error!("Runtime spec name mismatch, expected: {}, got: {}", expected_spec_name, spec_name);
+            return Err(());
+        }
+    }
+
+    Ok(())
 }
 
-pub trait PalletPaidInEvent: Send {
+pub trait PalletPaidInEvent: Send + Sync {
     type MetadataType: StaticEvent;
+    /// Name of the chain the event is generated for.
+    const CHAIN: &'static str;
+    /// Scale encoded metadata `MetadataType` was generated from.
+    const METADATA: &'static [u8];
 
     fn wrap(raw: Self::MetadataType) -> Self;
 
@@ -194,98 +296,226 @@ pub trait PalletPaidInEvent: Send {
     fn dest_chain(&self) -> Vec<u8>;
 }
 
-pub struct HeimaPaidInEvent {
-    raw: heima::omni_bridge::events::PaidIn,
+/// Declares `PalletPaidInEvent` wrapper and `ChainRuntime` of a subxt metadata module.
+macro_rules! chain_runtime {
+    ($runtime:ident, $event:ident, $name:literal, $metadata:ident, $spec_name:literal, $spec_versions:expr) => {
+        pub struct $event {
+            raw: $metadata::omni_bridge::events::PaidIn,
+        }
+
+        impl PalletPaidInEvent for $event {
+            type MetadataType = $metadata::omni_bridge::events::PaidIn;
+            const CHAIN: &'static str = $name;
+            const METADATA: &'static [u8] = include_bytes!(concat!("../../artifacts/", $name, ".scale"));
+
+            fn wrap(raw: Self::MetadataType) -> Self {
+                Self { raw }
+            }
+
+            fn amount(&self) -> u128 {
+                self.raw.amount
+            }
+
+            fn resource_id(&self) -> [u8; 32] {
+                self.raw.resource_id
+            }
+
+            fn dest_account(&self) -> Vec<u8> {
+                self.raw.dest_account.clone()
+            }
+
+            fn nonce(&self) -> u64 {
+                self.raw.nonce
+            }
+
+            fn dest_chain(&self) -> Vec<u8> {
+                self.raw.dest_chain.encode()
+            }
+        }
+
+        pub enum $runtime {}
+
+        impl ChainRuntime for $runtime {
+            const NAME: &'static str = $name;
+            const SPEC_NAME: &'static str = $spec_name;
+            const SPEC_VERSIONS: RangeInclusive<u32> = $spec_versions;
+            type PaidInEvent = $event;
+        }
+    };
 }
 
-impl PalletPaidInEvent for HeimaPaidInEvent {
-    type MetadataType = heima::omni_bridge::events::PaidIn;
-
-    fn wrap(raw: Self::MetadataType) -> Self {
-        Self { raw }
+// local development node runs paseo runtime
+chain_runtime!(LocalRuntime, LocalPaidInEvent, "local", local, "paseo-parachain", 9241..=9299);
+chain_runtime!(PaseoRuntime, PaseoPaidInEvent, "paseo", paseo, "paseo-parachain", 9241..=9299);
+chain_runtime!(HeimaRuntime, HeimaPaidInEvent, "heima", heima, "litentry-parachain", 9241..=9299);
+
+#[cfg(test)]
+mod tests {
+    use crate::listener::ListenerConfig;
+    use crate::rpc_client::{BlockEvent, PaidInEvent, PaidOutEvent, SubstrateRpcClient};
+    use crate::{
+        chain_runtimes, create_listener_for_chain, ensure_chain_matches_config, select_chain_runtime, CustomConfig,
+    };
+    use async_trait::async_trait;
+    use bridge_core::dead_letter::DeadLetterLog;
+    use bridge_core::listener::{ListenerContext, DEFAULT_FETCH_TIMEOUT, DEFAULT_POLL_INTERVAL};
+    use bridge_core::listener_control::ListenerControl;
+    use bridge_core::listener_status::ListenerStatus;
+    use bridge_core::nonce_tracker::NonceTracker;
+    use bridge_core::runtime_registry::{RuntimeVersion, RuntimeVersionFetcher};
+    use std::collections::HashMap;
+    use std::sync::Arc;
+    use std::time::Duration;
+    use tokio::runtime::Handle;
+
+    const GENESIS_HASH: [u8; 32] = [1; 32];
+
+    struct FakeRpcClient;
+
+    #[async_trait]
+    impl SubstrateRpcClient for FakeRpcClient {
+        async fn get_last_finalized_block_num(&mut self) -> Result<u64, ()> {
+            Ok(0)
+        }
+
+        async fn get_block_pay_in_events(&mut self, _block_num: u64) -> Result<Vec<BlockEvent<PaidInEvent>>, ()> {
+            Ok(vec![])
+        }
+
+        async fn get_block_paid_out_events(&mut self, _block_num: u64) -> Result<Vec<BlockEvent<PaidOutEvent>>, ()> {
+            Ok(vec![])
+        }
+
+        async fn get_block_timestamp(&mut self, _block_num: u64) -> Result<Option<u64>, ()> {
+            Ok(None)
+        }
+
+        async fn get_genesis_hash(&mut self) -> Result<Vec<u8>, ()> {
+            Ok(GENESIS_HASH.to_vec())
+        }
+
+        async fn get_spec_name(&mut self) -> Result<String, ()> {
+            Ok("heima".to_string())
+        }
     }
 
-    fn amount(&self) -> u128 {
-        self.raw.amount
+    fn config(expected_genesis_hash: Option<String>, expected_spec_name: Option<&str>) -> ListenerConfig {
+        ListenerConfig {
+            ws_rpc_endpoint: "ws://localhost:9944".to_string(),
+            chain: Some("heima".to_string()),
+            expected_genesis_hash,
+            expected_spec_name: expected_spec_name.map(|name| name.to_string()),
+            skip_startup_checks: false,
+            rpc_auth: None,
+            confirmation_blocks: 0,
+        }
     }
 
-    fn resource_id(&self) -> [u8; 32] {
-        self.raw.resource_id
+    #[tokio::test]
+    async fn chain_matching_config_passes_checks() {
+        let config = config(Some(format!("0x{}", hex::encode(GENESIS_HASH))), Some("heima"));
+        assert!(ensure_chain_matches_config(&mut FakeRpcClient, &config).await.is_ok());
     }
 
-    fn dest_account(&self) -> Vec<u8> {
-        self.raw.dest_account.clone()
+    #[tokio::test]
+    async fn genesis_hash_mismatch_fails_checks() {
+        let config = config(Some(hex::encode([2u8; 32])), None);
+        assert!(ensure_chain_matches_config(&mut FakeRpcClient, &config).await.is_err());
     }
 
-    fn nonce(&self) -> u64 {
-        self.raw.nonce
+    #[tokio::test]
+    async fn spec_name_mismatch_fails_checks() {
+        let config = config(None, Some("paseo"));
+        assert!(ensure_chain_matches_config(&mut FakeRpcClient, &config).await.is_err());
     }
 
-    fn dest_chain(&self) -> Vec<u8> {
-        self.raw.dest_chain.encode()
+    #[test]
+    fn registry_covers_all_supported_chains() {
+        let names: Vec<&str> = chain_runtimes::<CustomConfig>().iter().map(|runtime| runtime.chain).collect();
+        assert_eq!(names, vec!["local", "paseo", "heima"]);
     }
-}
-
-pub struct LocalPaidInEvent {
-    raw: local::omni_bridge::events::PaidIn,
-}
-
-impl PalletPaidInEvent for LocalPaidInEvent {
-    type MetadataType = local::omni_bridge::events::PaidIn;
 
-    fn wrap(raw: Self::MetadataType) -> Self {
-        Self { raw }
+    struct FakeRuntimeVersionFetcher {
+        spec_name: &'static str,
+        spec_version: u32,
     }
 
-    fn amount(&self) -> u128 {
-        self.raw.amount
+    #[async_trait]
+    impl RuntimeVersionFetcher for FakeRuntimeVersionFetcher {
+        async fn runtime_version(&self) -> Result<RuntimeVersion, ()> {
+            Ok(RuntimeVersion { spec_name: self.spec_name.to_string(), spec_version: self.spec_version })
+        }
     }
 
-    fn resource_id(&self) -> [u8; 32] {
-        self.raw.resource_id
+    #[tokio::test]
+    async fn runtime_of_each_supported_chain_is_selected_from_node() {
+        let registry = chain_runtimes::<CustomConfig>();
+        // chains sharing the spec name have to be configured
+        for (spec_name, chain, expected) in [
+            ("litentry-parachain", None, "heima"),
+            ("paseo-parachain", Some("paseo"), "paseo"),
+            ("paseo-parachain", Some("local"), "local"),
+        ] {
+            let mut config = config(None, None);
+            config.chain = chain.map(|chain| chain.to_string());
+            let fetcher = FakeRuntimeVersionFetcher { spec_name, spec_version: 9241 };
+
+            let runtime = select_chain_runtime("test", &registry, &fetcher, &config).await.unwrap();
+
+            assert_eq!(runtime.chain, expected);
+        }
     }
 
-    fn dest_account(&self) -> Vec<u8> {
-        self.raw.dest_account.clone()
-    }
-
-    fn nonce(&self) -> u64 {
-        self.raw.nonce
-    }
+    #[tokio::test]
+    async fn unknown_or_unsupported_runtime_fails_selection() {
+        let registry = chain_runtimes::<CustomConfig>();
+        let mut config = config(None, None);
+        config.chain = None;
 
-    fn dest_chain(&self) -> Vec<u8> {
-        self.raw.dest_chain.encode()
-    }
-}
+        let unknown = FakeRuntimeVersionFetcher { spec_name: "polkadot", spec_version: 1_003_000 };
+        assert!(select_chain_runtime("test", &registry, &unknown, &config).await.is_err());
 
-pub struct PaseoPaidInEvent {
-    raw: paseo::omni_bridge::events::PaidIn,
-}
-
-impl PalletPaidInEvent for PaseoPaidInEvent {
-    type MetadataType = paseo::omni_bridge::events::PaidIn;
-
-    fn wrap(raw: Self::MetadataType) -> Self {
-        Self { raw }
-    }
-
-    fn amount(&self) -> u128 {
-        self.raw.amount
-    }
-
-    fn resource_id(&self) -> [u8; 32] {
-        self.raw.resource_id
-    }
-
-    fn dest_account(&self) -> Vec<u8> {
-        self.raw.dest_account.clone()
-    }
+        let unsupported = FakeRuntimeVersionFetcher { spec_name: "litentry-parachain", spec_version: 9100 };
+        assert!(select_chain_runtime("test", &registry, &unsupported, &config).await.is_err());
 
-    fn nonce(&self) -> u64 {
-        self.raw.nonce
+        // configured chain is validated against the node
+        config.chain = Some("paseo".to_string());
+        let heima = FakeRuntimeVersionFetcher { spec_name: "litentry-parachain", spec_version: 9241 };
+        assert!(select_chain_runtime("test", &registry, &heima, &config).await.is_err());
     }
 
-    fn dest_chain(&self) -> Vec<u8> {
-        self.raw.dest_chain.encode()
+    #[tokio::test]
+    async fn unknown_chain_fails_listener_creation() {
+        let mut config = config(None, None);
+        config.chain = Some("unknown".to_string());
+        let context = ListenerContext {
+            id: "unknown_listener".to_string(),
+            config,
+            start_block: Default::default(),
+            chain_id: 0,
+            relayers: HashMap::new(),
+            relayer_destinations: HashMap::new(),
+            control: Arc::new(ListenerControl::in_memory()),
+            status: Arc::new(ListenerStatus::new("unknown_listener", 0)),
+            checkpoint_batching: Default::default(),
+            dead_letter_log: Arc::new(DeadLetterLog::in_memory()),
+            skip_failed_events: false,
+            validators: HashMap::new(),
+            fetch_timeout: DEFAULT_FETCH_TIMEOUT,
+            catch_up_only: false,
+            nonce_tracker: Arc::new(NonceTracker::in_memory()),
+            halt_on_nonce_gap: false,
+            start_nonce: None,
+            poll_interval: DEFAULT_POLL_INTERVAL,
+            fast_sync_interval: Duration::ZERO,
+            resource_registry: Default::default(),
+            notifier: Default::default(),
+            data_lock: None,
+        };
+        let (_stop_sender, stop_receiver) = tokio::sync::oneshot::channel();
+
+        let result = create_listener_for_chain::<CustomConfig>(&context, Handle::current(), stop_receiver).await;
+
+        assert!(result.is_err());
     }
 }
@@ -15,6 +15,7 @@
 // along with Litentry.  If not, see <https://www.gnu.org/licenses/>.
 
 use bridge_core::listener::Listener;
+use bridge_core::rpc_auth::RpcAuth;
 use serde::Deserialize;
 
 use crate::fetcher::Fetcher;
@@ -29,5 +30,19 @@ pub type SubstrateListener<RpcClient, RpcClientFactory, CheckpointRepository> =
 #[derive(Deserialize)]
 pub struct ListenerConfig {
     pub ws_rpc_endpoint: String,
-    pub chain: String,
+    // selected by the node's runtime spec name if not set, needed only to tell apart chains sharing it
+    #[serde(default)]
+    pub chain: Option<String>,
+    // hex encoded, checked against the node at startup if set
+    #[serde(default)]
+    pub expected_genesis_hash: Option<String>,
+    #[serde(default)]
+    pub expected_spec_name: Option<String>,
+    #[serde(default)]
+    pub skip_startup_checks: bool,
+    #[serde(default)]
+    pub rpc_auth: Option<RpcAuth>,
+    // blocks listener stays behind the finalized head, guards against finality reversions
+    #[serde(default)]
+    pub confirmation_blocks: u64,
 }
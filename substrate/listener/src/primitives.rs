@@ -17,9 +17,10 @@
 use crate::listener::PayInEventId;
 use bridge_core::sync_checkpoint_repository::Checkpoint;
 use parity_scale_codec::{Decode, Encode};
+use std::fmt::{Display, Formatter};
 
 /// Used to uniquely identify `PayIn` event on substrate based chain.
-#[derive(Clone)]
+#[derive(Clone, Debug)]
 pub struct EventId {
     block_num: u64,
     event_idx: u64,
@@ -31,6 +32,12 @@ impl EventId {
     }
 }
 
+impl Display for EventId {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}-{}", self.block_num, self.event_idx)
+    }
+}
+
 /// Represents substrate based chain sync checkpoint.
 #[derive(Clone, Debug, PartialEq, Encode, Decode)]
 pub struct SyncCheckpoint {
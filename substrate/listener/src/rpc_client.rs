@@ -15,23 +15,41 @@
 // along with Litentry.  If not, see <https://www.gnu.org/licenses/>.
 
 use crate::primitives::EventId;
+use crate::runtime_compat::{decode_metadata, ensure_event_compatible};
 use crate::PalletPaidInEvent;
 use async_trait::async_trait;
+use bridge_core::rpc_auth::RpcAuth;
+use bridge_core::runtime_registry::{RuntimeVersion, RuntimeVersionFetcher};
+use bridge_core::timestamp_cache::BlockTimestampCache;
+use http::header::{HeaderMap, HeaderValue, AUTHORIZATION};
+use sp_core::blake2_256;
+use std::future::Future;
 use std::marker::PhantomData;
+use std::time::Duration;
+use subxt::backend::legacy::rpc_methods::RuntimeVersion as LegacyRuntimeVersion;
 use subxt::backend::legacy::LegacyRpcMethods;
 use subxt::backend::BlockRef;
 use subxt::config::Header;
-use subxt::events::EventsClient;
+use subxt::events::{EventsClient, Phase, StaticEvent};
+use subxt::ext::scale_value::{Composite, Value};
+use subxt::storage::StorageClient;
 use subxt::{Config, OnlineClient};
 
 pub struct BlockEvent<T> {
     pub id: EventId,
     pub event: T,
+    // hash of the extrinsic which emitted the event
+    pub extrinsic_hash: Option<[u8; 32]>,
 }
 
 impl<T> BlockEvent<T> {
     pub fn new(id: EventId, event: T) -> Self {
-        Self { id, event }
+        Self { id, event, extrinsic_hash: None }
+    }
+
+    pub fn with_extrinsic_hash(mut self, extrinsic_hash: Option<[u8; 32]>) -> Self {
+        self.extrinsic_hash = extrinsic_hash;
+        self
     }
 }
 
@@ -44,20 +62,64 @@ pub struct PaidInEvent {
     pub dest_chain: Vec<u8>,
 }
 
+/// Pay out executed on the destination chain
+pub struct PaidOutEvent {
+    pub amount: u128,
+    pub nonce: u64,
+}
+
 /// For fetching data from Substrate RPC node
 #[async_trait]
 pub trait SubstrateRpcClient {
     async fn get_last_finalized_block_num(&mut self) -> Result<u64, ()>;
     async fn get_block_pay_in_events(&mut self, block_num: u64) -> Result<Vec<BlockEvent<PaidInEvent>>, ()>;
+    async fn get_block_paid_out_events(&mut self, block_num: u64) -> Result<Vec<BlockEvent<PaidOutEvent>>, ()>;
+    // unix timestamp in seconds
+    async fn get_block_timestamp(&mut self, block_num: u64) -> Result<Option<u64>, ()>;
+    async fn get_genesis_hash(&mut self) -> Result<Vec<u8>, ()>;
+    async fn get_spec_name(&mut self) -> Result<String, ()>;
+
+    /// Whether the client should be recreated, e.g. after a runtime upgrade invalidated its metadata.
+    fn is_stale(&self) -> bool {
+        false
+    }
 }
 
 pub struct RpcClient<ChainConfig: Config, PalletPaidInEventType: PalletPaidInEvent> {
     legacy: LegacyRpcMethods<ChainConfig>,
     events: EventsClient<ChainConfig, OnlineClient<ChainConfig>>,
+    storage: StorageClient<ChainConfig, OnlineClient<ChainConfig>>,
+    // runtime spec version the client metadata was fetched for
+    spec_version: u32,
+    stale: bool,
+    timestamps: BlockTimestampCache,
     phantom_data: PhantomData<PalletPaidInEventType>,
 }
 
-impl<ChainConfig: Config, PalletPaidInEventType: PalletPaidInEvent> RpcClient<ChainConfig, PalletPaidInEventType> {}
+impl<ChainConfig: Config, PalletPaidInEventType: PalletPaidInEvent> RpcClient<ChainConfig, PalletPaidInEventType> {
+    async fn get_block_hash(&self, block_num: u64) -> Result<Option<ChainConfig::Hash>, ()> {
+        self.legacy.chain_get_block_hash(Some(block_num.into())).await.map_err(|e| {
+            log::error!("Get block {} hash error: {:?}", block_num, e);
+        })
+    }
+
+    // hashes of block extrinsics, all supported runtimes hash extrinsics with blake2
+    async fn get_block_extrinsic_hashes(&self, hash: ChainConfig::Hash) -> Result<Vec<[u8; 32]>, ()> {
+        let block = self.legacy.chain_get_block(Some(hash)).await.map_err(|e| {
+            log::error!("Get block {:?} error: {:?}", hash, e);
+        })?;
+        Ok(block
+            .map(|block| {
+                block
+                    .block
+                    .extrinsics
+                    .iter()
+                    .map(|extrinsic| blake2_256(&extrinsic.0))
+                    .collect()
+            })
+            .unwrap_or_default())
+    }
+}
 
 #[async_trait]
 impl<ChainConfig: Config, PalletPaidInEventType: PalletPaidInEvent> SubstrateRpcClient
@@ -67,6 +129,22 @@ impl<ChainConfig: Config, PalletPaidInEventType: PalletPaidInEvent> SubstrateRpc
         let finalized_header = self.legacy.chain_get_finalized_head().await.map_err(|e| {
             log::error!("Get finalized head error: {:?}", e);
         })?;
+        let runtime_version = self
+            .legacy
+            .state_get_runtime_version(Some(finalized_header))
+            .await
+            .map_err(|e| {
+                log::error!("Get runtime version error: {:?}", e);
+            })?;
+        if runtime_version.spec_version != self.spec_version {
+            log::warn!(
+                "Runtime upgraded from spec version {} to {}, reconnecting to check compatibility",
+                self.spec_version,
+                runtime_version.spec_version
+            );
+            self.stale = true;
+            return Err(());
+        }
         match self.legacy.chain_get_header(Some(finalized_header)).await.map_err(|e| {
             log::error!("Get header error: {:?}", e);
         })? {
@@ -83,14 +161,32 @@ impl<ChainConfig: Config, PalletPaidInEventType: PalletPaidInEvent> SubstrateRpc
                     log::error!("Get events at {:?} error: {:?}", block_num, e);
                 })?;
 
-                let pay_in_events = events.find::<PalletPaidInEventType::MetadataType>();
-
-                Ok(pay_in_events
-                    .enumerate()
-                    .map(|(i, event)| {
-                        let event: PalletPaidInEventType = PalletPaidInEventType::wrap(event.unwrap());
+                let mut extrinsic_hashes: Option<Vec<[u8; 32]>> = None;
+                let mut pay_in_events = vec![];
+                for details in events.iter() {
+                    let details = details.map_err(|e| {
+                        log::error!("Could not decode event at {:?}: {:?}", block_num, e);
+                    })?;
+                    let Some(event) = details.as_event::<PalletPaidInEventType::MetadataType>().map_err(|e| {
+                        log::error!("Could not decode PaidIn event at {:?}: {:?}", block_num, e);
+                    })?
+                    else {
+                        continue;
+                    };
+                    let event: PalletPaidInEventType = PalletPaidInEventType::wrap(event);
+                    // block body is fetched only for blocks with pay ins
+                    let extrinsic_hash = match details.phase() {
+                        Phase::ApplyExtrinsic(index) => {
+                            if extrinsic_hashes.is_none() {
+                                extrinsic_hashes = Some(self.get_block_extrinsic_hashes(hash).await?);
+                            }
+                            extrinsic_hashes.as_ref().and_then(|hashes| hashes.get(index as usize).copied())
+                        },
+                        _ => None,
+                    };
+                    pay_in_events.push(
                         BlockEvent::new(
-                            EventId::new(block_num, i as u64),
+                            EventId::new(block_num, pay_in_events.len() as u64),
                             PaidInEvent {
                                 amount: event.amount(),
                                 resource_id: event.resource_id(),
@@ -99,12 +195,97 @@ impl<ChainConfig: Config, PalletPaidInEventType: PalletPaidInEvent> SubstrateRpc
                                 dest_chain: event.dest_chain(),
                             },
                         )
-                    })
-                    .collect())
+                        .with_extrinsic_hash(extrinsic_hash),
+                    );
+                }
+                Ok(pay_in_events)
             },
             None => Err(()),
         }
     }
+
+    // decoded dynamically, `PaidOut` fields used here are the same in all supported runtimes
+    async fn get_block_paid_out_events(&mut self, block_num: u64) -> Result<Vec<BlockEvent<PaidOutEvent>>, ()> {
+        let hash = self.get_block_hash(block_num).await?.ok_or(())?;
+        let events = self.events.at(BlockRef::from_hash(hash)).await.map_err(|e| {
+            log::error!("Get events at {:?} error: {:?}", block_num, e);
+        })?;
+
+        let mut paid_out_events = vec![];
+        for event in events.iter() {
+            let event = event.map_err(|e| {
+                log::error!("Could not decode event at {:?}: {:?}", block_num, e);
+            })?;
+            if event.pallet_name() != "OmniBridge" || event.variant_name() != "PaidOut" {
+                continue;
+            }
+            let fields = event.field_values().map_err(|e| {
+                log::error!("Could not decode PaidOut event fields at {:?}: {:?}", block_num, e);
+            })?;
+            let amount = named_field(&fields, "amount").and_then(|amount| amount.as_u128());
+            let nonce = named_field(&fields, "nonce").and_then(|nonce| nonce.as_u128());
+            match (amount, nonce) {
+                (Some(amount), Some(nonce)) => paid_out_events.push(BlockEvent::new(
+                    EventId::new(block_num, paid_out_events.len() as u64),
+                    PaidOutEvent { amount, nonce: nonce as u64 },
+                )),
+                _ => {
+                    log::error!("Unexpected PaidOut event fields at {:?}: {:?}", block_num, fields);
+                    return Err(());
+                },
+            }
+        }
+        Ok(paid_out_events)
+    }
+
+    async fn get_block_timestamp(&mut self, block_num: u64) -> Result<Option<u64>, ()> {
+        if let Some(timestamp) = self.timestamps.get(block_num) {
+            return Ok(Some(timestamp));
+        }
+        let Some(hash) = self.get_block_hash(block_num).await? else {
+            return Ok(None);
+        };
+        let now = self
+            .storage
+            .at(BlockRef::from_hash(hash))
+            .fetch(&subxt::dynamic::storage("Timestamp", "Now", ()))
+            .await
+            .map_err(|e| {
+                log::error!("Get timestamp at {:?} error: {:?}", block_num, e);
+            })?;
+        match now {
+            Some(now) => {
+                let millis = now.to_value().ok().and_then(|now| now.as_u128()).ok_or_else(|| {
+                    log::error!("Unexpected timestamp at {:?}", block_num);
+                })?;
+                let timestamp = (millis / 1000) as u64;
+                self.timestamps.insert(block_num, timestamp);
+                Ok(Some(timestamp))
+            },
+            None => Ok(None),
+        }
+    }
+
+    async fn get_genesis_hash(&mut self) -> Result<Vec<u8>, ()> {
+        self.legacy
+            .genesis_hash()
+            .await
+            .map(|hash| hash.as_ref().to_vec())
+            .map_err(|e| {
+                log::error!("Get genesis hash error: {:?}", e);
+            })
+    }
+
+    async fn get_spec_name(&mut self) -> Result<String, ()> {
+        let runtime_version = self.legacy.state_get_runtime_version(None).await.map_err(|e| {
+            log::error!("Get runtime version error: {:?}", e);
+        })?;
+        spec_name(&runtime_version)
+    }
+
+    fn is_stale(&self) -> bool {
+        self.stale
+    }
 }
 
 #[async_trait]
@@ -112,15 +293,57 @@ pub trait SubstrateRpcClientFactory<RpcClient: SubstrateRpcClient> {
     async fn new_client(&self) -> Result<RpcClient, ()>;
 }
 
+pub const CONNECT_MAX_ATTEMPTS: u32 = 5;
+pub const CONNECT_INITIAL_BACKOFF: Duration = Duration::from_millis(500);
+
 pub struct RpcClientFactory<ChainConfig: Config> {
     url: String,
+    rpc_auth: Option<RpcAuth>,
     _phantom: PhantomData<ChainConfig>,
 }
 
 impl<ChainConfig: Config> RpcClientFactory<ChainConfig> {
-    pub fn new(url: &str) -> Self {
-        Self { url: url.to_string(), _phantom: PhantomData }
+    pub fn new(url: &str, rpc_auth: Option<RpcAuth>) -> Self {
+        Self { url: url.to_string(), rpc_auth, _phantom: PhantomData }
+    }
+
+    async fn connect(&self) -> Result<(LegacyRpcMethods<ChainConfig>, OnlineClient<ChainConfig>), ()> {
+        let mut builder = subxt::backend::rpc::reconnecting_rpc_client::RpcClient::builder();
+        if let Some(rpc_auth) = self.rpc_auth.as_ref() {
+            builder = builder.set_headers(authorization_headers(rpc_auth)?);
+        }
+        let rpc_client = builder.build(self.url.clone()).await.map_err(|e| {
+            log::error!("Could not create RpcClient: {:?}", e);
+        })?;
+        let legacy = LegacyRpcMethods::new(rpc_client.clone().into());
+        let online_client = OnlineClient::from_rpc_client(rpc_client).await.map_err(|e| {
+            log::error!("Could not create OnlineClient: {:?}", e);
+        })?;
+        Ok((legacy, online_client))
+    }
+}
+
+/// Calls `connect` until it succeeds or `max_attempts` is reached, doubling the delay between attempts.
+#[allow(clippy::result_unit_err)]
+pub async fn connect_with_backoff<T, F, Fut>(max_attempts: u32, initial_backoff: Duration, connect: F) -> Result<T, ()>
+where
+    F: Fn() -> Fut,
+    Fut: Future<Output = Result<T, ()>>,
+{
+    let mut backoff = initial_backoff;
+    for attempt in 1..=max_attempts {
+        match connect().await {
+            Ok(client) => return Ok(client),
+            Err(_) if attempt < max_attempts => {
+                log::warn!("Connection attempt {} of {} failed, retrying in {:?}", attempt, max_attempts, backoff);
+                tokio::time::sleep(backoff).await;
+                backoff *= 2;
+            },
+            Err(_) => {},
+        }
     }
+    log::error!("Could not connect after {} attempts", max_attempts);
+    Err(())
 }
 
 #[async_trait]
@@ -128,19 +351,105 @@ impl<ChainConfig: Config, PalletPaidInEventType: PalletPaidInEvent>
     SubstrateRpcClientFactory<RpcClient<ChainConfig, PalletPaidInEventType>> for RpcClientFactory<ChainConfig>
 {
     async fn new_client(&self) -> Result<RpcClient<ChainConfig, PalletPaidInEventType>, ()> {
-        let rpc_client = subxt::backend::rpc::reconnecting_rpc_client::RpcClient::builder()
-            .build(self.url.clone())
-            .await
-            .map_err(|e| {
-                log::error!("Could not create RpcClient: {:?}", e);
-            })?;
-        let legacy = LegacyRpcMethods::new(rpc_client.clone().into());
+        let (legacy, online_client) =
+            connect_with_backoff(CONNECT_MAX_ATTEMPTS, CONNECT_INITIAL_BACKOFF, || self.connect()).await?;
+        let spec_version = online_client.runtime_version().spec_version;
+        ensure_event_compatible(
+            PalletPaidInEventType::CHAIN,
+            spec_version,
+            &decode_metadata(PalletPaidInEventType::METADATA)?,
+            &online_client.metadata(),
+            <PalletPaidInEventType::MetadataType as StaticEvent>::PALLET,
+            <PalletPaidInEventType::MetadataType as StaticEvent>::EVENT,
+        )?;
+        let events = online_client.events();
+        let storage = online_client.storage();
 
-        let online_client = OnlineClient::from_rpc_client(rpc_client).await.map_err(|e| {
-            log::error!("Could not create OnlineClient: {:?}", e);
+        Ok(RpcClient {
+            legacy,
+            events,
+            storage,
+            spec_version,
+            stale: false,
+            timestamps: BlockTimestampCache::default(),
+            phantom_data: PhantomData,
+        })
+    }
+}
+
+#[async_trait]
+impl<ChainConfig: Config> RuntimeVersionFetcher for RpcClientFactory<ChainConfig> {
+    async fn runtime_version(&self) -> Result<RuntimeVersion, ()> {
+        let (legacy, _) =
+            connect_with_backoff(CONNECT_MAX_ATTEMPTS, CONNECT_INITIAL_BACKOFF, || self.connect()).await?;
+        let runtime_version = legacy.state_get_runtime_version(None).await.map_err(|e| {
+            log::error!("Get runtime version error: {:?}", e);
         })?;
-        let events = online_client.events();
+        Ok(RuntimeVersion { spec_name: spec_name(&runtime_version)?, spec_version: runtime_version.spec_version })
+    }
+}
+
+// spec name is not a typed field of the runtime version returned by legacy rpc
+fn spec_name(runtime_version: &LegacyRuntimeVersion) -> Result<String, ()> {
+    runtime_version
+        .other
+        .get("specName")
+        .and_then(|spec_name| spec_name.as_str())
+        .map(|spec_name| spec_name.to_string())
+        .ok_or_else(|| {
+            log::error!("Runtime version does not contain spec name");
+        })
+}
+
+fn named_field<'a>(fields: &'a Composite<u32>, name: &str) -> Option<&'a Value<u32>> {
+    match fields {
+        Composite::Named(fields) => fields.iter().find(|(field_name, _)| field_name == name).map(|(_, value)| value),
+        Composite::Unnamed(_) => None,
+    }
+}
+
+fn authorization_headers(rpc_auth: &RpcAuth) -> Result<HeaderMap, ()> {
+    let mut authorization = HeaderValue::from_str(&rpc_auth.authorization_header()?).map_err(|e| {
+        log::error!("Invalid rpc authorization header: {:?}", e);
+    })?;
+    authorization.set_sensitive(true);
+    Ok(HeaderMap::from_iter([(AUTHORIZATION, authorization)]))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::connect_with_backoff;
+    use std::sync::atomic::{AtomicU32, Ordering};
+    use std::time::Duration;
+
+    #[tokio::test]
+    async fn connect_is_retried_until_success() {
+        let attempts = AtomicU32::new(0);
+
+        let result = connect_with_backoff(5, Duration::from_millis(1), || async {
+            if attempts.fetch_add(1, Ordering::SeqCst) < 2 {
+                Err(())
+            } else {
+                Ok("client")
+            }
+        })
+        .await;
+
+        assert_eq!(result, Ok("client"));
+        assert_eq!(attempts.load(Ordering::SeqCst), 3);
+    }
+
+    #[tokio::test]
+    async fn connect_gives_up_after_max_attempts() {
+        let attempts = AtomicU32::new(0);
+
+        let result: Result<(), ()> = connect_with_backoff(3, Duration::from_millis(1), || async {
+            attempts.fetch_add(1, Ordering::SeqCst);
+            Err(())
+        })
+        .await;
 
-        Ok(RpcClient { legacy, events, phantom_data: PhantomData })
+        assert_eq!(result, Err(()));
+        assert_eq!(attempts.load(Ordering::SeqCst), 3);
     }
 }
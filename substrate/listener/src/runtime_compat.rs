@@ -0,0 +1,241 @@
+// Copyright 2020-2024 Trust Computing GmbH.
+// This file is part of Litentry.
+//
+// Litentry is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// Litentry is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with Litentry.  If not, see <https://www.gnu.org/licenses/>.
+
+//! Detects drift between the metadata subxt types were generated from and the metadata of a live runtime,
+//! so a runtime upgrade changing the shape of the bridge event stops the listener instead of silently
+//! mis-decoding pay ins.
+
+use log::error;
+use metrics::gauge;
+use scale_info::form::PortableForm;
+use scale_info::{Field, PortableRegistry, TypeDef};
+use std::collections::HashSet;
+use subxt::ext::codec::Decode;
+use subxt::Metadata;
+
+/// Set to 1 for a chain whose runtime is incompatible with the bridge, 0 otherwise.
+pub const INCOMPATIBLE_RUNTIME_GAUGE: &str = "incompatible_runtime";
+
+#[allow(clippy::result_unit_err)]
+pub fn decode_metadata(bytes: &[u8]) -> Result<Metadata, ()> {
+    Metadata::decode(&mut &bytes[..]).map_err(|e| {
+        error!("Could not decode metadata: {:?}", e);
+    })
+}
+
+/// Checks that `pallet`'s `event` has the same shape in `on_chain` metadata as in `generated` metadata,
+/// updating `INCOMPATIBLE_RUNTIME_GAUGE` for `chain` with the outcome.
+#[allow(clippy::result_unit_err)]
+pub fn ensure_event_compatible(
+    chain: &str,
+    spec_version: u32,
+    generated: &Metadata,
+    on_chain: &Metadata,
+    pallet: &str,
+    event: &str,
+) -> Result<(), ()> {
+    let result = check_event_compatible(generated, on_chain, pallet, event);
+    if result.is_err() {
+        error!(
+            "Runtime spec version {} of chain {} is incompatible with event {}.{}, stopping relaying",
+            spec_version, chain, pallet, event
+        );
+    }
+    gauge!(INCOMPATIBLE_RUNTIME_GAUGE, "chain" => chain.to_string(), "component" => "listener")
+        .set(result.is_err() as u8 as f64);
+    result
+}
+
+fn check_event_compatible(generated: &Metadata, on_chain: &Metadata, pallet: &str, event: &str) -> Result<(), ()> {
+    let generated_variant = generated
+        .pallet_by_name(pallet)
+        .and_then(|pallet| pallet.event_variants())
+        .and_then(|variants| variants.iter().find(|variant| variant.name == event))
+        .ok_or_else(|| {
+            error!("Event {}.{} is missing in generated metadata", pallet, event);
+        })?;
+    let Some(on_chain_pallet) = on_chain.pallet_by_name(pallet) else {
+        error!("Pallet {} is missing in on-chain metadata", pallet);
+        return Err(());
+    };
+    let Some(on_chain_variant) = on_chain_pallet
+        .event_variants()
+        .and_then(|variants| variants.iter().find(|variant| variant.name == event))
+    else {
+        error!("Event {}.{} is missing in on-chain metadata", pallet, event);
+        return Err(());
+    };
+
+    let mut visited = HashSet::new();
+    for (index, generated_field) in generated_variant.fields.iter().enumerate() {
+        let on_chain_field = on_chain_variant.fields.get(index);
+        if !on_chain_field.is_some_and(|on_chain_field| {
+            same_field(generated.types(), generated_field, on_chain.types(), on_chain_field, &mut visited)
+        }) {
+            error!(
+                "Field {} of event {}.{} differs from on-chain metadata",
+                generated_field.name.as_deref().unwrap_or(&index.to_string()),
+                pallet,
+                event
+            );
+            return Err(());
+        }
+    }
+    if generated_variant.fields.len() != on_chain_variant.fields.len() {
+        error!(
+            "Event {}.{} has {} fields on-chain, expected {}",
+            pallet,
+            event,
+            on_chain_variant.fields.len(),
+            generated_variant.fields.len()
+        );
+        return Err(());
+    }
+    Ok(())
+}
+
+fn same_field(
+    generated: &PortableRegistry,
+    generated_field: &Field<PortableForm>,
+    on_chain: &PortableRegistry,
+    on_chain_field: &Field<PortableForm>,
+    visited: &mut HashSet<(u32, u32)>,
+) -> bool {
+    generated_field.name == on_chain_field.name
+        && same_type(generated, generated_field.ty.id, on_chain, on_chain_field.ty.id, visited)
+}
+
+fn same_fields(
+    generated: &PortableRegistry,
+    generated_fields: &[Field<PortableForm>],
+    on_chain: &PortableRegistry,
+    on_chain_fields: &[Field<PortableForm>],
+    visited: &mut HashSet<(u32, u32)>,
+) -> bool {
+    generated_fields.len() == on_chain_fields.len()
+        && generated_fields
+            .iter()
+            .zip(on_chain_fields)
+            .all(|(generated_field, on_chain_field)| {
+                same_field(generated, generated_field, on_chain, on_chain_field, visited)
+            })
+}
+
+// structural comparison, type ids differ between metadata versions so types are compared by shape
+fn same_type(
+    generated: &PortableRegistry,
+    generated_id: u32,
+    on_chain: &PortableRegistry,
+    on_chain_id: u32,
+    visited: &mut HashSet<(u32, u32)>,
+) -> bool {
+    // recursive types are assumed equal once their pair is being compared
+    if !visited.insert((generated_id, on_chain_id)) {
+        return true;
+    }
+    let (Some(generated_ty), Some(on_chain_ty)) = (generated.resolve(generated_id), on_chain.resolve(on_chain_id))
+    else {
+        return false;
+    };
+    match (&generated_ty.type_def, &on_chain_ty.type_def) {
+        (TypeDef::Composite(a), TypeDef::Composite(b)) => {
+            same_fields(generated, &a.fields, on_chain, &b.fields, visited)
+        },
+        (TypeDef::Variant(a), TypeDef::Variant(b)) => {
+            a.variants.len() == b.variants.len()
+                && a.variants.iter().all(|variant| {
+                    b.variants.iter().find(|other| other.name == variant.name).is_some_and(|other| {
+                        other.index == variant.index
+                            && same_fields(generated, &variant.fields, on_chain, &other.fields, visited)
+                    })
+                })
+        },
+        (TypeDef::Sequence(a), TypeDef::Sequence(b)) => {
+            same_type(generated, a.type_param.id, on_chain, b.type_param.id, visited)
+        },
+        (TypeDef::Array(a), TypeDef::Array(b)) => {
+            a.len == b.len && same_type(generated, a.type_param.id, on_chain, b.type_param.id, visited)
+        },
+        (TypeDef::Tuple(a), TypeDef::Tuple(b)) => {
+            a.fields.len() == b.fields.len()
+                && a.fields
+                    .iter()
+                    .zip(&b.fields)
+                    .all(|(a, b)| same_type(generated, a.id, on_chain, b.id, visited))
+        },
+        (TypeDef::Primitive(a), TypeDef::Primitive(b)) => a == b,
+        (TypeDef::Compact(a), TypeDef::Compact(b)) => {
+            same_type(generated, a.type_param.id, on_chain, b.type_param.id, visited)
+        },
+        (TypeDef::BitSequence(a), TypeDef::BitSequence(b)) => {
+            same_type(generated, a.bit_store_type.id, on_chain, b.bit_store_type.id, visited)
+                && same_type(generated, a.bit_order_type.id, on_chain, b.bit_order_type.id, visited)
+        },
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{decode_metadata, ensure_event_compatible};
+
+    const LOCAL_METADATA: &[u8] = include_bytes!("../../artifacts/local.scale");
+
+    // renames every occurrence of a scale encoded string, keeping its length so the blob still decodes
+    fn tampered_metadata(from: &str, to: &str) -> Vec<u8> {
+        assert_eq!(from.len(), to.len());
+        let mut from_encoded = vec![(from.len() as u8) << 2];
+        from_encoded.extend_from_slice(from.as_bytes());
+        let mut bytes = LOCAL_METADATA.to_vec();
+        let mut index = 0;
+        let mut replaced = 0;
+        while index + from_encoded.len() <= bytes.len() {
+            if bytes[index..].starts_with(&from_encoded) {
+                bytes[index + 1..index + from_encoded.len()].copy_from_slice(to.as_bytes());
+                index += from_encoded.len();
+                replaced += 1;
+            } else {
+                index += 1;
+            }
+        }
+        assert!(replaced > 0);
+        bytes
+    }
+
+    #[test]
+    fn unchanged_metadata_is_compatible() {
+        let generated = decode_metadata(LOCAL_METADATA).unwrap();
+        let on_chain = decode_metadata(LOCAL_METADATA).unwrap();
+
+        assert_eq!(ensure_event_compatible("local", 1, &generated, &on_chain, "OmniBridge", "PaidIn"), Ok(()));
+    }
+
+    #[test]
+    fn missing_event_is_incompatible() {
+        let generated = decode_metadata(LOCAL_METADATA).unwrap();
+        let on_chain = decode_metadata(&tampered_metadata("PaidIn", "PaidIx")).unwrap();
+
+        assert_eq!(ensure_event_compatible("local", 2, &generated, &on_chain, "OmniBridge", "PaidIn"), Err(()));
+    }
+
+    #[test]
+    fn changed_event_field_is_incompatible() {
+        let generated = decode_metadata(LOCAL_METADATA).unwrap();
+        let on_chain = decode_metadata(&tampered_metadata("amount", "amounx")).unwrap();
+
+        assert_eq!(ensure_event_compatible("local", 2, &generated, &on_chain, "OmniBridge", "PaidIn"), Err(()));
+    }
+}
@@ -0,0 +1,116 @@
+// Copyright 2020-2024 Trust Computing GmbH.
+// This file is part of Litentry.
+//
+// Litentry is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// Litentry is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with Litentry.  If not, see <https://www.gnu.org/licenses/>.
+
+use log::error;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
+
+/// Asset paid out for a resource, mirrors the pallet's `NativeOrWithId`. Written as `native` or `with_id:<id>`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(try_from = "String", into = "String")]
+pub enum PayOutAsset {
+    Native,
+    WithId(u32),
+}
+
+impl FromStr for PayOutAsset {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.split_once(':') {
+            None if s == "native" => Ok(Self::Native),
+            Some(("with_id", id)) => id
+                .parse()
+                .map(Self::WithId)
+                .map_err(|e| format!("Invalid asset id {}: {}", id, e)),
+            _ => Err(format!("Invalid asset {}, expected native or with_id:<u32>", s)),
+        }
+    }
+}
+
+impl TryFrom<String> for PayOutAsset {
+    type Error = String;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        value.parse()
+    }
+}
+
+impl From<PayOutAsset> for String {
+    fn from(asset: PayOutAsset) -> Self {
+        asset.to_string()
+    }
+}
+
+impl Display for PayOutAsset {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Native => write!(f, "native"),
+            Self::WithId(id) => write!(f, "with_id:{}", id),
+        }
+    }
+}
+
+/// Parses assets configured per hex encoded resource id.
+#[allow(clippy::result_unit_err)]
+pub fn parse_asset_hints(assets: &HashMap<String, PayOutAsset>) -> Result<HashMap<[u8; 32], PayOutAsset>, ()> {
+    assets
+        .iter()
+        .map(|(resource_id, asset)| {
+            hex::decode(resource_id.trim_start_matches("0x"))
+                .ok()
+                .and_then(|bytes| bytes.try_into().ok())
+                .map(|resource_id| (resource_id, *asset))
+                .ok_or_else(|| error!("Invalid resource id in assets: {}", resource_id))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{parse_asset_hints, PayOutAsset};
+    use std::collections::HashMap;
+
+    #[test]
+    fn asset_is_parsed() {
+        assert_eq!("native".parse(), Ok(PayOutAsset::Native));
+        assert_eq!("with_id:7".parse(), Ok(PayOutAsset::WithId(7)));
+        assert!("with_id:".parse::<PayOutAsset>().is_err());
+        assert!("with_id:-1".parse::<PayOutAsset>().is_err());
+        assert!("foreign:1".parse::<PayOutAsset>().is_err());
+        assert!("native:1".parse::<PayOutAsset>().is_err());
+    }
+
+    #[test]
+    fn asset_round_trips_through_config() {
+        let assets: HashMap<String, PayOutAsset> =
+            serde_json::from_str(r#"{"0x01": "native", "02": "with_id:1984"}"#).unwrap();
+
+        assert_eq!(assets["0x01"], PayOutAsset::Native);
+        assert_eq!(serde_json::to_string(&assets["02"]).unwrap(), r#""with_id:1984""#);
+    }
+
+    #[test]
+    fn asset_hints_are_keyed_by_resource_id() {
+        let resource_id = [1; 32];
+        let assets = HashMap::from([(format!("0x{}", hex::encode(resource_id)), PayOutAsset::WithId(1984))]);
+
+        assert_eq!(parse_asset_hints(&assets), Ok(HashMap::from([(resource_id, PayOutAsset::WithId(1984))])));
+        assert!(parse_asset_hints(&HashMap::from([("0x01".to_string(), PayOutAsset::Native)])).is_err());
+    }
+}
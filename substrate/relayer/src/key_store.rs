@@ -15,7 +15,28 @@
 // along with Litentry.  If not, see <https://www.gnu.org/licenses/>.
 
 use bridge_core::key_store::KeyStore;
-use subxt_signer::sr25519::SecretKeyBytes;
+use log::error;
+use subxt::tx::Signer;
+use subxt::utils::{AccountId32, MultiAddress, MultiSignature};
+use subxt::PolkadotConfig;
+use subxt_signer::{ecdsa, sr25519};
+
+// sr25519 keys are stored untagged, so key files written before ecdsa support are still read as sr25519
+const ECDSA_KEY_TAG: u8 = 1;
+
+/// Signature scheme of substrate relayer's account.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SubstrateKeyType {
+    Sr25519,
+    Ecdsa,
+}
+
+/// Secret key of substrate relayer's account together with its type.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SubstrateKey {
+    pub key_type: SubstrateKeyType,
+    pub secret: [u8; 32],
+}
 
 /// Generates and stores keys used by `SubstrateRelayer`
 pub struct SubstrateKeyStore {
@@ -28,24 +49,144 @@ impl SubstrateKeyStore {
     }
 }
 
-impl KeyStore<SecretKeyBytes> for SubstrateKeyStore {
+impl KeyStore<SubstrateKey> for SubstrateKeyStore {
     // unused
-    fn generate_key() -> Result<SecretKeyBytes, ()> {
-        Ok([
-            45, 219, 105, 155, 49, 74, 164, 131, 153, 192, 15, 213, 225, 179, 167, 129, 12, 160, 229, 37, 133, 168,
-            141, 233, 98, 117, 254, 112, 139, 210, 76, 6,
-        ])
+    fn generate_key() -> Result<SubstrateKey, ()> {
+        Ok(SubstrateKey {
+            key_type: SubstrateKeyType::Sr25519,
+            secret: [
+                45, 219, 105, 155, 49, 74, 164, 131, 153, 192, 15, 213, 225, 179, 167, 129, 12, 160, 229, 37, 133, 168,
+                141, 233, 98, 117, 254, 112, 139, 210, 76, 6,
+            ],
+        })
     }
 
-    fn serialize(k: &SecretKeyBytes) -> Result<Vec<u8>, ()> {
-        Ok(Vec::from(k))
+    fn serialize(k: &SubstrateKey) -> Result<Vec<u8>, ()> {
+        let mut sealed = match k.key_type {
+            SubstrateKeyType::Sr25519 => Vec::new(),
+            SubstrateKeyType::Ecdsa => vec![ECDSA_KEY_TAG],
+        };
+        sealed.extend_from_slice(&k.secret);
+        Ok(sealed)
     }
 
-    fn deserialize(sealed: Vec<u8>) -> Result<SecretKeyBytes, ()> {
-        sealed.as_slice().try_into().map_err(|_| ())
+    fn deserialize(sealed: Vec<u8>) -> Result<SubstrateKey, ()> {
+        let (key_type, secret) = match sealed.as_slice() {
+            [ECDSA_KEY_TAG, secret @ ..] if secret.len() == 32 => (SubstrateKeyType::Ecdsa, secret),
+            secret => (SubstrateKeyType::Sr25519, secret),
+        };
+        let secret = secret.try_into().map_err(|_| {
+            error!("Invalid substrate key of {} bytes", sealed.len());
+        })?;
+        Ok(SubstrateKey { key_type, secret })
     }
 
     fn path(&self) -> String {
         self.path.clone()
     }
 }
+
+/// Signs extrinsics with relayer's key according to its type.
+pub enum SubstrateSigner {
+    Sr25519(sr25519::Keypair),
+    Ecdsa(ecdsa::Keypair),
+}
+
+impl SubstrateSigner {
+    #[allow(clippy::result_unit_err)]
+    pub fn from_key(key: &SubstrateKey) -> Result<Self, ()> {
+        match key.key_type {
+            SubstrateKeyType::Sr25519 => sr25519::Keypair::from_secret_key(key.secret)
+                .map(SubstrateSigner::Sr25519)
+                .map_err(|e| error!("Could not create sr25519 secret key: {:?}", e)),
+            SubstrateKeyType::Ecdsa => ecdsa::Keypair::from_secret_key(key.secret)
+                .map(SubstrateSigner::Ecdsa)
+                .map_err(|e| error!("Could not create ecdsa secret key: {:?}", e)),
+        }
+    }
+
+    /// Account of the key, ecdsa accounts are blake2 hash of the compressed public key.
+    pub fn account_id(&self) -> AccountId32 {
+        match self {
+            SubstrateSigner::Sr25519(keypair) => keypair.public_key().to_account_id(),
+            SubstrateSigner::Ecdsa(keypair) => keypair.public_key().to_account_id(),
+        }
+    }
+}
+
+impl Signer<PolkadotConfig> for SubstrateSigner {
+    fn account_id(&self) -> AccountId32 {
+        SubstrateSigner::account_id(self)
+    }
+
+    fn address(&self) -> MultiAddress<AccountId32, ()> {
+        MultiAddress::Id(SubstrateSigner::account_id(self))
+    }
+
+    fn sign(&self, signer_payload: &[u8]) -> MultiSignature {
+        match self {
+            SubstrateSigner::Sr25519(keypair) => keypair.sign(signer_payload).into(),
+            SubstrateSigner::Ecdsa(keypair) => keypair.sign(signer_payload).into(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Alice
+    const SR25519_KEY: [u8; 32] = [
+        229, 190, 154, 80, 146, 184, 27, 202, 100, 190, 129, 210, 18, 231, 242, 249, 235, 161, 131, 187, 122, 144, 149,
+        79, 123, 118, 54, 31, 110, 219, 92, 10,
+    ];
+    const SR25519_ADDRESS: &str = "5GrwvaEF5zXb26Fz9rcQpDWS57CtERHpNehXCPcNoHGKutQY";
+
+    #[test]
+    pub fn untagged_key_is_read_as_sr25519() {
+        let key = SubstrateKeyStore::deserialize(SR25519_KEY.to_vec()).unwrap();
+        assert_eq!(key, SubstrateKey { key_type: SubstrateKeyType::Sr25519, secret: SR25519_KEY });
+        assert_eq!(SubstrateKeyStore::serialize(&key).unwrap(), SR25519_KEY.to_vec());
+    }
+
+    #[test]
+    pub fn tagged_key_is_read_as_ecdsa() {
+        let key = SubstrateKey { key_type: SubstrateKeyType::Ecdsa, secret: SR25519_KEY };
+        let sealed = SubstrateKeyStore::serialize(&key).unwrap();
+        assert_eq!(sealed.len(), 33);
+        assert_eq!(SubstrateKeyStore::deserialize(sealed).unwrap(), key);
+    }
+
+    #[test]
+    pub fn key_of_invalid_length_is_rejected() {
+        assert!(SubstrateKeyStore::deserialize(vec![1; 31]).is_err());
+        assert!(SubstrateKeyStore::deserialize(vec![2; 33]).is_err());
+        assert!(SubstrateKeyStore::deserialize(vec![1; 34]).is_err());
+    }
+
+    #[test]
+    pub fn sr25519_signer_derives_sr25519_account() {
+        let key = SubstrateKey { key_type: SubstrateKeyType::Sr25519, secret: SR25519_KEY };
+        let signer = SubstrateSigner::from_key(&key).unwrap();
+
+        assert!(matches!(signer, SubstrateSigner::Sr25519(_)));
+        assert_eq!(signer.account_id().to_string(), SR25519_ADDRESS);
+        assert!(matches!(Signer::<PolkadotConfig>::sign(&signer, b"payload"), MultiSignature::Sr25519(_)));
+    }
+
+    #[test]
+    pub fn ecdsa_signer_derives_ecdsa_account() {
+        let alice = ecdsa::dev::alice();
+        let key = SubstrateKey { key_type: SubstrateKeyType::Ecdsa, secret: alice.secret_key() };
+        let signer = SubstrateSigner::from_key(&key).unwrap();
+
+        assert_eq!(signer.account_id(), alice.public_key().to_account_id());
+        assert_ne!(signer.account_id().to_string(), SR25519_ADDRESS);
+        match Signer::<PolkadotConfig>::sign(&signer, b"payload") {
+            MultiSignature::Ecdsa(signature) => {
+                assert!(ecdsa::verify(&ecdsa::Signature(signature), b"payload", &alice.public_key()))
+            },
+            signature => panic!("Expected ecdsa signature, got {:?}", signature),
+        }
+    }
+}
@@ -14,26 +14,59 @@
 // You should have received a copy of the GNU General Public License
 // along with Litentry.  If not, see <https://www.gnu.org/licenses/>.
 
-use crate::key_store::SubstrateKeyStore;
+use crate::asset::{parse_asset_hints, PayOutAsset};
+use crate::key_store::{SubstrateKeyStore, SubstrateSigner};
+use crate::nonce_store::{FileNonceStore, InMemoryNonceStore, NonceStore};
+use crate::offline_signing::{OfflineSigningConfig, SigningQueue, SigningRequest};
+use crate::submit_failure::{classify_submit_error, classify_watch_error, SubmitFailure};
 use async_trait::async_trait;
+use bridge_core::amount_limit::{
+    default_payouts_file, unix_now, AmountBounds, AmountLimit, AmountLimiter, AmountOutOfBounds,
+};
+use bridge_core::destination_allowlist::DestinationAllowlist;
 use bridge_core::key_store::KeyStore;
-use bridge_core::relay::{RelayError, Relayer};
+use bridge_core::notification::{Notification, NotificationType, Notifier};
+use bridge_core::relay::{
+    with_relay_timeout, with_watch_timeout, Recipient, RelayError, RelayRequest, Relayer, DEFAULT_WATCH_TIMEOUT,
+};
+use bridge_core::relay_queue::{RelayQueue, DEFAULT_MAX_QUEUED_PER_SOURCE};
+use bridge_core::resource_allowlist::{ResourceAllowlist, FILTERED_RESOURCE_COUNTER};
+use bridge_core::rpc_auth::RpcAuth;
+use bridge_core::runtime_registry::{
+    resolve_runtime, RegisteredRuntime, RuntimeSelectionError, RuntimeVersion, RuntimeVersionFetcher,
+};
+use http::header::{HeaderMap, HeaderValue, AUTHORIZATION};
 use log::*;
+use metrics::{counter, gauge};
 use serde::Deserialize;
 #[cfg(test)]
 use serde::Serialize;
 use std::collections::HashMap;
 use std::fmt::Debug;
+use std::future::Future;
 use std::marker::PhantomData;
+use std::ops::RangeInclusive;
+use std::pin::Pin;
+use std::str::FromStr;
 use std::sync::Arc;
+use std::time::Duration;
+use subxt::config::polkadot::{PolkadotExtrinsicParams, PolkadotExtrinsicParamsBuilder};
+use subxt::config::ExtrinsicParams;
+use subxt::ext::scale_value::At;
 use subxt::ext::subxt_core::tx::payload::StaticPayload;
-use subxt::tx::Payload;
-use subxt::utils::AccountId32;
-use subxt::{Config, OnlineClient, PolkadotConfig};
+use subxt::storage::{Address, StaticAddress, StaticStorageKey};
+use subxt::tx::{Payload, TxProgress};
+use subxt::utils::{AccountId32, MultiAddress, MultiSignature, Yes};
+use subxt::{Config, Metadata, OnlineClient, PolkadotConfig};
 use subxt_signer::bip39::serde;
 use tokio::sync::Mutex;
+use tokio::time::Instant;
 
+pub mod asset;
 pub mod key_store;
+pub mod nonce_store;
+pub mod offline_signing;
+pub mod submit_failure;
 
 // Generate an interface that we can use from the node's metadata.
 #[subxt::subxt(runtime_metadata_path = "../artifacts/paseo.scale")]
@@ -47,82 +80,337 @@ pub mod local {}
 
 pub type CONF = PolkadotConfig;
 
+pub const DEFAULT_RELAY_TIMEOUT: Duration = Duration::from_secs(120);
+
+pub const RELAYER_REGISTERED_GAUGE: &str = "relayer_registered";
+
+/// Set to 1 for a chain whose runtime no longer accepts the pay out request call, 0 otherwise.
+pub const INCOMPATIBLE_RUNTIME_GAUGE: &str = "incompatible_runtime";
+
+/// Set to 1 while the destination's OmniBridge pallet is paused, labelled by destination id.
+pub const DESTINATION_PAUSED_GAUGE: &str = "destination_paused";
+
+/// How often the pallet's paused state is re-checked, short so relaying resumes soon after unpause.
+pub const PAUSE_CHECK_INTERVAL: Duration = Duration::from_secs(10);
+
+/// Storage entry of the pallet's paused flag. It's looked up in the node's metadata instead of the generated one,
+/// pallets without it are never paused.
+pub const PAUSED_STORAGE_ENTRY: &str = "Paused";
+
 #[derive(Deserialize)]
 #[cfg_attr(test, derive(Serialize))]
 pub struct RelayerConfig {
     pub ws_rpc_endpoint: String,
-    pub chain: String,
+    // selected by the node's runtime spec name if not set, needed only to tell apart chains sharing it
+    #[serde(default)]
+    pub chain: Option<String>,
+    // keyed by hex encoded resource id
+    #[serde(default)]
+    pub amount_limits: HashMap<String, AmountLimit>,
+    // payouts counted towards `amount_limits` windows, `data/<relayer id>_relayer_payouts.bin` if not set
+    #[serde(default)]
+    pub payouts_file: Option<String>,
+    // relaying attempt exceeding the timeout is treated as transport error and retried
+    #[serde(default = "default_relay_timeout_secs")]
+    pub relay_timeout_secs: u64,
+    // waiting for pay out request finalization exceeding the timeout is treated as watch error and retried
+    #[serde(default = "default_watch_timeout_secs")]
+    pub watch_timeout_secs: u64,
+    // `min_amount` and `max_amount` of every relayed request
+    #[serde(flatten)]
+    pub amount_bounds: AmountBounds,
+    #[serde(default)]
+    pub rpc_auth: Option<RpcAuth>,
+    // SS58 encoded accounts, if set requests to any other account are not relayed
+    #[serde(default)]
+    pub allowed_destinations: Option<Vec<String>>,
+    // paid to block author on top of the fee, helps pay out requests to get included when transaction queue is congested
+    #[serde(default)]
+    pub tip: u128,
+    // asset paid out per hex encoded resource id, passed on to `PayOutRequestCallFactory`
+    #[serde(default)]
+    pub assets: HashMap<String, PayOutAsset>,
+    // if set, pay out requests are signed on an offline machine instead of with the key in keystore
+    #[serde(default)]
+    pub offline_signing: Option<OfflineSigningConfig>,
+    // hex encoded resource ids, if not empty requests of any other resource are skipped
+    #[serde(default)]
+    pub allowed_resource_ids: Vec<String>,
+    // pay out requests of a single listener waiting for their turn, listeners sharing the relayer take turns
+    #[serde(default = "default_max_queued_per_listener")]
+    pub max_queued_per_listener: usize,
+}
+
+impl RelayerConfig {
+    #[allow(clippy::result_unit_err)]
+    pub fn destination_allowlist(&self) -> Result<DestinationAllowlist, ()> {
+        let Some(allowed_destinations) = &self.allowed_destinations else {
+            return Ok(DestinationAllowlist::unrestricted());
+        };
+        let allowed = allowed_destinations
+            .iter()
+            .map(|destination| {
+                AccountId32::from_str(destination)
+                    .map(|account| account.0.to_vec())
+                    .map_err(|e| error!("Invalid allowed destination: {}, {:?}", destination, e))
+            })
+            .collect::<Result<Vec<_>, ()>>()?;
+        Ok(DestinationAllowlist::new(allowed))
+    }
+
+    #[allow(clippy::result_unit_err)]
+    pub fn resource_allowlist(&self) -> Result<ResourceAllowlist, ()> {
+        ResourceAllowlist::from_hex(&self.allowed_resource_ids)
+    }
+}
+
+fn default_relay_timeout_secs() -> u64 {
+    DEFAULT_RELAY_TIMEOUT.as_secs()
+}
+
+fn default_max_queued_per_listener() -> usize {
+    DEFAULT_MAX_QUEUED_PER_SOURCE
+}
+
+fn default_watch_timeout_secs() -> u64 {
+    DEFAULT_WATCH_TIMEOUT.as_secs()
 }
 
 /// Relays bridge request to substrate node's OmniBridge pallet.
-pub struct SubstrateRelayer<T: Config, PRCF: PayOutRequestCallFactory> {
-    rpc_url: String,
-    key_store: SubstrateKeyStore,
+pub struct SubstrateRelayer<PRCF: PayOutRequestCallFactory, S: ExtrinsicSubmitter<PRCF::PayOutRequestCallType>> {
     payout_request_call_factory: PRCF,
+    submitter: S,
     destination_id: String,
-    relay_lock: Mutex<()>,
-    _phantom: PhantomData<T>,
+    amount_limiter: AmountLimiter,
+    amount_bounds: AmountBounds,
+    relay_timeout: Duration,
+    nonce_store: Box<dyn NonceStore>,
+    destination_allowlist: DestinationAllowlist,
+    resource_allowlist: ResourceAllowlist,
+    asset_hints: HashMap<[u8; 32], PayOutAsset>,
+    relay_queue: RelayQueue,
+    // pallet is assumed to be never paused if not set
+    pause_state_fetcher: Option<Box<dyn PauseStateFetcher>>,
+    // time of the last paused state check and its result
+    pause_check: std::sync::Mutex<(Option<Instant>, bool)>,
 }
 
-pub fn create_from_config<T: Config>(
+pub async fn create_from_config<T: Config>(
     keystore_dir: String,
     config_relayers: &[bridge_core::config::Relayer],
-) -> HashMap<String, Arc<Box<dyn Relayer<String>>>> {
+    notifier: Arc<Notifier>,
+) -> Result<HashMap<String, Arc<Box<dyn Relayer<String>>>>, ()> {
     let mut relayers: HashMap<String, Arc<Box<dyn Relayer<String>>>> = HashMap::new();
     for relayer_config in config_relayers.iter().filter(|r| r.relayer_type == "substrate") {
         let key_store = SubstrateKeyStore::new(format!("{}/{}.bin", keystore_dir.clone(), relayer_config.id));
+        let substrate_relayer_config: RelayerConfig = relayer_config.to_specific_config();
 
-        let signer = subxt_signer::sr25519::Keypair::from_secret_key(key_store.read().unwrap())
-            .map_err(|e| {
-                error!("Could not create secret key: {:?}", e);
-            })
-            .unwrap();
+        // offline signing relayers have no key in keystore
+        let account = match substrate_relayer_config.offline_signing {
+            Some(ref offline_signing) => AccountId32::from_str(&offline_signing.account).map_err(|e| {
+                error!("Invalid offline signing account of relayer {}: {:?}", relayer_config.id, e);
+            })?,
+            None => SubstrateSigner::from_key(&key_store.read()?)?.account_id(),
+        };
+        info!("Substrate relayer address: {}", account);
 
-        info!("Substrate relayer address: {}", signer.public_key().to_account_id());
+        let destination_allowlist = substrate_relayer_config.destination_allowlist()?;
+        let resource_allowlist = substrate_relayer_config.resource_allowlist()?;
+        let asset_hints = parse_asset_hints(&substrate_relayer_config.assets)?;
+        let amount_limiter = AmountLimiter::new(
+            &substrate_relayer_config.amount_limits,
+            Some(
+                substrate_relayer_config
+                    .payouts_file
+                    .clone()
+                    .unwrap_or_else(|| default_payouts_file(&relayer_config.id)),
+            ),
+        )
+        .map_err(|_| error!("Could not create amount limiter of relayer {}", relayer_config.id))?;
 
-        let substrate_relayer_config: RelayerConfig = relayer_config.to_specific_config();
+        let registry = chain_runtimes::<T>();
+        let fetcher = RpcRuntimeVersionFetcher::new(
+            &substrate_relayer_config.ws_rpc_endpoint,
+            substrate_relayer_config.rpc_auth.clone(),
+        );
+        let runtime = select_chain_runtime(&relayer_config.id, &registry, &fetcher, &substrate_relayer_config).await?;
+        let relayer = (runtime.value)(RelayerSetup {
+            id: relayer_config.id.clone(),
+            destination_id: relayer_config.destination_id.clone(),
+            config: substrate_relayer_config,
+            key_store,
+            account,
+            amount_limiter,
+            destination_allowlist,
+            resource_allowlist,
+            asset_hints,
+            notifier: notifier.clone(),
+        })
+        .await?;
+        relayers.insert(relayer_config.id.to_string(), relayer);
+    }
 
-        match substrate_relayer_config.chain.as_str() {
-            "local" => {
-                let payout_request_call_factory = LocalPayOutRequestCallFactory {};
-                let relayer: SubstrateRelayer<T, LocalPayOutRequestCallFactory> = SubstrateRelayer::new(
-                    &substrate_relayer_config.ws_rpc_endpoint,
-                    key_store,
-                    relayer_config.destination_id.clone(),
-                    payout_request_call_factory,
-                );
-                relayers.insert(relayer_config.id.to_string(), Arc::new(Box::new(relayer)));
-            },
-            "paseo" => {
-                let payout_request_call_factory = PaseoPayOutRequestCallFactory {};
-                let relayer: SubstrateRelayer<T, PaseoPayOutRequestCallFactory> = SubstrateRelayer::new(
-                    &substrate_relayer_config.ws_rpc_endpoint,
-                    key_store,
-                    relayer_config.destination_id.clone(),
-                    payout_request_call_factory,
-                );
-                relayers.insert(relayer_config.id.to_string(), Arc::new(Box::new(relayer)));
-            },
-            "heima" => {
-                let payout_request_call_factory = HeimaPayOutRequestCallFactory {};
-                let relayer: SubstrateRelayer<T, HeimaPayOutRequestCallFactory> = SubstrateRelayer::new(
-                    &substrate_relayer_config.ws_rpc_endpoint,
-                    key_store,
-                    relayer_config.destination_id.clone(),
-                    payout_request_call_factory,
-                );
-                relayers.insert(relayer_config.id.to_string(), Arc::new(Box::new(relayer)));
-            },
-            _ => panic!("Unknown chain in relayer config"),
-        }
+    Ok(relayers)
+}
+
+/// Selects runtime of the node from `registry`, spec version outside of the supported range marks the runtime
+/// incompatible.
+#[allow(clippy::result_unit_err)]
+pub async fn select_chain_runtime<'a, T>(
+    id: &str,
+    registry: &'a [RegisteredRuntime<T>],
+    fetcher: &dyn RuntimeVersionFetcher,
+    config: &RelayerConfig,
+) -> Result<&'a RegisteredRuntime<T>, ()> {
+    match resolve_runtime(registry, fetcher, config.chain.as_deref()).await {
+        Ok(runtime) => Ok(runtime),
+        Err(RuntimeSelectionError::UnsupportedSpecVersion { chain, spec_version }) => {
+            error!(
+                "Runtime spec version {} of chain {} is not supported by generated metadata, stopping relayer {}",
+                spec_version, chain, id
+            );
+            gauge!(INCOMPATIBLE_RUNTIME_GAUGE, "chain" => chain.to_string(), "component" => "relayer").set(1.0);
+            Err(())
+        },
+        Err(e) => {
+            error!("Could not select runtime of relayer {}: {:?}", id, e);
+            Err(())
+        },
     }
+}
+
+/// Runtime metadata variant of substrate based chain, resolvable by chain name from configuration
+/// or by the node's runtime spec name.
+pub trait ChainRuntime {
+    /// Chain name used in configuration
+    const NAME: &'static str;
+    const SPEC_NAME: &'static str;
+    /// Spec versions the generated metadata is known to match, to be extended when metadata is regenerated
+    const SPEC_VERSIONS: RangeInclusive<u32>;
+    type PayOutRequestCallFactory: PayOutRequestCallFactory + Default + 'static;
+}
+
+/// Everything needed to create relayer, independently of chain runtime.
+pub struct RelayerSetup {
+    pub id: String,
+    pub destination_id: String,
+    pub config: RelayerConfig,
+    pub key_store: SubstrateKeyStore,
+    pub account: AccountId32,
+    pub amount_limiter: AmountLimiter,
+    pub destination_allowlist: DestinationAllowlist,
+    pub resource_allowlist: ResourceAllowlist,
+    pub asset_hints: HashMap<[u8; 32], PayOutAsset>,
+    pub notifier: Arc<Notifier>,
+}
+
+pub type CreateRelayerFn = fn(RelayerSetup) -> Pin<Box<dyn Future<Output = Result<Arc<Box<dyn Relayer<String>>>, ()>>>>;
+
+/// Returns relayer factories of all supported chains.
+pub fn chain_runtimes<T: Config>() -> Vec<RegisteredRuntime<CreateRelayerFn>> {
+    vec![
+        registered::<LocalRuntime>(create_relayer::<T, LocalRuntime>),
+        registered::<PaseoRuntime>(create_relayer::<T, PaseoRuntime>),
+        registered::<HeimaRuntime>(create_relayer::<T, HeimaRuntime>),
+    ]
+}
+
+fn registered<Runtime: ChainRuntime>(create: CreateRelayerFn) -> RegisteredRuntime<CreateRelayerFn> {
+    RegisteredRuntime {
+        chain: Runtime::NAME,
+        spec_name: Runtime::SPEC_NAME,
+        spec_versions: Runtime::SPEC_VERSIONS,
+        value: create,
+    }
+}
+
+#[allow(clippy::type_complexity)]
+fn create_relayer<T: Config, Runtime: ChainRuntime>(
+    setup: RelayerSetup,
+) -> Pin<Box<dyn Future<Output = Result<Arc<Box<dyn Relayer<String>>>, ()>>>> {
+    Box::pin(async move {
+        let payout_request_call_factory = Runtime::PayOutRequestCallFactory::default();
+        let _ = check_relayer_registered(
+            &setup.id,
+            &payout_request_call_factory,
+            &RpcStorageFetcher::new(&setup.config.ws_rpc_endpoint, setup.config.rpc_auth.clone()),
+            setup.account,
+            &setup.notifier,
+        )
+        .await;
+        let watch_timeout = Duration::from_secs(setup.config.watch_timeout_secs);
+        let mut relay_timeout = Duration::from_secs(setup.config.relay_timeout_secs);
+        let signing_queue = setup
+            .config
+            .offline_signing
+            .as_ref()
+            .map(|offline_signing| {
+                SigningQueue::new(offline_signing).map_err(|_| {
+                    error!("Could not open signing queue {} of relayer {}", offline_signing.queue_dir, setup.id)
+                })
+            })
+            .transpose()?;
+        // relaying attempt must outlast waiting for the offline signature
+        if let Some(ref signing_queue) = signing_queue {
+            relay_timeout = relay_timeout.max(signing_queue.timeout() + watch_timeout);
+        }
+        let relayer: SubstrateRelayer<Runtime::PayOutRequestCallFactory, RpcExtrinsicSubmitter<T>> =
+            SubstrateRelayer::new(
+                setup.destination_id,
+                payout_request_call_factory,
+                RpcExtrinsicSubmitter::new(
+                    Runtime::NAME,
+                    &setup.config.ws_rpc_endpoint,
+                    setup.config.rpc_auth.clone(),
+                    setup.key_store,
+                )
+                .with_watch_timeout(watch_timeout)
+                .with_tip(setup.config.tip)
+                .with_signing_queue(signing_queue),
+            )
+            .with_amount_limiter(setup.amount_limiter)
+            .with_amount_bounds(setup.config.amount_bounds)
+            .with_relay_timeout(relay_timeout)
+            .with_destination_allowlist(setup.destination_allowlist)
+            .with_resource_allowlist(setup.resource_allowlist)
+            .with_asset_hints(setup.asset_hints)
+            .with_relay_queue(RelayQueue::new(setup.config.max_queued_per_listener))
+            .with_pause_state_fetcher(Box::new(RpcStorageFetcher::new(
+                &setup.config.ws_rpc_endpoint,
+                setup.config.rpc_auth.clone(),
+            )))
+            .with_nonce_store(Box::new(FileNonceStore::new(format!("data/{}_relayer_nonce.bin", setup.id))));
+        Ok(Arc::new(Box::new(relayer) as Box<dyn Relayer<String>>))
+    })
+}
+
+/// Amount which can't be paid out by the pallet
+#[derive(Debug, PartialEq)]
+pub enum InvalidPayOutAmount {
+    Zero,
+    AboveMax { max: u128 },
+}
 
-    relayers
+/// Rejects amounts the pallet's balance type can't represent or that would pay out nothing.
+pub fn check_pay_out_amount(amount: u128, max: u128) -> Result<u128, InvalidPayOutAmount> {
+    if amount == 0 {
+        return Err(InvalidPayOutAmount::Zero);
+    }
+    if amount > max {
+        return Err(InvalidPayOutAmount::AboveMax { max });
+    }
+    Ok(amount)
 }
 
 pub trait PayOutRequestCallFactory: Send + Sync {
     type PayOutRequestCallType: Debug + Payload + Send + Sync;
+    type RelayerStorageAddress: Address<IsFetchable = Yes> + Send + Sync;
 
+    /// Highest amount the pallet's balance type can hold
+    const MAX_AMOUNT: u128 = u128::MAX;
+
+    /// Builds pay out request call, amount is validated with `check_pay_out_amount` first. `asset` is the hint
+    /// configured for `resource_id`, supported pallet versions resolve the asset from `resource_id` themselves.
     fn create(
         &self,
         amount: u128,
@@ -130,157 +418,1266 @@ pub trait PayOutRequestCallFactory: Send + Sync {
         resource_id: [u8; 32],
         account: AccountId32,
         chain_id: u32,
-    ) -> Self::PayOutRequestCallType;
+        asset: Option<PayOutAsset>,
+    ) -> Result<Self::PayOutRequestCallType, InvalidPayOutAmount>;
+
+    // address of the pallet's relayer set entry for given account, storage layout differs per chain metadata
+    fn relayer_storage_address(&self, account: AccountId32) -> Self::RelayerStorageAddress;
 }
 
-pub struct LocalPayOutRequestCallFactory {}
+/// Declares `PayOutRequestCallFactory` and `ChainRuntime` of a subxt metadata module.
+macro_rules! chain_runtime {
+    ($runtime:ident, $factory:ident, $name:literal, $metadata:ident, $spec_name:literal, $spec_versions:expr) => {
+        #[derive(Default)]
+        pub struct $factory {}
 
-impl PayOutRequestCallFactory for LocalPayOutRequestCallFactory {
-    type PayOutRequestCallType = StaticPayload<local::omni_bridge::calls::types::RequestPayOut>;
+        impl PayOutRequestCallFactory for $factory {
+            type PayOutRequestCallType = StaticPayload<$metadata::omni_bridge::calls::types::RequestPayOut>;
+            type RelayerStorageAddress = StaticAddress<
+                StaticStorageKey<$metadata::omni_bridge::storage::types::relayers::Param0>,
+                $metadata::omni_bridge::storage::types::relayers::Relayers,
+                Yes,
+                (),
+                (),
+            >;
 
-    fn create(
-        &self,
-        amount: u128,
-        nonce: u64,
-        resource_id: [u8; 32],
-        account: AccountId32,
-        chain_id: u32,
-    ) -> Self::PayOutRequestCallType {
-        let request = local::runtime_types::pallet_omni_bridge::PayOutRequest {
-            source_chain: crate::local::runtime_types::core_primitives::omni::chain::ChainType::Ethereum(chain_id),
-            nonce,
-            resource_id,
-            dest_account: account,
-            amount,
+            fn create(
+                &self,
+                amount: u128,
+                nonce: u64,
+                resource_id: [u8; 32],
+                account: AccountId32,
+                chain_id: u32,
+                _asset: Option<PayOutAsset>,
+            ) -> Result<Self::PayOutRequestCallType, InvalidPayOutAmount> {
+                let request = $metadata::runtime_types::pallet_omni_bridge::PayOutRequest {
+                    source_chain: $metadata::runtime_types::core_primitives::omni::chain::ChainType::Ethereum(chain_id),
+                    nonce,
+                    resource_id,
+                    dest_account: account,
+                    amount: check_pay_out_amount(amount, Self::MAX_AMOUNT)?,
+                };
+                Ok($metadata::tx().omni_bridge().request_pay_out(request, true))
+            }
+
+            fn relayer_storage_address(&self, account: AccountId32) -> Self::RelayerStorageAddress {
+                $metadata::storage().omni_bridge().relayers(account)
+            }
+        }
+
+        pub enum $runtime {}
+
+        impl ChainRuntime for $runtime {
+            const NAME: &'static str = $name;
+            const SPEC_NAME: &'static str = $spec_name;
+            const SPEC_VERSIONS: RangeInclusive<u32> = $spec_versions;
+            type PayOutRequestCallFactory = $factory;
+        }
+    };
+}
+
+// local development node runs paseo runtime
+chain_runtime!(LocalRuntime, LocalPayOutRequestCallFactory, "local", local, "paseo-parachain", 9241..=9299);
+chain_runtime!(PaseoRuntime, PaseoPayOutRequestCallFactory, "paseo", paseo, "paseo-parachain", 9241..=9299);
+chain_runtime!(HeimaRuntime, HeimaPayOutRequestCallFactory, "heima", heima, "litentry-parachain", 9241..=9299);
+
+impl<PRCF: PayOutRequestCallFactory, S: ExtrinsicSubmitter<PRCF::PayOutRequestCallType>> SubstrateRelayer<PRCF, S> {
+    pub fn new(destination_id: String, payout_request_call_factory: PRCF, submitter: S) -> Self {
+        Self {
+            payout_request_call_factory,
+            submitter,
+            destination_id,
+            amount_limiter: AmountLimiter::unlimited(),
+            amount_bounds: AmountBounds::default(),
+            relay_timeout: DEFAULT_RELAY_TIMEOUT,
+            nonce_store: Box::new(InMemoryNonceStore::default()),
+            destination_allowlist: DestinationAllowlist::unrestricted(),
+            resource_allowlist: ResourceAllowlist::unrestricted(),
+            asset_hints: HashMap::new(),
+            relay_queue: RelayQueue::default(),
+            pause_state_fetcher: None,
+            pause_check: std::sync::Mutex::new((None, false)),
+        }
+    }
+
+    pub fn with_amount_limiter(mut self, amount_limiter: AmountLimiter) -> Self {
+        self.amount_limiter = amount_limiter;
+        self
+    }
+
+    pub fn with_amount_bounds(mut self, amount_bounds: AmountBounds) -> Self {
+        self.amount_bounds = amount_bounds;
+        self
+    }
+
+    pub fn with_relay_timeout(mut self, relay_timeout: Duration) -> Self {
+        self.relay_timeout = relay_timeout;
+        self
+    }
+
+    pub fn with_nonce_store(mut self, nonce_store: Box<dyn NonceStore>) -> Self {
+        self.nonce_store = nonce_store;
+        self
+    }
+
+    pub fn with_destination_allowlist(mut self, destination_allowlist: DestinationAllowlist) -> Self {
+        self.destination_allowlist = destination_allowlist;
+        self
+    }
+
+    pub fn with_resource_allowlist(mut self, resource_allowlist: ResourceAllowlist) -> Self {
+        self.resource_allowlist = resource_allowlist;
+        self
+    }
+
+    pub fn with_asset_hints(mut self, asset_hints: HashMap<[u8; 32], PayOutAsset>) -> Self {
+        self.asset_hints = asset_hints;
+        self
+    }
+
+    pub fn with_relay_queue(mut self, relay_queue: RelayQueue) -> Self {
+        self.relay_queue = relay_queue;
+        self
+    }
+
+    pub fn with_pause_state_fetcher(mut self, pause_state_fetcher: Box<dyn PauseStateFetcher>) -> Self {
+        self.pause_state_fetcher = Some(pause_state_fetcher);
+        self
+    }
+
+    // re-checks paused state if the last check is older than `PAUSE_CHECK_INTERVAL`,
+    // last known result is used if the check fails
+    async fn is_paused(&self) -> bool {
+        let Some(ref pause_state_fetcher) = self.pause_state_fetcher else {
+            return false;
         };
-        local::tx().omni_bridge().request_pay_out(request, true)
+        let (checked_at, paused) = *self.pause_check.lock().unwrap();
+        if checked_at.is_some_and(|checked_at| checked_at.elapsed() < PAUSE_CHECK_INTERVAL) {
+            return paused;
+        }
+        match pause_state_fetcher.is_paused().await {
+            Ok(now_paused) => {
+                self.set_paused(now_paused);
+                now_paused
+            },
+            Err(_) => paused,
+        }
+    }
+
+    fn set_paused(&self, now_paused: bool) {
+        let mut pause_check = self.pause_check.lock().unwrap();
+        if now_paused != pause_check.1 {
+            info!(
+                "OmniBridge pallet of destination {} is {}",
+                self.destination_id,
+                if now_paused { "paused" } else { "unpaused" }
+            );
+        }
+        gauge!(DESTINATION_PAUSED_GAUGE, "destination" => self.destination_id.clone()).set(now_paused as u8 as f64);
+        *pause_check = (Some(Instant::now()), now_paused);
     }
 }
 
-pub struct PaseoPayOutRequestCallFactory {}
+#[async_trait]
+impl<PRCF: PayOutRequestCallFactory, S: ExtrinsicSubmitter<PRCF::PayOutRequestCallType>> Relayer<String>
+    for SubstrateRelayer<PRCF, S>
+{
+    async fn relay(&self, request: &RelayRequest) -> Result<(), RelayError> {
+        let RelayRequest {
+            amount,
+            nonce,
+            ref resource_id,
+            ref recipient,
+            source_chain_id: chain_id,
+            skip_amount_limits,
+        } = *request;
+        let account_bytes = match recipient {
+            Recipient::Substrate(account_bytes) => *account_bytes,
+            Recipient::Evm(_) | Recipient::Raw(_) => {
+                error!("Could not relay nonce {}, recipient is not 32 bytes account: {:?}", nonce, recipient);
+                return Err(RelayError::Other);
+            },
+        };
+        let account: AccountId32 = AccountId32::from(account_bytes);
+        debug!("Relaying amount: {} with nonce: {} to account: {:?}", amount, nonce, account);
 
-impl PayOutRequestCallFactory for PaseoPayOutRequestCallFactory {
-    type PayOutRequestCallType = StaticPayload<paseo::omni_bridge::calls::types::RequestPayOut>;
+        if !self.resource_allowlist.is_allowed(resource_id) {
+            info!("Skipping nonce {}, resource id 0x{} is not on relayer allowlist", nonce, hex::encode(resource_id));
+            counter!(FILTERED_RESOURCE_COUNTER, "destination" => self.destination_id.clone()).increment(1);
+            return Err(RelayError::Filtered);
+        }
 
-    fn create(
-        &self,
-        amount: u128,
-        nonce: u64,
-        resource_id: [u8; 32],
-        account: AccountId32,
-        chain_id: u32,
-    ) -> Self::PayOutRequestCallType {
-        let request = paseo::runtime_types::pallet_omni_bridge::PayOutRequest {
-            source_chain: crate::paseo::runtime_types::core_primitives::omni::chain::ChainType::Ethereum(chain_id),
-            nonce,
-            resource_id,
-            dest_account: account,
-            amount,
+        if !self.destination_allowlist.is_allowed(&account_bytes) {
+            error!("Refusing to relay nonce {}, account {} is not on relayer allowlist", nonce, account);
+            return Err(RelayError::Other);
+        }
+
+        match self.amount_bounds.check(amount) {
+            Ok(()) => {},
+            Err(AmountOutOfBounds::BelowMin { min }) => {
+                info!("Skipping nonce {}, amount {} is below minimum {}", nonce, amount, min);
+                return Err(RelayError::BelowMinAmount);
+            },
+            Err(AmountOutOfBounds::AboveMax { max }) => {
+                error!("ALERT: Refusing to relay nonce {}, amount {} is above maximum {}", nonce, amount, max);
+                return Err(RelayError::AboveMaxAmount);
+            },
+        }
+
+        // amount stays counted towards the limits only if the pay out request is submitted
+        let now = unix_now();
+        let reservation = if skip_amount_limits {
+            info!("Relaying nonce {} regardless of amount limits", nonce);
+            self.amount_limiter.reserve_unchecked(resource_id, amount, now)
+        } else {
+            self.amount_limiter.reserve(resource_id, amount, now).map_err(|e| {
+                error!("Could not relay nonce {} due to amount limit: {:?}", nonce, e);
+                RelayError::LimitExceeded
+            })?
         };
-        paseo::tx().omni_bridge().request_pay_out(request, true)
+
+        let call = self
+            .payout_request_call_factory
+            .create(
+                amount,
+                nonce,
+                resource_id.to_owned(),
+                account,
+                chain_id,
+                self.asset_hints.get(resource_id).copied(),
+            )
+            .map_err(|e| {
+                error!("Could not relay nonce {}, invalid pay out amount {}: {:?}", nonce, amount, e);
+                RelayError::Other
+            })?;
+        log::debug!("Submitting PayOutRequest extrinsic: {:?}", call);
+
+        if self.is_paused().await {
+            debug!("Could not relay nonce {}, pallet of destination {} is paused", nonce, self.destination_id);
+            return Err(RelayError::Paused);
+        }
+
+        // held until the submitted nonce is stored, waiting for it doesn't count towards relay timeout
+        let _turn = self.relay_queue.turn(chain_id).await.map_err(|_| {
+            error!("Could not relay nonce {}, too many requests from chain {} are queued", nonce, chain_id);
+            RelayError::TransportError
+        })?;
+        // unreadable store is treated as empty, the chain's account nonce is used then
+        let min_nonce = self.nonce_store.load().unwrap_or_default().map(|nonce| nonce + 1);
+        let submitted_nonce = with_relay_timeout(self.relay_timeout, async {
+            self.submitter.submit(&call, min_nonce).await.map_err(|failure| {
+                error!("Could not relay nonce {}, submission failed: {:?}", nonce, failure);
+                if failure == SubmitFailure::InvalidNonce && self.nonce_store.clear().is_err() {
+                    error!("Could not clear stored nonce, next submission may fail again");
+                }
+                // pallet got paused after the last check
+                if failure == SubmitFailure::Paused {
+                    self.set_paused(true);
+                }
+                failure.to_relay_error()
+            })
+        })
+        .await?;
+        reservation.commit();
+        if self.nonce_store.save(submitted_nonce).is_err() {
+            error!("Could not store submitted nonce {}", submitted_nonce);
+        }
+        Ok(())
+    }
+
+    fn destination_id(&self) -> String {
+        self.destination_id.clone()
     }
 }
 
-pub struct HeimaPayOutRequestCallFactory {}
+/// Checks whether relayer's account is in the OmniBridge pallet relayer set, pay out requests of
+/// unregistered accounts fail after waiting for finalization. Missing registration is notified.
+pub async fn check_relayer_registered<
+    PRCF: PayOutRequestCallFactory,
+    F: StorageFetcher<PRCF::RelayerStorageAddress>,
+>(
+    relayer_id: &str,
+    payout_request_call_factory: &PRCF,
+    storage_fetcher: &F,
+    account: AccountId32,
+    notifier: &Notifier,
+) -> Result<bool, ()> {
+    let registered = storage_fetcher
+        .exists(&payout_request_call_factory.relayer_storage_address(account.clone()))
+        .await?;
+    gauge!(RELAYER_REGISTERED_GAUGE, "relayer_id" => relayer_id.to_string()).set(registered as u8 as f64);
+    if !registered {
+        error!(
+            "Substrate relayer {} account {} is not in the OmniBridge relayer set, pay out requests will fail",
+            relayer_id, account
+        );
+        notifier.notify(
+            Notification::new(
+                NotificationType::RelayerNotRegistered,
+                &format!("Account {} is not in the OmniBridge relayer set", account),
+            )
+            .with_relayer(relayer_id),
+        );
+    }
+    Ok(registered)
+}
 
-impl PayOutRequestCallFactory for HeimaPayOutRequestCallFactory {
-    type PayOutRequestCallType = StaticPayload<heima::omni_bridge::calls::types::RequestPayOut>;
+/// Reads runtime version of the node, spec name is taken from `System::Version` constant of on-chain metadata.
+pub struct RpcRuntimeVersionFetcher {
+    rpc_url: String,
+    rpc_auth: Option<RpcAuth>,
+}
 
-    fn create(
-        &self,
-        amount: u128,
-        nonce: u64,
-        resource_id: [u8; 32],
-        account: AccountId32,
-        chain_id: u32,
-    ) -> Self::PayOutRequestCallType {
-        let request = heima::runtime_types::pallet_omni_bridge::PayOutRequest {
-            source_chain: crate::heima::runtime_types::core_primitives::omni::chain::ChainType::Ethereum(chain_id),
-            nonce,
-            resource_id,
-            dest_account: account,
-            amount,
+impl RpcRuntimeVersionFetcher {
+    pub fn new(rpc_url: &str, rpc_auth: Option<RpcAuth>) -> Self {
+        Self { rpc_url: rpc_url.to_string(), rpc_auth }
+    }
+}
+
+#[async_trait]
+impl RuntimeVersionFetcher for RpcRuntimeVersionFetcher {
+    async fn runtime_version(&self) -> Result<RuntimeVersion, ()> {
+        let api = connect(&self.rpc_url, self.rpc_auth.as_ref()).await?;
+        let version = api
+            .constants()
+            .at(&subxt::dynamic::constant("System", "Version"))
+            .map_err(|e| {
+                error!("Could not read runtime version constant: {:?}", e);
+            })?
+            .to_value()
+            .map_err(|e| {
+                error!("Could not decode runtime version constant: {:?}", e);
+            })?;
+        let spec_name = version
+            .at("spec_name")
+            .and_then(|spec_name| spec_name.as_str())
+            .ok_or_else(|| {
+                error!("Runtime version does not contain spec name");
+            })?;
+        Ok(RuntimeVersion { spec_name: spec_name.to_string(), spec_version: api.runtime_version().spec_version })
+    }
+}
+
+/// Used to check presence of storage entries.
+#[async_trait]
+pub trait StorageFetcher<Addr>: Send + Sync {
+    async fn exists(&self, address: &Addr) -> Result<bool, ()>;
+}
+
+/// Fetches storage entries from the node at the latest block.
+pub struct RpcStorageFetcher {
+    rpc_url: String,
+    rpc_auth: Option<RpcAuth>,
+}
+
+impl RpcStorageFetcher {
+    pub fn new(rpc_url: &str, rpc_auth: Option<RpcAuth>) -> Self {
+        Self { rpc_url: rpc_url.to_string(), rpc_auth }
+    }
+}
+
+#[async_trait]
+impl<Addr: Address<IsFetchable = Yes> + Send + Sync> StorageFetcher<Addr> for RpcStorageFetcher {
+    async fn exists(&self, address: &Addr) -> Result<bool, ()> {
+        let api = connect(&self.rpc_url, self.rpc_auth.as_ref()).await?;
+        let storage = api.storage().at_latest().await.map_err(|e| {
+            error!("Could not get latest block storage: {:?}", e);
+        })?;
+        storage.fetch(address).await.map(|value| value.is_some()).map_err(|e| {
+            error!("Could not fetch storage: {:?}", e);
+        })
+    }
+}
+
+/// Used by `SubstrateRelayer` to hold off pay out requests while the OmniBridge pallet is paused.
+#[async_trait]
+pub trait PauseStateFetcher: Send + Sync {
+    async fn is_paused(&self) -> Result<bool, ()>;
+}
+
+#[async_trait]
+impl PauseStateFetcher for RpcStorageFetcher {
+    async fn is_paused(&self) -> Result<bool, ()> {
+        let api = connect(&self.rpc_url, self.rpc_auth.as_ref()).await?;
+        let has_paused_entry = api
+            .metadata()
+            .pallet_by_name("OmniBridge")
+            .and_then(|pallet| pallet.storage())
+            .is_some_and(|storage| storage.entry_by_name(PAUSED_STORAGE_ENTRY).is_some());
+        if !has_paused_entry {
+            return Ok(false);
+        }
+        let storage = api.storage().at_latest().await.map_err(|e| {
+            error!("Could not get latest block storage: {:?}", e);
+        })?;
+        let Some(paused) = storage
+            .fetch(&subxt::dynamic::storage("OmniBridge", PAUSED_STORAGE_ENTRY, ()))
+            .await
+            .map_err(|e| {
+                error!("Could not fetch paused state: {:?}", e);
+            })?
+        else {
+            return Ok(false);
         };
-        heima::tx().omni_bridge().request_pay_out(request, true)
+        paused.to_value().ok().and_then(|paused| paused.as_bool()).ok_or_else(|| {
+            error!("Paused state is not a bool");
+        })
     }
 }
 
-impl<T: Config, PRCF: PayOutRequestCallFactory> SubstrateRelayer<T, PRCF> {
-    pub fn new(
-        rpc_url: &str,
-        key_store: SubstrateKeyStore,
-        destination_id: String,
-        payout_request_call_factory: PRCF,
-    ) -> Self {
+/// Used by `SubstrateRelayer` to get extrinsics included on chain.
+#[async_trait]
+pub trait ExtrinsicSubmitter<Call>: Send + Sync {
+    /// Submits extrinsic with the account's next nonce, or `min_nonce` if it is higher, and returns the nonce used.
+    async fn submit(&self, call: &Call, min_nonce: Option<u64>) -> Result<u64, SubmitFailure>;
+}
+
+/// Signs extrinsics with relayer's key, or queues them for offline signing, and submits them to the node, waiting for finalization.
+pub struct RpcExtrinsicSubmitter<T: Config> {
+    chain: String,
+    rpc_url: String,
+    rpc_auth: Option<RpcAuth>,
+    key_store: SubstrateKeyStore,
+    relay_lock: Mutex<()>,
+    watch_timeout: Duration,
+    tip: u128,
+    // extrinsics are signed offline instead of with `key_store` if set
+    signing_queue: Option<SigningQueue>,
+    _phantom: PhantomData<T>,
+}
+
+impl<T: Config> RpcExtrinsicSubmitter<T> {
+    pub fn new(chain: &str, rpc_url: &str, rpc_auth: Option<RpcAuth>, key_store: SubstrateKeyStore) -> Self {
         Self {
+            chain: chain.to_string(),
             rpc_url: rpc_url.to_string(),
+            rpc_auth,
             key_store,
-            destination_id,
-            payout_request_call_factory,
             relay_lock: Mutex::new(()),
+            watch_timeout: DEFAULT_WATCH_TIMEOUT,
+            tip: 0,
+            signing_queue: None,
             _phantom: PhantomData,
         }
     }
-}
 
-#[async_trait]
-impl<ChainConfig: Config, PRCF: PayOutRequestCallFactory> Relayer<String> for SubstrateRelayer<ChainConfig, PRCF> {
-    async fn relay(
+    pub fn with_watch_timeout(mut self, watch_timeout: Duration) -> Self {
+        self.watch_timeout = watch_timeout;
+        self
+    }
+
+    pub fn with_tip(mut self, tip: u128) -> Self {
+        self.tip = tip;
+        self
+    }
+
+    pub fn with_signing_queue(mut self, signing_queue: Option<SigningQueue>) -> Self {
+        self.signing_queue = signing_queue;
+        self
+    }
+
+    // queues signer payload and submits the extrinsic once its signature is dropped back
+    async fn submit_signed_offline<Call: Payload>(
         &self,
-        amount: u128,
+        api: &OnlineClient<PolkadotConfig>,
+        signing_queue: &SigningQueue,
+        call: &Call,
+        account: AccountId32,
         nonce: u64,
-        resource_id: &[u8; 32],
-        _data: &[u8],
-        chain_id: u32,
-    ) -> Result<(), RelayError> {
-        let account_bytes: [u8; 32] = _data[64..96].try_into().unwrap();
-        let account: AccountId32 = AccountId32::from(account_bytes);
-        debug!("Relaying amount: {} with nonce: {} to account: {:?}", amount, nonce, account);
-        let call = self
-            .payout_request_call_factory
-            .create(amount, nonce, resource_id.to_owned(), account, chain_id);
-        log::debug!("Submitting PayOutRequest extrinsic: {:?}", call);
+    ) -> Result<TxProgress<PolkadotConfig, OnlineClient<PolkadotConfig>>, SubmitFailure> {
+        let partial = api
+            .tx()
+            .create_partial_signed(call, &account, extrinsic_params(nonce, self.tip))
+            .await
+            .map_err(|e| {
+                error!("Could not create unsigned tx: {:?}", e);
+                classify_submit_error(&e)
+            })?;
+        let request = SigningRequest {
+            chain: self.chain.clone(),
+            nonce,
+            signer: account.to_string(),
+            call_data: hex::encode(partial.call_data()),
+            signer_payload: hex::encode(partial.signer_payload()),
+        };
+        let signature = signing_queue.await_signature(&request).await?;
 
-        let api = OnlineClient::<PolkadotConfig>::from_insecure_url(&self.rpc_url)
+        let progress = partial
+            .sign_with_address_and_signature(&MultiAddress::Id(account), &MultiSignature::Sr25519(signature))
+            .submit_and_watch()
             .await
             .map_err(|e| {
-                error!("Could not connect to node: {:?}", e);
-                RelayError::TransportError
+                error!("Could not submit offline signed tx: {:?}", e);
+                classify_submit_error(&e)
             })?;
-        let secret_key_bytes = self.key_store.read().map_err(|e| {
-            error!("Could not unseal key: {:?}", e);
-            RelayError::Other
-        })?;
-        let signer = subxt_signer::sr25519::Keypair::from_secret_key(secret_key_bytes).map_err(|e| {
-            error!("Could not create secret key: {:?}", e);
-            RelayError::Other
-        })?;
+        signing_queue.complete(&request);
+        Ok(progress)
+    }
+}
+
+// tip is paid via `ChargeTransactionPayment` (or `ChargeAssetTxPayment`) extension, whichever the chain uses
+fn extrinsic_params(
+    nonce: u64,
+    tip: u128,
+) -> <PolkadotExtrinsicParams<PolkadotConfig> as ExtrinsicParams<PolkadotConfig>>::Params {
+    PolkadotExtrinsicParamsBuilder::new().nonce(nonce).tip(tip).build()
+}
+
+#[async_trait]
+impl<T: Config, Call: Payload + Send + Sync> ExtrinsicSubmitter<Call> for RpcExtrinsicSubmitter<T> {
+    async fn submit(&self, call: &Call, min_nonce: Option<u64>) -> Result<u64, SubmitFailure> {
+        let api = connect(&self.rpc_url, self.rpc_auth.as_ref())
+            .await
+            .map_err(|_| SubmitFailure::Transport)?;
+        // metadata is fetched on every connect, so runtime upgrades are caught before the first submission after them
+        ensure_call_compatible(&self.chain, call, &api.metadata(), api.runtime_version().spec_version)
+            .map_err(|_| SubmitFailure::Fatal)?;
+        // offline signed extrinsics are signed by queue's account, its key is not available here
+        let signer = match self.signing_queue {
+            Some(_) => None,
+            None => {
+                let key = self.key_store.read().map_err(|e| {
+                    error!("Could not unseal key: {:?}", e);
+                    SubmitFailure::Fatal
+                })?;
+                Some(SubstrateSigner::from_key(&key).map_err(|_| SubmitFailure::Fatal)?)
+            },
+        };
+        let account = match (&signer, &self.signing_queue) {
+            (Some(signer), _) => signer.account_id(),
+            (None, Some(signing_queue)) => signing_queue.account().clone(),
+            (None, None) => return Err(SubmitFailure::Fatal),
+        };
 
         // lets aquire lock here so no two tx's are pending for finalization, this will ensure that subxt logic will always get correct nonce from chain
         // alternative solution is to handle nonces on our side so we can submit txs in parallel (with different nonces)
         let _lock = self.relay_lock.lock().await;
 
-        let hash = api
-            .tx()
-            .sign_and_submit_then_watch(&call, &signer, Default::default())
-            .await
-            .map_err(|e| {
-                error!("Could not submit tx: {:?}", e);
-                RelayError::TransportError
-            })?
-            .wait_for_finalized_success()
-            .await
-            .map_err(|e| {
+        let account_nonce = api.tx().account_nonce(&account).await.map_err(|e| {
+            error!("Could not get account nonce: {:?}", e);
+            SubmitFailure::Transport
+        })?;
+        // stored nonce covers submissions not yet visible to the node
+        let nonce = min_nonce.map_or(account_nonce, |min_nonce| min_nonce.max(account_nonce));
+
+        let progress = match (signer, &self.signing_queue) {
+            (Some(signer), _) => api
+                .tx()
+                .sign_and_submit_then_watch(call, &signer, extrinsic_params(nonce, self.tip))
+                .await
+                .map_err(|e| {
+                    error!("Could not submit tx: {:?}", e);
+                    classify_submit_error(&e)
+                })?,
+            (None, Some(signing_queue)) => {
+                self.submit_signed_offline(&api, signing_queue, call, account, nonce).await?
+            },
+            (None, None) => return Err(SubmitFailure::Fatal),
+        };
+        let hash = with_watch_timeout(self.watch_timeout, async {
+            Ok(progress.wait_for_finalized_success().await.map_err(|e| {
                 error!("Transaction not finalized: {:?}", e);
-                RelayError::Other
+                classify_watch_error(&e)
+            }))
+        })
+        .await
+        .map_err(|_| SubmitFailure::Unconfirmed)??;
+
+        debug!("Relayed pay out request with hash: {:?}, nonce: {}", hash, nonce);
+
+        Ok(nonce)
+    }
+}
+
+/// Checks that `call` matches its definition in on-chain `metadata`, updating `INCOMPATIBLE_RUNTIME_GAUGE`
+/// of `chain` with the outcome.
+#[allow(clippy::result_unit_err)]
+pub fn ensure_call_compatible<Call: Payload>(
+    chain: &str,
+    call: &Call,
+    metadata: &Metadata,
+    spec_version: u32,
+) -> Result<(), ()> {
+    let result = subxt::ext::subxt_core::tx::validate(call, metadata).map_err(|e| {
+        let (pallet, name) = call
+            .validation_details()
+            .map_or(("unknown", "unknown"), |details| (details.pallet_name, details.call_name));
+        error!(
+            "Runtime spec version {} of chain {} is incompatible with call {}.{}, stopping relaying: {:?}",
+            spec_version, chain, pallet, name, e
+        );
+    });
+    gauge!(INCOMPATIBLE_RUNTIME_GAUGE, "chain" => chain.to_string(), "component" => "relayer")
+        .set(result.is_err() as u8 as f64);
+    result
+}
+
+// rpc client is built manually when auth is set, `from_url` doesn't allow custom headers
+async fn connect(rpc_url: &str, rpc_auth: Option<&RpcAuth>) -> Result<OnlineClient<PolkadotConfig>, ()> {
+    match rpc_auth {
+        Some(rpc_auth) => {
+            let mut authorization = HeaderValue::from_str(&rpc_auth.authorization_header()?).map_err(|e| {
+                error!("Invalid rpc authorization header: {:?}", e);
             })?;
+            authorization.set_sensitive(true);
+            let rpc_client = subxt::backend::rpc::reconnecting_rpc_client::RpcClient::builder()
+                .set_headers(HeaderMap::from_iter([(AUTHORIZATION, authorization)]))
+                .build(rpc_url.to_string())
+                .await
+                .map_err(|e| {
+                    error!("Could not create RpcClient: {:?}", e);
+                })?;
+            OnlineClient::<PolkadotConfig>::from_rpc_client(rpc_client).await
+        },
+        None => OnlineClient::<PolkadotConfig>::from_insecure_url(rpc_url).await,
+    }
+    .map_err(|e| {
+        error!("Could not connect to node: {:?}", e);
+    })
+}
 
-        debug!("Relayed pay out request with hash: {:?}", hash);
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use metrics_util::debugging::{DebugValue, DebuggingRecorder};
+    use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+    use subxt::config::ExtrinsicParamsEncoder;
+    use subxt::ext::codec::{Compact, Decode, Encode};
+    use subxt::ext::subxt_core::client::{ClientState, RuntimeVersion};
 
-        Ok(())
+    fn relay_request(
+        amount: u128,
+        nonce: u64,
+        resource_id: [u8; 32],
+        recipient: Recipient,
+        source_chain_id: u32,
+    ) -> RelayRequest {
+        RelayRequest { amount, nonce, resource_id, recipient, source_chain_id, skip_amount_limits: false }
     }
 
-    fn destination_id(&self) -> String {
-        self.destination_id.clone()
+    struct HangingSubmitter;
+
+    #[async_trait]
+    impl<Call: Sync> ExtrinsicSubmitter<Call> for HangingSubmitter {
+        async fn submit(&self, _call: &Call, _min_nonce: Option<u64>) -> Result<u64, SubmitFailure> {
+            std::future::pending().await
+        }
+    }
+
+    struct SlowSubmitter;
+
+    #[async_trait]
+    impl<Call: Sync> ExtrinsicSubmitter<Call> for SlowSubmitter {
+        async fn submit(&self, _call: &Call, _min_nonce: Option<u64>) -> Result<u64, SubmitFailure> {
+            tokio::time::sleep(Duration::from_secs(6)).await;
+            Ok(5)
+        }
+    }
+
+    // node's account nonce is always 5, records nonces requested by relayer and fails with queued failures first
+    #[derive(Default)]
+    struct RecordingSubmitter {
+        min_nonces: std::sync::Mutex<Vec<Option<u64>>>,
+        failures: std::sync::Mutex<std::collections::VecDeque<SubmitFailure>>,
+    }
+
+    impl RecordingSubmitter {
+        fn failing_with(failures: Vec<SubmitFailure>) -> Self {
+            Self { failures: std::sync::Mutex::new(failures.into()), ..Default::default() }
+        }
+    }
+
+    #[async_trait]
+    impl<Call: Sync> ExtrinsicSubmitter<Call> for RecordingSubmitter {
+        async fn submit(&self, _call: &Call, min_nonce: Option<u64>) -> Result<u64, SubmitFailure> {
+            self.min_nonces.lock().unwrap().push(min_nonce);
+            if let Some(failure) = self.failures.lock().unwrap().pop_front() {
+                return Err(failure);
+            }
+            Ok(min_nonce.map_or(5, |min_nonce| min_nonce.max(5)))
+        }
+    }
+
+    struct MockStorageFetcher {
+        registered: bool,
+    }
+
+    #[async_trait]
+    impl<Addr: Address + Sync> StorageFetcher<Addr> for MockStorageFetcher {
+        async fn exists(&self, address: &Addr) -> Result<bool, ()> {
+            assert_eq!(address.pallet_name(), "OmniBridge");
+            assert_eq!(address.entry_name(), "Relayers");
+            Ok(self.registered)
+        }
+    }
+
+    #[tokio::test]
+    async fn check_relayer_registered_works() {
+        let account = AccountId32::from([1; 32]);
+
+        let result = check_relayer_registered(
+            "test",
+            &LocalPayOutRequestCallFactory {},
+            &MockStorageFetcher { registered: true },
+            account.clone(),
+            &Notifier::disabled(),
+        )
+        .await;
+        assert_eq!(result, Ok(true));
+
+        let result = check_relayer_registered(
+            "test",
+            &LocalPayOutRequestCallFactory {},
+            &MockStorageFetcher { registered: false },
+            account,
+            &Notifier::disabled(),
+        )
+        .await;
+        assert_eq!(result, Ok(false));
+    }
+
+    // reports shared paused flag, counting storage reads
+    #[derive(Clone, Default)]
+    struct FakePauseStateFetcher {
+        paused: Arc<AtomicBool>,
+        reads: Arc<AtomicUsize>,
+    }
+
+    #[async_trait]
+    impl PauseStateFetcher for FakePauseStateFetcher {
+        async fn is_paused(&self) -> Result<bool, ()> {
+            self.reads.fetch_add(1, Ordering::SeqCst);
+            Ok(self.paused.load(Ordering::SeqCst))
+        }
+    }
+
+    fn destination_paused_gauge(recorder: &DebuggingRecorder) -> Option<f64> {
+        recorder
+            .snapshotter()
+            .snapshot()
+            .into_vec()
+            .into_iter()
+            .find_map(|(key, _, _, value)| match value {
+                DebugValue::Gauge(value) if key.key().name() == DESTINATION_PAUSED_GAUGE => Some(value.into_inner()),
+                _ => None,
+            })
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn relay_should_return_paused_error_while_pallet_is_paused() {
+        let fetcher = FakePauseStateFetcher::default();
+        let recorder = DebuggingRecorder::new();
+        let _guard = metrics::set_default_local_recorder(&recorder);
+        let relayer =
+            SubstrateRelayer::new("02".to_string(), LocalPayOutRequestCallFactory {}, RecordingSubmitter::default())
+                .with_pause_state_fetcher(Box::new(fetcher.clone()));
+        let request = relay_request(100, 1, [0; 32], Recipient::Substrate([0; 32]), 0);
+
+        relayer.relay(&request).await.unwrap();
+        assert_eq!(destination_paused_gauge(&recorder), Some(0.0));
+
+        fetcher.paused.store(true, Ordering::SeqCst);
+        // paused state is cached until the next check
+        relayer.relay(&request).await.unwrap();
+        tokio::time::advance(PAUSE_CHECK_INTERVAL).await;
+
+        assert!(matches!(relayer.relay(&request).await, Err(RelayError::Paused)));
+        assert_eq!(destination_paused_gauge(&recorder), Some(1.0));
+        assert_eq!(relayer.submitter.min_nonces.lock().unwrap().len(), 2);
+
+        fetcher.paused.store(false, Ordering::SeqCst);
+        assert!(matches!(relayer.relay(&request).await, Err(RelayError::Paused)));
+        tokio::time::advance(PAUSE_CHECK_INTERVAL).await;
+
+        // relaying resumes once unpause is seen
+        relayer.relay(&request).await.unwrap();
+        assert_eq!(destination_paused_gauge(&recorder), Some(0.0));
+        assert_eq!(relayer.submitter.min_nonces.lock().unwrap().len(), 3);
+        assert_eq!(fetcher.reads.load(Ordering::SeqCst), 3);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn relay_should_hold_off_after_paused_dispatch_error() {
+        let fetcher = FakePauseStateFetcher::default();
+        let relayer = SubstrateRelayer::new(
+            "02".to_string(),
+            LocalPayOutRequestCallFactory {},
+            RecordingSubmitter::failing_with(vec![SubmitFailure::Paused]),
+        )
+        .with_pause_state_fetcher(Box::new(fetcher.clone()));
+        let request = relay_request(100, 1, [0; 32], Recipient::Substrate([0; 32]), 0);
+
+        // pallet got paused between the check and dispatch
+        assert!(matches!(relayer.relay(&request).await, Err(RelayError::Paused)));
+        assert!(matches!(relayer.relay(&request).await, Err(RelayError::Paused)));
+        assert_eq!(relayer.submitter.min_nonces.lock().unwrap().len(), 1);
+
+        tokio::time::advance(PAUSE_CHECK_INTERVAL).await;
+        relayer.relay(&request).await.unwrap();
+        assert_eq!(fetcher.reads.load(Ordering::SeqCst), 2);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn relay_should_return_transport_error_on_timeout() {
+        let relayer = SubstrateRelayer::new("02".to_string(), LocalPayOutRequestCallFactory {}, HangingSubmitter)
+            .with_relay_timeout(Duration::from_secs(5));
+
+        let result = relayer
+            .relay(&relay_request(100, 1, [0; 32], Recipient::Substrate([0; 32]), 0))
+            .await;
+        assert!(matches!(result, Err(RelayError::TransportError)));
+    }
+
+    #[tokio::test]
+    async fn relay_should_return_other_error_if_recipient_is_not_account() {
+        let relayer =
+            SubstrateRelayer::new("02".to_string(), LocalPayOutRequestCallFactory {}, RecordingSubmitter::default());
+
+        for recipient in [vec![1; 20], vec![1; 96], vec![]] {
+            let result = relayer
+                .relay(&relay_request(100, 1, [0; 32], Recipient::from(recipient), 0))
+                .await;
+            assert!(matches!(result, Err(RelayError::Other)));
+        }
+        assert!(relayer.submitter.min_nonces.lock().unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn relay_should_submit_only_to_allowed_destinations() {
+        let relayer =
+            SubstrateRelayer::new("02".to_string(), LocalPayOutRequestCallFactory {}, RecordingSubmitter::default())
+                .with_destination_allowlist(DestinationAllowlist::new(vec![vec![1; 32]]));
+
+        assert!(relayer
+            .relay(&relay_request(100, 1, [0; 32], Recipient::Substrate([1; 32]), 0))
+            .await
+            .is_ok());
+        let result = relayer
+            .relay(&relay_request(100, 2, [0; 32], Recipient::Substrate([2; 32]), 0))
+            .await;
+        assert!(matches!(result, Err(RelayError::Other)));
+        assert_eq!(*relayer.submitter.min_nonces.lock().unwrap(), vec![None]);
+    }
+
+    #[tokio::test]
+    async fn relay_should_skip_resources_not_on_allowlist() {
+        let relayer =
+            SubstrateRelayer::new("02".to_string(), LocalPayOutRequestCallFactory {}, RecordingSubmitter::default())
+                .with_resource_allowlist(ResourceAllowlist::new(vec![[1; 32]]));
+
+        assert!(relayer
+            .relay(&relay_request(100, 1, [1; 32], Recipient::Substrate([1; 32]), 0))
+            .await
+            .is_ok());
+        let result = relayer
+            .relay(&relay_request(100, 2, [2; 32], Recipient::Substrate([1; 32]), 0))
+            .await;
+        assert!(matches!(result, Err(RelayError::Filtered)));
+        assert_eq!(*relayer.submitter.min_nonces.lock().unwrap(), vec![None]);
+    }
+
+    #[tokio::test]
+    async fn relay_should_submit_any_resource_without_allowlist() {
+        let relayer =
+            SubstrateRelayer::new("02".to_string(), LocalPayOutRequestCallFactory {}, RecordingSubmitter::default())
+                .with_resource_allowlist(ResourceAllowlist::new(vec![]));
+
+        assert!(relayer
+            .relay(&relay_request(100, 1, [1; 32], Recipient::Substrate([1; 32]), 0))
+            .await
+            .is_ok());
+        assert!(relayer
+            .relay(&relay_request(100, 2, [2; 32], Recipient::Substrate([1; 32]), 0))
+            .await
+            .is_ok());
+        assert_eq!(relayer.submitter.min_nonces.lock().unwrap().len(), 2);
+    }
+
+    #[test]
+    fn resource_allowlist_is_parsed_from_config() {
+        let config = |allowed_resource_ids: &str| -> RelayerConfig {
+            serde_json::from_str(&format!(
+                r#"{{"ws_rpc_endpoint": "ws://localhost:9944", "chain": "local"{}}}"#,
+                allowed_resource_ids
+            ))
+            .unwrap()
+        };
+
+        let allowlist = config(&format!(r#", "allowed_resource_ids": ["0x{}"]"#, hex::encode([1; 32])))
+            .resource_allowlist()
+            .unwrap();
+        assert!(allowlist.is_allowed(&[1; 32]));
+        assert!(!allowlist.is_allowed(&[2; 32]));
+
+        assert!(config("").resource_allowlist().unwrap().is_allowed(&[2; 32]));
+        assert!(config(r#", "allowed_resource_ids": ["0xzz"]"#).resource_allowlist().is_err());
+    }
+
+    #[test]
+    fn check_pay_out_amount_works() {
+        assert_eq!(check_pay_out_amount(0, u128::MAX), Err(InvalidPayOutAmount::Zero));
+        assert_eq!(check_pay_out_amount(1, u128::MAX), Ok(1));
+        assert_eq!(check_pay_out_amount(u128::MAX, u128::MAX), Ok(u128::MAX));
+        assert_eq!(check_pay_out_amount(100, 100), Ok(100));
+        assert_eq!(check_pay_out_amount(101, 100), Err(InvalidPayOutAmount::AboveMax { max: 100 }));
+    }
+
+    #[test]
+    fn factory_should_reject_zero_amount() {
+        let factory = LocalPayOutRequestCallFactory {};
+
+        let result = factory.create(0, 1, [0; 32], AccountId32::from([1; 32]), 0, None);
+        assert_eq!(result.err(), Some(InvalidPayOutAmount::Zero));
+        assert!(factory
+            .create(u128::MAX, 1, [0; 32], AccountId32::from([1; 32]), 0, None)
+            .is_ok());
+    }
+
+    #[tokio::test]
+    async fn relay_should_return_other_error_on_zero_amount() {
+        let relayer =
+            SubstrateRelayer::new("02".to_string(), LocalPayOutRequestCallFactory {}, RecordingSubmitter::default());
+
+        let result = relayer
+            .relay(&relay_request(0, 1, [0; 32], Recipient::Substrate([1; 32]), 0))
+            .await;
+        assert!(matches!(result, Err(RelayError::Other)));
+        assert!(relayer.submitter.min_nonces.lock().unwrap().is_empty());
+    }
+
+    #[test]
+    fn destination_allowlist_is_parsed_from_config() {
+        let config = |allowed_destinations: Option<Vec<String>>| RelayerConfig {
+            ws_rpc_endpoint: "ws://localhost:9944".to_string(),
+            chain: Some("local".to_string()),
+            amount_limits: HashMap::new(),
+            payouts_file: None,
+            relay_timeout_secs: 1,
+            watch_timeout_secs: 1,
+            amount_bounds: AmountBounds::default(),
+            rpc_auth: None,
+            allowed_destinations,
+            tip: 0,
+            assets: HashMap::new(),
+            offline_signing: None,
+            allowed_resource_ids: vec![],
+            max_queued_per_listener: DEFAULT_MAX_QUEUED_PER_SOURCE,
+        };
+        let alice = "5GrwvaEF5zXb26Fz9rcQpDWS57CtERHpNehXCPcNoHGKutQY".to_string();
+
+        let allowlist = config(Some(vec![alice.clone()])).destination_allowlist().unwrap();
+        assert!(allowlist.is_allowed(&AccountId32::from_str(&alice).unwrap().0));
+        assert!(!allowlist.is_allowed(&[1; 32]));
+
+        assert!(config(None).destination_allowlist().unwrap().is_allowed(&[1; 32]));
+        assert!(config(Some(vec!["not an account".to_string()]))
+            .destination_allowlist()
+            .is_err());
+    }
+
+    #[tokio::test]
+    async fn relay_should_store_submitted_nonce() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("relayer_nonce.bin").to_str().unwrap().to_string();
+        let relayer =
+            SubstrateRelayer::new("02".to_string(), LocalPayOutRequestCallFactory {}, RecordingSubmitter::default())
+                .with_nonce_store(Box::new(FileNonceStore::new(path.clone())));
+
+        relayer
+            .relay(&relay_request(100, 1, [0; 32], Recipient::Substrate([0; 32]), 0))
+            .await
+            .unwrap();
+        relayer
+            .relay(&relay_request(100, 2, [0; 32], Recipient::Substrate([0; 32]), 0))
+            .await
+            .unwrap();
+
+        assert_eq!(*relayer.submitter.min_nonces.lock().unwrap(), vec![None, Some(6)]);
+        assert_eq!(FileNonceStore::new(path).load(), Ok(Some(6)));
+    }
+
+    #[tokio::test]
+    async fn relay_should_refresh_nonce_after_invalid_nonce_failure() {
+        let nonce_store = InMemoryNonceStore::default();
+        nonce_store.save(9).unwrap();
+        let relayer = SubstrateRelayer::new(
+            "02".to_string(),
+            LocalPayOutRequestCallFactory {},
+            RecordingSubmitter::failing_with(vec![SubmitFailure::InvalidNonce]),
+        )
+        .with_nonce_store(Box::new(nonce_store));
+
+        let result = relayer
+            .relay(&relay_request(100, 1, [0; 32], Recipient::Substrate([0; 32]), 0))
+            .await;
+        assert!(matches!(result, Err(RelayError::WatchError)));
+
+        relayer
+            .relay(&relay_request(100, 1, [0; 32], Recipient::Substrate([0; 32]), 0))
+            .await
+            .unwrap();
+        // stored nonce ahead of the chain is dropped, account nonce is used on retry
+        assert_eq!(*relayer.submitter.min_nonces.lock().unwrap(), vec![Some(10), None]);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn listeners_sharing_relayer_take_turns() {
+        let relayer =
+            Arc::new(SubstrateRelayer::new("02".to_string(), LocalPayOutRequestCallFactory {}, SlowSubmitter));
+        let relayed = Arc::new(std::sync::Mutex::new(vec![]));
+
+        // busy listener of chain 1 queues up requests before the one of chain 2
+        let mut handles = vec![];
+        for (nonce, chain_id) in [(1, 1), (2, 1), (3, 1), (4, 1), (1, 2)] {
+            let relayer = relayer.clone();
+            let relayed = relayed.clone();
+            handles.push(tokio::spawn(async move {
+                relayer
+                    .relay(&relay_request(100, nonce, [0; 32], Recipient::Substrate([0; 32]), chain_id))
+                    .await
+                    .unwrap();
+                relayed.lock().unwrap().push(chain_id);
+            }));
+            tokio::task::yield_now().await;
+        }
+        for handle in handles {
+            handle.await.unwrap();
+        }
+
+        assert_eq!(*relayed.lock().unwrap(), vec![1, 2, 1, 1, 1]);
+    }
+
+    #[tokio::test]
+    async fn relay_should_classify_submit_failures() {
+        let cases = [
+            (SubmitFailure::Transport, RelayError::TransportError),
+            (SubmitFailure::Temporary, RelayError::TransportError),
+            (SubmitFailure::Unconfirmed, RelayError::WatchError),
+            (SubmitFailure::Fatal, RelayError::Other),
+            (SubmitFailure::Paused, RelayError::Paused),
+        ];
+        for (failure, expected) in cases {
+            let nonce_store = InMemoryNonceStore::default();
+            nonce_store.save(9).unwrap();
+            let relayer = SubstrateRelayer::new(
+                "02".to_string(),
+                LocalPayOutRequestCallFactory {},
+                RecordingSubmitter::failing_with(vec![failure]),
+            )
+            .with_nonce_store(Box::new(nonce_store));
+
+            let result = relayer
+                .relay(&relay_request(100, 1, [0; 32], Recipient::Substrate([0; 32]), 0))
+                .await;
+            assert_eq!(format!("{:?}", result), format!("{:?}", Err::<(), _>(expected)));
+
+            // nonce is refreshed only after invalid nonce failures
+            relayer
+                .relay(&relay_request(100, 1, [0; 32], Recipient::Substrate([0; 32]), 0))
+                .await
+                .unwrap();
+            assert_eq!(*relayer.submitter.min_nonces.lock().unwrap(), vec![Some(10), Some(10)]);
+        }
+    }
+
+    // encodes signed extensions data of params the way they are included in extrinsic
+    fn encoded_extra(params: <PolkadotExtrinsicParams<CONF> as ExtrinsicParams<CONF>>::Params) -> Vec<u8> {
+        let metadata_bytes = std::fs::read("../artifacts/local.scale").unwrap();
+        let client_state = ClientState::<CONF> {
+            genesis_hash: Default::default(),
+            runtime_version: RuntimeVersion { spec_version: 1, transaction_version: 1 },
+            metadata: Metadata::decode(&mut &metadata_bytes[..]).unwrap(),
+        };
+        let mut extra = vec![];
+        PolkadotExtrinsicParams::<CONF>::new(&client_state, params)
+            .unwrap()
+            .encode_extra_to(&mut extra);
+        extra
+    }
+
+    #[test]
+    fn extrinsic_params_should_include_tip() {
+        let tip = 1_000_000_000;
+
+        let extra = encoded_extra(extrinsic_params(3, tip));
+
+        assert_ne!(extra, encoded_extra(extrinsic_params(3, 0)));
+        let encoded_tip = Compact(tip).encode();
+        assert!(extra.windows(encoded_tip.len()).any(|window| window == encoded_tip));
+    }
+
+    // renames scale encoded `amount` fields, keeping blob length so it still decodes
+    fn tampered_metadata() -> Metadata {
+        let mut bytes = std::fs::read("../artifacts/local.scale").unwrap();
+        let from = b"\x18amount";
+        let mut index = 0;
+        while index + from.len() <= bytes.len() {
+            if bytes[index..].starts_with(from) {
+                bytes[index + from.len() - 1] = b'x';
+            }
+            index += 1;
+        }
+        Metadata::decode(&mut &bytes[..]).unwrap()
+    }
+
+    #[test]
+    fn pay_out_request_call_is_compatible_with_generated_metadata() {
+        let metadata_bytes = std::fs::read("../artifacts/local.scale").unwrap();
+        let metadata = Metadata::decode(&mut &metadata_bytes[..]).unwrap();
+        let call = LocalPayOutRequestCallFactory::default()
+            .create(10, 1, [1; 32], AccountId32::from([2; 32]), 1, None)
+            .unwrap();
+
+        assert_eq!(ensure_call_compatible("local", &call, &metadata, 1), Ok(()));
+    }
+
+    // supported pallets resolve the asset from resource id, so the hint must not change the submitted call
+    #[test]
+    fn pay_out_request_call_is_the_same_for_every_asset_hint() {
+        let metadata_bytes = std::fs::read("../artifacts/local.scale").unwrap();
+        let metadata = Metadata::decode(&mut &metadata_bytes[..]).unwrap();
+        let call_data = |asset: Option<PayOutAsset>| {
+            LocalPayOutRequestCallFactory::default()
+                .create(10, 1, [1; 32], AccountId32::from([2; 32]), 1, asset)
+                .unwrap()
+                .encode_call_data(&metadata)
+                .unwrap()
+        };
+
+        assert_eq!(call_data(Some(PayOutAsset::Native)), call_data(None));
+        assert_eq!(call_data(Some(PayOutAsset::WithId(1984))), call_data(None));
+    }
+
+    #[test]
+    fn pay_out_request_call_is_incompatible_with_changed_metadata() {
+        let call = LocalPayOutRequestCallFactory::default()
+            .create(10, 1, [1; 32], AccountId32::from([2; 32]), 1, None)
+            .unwrap();
+
+        assert_eq!(ensure_call_compatible("local", &call, &tampered_metadata(), 2), Err(()));
+    }
+
+    #[test]
+    fn tip_defaults_to_zero() {
+        let config: RelayerConfig =
+            serde_json::from_str(r#"{"ws_rpc_endpoint": "ws://localhost:9944", "chain": "local"}"#).unwrap();
+        assert_eq!(config.tip, 0);
+
+        let config: RelayerConfig =
+            serde_json::from_str(r#"{"ws_rpc_endpoint": "ws://localhost:9944", "chain": "local", "tip": 1000}"#)
+                .unwrap();
+        assert_eq!(config.tip, 1000);
+    }
+
+    #[test]
+    fn offline_signing_timeout_defaults_to_an_hour() {
+        let config: RelayerConfig = serde_json::from_str(
+            r#"{"ws_rpc_endpoint": "ws://localhost:9944", "chain": "local", "offline_signing": {"queue_dir": "/tmp/signing", "account": "5GrwvaEF5zXb26Fz9rcQpDWS57CtERHpNehXCPcNoHGKutQY"}}"#,
+        )
+        .unwrap();
+        let offline_signing = config.offline_signing.unwrap();
+        assert_eq!(offline_signing.queue_dir, "/tmp/signing");
+        assert_eq!(offline_signing.signing_timeout_secs, 3600);
+    }
+
+    #[test]
+    fn registry_covers_all_supported_chains() {
+        let names: Vec<&str> = chain_runtimes::<CONF>().iter().map(|runtime| runtime.chain).collect();
+        assert_eq!(names, vec!["local", "paseo", "heima"]);
+    }
+
+    struct FakeRuntimeVersionFetcher {
+        spec_name: &'static str,
+        spec_version: u32,
+    }
+
+    #[async_trait]
+    impl RuntimeVersionFetcher for FakeRuntimeVersionFetcher {
+        async fn runtime_version(&self) -> Result<bridge_core::runtime_registry::RuntimeVersion, ()> {
+            Ok(bridge_core::runtime_registry::RuntimeVersion {
+                spec_name: self.spec_name.to_string(),
+                spec_version: self.spec_version,
+            })
+        }
+    }
+
+    fn chain_config(chain: Option<&str>) -> RelayerConfig {
+        let mut config: RelayerConfig = serde_json::from_str(r#"{"ws_rpc_endpoint": "ws://localhost:9944"}"#).unwrap();
+        config.chain = chain.map(|chain| chain.to_string());
+        config
+    }
+
+    #[tokio::test]
+    async fn runtime_of_each_supported_chain_is_selected_from_node() {
+        let registry = chain_runtimes::<CONF>();
+        // chains sharing the spec name have to be configured
+        for (spec_name, chain, expected) in [
+            ("litentry-parachain", None, "heima"),
+            ("paseo-parachain", Some("paseo"), "paseo"),
+            ("paseo-parachain", Some("local"), "local"),
+        ] {
+            let fetcher = FakeRuntimeVersionFetcher { spec_name, spec_version: 9241 };
+
+            let runtime = select_chain_runtime("test", &registry, &fetcher, &chain_config(chain))
+                .await
+                .unwrap();
+
+            assert_eq!(runtime.chain, expected);
+        }
+    }
+
+    #[tokio::test]
+    async fn unknown_or_unsupported_runtime_fails_selection() {
+        let registry = chain_runtimes::<CONF>();
+
+        let unknown = FakeRuntimeVersionFetcher { spec_name: "polkadot", spec_version: 1_003_000 };
+        assert!(select_chain_runtime("test", &registry, &unknown, &chain_config(None))
+            .await
+            .is_err());
+
+        let unsupported = FakeRuntimeVersionFetcher { spec_name: "litentry-parachain", spec_version: 9100 };
+        assert!(select_chain_runtime("test", &registry, &unsupported, &chain_config(None))
+            .await
+            .is_err());
+
+        let paseo = FakeRuntimeVersionFetcher { spec_name: "paseo-parachain", spec_version: 9241 };
+        assert!(select_chain_runtime("test", &registry, &paseo, &chain_config(Some("heima")))
+            .await
+            .is_err());
+    }
+
+    #[tokio::test]
+    async fn create_from_config_fails_on_unknown_chain() {
+        let keystore_dir = tempfile::tempdir().unwrap();
+        let keystore_path = keystore_dir.path().to_str().unwrap().to_string();
+        SubstrateKeyStore::new(format!("{}/unknown.bin", keystore_path))
+            .write(&SubstrateKeyStore::generate_key().unwrap())
+            .unwrap();
+        let relayer_config = bridge_core::config::Relayer {
+            id: "unknown".to_string(),
+            relayer_type: "substrate".to_string(),
+            destination_id: "unknown".to_string(),
+            config: serde_json::to_value(RelayerConfig {
+                ws_rpc_endpoint: "ws://localhost:9944".to_string(),
+                chain: Some("unknown".to_string()),
+                amount_limits: HashMap::new(),
+                payouts_file: None,
+                relay_timeout_secs: 1,
+                watch_timeout_secs: 1,
+                amount_bounds: AmountBounds::default(),
+                rpc_auth: None,
+                allowed_destinations: None,
+                tip: 0,
+                assets: HashMap::new(),
+                offline_signing: None,
+                allowed_resource_ids: vec![],
+                max_queued_per_listener: DEFAULT_MAX_QUEUED_PER_SOURCE,
+            })
+            .unwrap(),
+        };
+
+        assert!(create_from_config::<CONF>(keystore_path, &[relayer_config], Arc::new(Notifier::disabled()))
+            .await
+            .is_err());
     }
 }
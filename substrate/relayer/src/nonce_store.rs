@@ -0,0 +1,132 @@
+// Copyright 2020-2024 Trust Computing GmbH.
+// This file is part of Litentry.
+//
+// Litentry is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// Litentry is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with Litentry.  If not, see <https://www.gnu.org/licenses/>.
+
+use std::fs;
+use std::io::ErrorKind;
+use std::sync::Mutex;
+
+/// Stores the highest account nonce of extrinsics submitted by `SubstrateRelayer`. Unlike listener checkpoints,
+/// which track processed source chain events, it tracks submission progress on the destination chain.
+#[allow(clippy::result_unit_err)]
+pub trait NonceStore: Send + Sync {
+    fn load(&self) -> Result<Option<u64>, ()>;
+    fn save(&self, nonce: u64) -> Result<(), ()>;
+    // forgets stored nonce, the chain's account nonce is used for the next submission
+    fn clear(&self) -> Result<(), ()>;
+}
+
+/// File based `NonceStore`, used to keep submission progress across restarts.
+pub struct FileNonceStore {
+    path: String,
+}
+
+impl FileNonceStore {
+    pub fn new(path: String) -> Self {
+        Self { path }
+    }
+}
+
+impl NonceStore for FileNonceStore {
+    fn load(&self) -> Result<Option<u64>, ()> {
+        match fs::read(&self.path) {
+            Ok(content) => {
+                let bytes: [u8; 8] = content.as_slice().try_into().map_err(|_| {
+                    log::error!("Invalid nonce stored in {}", self.path);
+                })?;
+                Ok(Some(u64::from_le_bytes(bytes)))
+            },
+            Err(e) if e.kind() == ErrorKind::NotFound => Ok(None),
+            Err(e) => {
+                log::error!("Could not read nonce from {}: {:?}", self.path, e);
+                Err(())
+            },
+        }
+    }
+
+    // written to temporary file first, so the stored nonce is never partially written
+    fn save(&self, nonce: u64) -> Result<(), ()> {
+        let tmp_path = format!("{}.tmp", self.path);
+        fs::write(&tmp_path, nonce.to_le_bytes())
+            .and_then(|_| fs::rename(&tmp_path, &self.path))
+            .map_err(|e| {
+                log::error!("Could not save nonce to {}: {:?}", self.path, e);
+            })
+    }
+
+    fn clear(&self) -> Result<(), ()> {
+        match fs::remove_file(&self.path) {
+            Ok(()) => Ok(()),
+            Err(e) if e.kind() == ErrorKind::NotFound => Ok(()),
+            Err(e) => {
+                log::error!("Could not clear nonce stored in {}: {:?}", self.path, e);
+                Err(())
+            },
+        }
+    }
+}
+
+/// Keeps nonce in memory only, submission progress is lost on restart.
+#[derive(Default)]
+pub struct InMemoryNonceStore {
+    nonce: Mutex<Option<u64>>,
+}
+
+impl NonceStore for InMemoryNonceStore {
+    fn load(&self) -> Result<Option<u64>, ()> {
+        Ok(*self.nonce.lock().unwrap())
+    }
+
+    fn save(&self, nonce: u64) -> Result<(), ()> {
+        *self.nonce.lock().unwrap() = Some(nonce);
+        Ok(())
+    }
+
+    fn clear(&self) -> Result<(), ()> {
+        *self.nonce.lock().unwrap() = None;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn file_nonce_store_saves_and_reloads_nonce() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("relayer_nonce.bin").to_str().unwrap().to_string();
+
+        assert_eq!(FileNonceStore::new(path.clone()).load(), Ok(None));
+
+        FileNonceStore::new(path.clone()).save(41).unwrap();
+        FileNonceStore::new(path.clone()).save(42).unwrap();
+
+        assert_eq!(FileNonceStore::new(path.clone()).load(), Ok(Some(42)));
+
+        FileNonceStore::new(path.clone()).clear().unwrap();
+        FileNonceStore::new(path.clone()).clear().unwrap();
+        assert_eq!(FileNonceStore::new(path).load(), Ok(None));
+    }
+
+    #[test]
+    fn file_nonce_store_fails_on_invalid_content() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("relayer_nonce.bin");
+        fs::write(&path, [1, 2, 3]).unwrap();
+
+        assert!(FileNonceStore::new(path.to_str().unwrap().to_string()).load().is_err());
+    }
+}
@@ -0,0 +1,302 @@
+// Copyright 2020-2024 Trust Computing GmbH.
+// This file is part of Litentry.
+//
+// Litentry is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// Litentry is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with Litentry.  If not, see <https://www.gnu.org/licenses/>.
+//! Offline (cold) signing of pay out requests. The worker builds the extrinsic and writes its signer payload
+//! to a queue directory as `<chain>_<nonce>.request.json`, the payload is signed on an air-gapped machine
+//! (see `bridge-cli sign-pay-out`) and the signature is dropped back as `<chain>_<nonce>.signature.json`.
+
+use crate::submit_failure::SubmitFailure;
+use log::{error, info};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::io::ErrorKind;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use std::time::Duration;
+use subxt::utils::AccountId32;
+use subxt_signer::sr25519;
+
+pub const DEFAULT_SIGNING_TIMEOUT: Duration = Duration::from_secs(3600);
+
+const REQUEST_SUFFIX: &str = ".request.json";
+const SIGNATURE_SUFFIX: &str = ".signature.json";
+const POLL_INTERVAL: Duration = Duration::from_secs(5);
+
+#[derive(Clone, Deserialize)]
+#[cfg_attr(test, derive(Serialize))]
+pub struct OfflineSigningConfig {
+    // directory signing requests are written to and signatures are read from
+    pub queue_dir: String,
+    // SS58 encoded account signing pay out requests, its key is not needed by the worker
+    pub account: String,
+    // waiting for signature exceeding the timeout is treated as transport error and retried
+    #[serde(default = "default_signing_timeout_secs")]
+    pub signing_timeout_secs: u64,
+}
+
+fn default_signing_timeout_secs() -> u64 {
+    DEFAULT_SIGNING_TIMEOUT.as_secs()
+}
+
+/// Unsigned pay out request extrinsic, hex encoded.
+#[derive(Debug, Deserialize, Serialize, PartialEq)]
+pub struct SigningRequest {
+    pub chain: String,
+    pub nonce: u64,
+    // SS58 encoded account expected to sign
+    pub signer: String,
+    pub call_data: String,
+    // exactly the bytes to sign, already hashed by subxt if the payload is long
+    pub signer_payload: String,
+}
+
+/// sr25519 signature of request's signer payload, hex encoded.
+#[derive(Debug, Deserialize, Serialize, PartialEq)]
+pub struct SignedPayload {
+    pub signer_payload: String,
+    pub signature: String,
+}
+
+/// Signs `request` with the sr25519 key of its signer, used on the offline machine.
+pub fn sign_request(request: &SigningRequest, secret_key: [u8; 32]) -> Result<SignedPayload, String> {
+    let keypair = sr25519::Keypair::from_secret_key(secret_key).map_err(|e| format!("Invalid key: {:?}", e))?;
+    let account = keypair.public_key().to_account_id();
+    if account.to_string() != request.signer {
+        return Err(format!("Request is to be signed by {}, key belongs to {}", request.signer, account));
+    }
+    let payload = hex::decode(&request.signer_payload).map_err(|e| format!("Invalid signer payload: {}", e))?;
+    Ok(SignedPayload {
+        signer_payload: request.signer_payload.clone(),
+        signature: hex::encode(keypair.sign(&payload).0),
+    })
+}
+
+/// Path the signature of request stored at `request_path` is expected at.
+pub fn signature_path(request_path: &Path) -> Option<PathBuf> {
+    let file_name = request_path.file_name()?.to_str()?;
+    let id = file_name.strip_suffix(REQUEST_SUFFIX)?;
+    Some(request_path.with_file_name(format!("{}{}", id, SIGNATURE_SUFFIX)))
+}
+
+/// Queue directory shared with the offline signer.
+pub struct SigningQueue {
+    dir: PathBuf,
+    account: AccountId32,
+    timeout: Duration,
+}
+
+impl SigningQueue {
+    #[allow(clippy::result_unit_err)]
+    pub fn new(config: &OfflineSigningConfig) -> Result<Self, ()> {
+        let account = AccountId32::from_str(&config.account).map_err(|e| {
+            error!("Invalid offline signing account {}: {:?}", config.account, e);
+        })?;
+        fs::create_dir_all(&config.queue_dir).map_err(|e| {
+            error!("Could not create signing queue {}: {:?}", config.queue_dir, e);
+        })?;
+        Ok(Self {
+            dir: PathBuf::from(&config.queue_dir),
+            account,
+            timeout: Duration::from_secs(config.signing_timeout_secs),
+        })
+    }
+
+    pub fn account(&self) -> &AccountId32 {
+        &self.account
+    }
+
+    pub fn timeout(&self) -> Duration {
+        self.timeout
+    }
+
+    fn request_path(&self, chain: &str, nonce: u64) -> PathBuf {
+        self.dir.join(format!("{}_{}{}", chain, nonce, REQUEST_SUFFIX))
+    }
+
+    /// Queues request unless the same one is queued already and waits until a valid signature of its payload
+    /// is dropped back. Fails with `SubmitFailure::Temporary` once the signing timeout elapses, the request
+    /// stays queued so a signature provided later is picked up on retry.
+    pub async fn await_signature(&self, request: &SigningRequest) -> Result<[u8; 64], SubmitFailure> {
+        let request_path = self.request_path(&request.chain, request.nonce);
+        let signature_path = signature_path(&request_path).ok_or(SubmitFailure::Fatal)?;
+        self.enqueue(&request_path, &signature_path, request)?;
+
+        tokio::time::timeout(self.timeout, async {
+            loop {
+                if let Some(signature) = self.read_signature(&signature_path, request) {
+                    return signature;
+                }
+                tokio::time::sleep(POLL_INTERVAL).await;
+            }
+        })
+        .await
+        .map_err(|_| {
+            error!("Pay out request {} was not signed within {:?}", request_path.display(), self.timeout);
+            SubmitFailure::Temporary
+        })
+    }
+
+    /// Removes request and its signature once the extrinsic is submitted.
+    pub fn complete(&self, request: &SigningRequest) {
+        let request_path = self.request_path(&request.chain, request.nonce);
+        for path in [Some(request_path.clone()), signature_path(&request_path)]
+            .into_iter()
+            .flatten()
+        {
+            if let Err(e) = fs::remove_file(&path) {
+                if e.kind() != ErrorKind::NotFound {
+                    error!("Could not remove {}: {:?}", path.display(), e);
+                }
+            }
+        }
+    }
+
+    // a different request with the same nonce invalidates signature of the queued one
+    fn enqueue(
+        &self,
+        request_path: &Path,
+        signature_path: &Path,
+        request: &SigningRequest,
+    ) -> Result<(), SubmitFailure> {
+        let queued = fs::read(request_path)
+            .ok()
+            .and_then(|content| serde_json::from_slice::<SigningRequest>(&content).ok());
+        if queued.as_ref() == Some(request) {
+            return Ok(());
+        }
+        let _ = fs::remove_file(signature_path);
+        fs::write(request_path, serde_json::to_vec_pretty(request).map_err(|_| SubmitFailure::Fatal)?).map_err(
+            |e| {
+                error!("Could not queue signing request {}: {:?}", request_path.display(), e);
+                SubmitFailure::Fatal
+            },
+        )?;
+        info!("Queued pay out request {} for offline signing by {}", request_path.display(), request.signer);
+        Ok(())
+    }
+
+    fn read_signature(&self, signature_path: &Path, request: &SigningRequest) -> Option<[u8; 64]> {
+        let content = fs::read(signature_path).ok()?;
+        let signed: SignedPayload = serde_json::from_slice(&content)
+            .map_err(|e| error!("Invalid signature file {}: {:?}", signature_path.display(), e))
+            .ok()?;
+        let signature: [u8; 64] = hex::decode(&signed.signature).ok().and_then(|bytes| bytes.try_into().ok())?;
+        let payload = hex::decode(&request.signer_payload).ok()?;
+        let public_key = sr25519::PublicKey(self.account.0);
+        if signed.signer_payload != request.signer_payload
+            || !sr25519::verify(&sr25519::Signature(signature), &payload, &public_key)
+        {
+            error!("Signature in {} doesn't match queued request, ignoring it", signature_path.display());
+            let _ = fs::remove_file(signature_path);
+            return None;
+        }
+        Some(signature)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // dev key of local keystore
+    const SECRET_KEY_HEX: &str = "e5be9a5092b81bca64be81d212e7f2f9eba183bb7a90954f7b76361f6edb5c0a";
+
+    fn secret_key() -> [u8; 32] {
+        hex::decode(SECRET_KEY_HEX).unwrap().try_into().unwrap()
+    }
+
+    fn account() -> AccountId32 {
+        sr25519::Keypair::from_secret_key(secret_key())
+            .unwrap()
+            .public_key()
+            .to_account_id()
+    }
+
+    fn queue(dir: &tempfile::TempDir) -> SigningQueue {
+        SigningQueue::new(&OfflineSigningConfig {
+            queue_dir: dir.path().to_str().unwrap().to_string(),
+            account: account().to_string(),
+            signing_timeout_secs: 60,
+        })
+        .unwrap()
+    }
+
+    fn request(signer_payload: &[u8]) -> SigningRequest {
+        SigningRequest {
+            chain: "local".to_string(),
+            nonce: 3,
+            signer: account().to_string(),
+            call_data: "0102".to_string(),
+            signer_payload: hex::encode(signer_payload),
+        }
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn signature_dropped_back_is_returned() {
+        let dir = tempfile::tempdir().unwrap();
+        let queue = queue(&dir);
+        let request = request(&[7; 40]);
+        let request_path = dir.path().join("local_3.request.json");
+
+        let signer = async {
+            while !request_path.exists() {
+                tokio::time::sleep(Duration::from_secs(1)).await;
+            }
+            let queued: SigningRequest = serde_json::from_slice(&fs::read(&request_path).unwrap()).unwrap();
+            let signed = sign_request(&queued, secret_key()).unwrap();
+            fs::write(signature_path(&request_path).unwrap(), serde_json::to_vec(&signed).unwrap()).unwrap();
+        };
+        let (signature, _) = tokio::join!(queue.await_signature(&request), signer);
+
+        let signature = signature.unwrap();
+        assert!(sr25519::verify(&sr25519::Signature(signature), [7; 40], &sr25519::PublicKey(account().0)));
+
+        queue.complete(&request);
+        assert_eq!(fs::read_dir(dir.path()).unwrap().count(), 0);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn signature_of_other_payload_is_ignored() {
+        let dir = tempfile::tempdir().unwrap();
+        let queue = queue(&dir);
+        let signature_path = dir.path().join("local_3.signature.json");
+        let stale = sign_request(&request(&[1; 40]), secret_key()).unwrap();
+        fs::write(&signature_path, serde_json::to_vec(&stale).unwrap()).unwrap();
+
+        let result = queue.await_signature(&request(&[2; 40])).await;
+
+        assert_eq!(result, Err(SubmitFailure::Temporary));
+        assert!(!signature_path.exists());
+        assert!(dir.path().join("local_3.request.json").exists());
+    }
+
+    #[test]
+    fn request_is_signed_only_by_its_signer() {
+        let mut request = request(&[7; 40]);
+        assert!(sign_request(&request, secret_key()).is_ok());
+
+        request.signer = AccountId32::from([1; 32]).to_string();
+        assert!(sign_request(&request, secret_key()).is_err());
+    }
+
+    #[test]
+    fn signature_path_is_derived_from_request_path() {
+        assert_eq!(
+            signature_path(Path::new("queue/heima_12.request.json")),
+            Some(PathBuf::from("queue/heima_12.signature.json"))
+        );
+        assert_eq!(signature_path(Path::new("queue/heima_12.json")), None);
+    }
+}
@@ -0,0 +1,170 @@
+// Copyright 2020-2024 Trust Computing GmbH.
+// This file is part of Litentry.
+//
+// Litentry is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// Litentry is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with Litentry.  If not, see <https://www.gnu.org/licenses/>.
+use bridge_core::relay::RelayError;
+use subxt::error::{DispatchError, TransactionError};
+
+/// Reason of a failed extrinsic submission, determines how relaying of the request is retried.
+#[derive(Debug, PartialEq)]
+pub enum SubmitFailure {
+    // node unreachable or connection dropped, retried after a pause
+    Transport,
+    // nonce is outdated or ahead of the chain, e.g. previous extrinsic was dropped, retried with refreshed nonce
+    InvalidNonce,
+    // rejected by transaction pool for a while, e.g. temporarily banned or dropped, retried after a pause
+    Temporary,
+    // submitted, but not finalized within watch timeout, retried without pause
+    Unconfirmed,
+    // dispatch rejected by paused OmniBridge pallet, retried until it's unpaused
+    Paused,
+    // e.g. bad signature, incompatible call or failed dispatch, retrying won't help
+    Fatal,
+}
+
+impl SubmitFailure {
+    pub fn to_relay_error(&self) -> RelayError {
+        match self {
+            SubmitFailure::Transport | SubmitFailure::Temporary => RelayError::TransportError,
+            SubmitFailure::InvalidNonce | SubmitFailure::Unconfirmed => RelayError::WatchError,
+            SubmitFailure::Paused => RelayError::Paused,
+            SubmitFailure::Fatal => RelayError::Other,
+        }
+    }
+}
+
+/// Classifies error of submitting the extrinsic to the transaction pool. Pool rejections come as rpc errors
+/// carrying the reason in their message, other rpc errors are connection issues.
+pub fn classify_submit_error(e: &subxt::Error) -> SubmitFailure {
+    match e {
+        subxt::Error::Io(_) => SubmitFailure::Transport,
+        subxt::Error::Rpc(_) => classify_rejection(&e.to_string()).unwrap_or(SubmitFailure::Transport),
+        _ => SubmitFailure::Fatal,
+    }
+}
+
+/// Classifies error of waiting for the submitted extrinsic to be finalized.
+pub fn classify_watch_error(e: &subxt::Error) -> SubmitFailure {
+    match e {
+        subxt::Error::Io(_) | subxt::Error::Rpc(_) => SubmitFailure::Transport,
+        subxt::Error::Transaction(TransactionError::Dropped(_)) => SubmitFailure::Temporary,
+        subxt::Error::Transaction(TransactionError::Invalid(reason)) => {
+            classify_rejection(reason).unwrap_or(SubmitFailure::Fatal)
+        },
+        subxt::Error::Runtime(DispatchError::Module(e)) if is_paused_error(&e.to_string()) => SubmitFailure::Paused,
+        _ => SubmitFailure::Fatal,
+    }
+}
+
+// see `InvalidTransaction` and transaction pool errors of substrate, e.g. `Invalid Transaction (1010)`
+fn classify_rejection(message: &str) -> Option<SubmitFailure> {
+    let message = message.to_lowercase();
+    if ["outdated", "stale", "future"].iter().any(|reason| message.contains(reason)) {
+        return Some(SubmitFailure::InvalidNonce);
+    }
+    if ["temporarily banned", "priority is too low", "immediately dropped", "exhausts resources"]
+        .iter()
+        .any(|reason| message.contains(reason))
+    {
+        return Some(SubmitFailure::Temporary);
+    }
+    if ["bad signature", "invalid signing address", "invalid transaction", "unknown transaction"]
+        .iter()
+        .any(|reason| message.contains(reason))
+    {
+        return Some(SubmitFailure::Fatal);
+    }
+    None
+}
+
+// module error of the pallet rejecting calls while paused, displayed as e.g. `Pallet error OmniBridge::Paused`
+fn is_paused_error(message: &str) -> bool {
+    message
+        .split("OmniBridge::")
+        .nth(1)
+        .is_some_and(|error| error.to_lowercase().contains("paused"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn pool_rejections_are_classified() {
+        let cases = [
+            ("Invalid Transaction (1010): Transaction is outdated", SubmitFailure::InvalidNonce),
+            ("Invalid Transaction (1010): Transaction will be valid in the future", SubmitFailure::InvalidNonce),
+            ("Transaction is temporarily banned (1012)", SubmitFailure::Temporary),
+            ("Priority is too low: (10 vs 10) (1014)", SubmitFailure::Temporary),
+            ("Invalid Transaction (1010): Transaction has a bad signature", SubmitFailure::Fatal),
+            ("Invalid Transaction (1010): Inability to pay some fees", SubmitFailure::Fatal),
+        ];
+        for (message, expected) in cases {
+            assert_eq!(classify_rejection(message), Some(expected), "{}", message);
+        }
+        assert_eq!(classify_rejection("connection reset by peer"), None);
+    }
+
+    #[test]
+    fn paused_pallet_errors_are_recognized() {
+        assert!(is_paused_error("Pallet error OmniBridge::Paused"));
+        assert!(is_paused_error("Pallet error OmniBridge::BridgePaused"));
+        assert!(!is_paused_error("Pallet error OmniBridge::RequireRelayer"));
+        assert!(!is_paused_error("Pallet error Balances::Paused"));
+        assert!(!is_paused_error("Unknown pallet error (pallet and error details cannot be retrieved)"));
+    }
+
+    #[test]
+    fn watch_errors_are_classified() {
+        let cases = [
+            (subxt::Error::Transaction(TransactionError::Dropped("pool full".to_string())), SubmitFailure::Temporary),
+            (
+                subxt::Error::Transaction(TransactionError::Invalid("Transaction is outdated".to_string())),
+                SubmitFailure::InvalidNonce,
+            ),
+            (
+                subxt::Error::Transaction(TransactionError::Invalid("Transaction has a bad signature".to_string())),
+                SubmitFailure::Fatal,
+            ),
+            (subxt::Error::Transaction(TransactionError::Error("usurped".to_string())), SubmitFailure::Fatal),
+            (subxt::Error::Other("decoding failed".to_string()), SubmitFailure::Fatal),
+            (
+                subxt::Error::Io(std::io::Error::new(std::io::ErrorKind::ConnectionReset, "reset")),
+                SubmitFailure::Transport,
+            ),
+        ];
+        for (error, expected) in cases {
+            assert_eq!(classify_watch_error(&error), expected, "{:?}", error);
+        }
+    }
+
+    #[test]
+    fn submit_errors_other_than_transport_are_fatal() {
+        assert_eq!(
+            classify_submit_error(&subxt::Error::Io(std::io::Error::new(std::io::ErrorKind::BrokenPipe, "pipe"))),
+            SubmitFailure::Transport
+        );
+        assert_eq!(classify_submit_error(&subxt::Error::Other("metadata".to_string())), SubmitFailure::Fatal);
+    }
+
+    #[test]
+    fn failures_are_mapped_to_relay_errors() {
+        assert!(matches!(SubmitFailure::InvalidNonce.to_relay_error(), RelayError::WatchError));
+        assert!(matches!(SubmitFailure::Unconfirmed.to_relay_error(), RelayError::WatchError));
+        assert!(matches!(SubmitFailure::Temporary.to_relay_error(), RelayError::TransportError));
+        assert!(matches!(SubmitFailure::Transport.to_relay_error(), RelayError::TransportError));
+        assert!(matches!(SubmitFailure::Fatal.to_relay_error(), RelayError::Other));
+        assert!(matches!(SubmitFailure::Paused.to_relay_error(), RelayError::Paused));
+    }
+}